@@ -25,12 +25,15 @@ fn main() {
     println!("cargo:rerun-if-changed=runtime/mdh_runtime_rs/src/tri_runtime.rs");
     println!("cargo:rerun-if-changed=runtime/mdh_runtime_rs/src/tri_engine.rs");
 
+    // `mdhavers::build_info()` reports the target triple
+    let target = env::var("TARGET").unwrap_or_else(|_| "unknown".to_string());
+    println!("cargo:rustc-env=MDHAVERS_TARGET={target}");
+
     let llvm_enabled = env::var("CARGO_FEATURE_LLVM").is_ok();
     if !llvm_enabled {
         return;
     }
 
-    let target = env::var("TARGET").unwrap_or_else(|_| "unknown".to_string());
     if target.starts_with("wasm32") {
         panic!("The 'llvm' feature is not supported for target {target}");
     }
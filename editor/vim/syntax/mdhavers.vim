@@ -39,7 +39,7 @@ syn keyword mdhaversDeclaration ken dae kin thing fae
 syn keyword mdhaversReturn gie
 
 " Import
-syn keyword mdhaversImport fetch tae
+syn keyword mdhaversImport fetch bring frae tae
 
 " Special keywords
 syn keyword mdhaversSpecial blether mak_siccar masel
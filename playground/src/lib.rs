@@ -6,6 +6,18 @@
 use wasm_bindgen::prelude::*;
 use serde::{Deserialize, Serialize};
 
+// The browser has no clock or entropy for Rust to read, so borrow Math.random
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = Math)]
+    fn random() -> f64;
+}
+
+/// Printed lines kept before the rest are dropped, so a runaway `blether`
+/// loop cannot eat the browser tab's memory
+const OUTPUT_LIMIT: usize = 10_000;
+
 // Initialize panic hook for better error messages in browser
 #[wasm_bindgen(start)]
 pub fn init() {
@@ -35,13 +47,26 @@ pub struct RunResult {
 /// A JSON string containing the RunResult
 #[wasm_bindgen]
 pub fn run(code: &str) -> String {
-    let result = run_internal(code);
+    run_with_input(code, "")
+}
+
+/// Run mdhavers code with `input` standing in for stdin, one `speir` per line
+///
+/// # Arguments
+/// * `code` - The mdhavers source code to execute
+/// * `input` - The lines `speir` reads; after the last one it gets `naething`
+///
+/// # Returns
+/// A JSON string containing the RunResult
+#[wasm_bindgen]
+pub fn run_with_input(code: &str, input: &str) -> String {
+    let result = run_internal(code, input);
     serde_json::to_string(&result).unwrap_or_else(|e| {
         format!(r#"{{"success":false,"error":"Serialization error: {}","result":"","output":[]}}"#, e)
     })
 }
 
-fn run_internal(code: &str) -> RunResult {
+fn run_internal(code: &str, input: &str) -> RunResult {
     // Parse the code
     let program = match mdhavers::parse(code) {
         Ok(p) => p,
@@ -57,10 +82,14 @@ fn run_internal(code: &str) -> RunResult {
 
     // Create interpreter and run
     let mut interpreter = mdhavers::Interpreter::new();
+    interpreter.set_input_source(Box::new(std::io::Cursor::new(input.to_string())));
+    interpreter.set_output_limit(Some(OUTPUT_LIMIT));
+    #[cfg(target_arch = "wasm32")]
+    interpreter.seed_random((random() * u64::MAX as f64) as u64);
 
     match interpreter.interpret(&program) {
         Ok(value) => {
-            let output = interpreter.get_output().to_vec();
+            let output = interpreter.take_output();
             RunResult {
                 success: true,
                 result: format!("{}", value),
@@ -69,7 +98,7 @@ fn run_internal(code: &str) -> RunResult {
             }
         }
         Err(e) => {
-            let output = interpreter.get_output().to_vec();
+            let output = interpreter.take_output();
             RunResult {
                 success: false,
                 result: String::new(),
@@ -134,7 +163,13 @@ pub fn compile_to_js(code: &str) -> String {
 /// Get version information
 #[wasm_bindgen]
 pub fn version() -> String {
-    "0.1.0".to_string()
+    mdhavers::version().to_string()
+}
+
+/// Get the version, the optional backends compiled in, and the target as JSON
+#[wasm_bindgen]
+pub fn build_info() -> String {
+    mdhavers::build_info().to_json()
 }
 
 #[cfg(test)]
@@ -153,6 +188,19 @@ mod tests {
         assert!(result.contains("success\":false"));
     }
 
+    #[test]
+    fn test_run_with_input_feeds_speir() {
+        let result = run_with_input("blether speir \"\"\nblether speir \"\"", "aye\n");
+        assert!(result.contains(r#""output":["aye","naething"]"#), "{}", result);
+    }
+
+    #[test]
+    fn test_run_caps_the_output() {
+        let result = run("fer i in 0..20000 {\n    blether i\n}");
+        assert!(result.contains("success\":true"), "{}", result);
+        assert!(result.contains(r#""9999","… output truncated efter 10000 lines"]"#), "{}", result);
+    }
+
     #[test]
     fn test_check_valid() {
         let result = check("ken x = 42");
@@ -164,4 +212,10 @@ mod tests {
         let result = check("ken = ");
         assert!(result.contains("success\":false"));
     }
+
+    #[test]
+    fn test_version_comes_fae_the_crate() {
+        assert_eq!(version(), mdhavers::version());
+        assert!(build_info().contains(&format!("\"version\":\"{}\"", mdhavers::version())));
+    }
 }
@@ -1,6 +1,6 @@
 // mdhavers Playground - Main Application
 
-import init, { run, check, format, compile_to_js, version } from './pkg/mdhavers_playground.js';
+import init, { run, check, format, compile_to_js, version, build_info } from './pkg/mdhavers_playground.js';
 
 // Example code snippets
 const EXAMPLES = {
@@ -238,6 +238,8 @@ async function initApp() {
         await init();
         wasmReady = true;
         setStatus('Ready', 'ready');
+        const info = JSON.parse(build_info());
+        status.title = `mdhavers ${info.version} (${info.target})`;
         console.log(`mdhavers Playground v${version()} loaded`);
     } catch (error) {
         setStatus('WASM Error', 'error');
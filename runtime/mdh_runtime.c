@@ -58,22 +58,50 @@ typedef struct {
 extern MdhRsResult __mdh_rs_json_parse(MdhValue json_str);
 extern MdhRsResult __mdh_rs_json_stringify(MdhValue value);
 extern MdhRsResult __mdh_rs_json_pretty(MdhValue value);
+extern MdhRsResult __mdh_rs_json_stringify_opts(MdhValue value, MdhValue indent, MdhValue sort_keys);
+extern MdhRsResult __mdh_rs_json_query(MdhValue value, MdhValue path);
 extern MdhRsResult __mdh_rs_regex_test(MdhValue text, MdhValue pattern);
 extern MdhRsResult __mdh_rs_regex_match(MdhValue text, MdhValue pattern);
 extern MdhRsResult __mdh_rs_regex_match_all(MdhValue text, MdhValue pattern);
 extern MdhRsResult __mdh_rs_regex_replace(MdhValue text, MdhValue pattern, MdhValue replacement);
 extern MdhRsResult __mdh_rs_regex_replace_first(MdhValue text, MdhValue pattern, MdhValue replacement);
 extern MdhRsResult __mdh_rs_regex_split(MdhValue text, MdhValue pattern);
-extern MdhRsResult __mdh_rs_dns_srv(MdhValue service, MdhValue domain);
-extern MdhRsResult __mdh_rs_dns_naptr(MdhValue domain);
+extern MdhRsResult __mdh_rs_sha256(MdhValue data);
+extern MdhRsResult __mdh_rs_sha1(MdhValue data);
+extern MdhRsResult __mdh_rs_md5(MdhValue data);
+extern MdhRsResult __mdh_rs_sha256_hex(MdhValue data);
+extern MdhRsResult __mdh_rs_sha1_hex(MdhValue data);
+extern MdhRsResult __mdh_rs_md5_hex(MdhValue data);
+extern MdhRsResult __mdh_rs_hmac_sha256(MdhValue key, MdhValue message);
+extern MdhRsResult __mdh_rs_hmac_sha256_hex(MdhValue key, MdhValue message);
+extern MdhRsResult __mdh_rs_ct_eq(MdhValue a, MdhValue b);
+extern MdhRsResult __mdh_rs_random_bytes(MdhValue n);
+extern MdhRsResult __mdh_rs_random_hex(MdhValue n);
+extern MdhRsResult __mdh_rs_pbkdf2(MdhValue password, MdhValue salt, MdhValue iterations, MdhValue length);
+extern MdhRsResult __mdh_rs_hex_encode(MdhValue data);
+extern MdhRsResult __mdh_rs_hex_decode(MdhValue text);
+extern MdhRsResult __mdh_rs_hexdump(MdhValue data);
+extern void __mdh_rs_format_float(double value, char *buf, size_t cap);
+extern MdhRsResult __mdh_rs_fmt_float(MdhValue value, MdhValue decimals);
+extern MdhRsResult __mdh_rs_csv_parse(MdhValue text, MdhValue delim);
+extern MdhRsResult __mdh_rs_csv_write(MdhValue rows, MdhValue delim);
+extern MdhRsResult __mdh_rs_uuid_v4(void);
+extern MdhRsResult __mdh_rs_uuid_v7(void);
+extern MdhRsResult __mdh_rs_read_dir(MdhValue path);
+extern MdhRsResult __mdh_rs_walk_dir(MdhValue path);
+extern MdhRsResult __mdh_rs_dns_srv(MdhValue service, MdhValue domain, MdhValue timeout_ms);
+extern MdhRsResult __mdh_rs_dns_naptr(MdhValue domain, MdhValue timeout_ms);
 extern MdhRsResult __mdh_rs_tls_client_new(MdhValue config);
 extern MdhRsResult __mdh_rs_tls_connect(MdhValue tls, MdhValue sock_fd);
+extern MdhRsResult __mdh_rs_tls_connect_start(MdhValue tls, MdhValue sock_fd);
+extern MdhRsResult __mdh_rs_tls_handshake_step(MdhValue tls);
 extern MdhRsResult __mdh_rs_tls_send(MdhValue tls, MdhValue buf);
 extern MdhRsResult __mdh_rs_tls_recv(MdhValue tls, MdhValue max_len);
 extern MdhRsResult __mdh_rs_tls_close(MdhValue tls);
 extern MdhRsResult __mdh_rs_srtp_create(MdhValue config);
 extern MdhRsResult __mdh_rs_srtp_protect(MdhValue ctx, MdhValue packet);
 extern MdhRsResult __mdh_rs_srtp_unprotect(MdhValue ctx, MdhValue packet);
+extern MdhRsResult __mdh_rs_srtp_set_roc(MdhValue ctx, MdhValue ssrc, MdhValue roc);
 extern MdhRsResult __mdh_rs_dtls_server_new(MdhValue config);
 extern MdhRsResult __mdh_rs_dtls_handshake(MdhValue dtls, MdhValue sock_fd);
 
@@ -84,6 +112,10 @@ static int __mdh_random_initialized = 0;
 static int32_t __mdh_argc = 0;
 static char **__mdh_argv = NULL;
 
+/* (fn pointer, name, arity) triples for printing functions (set by the generated main) */
+static const int64_t *__mdh_function_table = NULL;
+static int64_t __mdh_function_count = 0;
+
 static void __mdh_ensure_rng(void) {
     if (!__mdh_random_initialized) {
         srand((unsigned int)time(NULL));
@@ -514,7 +546,17 @@ void __mdh_type_error(const char *op, uint8_t got1, uint8_t got2) {
     __mdh_hurl(__mdh_make_string(buf));
 }
 
+/* An instance's first word points at its class name */
+static const char *__mdh_instance_class_name(MdhValue v) {
+    if (v.tag != MDH_TAG_INSTANCE || v.data == 0) return NULL;
+    return (const char *)(intptr_t)*(int64_t *)(intptr_t)v.data;
+}
+
 MdhValue __mdh_type_of(MdhValue a) {
+    const char *class_name = __mdh_instance_class_name(a);
+    if (class_name) {
+        return __mdh_make_string(class_name);
+    }
     return __mdh_make_string(__mdh_type_name(a));
 }
 
@@ -1320,6 +1362,9 @@ MdhValue __mdh_contains(MdhValue container, MdhValue elem) {
     if (container.tag == MDH_TAG_DICT) {
         return __mdh_dict_contains(container, elem);
     }
+    if (container.tag == MDH_TAG_SET) {
+        return __mdh_set_contains(container, elem);
+    }
     if (container.tag == MDH_TAG_STRING) {
         if (elem.tag != MDH_TAG_STRING) {
             __mdh_type_error("contains", container.tag, elem.tag);
@@ -1391,6 +1436,36 @@ static int __mdh_cmp_cstr(const void *a, const void *b) {
 
 static bool __mdh_dict_is_creel(MdhValue dict);
 
+/* A function prints as `<dae add/2>`, or `<lambda@3:9/1>` for an anonymous one */
+static void __mdh_function_to_string_sb(MdhStrBuf *out, MdhValue v) {
+    if (v.tag == MDH_TAG_CLOSURE) {
+        /* A closure is [fn, captures...] - it's the function inside that has the name */
+        uint8_t *base = (uint8_t *)(intptr_t)v.data;
+        int64_t len = base ? ((int64_t *)base)[1] : 0;
+        if (len <= 0) {
+            __mdh_sb_append(out, "<dae>");
+            return;
+        }
+        v = ((MdhValue *)(base + 16))[0];
+    }
+    for (int64_t i = 0; i < __mdh_function_count; i++) {
+        if (__mdh_function_table[i * 3] != v.data) {
+            continue;
+        }
+        const char *name = (const char *)(intptr_t)__mdh_function_table[i * 3 + 1];
+        long long arity = (long long)__mdh_function_table[i * 3 + 2];
+        char buf[256];
+        if (name[0] == '<') {
+            snprintf(buf, sizeof(buf), "%.*s/%lld>", (int)strlen(name) - 1, name, arity);
+        } else {
+            snprintf(buf, sizeof(buf), "<dae %s/%lld>", name, arity);
+        }
+        __mdh_sb_append(out, buf);
+        return;
+    }
+    __mdh_sb_append(out, "<dae>");
+}
+
 static void __mdh_value_to_string_sb(MdhStrBuf *out, MdhValue v) {
     char tmp[128];
 
@@ -1406,7 +1481,7 @@ static void __mdh_value_to_string_sb(MdhStrBuf *out, MdhValue v) {
             __mdh_sb_append(out, tmp);
             return;
         case MDH_TAG_FLOAT:
-            snprintf(tmp, sizeof(tmp), "%g", __mdh_get_float(v));
+            __mdh_format_float(__mdh_get_float(v), tmp, sizeof(tmp));
             __mdh_sb_append(out, tmp);
             return;
         case MDH_TAG_STRING:
@@ -1487,6 +1562,10 @@ static void __mdh_value_to_string_sb(MdhStrBuf *out, MdhValue v) {
             __mdh_sb_append_char(out, '}');
             return;
         }
+        case MDH_TAG_FUNCTION:
+        case MDH_TAG_CLOSURE:
+            __mdh_function_to_string_sb(out, v);
+            return;
         case MDH_TAG_NATIVE: {
             MdhNativeObject *native = __mdh_get_native(v);
             if (!native) {
@@ -1523,12 +1602,133 @@ MdhValue __mdh_to_string(MdhValue a) {
     return __mdh_string_from_buf(sb.buf);
 }
 
+#define MDH_SHAW_MAX_DEPTH 64
+
+static void __mdh_shaw_indent(MdhStrBuf *out, int depth) {
+    for (int i = 0; i < depth; i++) {
+        __mdh_sb_append(out, "  ");
+    }
+}
+
+static void __mdh_shaw_sb(MdhStrBuf *out, MdhValue v, int pretty, int depth, int64_t *path) {
+    char tmp[128];
+
+    switch (v.tag) {
+        case MDH_TAG_NIL:
+            __mdh_sb_append(out, "Naething");
+            return;
+        case MDH_TAG_BOOL:
+            __mdh_sb_append(out, v.data ? "Bool:aye" : "Bool:nae");
+            return;
+        case MDH_TAG_INT:
+            snprintf(tmp, sizeof(tmp), "Int:%lld", (long long)v.data);
+            __mdh_sb_append(out, tmp);
+            return;
+        case MDH_TAG_FLOAT:
+            __mdh_sb_append(out, "Float:");
+            __mdh_format_float(__mdh_get_float(v), tmp, sizeof(tmp));
+            __mdh_sb_append(out, tmp);
+            return;
+        case MDH_TAG_STRING: {
+            const char *s = __mdh_get_string(v);
+            __mdh_sb_append(out, "Str:\"");
+            for (; s && *s; s++) {
+                switch (*s) {
+                    case '"': __mdh_sb_append(out, "\\\""); break;
+                    case '\\': __mdh_sb_append(out, "\\\\"); break;
+                    case '\n': __mdh_sb_append(out, "\\n"); break;
+                    case '\t': __mdh_sb_append(out, "\\t"); break;
+                    default: __mdh_sb_append_char(out, *s); break;
+                }
+            }
+            __mdh_sb_append_char(out, '"');
+            return;
+        }
+        case MDH_TAG_LIST:
+        case MDH_TAG_DICT:
+            break;
+        default: {
+            MdhValue s = __mdh_to_string(v);
+            __mdh_sb_append(out, __mdh_get_string(s));
+            return;
+        }
+    }
+
+    /* A container already on the path is inside itself */
+    for (int i = 0; i < depth; i++) {
+        if (path[i] == v.data) {
+            __mdh_sb_append(out, "…");
+            return;
+        }
+    }
+    if (depth >= MDH_SHAW_MAX_DEPTH) {
+        __mdh_sb_append(out, "…");
+        return;
+    }
+    path[depth] = v.data;
+
+    int64_t count;
+    MdhValue *items = NULL;
+    MdhValue *entries = NULL;
+    if (v.tag == MDH_TAG_LIST) {
+        MdhList *list = __mdh_get_list(v);
+        count = list ? list->length : 0;
+        items = list ? list->items : NULL;
+        snprintf(tmp, sizeof(tmp), "List[%lld]: [", (long long)count);
+    } else {
+        int64_t *dict_ptr = (int64_t *)(intptr_t)v.data;
+        count = dict_ptr ? *dict_ptr : 0;
+        entries = dict_ptr ? (MdhValue *)(dict_ptr + 1) : NULL;
+        snprintf(tmp, sizeof(tmp), "Dict[%lld]: {", (long long)count);
+    }
+    __mdh_sb_append(out, tmp);
+
+    for (int64_t i = 0; i < count; i++) {
+        if (pretty) {
+            __mdh_sb_append_char(out, '\n');
+            __mdh_shaw_indent(out, depth + 1);
+        } else if (i > 0) {
+            __mdh_sb_append(out, ", ");
+        }
+        if (items) {
+            __mdh_shaw_sb(out, items[i], pretty, depth + 1, path);
+        } else {
+            __mdh_shaw_sb(out, entries[i * 2], 0, depth + 1, path);
+            __mdh_sb_append(out, ": ");
+            __mdh_shaw_sb(out, entries[i * 2 + 1], pretty, depth + 1, path);
+        }
+        if (pretty && i + 1 < count) {
+            __mdh_sb_append_char(out, ',');
+        }
+    }
+    if (pretty && count > 0) {
+        __mdh_sb_append_char(out, '\n');
+        __mdh_shaw_indent(out, depth);
+    }
+    __mdh_sb_append_char(out, v.tag == MDH_TAG_LIST ? ']' : '}');
+}
+
+MdhValue __mdh_shaw(MdhValue value, int64_t pretty) {
+    int64_t path[MDH_SHAW_MAX_DEPTH];
+    MdhStrBuf sb;
+    __mdh_sb_init(&sb);
+    __mdh_shaw_sb(&sb, value, pretty != 0, 0, path);
+    return __mdh_string_from_buf(sb.buf);
+}
+
 MdhValue __mdh_to_int(MdhValue a) {
     switch (a.tag) {
         case MDH_TAG_INT:
             return a;
-        case MDH_TAG_FLOAT:
-            return __mdh_make_int((int64_t)__mdh_get_float(a));
+        case MDH_TAG_FLOAT: {
+            /* Cut toward zero, but the cast is undefined outside the range */
+            double f = __mdh_get_float(a);
+            if (!isfinite(f) || f >= 9223372036854775808.0 || f < -9223372036854775808.0) {
+                __mdh_hurl(__mdh_make_string("Cannae turn inf, NaN or a float that muckle intae an integer"));
+                return __mdh_make_int(0);
+            }
+            return __mdh_make_int((int64_t)f);
+        }
         case MDH_TAG_BOOL:
             return __mdh_make_int(a.data ? 1 : 0);
         case MDH_TAG_STRING: {
@@ -1949,6 +2149,18 @@ MdhValue __mdh_random_int(MdhValue min, MdhValue max) {
     return __mdh_random(min_i, max_i);
 }
 
+MdhValue __mdh_seed_random(MdhValue seed) {
+    if (seed.tag != MDH_TAG_INT) {
+        __mdh_hurl(__mdh_make_string("seed_random() needs an integer seed"));
+        return __mdh_make_nil();
+    }
+
+    /* Mark the generator as set up so the first draw does not reseed from the clock */
+    srand((unsigned int)(seed.data ^ (seed.data >> 32)));
+    __mdh_random_initialized = 1;
+    return __mdh_make_nil();
+}
+
 MdhValue __mdh_floor(MdhValue a) {
     if (a.tag == MDH_TAG_INT) return a;
     if (a.tag == MDH_TAG_FLOAT) {
@@ -1976,6 +2188,74 @@ MdhValue __mdh_round(MdhValue a) {
     return __mdh_make_nil();
 }
 
+static bool __mdh_is_number(MdhValue v) {
+    return v.tag == MDH_TAG_INT || v.tag == MDH_TAG_FLOAT;
+}
+
+static double __mdh_number_as_float(MdhValue v) {
+    return v.tag == MDH_TAG_INT ? (double)v.data : __mdh_get_float(v);
+}
+
+/* sign(x): -1, 0 or 1, a float for a float */
+MdhValue __mdh_sign(MdhValue x) {
+    if (x.tag == MDH_TAG_INT) {
+        return __mdh_make_int((x.data > 0) - (x.data < 0));
+    }
+    if (x.tag == MDH_TAG_FLOAT) {
+        double f = __mdh_get_float(x);
+        if (isnan(f)) {
+            return x;
+        }
+        return __mdh_make_float(f > 0.0 ? 1.0 : (f < 0.0 ? -1.0 : 0.0));
+    }
+    __mdh_type_error("sign", x.tag, 0);
+    return __mdh_make_nil();
+}
+
+/* clamp(x, lo, hi): ints stay ints, any float makes it a float */
+MdhValue __mdh_clamp(MdhValue x, MdhValue lo, MdhValue hi) {
+    if (!__mdh_is_number(x) || !__mdh_is_number(lo) || !__mdh_is_number(hi)) {
+        __mdh_hurl(__mdh_make_string("clamp() needs three numbers"));
+        return __mdh_make_nil();
+    }
+    double lo_f = __mdh_number_as_float(lo);
+    double hi_f = __mdh_number_as_float(hi);
+    bool all_ints = x.tag == MDH_TAG_INT && lo.tag == MDH_TAG_INT && hi.tag == MDH_TAG_INT;
+    if (all_ints ? lo.data > hi.data : !(lo_f <= hi_f)) {
+        MdhStrBuf sb;
+        __mdh_sb_init(&sb);
+        __mdh_sb_append(&sb, "clamp() needs lo no bigger than hi, but got ");
+        __mdh_sb_append(&sb, __mdh_get_string(__mdh_to_string(lo)));
+        __mdh_sb_append(&sb, " an' ");
+        __mdh_sb_append(&sb, __mdh_get_string(__mdh_to_string(hi)));
+        __mdh_hurl(__mdh_string_from_buf(sb.buf));
+        return __mdh_make_nil();
+    }
+    if (all_ints) {
+        int64_t v = x.data < lo.data ? lo.data : x.data;
+        return __mdh_make_int(v > hi.data ? hi.data : v);
+    }
+    double v = __mdh_number_as_float(x);
+    if (v < lo_f) {
+        v = lo_f;
+    }
+    if (v > hi_f) {
+        v = hi_f;
+    }
+    return __mdh_make_float(v);
+}
+
+/* lerp(a, b, t): a + (b - a) * t, always a float */
+MdhValue __mdh_lerp(MdhValue a, MdhValue b, MdhValue t) {
+    if (!__mdh_is_number(a) || !__mdh_is_number(b) || !__mdh_is_number(t)) {
+        __mdh_hurl(__mdh_make_string("lerp() needs numbers"));
+        return __mdh_make_nil();
+    }
+    double a_f = __mdh_number_as_float(a);
+    double b_f = __mdh_number_as_float(b);
+    return __mdh_make_float(a_f + (b_f - a_f) * __mdh_number_as_float(t));
+}
+
 /* ========== Timing ========== */
 
 MdhValue __mdh_mono_ms(void) {
@@ -2497,13 +2777,13 @@ MdhValue __mdh_dns_lookup(MdhValue host) {
     return __mdh_result_ok(list);
 }
 
-MdhValue __mdh_dns_srv(MdhValue service, MdhValue domain) {
+MdhValue __mdh_dns_srv(MdhValue service, MdhValue domain, MdhValue timeout_ms) {
     if (service.tag != MDH_TAG_STRING || domain.tag != MDH_TAG_STRING) {
         __mdh_type_error("dns_srv", service.tag, domain.tag);
         return __mdh_result_err("dns_srv expects service and domain strings", -1);
     }
 
-    MdhRsResult r = __mdh_rs_dns_srv(service, domain);
+    MdhRsResult r = __mdh_rs_dns_srv(service, domain, timeout_ms);
     if (!r.ok) {
         const char *msg = __mdh_get_string(r.error);
         if (!msg || msg[0] == '\0') {
@@ -2514,13 +2794,13 @@ MdhValue __mdh_dns_srv(MdhValue service, MdhValue domain) {
     return __mdh_result_ok(r.value);
 }
 
-MdhValue __mdh_dns_naptr(MdhValue domain) {
+MdhValue __mdh_dns_naptr(MdhValue domain, MdhValue timeout_ms) {
     if (domain.tag != MDH_TAG_STRING) {
         __mdh_type_error("dns_naptr", domain.tag, 0);
         return __mdh_result_err("dns_naptr expects a domain string", -1);
     }
 
-    MdhRsResult r = __mdh_rs_dns_naptr(domain);
+    MdhRsResult r = __mdh_rs_dns_naptr(domain, timeout_ms);
     if (!r.ok) {
         const char *msg = __mdh_get_string(r.error);
         if (!msg || msg[0] == '\0') {
@@ -2572,6 +2852,44 @@ MdhValue __mdh_tls_connect(MdhValue tls, MdhValue sock) {
     return __mdh_result_ok(r.value);
 }
 
+MdhValue __mdh_tls_connect_start(MdhValue tls, MdhValue sock) {
+    if (tls.tag != MDH_TAG_INT) {
+        __mdh_type_error("tls_connect_start", tls.tag, 0);
+        return __mdh_result_err("tls_connect_start expects TLS handle", -1);
+    }
+    if (sock.tag != MDH_TAG_INT && sock.tag != MDH_TAG_FLOAT) {
+        __mdh_type_error("tls_connect_start", sock.tag, 0);
+        return __mdh_result_err("tls_connect_start expects socket", -1);
+    }
+    int fd = sock.tag == MDH_TAG_INT ? (int)sock.data : (int)__mdh_get_float(sock);
+    /* The Rust side keeps its own duplicate, so fd stays the caller's */
+    MdhRsResult r = __mdh_rs_tls_connect_start(tls, __mdh_make_int(fd));
+    if (!r.ok) {
+        const char *msg = __mdh_get_string(r.error);
+        if (!msg || msg[0] == '\0') {
+            msg = "tls_connect_start failed";
+        }
+        return __mdh_result_err(msg, -1);
+    }
+    return __mdh_result_ok(r.value);
+}
+
+MdhValue __mdh_tls_handshake_step(MdhValue tls) {
+    if (tls.tag != MDH_TAG_INT) {
+        __mdh_type_error("tls_handshake_step", tls.tag, 0);
+        return __mdh_result_err("tls_handshake_step expects TLS handle", -1);
+    }
+    MdhRsResult r = __mdh_rs_tls_handshake_step(tls);
+    if (!r.ok) {
+        const char *msg = __mdh_get_string(r.error);
+        if (!msg || msg[0] == '\0') {
+            msg = "tls_handshake_step failed";
+        }
+        return __mdh_result_err(msg, -1);
+    }
+    return __mdh_result_ok(r.value);
+}
+
 MdhValue __mdh_tls_send(MdhValue tls, MdhValue buf) {
     if (tls.tag != MDH_TAG_INT) {
         __mdh_type_error("tls_send", tls.tag, 0);
@@ -2719,6 +3037,22 @@ MdhValue __mdh_srtp_unprotect(MdhValue srtp, MdhValue rtp_packet) {
     return __mdh_result_ok(r.value);
 }
 
+MdhValue __mdh_srtp_set_roc(MdhValue srtp, MdhValue ssrc, MdhValue roc) {
+    if (srtp.tag != MDH_TAG_INT) {
+        __mdh_type_error("srtp_set_roc", srtp.tag, 0);
+        return __mdh_result_err("srtp_set_roc expects SRTP handle", -1);
+    }
+    MdhRsResult r = __mdh_rs_srtp_set_roc(srtp, ssrc, roc);
+    if (!r.ok) {
+        const char *msg = __mdh_get_string(r.error);
+        if (!msg || msg[0] == '\0') {
+            msg = "srtp_set_roc failed";
+        }
+        return __mdh_result_err(msg, -1);
+    }
+    return __mdh_result_ok(r.value);
+}
+
 /* ========== Event Loop + Timers ========== */
 
 typedef struct {
@@ -3859,6 +4193,28 @@ MdhValue __mdh_dict_remove(MdhValue dict, MdhValue key) {
     return result;
 }
 
+MdhValue __mdh_dict_pop(MdhValue dict, MdhValue key) {
+    /* In-place removal; later entries shift down so insertion order holds */
+    if (dict.tag != MDH_TAG_DICT) {
+        __mdh_type_error("remove", dict.tag, 0);
+        return __mdh_make_nil();
+    }
+
+    int64_t *dict_ptr = (int64_t *)(intptr_t)dict.data;
+    int64_t count = *dict_ptr;
+    MdhValue *entries = (MdhValue *)(dict_ptr + 1);
+    for (int64_t i = 0; i < count; i++) {
+        if (__mdh_values_equal(entries[i * 2], key)) {
+            MdhValue removed = entries[i * 2 + 1];
+            memmove(&entries[i * 2], &entries[(i + 1) * 2],
+                    (size_t)(count - i - 1) * 2 * sizeof(MdhValue));
+            *dict_ptr = count - 1;
+            return removed;
+        }
+    }
+    return __mdh_make_nil();
+}
+
 MdhValue __mdh_dict_invert(MdhValue dict) {
     if (dict.tag != MDH_TAG_DICT) {
         __mdh_type_error("dict_invert", dict.tag, 0);
@@ -4104,15 +4460,20 @@ MdhValue __mdh_words(MdhValue str) {
 
 /* ========== Logging/Debug ========== */
 
-static int __mdh_log_level = 2;  /* Default: INFO */
+/* Same numbering as the interpreter's LogLevel: 0 wheesht (off), 1 roar (error),
+ * 2 holler (warn), 3 blether (info), 4 mutter (debug), 5 whisper (trace) */
+static int __mdh_log_level = 3;  /* Default: BLETHER (info) */
+
+static int __mdh_log_parse_level_val(MdhValue level, int *out);
 
 MdhValue __mdh_get_log_level(void) {
     return __mdh_make_int(__mdh_log_level);
 }
 
 MdhValue __mdh_set_log_level(MdhValue level) {
-    if (level.tag == MDH_TAG_INT) {
-        __mdh_log_level = (int)level.data;
+    int lvl;
+    if (__mdh_log_parse_level_val(level, &lvl)) {
+        __mdh_log_level = lvl;
     }
     return __mdh_make_nil();
 }
@@ -4166,12 +4527,13 @@ static MdhNativeObject *__mdh_span_stack_pop(void) {
 
 static int __mdh_log_parse_level_str(const char *s) {
     if (!s) return 3;
-    if (strcasecmp(s, "wheesht") == 0) return 0;
-    if (strcasecmp(s, "roar") == 0) return 1;
-    if (strcasecmp(s, "holler") == 0) return 2;
-    if (strcasecmp(s, "blether") == 0) return 3;
-    if (strcasecmp(s, "mutter") == 0) return 4;
-    if (strcasecmp(s, "whisper") == 0) return 5;
+    if (strcasecmp(s, "wheesht") == 0 || strcasecmp(s, "off") == 0) return 0;
+    if (strcasecmp(s, "roar") == 0 || strcasecmp(s, "error") == 0) return 1;
+    if (strcasecmp(s, "holler") == 0 || strcasecmp(s, "warn") == 0 ||
+        strcasecmp(s, "warning") == 0) return 2;
+    if (strcasecmp(s, "blether") == 0 || strcasecmp(s, "info") == 0) return 3;
+    if (strcasecmp(s, "mutter") == 0 || strcasecmp(s, "debug") == 0) return 4;
+    if (strcasecmp(s, "whisper") == 0 || strcasecmp(s, "trace") == 0) return 5;
     return 3;
 }
 
@@ -4500,7 +4862,7 @@ MdhValue __mdh_log_span_in(MdhValue span, MdhValue func) {
 
 MdhValue __mdh_log_init(MdhValue config) {
     if (config.tag == MDH_TAG_NIL) {
-        __mdh_log_level = 2;
+        __mdh_log_level = 3;
         __mdh_log_filter = NULL;
         __mdh_log_format = 0;
         __mdh_log_timestamps = 1;
@@ -4736,29 +5098,89 @@ MdhValue __mdh_pair_up(MdhValue list1, MdhValue list2) {
     return result;
 }
 
-MdhValue __mdh_tae_binary(MdhValue n) {
-    /* Convert integer to binary string */
-    if (n.tag != MDH_TAG_INT) {
-        return __mdh_make_string("0");
+MdhValue __mdh_tae_base(MdhValue n, MdhValue base) {
+    /* Write an integer in base 2-36, lowercase, negatives with a leading '-' */
+    if (n.tag != MDH_TAG_INT || base.tag != MDH_TAG_INT) {
+        __mdh_type_error("tae_base", n.tag, base.tag);
+        return __mdh_make_string("");
+    }
+    int64_t b = base.data;
+    if (b < 2 || b > 36) {
+        char msg[96];
+        snprintf(msg, sizeof(msg), "Base must be atween 2 an' 36, no' %lld", (long long)b);
+        __mdh_hurl(__mdh_make_string(msg));
+        return __mdh_make_string("");
     }
 
+    static const char digits[] = "0123456789abcdefghijklmnopqrstuvwxyz";
     int64_t val = n.data;
-    if (val == 0) return __mdh_make_string("0");
-
-    char buf[65];  /* 64 bits + null */
-    int idx = 64;
-    buf[idx--] = '\0';
+    /* Negate in unsigned arithmetic so INT64_MIN does not overflow */
+    uint64_t rest = val < 0 ? (uint64_t)0 - (uint64_t)val : (uint64_t)val;
+    char buf[66];  /* 64 binary digits + sign + null */
+    int idx = 65;
+    buf[idx] = '\0';
+    do {
+        buf[--idx] = digits[rest % (uint64_t)b];
+        rest /= (uint64_t)b;
+    } while (rest > 0);
+    if (val < 0) {
+        buf[--idx] = '-';
+    }
+    return __mdh_make_string(&buf[idx]);
+}
+
+MdhValue __mdh_frae_base(MdhValue str, MdhValue base) {
+    /* Read a string written in base 2-36, with an optional sign */
+    if (str.tag != MDH_TAG_STRING || base.tag != MDH_TAG_INT) {
+        __mdh_type_error("frae_base", str.tag, base.tag);
+        return __mdh_make_int(0);
+    }
+    int64_t b = base.data;
+    char msg[160];
+    if (b < 2 || b > 36) {
+        snprintf(msg, sizeof(msg), "Base must be atween 2 an' 36, no' %lld", (long long)b);
+        __mdh_hurl(__mdh_make_string(msg));
+        return __mdh_make_int(0);
+    }
 
-    int64_t abs_val = val < 0 ? -val : val;
-    while (abs_val > 0 && idx >= 0) {
-        buf[idx--] = (abs_val & 1) ? '1' : '0';
-        abs_val >>= 1;
+    const char *text = __mdh_get_string(str);
+    const char *s = text;
+    int negative = 0;
+    if (*s == '-' || *s == '+') {
+        negative = (*s == '-');
+        s++;
+    }
+    if (*s == '\0') {
+        snprintf(msg, sizeof(msg), "Cannae read '%.64s' as a base %lld number", text, (long long)b);
+        __mdh_hurl(__mdh_make_string(msg));
+        return __mdh_make_int(0);
     }
-    if (val < 0 && idx >= 0) {
-        buf[idx--] = '-';
+
+    uint64_t limit = negative ? (uint64_t)INT64_MAX + 1 : (uint64_t)INT64_MAX;
+    uint64_t value = 0;
+    for (; *s; s++) {
+        int c = tolower((unsigned char)*s);
+        int digit = (c >= '0' && c <= '9') ? c - '0'
+                  : (c >= 'a' && c <= 'z') ? c - 'a' + 10
+                  : 99;
+        if (digit >= b) {
+            snprintf(msg, sizeof(msg), "'%c' isnae a base %lld digit (in '%.64s')", *s, (long long)b, text);
+            __mdh_hurl(__mdh_make_string(msg));
+            return __mdh_make_int(0);
+        }
+        if (value > (limit - (uint64_t)digit) / (uint64_t)b) {
+            snprintf(msg, sizeof(msg), "'%.64s' is too big fer an integer", text);
+            __mdh_hurl(__mdh_make_string(msg));
+            return __mdh_make_int(0);
+        }
+        value = value * (uint64_t)b + (uint64_t)digit;
     }
+    return __mdh_make_int(negative ? (int64_t)((uint64_t)0 - value) : (int64_t)value);
+}
 
-    return __mdh_make_string(&buf[idx + 1]);
+MdhValue __mdh_tae_binary(MdhValue n) {
+    /* Convert integer to binary string */
+    return __mdh_tae_base(n, __mdh_make_int(2));
 }
 
 MdhValue __mdh_fae_binary(MdhValue str) {
@@ -4908,32 +5330,12 @@ MdhValue __mdh_title_case(MdhValue str) {
 
 MdhValue __mdh_tae_hex(MdhValue num) {
     /* Convert integer to hex string */
-    if (num.tag != MDH_TAG_INT) {
-        return __mdh_make_string("0");
-    }
-    int64_t n = num.data;
-    char buf[32];
-    if (n < 0) {
-        snprintf(buf, sizeof(buf), "-%llx", (unsigned long long)(-n));
-    } else {
-        snprintf(buf, sizeof(buf), "%llx", (unsigned long long)n);
-    }
-    return __mdh_make_string(buf);
+    return __mdh_tae_base(num, __mdh_make_int(16));
 }
 
 MdhValue __mdh_tae_octal(MdhValue num) {
     /* Convert integer to octal string */
-    if (num.tag != MDH_TAG_INT) {
-        return __mdh_make_string("0");
-    }
-    int64_t n = num.data;
-    char buf[32];
-    if (n < 0) {
-        snprintf(buf, sizeof(buf), "-%llo", (unsigned long long)(-n));
-    } else {
-        snprintf(buf, sizeof(buf), "%llo", (unsigned long long)n);
-    }
-    return __mdh_make_string(buf);
+    return __mdh_tae_base(num, __mdh_make_int(8));
 }
 
 MdhValue __mdh_center(MdhValue str, MdhValue width_val) {
@@ -5391,16 +5793,33 @@ MdhValue __mdh_assert(MdhValue condition, MdhValue msg) {
     }
 
     if (!cond) {
-        printf("Assertion failed");
+        /* Hurled, so a hae_a_bash can catch it the same as in the interpreter */
+        char buf[1024];
         if (msg.tag == MDH_TAG_STRING) {
-            printf(": %s", (const char *)(intptr_t)msg.data);
+            snprintf(buf, sizeof(buf), "Assertion failed: %s", __mdh_get_string(msg));
+        } else {
+            snprintf(buf, sizeof(buf), "Assertion failed");
         }
-        printf("\n");
-        exit(1);
+        __mdh_hurl(__mdh_make_string(buf));
     }
     return __mdh_make_nil();
 }
 
+MdhValue __mdh_assert_eq(MdhValue actual, MdhValue expected) {
+    if (!__mdh_eq(actual, expected)) {
+        char buf[1024];
+        snprintf(
+            buf,
+            sizeof(buf),
+            "Assertion failed: Expected %s but got %s",
+            __mdh_get_string(__mdh_to_string(expected)),
+            __mdh_get_string(__mdh_to_string(actual))
+        );
+        __mdh_hurl(__mdh_make_string(buf));
+    }
+    return __mdh_make_bool(true);
+}
+
 MdhValue __mdh_skip(MdhValue reason) {
     printf("Test skipped");
     if (reason.tag == MDH_TAG_STRING) {
@@ -5865,6 +6284,11 @@ void __mdh_set_args(int32_t argc, char **argv) {
     __mdh_argv = argv;
 }
 
+void __mdh_set_function_table(const int64_t *table, int64_t count) {
+    __mdh_function_table = table;
+    __mdh_function_count = count;
+}
+
 MdhValue __mdh_args(void) {
     MdhValue result = __mdh_make_list(__mdh_argc);
     for (int32_t i = 0; i < __mdh_argc; i++) {
@@ -6232,6 +6656,41 @@ MdhValue __mdh_is_dir(MdhValue path) {
     return __mdh_make_bool(S_ISDIR(st.st_mode));
 }
 
+MdhValue __mdh_is_file(MdhValue path) {
+    if (path.tag != MDH_TAG_STRING) {
+        __mdh_type_error("is_file", path.tag, 0);
+        return __mdh_make_bool(false);
+    }
+    const char *p = __mdh_get_string(path);
+    struct stat st;
+    if (stat(p, &st) != 0) {
+        return __mdh_make_bool(false);
+    }
+    return __mdh_make_bool(S_ISREG(st.st_mode));
+}
+
+static MdhValue __mdh_dir_call(const char *name, MdhRsResult (*op)(MdhValue), MdhValue path) {
+    if (path.tag != MDH_TAG_STRING) {
+        __mdh_type_error(name, path.tag, 0);
+        return __mdh_make_list(0);
+    }
+
+    MdhRsResult r = op(path);
+    if (!r.ok) {
+        __mdh_hurl(r.error);
+        return __mdh_make_list(0);
+    }
+    return r.value;
+}
+
+MdhValue __mdh_read_dir(MdhValue path) {
+    return __mdh_dir_call("read_dir", __mdh_rs_read_dir, path);
+}
+
+MdhValue __mdh_walk_dir(MdhValue path) {
+    return __mdh_dir_call("walk_dir", __mdh_rs_walk_dir, path);
+}
+
 MdhValue __mdh_scrieve_append(MdhValue path, MdhValue content) {
     if (path.tag != MDH_TAG_STRING) {
         __mdh_type_error("scrieve_append", path.tag, 0);
@@ -6305,6 +6764,37 @@ MdhValue __mdh_date_format(MdhValue timestamp_secs, MdhValue format) {
     return __mdh_string_from_buf(buf);
 }
 
+MdhValue __mdh_format_time(MdhValue epoch_secs, MdhValue format) {
+    if ((epoch_secs.tag != MDH_TAG_INT && epoch_secs.tag != MDH_TAG_FLOAT) ||
+        format.tag != MDH_TAG_STRING) {
+        __mdh_type_error("format_time", epoch_secs.tag, format.tag);
+        return __mdh_make_string("");
+    }
+
+    /* Always UTC, so the same epoch formats the same everywhere */
+    time_t sec = (epoch_secs.tag == MDH_TAG_FLOAT)
+        ? (time_t)floor(__mdh_get_float(epoch_secs))
+        : (time_t)epoch_secs.data;
+    struct tm tm_val;
+    gmtime_r(&sec, &tm_val);
+
+    const char *fmt = __mdh_get_string(format);
+    size_t cap = 128;
+    char *buf = (char *)GC_malloc(cap);
+    size_t out = strftime(buf, cap, fmt, &tm_val);
+    while (out == 0 && cap < 8192) {
+        cap *= 2;
+        buf = (char *)GC_realloc(buf, cap);
+        out = strftime(buf, cap, fmt, &tm_val);
+    }
+    if (out == 0) {
+        __mdh_hurl(__mdh_make_string("Couldnae format time"));
+        return __mdh_make_string("");
+    }
+
+    return __mdh_string_from_buf(buf);
+}
+
 MdhValue __mdh_date_parse(MdhValue date_str, MdhValue format) {
     if (date_str.tag != MDH_TAG_STRING || format.tag != MDH_TAG_STRING) {
         __mdh_type_error("date_parse", date_str.tag, format.tag);
@@ -7132,7 +7622,7 @@ static void __mdh_json_stringify_value(MdhStrBuf *sb, MdhValue v, bool pretty, i
                 __mdh_sb_append(sb, "null");
             } else {
                 char buf[64];
-                snprintf(buf, sizeof(buf), "%.15g", f);
+                __mdh_format_float(f, buf, sizeof(buf));
                 __mdh_sb_append(sb, buf);
             }
             return;
@@ -7286,6 +7776,352 @@ MdhValue __mdh_json_pretty(MdhValue value) {
     return r.value;
 }
 
+MdhValue __mdh_json_stringify_opts(MdhValue value, MdhValue indent, MdhValue sort_keys) {
+    MdhRsResult r = __mdh_rs_json_stringify_opts(value, indent, sort_keys);
+    if (!r.ok) {
+        __mdh_hurl(r.error);
+        return __mdh_make_string("");
+    }
+    return r.value;
+}
+
+MdhValue __mdh_json_query(MdhValue value, MdhValue path) {
+    MdhRsResult r = __mdh_rs_json_query(value, path);
+    if (!r.ok) {
+        __mdh_hurl(r.error);
+        return __mdh_make_nil();
+    }
+    return r.value;
+}
+
+/* ========== Digests (Rust FFI) ========== */
+
+static MdhValue __mdh_digest(const char *name, MdhRsResult (*digest)(MdhValue), MdhValue data) {
+    if (data.tag != MDH_TAG_BYTES && data.tag != MDH_TAG_STRING) {
+        __mdh_type_error(name, data.tag, 0);
+        return __mdh_make_nil();
+    }
+
+    MdhRsResult r = digest(data);
+    if (!r.ok) {
+        __mdh_hurl(r.error);
+        return __mdh_make_nil();
+    }
+    return r.value;
+}
+
+MdhValue __mdh_sha256(MdhValue data) {
+    return __mdh_digest("sha256", __mdh_rs_sha256, data);
+}
+
+MdhValue __mdh_sha1(MdhValue data) {
+    return __mdh_digest("sha1", __mdh_rs_sha1, data);
+}
+
+MdhValue __mdh_md5(MdhValue data) {
+    return __mdh_digest("md5", __mdh_rs_md5, data);
+}
+
+MdhValue __mdh_sha256_hex(MdhValue data) {
+    return __mdh_digest("sha256_hex", __mdh_rs_sha256_hex, data);
+}
+
+MdhValue __mdh_sha1_hex(MdhValue data) {
+    return __mdh_digest("sha1_hex", __mdh_rs_sha1_hex, data);
+}
+
+MdhValue __mdh_md5_hex(MdhValue data) {
+    return __mdh_digest("md5_hex", __mdh_rs_md5_hex, data);
+}
+
+static MdhValue __mdh_digest2(const char *name, MdhRsResult (*op)(MdhValue, MdhValue), MdhValue a, MdhValue b) {
+    if (a.tag != MDH_TAG_BYTES && a.tag != MDH_TAG_STRING) {
+        __mdh_type_error(name, a.tag, b.tag);
+        return __mdh_make_nil();
+    }
+    if (b.tag != MDH_TAG_BYTES && b.tag != MDH_TAG_STRING) {
+        __mdh_type_error(name, a.tag, b.tag);
+        return __mdh_make_nil();
+    }
+
+    MdhRsResult r = op(a, b);
+    if (!r.ok) {
+        __mdh_hurl(r.error);
+        return __mdh_make_nil();
+    }
+    return r.value;
+}
+
+MdhValue __mdh_hmac_sha256(MdhValue key, MdhValue message) {
+    return __mdh_digest2("hmac_sha256", __mdh_rs_hmac_sha256, key, message);
+}
+
+MdhValue __mdh_hmac_sha256_hex(MdhValue key, MdhValue message) {
+    return __mdh_digest2("hmac_sha256_hex", __mdh_rs_hmac_sha256_hex, key, message);
+}
+
+MdhValue __mdh_ct_eq(MdhValue a, MdhValue b) {
+    return __mdh_digest2("ct_eq", __mdh_rs_ct_eq, a, b);
+}
+
+/* Secure random bytes come from the OS via the Rust runtime, not the shuffle PRNG */
+MdhValue __mdh_random_bytes(MdhValue n) {
+    MdhRsResult r = __mdh_rs_random_bytes(n);
+    if (!r.ok) {
+        __mdh_hurl(r.error);
+        return __mdh_make_nil();
+    }
+    return r.value;
+}
+
+MdhValue __mdh_random_hex(MdhValue n) {
+    MdhRsResult r = __mdh_rs_random_hex(n);
+    if (!r.ok) {
+        __mdh_hurl(r.error);
+        return __mdh_make_nil();
+    }
+    return r.value;
+}
+
+MdhValue __mdh_pbkdf2(MdhValue password, MdhValue salt, MdhValue iterations, MdhValue length) {
+    MdhRsResult r = __mdh_rs_pbkdf2(password, salt, iterations, length);
+    if (!r.ok) {
+        __mdh_hurl(r.error);
+        return __mdh_make_nil();
+    }
+    return r.value;
+}
+
+/* ========== Hex (Rust FFI) ========== */
+
+static MdhValue __mdh_hex_call(const char *name, MdhRsResult (*op)(MdhValue), MdhValue v, uint8_t want) {
+    if (v.tag != want) {
+        __mdh_type_error(name, v.tag, 0);
+        return __mdh_make_nil();
+    }
+
+    MdhRsResult r = op(v);
+    if (!r.ok) {
+        __mdh_hurl(r.error);
+        return __mdh_make_nil();
+    }
+    return r.value;
+}
+
+MdhValue __mdh_hex_encode(MdhValue data) {
+    return __mdh_hex_call("hex_encode", __mdh_rs_hex_encode, data, MDH_TAG_BYTES);
+}
+
+MdhValue __mdh_hex_decode(MdhValue text) {
+    return __mdh_hex_call("hex_decode", __mdh_rs_hex_decode, text, MDH_TAG_STRING);
+}
+
+MdhValue __mdh_hexdump(MdhValue data) {
+    return __mdh_hex_call("hexdump", __mdh_rs_hexdump, data, MDH_TAG_BYTES);
+}
+
+void __mdh_format_float(double value, char *buf, int64_t cap) {
+    if (cap <= 0) {
+        return;
+    }
+    __mdh_rs_format_float(value, buf, (size_t)cap);
+}
+
+MdhValue __mdh_fmt_float(MdhValue value, MdhValue decimals) {
+    if (value.tag != MDH_TAG_FLOAT && value.tag != MDH_TAG_INT) {
+        __mdh_type_error("fmt_float", value.tag, 0);
+        return __mdh_make_nil();
+    }
+    if (decimals.tag != MDH_TAG_INT) {
+        __mdh_type_error("fmt_float", decimals.tag, 0);
+        return __mdh_make_nil();
+    }
+
+    MdhRsResult r = __mdh_rs_fmt_float(value, decimals);
+    if (!r.ok) {
+        __mdh_hurl(r.error);
+        return __mdh_make_nil();
+    }
+    return r.value;
+}
+
+static void __mdh_format_fail(const char *message, const char *spec) {
+    char buf[256];
+    if (spec) {
+        snprintf(buf, sizeof(buf), message, spec);
+    } else {
+        snprintf(buf, sizeof(buf), "%s", message);
+    }
+    __mdh_hurl(__mdh_make_string(buf));
+}
+
+/* format(template, ...): `{}` placeholders filled in order, with an optional
+   `{:W}` width and `{:.P}` precision; numbers pad on the left, the rest on the right */
+MdhValue __mdh_format(MdhValue template_str, MdhValue args) {
+    if (template_str.tag != MDH_TAG_STRING) {
+        __mdh_hurl(__mdh_make_string("format() needs a template string first"));
+        return __mdh_make_nil();
+    }
+    const char *s = __mdh_get_string(template_str);
+    int64_t argc = __mdh_list_len(args);
+    int64_t next = 0;
+
+    MdhStrBuf sb;
+    __mdh_sb_init(&sb);
+    while (*s) {
+        if ((s[0] == '{' && s[1] == '{') || (s[0] == '}' && s[1] == '}')) {
+            __mdh_sb_append_char(&sb, s[0]);
+            s += 2;
+            continue;
+        }
+        if (*s == '}') {
+            __mdh_format_fail("format() found a lone '}' - write '}}' fer a brace", NULL);
+            return __mdh_make_nil();
+        }
+        if (*s != '{') {
+            __mdh_sb_append_char(&sb, *s++);
+            continue;
+        }
+
+        const char *close = strchr(s, '}');
+        if (!close) {
+            __mdh_format_fail("format() found a '{' that's never closed - write '{{' fer a brace", NULL);
+            return __mdh_make_nil();
+        }
+        char spec[64];
+        size_t spec_len = (size_t)(close - s - 1);
+        if (spec_len >= sizeof(spec)) {
+            spec_len = sizeof(spec) - 1;
+        }
+        memcpy(spec, s + 1, spec_len);
+        spec[spec_len] = '\0';
+        s = close + 1;
+
+        /* Spec is empty or `:` then digits, with an optional `.` and more digits */
+        const char *p = spec;
+        long width = 0;
+        long precision = -1;
+        bool ok = true;
+        if (*p == ':') {
+            p++;
+            while (isdigit((unsigned char)*p)) {
+                width = width * 10 + (*p++ - '0');
+            }
+            if (*p == '.') {
+                p++;
+                ok = isdigit((unsigned char)*p);
+                precision = 0;
+                while (isdigit((unsigned char)*p)) {
+                    precision = precision * 10 + (*p++ - '0');
+                }
+                ok = ok && precision <= 100;
+            }
+        }
+        if (!ok || *p != '\0' || width > 4096) {
+            __mdh_format_fail("format() disnae ken the placeholder '{%s}'", spec);
+            return __mdh_make_nil();
+        }
+        if (next >= argc) {
+            char buf[128];
+            snprintf(buf, sizeof(buf), "format() has mair placeholders than values - only gied %lld",
+                     (long long)argc);
+            __mdh_hurl(__mdh_make_string(buf));
+            return __mdh_make_nil();
+        }
+
+        MdhValue value = __mdh_list_get(args, next++);
+        bool is_number = value.tag == MDH_TAG_INT || value.tag == MDH_TAG_FLOAT;
+        MdhValue text;
+        if (precision >= 0) {
+            if (!is_number) {
+                __mdh_format_fail("format() precision is fer numbers, no' a %s", __mdh_type_name(value));
+                return __mdh_make_nil();
+            }
+            double x = value.tag == MDH_TAG_INT ? (double)value.data : __mdh_get_float(value);
+            MdhStrBuf num;
+            __mdh_sb_init(&num);
+            int n = snprintf(NULL, 0, "%.*f", (int)precision, x);
+            __mdh_sb_reserve(&num, (size_t)n);
+            snprintf(num.buf, (size_t)n + 1, "%.*f", (int)precision, x);
+            num.len = (size_t)n;
+            text = __mdh_string_from_buf(num.buf);
+        } else {
+            text = __mdh_to_string(value);
+        }
+
+        const char *t = __mdh_get_string(text);
+        long chars = 0;
+        for (const char *c = t; *c; c++) {
+            if (((unsigned char)*c & 0xC0) != 0x80) {
+                chars++;
+            }
+        }
+        long pad = width > chars ? width - chars : 0;
+        if (!is_number) {
+            __mdh_sb_append(&sb, t);
+        }
+        for (long i = 0; i < pad; i++) {
+            __mdh_sb_append_char(&sb, ' ');
+        }
+        if (is_number) {
+            __mdh_sb_append(&sb, t);
+        }
+    }
+
+    if (next < argc) {
+        char buf[128];
+        snprintf(buf, sizeof(buf),
+                 "format() wis gied %lld values but the template only has %lld placeholders",
+                 (long long)argc, (long long)next);
+        __mdh_hurl(__mdh_make_string(buf));
+        return __mdh_make_nil();
+    }
+    return __mdh_string_from_buf(sb.buf);
+}
+
+/* ========== CSV ========== */
+
+/* csv_parse(text, delim?): a list of rows, each a list of string fields;
+   `delim` is nil for a comma */
+MdhValue __mdh_csv_parse(MdhValue text, MdhValue delim) {
+    MdhRsResult r = __mdh_rs_csv_parse(text, delim);
+    if (!r.ok) {
+        __mdh_hurl(r.error);
+        return __mdh_make_nil();
+    }
+    return r.value;
+}
+
+/* csv_write(rows, delim?): rows back to CSV text, quoting where needed */
+MdhValue __mdh_csv_write(MdhValue rows, MdhValue delim) {
+    MdhRsResult r = __mdh_rs_csv_write(rows, delim);
+    if (!r.ok) {
+        __mdh_hurl(r.error);
+        return __mdh_make_nil();
+    }
+    return r.value;
+}
+
+/* ========== UUIDs ========== */
+
+MdhValue __mdh_uuid_v4(void) {
+    MdhRsResult r = __mdh_rs_uuid_v4();
+    if (!r.ok) {
+        __mdh_hurl(r.error);
+        return __mdh_make_nil();
+    }
+    return r.value;
+}
+
+MdhValue __mdh_uuid_v7(void) {
+    MdhRsResult r = __mdh_rs_uuid_v7();
+    if (!r.ok) {
+        __mdh_hurl(r.error);
+        return __mdh_make_nil();
+    }
+    return r.value;
+}
+
 /* ========== Misc Parity Helpers ========== */
 
 static bool __mdh_char_in_set(unsigned char c, const char *set) {
@@ -7335,8 +8171,11 @@ MdhValue __mdh_is_a(MdhValue value, MdhValue type_name) {
         matches = value.tag == MDH_TAG_NIL;
     } else if (strcmp(t, "range") == 0) {
         matches = value.tag == MDH_TAG_RANGE;
+    } else if (strcmp(t, "instance") == 0) {
+        matches = value.tag == MDH_TAG_INSTANCE;
     } else {
-        matches = false;
+        const char *class_name = __mdh_instance_class_name(value);
+        matches = class_name && strcmp(t, class_name) == 0;
     }
 
     return __mdh_make_bool(matches);
@@ -8230,9 +9069,12 @@ MdhValue __mdh_clype(MdhValue val) {
         case MDH_TAG_INT:
             snprintf(info, sizeof(info), "integer: %lld", (long long)val.data);
             break;
-        case MDH_TAG_FLOAT:
-            snprintf(info, sizeof(info), "float: %g", __mdh_get_float(val));
+        case MDH_TAG_FLOAT: {
+            char num[64];
+            __mdh_format_float(__mdh_get_float(val), num, sizeof(num));
+            snprintf(info, sizeof(info), "float: %s", num);
             break;
+        }
         case MDH_TAG_BOOL:
             snprintf(info, sizeof(info), "boolean: %s", val.data ? "aye" : "nae");
             break;
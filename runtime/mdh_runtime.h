@@ -144,6 +144,7 @@ MdhValue __mdh_range(int64_t start, int64_t end, int64_t step);
 MdhValue __mdh_str_concat(MdhValue a, MdhValue b);
 int64_t __mdh_str_len(MdhValue s);
 MdhValue __mdh_to_string(MdhValue a);
+MdhValue __mdh_shaw(MdhValue value, int64_t pretty);
 MdhValue __mdh_to_int(MdhValue a);
 MdhValue __mdh_to_float(MdhValue a);
 
@@ -167,9 +168,13 @@ MdhValue __mdh_abs(MdhValue a);
 MdhValue __mdh_random(int64_t min, int64_t max);
 MdhValue __mdh_jammy(MdhValue min, MdhValue max);
 MdhValue __mdh_random_int(MdhValue min, MdhValue max);
+MdhValue __mdh_seed_random(MdhValue seed);
 MdhValue __mdh_floor(MdhValue a);
 MdhValue __mdh_ceil(MdhValue a);
 MdhValue __mdh_round(MdhValue a);
+MdhValue __mdh_sign(MdhValue x);
+MdhValue __mdh_clamp(MdhValue x, MdhValue lo, MdhValue hi);
+MdhValue __mdh_lerp(MdhValue a, MdhValue b, MdhValue t);
 
 /* ========== Timing ========== */
 
@@ -249,13 +254,15 @@ MdhValue __mdh_tcp_send(MdhValue sock, MdhValue buf);
 MdhValue __mdh_tcp_recv(MdhValue sock, MdhValue max_len);
 
 MdhValue __mdh_dns_lookup(MdhValue host);
-MdhValue __mdh_dns_srv(MdhValue service, MdhValue domain);
-MdhValue __mdh_dns_naptr(MdhValue domain);
+MdhValue __mdh_dns_srv(MdhValue service, MdhValue domain, MdhValue timeout_ms);
+MdhValue __mdh_dns_naptr(MdhValue domain, MdhValue timeout_ms);
 
 /* ========== TLS/DTLS/SRTP ========== */
 
 MdhValue __mdh_tls_client_new(MdhValue config);
 MdhValue __mdh_tls_connect(MdhValue tls, MdhValue sock);
+MdhValue __mdh_tls_connect_start(MdhValue tls, MdhValue sock);
+MdhValue __mdh_tls_handshake_step(MdhValue tls);
 MdhValue __mdh_tls_send(MdhValue tls, MdhValue buf);
 MdhValue __mdh_tls_recv(MdhValue tls, MdhValue max_len);
 MdhValue __mdh_tls_close(MdhValue tls);
@@ -265,6 +272,7 @@ MdhValue __mdh_dtls_handshake(MdhValue dtls, MdhValue sock);
 MdhValue __mdh_srtp_create(MdhValue keys);
 MdhValue __mdh_srtp_protect(MdhValue srtp, MdhValue rtp_packet);
 MdhValue __mdh_srtp_unprotect(MdhValue srtp, MdhValue rtp_packet);
+MdhValue __mdh_srtp_set_roc(MdhValue srtp, MdhValue ssrc, MdhValue roc);
 
 /* ========== Event Loop + Timers ========== */
 
@@ -322,6 +330,7 @@ MdhValue __mdh_dict_get(MdhValue dict, MdhValue key);
 MdhValue __mdh_dict_get_default(MdhValue dict, MdhValue key, MdhValue default_val);
 MdhValue __mdh_dict_merge(MdhValue a, MdhValue b);
 MdhValue __mdh_dict_remove(MdhValue dict, MdhValue key);
+MdhValue __mdh_dict_pop(MdhValue dict, MdhValue key);
 MdhValue __mdh_dict_invert(MdhValue dict);
 MdhValue __mdh_fae_pairs(MdhValue pairs);
 MdhValue __mdh_toss_in(MdhValue dict, MdhValue item);
@@ -342,6 +351,9 @@ MdhValue __mdh_file_delete(MdhValue path);
 MdhValue __mdh_list_dir(MdhValue path);
 MdhValue __mdh_make_dir(MdhValue path);
 MdhValue __mdh_is_dir(MdhValue path);
+MdhValue __mdh_is_file(MdhValue path);
+MdhValue __mdh_read_dir(MdhValue path);
+MdhValue __mdh_walk_dir(MdhValue path);
 MdhValue __mdh_slurp(MdhValue path);
 MdhValue __mdh_scrieve(MdhValue path, MdhValue content);
 MdhValue __mdh_scrieve_append(MdhValue path, MdhValue content);
@@ -382,6 +394,8 @@ MdhValue __mdh_wee(MdhValue a, MdhValue b);
 MdhValue __mdh_tak(MdhValue list, MdhValue n);
 MdhValue __mdh_pair_up(MdhValue list1, MdhValue list2);
 MdhValue __mdh_tae_binary(MdhValue n);
+MdhValue __mdh_tae_base(MdhValue n, MdhValue base);
+MdhValue __mdh_frae_base(MdhValue str, MdhValue base);
 MdhValue __mdh_fae_binary(MdhValue str);
 MdhValue __mdh_fae_hex(MdhValue str);
 MdhValue __mdh_ltrim(MdhValue str);
@@ -407,6 +421,7 @@ MdhValue __mdh_chynge(MdhValue str, MdhValue old_sub, MdhValue new_sub);
 /* ========== Testing ========== */
 
 MdhValue __mdh_assert(MdhValue condition, MdhValue msg);
+MdhValue __mdh_assert_eq(MdhValue actual, MdhValue expected);
 MdhValue __mdh_skip(MdhValue reason);
 MdhValue __mdh_stacktrace(void);
 
@@ -426,6 +441,7 @@ MdhValue __mdh_bit_xor(MdhValue a, MdhValue b);
 /* ========== Environment/System ========== */
 
 void __mdh_set_args(int32_t argc, char **argv);
+void __mdh_set_function_table(const int64_t *table, int64_t count);
 MdhValue __mdh_args(void);
 MdhValue __mdh_cwd(void);
 MdhValue __mdh_chdir(MdhValue path);
@@ -440,6 +456,7 @@ MdhValue __mdh_shell_status(MdhValue cmd);
 
 MdhValue __mdh_date_now(void);
 MdhValue __mdh_date_format(MdhValue timestamp_secs, MdhValue format);
+MdhValue __mdh_format_time(MdhValue epoch_secs, MdhValue format);
 MdhValue __mdh_date_parse(MdhValue date_str, MdhValue format);
 MdhValue __mdh_date_add(MdhValue timestamp_secs, MdhValue amount, MdhValue unit);
 MdhValue __mdh_date_diff(MdhValue ts1, MdhValue ts2, MdhValue unit);
@@ -458,7 +475,41 @@ MdhValue __mdh_regex_split(MdhValue text, MdhValue pattern);
 
 MdhValue __mdh_json_parse(MdhValue json_str);
 MdhValue __mdh_json_stringify(MdhValue value);
+MdhValue __mdh_csv_parse(MdhValue text, MdhValue delim);
+MdhValue __mdh_csv_write(MdhValue rows, MdhValue delim);
+MdhValue __mdh_uuid_v4(void);
+MdhValue __mdh_uuid_v7(void);
 MdhValue __mdh_json_pretty(MdhValue value);
+MdhValue __mdh_json_stringify_opts(MdhValue value, MdhValue indent, MdhValue sort_keys);
+MdhValue __mdh_json_query(MdhValue value, MdhValue path);
+
+/* ========== Digests ========== */
+
+MdhValue __mdh_sha256(MdhValue data);
+MdhValue __mdh_sha1(MdhValue data);
+MdhValue __mdh_md5(MdhValue data);
+MdhValue __mdh_sha256_hex(MdhValue data);
+MdhValue __mdh_sha1_hex(MdhValue data);
+MdhValue __mdh_md5_hex(MdhValue data);
+MdhValue __mdh_hmac_sha256(MdhValue key, MdhValue message);
+MdhValue __mdh_hmac_sha256_hex(MdhValue key, MdhValue message);
+MdhValue __mdh_ct_eq(MdhValue a, MdhValue b);
+MdhValue __mdh_random_bytes(MdhValue n);
+MdhValue __mdh_random_hex(MdhValue n);
+MdhValue __mdh_pbkdf2(MdhValue password, MdhValue salt, MdhValue iterations, MdhValue length);
+
+/* ========== Hex ========== */
+
+MdhValue __mdh_hex_encode(MdhValue data);
+MdhValue __mdh_hex_decode(MdhValue text);
+MdhValue __mdh_hexdump(MdhValue data);
+
+/* ========== Float Formatting ========== */
+
+/* Canonical float text (shortest round-trip, whole floats keep ".0") */
+void __mdh_format_float(double value, char *buf, int64_t cap);
+MdhValue __mdh_fmt_float(MdhValue value, MdhValue decimals);
+MdhValue __mdh_format(MdhValue template_str, MdhValue args);
 
 /* ========== Misc Parity Helpers ========== */
 
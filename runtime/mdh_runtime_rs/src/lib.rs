@@ -4,6 +4,7 @@ use std::io::{Read, Write};
 use std::os::raw::c_char;
 use std::os::unix::io::FromRawFd;
 use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
 
 use regex::Regex;
 use serde_json::Value as JsonValue;
@@ -11,12 +12,17 @@ use rustls::client::{ServerCertVerified, ServerCertVerifier};
 use rustls::{Certificate, ClientConfig, ClientConnection, PrivateKey, RootCertStore, ServerConfig, ServerConnection, ServerName, StreamOwned, OwnedTrustAnchor};
 use rustls_pemfile::{certs, pkcs8_private_keys, rsa_private_keys};
 use libsrtp::{MasterKey, ProtectionProfile, RecvSession, SendSession, StreamConfig};
+use openssl::hash::MessageDigest;
 use openssl::pkcs12::Pkcs12;
 use openssl::pkey::PKey;
+use openssl::sign::Signer;
 use openssl::x509::X509;
-use udp_dtls::{DtlsAcceptor, DtlsConnector, Identity, SrtpProfile, UdpChannel};
+use udp_dtls::{DtlsAcceptor, DtlsConnector, DtlsStream, Identity, SrtpProfile, UdpChannel};
 use trust_dns_resolver::config::{ResolverConfig, ResolverOpts};
+use trust_dns_resolver::error::{ResolveError, ResolveErrorKind};
+use trust_dns_resolver::proto::error::ProtoErrorKind;
 use trust_dns_resolver::proto::rr::{RData, RecordType};
+use trust_dns_resolver::system_conf::read_system_conf;
 use trust_dns_resolver::Resolver;
 
 #[cfg(feature = "audio")]
@@ -132,6 +138,15 @@ pub(crate) unsafe fn mdh_float_value(value: MdhValue) -> f64 {
     f64::from_bits(value.data as u64)
 }
 
+/// Canonical float text - keep in step with `mdhavers::value::format_float`.
+/// Shortest round-trip digits, and whole floats keep their `.0`.
+pub(crate) fn mdh_format_float(f: f64) -> String {
+    format!("{:?}", f)
+}
+
+/// Most decimals `fmt_float` will give (matches the interpreter)
+const MDH_MAX_FLOAT_DECIMALS: i64 = 100;
+
 unsafe fn mdh_bytes_to_vec(value: MdhValue) -> Option<Vec<u8>> {
     if value.tag != MDH_TAG_BYTES || value.data == 0 {
         return None;
@@ -202,10 +217,51 @@ unsafe fn mdh_dict_get_u16(dict: MdhValue, key: &str) -> Option<u16> {
     }
 }
 
-fn make_resolver() -> Result<Resolver, String> {
-    Resolver::from_system_conf()
-        .or_else(|_| Resolver::new(ResolverConfig::default(), ResolverOpts::default()))
-        .map_err(|e| format!("DNS resolver init failed: {}", e))
+/// Upper bound for DNS lookups and TLS/DTLS handshakes when no `timeout_ms` is given.
+const DEFAULT_NET_TIMEOUT_MS: u64 = 10_000;
+
+unsafe fn mdh_timeout_ms(value: MdhValue) -> Result<u64, String> {
+    if value.tag == MDH_TAG_NIL {
+        Ok(DEFAULT_NET_TIMEOUT_MS)
+    } else if value.tag == MDH_TAG_INT && value.data > 0 {
+        Ok(value.data as u64)
+    } else if value.tag == MDH_TAG_FLOAT && mdh_float_value(value) > 0.0 {
+        Ok((mdh_float_value(value) as u64).max(1))
+    } else {
+        Err("timeout_ms must be a positive number".to_string())
+    }
+}
+
+unsafe fn mdh_dict_get_timeout_ms(dict: MdhValue, key: &str) -> Result<u64, String> {
+    mdh_timeout_ms(__mdh_dict_get_default(
+        dict,
+        mdh_make_string_from_rust(key),
+        __mdh_make_nil(),
+    ))
+}
+
+fn io_timed_out(e: &std::io::Error) -> bool {
+    matches!(
+        e.kind(),
+        std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+    )
+}
+
+fn resolve_timed_out(e: &ResolveError) -> bool {
+    match e.kind() {
+        ResolveErrorKind::Timeout => true,
+        ResolveErrorKind::Io(io) => io_timed_out(io),
+        ResolveErrorKind::Proto(proto) => matches!(proto.kind(), ProtoErrorKind::Timeout),
+        _ => false,
+    }
+}
+
+fn make_resolver(timeout_ms: u64) -> Result<Resolver, String> {
+    let (config, mut opts) = read_system_conf()
+        .unwrap_or_else(|_| (ResolverConfig::default(), ResolverOpts::default()));
+    opts.timeout = Duration::from_millis(timeout_ms);
+    opts.attempts = 1;
+    Resolver::new(config, opts).map_err(|e| format!("DNS resolver init failed: {}", e))
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -221,6 +277,7 @@ struct TlsConfigData {
     ca_pem: Option<String>,
     cert_pem: Option<String>,
     key_pem: Option<String>,
+    timeout_ms: u64,
 }
 
 enum TlsStream {
@@ -228,12 +285,20 @@ enum TlsStream {
     Server(StreamOwned<ServerConnection, std::net::TcpStream>),
 }
 
+/// A handshake started by tls_connect_start, stepped by the caller's own loop
+struct TlsHandshake {
+    conn: rustls::Connection,
+    stream: std::net::TcpStream,
+}
+
 struct TlsSession {
     mode: TlsMode,
     server_name: String,
     client_config: Option<Arc<ClientConfig>>,
     server_config: Option<Arc<ServerConfig>>,
     stream: Option<TlsStream>,
+    handshake: Option<TlsHandshake>,
+    timeout_ms: u64,
 }
 
 struct TlsRegistry {
@@ -308,6 +373,66 @@ where
     f(session)
 }
 
+/// Replay window bounds libsrtp accepts, in packets.
+const SRTP_MIN_WINDOW: i64 = 64;
+const SRTP_MAX_WINDOW: i64 = 0x7fff;
+
+/// Optional stream settings from an `srtp_create` config. A starting ROC
+/// belongs to one stream, so it needs the `ssrc` along with it.
+struct SrtpStreamOpts {
+    ssrc: Option<u32>,
+    roc: Option<u32>,
+    window_size: Option<i64>,
+}
+
+fn srtp_u32(name: &str, n: i64) -> Result<u32, String> {
+    u32::try_from(n).map_err(|_| format!("SRTP {} must be atween 0 an' {}", name, u32::MAX))
+}
+
+fn srtp_stream_opts(
+    ssrc: Option<i64>,
+    roc: Option<i64>,
+    window_size: Option<i64>,
+) -> Result<SrtpStreamOpts, String> {
+    let ssrc = ssrc.map(|n| srtp_u32("ssrc", n)).transpose()?;
+    let roc = roc.map(|n| srtp_u32("roc", n)).transpose()?;
+    if roc.is_some() && ssrc.is_none() {
+        return Err("SRTP roc needs an ssrc tae go wi' it".to_string());
+    }
+    if let Some(n) = window_size {
+        if !(SRTP_MIN_WINDOW..=SRTP_MAX_WINDOW).contains(&n) {
+            return Err(format!(
+                "SRTP window_size must be atween {} an' {}",
+                SRTP_MIN_WINDOW, SRTP_MAX_WINDOW
+            ));
+        }
+    }
+    Ok(SrtpStreamOpts {
+        ssrc,
+        roc,
+        window_size,
+    })
+}
+
+unsafe fn mdh_dict_get_opt_int(dict: MdhValue, key: &str) -> Result<Option<i64>, String> {
+    let val = __mdh_dict_get_default(dict, mdh_make_string_from_rust(key), __mdh_make_nil());
+    if val.tag == MDH_TAG_NIL {
+        Ok(None)
+    } else if val.tag == MDH_TAG_INT {
+        Ok(Some(val.data))
+    } else {
+        Err(format!("SRTP {} must be an integer", key))
+    }
+}
+
+unsafe fn mdh_srtp_stream_opts(config: MdhValue) -> Result<SrtpStreamOpts, String> {
+    srtp_stream_opts(
+        mdh_dict_get_opt_int(config, "ssrc")?,
+        mdh_dict_get_opt_int(config, "roc")?,
+        mdh_dict_get_opt_int(config, "window_size")?,
+    )
+}
+
 #[derive(Clone)]
 struct DtlsConfigData {
     mode: TlsMode,
@@ -319,6 +444,7 @@ struct DtlsConfigData {
     remote_host: Option<String>,
     remote_port: Option<u16>,
     srtp_profiles: Vec<SrtpProfile>,
+    timeout_ms: u64,
 }
 
 struct DtlsRegistry {
@@ -377,6 +503,7 @@ fn tls_config_from_value(config: MdhValue) -> Result<TlsConfigData, String> {
                 ca_pem: None,
                 cert_pem: None,
                 key_pem: None,
+                timeout_ms: DEFAULT_NET_TIMEOUT_MS,
             });
         }
         if config.tag != MDH_TAG_DICT {
@@ -453,6 +580,7 @@ fn tls_config_from_value(config: MdhValue) -> Result<TlsConfigData, String> {
         } else {
             None
         };
+        let timeout_ms = mdh_dict_get_timeout_ms(config, "timeout_ms")?;
 
         Ok(TlsConfigData {
             mode,
@@ -461,6 +589,7 @@ fn tls_config_from_value(config: MdhValue) -> Result<TlsConfigData, String> {
             ca_pem,
             cert_pem,
             key_pem,
+            timeout_ms,
         })
     }
 }
@@ -580,6 +709,7 @@ fn dtls_config_from_value(config: MdhValue) -> Result<DtlsConfigData, String> {
                 remote_host: None,
                 remote_port: None,
                 srtp_profiles: vec![SrtpProfile::Aes128CmSha180],
+                timeout_ms: DEFAULT_NET_TIMEOUT_MS,
             });
         }
         if config.tag != MDH_TAG_DICT {
@@ -628,6 +758,7 @@ fn dtls_config_from_value(config: MdhValue) -> Result<DtlsConfigData, String> {
         if profiles.is_empty() {
             profiles.push(SrtpProfile::Aes128CmSha180);
         }
+        let timeout_ms = mdh_dict_get_timeout_ms(config, "timeout_ms")?;
 
         Ok(DtlsConfigData {
             mode,
@@ -639,6 +770,7 @@ fn dtls_config_from_value(config: MdhValue) -> Result<DtlsConfigData, String> {
             remote_host,
             remote_port,
             srtp_profiles: profiles,
+            timeout_ms,
         })
     }
 }
@@ -717,10 +849,88 @@ unsafe fn json_fallback_string(value: MdhValue) -> String {
     format!("\"{}\"", escaped)
 }
 
-unsafe fn mdh_value_to_json(value: MdhValue, pretty: bool, indent: usize) -> String {
-    let ws = "  ".repeat(indent);
-    let ws_inner = "  ".repeat(indent + 1);
+/// How mdh_value_to_json lays out its output
+struct MdhJsonStyle {
+    /// What each nesting level is indented by; `None` keeps it on one line
+    indent: Option<String>,
+    /// Write dict keys in sorted order so the output diffs cleanly
+    sort_keys: bool,
+}
+
+impl MdhJsonStyle {
+    fn compact() -> Self {
+        MdhJsonStyle {
+            indent: None,
+            sort_keys: false,
+        }
+    }
+
+    fn pretty() -> Self {
+        MdhJsonStyle {
+            indent: Some("  ".to_string()),
+            sort_keys: false,
+        }
+    }
+}
+
+/// Widest indent json_pretty will take as a number of spaces
+const MDH_MAX_JSON_INDENT: i64 = 16;
+
+/// Read json_pretty's indent and sort_keys arguments - keep in step with the
+/// interpreter's `json_style`
+unsafe fn mdh_json_style(indent: MdhValue, sort_keys: MdhValue) -> Result<MdhJsonStyle, String> {
+    let indent = match indent.tag {
+        MDH_TAG_NIL => None,
+        MDH_TAG_INT if (0..=MDH_MAX_JSON_INDENT).contains(&indent.data) => {
+            Some(" ".repeat(indent.data as usize))
+        }
+        MDH_TAG_STRING => {
+            let unit = mdh_string_to_rust(indent);
+            if !unit.chars().all(|c| c == ' ' || c == '\t') {
+                return Err("json_pretty() indent string can only hae spaces an' tabs".to_string());
+            }
+            Some(unit)
+        }
+        _ => {
+            return Err(format!(
+                "json_pretty() indent must be naething, 0 tae {} spaces, or a string o' spaces an' tabs",
+                MDH_MAX_JSON_INDENT
+            ))
+        }
+    };
+    let sort_keys = match sort_keys.tag {
+        MDH_TAG_NIL => false,
+        MDH_TAG_BOOL => sort_keys.data != 0,
+        _ => return Err("json_pretty() sort_keys must be aye or nae".to_string()),
+    };
+    Ok(MdhJsonStyle { indent, sort_keys })
+}
+
+/// Wrap the parts of a list or dict in its brackets, one per line when indenting
+fn mdh_json_layout(
+    parts: Vec<String>,
+    style: &MdhJsonStyle,
+    depth: usize,
+    open: char,
+    close: char,
+) -> String {
+    match &style.indent {
+        Some(unit) if !parts.is_empty() => {
+            let inner = unit.repeat(depth + 1);
+            let lines: Vec<String> = parts.iter().map(|p| format!("{}{}", inner, p)).collect();
+            format!(
+                "{}\n{}\n{}{}",
+                open,
+                lines.join(",\n"),
+                unit.repeat(depth),
+                close
+            )
+        }
+        _ => format!("{}{}{}", open, parts.join(", "), close),
+    }
+}
 
+unsafe fn mdh_value_to_json(value: MdhValue, style: &MdhJsonStyle, depth: usize) -> String {
     match value.tag {
         MDH_TAG_NIL => "null".to_string(),
         MDH_TAG_BOOL => {
@@ -736,7 +946,7 @@ unsafe fn mdh_value_to_json(value: MdhValue, pretty: bool, indent: usize) -> Str
             if f.is_nan() || f.is_infinite() {
                 "null".to_string()
             } else {
-                f.to_string()
+                mdh_format_float(f)
             }
         }
         MDH_TAG_STRING => {
@@ -744,80 +954,37 @@ unsafe fn mdh_value_to_json(value: MdhValue, pretty: bool, indent: usize) -> Str
             json_escape_string(&s)
         }
         MDH_TAG_LIST => {
-            if value.data == 0 {
-                return "[]".to_string();
-            }
-            let list_ptr = value.data as *const MdhList;
-            if list_ptr.is_null() {
-                return "[]".to_string();
-            }
-            let list = &*list_ptr;
-            let len = list.length.max(0) as usize;
-            if len == 0 {
-                return "[]".to_string();
-            }
-            let items = if list.items.is_null() {
-                &[] as &[MdhValue]
-            } else {
-                std::slice::from_raw_parts(list.items, len)
-            };
-            if pretty {
-                let parts: Vec<String> = items
-                    .iter()
-                    .map(|v| format!("{}{}", ws_inner, mdh_value_to_json(*v, true, indent + 1)))
-                    .collect();
-                format!("[\n{}\n{}]", parts.join(",\n"), ws)
-            } else {
-                let parts: Vec<String> = items
-                    .iter()
-                    .map(|v| mdh_value_to_json(*v, false, indent))
-                    .collect();
-                format!("[{}]", parts.join(", "))
-            }
+            let items = mdh_list_items(value).unwrap_or(&[]);
+            let parts = items
+                .iter()
+                .map(|v| mdh_value_to_json(*v, style, depth + 1))
+                .collect();
+            mdh_json_layout(parts, style, depth, '[', ']')
         }
         MDH_TAG_SET | MDH_TAG_FUNCTION | MDH_TAG_CLOSURE | MDH_TAG_BYTES => {
             json_fallback_string(value)
         }
         MDH_TAG_DICT => {
-            if value.data == 0 {
-                return "{}".to_string();
-            }
-            let dict_ptr = value.data as *const i64;
-            if dict_ptr.is_null() {
-                return "{}".to_string();
-            }
-            let count = *dict_ptr;
-            if count <= 0 {
-                return "{}".to_string();
-            }
-            let entries_ptr = dict_ptr.add(1) as *const MdhValue;
-            let entries = std::slice::from_raw_parts(entries_ptr, (count * 2) as usize);
-            let mut parts = Vec::with_capacity(count as usize);
-            for i in 0..count {
-                let key_val = entries[(i * 2) as usize];
-                let val = entries[(i * 2 + 1) as usize];
-                let key_str = if key_val.tag == MDH_TAG_STRING {
-                    mdh_string_to_rust(key_val)
-                } else {
-                    mdh_value_to_string(key_val)
-                };
-                let key_json = json_escape_string(&key_str);
-                let val_json = if pretty {
-                    mdh_value_to_json(val, true, indent + 1)
-                } else {
-                    mdh_value_to_json(val, false, indent)
-                };
-                if pretty {
-                    parts.push(format!("{}{}: {}", ws_inner, key_json, val_json));
-                } else {
-                    parts.push(format!("{}: {}", key_json, val_json));
-                }
-            }
-            if pretty {
-                format!("{{\n{}\n{}}}", parts.join(",\n"), ws)
-            } else {
-                format!("{{{}}}", parts.join(", "))
+            let mut pairs: Vec<(String, String)> = mdh_dict_entries(value)
+                .unwrap_or(&[])
+                .chunks_exact(2)
+                .map(|pair| {
+                    let key_str = if pair[0].tag == MDH_TAG_STRING {
+                        mdh_string_to_rust(pair[0])
+                    } else {
+                        mdh_value_to_string(pair[0])
+                    };
+                    (key_str, mdh_value_to_json(pair[1], style, depth + 1))
+                })
+                .collect();
+            if style.sort_keys {
+                pairs.sort_by(|a, b| a.0.cmp(&b.0));
             }
+            let parts = pairs
+                .into_iter()
+                .map(|(key, val)| format!("{}: {}", json_escape_string(&key), val))
+                .collect();
+            mdh_json_layout(parts, style, depth, '{', '}')
         }
         _ => json_fallback_string(value),
     }
@@ -890,7 +1057,7 @@ pub extern "C" fn __mdh_rs_json_parse(json_str: MdhValue) -> MdhRsResult {
 #[no_mangle]
 pub extern "C" fn __mdh_rs_json_stringify(value: MdhValue) -> MdhRsResult {
     match std::panic::catch_unwind(|| unsafe {
-        let s = mdh_value_to_json(value, false, 0);
+        let s = mdh_value_to_json(value, &MdhJsonStyle::compact(), 0);
         mdh_ok(mdh_make_string_from_rust(&s))
     }) {
         Ok(result) => result,
@@ -901,7 +1068,7 @@ pub extern "C" fn __mdh_rs_json_stringify(value: MdhValue) -> MdhRsResult {
 #[no_mangle]
 pub extern "C" fn __mdh_rs_json_pretty(value: MdhValue) -> MdhRsResult {
     match std::panic::catch_unwind(|| unsafe {
-        let s = mdh_value_to_json(value, true, 0);
+        let s = mdh_value_to_json(value, &MdhJsonStyle::pretty(), 0);
         mdh_ok(mdh_make_string_from_rust(&s))
     }) {
         Ok(result) => result,
@@ -909,6 +1076,201 @@ pub extern "C" fn __mdh_rs_json_pretty(value: MdhValue) -> MdhRsResult {
     }
 }
 
+/// json_pretty with a chosen indent (nil for one line, a number of
+/// spaces, or a string like "\t") and optionally sorted dict keys
+#[no_mangle]
+pub extern "C" fn __mdh_rs_json_stringify_opts(
+    value: MdhValue,
+    indent: MdhValue,
+    sort_keys: MdhValue,
+) -> MdhRsResult {
+    match std::panic::catch_unwind(|| unsafe {
+        let style = match mdh_json_style(indent, sort_keys) {
+            Ok(style) => style,
+            Err(e) => return mdh_err(&e),
+        };
+        let s = mdh_value_to_json(value, &style, 0);
+        mdh_ok(mdh_make_string_from_rust(&s))
+    }) {
+        Ok(result) => result,
+        Err(_) => unsafe { mdh_err("Rust panic in json_stringify_opts") },
+    }
+}
+
+/// One step of a json_query path
+#[derive(Debug, Clone, PartialEq)]
+enum MdhJsonStep {
+    Key(String),
+    Index(i64),
+    All,
+}
+
+/// Split a json_query path into steps - keep in step with the interpreter's
+/// `parse_json_path`
+fn mdh_json_path(path: &str) -> Result<Vec<MdhJsonStep>, String> {
+    let chars: Vec<char> = path.chars().collect();
+    let unclosed = || format!("json_query() path '{}' has a '[' that's never closed", path);
+    let empty_key = || format!("json_query() path '{}' has an empty key", path);
+    let mut steps = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] == '[' {
+            i += 1;
+            if let Some(&quote) = chars.get(i).filter(|c| matches!(c, '"' | '\'')) {
+                let end = chars[i + 1..]
+                    .iter()
+                    .position(|&c| c == quote)
+                    .ok_or_else(unclosed)?;
+                let key: String = chars[i + 1..i + 1 + end].iter().collect();
+                steps.push(MdhJsonStep::Key(key));
+                i += end + 2;
+            } else {
+                let end = chars[i..]
+                    .iter()
+                    .position(|&c| c == ']')
+                    .ok_or_else(unclosed)?;
+                let inner: String = chars[i..i + end].iter().collect();
+                let inner = inner.trim();
+                steps.push(if inner == "*" {
+                    MdhJsonStep::All
+                } else {
+                    MdhJsonStep::Index(inner.parse().map_err(|_| {
+                        format!(
+                            "json_query() path '{}' has '[{}]' - use a number, * or a quoted key",
+                            path, inner
+                        )
+                    })?)
+                });
+                i += end;
+            }
+            if chars.get(i) != Some(&']') {
+                return Err(unclosed());
+            }
+            i += 1;
+        } else {
+            let start = i;
+            while i < chars.len() && chars[i] != '.' && chars[i] != '[' {
+                i += 1;
+            }
+            if start == i {
+                return Err(empty_key());
+            }
+            let key: String = chars[start..i].iter().collect();
+            steps.push(if key == "*" {
+                MdhJsonStep::All
+            } else {
+                MdhJsonStep::Key(key)
+            });
+        }
+        if chars.get(i) == Some(&'.') {
+            i += 1;
+            if i == chars.len() {
+                return Err(empty_key());
+            }
+        }
+    }
+    Ok(steps)
+}
+
+/// A dict's entries as a flat key, value, key, value... slice
+unsafe fn mdh_dict_entries<'a>(value: MdhValue) -> Option<&'a [MdhValue]> {
+    if value.tag != MDH_TAG_DICT || value.data == 0 {
+        return None;
+    }
+    let dict_ptr = value.data as *const i64;
+    let count = (*dict_ptr).max(0) as usize;
+    if count == 0 {
+        return Some(&[]);
+    }
+    Some(std::slice::from_raw_parts(
+        dict_ptr.add(1) as *const MdhValue,
+        count * 2,
+    ))
+}
+
+/// One step down: a dict key, or a list index (negatives count from the end)
+unsafe fn mdh_json_step(value: MdhValue, step: &MdhJsonStep) -> Option<MdhValue> {
+    if let (Some(entries), MdhJsonStep::Key(key)) = (mdh_dict_entries(value), step) {
+        return entries
+            .chunks_exact(2)
+            .find(|pair| pair[0].tag == MDH_TAG_STRING && mdh_string_to_rust(pair[0]) == *key)
+            .map(|pair| pair[1]);
+    }
+    let items = mdh_list_items(value)?;
+    let index = match step {
+        MdhJsonStep::Index(i) => *i,
+        MdhJsonStep::Key(key) => key.parse().ok()?,
+        MdhJsonStep::All => return None,
+    };
+    let index = if index < 0 {
+        items.len() as i64 + index
+    } else {
+        index
+    };
+    usize::try_from(index)
+        .ok()
+        .and_then(|i| items.get(i))
+        .copied()
+}
+
+unsafe fn mdh_json_collect(value: MdhValue, steps: &[MdhJsonStep], found: &mut Vec<MdhValue>) {
+    let Some((step, rest)) = steps.split_first() else {
+        if value.tag != MDH_TAG_NIL {
+            found.push(value);
+        }
+        return;
+    };
+    if *step != MdhJsonStep::All {
+        if let Some(next) = mdh_json_step(value, step) {
+            mdh_json_collect(next, rest, found);
+        }
+        return;
+    }
+    if let Some(items) = mdh_list_items(value) {
+        for item in items.iter().copied() {
+            mdh_json_collect(item, rest, found);
+        }
+    } else if let Some(entries) = mdh_dict_entries(value) {
+        for pair in entries.to_vec().chunks_exact(2) {
+            mdh_json_collect(pair[1], rest, found);
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn __mdh_rs_json_query(value: MdhValue, path: MdhValue) -> MdhRsResult {
+    match std::panic::catch_unwind(|| unsafe {
+        if path.tag != MDH_TAG_STRING {
+            return mdh_err("json_query() needs the path as a string");
+        }
+        let steps = match mdh_json_path(&mdh_string_to_rust(path)) {
+            Ok(steps) => steps,
+            Err(e) => return mdh_err(&e),
+        };
+        // Wildcards gather every non-nil match into a flat list
+        if steps.contains(&MdhJsonStep::All) {
+            let mut found = Vec::new();
+            mdh_json_collect(value, &steps, &mut found);
+            let list = __mdh_make_list(found.len() as i32);
+            for item in found {
+                __mdh_list_push(list, item);
+            }
+            return mdh_ok(list);
+        }
+        let mut current = value;
+        for step in &steps {
+            match mdh_json_step(current, step) {
+                Some(next) => current = next,
+                None => return mdh_ok(__mdh_make_nil()),
+            }
+        }
+        mdh_ok(current)
+    }) {
+        Ok(result) => result,
+        Err(_) => unsafe { mdh_err("Rust panic in json_query") },
+    }
+}
+
 #[no_mangle]
 pub extern "C" fn __mdh_rs_regex_test(text: MdhValue, pattern: MdhValue) -> MdhRsResult {
     match std::panic::catch_unwind(|| unsafe {
@@ -1091,12 +1453,702 @@ pub extern "C" fn __mdh_rs_regex_split(text: MdhValue, pattern: MdhValue) -> Mdh
     }
 }
 
+unsafe fn mdh_bytes_or_string(value: MdhValue) -> Option<Vec<u8>> {
+    if value.tag == MDH_TAG_BYTES {
+        Some(mdh_bytes_to_vec(value).unwrap_or_default())
+    } else if value.tag == MDH_TAG_STRING {
+        Some(mdh_string_to_rust(value).into_bytes())
+    } else {
+        None
+    }
+}
+
+fn mdh_hex(data: &[u8]) -> String {
+    data.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+unsafe fn mdh_digest(data: MdhValue, md: MessageDigest, name: &str, hex: bool) -> MdhRsResult {
+    let input = match mdh_bytes_or_string(data) {
+        Some(input) => input,
+        None => return mdh_err(&format!("{} expects bytes or a string", name)),
+    };
+    let digest = match openssl::hash::hash(md, &input) {
+        Ok(d) => d,
+        Err(e) => return mdh_err(&format!("{} failed: {}", name, e)),
+    };
+    if hex {
+        mdh_ok(mdh_make_string_from_rust(&mdh_hex(&digest)))
+    } else {
+        mdh_ok(mdh_make_bytes_from_vec(&digest))
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn __mdh_rs_sha256(data: MdhValue) -> MdhRsResult {
+    match std::panic::catch_unwind(|| unsafe {
+        mdh_digest(data, MessageDigest::sha256(), "sha256", false)
+    }) {
+        Ok(result) => result,
+        Err(_) => unsafe { mdh_err("Rust panic in sha256") },
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn __mdh_rs_sha1(data: MdhValue) -> MdhRsResult {
+    match std::panic::catch_unwind(|| unsafe {
+        mdh_digest(data, MessageDigest::sha1(), "sha1", false)
+    }) {
+        Ok(result) => result,
+        Err(_) => unsafe { mdh_err("Rust panic in sha1") },
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn __mdh_rs_md5(data: MdhValue) -> MdhRsResult {
+    match std::panic::catch_unwind(|| unsafe {
+        mdh_digest(data, MessageDigest::md5(), "md5", false)
+    }) {
+        Ok(result) => result,
+        Err(_) => unsafe { mdh_err("Rust panic in md5") },
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn __mdh_rs_sha256_hex(data: MdhValue) -> MdhRsResult {
+    match std::panic::catch_unwind(|| unsafe {
+        mdh_digest(data, MessageDigest::sha256(), "sha256_hex", true)
+    }) {
+        Ok(result) => result,
+        Err(_) => unsafe { mdh_err("Rust panic in sha256_hex") },
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn __mdh_rs_sha1_hex(data: MdhValue) -> MdhRsResult {
+    match std::panic::catch_unwind(|| unsafe {
+        mdh_digest(data, MessageDigest::sha1(), "sha1_hex", true)
+    }) {
+        Ok(result) => result,
+        Err(_) => unsafe { mdh_err("Rust panic in sha1_hex") },
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn __mdh_rs_md5_hex(data: MdhValue) -> MdhRsResult {
+    match std::panic::catch_unwind(|| unsafe {
+        mdh_digest(data, MessageDigest::md5(), "md5_hex", true)
+    }) {
+        Ok(result) => result,
+        Err(_) => unsafe { mdh_err("Rust panic in md5_hex") },
+    }
+}
+
+unsafe fn mdh_hmac_sha256(key: MdhValue, message: MdhValue, name: &str, hex: bool) -> MdhRsResult {
+    let key = match mdh_bytes_or_string(key) {
+        Some(key) => key,
+        None => return mdh_err(&format!("{} expects key as bytes or a string", name)),
+    };
+    let message = match mdh_bytes_or_string(message) {
+        Some(message) => message,
+        None => return mdh_err(&format!("{} expects message as bytes or a string", name)),
+    };
+    let mac = PKey::hmac(&key).and_then(|pkey| {
+        let mut signer = Signer::new(MessageDigest::sha256(), &pkey)?;
+        signer.update(&message)?;
+        signer.sign_to_vec()
+    });
+    let mac = match mac {
+        Ok(mac) => mac,
+        Err(e) => return mdh_err(&format!("{} failed: {}", name, e)),
+    };
+    if hex {
+        mdh_ok(mdh_make_string_from_rust(&mdh_hex(&mac)))
+    } else {
+        mdh_ok(mdh_make_bytes_from_vec(&mac))
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn __mdh_rs_hmac_sha256(key: MdhValue, message: MdhValue) -> MdhRsResult {
+    match std::panic::catch_unwind(|| unsafe {
+        mdh_hmac_sha256(key, message, "hmac_sha256", false)
+    }) {
+        Ok(result) => result,
+        Err(_) => unsafe { mdh_err("Rust panic in hmac_sha256") },
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn __mdh_rs_hmac_sha256_hex(key: MdhValue, message: MdhValue) -> MdhRsResult {
+    match std::panic::catch_unwind(|| unsafe {
+        mdh_hmac_sha256(key, message, "hmac_sha256_hex", true)
+    }) {
+        Ok(result) => result,
+        Err(_) => unsafe { mdh_err("Rust panic in hmac_sha256_hex") },
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn __mdh_rs_ct_eq(a: MdhValue, b: MdhValue) -> MdhRsResult {
+    match std::panic::catch_unwind(|| unsafe {
+        let (a, b) = match (mdh_bytes_or_string(a), mdh_bytes_or_string(b)) {
+            (Some(a), Some(b)) => (a, b),
+            _ => return mdh_err("ct_eq expects bytes or strings"),
+        };
+        // Lengths are not secret; only the contents get the constant-time treatment
+        let equal = a.len() == b.len() && openssl::memcmp::eq(&a, &b);
+        mdh_ok(__mdh_make_bool(equal))
+    }) {
+        Ok(result) => result,
+        Err(_) => unsafe { mdh_err("Rust panic in ct_eq") },
+    }
+}
+
+/// Most bytes a single random_bytes()/random_hex() call will return
+const MAX_RANDOM_BYTES: i64 = 1 << 20;
+
+/// `n` bytes from the OS CSPRNG - keep in step with the interpreter's `secure_random_bytes`
+unsafe fn mdh_secure_random_bytes(name: &str, n: MdhValue) -> Result<Vec<u8>, String> {
+    if n.tag != MDH_TAG_INT {
+        return Err(format!("{}() expects an integer count", name));
+    }
+    if !(0..=MAX_RANDOM_BYTES).contains(&n.data) {
+        return Err(format!(
+            "{}() count must be atween 0 an' {}, no' {}",
+            name, MAX_RANDOM_BYTES, n.data
+        ));
+    }
+    let mut bytes = vec![0u8; n.data as usize];
+    openssl::rand::rand_bytes(&mut bytes)
+        .map_err(|e| format!("{}() cannae get random bytes: {}", name, e))?;
+    Ok(bytes)
+}
+
+#[no_mangle]
+pub extern "C" fn __mdh_rs_random_bytes(n: MdhValue) -> MdhRsResult {
+    match std::panic::catch_unwind(|| unsafe {
+        match mdh_secure_random_bytes("random_bytes", n) {
+            Ok(bytes) => mdh_ok(mdh_make_bytes_from_vec(&bytes)),
+            Err(e) => mdh_err(&e),
+        }
+    }) {
+        Ok(result) => result,
+        Err(_) => unsafe { mdh_err("Rust panic in random_bytes") },
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn __mdh_rs_random_hex(n: MdhValue) -> MdhRsResult {
+    match std::panic::catch_unwind(|| unsafe {
+        match mdh_secure_random_bytes("random_hex", n) {
+            Ok(bytes) => mdh_ok(mdh_make_string_from_rust(&mdh_hex(&bytes))),
+            Err(e) => mdh_err(&e),
+        }
+    }) {
+        Ok(result) => result,
+        Err(_) => unsafe { mdh_err("Rust panic in random_hex") },
+    }
+}
+
+/// Longest key pbkdf2() will derive in one go
+const MAX_DERIVED_KEY_BYTES: i64 = 1024;
+
+#[no_mangle]
+pub extern "C" fn __mdh_rs_pbkdf2(
+    password: MdhValue,
+    salt: MdhValue,
+    iterations: MdhValue,
+    length: MdhValue,
+) -> MdhRsResult {
+    match std::panic::catch_unwind(|| unsafe {
+        let password = match mdh_bytes_or_string(password) {
+            Some(password) => password,
+            None => return mdh_err("pbkdf2 expects password as bytes or a string"),
+        };
+        let salt = match mdh_bytes_or_string(salt) {
+            Some(salt) => salt,
+            None => return mdh_err("pbkdf2 expects salt as bytes or a string"),
+        };
+        if iterations.tag != MDH_TAG_INT {
+            return mdh_err("pbkdf2 expects an integer iteration count");
+        }
+        if iterations.data <= 0 {
+            return mdh_err("pbkdf2 needs at least 1 iteration");
+        }
+        if length.tag != MDH_TAG_INT || !(1..=MAX_DERIVED_KEY_BYTES).contains(&length.data) {
+            return mdh_err(&format!(
+                "pbkdf2 key length must be atween 1 an' {} bytes",
+                MAX_DERIVED_KEY_BYTES
+            ));
+        }
+        let mut key = vec![0u8; length.data as usize];
+        match openssl::pkcs5::pbkdf2_hmac(
+            &password,
+            &salt,
+            iterations.data as usize,
+            MessageDigest::sha256(),
+            &mut key,
+        ) {
+            Ok(()) => mdh_ok(mdh_make_bytes_from_vec(&key)),
+            Err(e) => mdh_err(&format!("pbkdf2 failed: {}", e)),
+        }
+    }) {
+        Ok(result) => result,
+        Err(_) => unsafe { mdh_err("Rust panic in pbkdf2") },
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn __mdh_rs_hex_encode(data: MdhValue) -> MdhRsResult {
+    match std::panic::catch_unwind(|| unsafe {
+        match mdh_bytes_to_vec(data) {
+            Some(bytes) => mdh_ok(mdh_make_string_from_rust(&mdh_hex(&bytes))),
+            None => mdh_err("hex_encode expects bytes"),
+        }
+    }) {
+        Ok(result) => result,
+        Err(_) => unsafe { mdh_err("Rust panic in hex_encode") },
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn __mdh_rs_hex_decode(text: MdhValue) -> MdhRsResult {
+    match std::panic::catch_unwind(|| unsafe {
+        if text.tag != MDH_TAG_STRING {
+            return mdh_err("hex_decode expects a string");
+        }
+        let text = mdh_string_to_rust(text);
+        if !text.len().is_multiple_of(2) {
+            return mdh_err("hex_decode needs an even number o' hex digits");
+        }
+        let mut out = Vec::with_capacity(text.len() / 2);
+        for pair in text.as_bytes().chunks(2) {
+            let byte = std::str::from_utf8(pair)
+                .ok()
+                .and_then(|p| u8::from_str_radix(p, 16).ok());
+            match byte {
+                Some(b) => out.push(b),
+                None => {
+                    return mdh_err(&format!(
+                        "hex_decode found '{}' - that's no' hex",
+                        String::from_utf8_lossy(pair)
+                    ))
+                }
+            }
+        }
+        mdh_ok(mdh_make_bytes_from_vec(&out))
+    }) {
+        Ok(result) => result,
+        Err(_) => unsafe { mdh_err("Rust panic in hex_decode") },
+    }
+}
+
+/// Write the canonical text for a float into a C buffer (always NUL terminated)
+///
+/// # Safety
+///
+/// `buf` must be null or point at `cap` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn __mdh_rs_format_float(value: f64, buf: *mut c_char, cap: usize) {
+    if buf.is_null() || cap == 0 {
+        return;
+    }
+    let text = mdh_format_float(value);
+    let len = text.len().min(cap - 1);
+    std::ptr::copy_nonoverlapping(text.as_ptr() as *const c_char, buf, len);
+    *buf.add(len) = 0;
+}
+
+#[no_mangle]
+pub extern "C" fn __mdh_rs_fmt_float(value: MdhValue, decimals: MdhValue) -> MdhRsResult {
+    match std::panic::catch_unwind(|| unsafe {
+        let x = match value.tag {
+            MDH_TAG_FLOAT => mdh_float_value(value),
+            MDH_TAG_INT => value.data as f64,
+            _ => return mdh_err("fmt_float() expects a number"),
+        };
+        if decimals.tag != MDH_TAG_INT {
+            return mdh_err("fmt_float() decimals must be an integer");
+        }
+        if !(0..=MDH_MAX_FLOAT_DECIMALS).contains(&decimals.data) {
+            return mdh_err(&format!(
+                "fmt_float() decimals must be between 0 an' {}",
+                MDH_MAX_FLOAT_DECIMALS
+            ));
+        }
+        mdh_ok(mdh_make_string_from_rust(&format!("{:.*}", decimals.data as usize, x)))
+    }) {
+        Ok(result) => result,
+        Err(_) => unsafe { mdh_err("Rust panic in fmt_float") },
+    }
+}
+
+/// The CSV delimiter: a comma for nil, else a single character that is not a
+/// quote or newline
+unsafe fn mdh_csv_delimiter(name: &str, value: MdhValue) -> Result<char, String> {
+    if value.tag == MDH_TAG_NIL {
+        return Ok(',');
+    }
+    if value.tag != MDH_TAG_STRING {
+        return Err(format!("{}() delimiter must be a string", name));
+    }
+    let text = mdh_string_to_rust(value);
+    let mut chars = text.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if !matches!(c, '"' | '\n' | '\r') => Ok(c),
+        _ => Err(format!(
+            "{}() delimiter must be a single character (no' a quote or newline)",
+            name
+        )),
+    }
+}
+
+unsafe fn mdh_list_items<'a>(value: MdhValue) -> Option<&'a [MdhValue]> {
+    if value.tag != MDH_TAG_LIST || value.data == 0 {
+        return None;
+    }
+    let list = &*(value.data as *const MdhList);
+    let len = list.length.max(0) as usize;
+    if list.items.is_null() || len == 0 {
+        return Some(&[]);
+    }
+    Some(std::slice::from_raw_parts(list.items, len))
+}
+
+/// Split CSV text into rows of fields - keep in step with the interpreter's
+/// `csv_parse_rows`. Quoted fields can hold the delimiter, newlines, and `""`
+/// for a quote; a quote anywhere else is an error.
+fn mdh_csv_parse_rows(text: &str, delim: char) -> Result<Vec<Vec<String>>, String> {
+    let mut rows = Vec::new();
+    if text.is_empty() {
+        return Ok(rows);
+    }
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut line = 1;
+    let mut chars = text.chars().peekable();
+    loop {
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            let start = line;
+            loop {
+                match chars.next() {
+                    Some('"') if chars.peek() == Some(&'"') => {
+                        chars.next();
+                        field.push('"');
+                    }
+                    Some('"') => break,
+                    Some(c) => {
+                        if c == '\n' {
+                            line += 1;
+                        }
+                        field.push(c);
+                    }
+                    None => {
+                        return Err(format!(
+                            "csv_parse() found a quoted field on line {} that's never closed",
+                            start
+                        ))
+                    }
+                }
+            }
+            if matches!(chars.peek(), Some(&c) if c != delim && c != '\n' && c != '\r') {
+                return Err(format!(
+                    "csv_parse() found stray text efter a closin' quote on line {}",
+                    line
+                ));
+            }
+        } else {
+            while let Some(&c) = chars.peek() {
+                if c == delim || c == '\n' || c == '\r' {
+                    break;
+                }
+                if c == '"' {
+                    return Err(format!(
+                        "csv_parse() found a quote in the middle o' an unquoted field on line {}",
+                        line
+                    ));
+                }
+                field.push(c);
+                chars.next();
+            }
+        }
+        row.push(std::mem::take(&mut field));
+
+        match chars.next() {
+            Some(c) if c == delim => continue,
+            Some('\r') if chars.peek() == Some(&'\n') => {
+                chars.next();
+            }
+            Some(_) => {}
+            None => {
+                rows.push(row);
+                return Ok(rows);
+            }
+        }
+        line += 1;
+        rows.push(std::mem::take(&mut row));
+        if chars.peek().is_none() {
+            return Ok(rows);
+        }
+    }
+}
+
+/// Write rows as CSV, quoting only the fields that need it, with a newline
+/// after every row
+fn mdh_csv_write_rows(rows: &[Vec<String>], delim: char) -> String {
+    let mut out = String::new();
+    for row in rows {
+        for (i, field) in row.iter().enumerate() {
+            if i > 0 {
+                out.push(delim);
+            }
+            if field.contains([delim, '"', '\n', '\r']) {
+                out.push('"');
+                out.push_str(&field.replace('"', "\"\""));
+                out.push('"');
+            } else {
+                out.push_str(field);
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[no_mangle]
+pub extern "C" fn __mdh_rs_csv_parse(text: MdhValue, delim: MdhValue) -> MdhRsResult {
+    match std::panic::catch_unwind(|| unsafe {
+        if text.tag != MDH_TAG_STRING {
+            return mdh_err("csv_parse() expects a string");
+        }
+        let delim = match mdh_csv_delimiter("csv_parse", delim) {
+            Ok(delim) => delim,
+            Err(e) => return mdh_err(&e),
+        };
+        let rows = match mdh_csv_parse_rows(&mdh_string_to_rust(text), delim) {
+            Ok(rows) => rows,
+            Err(e) => return mdh_err(&e),
+        };
+        let result = __mdh_make_list(rows.len() as i32);
+        for row in rows {
+            let fields = __mdh_make_list(row.len() as i32);
+            for field in row {
+                __mdh_list_push(fields, mdh_make_string_from_rust(&field));
+            }
+            __mdh_list_push(result, fields);
+        }
+        mdh_ok(result)
+    }) {
+        Ok(result) => result,
+        Err(_) => unsafe { mdh_err("Rust panic in csv_parse") },
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn __mdh_rs_csv_write(rows: MdhValue, delim: MdhValue) -> MdhRsResult {
+    match std::panic::catch_unwind(|| unsafe {
+        let Some(items) = mdh_list_items(rows) else {
+            return mdh_err("csv_write() expects a list o' rows");
+        };
+        let delim = match mdh_csv_delimiter("csv_write", delim) {
+            Ok(delim) => delim,
+            Err(e) => return mdh_err(&e),
+        };
+        let mut table = Vec::with_capacity(items.len());
+        for row in items {
+            let Some(fields) = mdh_list_items(*row) else {
+                return mdh_err("csv_write() needs every row tae be a list");
+            };
+            table.push(fields.iter().map(|f| mdh_value_to_string(*f)).collect::<Vec<_>>());
+        }
+        mdh_ok(mdh_make_string_from_rust(&mdh_csv_write_rows(&table, delim)))
+    }) {
+        Ok(result) => result,
+        Err(_) => unsafe { mdh_err("Rust panic in csv_write") },
+    }
+}
+
+/// Sixteen random bytes with the version and RFC 9562 variant stamped on,
+/// written lowercase with hyphens - keep in step with the interpreter's `format_uuid`
+fn mdh_uuid_string(version: u8, millis: Option<u64>) -> Result<String, String> {
+    let mut bytes = [0u8; 16];
+    openssl::rand::rand_bytes(&mut bytes).map_err(|e| format!("Cannae get random bytes: {}", e))?;
+    if let Some(millis) = millis {
+        bytes[..6].copy_from_slice(&millis.to_be_bytes()[2..]);
+    }
+    bytes[6] = (bytes[6] & 0x0f) | (version << 4);
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    Ok(format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    ))
+}
+
+#[no_mangle]
+pub extern "C" fn __mdh_rs_uuid_v4() -> MdhRsResult {
+    match std::panic::catch_unwind(|| unsafe {
+        match mdh_uuid_string(4, None) {
+            Ok(id) => mdh_ok(mdh_make_string_from_rust(&id)),
+            Err(e) => mdh_err(&format!("uuid_v4() {}", e)),
+        }
+    }) {
+        Ok(result) => result,
+        Err(_) => unsafe { mdh_err("Rust panic in uuid_v4") },
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn __mdh_rs_uuid_v7() -> MdhRsResult {
+    match std::panic::catch_unwind(|| unsafe {
+        let millis = match std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH) {
+            Ok(elapsed) => elapsed.as_millis() as u64,
+            Err(e) => return mdh_err(&format!("uuid_v7() cannae read the clock: {}", e)),
+        };
+        match mdh_uuid_string(7, Some(millis)) {
+            Ok(id) => mdh_ok(mdh_make_string_from_rust(&id)),
+            Err(e) => mdh_err(&format!("uuid_v7() {}", e)),
+        }
+    }) {
+        Ok(result) => result,
+        Err(_) => unsafe { mdh_err("Rust panic in uuid_v7") },
+    }
+}
+
 #[no_mangle]
-pub extern "C" fn __mdh_rs_dns_srv(service: MdhValue, domain: MdhValue) -> MdhRsResult {
+pub extern "C" fn __mdh_rs_hexdump(data: MdhValue) -> MdhRsResult {
+    match std::panic::catch_unwind(|| unsafe {
+        let bytes = match mdh_bytes_to_vec(data) {
+            Some(bytes) => bytes,
+            None => return mdh_err("hexdump expects bytes"),
+        };
+        // Same layout as `hexdump -C`: offset, sixteen hex bytes, then the ASCII
+        let mut lines = Vec::new();
+        for (row, chunk) in bytes.chunks(16).enumerate() {
+            let mut line = format!("{:08x}  ", row * 16);
+            for i in 0..16 {
+                match chunk.get(i) {
+                    Some(b) => line.push_str(&format!("{:02x} ", b)),
+                    None => line.push_str("   "),
+                }
+                if i == 7 {
+                    line.push(' ');
+                }
+            }
+            line.push_str(" |");
+            for &b in chunk {
+                line.push(if b.is_ascii_graphic() || b == b' ' {
+                    b as char
+                } else {
+                    '.'
+                });
+            }
+            line.push('|');
+            lines.push(line);
+        }
+        mdh_ok(mdh_make_string_from_rust(&lines.join("\n")))
+    }) {
+        Ok(result) => result,
+        Err(_) => unsafe { mdh_err("Rust panic in hexdump") },
+    }
+}
+
+unsafe fn mdh_string_list(items: &[String]) -> MdhValue {
+    let list = __mdh_make_list(items.len() as i32);
+    for item in items {
+        __mdh_list_push(list, mdh_make_string_from_rust(item));
+    }
+    list
+}
+
+fn mdh_walk_dir(dir: &std::path::Path, prefix: &str, out: &mut Vec<String>) -> std::io::Result<()> {
+    let mut entries = std::fs::read_dir(dir)?.collect::<std::io::Result<Vec<_>>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let rel = if prefix.is_empty() {
+            name
+        } else {
+            format!("{}/{}", prefix, name)
+        };
+        if entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
+            mdh_walk_dir(&entry.path(), &rel, out)?;
+        } else {
+            out.push(rel);
+        }
+    }
+    Ok(())
+}
+
+#[no_mangle]
+pub extern "C" fn __mdh_rs_read_dir(path: MdhValue) -> MdhRsResult {
+    match std::panic::catch_unwind(|| unsafe {
+        let path = mdh_string_to_rust(path);
+        if !std::path::Path::new(&path).is_dir() {
+            return mdh_err(&format!(
+                "read_dir() cannae read '{}' - it's no' a directory",
+                path
+            ));
+        }
+        let names = std::fs::read_dir(&path).and_then(|entries| {
+            entries
+                .map(|entry| entry.map(|e| e.file_name().to_string_lossy().to_string()))
+                .collect::<std::io::Result<Vec<_>>>()
+        });
+        match names {
+            Ok(mut names) => {
+                names.sort();
+                mdh_ok(mdh_string_list(&names))
+            }
+            Err(e) => mdh_err(&format!("Couldnae read directory '{}': {}", path, e)),
+        }
+    }) {
+        Ok(result) => result,
+        Err(_) => unsafe { mdh_err("Rust panic in read_dir") },
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn __mdh_rs_walk_dir(path: MdhValue) -> MdhRsResult {
+    match std::panic::catch_unwind(|| unsafe {
+        let path = mdh_string_to_rust(path);
+        let root = std::path::Path::new(&path);
+        if !root.is_dir() {
+            return mdh_err(&format!(
+                "walk_dir() cannae read '{}' - it's no' a directory",
+                path
+            ));
+        }
+        let mut files = Vec::new();
+        match mdh_walk_dir(root, "", &mut files) {
+            Ok(()) => mdh_ok(mdh_string_list(&files)),
+            Err(e) => mdh_err(&format!("Couldnae read directory '{}': {}", path, e)),
+        }
+    }) {
+        Ok(result) => result,
+        Err(_) => unsafe { mdh_err("Rust panic in walk_dir") },
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn __mdh_rs_dns_srv(
+    service: MdhValue,
+    domain: MdhValue,
+    timeout_ms: MdhValue,
+) -> MdhRsResult {
     match std::panic::catch_unwind(|| unsafe {
         if service.tag != MDH_TAG_STRING || domain.tag != MDH_TAG_STRING {
             return mdh_err("dns_srv expects strings");
         }
+        let timeout_ms = match mdh_timeout_ms(timeout_ms) {
+            Ok(ms) => ms,
+            Err(e) => return mdh_err(&format!("dns_srv {}", e)),
+        };
         let service_s = mdh_string_to_rust(service);
         let domain_s = mdh_string_to_rust(domain);
         let name = if service_s.is_empty() {
@@ -1106,12 +2158,13 @@ pub extern "C" fn __mdh_rs_dns_srv(service: MdhValue, domain: MdhValue) -> MdhRs
             let d = domain_s.trim_start_matches('.');
             format!("{}.{}", s, d)
         };
-        let resolver = match make_resolver() {
+        let resolver = match make_resolver(timeout_ms) {
             Ok(r) => r,
             Err(e) => return mdh_err(&e),
         };
         let lookup = match resolver.lookup(name.as_str(), RecordType::SRV) {
             Ok(l) => l,
+            Err(e) if resolve_timed_out(&e) => return mdh_err("Timed oot"),
             Err(e) => return mdh_err(&format!("DNS SRV lookup failed: {}", e)),
         };
         let list = __mdh_make_list(8);
@@ -1149,18 +2202,23 @@ pub extern "C" fn __mdh_rs_dns_srv(service: MdhValue, domain: MdhValue) -> MdhRs
 }
 
 #[no_mangle]
-pub extern "C" fn __mdh_rs_dns_naptr(domain: MdhValue) -> MdhRsResult {
+pub extern "C" fn __mdh_rs_dns_naptr(domain: MdhValue, timeout_ms: MdhValue) -> MdhRsResult {
     match std::panic::catch_unwind(|| unsafe {
         if domain.tag != MDH_TAG_STRING {
             return mdh_err("dns_naptr expects string");
         }
+        let timeout_ms = match mdh_timeout_ms(timeout_ms) {
+            Ok(ms) => ms,
+            Err(e) => return mdh_err(&format!("dns_naptr {}", e)),
+        };
         let domain_s = mdh_string_to_rust(domain);
-        let resolver = match make_resolver() {
+        let resolver = match make_resolver(timeout_ms) {
             Ok(r) => r,
             Err(e) => return mdh_err(&e),
         };
         let lookup = match resolver.lookup(domain_s.as_str(), RecordType::NAPTR) {
             Ok(l) => l,
+            Err(e) if resolve_timed_out(&e) => return mdh_err("Timed oot"),
             Err(e) => return mdh_err(&format!("DNS NAPTR lookup failed: {}", e)),
         };
         let list = __mdh_make_list(8);
@@ -1207,6 +2265,103 @@ pub extern "C" fn __mdh_rs_dns_naptr(domain: MdhValue) -> MdhRsResult {
     }
 }
 
+fn tls_check_unconnected(session: &TlsSession) -> Result<(), String> {
+    if session.stream.is_some() {
+        return Err("TLS session already connected".to_string());
+    }
+    if session.handshake.is_some() {
+        return Err("TLS handshake already under way".to_string());
+    }
+    Ok(())
+}
+
+/// A fresh client or server connection for this session's config
+fn tls_new_connection(session: &TlsSession) -> Result<rustls::Connection, String> {
+    match session.mode {
+        TlsMode::Client => {
+            let config = session
+                .client_config
+                .as_ref()
+                .ok_or("Missing client config")?
+                .clone();
+            let server_name = ServerName::try_from(session.server_name.as_str())
+                .map_err(|_| "Invalid server_name")?;
+            let conn = ClientConnection::new(config, server_name).map_err(|e| e.to_string())?;
+            Ok(rustls::Connection::Client(conn))
+        }
+        TlsMode::Server => {
+            let config = session
+                .server_config
+                .as_ref()
+                .ok_or("Missing server config")?
+                .clone();
+            let conn = ServerConnection::new(config).map_err(|e| e.to_string())?;
+            Ok(rustls::Connection::Server(conn))
+        }
+    }
+}
+
+fn tls_stream_from(conn: rustls::Connection, stream: std::net::TcpStream) -> TlsStream {
+    match conn {
+        rustls::Connection::Client(conn) => TlsStream::Client(StreamOwned::new(conn, stream)),
+        rustls::Connection::Server(conn) => TlsStream::Server(StreamOwned::new(conn, stream)),
+    }
+}
+
+/// Push a non-blocking handshake as far as the socket allows. Returns "done",
+/// or "want_read"/"want_write" when the caller should wait on the socket.
+fn tls_handshake_advance(hs: &mut TlsHandshake) -> Result<&'static str, String> {
+    let failed = |e: &dyn std::fmt::Display| format!("TLS handshake failed: {}", e);
+    loop {
+        // Send whatever we owe the peer before looking for more from them
+        while hs.conn.wants_write() {
+            match hs.conn.write_tls(&mut hs.stream) {
+                Ok(_) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => return Ok("want_write"),
+                Err(e) => return Err(failed(&e)),
+            }
+        }
+        if !hs.conn.is_handshaking() {
+            return Ok("done");
+        }
+        match hs.conn.read_tls(&mut hs.stream) {
+            Ok(0) => return Err("TLS handshake failed: peer closed the connection".to_string()),
+            Ok(_) => {
+                if let Err(e) = hs.conn.process_new_packets() {
+                    // Tell the peer why, if the socket will take it
+                    let _ = hs.conn.write_tls(&mut hs.stream);
+                    return Err(failed(&e));
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => return Ok("want_read"),
+            Err(e) => return Err(failed(&e)),
+        }
+    }
+}
+
+/// Step the session's pending handshake, handing the stream over to
+/// tls_send/tls_recv (back in blocking mode) once it's done
+fn tls_handshake_step_session(session: &mut TlsSession) -> Result<&'static str, String> {
+    let hs = session
+        .handshake
+        .as_mut()
+        .ok_or("Nae TLS handshake under way (call tls_connect_start first)")?;
+    let status = match tls_handshake_advance(hs) {
+        Ok(status) => status,
+        Err(e) => {
+            session.handshake = None;
+            return Err(e);
+        }
+    };
+    if status == "done" {
+        if let Some(TlsHandshake { conn, stream }) = session.handshake.take() {
+            let _ = stream.set_nonblocking(false);
+            session.stream = Some(tls_stream_from(conn, stream));
+        }
+    }
+    Ok(status)
+}
+
 #[no_mangle]
 pub extern "C" fn __mdh_rs_tls_client_new(config: MdhValue) -> MdhRsResult {
     match std::panic::catch_unwind(|| unsafe {
@@ -1226,6 +2381,8 @@ pub extern "C" fn __mdh_rs_tls_client_new(config: MdhValue) -> MdhRsResult {
                 client_config: Some(client_config),
                 server_config: None,
                 stream: None,
+                handshake: None,
+                timeout_ms: cfg.timeout_ms,
             }
         } else {
             let server_config = match build_server_config(&cfg) {
@@ -1238,6 +2395,8 @@ pub extern "C" fn __mdh_rs_tls_client_new(config: MdhValue) -> MdhRsResult {
                 client_config: None,
                 server_config: Some(server_config),
                 stream: None,
+                handshake: None,
+                timeout_ms: cfg.timeout_ms,
             }
         };
 
@@ -1262,44 +2421,27 @@ pub extern "C" fn __mdh_rs_tls_connect(tls: MdhValue, sock: MdhValue) -> MdhRsRe
         let fd = sock.data as i32;
 
         let res = tls_with_mut(tls_id, |session| {
-            if session.stream.is_some() {
-                return Err("TLS session already connected".to_string());
-            }
+            tls_check_unconnected(session)?;
             let mut stream = std::net::TcpStream::from_raw_fd(fd);
             let _ = stream.set_nonblocking(false);
-
-            match session.mode {
-                TlsMode::Client => {
-                    let config = session
-                        .client_config
-                        .as_ref()
-                        .ok_or("Missing client config")?
-                        .clone();
-                    let server_name = ServerName::try_from(session.server_name.as_str())
-                        .map_err(|_| "Invalid server_name")?;
-                    let mut conn =
-                        ClientConnection::new(config, server_name).map_err(|e| e.to_string())?;
-                    while conn.is_handshaking() {
-                        conn.complete_io(&mut stream)
-                            .map_err(|e| format!("TLS handshake failed: {}", e))?;
-                    }
-                    session.stream = Some(TlsStream::Client(StreamOwned::new(conn, stream)));
-                }
-                TlsMode::Server => {
-                    let config = session
-                        .server_config
-                        .as_ref()
-                        .ok_or("Missing server config")?
-                        .clone();
-                    let mut conn =
-                        ServerConnection::new(config).map_err(|e| e.to_string())?;
-                    while conn.is_handshaking() {
-                        conn.complete_io(&mut stream)
-                            .map_err(|e| format!("TLS handshake failed: {}", e))?;
-                    }
-                    session.stream = Some(TlsStream::Server(StreamOwned::new(conn, stream)));
+            let timeout = Some(Duration::from_millis(session.timeout_ms));
+            let _ = stream.set_read_timeout(timeout);
+            let _ = stream.set_write_timeout(timeout);
+            let handshake_err = |e: std::io::Error| {
+                if io_timed_out(&e) {
+                    "Timed oot".to_string()
+                } else {
+                    format!("TLS handshake failed: {}", e)
                 }
+            };
+
+            let mut conn = tls_new_connection(session)?;
+            while conn.is_handshaking() {
+                conn.complete_io(&mut stream).map_err(handshake_err)?;
             }
+            let _ = stream.set_read_timeout(None);
+            let _ = stream.set_write_timeout(None);
+            session.stream = Some(tls_stream_from(conn, stream));
             Ok(())
         });
 
@@ -1313,6 +2455,62 @@ pub extern "C" fn __mdh_rs_tls_connect(tls: MdhValue, sock: MdhValue) -> MdhRsRe
     }
 }
 
+/// Start a handshake without blocking: the socket is switched to non-blocking
+/// and the first step is taken. Returns the same status as tls_handshake_step.
+/// The session works on its own duplicate of the fd, so the caller's fd stays
+/// open whether this succeeds or fails.
+#[no_mangle]
+pub extern "C" fn __mdh_rs_tls_connect_start(tls: MdhValue, sock: MdhValue) -> MdhRsResult {
+    match std::panic::catch_unwind(|| unsafe {
+        let tls_id = tls.data;
+        if tls.tag != MDH_TAG_INT || tls_id <= 0 {
+            return mdh_err("tls_connect_start expects a TLS handle");
+        }
+        if sock.tag != MDH_TAG_INT || sock.data < 0 {
+            return mdh_err("tls_connect_start expects a socket fd");
+        }
+        let fd = sock.data as i32;
+
+        let res = tls_with_mut(tls_id, |session| {
+            tls_check_unconnected(session)?;
+            let conn = tls_new_connection(session)?;
+            let owned = std::os::unix::io::BorrowedFd::borrow_raw(fd)
+                .try_clone_to_owned()
+                .map_err(|e| format!("TLS handshake failed: {}", e))?;
+            let stream = std::net::TcpStream::from(owned);
+            stream
+                .set_nonblocking(true)
+                .map_err(|e| format!("TLS handshake failed: {}", e))?;
+            session.handshake = Some(TlsHandshake { conn, stream });
+            tls_handshake_step_session(session)
+        });
+
+        match res {
+            Ok(status) => mdh_ok(mdh_make_string_from_rust(status)),
+            Err(e) => mdh_err(&e),
+        }
+    }) {
+        Ok(result) => result,
+        Err(_) => unsafe { mdh_err("Rust panic in tls_connect_start") },
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn __mdh_rs_tls_handshake_step(tls: MdhValue) -> MdhRsResult {
+    match std::panic::catch_unwind(|| unsafe {
+        if tls.tag != MDH_TAG_INT || tls.data <= 0 {
+            return mdh_err("tls_handshake_step expects a TLS handle");
+        }
+        match tls_with_mut(tls.data, tls_handshake_step_session) {
+            Ok(status) => mdh_ok(mdh_make_string_from_rust(status)),
+            Err(e) => mdh_err(&e),
+        }
+    }) {
+        Ok(result) => result,
+        Err(_) => unsafe { mdh_err("Rust panic in tls_handshake_step") },
+    }
+}
+
 #[no_mangle]
 pub extern "C" fn __mdh_rs_tls_send(tls: MdhValue, buf: MdhValue) -> MdhRsResult {
     match std::panic::catch_unwind(|| unsafe {
@@ -1487,19 +2685,35 @@ pub extern "C" fn __mdh_rs_srtp_create(config: MdhValue) -> MdhRsResult {
             None => return mdh_err("Missing SRTP recv_salt"),
         };
 
+        let opts = match mdh_srtp_stream_opts(config) {
+            Ok(opts) => opts,
+            Err(e) => return mdh_err(&e),
+        };
+
         let send_master = MasterKey::new(&send_key, &send_salt, &None);
         let recv_master = MasterKey::new(&recv_key, &recv_salt, &None);
-        let send_cfg = StreamConfig::new(vec![send_master], &profile, &profile);
-        let recv_cfg = StreamConfig::new(vec![recv_master], &profile, &profile);
+        let mut send_cfg = StreamConfig::new(vec![send_master], &profile, &profile);
+        let mut recv_cfg = StreamConfig::new(vec![recv_master], &profile, &profile);
+        if let Some(window_size) = opts.window_size {
+            send_cfg.set_replay_window_size(window_size as u16);
+            recv_cfg.set_replay_window_size(window_size as u16);
+        }
 
         let mut send = SendSession::new();
-        if let Err(e) = send.add_stream(None, &send_cfg) {
+        if let Err(e) = send.add_stream(opts.ssrc, &send_cfg) {
             return mdh_err(&format!("SRTP send session error: {}", e));
         }
         let mut recv = RecvSession::new();
-        if let Err(e) = recv.add_stream(None, &recv_cfg) {
+        if let Err(e) = recv.add_stream(opts.ssrc, &recv_cfg) {
             return mdh_err(&format!("SRTP recv session error: {}", e));
         }
+        // libsrtp only lets a receiver start at a later ROC; a sender
+        // counts its own from the sequence numbers it protects
+        if let (Some(ssrc), Some(roc)) = (opts.ssrc, opts.roc) {
+            if let Err(e) = recv.set_roc(ssrc, roc) {
+                return mdh_err(&format!("SRTP recv roc error: {}", e));
+            }
+        }
 
         let id = srtp_register(SrtpSession { send, recv });
         mdh_ok(__mdh_make_int(id))
@@ -1561,6 +2775,59 @@ pub extern "C" fn __mdh_rs_srtp_unprotect(ctx: MdhValue, packet: MdhValue) -> Md
     }
 }
 
+#[no_mangle]
+pub extern "C" fn __mdh_rs_srtp_set_roc(
+    ctx: MdhValue,
+    ssrc: MdhValue,
+    roc: MdhValue,
+) -> MdhRsResult {
+    match std::panic::catch_unwind(|| unsafe {
+        if ctx.tag != MDH_TAG_INT || ctx.data <= 0 {
+            return mdh_err("srtp_set_roc expects SRTP handle");
+        }
+        if ssrc.tag != MDH_TAG_INT || roc.tag != MDH_TAG_INT {
+            return mdh_err("srtp_set_roc expects integer ssrc an' roc");
+        }
+        let (ssrc, roc) = match srtp_u32("ssrc", ssrc.data)
+            .and_then(|ssrc| Ok((ssrc, srtp_u32("roc", roc.data)?)))
+        {
+            Ok(pair) => pair,
+            Err(e) => return mdh_err(&e),
+        };
+        let res = srtp_with_mut(ctx.data, |session| {
+            session
+                .recv
+                .set_roc(ssrc, roc)
+                .map_err(|e| format!("SRTP set_roc failed: {}", e))
+        });
+        match res {
+            Ok(()) => mdh_ok(__mdh_make_nil()),
+            Err(e) => mdh_err(&e),
+        }
+    }) {
+        Ok(result) => result,
+        Err(_) => unsafe { mdh_err("Rust panic in srtp_set_roc") },
+    }
+}
+
+/// The negotiated cipher suite and the peer certificate's SHA-256 fingerprint,
+/// written the way SDP `a=fingerprint` lines want it (`AB:CD:...`).
+fn dtls_session_info<S>(stream: &DtlsStream<S>) -> (Option<String>, Option<String>) {
+    let ssl = stream.0.ssl();
+    let cipher = ssl.current_cipher().map(|c| c.name().to_string());
+    let fingerprint = ssl
+        .peer_certificate()
+        .and_then(|cert| cert.digest(MessageDigest::sha256()).ok())
+        .map(|digest| {
+            digest
+                .iter()
+                .map(|b| format!("{:02X}", b))
+                .collect::<Vec<_>>()
+                .join(":")
+        });
+    (cipher, fingerprint)
+}
+
 fn identity_from_pem(cert_pem: &str, key_pem: &str) -> Result<Identity, String> {
     let cert = X509::from_pem(cert_pem.as_bytes()).map_err(|e| format!("Invalid cert PEM: {}", e))?;
     let key = PKey::private_key_from_pem(key_pem.as_bytes())
@@ -1608,6 +2875,13 @@ pub extern "C" fn __mdh_rs_dtls_handshake(dtls: MdhValue, sock: MdhValue) -> Mdh
         if let Err(e) = socket.set_nonblocking(false) {
             return mdh_err(&format!("DTLS socket setup failed: {}", e));
         }
+        let timeout = Some(Duration::from_millis(cfg.timeout_ms));
+        if let Err(e) = socket
+            .set_read_timeout(timeout)
+            .and_then(|_| socket.set_write_timeout(timeout))
+        {
+            return mdh_err(&format!("DTLS socket setup failed: {}", e));
+        }
 
         let remote = if let (Some(host), Some(port)) = (cfg.remote_host.clone(), cfg.remote_port) {
             match format!("{}:{}", host, port).parse() {
@@ -1662,6 +2936,7 @@ pub extern "C" fn __mdh_rs_dtls_handshake(dtls: MdhValue, sock: MdhValue) -> Mdh
                     let selected_profile = stream.selected_srtp_profile().ok().flatten();
                     (stream, selected_profile)
                 }
+                Err(udp_dtls::HandshakeError::WouldBlock(_)) => return mdh_err("Timed oot"),
                 Err(err) => {
                     return mdh_err(&format!("DTLS connect failed: {:?}", err));
                 }
@@ -1692,6 +2967,7 @@ pub extern "C" fn __mdh_rs_dtls_handshake(dtls: MdhValue, sock: MdhValue) -> Mdh
                     let selected_profile = stream.selected_srtp_profile().ok().flatten();
                     (stream, selected_profile)
                 }
+                Err(udp_dtls::HandshakeError::WouldBlock(_)) => return mdh_err("Timed oot"),
                 Err(err) => {
                     return mdh_err(&format!("DTLS accept failed: {:?}", err));
                 }
@@ -1719,6 +2995,13 @@ pub extern "C" fn __mdh_rs_dtls_handshake(dtls: MdhValue, sock: MdhValue) -> Mdh
         dict = __mdh_dict_set(dict, mdh_make_string_from_rust("server_salt"), mdh_make_bytes_from_vec(server_salt));
         dict = __mdh_dict_set(dict, mdh_make_string_from_rust("key_len"), __mdh_make_int(key_len as i64));
         dict = __mdh_dict_set(dict, mdh_make_string_from_rust("salt_len"), __mdh_make_int(salt_len as i64));
+        let (cipher, fingerprint) = dtls_session_info(&stream);
+        if let Some(cipher) = cipher {
+            dict = __mdh_dict_set(dict, mdh_make_string_from_rust("cipher"), mdh_make_string_from_rust(&cipher));
+        }
+        if let Some(fingerprint) = fingerprint {
+            dict = __mdh_dict_set(dict, mdh_make_string_from_rust("fingerprint"), mdh_make_string_from_rust(&fingerprint));
+        }
 
         mdh_ok(dict)
     }) {
@@ -53,6 +53,8 @@ pub extern "C" fn dealloc(ptr: *mut u8, size: usize) {
     }
 }
 
+/// Render a whole MIDI file to interleaved stereo f32 samples at its natural
+/// length. See `render_midi_ex` for how the result is reported.
 #[no_mangle]
 pub extern "C" fn render_midi(
     sf_ptr: *const u8,
@@ -60,8 +62,42 @@ pub extern "C" fn render_midi(
     midi_ptr: *const u8,
     midi_len: usize,
     sample_rate: u32,
+) -> *mut f32 {
+    render_midi_ex(sf_ptr, sf_len, midi_ptr, midi_len, sample_rate, 1.0, 0.0)
+}
+
+/// Like `render_midi`, but `tempo` scales the playback speed (2.0 plays twice
+/// as fast, so the natural length halves) and `max_seconds`, when above
+/// zero, sets the rendered length: shorter trims the tune, longer lets it
+/// ring out into silence. Zero keeps the (tempo-scaled) natural length.
+///
+/// Returns a pointer to `render_midi_len()` floats (left, right, left, ...)
+/// and `render_midi_frames()` gives the frame count, always half the float count.
+/// A null pointer with zero frames and no error just means there was nothing
+/// to render; otherwise `last_error_ptr`/`last_error_len` say what went wrong.
+#[no_mangle]
+pub extern "C" fn render_midi_ex(
+    sf_ptr: *const u8,
+    sf_len: usize,
+    midi_ptr: *const u8,
+    midi_len: usize,
+    sample_rate: u32,
+    tempo: f64,
+    max_seconds: f64,
 ) -> *mut f32 {
     clear_error();
+    unsafe {
+        LAST_LEN = 0;
+        LAST_FRAMES = 0;
+    }
+    if !(tempo.is_finite() && tempo > 0.0) {
+        set_error("The tempo must be mair than zero");
+        return std::ptr::null_mut();
+    }
+    if !(max_seconds.is_finite() && max_seconds >= 0.0) {
+        set_error("The maximum length cannae be negative");
+        return std::ptr::null_mut();
+    }
     if sf_ptr.is_null() || sf_len == 0 {
         set_error("Cannae read the soondfont");
         return std::ptr::null_mut();
@@ -103,9 +139,14 @@ pub extern "C" fn render_midi(
     };
 
     let mut sequencer = MidiFileSequencer::new(synth);
+    sequencer.set_speed(tempo);
     sequencer.play(&midi, false);
 
-    let length = midi.get_length();
+    let length = if max_seconds > 0.0 {
+        max_seconds
+    } else {
+        midi.get_length() / tempo
+    };
     let total_frames = if length <= 0.0 {
         0
     } else {
@@ -176,3 +217,49 @@ pub extern "C" fn last_error_ptr() -> *const u8 {
 pub extern "C" fn last_error_len() -> usize {
     unsafe { LAST_ERR_LEN }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn last_error() -> String {
+        let bytes = unsafe { std::slice::from_raw_parts(last_error_ptr(), last_error_len()) };
+        String::from_utf8_lossy(bytes).into_owned()
+    }
+
+    fn render(tempo: f64, max_seconds: f64) -> *mut f32 {
+        // Left over from an earlier render, so the reset is visible
+        unsafe {
+            LAST_LEN = 8;
+            LAST_FRAMES = 4;
+        }
+        let byte = 0u8;
+        render_midi_ex(&byte, 1, &byte, 1, 44100, tempo, max_seconds)
+    }
+
+    #[test]
+    fn test_bad_tempo_and_length_are_rejected_before_loading() {
+        for (tempo, max_seconds, message) in [
+            (0.0, 0.0, "tempo"),
+            (-1.0, 0.0, "tempo"),
+            (f64::NAN, 0.0, "tempo"),
+            (f64::INFINITY, 0.0, "tempo"),
+            (1.0, -1.0, "maximum length"),
+            (1.0, f64::NAN, "maximum length"),
+        ] {
+            assert!(render(tempo, max_seconds).is_null());
+            assert_eq!(render_midi_frames(), 0);
+            assert_eq!(render_midi_len(), 0);
+            assert!(
+                last_error().contains(message),
+                "tempo {tempo}, max_seconds {max_seconds}: {}",
+                last_error()
+            );
+        }
+
+        // A good tempo gets as far as the soundfont, which one byte isn't
+        assert!(render(1.0, 0.0).is_null());
+        assert_eq!(render_midi_frames(), 0);
+        assert_eq!(last_error(), "Cannae read the soondfont");
+    }
+}
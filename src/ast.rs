@@ -1,7 +1,9 @@
 use std::fmt;
 
+use serde::Serialize;
+
 /// Log levels for the logging system
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize)]
 pub enum LogLevel {
     /// Silent - no output
     Wheesht = 0,
@@ -31,15 +33,16 @@ impl LogLevel {
         }
     }
 
-    /// Parse a log level from a string (case-insensitive)
+    /// Parse a log level from a string (case-insensitive). The usual
+    /// English names work too: trace < debug < info < warn < error.
     pub fn parse_level(s: &str) -> Option<LogLevel> {
         match s.to_lowercase().as_str() {
-            "wheesht" => Some(LogLevel::Wheesht),
-            "roar" => Some(LogLevel::Roar),
-            "holler" => Some(LogLevel::Holler),
-            "blether" => Some(LogLevel::Blether),
-            "mutter" => Some(LogLevel::Mutter),
-            "whisper" => Some(LogLevel::Whisper),
+            "wheesht" | "off" => Some(LogLevel::Wheesht),
+            "roar" | "error" => Some(LogLevel::Roar),
+            "holler" | "warn" | "warning" => Some(LogLevel::Holler),
+            "blether" | "info" => Some(LogLevel::Blether),
+            "mutter" | "debug" => Some(LogLevel::Mutter),
+            "whisper" | "trace" => Some(LogLevel::Whisper),
             _ => None,
         }
     }
@@ -52,7 +55,7 @@ impl fmt::Display for LogLevel {
 }
 
 /// Span information for error reporting
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
 pub struct Span {
     pub line: usize,
     pub column: usize,
@@ -65,7 +68,7 @@ impl Span {
 }
 
 /// A program is a list of statements
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Program {
     pub statements: Vec<Stmt>,
 }
@@ -77,7 +80,7 @@ impl Program {
 }
 
 /// Statements in mdhavers
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub enum Stmt {
     /// Variable declaration: ken x = 5
     VarDecl {
@@ -100,6 +103,17 @@ pub enum Stmt {
         span: Span,
     },
 
+    /// Binding conditional: gin ken x = lookup() { ... } ither { ... }
+    /// Takes the then branch when the value is not nil, with `x` bound
+    /// only inside it
+    IfLet {
+        name: String,
+        value: Expr,
+        then_branch: Box<Stmt>,
+        else_branch: Option<Box<Stmt>>,
+        span: Span,
+    },
+
     /// While loop: whiles x < 10 { ... }
     While {
         condition: Expr,
@@ -110,6 +124,9 @@ pub enum Stmt {
     /// For loop: fer i in 1..10 { ... }
     For {
         variable: String,
+        /// The second name in `fer k, v in d { ... }`. Each item is unpacked
+        /// as a pair, and a dict yields its keys and values
+        value_variable: Option<String>,
         iterable: Expr,
         body: Box<Stmt>,
         span: Span,
@@ -121,6 +138,7 @@ pub enum Stmt {
         name: String,
         params: Vec<Param>,
         body: Vec<Stmt>,
+        attributes: Vec<Attribute>,
         span: Span,
     },
 
@@ -141,6 +159,7 @@ pub enum Stmt {
         name: String,
         superclass: Option<String>,
         methods: Vec<Stmt>,
+        attributes: Vec<Attribute>,
         span: Span,
     },
 
@@ -151,10 +170,17 @@ pub enum Stmt {
         span: Span,
     },
 
-    /// Import statement: fetch "module"
+    /// Import statement: fetch "module", bring "module" as m, or
+    /// bring add, sub frae "module"
     Import {
         path: String,
+        /// The name the module's dict is bound to. `bring "module"` gets
+        /// the module's own name here.
         alias: Option<String>,
+        /// The names picked out by `bring ... frae`
+        only: Option<Vec<String>>,
+        /// Written with `bring` rather than `fetch`
+        bring: bool,
         span: Span,
     },
 
@@ -180,10 +206,14 @@ pub enum Stmt {
         span: Span,
     },
 
-    /// Destructuring assignment: ken [a, b, ...rest] = list
+    /// Destructuring assignment: ken [a, b, ...rest] = list, or ken a, b = f()
     Destructure {
         patterns: Vec<DestructPattern>,
         value: Expr,
+        /// Written without brackets (`ken a, b = ...`): the value must have
+        /// exactly as many items as there are names, or at least that many
+        /// less the rest pattern if there is one
+        exact: bool,
         span: Span,
     },
 
@@ -197,10 +227,13 @@ pub enum Stmt {
 
     /// Hurl statement: throw/raise an exception with a message
     Hurl { message: Expr, span: Span },
+
+    /// Yield from a generator: gie_back value
+    Yield { value: Expr, span: Span },
 }
 
 /// A match arm: whan pattern -> body
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 #[allow(dead_code)]
 pub struct MatchArm {
     pub pattern: Pattern,
@@ -209,7 +242,7 @@ pub struct MatchArm {
 }
 
 /// Patterns for matching
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub enum Pattern {
     /// Literal value
     Literal(Literal),
@@ -221,15 +254,43 @@ pub enum Pattern {
     Range { start: Box<Expr>, end: Box<Expr> },
 }
 
+/// A clause of a list comprehension
+#[derive(Debug, Clone, Serialize)]
+pub enum CompClause {
+    /// fer x in iterable
+    For { variable: String, iterable: Expr },
+    /// gin condition
+    If(Expr),
+}
+
+/// An attribute written before a `dae` or `kin`: @deprecated("use new_fn instead")
+#[derive(Debug, Clone, Serialize)]
+pub struct Attribute {
+    pub name: String,
+    pub args: Vec<String>,
+    pub span: Span,
+}
+
+impl Attribute {
+    /// The message from a `@deprecated` attribute, if there is one
+    /// (empty when it was written without a reason)
+    pub fn deprecation(attributes: &[Attribute]) -> Option<String> {
+        attributes
+            .iter()
+            .find(|attr| attr.name == "deprecated")
+            .map(|attr| attr.args.first().cloned().unwrap_or_default())
+    }
+}
+
 /// A function parameter with optional default value
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Param {
     pub name: String,
     pub default: Option<Expr>,
 }
 
 /// Destructuring pattern fer unpacking lists
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub enum DestructPattern {
     /// Single variable: x
     Variable(String),
@@ -240,7 +301,7 @@ pub enum DestructPattern {
 }
 
 /// Expressions in mdhavers
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 #[allow(clippy::enum_variant_names)]
 pub enum Expr {
     /// Literal values
@@ -328,17 +389,26 @@ pub enum Expr {
     /// List literal: [1, 2, 3]
     List { elements: Vec<Expr>, span: Span },
 
+    /// List comprehension: [x * 2 fer x in xs gin x > 0]
+    /// The clauses run left to right, like nested loops and ifs
+    ListComp {
+        element: Box<Expr>,
+        clauses: Vec<CompClause>,
+        span: Span,
+    },
+
     /// Dictionary literal: {key: value}
     Dict {
         pairs: Vec<(Expr, Expr)>,
         span: Span,
     },
 
-    /// Range: 1..10
+    /// Range: 1..10, 10..1, or 0..10 by 2
     Range {
         start: Box<Expr>,
         end: Box<Expr>,
         inclusive: bool,
+        step: Option<Box<Expr>>,
         span: Span,
     },
 
@@ -385,7 +455,7 @@ pub enum Expr {
 }
 
 /// Parts of an f-string
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub enum FStringPart {
     /// Literal text
     Text(String),
@@ -394,7 +464,7 @@ pub enum FStringPart {
 }
 
 /// Literal values
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum Literal {
     Integer(i64),
     Float(f64),
@@ -417,19 +487,29 @@ impl fmt::Display for Literal {
 }
 
 /// Binary operators
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
 pub enum BinaryOp {
     Add,
     Subtract,
     Multiply,
     Divide,
     Modulo,
+    Power,
     Equal,
     NotEqual,
     Less,
     LessEqual,
     Greater,
     GreaterEqual,
+    BitAnd,
+    BitOr,
+    BitXor,
+    ShiftLeft,
+    ShiftRight,
+    /// `x in xs`: list item, dict key, creel member or substring
+    In,
+    /// `x nae in xs` (or `x !in xs`)
+    NotIn,
 }
 
 impl fmt::Display for BinaryOp {
@@ -440,18 +520,26 @@ impl fmt::Display for BinaryOp {
             BinaryOp::Multiply => write!(f, "*"),
             BinaryOp::Divide => write!(f, "/"),
             BinaryOp::Modulo => write!(f, "%"),
+            BinaryOp::Power => write!(f, "**"),
             BinaryOp::Equal => write!(f, "=="),
             BinaryOp::NotEqual => write!(f, "!="),
             BinaryOp::Less => write!(f, "<"),
             BinaryOp::LessEqual => write!(f, "<="),
             BinaryOp::Greater => write!(f, ">"),
             BinaryOp::GreaterEqual => write!(f, ">="),
+            BinaryOp::BitAnd => write!(f, "&"),
+            BinaryOp::BitOr => write!(f, "|"),
+            BinaryOp::BitXor => write!(f, "^"),
+            BinaryOp::ShiftLeft => write!(f, "<<"),
+            BinaryOp::ShiftRight => write!(f, ">>"),
+            BinaryOp::In => write!(f, "in"),
+            BinaryOp::NotIn => write!(f, "nae in"),
         }
     }
 }
 
 /// Unary operators
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
 pub enum UnaryOp {
     Negate,
     Not,
@@ -467,7 +555,7 @@ impl fmt::Display for UnaryOp {
 }
 
 /// Logical operators
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
 pub enum LogicalOp {
     And,
     Or,
@@ -498,6 +586,7 @@ impl Expr {
             Expr::IndexSet { span, .. } => *span,
             Expr::Slice { span, .. } => *span,
             Expr::List { span, .. } => *span,
+            Expr::ListComp { span, .. } => *span,
             Expr::Dict { span, .. } => *span,
             Expr::Range { span, .. } => *span,
             Expr::Grouping { span, .. } => *span,
@@ -520,6 +609,7 @@ impl Stmt {
             Stmt::Expression { span, .. } => *span,
             Stmt::Block { span, .. } => *span,
             Stmt::If { span, .. } => *span,
+            Stmt::IfLet { span, .. } => *span,
             Stmt::While { span, .. } => *span,
             Stmt::For { span, .. } => *span,
             Stmt::Function { span, .. } => *span,
@@ -536,6 +626,94 @@ impl Stmt {
             Stmt::Destructure { span, .. } => *span,
             Stmt::Log { span, .. } => *span,
             Stmt::Hurl { span, .. } => *span,
+            Stmt::Yield { span, .. } => *span,
+        }
+    }
+
+    /// Spell an `IfLet` out in plain statements, for backends that do not
+    /// handle it themselves. The value lands in a hidden variable first, so
+    /// `name` is only declared inside the then branch:
+    ///
+    /// ```text
+    /// { ken <hidden> = value
+    ///   gin <hidden> != naething { ken name = <hidden>; ... } ither { ... } }
+    /// ```
+    pub fn lower_if_let(
+        name: &str,
+        value: &Expr,
+        then_branch: &Stmt,
+        else_branch: Option<&Stmt>,
+        span: Span,
+    ) -> Stmt {
+        let hidden = format!("__gin_ken_{}_{}", span.line, span.column);
+        let hidden_var = || Expr::Variable {
+            name: hidden.clone(),
+            span,
+        };
+        let then_branch = Stmt::Block {
+            statements: vec![
+                Stmt::VarDecl {
+                    name: name.to_string(),
+                    initializer: Some(hidden_var()),
+                    span,
+                },
+                then_branch.clone(),
+            ],
+            span,
+        };
+        Stmt::Block {
+            statements: vec![
+                Stmt::VarDecl {
+                    name: hidden.clone(),
+                    initializer: Some(value.clone()),
+                    span,
+                },
+                Stmt::If {
+                    condition: Expr::Binary {
+                        left: Box::new(hidden_var()),
+                        operator: BinaryOp::NotEqual,
+                        right: Box::new(Expr::Literal {
+                            value: Literal::Nil,
+                            span,
+                        }),
+                        span,
+                    },
+                    then_branch: Box::new(then_branch),
+                    else_branch: else_branch.map(|e| Box::new(e.clone())),
+                    span,
+                },
+            ],
+            span,
+        }
+    }
+
+    /// Whether this statement can `gie_back` from the function it's in.
+    /// Nested functions and lambdas are their own generators, so they don't count.
+    pub fn contains_yield(&self) -> bool {
+        match self {
+            Stmt::Yield { .. } => true,
+            Stmt::Block { statements, .. } => statements.iter().any(Stmt::contains_yield),
+            Stmt::If {
+                then_branch,
+                else_branch,
+                ..
+            }
+            | Stmt::IfLet {
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                then_branch.contains_yield()
+                    || else_branch.as_ref().is_some_and(|e| e.contains_yield())
+            }
+            Stmt::While { body, .. } | Stmt::For { body, .. } => body.contains_yield(),
+            Stmt::TryCatch {
+                try_block,
+                catch_block,
+                ..
+            } => try_block.contains_yield() || catch_block.contains_yield(),
+            Stmt::Match { arms, .. } => arms.iter().any(|arm| arm.body.contains_yield()),
+            _ => false,
         }
     }
 }
@@ -567,6 +745,8 @@ mod tests {
             assert_eq!(level.to_string(), name);
             assert_eq!(LogLevel::parse_level(&name.to_lowercase()), Some(level));
         }
+        assert_eq!(LogLevel::parse_level("Warn"), Some(LogLevel::Holler));
+        assert_eq!(LogLevel::parse_level("debug"), Some(LogLevel::Mutter));
         assert_eq!(LogLevel::parse_level("nope"), None);
     }
 
@@ -619,12 +799,18 @@ mod tests {
         assert_eq!(format!("{}", BinaryOp::Multiply), "*");
         assert_eq!(format!("{}", BinaryOp::Divide), "/");
         assert_eq!(format!("{}", BinaryOp::Modulo), "%");
+        assert_eq!(format!("{}", BinaryOp::Power), "**");
         assert_eq!(format!("{}", BinaryOp::Equal), "==");
         assert_eq!(format!("{}", BinaryOp::NotEqual), "!=");
         assert_eq!(format!("{}", BinaryOp::Less), "<");
         assert_eq!(format!("{}", BinaryOp::LessEqual), "<=");
         assert_eq!(format!("{}", BinaryOp::Greater), ">");
         assert_eq!(format!("{}", BinaryOp::GreaterEqual), ">=");
+        assert_eq!(format!("{}", BinaryOp::BitAnd), "&");
+        assert_eq!(format!("{}", BinaryOp::BitOr), "|");
+        assert_eq!(format!("{}", BinaryOp::BitXor), "^");
+        assert_eq!(format!("{}", BinaryOp::ShiftLeft), "<<");
+        assert_eq!(format!("{}", BinaryOp::ShiftRight), ">>");
     }
 
     #[test]
@@ -821,6 +1007,7 @@ mod tests {
                 span,
             }),
             inclusive: false,
+            step: None,
             span,
         };
         assert_eq!(range.span(), span);
@@ -957,6 +1144,7 @@ mod tests {
 
         let for_stmt = Stmt::For {
             variable: "i".to_string(),
+            value_variable: None,
             iterable: Expr::Literal {
                 value: Literal::Integer(0),
                 span,
@@ -973,6 +1161,7 @@ mod tests {
             name: "foo".to_string(),
             params: vec![],
             body: vec![],
+            attributes: vec![],
             span,
         };
         assert_eq!(func.span(), span);
@@ -999,6 +1188,7 @@ mod tests {
             name: "Foo".to_string(),
             superclass: None,
             methods: vec![],
+            attributes: vec![],
             span,
         };
         assert_eq!(class.span(), span);
@@ -1013,6 +1203,8 @@ mod tests {
         let import = Stmt::Import {
             path: "lib".to_string(),
             alias: None,
+            only: None,
+            bring: false,
             span,
         };
         assert_eq!(import.span(), span);
@@ -1057,6 +1249,7 @@ mod tests {
                 elements: vec![],
                 span,
             },
+            exact: false,
             span,
         };
         assert_eq!(destruct.span(), span);
@@ -1156,4 +1349,17 @@ mod tests {
         };
         assert_eq!(arm.span, span);
     }
+
+    #[test]
+    fn test_program_serialises_to_json() {
+        let program =
+            crate::parser::parse("ken n = 1\ndae greet(name) {\n    blether name\n}\n").unwrap();
+        let json = serde_json::to_value(&program).unwrap();
+        let function = &json["statements"][1]["Function"];
+        assert_eq!(function["name"], "greet");
+        assert_eq!(function["params"][0]["name"], "name");
+        assert_eq!(function["span"]["line"], 2);
+        assert!(function["body"][0]["Print"].is_object());
+        assert_eq!(json["statements"][0]["VarDecl"]["name"], "n");
+    }
 }
@@ -860,6 +860,160 @@ fn seek_midi(entry: &mut MidiEntry, seconds: f64) -> Result<(), String> {
     Ok(())
 }
 
+/// Render a whole MIDI file to interleaved stereo f32 samples, chunk by
+/// chunk the same way the rustysynth WASM helper does
+#[cfg(any(feature = "audio", test))]
+fn render_midi_samples(
+    sf: &Arc<SoundFont>,
+    midi: &Arc<MidiFile>,
+    sample_rate: u32,
+) -> Result<Vec<f32>, String> {
+    let settings = SynthesizerSettings::new(sample_rate as i32);
+    let synth =
+        Synthesizer::new(sf, &settings).map_err(|_| "Cannae set up the synth".to_string())?;
+    let mut sequencer = MidiFileSequencer::new(synth);
+    sequencer.play(midi, false);
+
+    let length = midi.get_length();
+    let total_frames = if length <= 0.0 {
+        0
+    } else {
+        (length * sample_rate as f64).ceil() as usize
+    };
+
+    let mut output = Vec::with_capacity(total_frames * 2);
+    let mut left = vec![0.0_f32; DECODE_CHUNK_FRAMES];
+    let mut right = vec![0.0_f32; DECODE_CHUNK_FRAMES];
+    let mut remaining = total_frames;
+    while remaining > 0 {
+        let chunk = remaining.min(DECODE_CHUNK_FRAMES);
+        sequencer.render(&mut left[..chunk], &mut right[..chunk]);
+        for i in 0..chunk {
+            output.push(left[i]);
+            output.push(right[i]);
+        }
+        remaining -= chunk;
+    }
+    Ok(output)
+}
+
+/// Pull interleaved samples and a channel count out of a save_wav argument:
+/// bytes from render_midi (stereo f32), a list of numbers (mono), or a list
+/// of [left, right] pairs (stereo)
+#[cfg(any(feature = "audio", test))]
+fn wav_samples(value: &Value) -> Result<(Vec<f32>, u16), String> {
+    match value {
+        Value::Bytes(bytes) => {
+            let bytes = bytes.borrow();
+            if bytes.len() % 8 != 0 {
+                return Err("save_wav needs whole stereo frames o' f32 samples".to_string());
+            }
+            let samples = bytes
+                .chunks_exact(4)
+                .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .collect();
+            Ok((samples, 2))
+        }
+        Value::List(list) => {
+            let list = list.borrow();
+            if let Some(Value::List(_)) = list.first() {
+                let mut samples = Vec::with_capacity(list.len() * 2);
+                for frame in list.iter() {
+                    let Value::List(pair) = frame else {
+                        return Err(
+                            "save_wav needs every frame tae be a [left, right] pair".to_string()
+                        );
+                    };
+                    let pair = pair.borrow();
+                    if pair.len() != 2 {
+                        return Err(
+                            "save_wav needs every frame tae be a [left, right] pair".to_string()
+                        );
+                    }
+                    samples.push(as_number(&pair[0], "save_wav")? as f32);
+                    samples.push(as_number(&pair[1], "save_wav")? as f32);
+                }
+                Ok((samples, 2))
+            } else {
+                let samples = list
+                    .iter()
+                    .map(|v| as_number(v, "save_wav").map(|n| n as f32))
+                    .collect::<Result<_, _>>()?;
+                Ok((samples, 1))
+            }
+        }
+        _ => Err("save_wav needs bytes fae render_midi or a list o' samples".to_string()),
+    }
+}
+
+/// Samples for `save_wav(path, rate, channels, samples)`: a flat list of
+/// numbers, interleaved across however many channels are asked for
+#[cfg(any(feature = "audio", test))]
+fn interleaved_wav_samples(channels: &Value, samples: &Value) -> Result<(Vec<f32>, u16), String> {
+    let channels = match channels {
+        Value::Integer(n) if (1..=u16::MAX as i64).contains(n) => *n as u16,
+        _ => {
+            return Err(format!(
+                "save_wav needs a channel count atween 1 an' {}",
+                u16::MAX
+            ))
+        }
+    };
+    let Value::List(list) = samples else {
+        return Err("save_wav needs a flat list o' samples".to_string());
+    };
+    let samples: Vec<f32> = list
+        .borrow()
+        .iter()
+        .map(|v| as_number(v, "save_wav").map(|n| n as f32))
+        .collect::<Result<_, _>>()?;
+    if !samples.len().is_multiple_of(channels as usize) {
+        return Err(format!(
+            "save_wav got {} samples, which isnae whole frames o' {} channels",
+            samples.len(),
+            channels
+        ));
+    }
+    Ok((samples, channels))
+}
+
+/// A 16-bit PCM WAV file: RIFF header, fmt chunk, then the data
+#[cfg(any(feature = "audio", test))]
+fn encode_wav(samples: &[f32], channels: u16, sample_rate: u32) -> Vec<u8> {
+    let block_align = channels as u32 * 2;
+    let data_len = samples.len() as u32 * 2;
+    let mut out = Vec::with_capacity(44 + data_len as usize);
+    out.extend_from_slice(b"RIFF");
+    out.extend_from_slice(&(36 + data_len).to_le_bytes());
+    out.extend_from_slice(b"WAVE");
+    out.extend_from_slice(b"fmt ");
+    out.extend_from_slice(&16u32.to_le_bytes());
+    out.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    out.extend_from_slice(&channels.to_le_bytes());
+    out.extend_from_slice(&sample_rate.to_le_bytes());
+    out.extend_from_slice(&(sample_rate * block_align).to_le_bytes());
+    out.extend_from_slice(&(block_align as u16).to_le_bytes());
+    out.extend_from_slice(&16u16.to_le_bytes());
+    out.extend_from_slice(b"data");
+    out.extend_from_slice(&data_len.to_le_bytes());
+    for sample in samples {
+        let pcm = (sample.clamp(-1.0, 1.0) * i16::MAX as f32).round() as i16;
+        out.extend_from_slice(&pcm.to_le_bytes());
+    }
+    out
+}
+
+#[cfg(any(feature = "audio", test))]
+fn as_sample_rate(value: &Value, name: &str) -> Result<u32, String> {
+    match value {
+        Value::Integer(rate) if (8_000..=192_000).contains(rate) => Ok(*rate as u32),
+        _ => Err(format!(
+            "{} needs a sample rate atween 8000 an' 192000",
+            name
+        )),
+    }
+}
+
 #[cfg(any(feature = "audio", test))]
 fn with_state<F>(func: F) -> Result<Value, String>
 where
@@ -1597,6 +1751,57 @@ pub fn register_audio_functions(globals: &Rc<RefCell<crate::value::Environment>>
             Ok(Value::Nil)
         })
     });
+
+    // render_midi - synthesise a MIDI file to interleaved stereo f32 bytes
+    define_native(globals, "render_midi", 3, |args| {
+        let sf = match &args[0] {
+            Value::Nil => load_soundfont(resolve_default_soundfont()?.as_path())?,
+            Value::String(path) => load_soundfont(Path::new(path))?,
+            _ => return Err("render_midi needs a soondfont path or naething".to_string()),
+        };
+        let midi_path = match &args[1] {
+            Value::String(s) => s.clone(),
+            _ => return Err("render_midi needs a midi filepath".to_string()),
+        };
+        let sample_rate = as_sample_rate(&args[2], "render_midi")?;
+
+        let mut midi_file =
+            File::open(&midi_path).map_err(|_| "Cannae open the midi file".to_string())?;
+        let midi = MidiFile::new(&mut midi_file).map_err(|_| "Cannae read the midi".to_string())?;
+        let samples = render_midi_samples(&sf, &Arc::new(midi), sample_rate)?;
+
+        let bytes = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+        Ok(Value::Bytes(Rc::new(RefCell::new(bytes))))
+    });
+
+    // save_wav - write samples out as a 16-bit PCM WAV file, either
+    // save_wav(samples, sample_rate, path) or save_wav(path, sample_rate, channels, samples)
+    define_native(globals, "save_wav", usize::MAX, |args| {
+        let (samples, channels, path) = match args.len() {
+            3 => {
+                let (samples, channels) = wav_samples(&args[0])?;
+                (samples, channels, &args[2])
+            }
+            4 => {
+                let (samples, channels) = interleaved_wav_samples(&args[2], &args[3])?;
+                (samples, channels, &args[0])
+            }
+            n => {
+                return Err(format!(
+                    "save_wav takes (samples, sample_rate, path) or (path, sample_rate, channels, samples), no' {} arguments",
+                    n
+                ))
+            }
+        };
+        let sample_rate = as_sample_rate(&args[1], "save_wav")?;
+        let path = match path {
+            Value::String(s) => s.clone(),
+            _ => return Err("save_wav needs a filepath".to_string()),
+        };
+        std::fs::write(&path, encode_wav(&samples, channels, sample_rate))
+            .map_err(|e| format!("Cannae write the wav file '{}': {}", path, e))?;
+        Ok(Value::Nil)
+    });
 }
 
 #[cfg(not(any(feature = "audio", test)))]
@@ -1658,6 +1863,8 @@ pub fn register_audio_functions(globals: &Rc<RefCell<crate::value::Environment>>
         ("midi_pit_luid", 2),
         ("midi_pit_pan", 2),
         ("midi_pit_rin_roond", 2),
+        ("render_midi", 3),
+        ("save_wav", usize::MAX),
     ];
 
     for (name, arity) in stubs {
@@ -2329,6 +2536,136 @@ mod tests {
         assert_eq!(entry.state, PlayState::Stopped);
     }
 
+    /// (channels, sample rate, frames) read back from a WAV file's headers
+    fn parse_wav(bytes: &[u8]) -> (u16, u32, usize) {
+        assert_eq!(&bytes[0..4], b"RIFF");
+        assert_eq!(&bytes[8..16], b"WAVEfmt ");
+        assert_eq!(
+            u32::from_le_bytes(bytes[4..8].try_into().unwrap()) as usize,
+            bytes.len() - 8
+        );
+        assert_eq!(u16::from_le_bytes([bytes[20], bytes[21]]), 1);
+        let channels = u16::from_le_bytes([bytes[22], bytes[23]]);
+        let rate = u32::from_le_bytes(bytes[24..28].try_into().unwrap());
+        let block_align = u16::from_le_bytes([bytes[32], bytes[33]]) as usize;
+        assert_eq!(block_align, channels as usize * 2);
+        assert_eq!(&bytes[36..40], b"data");
+        let data_len = u32::from_le_bytes(bytes[40..44].try_into().unwrap()) as usize;
+        assert_eq!(data_len, bytes.len() - 44);
+        (channels, rate, data_len / block_align)
+    }
+
+    #[test]
+    fn test_render_midi_and_save_wav_round_trip() {
+        let dir = tempdir().unwrap();
+        let sf_path = dir.path().join("font.sf2");
+        fs::write(&sf_path, b"sf").unwrap();
+        let midi_path = dir.path().join("song.mid");
+        fs::write(&midi_path, b"midi").unwrap();
+        let wav_path = dir.path().join("song.wav");
+        let path_value = |p: &Path| Value::String(p.to_string_lossy().to_string());
+
+        let env = Rc::new(RefCell::new(Environment::new()));
+        register_audio_functions(&env);
+        let render_midi = get_native(&env, "render_midi");
+        let save_wav = get_native(&env, "save_wav");
+
+        // The test MIDI file lasts 0.1s, so 8000Hz gives 800 stereo frames
+        let samples = (render_midi.func)(vec![
+            path_value(&sf_path),
+            path_value(&midi_path),
+            Value::Integer(8_000),
+        ])
+        .unwrap();
+        let Value::Bytes(bytes) = &samples else {
+            panic!("render_midi should gie bytes");
+        };
+        assert_eq!(bytes.borrow().len(), 800 * 2 * 4);
+
+        (save_wav.func)(vec![
+            samples.clone(),
+            Value::Integer(8_000),
+            path_value(&wav_path),
+        ])
+        .unwrap();
+        assert_eq!(parse_wav(&fs::read(&wav_path).unwrap()), (2, 8_000, 800));
+
+        // A flat list is mono, a list of pairs is stereo
+        let list = |items: Vec<Value>| Value::List(Rc::new(RefCell::new(items)));
+        let mono = list(vec![
+            Value::Float(0.5),
+            Value::Integer(-1),
+            Value::Float(2.0),
+        ]);
+        (save_wav.func)(vec![mono, Value::Integer(22_050), path_value(&wav_path)]).unwrap();
+        let wav = fs::read(&wav_path).unwrap();
+        assert_eq!(parse_wav(&wav), (1, 22_050, 3));
+        let pcm: Vec<i16> = wav[44..]
+            .chunks_exact(2)
+            .map(|b| i16::from_le_bytes([b[0], b[1]]))
+            .collect();
+        assert_eq!(pcm, [16384, -32767, 32767]);
+
+        let pair = || list(vec![Value::Float(0.1), Value::Float(-0.1)]);
+        let stereo = list(vec![pair(), pair()]);
+        (save_wav.func)(vec![stereo, Value::Integer(44_100), path_value(&wav_path)]).unwrap();
+        assert_eq!(parse_wav(&fs::read(&wav_path).unwrap()), (2, 44_100, 2));
+
+        let err =
+            (save_wav.func)(vec![samples, Value::Integer(10), path_value(&wav_path)]).unwrap_err();
+        assert_eq!(err, "save_wav needs a sample rate atween 8000 an' 192000");
+        let err = (render_midi.func)(vec![
+            path_value(&sf_path),
+            path_value(&dir.path().join("nae.mid")),
+            Value::Integer(8_000),
+        ])
+        .unwrap_err();
+        assert_eq!(err, "Cannae open the midi file");
+    }
+
+    #[test]
+    fn test_save_wav_wi_a_channel_count_writes_the_headers() {
+        let dir = tempdir().unwrap();
+        let wav_path = dir.path().join("sine.wav");
+        let path = Value::String(wav_path.to_string_lossy().to_string());
+        let env = Rc::new(RefCell::new(Environment::new()));
+        register_audio_functions(&env);
+        let save_wav = get_native(&env, "save_wav");
+
+        // 100 frames of a 440Hz sine, the same in both ears, with the first sample too loud
+        let mut samples = Vec::new();
+        for i in 0..100 {
+            let s = (2.0 * std::f64::consts::PI * 440.0 * i as f64 / 8_000.0).sin();
+            samples.push(Value::Float(s));
+            samples.push(Value::Float(s));
+        }
+        samples[0] = Value::Float(1.5);
+        let samples = Value::List(Rc::new(RefCell::new(samples)));
+        (save_wav.func)(vec![
+            path.clone(),
+            Value::Integer(8_000),
+            Value::Integer(2),
+            samples.clone(),
+        ])
+        .unwrap();
+        let wav = fs::read(&wav_path).unwrap();
+        assert_eq!(parse_wav(&wav), (2, 8_000, 100));
+        assert_eq!(u32::from_le_bytes(wav[40..44].try_into().unwrap()), 400);
+        assert_eq!(i16::from_le_bytes([wav[44], wav[45]]), i16::MAX);
+
+        let err = (save_wav.func)(vec![
+            path.clone(),
+            Value::Integer(8_000),
+            Value::Integer(0),
+            samples.clone(),
+        ])
+        .unwrap_err();
+        assert!(err.contains("channel count"), "{err}");
+        let err = (save_wav.func)(vec![path, Value::Integer(8_000), Value::Integer(3), samples])
+            .unwrap_err();
+        assert!(err.contains("isnae whole frames"), "{err}");
+    }
+
     #[test]
     fn test_music_builtins_roundtrip() {
         let dir = tempdir().unwrap();
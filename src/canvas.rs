@@ -0,0 +1,581 @@
+//! Canvas module for mdhavers - drawing into an RGBA buffer
+//!
+//! A canvas is a native object holding width × height pixels. You can set
+//! pixels, draw lines, rectangles and circles, then save it as a PNG. There's
+//! no window involved, so it works headless (unlike the raylib `screen_*`
+//! functions in `graphics`). PNG encoding and decoding go through the `png`
+//! crate, which the `canvas` feature pulls in.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::value::{NativeFunction, Value};
+
+#[cfg(any(feature = "canvas", test))]
+pub use drawing::{register_canvas_functions, Canvas};
+
+fn define_native<F>(
+    globals: &Rc<RefCell<crate::value::Environment>>,
+    name: &str,
+    arity: usize,
+    func: F,
+) where
+    F: Fn(Vec<Value>) -> Result<Value, String> + 'static,
+{
+    globals.borrow_mut().define(
+        name.to_string(),
+        Value::NativeFunction(Rc::new(NativeFunction::new(name, arity, func))),
+    );
+}
+
+#[cfg(any(feature = "canvas", test))]
+mod drawing {
+    use std::any::Any;
+
+    use super::*;
+    use crate::error::{HaversError, HaversResult};
+    use crate::value::NativeObject;
+
+    /// Biggest width or height a canvas can have
+    const MAX_SIDE: i64 = 16384;
+
+    /// An RGBA pixel buffer, four bytes per pixel, row by row
+    #[derive(Debug)]
+    pub struct Canvas {
+        width: usize,
+        height: usize,
+        pixels: RefCell<Vec<u8>>,
+    }
+
+    impl Canvas {
+        pub fn new(width: usize, height: usize) -> Self {
+            Canvas {
+                width,
+                height,
+                pixels: RefCell::new(vec![0; width * height * 4]),
+            }
+        }
+
+        pub fn width(&self) -> usize {
+            self.width
+        }
+
+        pub fn height(&self) -> usize {
+            self.height
+        }
+
+        /// The colour at (x, y), or None if it's off the edge
+        pub fn pixel(&self, x: i64, y: i64) -> Option<[u8; 4]> {
+            let i = self.offset(x, y)?;
+            let pixels = self.pixels.borrow();
+            Some([pixels[i], pixels[i + 1], pixels[i + 2], pixels[i + 3]])
+        }
+
+        /// Paint one pixel - anything off the edge is quietly clipped
+        pub fn put(&self, x: i64, y: i64, colour: [u8; 4]) {
+            if let Some(i) = self.offset(x, y) {
+                self.pixels.borrow_mut()[i..i + 4].copy_from_slice(&colour);
+            }
+        }
+
+        pub fn fill(&self, colour: [u8; 4]) {
+            for pixel in self.pixels.borrow_mut().chunks_exact_mut(4) {
+                pixel.copy_from_slice(&colour);
+            }
+        }
+
+        /// A filled rectangle with its top-left corner at (x, y)
+        pub fn rect(&self, x: i64, y: i64, w: i64, h: i64, colour: [u8; 4]) {
+            let x0 = x.max(0);
+            let y0 = y.max(0);
+            let x1 = x.saturating_add(w).min(self.width as i64);
+            let y1 = y.saturating_add(h).min(self.height as i64);
+            for py in y0..y1 {
+                for px in x0..x1 {
+                    self.put(px, py, colour);
+                }
+            }
+        }
+
+        /// A straight line from (x0, y0) to (x1, y1), both ends included
+        pub fn line(&self, x0: i64, y0: i64, x1: i64, y1: i64, colour: [u8; 4]) {
+            // Bresenham, walking whichever axis is longer
+            let dx = (x1 - x0).abs();
+            let dy = -(y1 - y0).abs();
+            let sx = if x0 < x1 { 1 } else { -1 };
+            let sy = if y0 < y1 { 1 } else { -1 };
+            let (mut x, mut y, mut err) = (x0, y0, dx + dy);
+            loop {
+                self.put(x, y, colour);
+                if x == x1 && y == y1 {
+                    break;
+                }
+                let e2 = 2 * err;
+                if e2 >= dy {
+                    err += dy;
+                    x += sx;
+                }
+                if e2 <= dx {
+                    err += dx;
+                    y += sy;
+                }
+            }
+        }
+
+        /// A filled circle centred on (cx, cy)
+        pub fn circle(&self, cx: i64, cy: i64, radius: i64, colour: [u8; 4]) {
+            if radius < 0 {
+                return;
+            }
+            let r2 = radius * radius;
+            for dy in -radius..=radius {
+                let py = cy + dy;
+                if py < 0 || py >= self.height as i64 {
+                    continue;
+                }
+                for dx in -radius..=radius {
+                    if dx * dx + dy * dy <= r2 {
+                        self.put(cx + dx, py, colour);
+                    }
+                }
+            }
+        }
+
+        /// Encode as an 8-bit RGBA PNG
+        pub fn to_png(&self) -> Result<Vec<u8>, String> {
+            let mut png = Vec::new();
+            let mut encoder = png::Encoder::new(&mut png, self.width as u32, self.height as u32);
+            encoder.set_color(png::ColorType::Rgba);
+            encoder.set_depth(png::BitDepth::Eight);
+            encoder
+                .write_header()
+                .and_then(|mut writer| writer.write_image_data(&self.pixels.borrow()))
+                .map_err(|e| format!("Cannae encode PNG: {}", e))?;
+            Ok(png)
+        }
+
+        /// Decode a PNG of any colour type or bit depth; palettes and 16-bit
+        /// samples are brought down to 8-bit RGBA
+        pub fn from_png(data: &[u8]) -> Result<Canvas, String> {
+            let mut decoder = png::Decoder::new(data);
+            decoder
+                .set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+            let mut reader = decoder
+                .read_info()
+                .map_err(|e| format!("Cannae read PNG: {}", e))?;
+            let (width, height) = {
+                let info = reader.info();
+                (info.width as usize, info.height as usize)
+            };
+            if width as i64 > MAX_SIDE || height as i64 > MAX_SIDE {
+                return Err(format!("PNG size {}x{} is oot o' range", width, height));
+            }
+            let mut buf = vec![0; reader.output_buffer_size()];
+            let frame = reader
+                .next_frame(&mut buf)
+                .map_err(|e| format!("Cannae read PNG: {}", e))?;
+            let channels = frame.color_type.samples();
+
+            let canvas = Canvas::new(width, height);
+            for (dst, src) in canvas
+                .pixels
+                .borrow_mut()
+                .chunks_exact_mut(4)
+                .zip(buf[..frame.buffer_size()].chunks_exact(channels))
+            {
+                let rgba = match channels {
+                    1 => [src[0], src[0], src[0], 255],
+                    2 => [src[0], src[0], src[0], src[1]],
+                    3 => [src[0], src[1], src[2], 255],
+                    _ => [src[0], src[1], src[2], src[3]],
+                };
+                dst.copy_from_slice(&rgba);
+            }
+            Ok(canvas)
+        }
+
+        fn offset(&self, x: i64, y: i64) -> Option<usize> {
+            if x < 0 || y < 0 || x >= self.width as i64 || y >= self.height as i64 {
+                None
+            } else {
+                Some((y as usize * self.width + x as usize) * 4)
+            }
+        }
+    }
+
+    impl NativeObject for Canvas {
+        fn type_name(&self) -> &str {
+            "canvas"
+        }
+
+        fn get(&self, prop: &str) -> HaversResult<Value> {
+            match prop {
+                "width" => Ok(Value::Integer(self.width as i64)),
+                "height" => Ok(Value::Integer(self.height as i64)),
+                _ => Err(HaversError::UndefinedVariable {
+                    name: prop.to_string(),
+                    line: 0,
+                }),
+            }
+        }
+
+        fn set(&self, prop: &str, _value: Value) -> HaversResult<Value> {
+            Err(HaversError::TypeError {
+                message: format!("Cannae set '{}' on a canvas", prop),
+                line: 0,
+            })
+        }
+
+        fn call(&self, method: &str, _args: Vec<Value>) -> HaversResult<Value> {
+            Err(HaversError::UndefinedVariable {
+                name: method.to_string(),
+                line: 0,
+            })
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn to_string(&self) -> String {
+            format!("<canvas {}x{}>", self.width, self.height)
+        }
+    }
+
+    /// Turn a colour value into RGBA: a name like "reid", or an [r, g, b]
+    /// / [r, g, b, a] list (the same shapes `rgb` and `rgba` return)
+    fn value_to_rgba(value: &Value) -> Result<[u8; 4], String> {
+        match value {
+            Value::String(name) => match name.to_lowercase().as_str() {
+                "reid" | "red" => Ok([230, 41, 55, 255]),
+                "green" | "gress" => Ok([0, 228, 48, 255]),
+                "blue" | "blae" => Ok([0, 121, 241, 255]),
+                "white" | "whit" => Ok([255, 255, 255, 255]),
+                "black" | "bleck" => Ok([0, 0, 0, 255]),
+                "yellow" | "yella" => Ok([253, 249, 0, 255]),
+                "orange" => Ok([255, 161, 0, 255]),
+                "pink" => Ok([255, 109, 194, 255]),
+                "purple" | "purpie" => Ok([200, 122, 255, 255]),
+                "gray" | "grey" => Ok([130, 130, 130, 255]),
+                "darkgray" | "derkgrey" => Ok([80, 80, 80, 255]),
+                "lightgray" | "lichtgrey" => Ok([200, 200, 200, 255]),
+                "brown" | "broon" => Ok([127, 106, 79, 255]),
+                "gold" | "gowd" => Ok([255, 203, 0, 255]),
+                "skyblue" | "skyblae" => Ok([102, 191, 255, 255]),
+                "clear" | "toom" => Ok([0, 0, 0, 0]),
+                _ => Err(format!("Unknown color: {}", name)),
+            },
+            Value::List(list) => {
+                let list = list.borrow();
+                if list.len() != 3 && list.len() != 4 {
+                    return Err("Color list must be [r, g, b] or [r, g, b, a]".to_string());
+                }
+                let mut rgba = [255u8; 4];
+                for (slot, channel) in rgba.iter_mut().zip(list.iter()) {
+                    *slot = channel
+                        .as_integer()
+                        .ok_or("Color channels must be integers")?
+                        .clamp(0, 255) as u8;
+                }
+                Ok(rgba)
+            }
+            _ => Err("Color must be a string name or [r, g, b] list".to_string()),
+        }
+    }
+
+    fn canvas_arg<'a>(value: &'a Value, func: &str) -> Result<&'a Canvas, String> {
+        match value {
+            Value::NativeObject(obj) => obj
+                .as_any()
+                .downcast_ref::<Canvas>()
+                .ok_or_else(|| format!("{}() needs a canvas, no' a {}", func, obj.type_name())),
+            other => Err(format!(
+                "{}() needs a canvas, no' a {}",
+                func,
+                other.type_name()
+            )),
+        }
+    }
+
+    fn int_args<const N: usize>(args: &[Value], func: &str) -> Result<[i64; N], String> {
+        let mut out = [0; N];
+        for (slot, arg) in out.iter_mut().zip(args) {
+            *slot = arg
+                .as_integer()
+                .ok_or_else(|| format!("{}() needs integer coordinates", func))?;
+        }
+        Ok(out)
+    }
+
+    /// Build a canvas from `width, height, [r, g, b, a, ...]`
+    fn pixels_to_canvas(args: &[Value]) -> Result<Canvas, String> {
+        let [w, h] = int_args::<2>(&args[..2], "save_png")?;
+        if !(1..=MAX_SIDE).contains(&w) || !(1..=MAX_SIDE).contains(&h) {
+            return Err(format!(
+                "save_png() size must be atween 1 an' {} on each side, no' {}x{}",
+                MAX_SIDE, w, h
+            ));
+        }
+        let Value::List(list) = &args[2] else {
+            return Err(format!(
+                "save_png() needs a flat list o' RGBA integers, no' a {}",
+                args[2].type_name()
+            ));
+        };
+        let list = list.borrow();
+        let expected = (w * h * 4) as usize;
+        if list.len() != expected {
+            return Err(format!(
+                "save_png() needs {} values fer a {}x{} image (width * height * 4), but got {}",
+                expected,
+                w,
+                h,
+                list.len()
+            ));
+        }
+        let canvas = Canvas::new(w as usize, h as usize);
+        for (slot, value) in canvas.pixels.borrow_mut().iter_mut().zip(list.iter()) {
+            *slot = value
+                .as_integer()
+                .and_then(|c| u8::try_from(c).ok())
+                .ok_or_else(|| {
+                    format!(
+                        "save_png() pixel values must be integers 0-255, no' {}",
+                        value
+                    )
+                })?;
+        }
+        Ok(canvas)
+    }
+
+    /// Register the canvas functions in the interpreter globals
+    pub fn register_canvas_functions(globals: &Rc<RefCell<crate::value::Environment>>) {
+        // canvas(w, h) - a new, fully transparent canvas
+        define_native(globals, "canvas", 2, |args| {
+            let [w, h] = int_args::<2>(&args, "canvas")?;
+            if !(1..=MAX_SIDE).contains(&w) || !(1..=MAX_SIDE).contains(&h) {
+                return Err(format!(
+                    "canvas() size must be atween 1 an' {} on each side, no' {}x{}",
+                    MAX_SIDE, w, h
+                ));
+            }
+            Ok(Value::NativeObject(Rc::new(Canvas::new(
+                w as usize, h as usize,
+            ))))
+        });
+
+        // canvas_set(c, x, y, colour) - paint one pixel
+        define_native(globals, "canvas_set", 4, |args| {
+            let canvas = canvas_arg(&args[0], "canvas_set")?;
+            let [x, y] = int_args::<2>(&args[1..3], "canvas_set")?;
+            canvas.put(x, y, value_to_rgba(&args[3])?);
+            Ok(Value::Nil)
+        });
+
+        // canvas_get(c, x, y) - the [r, g, b, a] at a pixel
+        define_native(globals, "canvas_get", 3, |args| {
+            let canvas = canvas_arg(&args[0], "canvas_get")?;
+            let [x, y] = int_args::<2>(&args[1..3], "canvas_get")?;
+            let rgba = canvas.pixel(x, y).ok_or_else(|| {
+                format!(
+                    "Pixel ({}, {}) is aff the edge o' a {}x{} canvas",
+                    x,
+                    y,
+                    canvas.width(),
+                    canvas.height()
+                )
+            })?;
+            Ok(Value::List(Rc::new(RefCell::new(
+                rgba.iter().map(|&c| Value::Integer(c as i64)).collect(),
+            ))))
+        });
+
+        // canvas_fill(c, colour) - paint the whole canvas
+        define_native(globals, "canvas_fill", 2, |args| {
+            let canvas = canvas_arg(&args[0], "canvas_fill")?;
+            canvas.fill(value_to_rgba(&args[1])?);
+            Ok(Value::Nil)
+        });
+
+        // canvas_line(c, x0, y0, x1, y1, colour)
+        define_native(globals, "canvas_line", 6, |args| {
+            let canvas = canvas_arg(&args[0], "canvas_line")?;
+            let [x0, y0, x1, y1] = int_args::<4>(&args[1..5], "canvas_line")?;
+            canvas.line(x0, y0, x1, y1, value_to_rgba(&args[5])?);
+            Ok(Value::Nil)
+        });
+
+        // canvas_rect(c, x, y, w, h, colour) - a filled rectangle
+        define_native(globals, "canvas_rect", 6, |args| {
+            let canvas = canvas_arg(&args[0], "canvas_rect")?;
+            let [x, y, w, h] = int_args::<4>(&args[1..5], "canvas_rect")?;
+            canvas.rect(x, y, w, h, value_to_rgba(&args[5])?);
+            Ok(Value::Nil)
+        });
+
+        // canvas_circle(c, cx, cy, radius, colour) - a filled circle
+        define_native(globals, "canvas_circle", 5, |args| {
+            let canvas = canvas_arg(&args[0], "canvas_circle")?;
+            let [cx, cy, radius] = int_args::<3>(&args[1..4], "canvas_circle")?;
+            canvas.circle(cx, cy, radius, value_to_rgba(&args[4])?);
+            Ok(Value::Nil)
+        });
+
+        // save_png(c, path) - write the canvas out as a PNG file
+        // save_png(path, w, h, pixels) - or a flat [r, g, b, a, ...] list
+        define_native(globals, "save_png", usize::MAX, |args| {
+            let (path, png) = match args.len() {
+                2 => {
+                    let canvas = canvas_arg(&args[0], "save_png")?;
+                    (&args[1], canvas.to_png()?)
+                }
+                4 => (&args[0], pixels_to_canvas(&args[1..])?.to_png()?),
+                n => {
+                    return Err(format!(
+                        "save_png() takes (canvas, path) or (path, width, height, pixels), no' {} arguments",
+                        n
+                    ))
+                }
+            };
+            let path = path.as_string().ok_or("save_png() needs a file path")?;
+            std::fs::write(path, png).map_err(|e| format!("Cannae write PNG '{}': {}", path, e))?;
+            Ok(Value::Nil)
+        });
+
+        // load_png(path) - read a PNG file into a new canvas
+        define_native(globals, "load_png", 1, |args| {
+            let path = args[0].as_string().ok_or("load_png() needs a file path")?;
+            let data =
+                std::fs::read(path).map_err(|e| format!("Cannae read PNG '{}': {}", path, e))?;
+            let canvas = Canvas::from_png(&data).map_err(|e| format!("{} ('{}')", e, path))?;
+            Ok(Value::NativeObject(Rc::new(canvas)))
+        });
+    }
+}
+
+/// Stubs for when the canvas feature is not built in
+#[cfg(not(any(feature = "canvas", test)))]
+pub fn register_canvas_functions(globals: &Rc<RefCell<crate::value::Environment>>) {
+    const ERR_NO_CANVAS: &str = "Canvas drawin' isnae available - build wi' --features canvas";
+    let stubs = [
+        ("canvas", 2),
+        ("canvas_set", 4),
+        ("canvas_get", 3),
+        ("canvas_fill", 2),
+        ("canvas_line", 6),
+        ("canvas_rect", 6),
+        ("canvas_circle", 5),
+        ("save_png", usize::MAX),
+        ("load_png", 1),
+    ];
+    for (name, arity) in stubs {
+        define_native(globals, name, arity, |_args| Err(ERR_NO_CANVAS.to_string()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::HaversError;
+    use crate::{parse, Interpreter};
+
+    fn run(source: &str) -> Result<Value, HaversError> {
+        let mut interp = Interpreter::new();
+        interp.interpret(&parse(source)?)
+    }
+
+    #[test]
+    fn test_png_round_trip_keeps_pixels() {
+        let path = std::env::temp_dir().join(format!("mdh_canvas_{}.png", std::process::id()));
+        let path = path.to_string_lossy().replace('\\', "/");
+        let result = run(&format!(
+            r#"
+ken c = canvas(2, 2)
+canvas_set(c, 0, 0, [255, 0, 0])
+canvas_set(c, 1, 0, [0, 255, 0, 128])
+canvas_set(c, 0, 1, "blae")
+save_png(c, "{path}")
+ken back = load_png("{path}")
+[back.width, back.height, canvas_get(back, 0, 0), canvas_get(back, 1, 0), canvas_get(back, 0, 1), canvas_get(back, 1, 1)]
+"#
+        ))
+        .unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(
+            result.to_string(),
+            "[2, 2, [255, 0, 0, 255], [0, 255, 0, 128], [0, 121, 241, 255], [0, 0, 0, 0]]"
+        );
+    }
+
+    #[test]
+    fn test_save_png_fae_a_flat_pixel_list() {
+        let path = std::env::temp_dir().join(format!("mdh_pixels_{}.png", std::process::id()));
+        let path = path.to_string_lossy().replace('\\', "/");
+        run(&format!(
+            r#"save_png("{path}", 2, 2, [255, 0, 0, 255, 0, 255, 0, 128, 0, 0, 255, 255, 9, 8, 7, 0])"#
+        ))
+        .unwrap();
+        let data = std::fs::read(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        let canvas = Canvas::from_png(&data).unwrap();
+        assert_eq!((canvas.width(), canvas.height()), (2, 2));
+        assert_eq!(canvas.pixel(0, 0), Some([255, 0, 0, 255]));
+        assert_eq!(canvas.pixel(1, 0), Some([0, 255, 0, 128]));
+        assert_eq!(canvas.pixel(0, 1), Some([0, 0, 255, 255]));
+        assert_eq!(canvas.pixel(1, 1), Some([9, 8, 7, 0]));
+
+        let err = run(r#"save_png("nae.png", 2, 2, [1, 2, 3])"#).unwrap_err();
+        assert!(err.to_string().contains("needs 16 values"), "{err}");
+        assert!(run(r#"save_png("nae.png", 1, 1, [0, 0, 0, 256])"#).is_err());
+        assert!(run(r#"save_png("nae.png", 1, 1)"#).is_err());
+    }
+
+    #[test]
+    fn test_draw_primitives_clip_at_the_edges() {
+        let canvas = Canvas::new(5, 5);
+        let red = [255, 0, 0, 255];
+        canvas.line(0, 0, 4, 4, red);
+        assert!((0..5).all(|i| canvas.pixel(i, i) == Some(red)));
+        assert_eq!(canvas.pixel(1, 0), Some([0; 4]));
+
+        canvas.fill([0; 4]);
+        canvas.rect(3, 3, 10, 10, red);
+        assert_eq!(canvas.pixel(4, 4), Some(red));
+        assert_eq!(canvas.pixel(2, 3), Some([0; 4]));
+
+        canvas.fill([0; 4]);
+        canvas.circle(2, 2, 1, red);
+        let painted = (0..5)
+            .flat_map(|y| (0..5).map(move |x| (x, y)))
+            .filter(|&(x, y)| canvas.pixel(x, y) == Some(red))
+            .count();
+        assert_eq!(painted, 5);
+        assert_eq!(canvas.pixel(5, 0), None);
+
+        assert!(run("canvas(0, 4)").is_err());
+        assert!(run("canvas_get(canvas(1, 1), 1, 0)").is_err());
+        assert!(run("canvas_fill(canvas(1, 1), \"tartan\")").is_err());
+    }
+
+    #[test]
+    fn test_reads_compressed_pngs() {
+        // 3x2 RGB, zlib-compressed with fixed Huffman codes, rows filtered Sub then Up
+        let png = [
+            0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x48,
+            0x44, 0x52, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x02, 0x08, 0x02, 0x00, 0x00,
+            0x00, 0x12, 0x16, 0xf1, 0x4d, 0x00, 0x00, 0x00, 0x19, 0x49, 0x44, 0x41, 0x54, 0x78,
+            0xda, 0x63, 0xfc, 0xcf, 0xc0, 0xc0, 0x08, 0xc6, 0x4c, 0xdc, 0x22, 0x72, 0x1a, 0xc6,
+            0x36, 0xff, 0xff, 0x33, 0x00, 0x00, 0x36, 0xda, 0x05, 0xd5, 0x4f, 0x4d, 0x3c, 0xd7,
+            0x00, 0x00, 0x00, 0x00, 0x49, 0x45, 0x4e, 0x44, 0xae, 0x42, 0x60, 0x82,
+        ];
+        let canvas = Canvas::from_png(&png).unwrap();
+        assert_eq!((canvas.width(), canvas.height()), (3, 2));
+        assert_eq!(canvas.pixel(0, 0), Some([255, 0, 0, 255]));
+        assert_eq!(canvas.pixel(2, 0), Some([0, 0, 255, 255]));
+        assert_eq!(canvas.pixel(1, 1), Some([40, 50, 60, 255]));
+
+        let mut corrupt = png;
+        corrupt[50] ^= 1;
+        assert!(Canvas::from_png(&corrupt).is_err());
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use crate::ast::*;
 use crate::error::{HaversError, HaversResult};
 
@@ -6,6 +8,30 @@ pub struct Compiler {
     indent: usize,
     output: String,
     match_counter: usize,
+    /// Names declared with `kin` or `thing`, so calls to them get `new`
+    class_names: HashSet<String>,
+    /// Names the program binds at its top level
+    user_globals: HashSet<String>,
+    /// Source map state - only filled in by `compile_with_source_map`
+    record_mappings: bool,
+    mappings: Vec<Mapping>,
+    /// How far through `output` we've counted lines, the line count so far,
+    /// and where the current generated line starts
+    scanned: usize,
+    generated_line: usize,
+    line_start: usize,
+}
+
+/// Runtime helpers the compiled program can call by their bare names
+const RUNTIME_GLOBALS: &str = "len, whit_kind, airity, tae_string, shaw, shaw_pretty, tae_int, tae_float, shove, yank, stick_in, tak_oot, whaur, keys, values, items, get, has_key, remove, range, abs, min, max, floor, ceil, round, sqrt, pow, fmod, fmt_float, format, split, join, split_lines, contains, flatten, flatten_deep, reverse, sort, blether, set_log_level, get_log_level, log_set_filter, log_get_filter, log_enabled, log_event, log_init, log_span, log_span_enter, log_span_exit, log_span_current, log_span_in, speir, heid, tail, bum, scran, slice, slap, sumaw, sum, average, coont, wheesht, upper, lower, shuffle, noo, tick, bide, now, now_ms, sleep, gaun, sieve, tumble, aw, ony, hunt, soond_stairt, soond_steek, soond_wheesht, soond_luid, soond_hou_luid, soond_haud_gang, soond_lade, soond_spiel, soond_haud, soond_gae_on, soond_stap, soond_unlade, soond_is_spielin, soond_pit_luid, soond_pit_pan, soond_pit_tune, soond_pit_rin_roond, soond_ready, muisic_lade, muisic_spiel, muisic_haud, muisic_gae_on, muisic_stap, muisic_unlade, muisic_is_spielin, muisic_loup, muisic_hou_lang, muisic_whaur, muisic_pit_luid, muisic_pit_pan, muisic_pit_tune, muisic_pit_rin_roond, midi_lade, midi_spiel, midi_haud, midi_gae_on, midi_stap, midi_unlade, midi_is_spielin, midi_loup, midi_hou_lang, midi_whaur, midi_pit_luid, midi_pit_pan, midi_pit_rin_roond";
+
+/// One source map segment: a spot in the JS and the .braw position it came from (0-based)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Mapping {
+    generated_line: usize,
+    generated_column: usize,
+    source_line: usize,
+    source_column: usize,
 }
 
 impl Compiler {
@@ -14,6 +40,13 @@ impl Compiler {
             indent: 0,
             output: String::new(),
             match_counter: 0,
+            class_names: HashSet::new(),
+            user_globals: HashSet::new(),
+            record_mappings: false,
+            mappings: Vec::new(),
+            scanned: 0,
+            generated_line: 0,
+            line_start: 0,
         }
     }
 
@@ -22,6 +55,14 @@ impl Compiler {
         self.output.clear();
         self.indent = 0;
         self.match_counter = 0;
+        self.mappings.clear();
+        self.scanned = 0;
+        self.generated_line = 0;
+        self.line_start = 0;
+        self.class_names.clear();
+        Self::collect_class_names(&program.statements, &mut self.class_names);
+        self.user_globals.clear();
+        Self::collect_top_level_names(&program.statements, &mut self.user_globals);
 
         let mut needs_tri_runtime = false;
         for stmt in &program.statements {
@@ -39,6 +80,121 @@ impl Compiler {
         Ok(self.output.clone())
     }
 
+    /// Compile a program to JavaScript plus a V3 source map pointing back at
+    /// `source_name`. The map carries the source text in `sourcesContent`, so
+    /// debuggers can show the .braw even if it's not served alongside the JS.
+    pub fn compile_with_source_map(
+        &mut self,
+        program: &Program,
+        source_name: &str,
+        source: &str,
+    ) -> HaversResult<(String, String)> {
+        self.record_mappings = true;
+        let js = self.compile(program);
+        self.record_mappings = false;
+        let js = js?;
+
+        let map = serde_json::json!({
+            "version": 3,
+            "sources": [source_name],
+            "sourcesContent": [source],
+            "names": [],
+            "mappings": encode_mappings(&self.mappings),
+        });
+        Ok((js, map.to_string()))
+    }
+
+    /// Note that the JS about to be emitted came from `span`
+    fn record_mapping(&mut self, span: Span) {
+        if !self.record_mappings || span.line == 0 {
+            return;
+        }
+
+        let bytes = self.output.as_bytes();
+        for (offset, byte) in bytes[self.scanned..].iter().enumerate() {
+            if *byte == b'\n' {
+                self.generated_line += 1;
+                self.line_start = self.scanned + offset + 1;
+            }
+        }
+        self.scanned = bytes.len();
+
+        // At the start of a line the statement's indentation hasn't been written yet
+        let mut generated_column = self.output.len() - self.line_start;
+        if generated_column == 0 {
+            generated_column = self.indent * 2;
+        }
+
+        self.mappings.push(Mapping {
+            generated_line: self.generated_line,
+            generated_column,
+            source_line: span.line - 1,
+            source_column: span.column.saturating_sub(1),
+        });
+    }
+
+    fn collect_class_names(stmts: &[Stmt], names: &mut HashSet<String>) {
+        for stmt in stmts {
+            match stmt {
+                Stmt::Class { name, .. } | Stmt::Struct { name, .. } => {
+                    names.insert(name.clone());
+                }
+                Stmt::Block { statements, .. } => Self::collect_class_names(statements, names),
+                _ => {}
+            }
+        }
+    }
+
+    /// Names a program binds at its top level, which would clash with the
+    /// runtime's own `const` if it pulled in the same name
+    fn collect_top_level_names(stmts: &[Stmt], names: &mut HashSet<String>) {
+        for stmt in stmts {
+            match stmt {
+                Stmt::VarDecl { name, .. }
+                | Stmt::Function { name, .. }
+                | Stmt::Class { name, .. }
+                | Stmt::Struct { name, .. } => {
+                    names.insert(name.clone());
+                }
+                Stmt::Destructure { patterns, .. } => {
+                    for pattern in patterns {
+                        if let DestructPattern::Variable(name) | DestructPattern::Rest(name) =
+                            pattern
+                        {
+                            names.insert(name.clone());
+                        }
+                    }
+                }
+                Stmt::Import {
+                    path, alias, only, ..
+                } => match only {
+                    Some(only) if !Self::is_tri_import_path(path) => {
+                        names.extend(only.iter().cloned());
+                    }
+                    _ => {
+                        names.insert(Self::module_binding(path, alias.as_deref()));
+                    }
+                },
+                _ => {}
+            }
+        }
+    }
+
+    /// The name a whole-module import is bound to
+    fn module_binding(path: &str, alias: Option<&str>) -> String {
+        if Self::is_tri_import_path(path) {
+            return alias.unwrap_or("tri").to_string();
+        }
+        alias.map(str::to_string).unwrap_or_else(|| {
+            // Extract filename from path
+            path.rsplit('/')
+                .next()
+                .unwrap_or(path)
+                .replace(".braw", "")
+                .replace(".js", "")
+        })
+    }
+
     fn is_tri_import_path(path: &str) -> bool {
         path == "tri" || path == "tri.js" || path == "tri.braw"
     }
@@ -48,7 +204,9 @@ impl Compiler {
         needs_tri_runtime: &mut bool,
     ) -> HaversResult<()> {
         match stmt {
-            Stmt::Import { path, alias, span } => {
+            Stmt::Import {
+                path, alias, span, ..
+            } => {
                 if Self::is_tri_import_path(path) {
                     if alias.is_none() {
                         return Err(HaversError::TypeError {
@@ -71,6 +229,11 @@ impl Compiler {
                 then_branch,
                 else_branch,
                 ..
+            }
+            | Stmt::IfLet {
+                then_branch,
+                else_branch,
+                ..
             } => {
                 Self::scan_stmt_for_runtime_requirements(then_branch, needs_tri_runtime)?;
                 if let Some(else_branch) = else_branch {
@@ -123,7 +286,8 @@ impl Compiler {
             | Stmt::Assert { .. }
             | Stmt::Destructure { .. }
             | Stmt::Log { .. }
-            | Stmt::Hurl { .. } => {}
+            | Stmt::Hurl { .. }
+            | Stmt::Yield { .. } => {}
         }
 
         Ok(())
@@ -162,15 +326,45 @@ impl Compiler {
         self.indent -= 1;
         self.emit_line("},");
 
+        // airity - required parameter count (JS stops counting at the first default)
+        self.emit_line("airity: (f) => {");
+        self.indent += 1;
+        self.emit_line("if (typeof f !== 'function') throw new Error(`airity() needs a function, no' a ${__havers.whit_kind(f)}`);");
+        self.emit_line("return f.length;");
+        self.indent -= 1;
+        self.emit_line("},");
+
         // tae_string function
         self.emit_line("tae_string: (x) => String(x),");
 
-        // tae_int function
-        self.emit_line("tae_int: (x) => {");
+        // tae_int function - the whole string has to be digits, as in the
+        // interpreter, since parseInt stops quietly at the first bad one
+        self.emit_line("tae_int: (x, base) => {");
         self.indent += 1;
-        self.emit_line("const n = parseInt(x, 10);");
-        self.emit_line("if (isNaN(n)) throw new Error(`Cannae turn '${x}' intae an integer`);");
-        self.emit_line("return n;");
+        self.emit_line("if (base !== undefined) {");
+        self.indent += 1;
+        self.emit_line("if (typeof x !== 'string' || !Number.isInteger(base)) throw new Error(\"tae_int() wi' a base needs a string an' an integer base\");");
+        self.emit_line("if (base < 2 || base > 36) throw new Error(`Base must be atween 2 an' 36, no' ${base}`);");
+        self.emit_line("const digits = x.startsWith('-') || x.startsWith('+') ? x.slice(1) : x;");
+        self.emit_line("if (digits === '') throw new Error(`Cannae read '${x}' as a base ${base} number`);");
+        self.emit_line("for (const c of digits) {");
+        self.indent += 1;
+        self.emit_line("const d = /^[0-9a-zA-Z]$/.test(c) ? parseInt(c, 36) : NaN;");
+        self.emit_line("if (!(d < base)) throw new Error(`'${c}' isnae a base ${base} digit (in '${x}')`);");
+        self.indent -= 1;
+        self.emit_line("}");
+        self.emit_line("return parseInt(x, base);");
+        self.indent -= 1;
+        self.emit_line("}");
+        self.emit_line("if (typeof x === 'number') {");
+        self.indent += 1;
+        self.emit_line("if (!Number.isFinite(x) || Math.abs(x) >= 2 ** 63) throw new Error('Cannae turn inf, NaN or a float that muckle intae an integer');");
+        self.emit_line("return Math.trunc(x);");
+        self.indent -= 1;
+        self.emit_line("}");
+        self.emit_line("if (typeof x === 'boolean') return x ? 1 : 0;");
+        self.emit_line("if (typeof x !== 'string' || !/^[+-]?[0-9]+$/.test(x)) throw new Error(`Cannae turn '${x}' intae an integer`);");
+        self.emit_line("return parseInt(x, 10);");
         self.indent -= 1;
         self.emit_line("},");
 
@@ -194,17 +388,83 @@ impl Compiler {
         self.indent -= 1;
         self.emit_line("},");
 
+        // stick_in (insert at index, in place) function
+        self.emit_line("stick_in: (arr, i, val) => {");
+        self.indent += 1;
+        self.emit_line("const pos = i < 0 ? arr.length + i : i;");
+        self.emit_line("if (pos < 0 || pos > arr.length) throw new Error(`Index ${i} oot o' bounds fer stick_in on a list o' length ${arr.length}`);");
+        self.emit_line("arr.splice(pos, 0, val);");
+        self.emit_line("return arr;");
+        self.indent -= 1;
+        self.emit_line("},");
+
+        // tak_oot (remove at index, in place) function
+        self.emit_line("tak_oot: (arr, i) => {");
+        self.indent += 1;
+        self.emit_line("const pos = i < 0 ? arr.length + i : i;");
+        self.emit_line("if (pos < 0 || pos >= arr.length) throw new Error(`Index ${i} oot o' bounds fer tak_oot on a list o' length ${arr.length}`);");
+        self.emit_line("return arr.splice(pos, 1)[0];");
+        self.indent -= 1;
+        self.emit_line("},");
+
+        // whaur (index of first match) function
+        self.emit_line("whaur: (arr, val) => arr.indexOf(val),");
+
         // keys function
         self.emit_line("keys: (obj) => Object.keys(obj),");
 
         // values function
         self.emit_line("values: (obj) => Object.values(obj),");
 
-        // range function
-        self.emit_line("range: (start, end) => {");
+        // items function - [key, value] pairs
+        self.emit_line("items: (obj) => Object.entries(obj),");
+
+        // fer_pairs - what `fer k, v in x` steps through: a dict's [key, value]
+        // pairs, or any other iterable's items unpacked as pairs
+        self.emit_line("fer_pairs: function* (x) {");
+        self.indent += 1;
+        self.emit_line("const items = (x !== null && typeof x === 'object' && !(Symbol.iterator in x)) ? Object.entries(x) : x;");
+        self.emit_line("for (const pair of items) {");
         self.indent += 1;
+        self.emit_line("if (!Array.isArray(pair)) throw new Error(`Cannae unpack a ${__havers.whit_kind(pair)} intae 2 names`);");
+        self.emit_line("if (pair.length !== 2) throw new Error(`Cannae unpack ${pair.length} values intae 2 names`);");
+        self.emit_line("yield pair;");
+        self.indent -= 1;
+        self.emit_line("}");
+        self.indent -= 1;
+        self.emit_line("},");
+
+        // get / has_key / remove - dict lookups that don't crash on a missing key
+        self.emit_line("get: (obj, key, dflt) => Object.prototype.hasOwnProperty.call(obj, key) ? obj[key] : dflt,");
+        self.emit_line("has_key: (obj, key) => Object.prototype.hasOwnProperty.call(obj, key),");
+        self.emit_line("remove: (obj, key) => {");
+        self.indent += 1;
+        self.emit_line("if (!Object.prototype.hasOwnProperty.call(obj, key)) return null;");
+        self.emit_line("const val = obj[key];");
+        self.emit_line("delete obj[key];");
+        self.emit_line("return val;");
+        self.indent -= 1;
+        self.emit_line("},");
+
+        // range function - counts down when start > end, step is the stride
+        self.emit_line("range: (start, end, step = 1, inclusive = false) => {");
+        self.indent += 1;
+        self.emit_line("if (step === 0) throw new Error(\"Range step cannae be zero\");");
+        self.emit_line("const stride = Math.abs(step);");
         self.emit_line("const result = [];");
-        self.emit_line("for (let i = start; i < end; i++) result.push(i);");
+        self.emit_line("if (start <= end) {");
+        self.indent += 1;
+        self.emit_line(
+            "for (let i = start; i < end || (inclusive && i === end); i += stride) result.push(i);",
+        );
+        self.indent -= 1;
+        self.emit_line("} else {");
+        self.indent += 1;
+        self.emit_line(
+            "for (let i = start; i > end || (inclusive && i === end); i -= stride) result.push(i);",
+        );
+        self.indent -= 1;
+        self.emit_line("}");
         self.emit_line("return result;");
         self.indent -= 1;
         self.emit_line("},");
@@ -212,11 +472,27 @@ impl Compiler {
         // abs function
         self.emit_line("abs: Math.abs,");
 
-        // min function
-        self.emit_line("min: Math.min,");
-
-        // max function
-        self.emit_line("max: Math.max,");
+        // min / max - of two values, or of a single list. Numbers order
+        // against numbers and strings against strings, like the interpreter
+        self.emit_line("__extreme: (name, args, wanted, empty) => {");
+        self.indent += 1;
+        self.emit_line("if (args.length === 1 && !Array.isArray(args[0])) throw new Error(`${name}() needs a list or twa values, no' a lane ${__havers.whit_kind(args[0])}`);");
+        self.emit_line("if (args.length !== 1 && args.length !== 2) throw new Error(`${name}() expects a list or twa values`);");
+        self.emit_line("const items = args.length === 1 ? args[0] : args;");
+        self.emit_line("if (items.length === 0) throw new Error(empty);");
+        self.emit_line("let best = items[0];");
+        self.emit_line("for (const item of items.slice(1)) {");
+        self.indent += 1;
+        self.emit_line("const kinds = [typeof best, typeof item];");
+        self.emit_line("if (kinds[0] !== kinds[1] || (kinds[0] !== 'number' && kinds[0] !== 'string')) throw new Error(`${name}() cannae compare ${__havers.whit_kind(best)} wi' ${__havers.whit_kind(item)}`);");
+        self.emit_line("if (wanted < 0 ? item < best : item > best) best = item;");
+        self.indent -= 1;
+        self.emit_line("}");
+        self.emit_line("return best;");
+        self.indent -= 1;
+        self.emit_line("},");
+        self.emit_line("min: (...args) => __havers.__extreme('min', args, -1, \"Cannae find the wee-est o' naething\"),");
+        self.emit_line("max: (...args) => __havers.__extreme('max', args, 1, \"Cannae find the muckle-est o' naething\"),");
 
         // floor function
         self.emit_line("floor: Math.floor,");
@@ -227,25 +503,93 @@ impl Compiler {
         // round function
         self.emit_line("round: Math.round,");
 
+        // fmt_float function - fixed decimals
+        self.emit_line("fmt_float: (x, decimals) => x.toFixed(decimals),");
+
+        // format function - `{}` placeholders with `{:W}` width and `{:.P}` precision
+        self.emit_line("format: (template, ...args) => {");
+        self.indent += 1;
+        self.emit_line("let next = 0;");
+        self.emit_line(
+            "const out = template.replace(/\\{\\{|\\}\\}|\\{([^{}]*)\\}|[{}]/g, (m, spec) => {",
+        );
+        self.indent += 1;
+        self.emit_line("if (m === '{{') return '{';");
+        self.emit_line("if (m === '}}') return '}';");
+        self.emit_line("if (spec === undefined) throw new Error(`format() found a lone '${m}' - write '${m}${m}' fer a brace`);");
+        self.emit_line("const parts = /^(?::(\\d*)(?:\\.(\\d+))?)?$/.exec(spec);");
+        self.emit_line(
+            "if (!parts) throw new Error(`format() disnae ken the placeholder '{${spec}}'`);",
+        );
+        self.emit_line("if (next >= args.length) throw new Error(`format() has mair placeholders than values - only gied ${args.length}`);");
+        self.emit_line("const x = args[next++];");
+        self.emit_line("const isNumber = typeof x === 'number';");
+        self.emit_line("if (parts[2] !== undefined && !isNumber) throw new Error(`format() precision is fer numbers, no' a ${typeof x}`);");
+        self.emit_line(
+            "const text = parts[2] !== undefined ? x.toFixed(Number(parts[2])) : String(x);",
+        );
+        self.emit_line("const width = Number(parts[1] || 0);");
+        self.emit_line("return isNumber ? text.padStart(width) : text.padEnd(width);");
+        self.indent -= 1;
+        self.emit_line("});");
+        self.emit_line("if (next < args.length) throw new Error(`format() wis gied ${args.length} values but the template only has ${next} placeholders`);");
+        self.emit_line("return out;");
+        self.indent -= 1;
+        self.emit_line("},");
+
         // sqrt function
-        self.emit_line("sqrt: Math.sqrt,");
+        self.emit_line("sqrt: (x) => {");
+        self.indent += 1;
+        self.emit_line("if (x < 0) throw new Error(`sqrt() cannae take the square root o' a negative number (${x})`);");
+        self.emit_line("return Math.sqrt(x);");
+        self.indent -= 1;
+        self.emit_line("},");
+
+        // pow function
+        self.emit_line("pow: Math.pow,");
+
+        // fmod function (JS % already keeps the sign of the first number)
+        self.emit_line("fmod: (a, b) => {");
+        self.indent += 1;
+        self.emit_line("if (b === 0) throw new Error('fmod() cannae divide by zero');");
+        self.emit_line("return a % b;");
+        self.indent -= 1;
+        self.emit_line("},");
 
         // split function
         self.emit_line("split: (str, delim) => str.split(delim),");
 
-        // join function
-        self.emit_line("join: (arr, delim) => arr.join(delim),");
+        // join function (non-strings go through tae_string, not JS's own join rules)
+        self.emit_line("join: (arr, delim) => arr.map(__havers.tae_string).join(delim),");
+
+        // split_lines function - \n or \r\n, dropping a trailing empty line
+        self.emit_line("split_lines: (str) => {");
+        self.indent += 1;
+        self.emit_line("const lines = str.split(/\\r?\\n/);");
+        self.emit_line("if (lines[lines.length - 1] === '') lines.pop();");
+        self.emit_line("return lines;");
+        self.indent -= 1;
+        self.emit_line("},");
 
         // contains function
         self.emit_line("contains: (container, item) => {");
         self.indent += 1;
         self.emit_line("if (typeof container === 'string') return container.includes(item);");
         self.emit_line("if (Array.isArray(container)) return container.includes(item);");
-        self.emit_line("if (typeof container === 'object') return item in container;");
-        self.emit_line("return false;");
+        self.emit_line("if (container instanceof Set) return container.has(item);");
+        self.emit_line(
+            "if (container !== null && typeof container === 'object') return item in container;",
+        );
+        self.emit_line("throw new Error(`Cannae look inside ${container} - it needs tae be a list, dict, creel or string`);");
         self.indent -= 1;
         self.emit_line("},");
 
+        // flatten functions - one level, or up to a depth (negative/left out is all the way)
+        self.emit_line("flatten: (arr) => arr.flat(1),");
+        self.emit_line(
+            "flatten_deep: (arr, depth) => arr.flat(depth == null || depth < 0 ? Infinity : depth),",
+        );
+
         // reverse function
         self.emit_line("reverse: (x) => {");
         self.indent += 1;
@@ -266,6 +610,47 @@ impl Compiler {
         // blether (print) function
         self.emit_line("blether: console.log,");
 
+        // shaw / shaw_pretty - type-tagged dumps, with `…` for a container inside itself
+        self.emit_line("__shaw: (x, pretty, depth = 0, seen = []) => {");
+        self.indent += 1;
+        self.emit_line("if (x === null || x === undefined) return 'Naething';");
+        self.emit_line("if (typeof x === 'boolean') return x ? 'Bool:aye' : 'Bool:nae';");
+        self.emit_line("if (typeof x === 'number') return Number.isInteger(x) ? `Int:${x}` : `Float:${x}`;");
+        self.emit_line("if (typeof x === 'string') return `Str:${JSON.stringify(x)}`;");
+        self.emit_line("if (typeof x === 'function') return `Dae:${x.name}`;");
+        self.emit_line("if (seen.includes(x)) return '…';");
+        self.emit_line("let header, open, close, entries;");
+        self.emit_line("if (Array.isArray(x)) {");
+        self.indent += 1;
+        self.emit_line("[header, open, close] = [`List[${x.length}]`, '[', ']'];");
+        self.emit_line("entries = x.map((v) => [null, v]);");
+        self.indent -= 1;
+        self.emit_line("} else if (x instanceof Set) {");
+        self.indent += 1;
+        self.emit_line("[header, open, close] = [`Creel[${x.size}]`, '{', '}'];");
+        self.emit_line("entries = [...x].sort().map((v) => [null, v]);");
+        self.indent -= 1;
+        self.emit_line("} else if (x.constructor && x.constructor !== Object) {");
+        self.indent += 1;
+        self.emit_line("[header, open, close] = [`Instance ${x.constructor.name}`, '{', '}'];");
+        self.emit_line("entries = Object.keys(x).sort().map((k) => [k, x[k]]);");
+        self.indent -= 1;
+        self.emit_line("} else {");
+        self.indent += 1;
+        self.emit_line("[header, open, close] = [`Dict[${Object.keys(x).length}]`, '{', '}'];");
+        self.emit_line("entries = Object.keys(x).map((k) => [__havers.__shaw(k, false), x[k]]);");
+        self.indent -= 1;
+        self.emit_line("}");
+        self.emit_line("const inner = seen.concat([x]);");
+        self.emit_line("const parts = entries.map(([k, v]) => (k === null ? '' : `${k}: `) + __havers.__shaw(v, pretty, depth + 1, inner));");
+        self.emit_line("if (!pretty || parts.length === 0) return `${header}: ${open}${parts.join(', ')}${close}`;");
+        self.emit_line("const pad = '  '.repeat(depth + 1);");
+        self.emit_line("return `${header}: ${open}\\n${pad}${parts.join(`,\\n${pad}`)}\\n${'  '.repeat(depth)}${close}`;");
+        self.indent -= 1;
+        self.emit_line("},");
+        self.emit_line("shaw: (x) => console.log(__havers.__shaw(x, false)),");
+        self.emit_line("shaw_pretty: (x) => console.log(__havers.__shaw(x, true)),");
+
         // logging helpers
         self.emit_line("set_log_level: __mdh_log_set_level,");
         self.emit_line("get_log_level: __mdh_log_get_level,");
@@ -359,6 +744,31 @@ impl Compiler {
         self.indent -= 1;
         self.emit_line("},");
 
+        // sum / average - totals of a list of numbers
+        self.emit_line("sum: (arr) => {");
+        self.indent += 1;
+        self.emit_line("if (!Array.isArray(arr)) throw new Error('sum() needs a list');");
+        self.emit_line("let total = 0;");
+        self.emit_line("for (const x of arr) {");
+        self.indent += 1;
+        self.emit_line("if (typeof x !== 'number') throw new Error(`sum() needs a list o' numbers, no' a ${__havers.whit_kind(x)}`);");
+        self.emit_line("total += x;");
+        self.indent -= 1;
+        self.emit_line("}");
+        self.emit_line("return total;");
+        self.indent -= 1;
+        self.emit_line("},");
+        self.emit_line("average: (arr) => {");
+        self.indent += 1;
+        self.emit_line("if (!Array.isArray(arr)) throw new Error('average() needs a list');");
+        self.emit_line(
+            "if (arr.length === 0) throw new Error(\"Cannae calculate average o' empty list!\");",
+        );
+        self.emit_line("if (arr.some((x) => typeof x !== 'number')) throw new Error(\"average() needs a list o' numbers\");");
+        self.emit_line("return arr.reduce((a, b) => a + b, 0) / arr.length;");
+        self.indent -= 1;
+        self.emit_line("},");
+
         // coont - count occurrences
         self.emit_line("coont: (x, item) => {");
         self.indent += 1;
@@ -393,7 +803,7 @@ impl Compiler {
         self.emit_line("},");
 
         // slice - slice with step (fer [start:end:step] syntax)
-        self.emit_line("slice: (x, start, end, step) => {");
+        self.emit_line("slice: (x, start, end = null, step = 1) => {");
         self.indent += 1;
         self.emit_line("const len = x.length;");
         self.emit_line("const isStr = typeof x === 'string';");
@@ -416,6 +826,47 @@ impl Compiler {
         self.indent -= 1;
         self.emit_line("},");
 
+        // index - x[i] with negative indices for lists/strings, and no silent undefined
+        self.emit_line("index: (x, i) => {");
+        self.indent += 1;
+        self.emit_line("if (typeof x === 'string' || Array.isArray(x)) {");
+        self.indent += 1;
+        self.emit_line("const n = i < 0 ? x.length + i : i;");
+        self.emit_line("if (!Number.isInteger(n) || n < 0 || n >= x.length) throw new Error(`Index ${i} is oot o' bounds (size is ${x.length})`);");
+        self.emit_line("return x[n];");
+        self.indent -= 1;
+        self.emit_line("}");
+        self.emit_line("if (x !== null && typeof x === 'object') {");
+        self.indent += 1;
+        self.emit_line("if (!Object.prototype.hasOwnProperty.call(x, i)) throw new Error(`Key '${i}' isnae in the dict`);");
+        self.emit_line("return x[i];");
+        self.indent -= 1;
+        self.emit_line("}");
+        self.emit_line("throw new Error(`Cannae index a ${__havers.whit_kind(x)}`);");
+        self.indent -= 1;
+        self.emit_line("},");
+
+        // index_set - x[i] = v, the same rules as index but dicts grow new keys
+        self.emit_line("index_set: (x, i, v) => {");
+        self.indent += 1;
+        self.emit_line("if (Array.isArray(x)) {");
+        self.indent += 1;
+        self.emit_line("const n = i < 0 ? x.length + i : i;");
+        self.emit_line("if (!Number.isInteger(n) || n < 0 || n >= x.length) throw new Error(`Index ${i} is oot o' bounds (size is ${x.length})`);");
+        self.emit_line("x[n] = v;");
+        self.emit_line("return v;");
+        self.indent -= 1;
+        self.emit_line("}");
+        self.emit_line("if (x !== null && typeof x === 'object') {");
+        self.indent += 1;
+        self.emit_line("x[i] = v;");
+        self.emit_line("return v;");
+        self.indent -= 1;
+        self.emit_line("}");
+        self.emit_line("throw new Error(`Cannae set an index on a ${__havers.whit_kind(x)}`);");
+        self.indent -= 1;
+        self.emit_line("},");
+
         // Timing functions
         self.emit_line("// Timing functions");
         self.emit_line("noo: () => Date.now(),");
@@ -436,6 +887,9 @@ impl Compiler {
         self.emit_line("while (Date.now() < end) {} // Busy wait (sync)");
         self.indent -= 1;
         self.emit_line("},");
+        self.emit_line("now: () => Date.now() / 1000,");
+        self.emit_line("now_ms: () => Date.now(),");
+        self.emit_line("sleep: (ms) => __havers.bide(ms),");
 
         // Higher-order functions
         self.emit_line("// Higher-order functions");
@@ -585,12 +1039,18 @@ impl Compiler {
             self.emit_line("");
         }
 
-        // Import runtime functions to global scope
-        self.emit_line("const { len, whit_kind, tae_string, tae_int, tae_float, shove, yank, keys, values, range, abs, min, max, floor, ceil, round, sqrt, split, join, contains, reverse, sort, blether, set_log_level, get_log_level, log_set_filter, log_get_filter, log_enabled, log_event, log_init, log_span, log_span_enter, log_span_exit, log_span_current, log_span_in, speir, heid, tail, bum, scran, slap, sumaw, coont, wheesht, upper, lower, shuffle, noo, tick, bide, gaun, sieve, tumble, aw, ony, hunt, soond_stairt, soond_steek, soond_wheesht, soond_luid, soond_hou_luid, soond_haud_gang, soond_lade, soond_spiel, soond_haud, soond_gae_on, soond_stap, soond_unlade, soond_is_spielin, soond_pit_luid, soond_pit_pan, soond_pit_tune, soond_pit_rin_roond, soond_ready, muisic_lade, muisic_spiel, muisic_haud, muisic_gae_on, muisic_stap, muisic_unlade, muisic_is_spielin, muisic_loup, muisic_hou_lang, muisic_whaur, muisic_pit_luid, muisic_pit_pan, muisic_pit_tune, muisic_pit_rin_roond, midi_lade, midi_spiel, midi_haud, midi_gae_on, midi_stap, midi_unlade, midi_is_spielin, midi_loup, midi_hou_lang, midi_whaur, midi_pit_luid, midi_pit_pan, midi_pit_rin_roond } = __havers;");
+        // Import runtime functions to global scope, leaving out any the
+        // program declares at its top level so its own `let`/`function` wins
+        let globals: Vec<&str> = RUNTIME_GLOBALS
+            .split(", ")
+            .filter(|name| !self.user_globals.contains(*name))
+            .collect();
+        self.emit_line(&format!("const {{ {} }} = __havers;", globals.join(", ")));
         self.emit_line("");
     }
 
     fn compile_stmt(&mut self, stmt: &Stmt) {
+        self.record_mapping(stmt.span());
         match stmt {
             Stmt::VarDecl {
                 name, initializer, ..
@@ -639,6 +1099,37 @@ impl Compiler {
                 self.output.push('\n');
             }
 
+            Stmt::IfLet {
+                name,
+                value,
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                // Its own block, so the binding never leaks into the other branch.
+                // `!= null` catches undefined from missing keys as well.
+                self.emit_line("{");
+                self.indent += 1;
+                self.emit_indent();
+                self.output.push_str("const __gin_ken = ");
+                self.compile_expr(value);
+                self.output.push_str(";\n");
+                self.emit_line("if (__gin_ken != null) {");
+                self.indent += 1;
+                self.emit_line(&format!("let {} = __gin_ken;", name));
+                self.compile_stmt(then_branch);
+                self.indent -= 1;
+                self.emit_indent();
+                self.output.push('}');
+                if let Some(else_br) = else_branch {
+                    self.output.push_str(" else ");
+                    self.compile_stmt_inline(else_br);
+                }
+                self.output.push('\n');
+                self.indent -= 1;
+                self.emit_line("}");
+            }
+
             Stmt::While {
                 condition, body, ..
             } => {
@@ -652,14 +1143,27 @@ impl Compiler {
 
             Stmt::For {
                 variable,
+                value_variable,
                 iterable,
                 body,
                 ..
             } => {
                 self.emit_indent();
-                self.output
-                    .push_str(&format!("for (const {} of ", variable));
-                self.compile_expr(iterable);
+                match value_variable {
+                    Some(value_variable) => {
+                        self.output.push_str(&format!(
+                            "for (const [{}, {}] of __havers.fer_pairs(",
+                            variable, value_variable
+                        ));
+                        self.compile_expr(iterable);
+                        self.output.push(')');
+                    }
+                    None => {
+                        self.output
+                            .push_str(&format!("for (const {} of ", variable));
+                        self.compile_expr(iterable);
+                    }
+                }
                 self.output.push_str(") ");
                 self.compile_stmt_inline(body);
                 self.output.push('\n');
@@ -670,8 +1174,14 @@ impl Compiler {
             } => {
                 self.emit_indent();
                 let params_str = self.compile_params(params);
+                // A body that gie_backs is a JS generator
+                let star = if body.iter().any(Stmt::contains_yield) {
+                    "*"
+                } else {
+                    ""
+                };
                 self.output
-                    .push_str(&format!("function {}({}) {{\n", name, params_str));
+                    .push_str(&format!("function{} {}({}) {{\n", star, name, params_str));
                 self.indent += 1;
                 for stmt in body {
                     self.compile_stmt(stmt);
@@ -719,6 +1229,17 @@ impl Compiler {
                 self.output.push_str(" {\n");
                 self.indent += 1;
 
+                // `init` stays an ordinary method, called from the base class
+                // constructor. Like the interpreter, that runs the nearest `init`
+                // up the chain, and subclasses never need to call super().
+                if superclass.is_none() {
+                    self.emit_line("constructor(...args) {");
+                    self.indent += 1;
+                    self.emit_line("if (typeof this.init === 'function') this.init(...args);");
+                    self.indent -= 1;
+                    self.emit_line("}");
+                }
+
                 for method in methods {
                     if let Stmt::Function {
                         name: method_name,
@@ -728,14 +1249,16 @@ impl Compiler {
                     } = method
                     {
                         self.emit_indent();
-                        let js_name = if method_name == "init" {
-                            "constructor"
+                        let params_str = self.compile_params(params);
+                        let star = if body.iter().any(Stmt::contains_yield) {
+                            "*"
                         } else {
-                            method_name
+                            ""
                         };
-                        let params_str = self.compile_params(params);
-                        self.output
-                            .push_str(&format!("{}({}) {{\n", js_name, params_str));
+                        self.output.push_str(&format!(
+                            "{}{}({}) {{\n",
+                            star, method_name, params_str
+                        ));
                         self.indent += 1;
                         for stmt in body {
                             self.compile_stmt(stmt);
@@ -767,19 +1290,19 @@ impl Compiler {
                 self.emit_line("}");
             }
 
-            Stmt::Import { path, alias, .. } => {
-                if path == "tri" || path == "tri.js" || path == "tri.braw" {
-                    let module_name = alias.as_deref().unwrap_or("tri").to_string();
+            Stmt::Import {
+                path, alias, only, ..
+            } => {
+                let module_name = Self::module_binding(path, alias.as_deref());
+                if Self::is_tri_import_path(path) {
                     self.emit_line(&format!("const {} = __havers_tri;", module_name));
+                } else if let Some(names) = only {
+                    self.emit_line(&format!(
+                        "const {{ {} }} = require('{}');",
+                        names.join(", "),
+                        path
+                    ));
                 } else {
-                    let module_name = alias.clone().unwrap_or_else(|| {
-                        // Extract filename from path
-                        path.rsplit('/')
-                            .next()
-                            .unwrap_or(path)
-                            .replace(".braw", "")
-                            .replace(".js", "")
-                    });
                     self.emit_line(&format!("const {} = require('{}');", module_name, path));
                 }
             }
@@ -872,7 +1395,10 @@ impl Compiler {
             }
 
             Stmt::Destructure {
-                patterns, value, ..
+                patterns,
+                value,
+                exact,
+                ..
             } => {
                 // JavaScript destructuring: const [a, b, ...rest] = value
                 self.emit_indent();
@@ -893,7 +1419,25 @@ impl Compiler {
                 }
 
                 self.output.push_str("] = ");
-                self.compile_expr(value);
+                if *exact {
+                    // `ken a, b = ...` checks the count like the interpreter does
+                    let has_rest = patterns
+                        .iter()
+                        .any(|p| matches!(p, DestructPattern::Rest(_)));
+                    let (test, wanted) = if has_rest {
+                        ("<", patterns.len() - 1)
+                    } else {
+                        ("!==", patterns.len())
+                    };
+                    self.output.push_str(&format!(
+                        "((t) => {{ if (t.length {} {}) throw new Error(\"Cannae unpack \" + t.length + \" values intae {} names\"); return t; }})(",
+                        test, wanted, patterns.len()
+                    ));
+                    self.compile_expr(value);
+                    self.output.push(')');
+                } else {
+                    self.compile_expr(value);
+                }
                 self.output.push_str(";\n");
             }
 
@@ -929,6 +1473,13 @@ impl Compiler {
                 self.compile_expr(message);
                 self.output.push_str(");\n");
             }
+
+            Stmt::Yield { value, .. } => {
+                self.emit_indent();
+                self.output.push_str("yield ");
+                self.compile_expr(value);
+                self.output.push_str(";\n");
+            }
         }
     }
 
@@ -1027,6 +1578,23 @@ impl Compiler {
                 self.output.push(')');
             }
 
+            // `in` asks the container, so the operands swap round
+            Expr::Binary {
+                left,
+                operator: operator @ (BinaryOp::In | BinaryOp::NotIn),
+                right,
+                ..
+            } => {
+                if *operator == BinaryOp::NotIn {
+                    self.output.push('!');
+                }
+                self.output.push_str("__havers.contains(");
+                self.compile_expr(right);
+                self.output.push_str(", ");
+                self.compile_expr(left);
+                self.output.push(')');
+            }
+
             Expr::Binary {
                 left,
                 operator,
@@ -1041,12 +1609,19 @@ impl Compiler {
                     BinaryOp::Multiply => " * ",
                     BinaryOp::Divide => " / ",
                     BinaryOp::Modulo => " % ",
+                    BinaryOp::Power => " ** ",
                     BinaryOp::Equal => " === ",
                     BinaryOp::NotEqual => " !== ",
                     BinaryOp::Less => " < ",
                     BinaryOp::LessEqual => " <= ",
                     BinaryOp::Greater => " > ",
                     BinaryOp::GreaterEqual => " >= ",
+                    BinaryOp::BitAnd => " & ",
+                    BinaryOp::BitOr => " | ",
+                    BinaryOp::BitXor => " ^ ",
+                    BinaryOp::ShiftLeft => " << ",
+                    BinaryOp::ShiftRight => " >> ",
+                    BinaryOp::In | BinaryOp::NotIn => unreachable!("compiled above"),
                 };
                 self.output.push_str(op_str);
                 self.compile_expr(right);
@@ -1088,9 +1663,12 @@ impl Compiler {
             Expr::Call {
                 callee, arguments, ..
             } => {
-                // Heuristic: If calling a variable with a capitalized name, assume it's a class constructor
+                // Known classes get `new`; for anything else (e.g. imported classes)
+                // fall back to assuming a capitalized name is a constructor
                 if let Expr::Variable { name, .. } = &**callee {
-                    if name.chars().next().is_some_and(|c| c.is_uppercase()) {
+                    if self.class_names.contains(name)
+                        || name.chars().next().is_some_and(|c| c.is_uppercase())
+                    {
                         self.output.push_str("new ");
                     }
                 }
@@ -1129,11 +1707,14 @@ impl Compiler {
                 self.output.push(')');
             }
 
+            // Indexing goes through the runtime so negative indices and missing keys
+            // behave like the interpreter rather than quietly giving undefined
             Expr::Index { object, index, .. } => {
+                self.output.push_str("__havers.index(");
                 self.compile_expr(object);
-                self.output.push('[');
+                self.output.push_str(", ");
                 self.compile_expr(index);
-                self.output.push(']');
+                self.output.push(')');
             }
 
             Expr::IndexSet {
@@ -1142,11 +1723,11 @@ impl Compiler {
                 value,
                 ..
             } => {
-                self.output.push('(');
+                self.output.push_str("__havers.index_set(");
                 self.compile_expr(object);
-                self.output.push('[');
+                self.output.push_str(", ");
                 self.compile_expr(index);
-                self.output.push_str("] = ");
+                self.output.push_str(", ");
                 self.compile_expr(value);
                 self.output.push(')');
             }
@@ -1206,13 +1787,26 @@ impl Compiler {
                 self.output.push(']');
             }
 
+            Expr::ListComp {
+                element, clauses, ..
+            } => self.compile_comprehension(element, clauses),
+
+            // Dicts become plain JS objects, so `d["key"]` and `d.key` see the same
+            // entry. JS stringifies keys: `{1: "a"}` and `{"1": "a"}` are the same dict.
+            // Keys that are not literals are emitted as computed keys.
             Expr::Dict { pairs, .. } => {
                 self.output.push('{');
                 for (i, (key, value)) in pairs.iter().enumerate() {
                     if i > 0 {
                         self.output.push_str(", ");
                     }
-                    self.compile_expr(key);
+                    if matches!(key, Expr::Literal { .. }) {
+                        self.compile_expr(key);
+                    } else {
+                        self.output.push('[');
+                        self.compile_expr(key);
+                        self.output.push(']');
+                    }
                     self.output.push_str(": ");
                     self.compile_expr(value);
                 }
@@ -1223,17 +1817,22 @@ impl Compiler {
                 start,
                 end,
                 inclusive,
+                step,
                 ..
             } => {
                 self.output.push_str("__havers.range(");
                 self.compile_expr(start);
                 self.output.push_str(", ");
+                self.compile_expr(end);
+                if step.is_some() || *inclusive {
+                    self.output.push_str(", ");
+                    match step {
+                        Some(st) => self.compile_expr(st),
+                        None => self.output.push('1'),
+                    }
+                }
                 if *inclusive {
-                    self.output.push('(');
-                    self.compile_expr(end);
-                    self.output.push_str(" + 1)");
-                } else {
-                    self.compile_expr(end);
+                    self.output.push_str(", true");
                 }
                 self.output.push(')');
             }
@@ -1267,14 +1866,16 @@ impl Compiler {
                 for part in parts {
                     match part {
                         FStringPart::Text(text) => {
-                            // Escape backticks in the text
+                            // Escape anything a template literal would read
+                            // as syntax: backticks, `${`, and backslashes
                             for c in text.chars() {
-                                if c == '`' {
-                                    self.output.push_str("\\`");
-                                } else if c == '$' {
-                                    self.output.push_str("\\$");
-                                } else {
-                                    self.output.push(c);
+                                match c {
+                                    '`' => self.output.push_str("\\`"),
+                                    '$' => self.output.push_str("\\$"),
+                                    '\\' => self.output.push_str("\\\\"),
+                                    // JS folds a raw CR in a template into LF
+                                    '\r' => self.output.push_str("\\r"),
+                                    _ => self.output.push(c),
                                 }
                             }
                         }
@@ -1330,6 +1931,33 @@ impl Compiler {
         }
     }
 
+    /// A comprehension becomes nested `flatMap`s: each `fer` maps its items
+    /// to the lists the clauses after it make, and a `gin` gives `[]` to drop one
+    fn compile_comprehension(&mut self, element: &Expr, clauses: &[CompClause]) {
+        match clauses.split_first() {
+            None => {
+                self.output.push('[');
+                self.compile_expr(element);
+                self.output.push(']');
+            }
+            Some((CompClause::If(condition), rest)) => {
+                self.output.push_str("((");
+                self.compile_expr(condition);
+                self.output.push_str(") ? ");
+                self.compile_comprehension(element, rest);
+                self.output.push_str(" : [])");
+            }
+            Some((CompClause::For { variable, iterable }, rest)) => {
+                self.output.push_str("Array.from(");
+                self.compile_expr(iterable);
+                self.output
+                    .push_str(&format!(").flatMap(({}) => ", variable));
+                self.compile_comprehension(element, rest);
+                self.output.push(')');
+            }
+        }
+    }
+
     fn emit_indent(&mut self) {
         for _ in 0..self.indent {
             self.output.push_str("  ");
@@ -1356,10 +1984,154 @@ pub fn compile(source: &str) -> HaversResult<String> {
     compiler.compile(&program)
 }
 
+/// Compile source code to JavaScript and a V3 source map for it
+pub fn compile_with_source_map(source: &str, source_name: &str) -> HaversResult<(String, String)> {
+    let program = crate::parser::parse(source)?;
+    let mut compiler = Compiler::new();
+    compiler.compile_with_source_map(&program, source_name, source)
+}
+
+/// Encode mappings as the `mappings` field of a V3 source map: lines split by
+/// `;`, segments by `,`, each segment a run of base64 VLQ deltas
+fn encode_mappings(mappings: &[Mapping]) -> String {
+    let mut out = String::new();
+    let mut line = 0;
+    let mut prev_generated_column = 0i64;
+    let mut prev_source_line = 0i64;
+    let mut prev_source_column = 0i64;
+    let mut first_in_line = true;
+
+    for m in mappings {
+        while line < m.generated_line {
+            out.push(';');
+            line += 1;
+            prev_generated_column = 0;
+            first_in_line = true;
+        }
+        if !first_in_line {
+            out.push(',');
+        }
+        first_in_line = false;
+
+        encode_vlq(&mut out, m.generated_column as i64 - prev_generated_column);
+        encode_vlq(&mut out, 0); // always the first (and only) source
+        encode_vlq(&mut out, m.source_line as i64 - prev_source_line);
+        encode_vlq(&mut out, m.source_column as i64 - prev_source_column);
+
+        prev_generated_column = m.generated_column as i64;
+        prev_source_line = m.source_line as i64;
+        prev_source_column = m.source_column as i64;
+    }
+
+    out
+}
+
+fn encode_vlq(out: &mut String, value: i64) {
+    const BASE64: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut vlq = if value < 0 {
+        ((-value) << 1) | 1
+    } else {
+        value << 1
+    };
+    loop {
+        let mut digit = vlq & 0b11111;
+        vlq >>= 5;
+        if vlq > 0 {
+            digit |= 0b100000;
+        }
+        out.push(BASE64[digit as usize] as char);
+        if vlq == 0 {
+            break;
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    // ==================== Source Map Tests ====================
+
+    #[test]
+    fn test_encode_vlq() {
+        let cases = [
+            (0, "A"),
+            (1, "C"),
+            (-1, "D"),
+            (15, "e"),
+            (16, "gB"),
+            (123, "2H"),
+        ];
+        for (value, expected) in cases {
+            let mut out = String::new();
+            encode_vlq(&mut out, value);
+            assert_eq!(out, expected, "value {}", value);
+        }
+    }
+
+    #[test]
+    fn test_encode_mappings() {
+        let mappings = [
+            Mapping {
+                generated_line: 1,
+                generated_column: 0,
+                source_line: 0,
+                source_column: 0,
+            },
+            Mapping {
+                generated_line: 1,
+                generated_column: 4,
+                source_line: 0,
+                source_column: 4,
+            },
+            Mapping {
+                generated_line: 3,
+                generated_column: 2,
+                source_line: 2,
+                source_column: 2,
+            },
+        ];
+        assert_eq!(encode_mappings(&mappings), ";AAAA,IAAI;;EAEF");
+    }
+
+    #[test]
+    fn test_source_map_points_at_statements() {
+        let source = "ken x = 1\ngin x > 0 {\n    blether x\n}";
+        let program = crate::parser::parse(source).unwrap();
+        let mut compiler = Compiler::new();
+        let (js, map) = compiler
+            .compile_with_source_map(&program, "wee.braw", source)
+            .unwrap();
+
+        let js_lines: Vec<&str> = js.lines().collect();
+        let expected = [(0, "let x"), (1, "if ("), (2, "blether(x)")];
+        assert_eq!(compiler.mappings.len(), expected.len());
+        for (m, (line, text)) in compiler.mappings.iter().zip(expected) {
+            assert_eq!(m.source_line, line);
+            assert!(
+                js_lines[m.generated_line][m.generated_column..].starts_with(text),
+                "line {} should start wi' {:?}: {:?}",
+                m.generated_line,
+                text,
+                js_lines[m.generated_line]
+            );
+        }
+        assert_eq!(compiler.mappings[2].source_column, 4);
+
+        let map: serde_json::Value = serde_json::from_str(&map).unwrap();
+        assert_eq!(map["version"], 3);
+        assert_eq!(map["sources"][0], "wee.braw");
+        assert_eq!(map["sourcesContent"][0], source);
+    }
+
+    #[test]
+    fn test_plain_compile_records_no_mappings() {
+        let program = crate::parser::parse("ken x = 1").unwrap();
+        let mut compiler = Compiler::new();
+        compiler.compile(&program).unwrap();
+        assert!(compiler.mappings.is_empty());
+    }
+
     // ==================== Basic Tests ====================
 
     #[test]
@@ -1448,6 +2220,24 @@ gin x > 5 {
         assert!(result.contains("for (const i of"));
     }
 
+    #[test]
+    fn test_top_level_names_shadow_the_runtime() {
+        let result = compile("ken sum = 0\ndae max(a) { gie a }").unwrap();
+        let globals = result
+            .lines()
+            .find(|line| line.ends_with("} = __havers;"))
+            .unwrap();
+        assert!(!globals.contains(" sum,"), "{globals}");
+        assert!(!globals.contains(" max,"), "{globals}");
+        assert!(globals.contains(" average,"), "{globals}");
+    }
+
+    #[test]
+    fn test_for_key_value_compile() {
+        let result = compile("fer k, v in d { blether k }").unwrap();
+        assert!(result.contains("for (const [k, v] of __havers.fer_pairs(d))"));
+    }
+
     #[test]
     fn test_break_compile() {
         let result = compile("whiles aye { brak }").unwrap();
@@ -1487,10 +2277,28 @@ kin Animal {
         )
         .unwrap();
         assert!(result.contains("class Animal"));
-        assert!(result.contains("constructor(name)"));
+        assert!(result.contains("constructor(...args)"));
+        assert!(result.contains("init(name)"));
+        assert!(result.contains("speak()"));
         assert!(result.contains("this.name"));
     }
 
+    #[test]
+    fn test_class_construction_uses_new() {
+        let result = compile(
+            r#"
+kin beastie {
+    dae init(name) {
+        masel.name = name
+    }
+}
+ken b = beastie("Nessie")
+"#,
+        )
+        .unwrap();
+        assert!(result.contains("new beastie(\"Nessie\")"));
+    }
+
     #[test]
     fn test_class_inheritance() {
         let result = compile(
@@ -1502,6 +2310,10 @@ kin Animal {
         )
         .unwrap();
         assert!(result.contains("class Dog extends Animal"));
+        assert!(result.contains("bark()"));
+        // Subclasses inherit the base constructor rather than needing super()
+        let class_js = &result[result.find("class Dog").unwrap()..];
+        assert!(!class_js.contains("constructor"));
     }
 
     // ==================== Struct Tests ====================
@@ -1666,6 +2478,15 @@ kin Animal {
         assert!(result.contains("const [first, ...rest] = "));
     }
 
+    #[test]
+    fn test_tuple_destructure_checks_the_count() {
+        let result = compile("dae f() { gie 1, 2 }\nken a, b = f()").unwrap();
+        assert!(result.contains("return [1, 2];"), "{}", result);
+        assert!(result.contains("const [a, b] = ((t) => { if (t.length !== 2)"));
+        let result = compile("ken a, ...b = [1, 2]").unwrap();
+        assert!(result.contains("if (t.length < 1)"));
+    }
+
     #[test]
     fn test_destructure_ignore() {
         let result = compile("ken [_, second, _] = [1, 2, 3]").unwrap();
@@ -1725,13 +2546,13 @@ kin Animal {
     #[test]
     fn test_index_compile() {
         let result = compile("list[0]").unwrap();
-        assert!(result.contains("list[0]"));
+        assert!(result.contains("__havers.index(list, 0)"));
     }
 
     #[test]
     fn test_index_set_compile() {
         let result = compile("ken list = [1,2,3]\nlist[0] = 99").unwrap();
-        assert!(result.contains("list[0] = 99"));
+        assert!(result.contains("__havers.index_set(list, 0, 99)"));
     }
 
     #[test]
@@ -1765,6 +2586,12 @@ kin Animal {
         assert!(result.contains("}"));
     }
 
+    #[test]
+    fn test_dict_compile_computed_key() {
+        let result = compile("ken k = \"a\"\nken d = {k: 1, \"b\": {\"c\": 2}}").unwrap();
+        assert!(result.contains("{[k]: 1, \"b\": {\"c\": 2}}"));
+    }
+
     #[test]
     fn test_range_compile() {
         let result = compile("0..10").unwrap();
@@ -1774,7 +2601,13 @@ kin Animal {
     #[test]
     fn test_range_compile_inclusive() {
         let result = compile("0..=10").unwrap();
-        assert!(result.contains("__havers.range(0, (10 + 1))"));
+        assert!(result.contains("__havers.range(0, 10, 1, true)"));
+    }
+
+    #[test]
+    fn test_range_compile_with_step() {
+        let result = compile("10..0 by 2").unwrap();
+        assert!(result.contains("__havers.range(10, 0, 2)"));
     }
 
     #[test]
@@ -1813,6 +2646,14 @@ kin Animal {
         assert!(result.contains("`cost: \\$5`"));
     }
 
+    #[test]
+    fn test_fstring_template_literal_escapes() {
+        let source = "ken name = \"Jo\"\nken age = 9\nf\"Hi {name}, `${{age}}` \\\\ {age}\"";
+        let result = compile(source).unwrap();
+        assert!(result.contains("`Hi ${name}, \\`\\${age}\\` \\\\ ${age}`"));
+        assert!(!result.contains("\"Hi \" +"));
+    }
+
     #[test]
     fn test_spread_compile() {
         let result = compile("[1, ...[2, 3]]").unwrap();
@@ -1960,4 +2801,45 @@ keek 1 {
         assert!(result.contains("blether:"));
         assert!(result.contains("soond_stairt"));
     }
+
+    #[test]
+    fn test_math_runtime_uses_math_calls() {
+        let result = compile("blether pow(2, 3) + fmod(7, 3) + sqrt(16)").unwrap();
+        assert!(result.contains("pow: Math.pow,"));
+        assert!(result.contains("return Math.sqrt(x);"));
+        assert!(result.contains("return a % b;"));
+        assert!(result.contains("round, sqrt, pow, fmod,"));
+    }
+
+    #[test]
+    fn test_list_mutation_runtime_uses_splice() {
+        let result = compile("ken l = [1]\nstick_in(l, 0, 0)\ntak_oot(l, 1)").unwrap();
+        assert!(result.contains("arr.splice(pos, 0, val)"));
+        assert!(result.contains("arr.splice(pos, 1)[0]"));
+        assert!(result.contains("whaur: (arr, val) => arr.indexOf(val)"));
+        assert!(result.contains("stick_in(l, 0, 0)"));
+    }
+
+    #[test]
+    fn test_bring_compiles_tae_require() {
+        let result = compile("bring \"lib/maths\"\nbring add, sub frae \"lib/maths\"").unwrap();
+        assert!(result.contains("const maths = require('lib/maths');"));
+        assert!(result.contains("const { add, sub } = require('lib/maths');"));
+    }
+
+    #[test]
+    fn test_format_runtime_helper() {
+        let result = compile("blether format(\"{:5.2}|\", 3.14159)").unwrap();
+        assert!(result.contains("format: (template, ...args) =>"));
+        assert!(result.contains("fmt_float, format, split"));
+        assert!(result.contains("text.padStart(width)"));
+    }
+
+    #[test]
+    fn test_in_operator_asks_the_container() {
+        let result = compile("blether 2 in xs\nblether 2 nae in xs").unwrap();
+        assert!(result.contains("__havers.contains(xs, 2)"));
+        assert!(result.contains("!__havers.contains(xs, 2)"));
+        assert!(result.contains("container instanceof Set"));
+    }
 }
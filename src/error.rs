@@ -1,5 +1,59 @@
 use thiserror::Error;
 
+use crate::token::{Token, TokenKind};
+
+/// Exactly where an error sits in the source, from its first character to
+/// just past its last. Lines and columns count from 1, columns in characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ErrorSpan {
+    pub start_line: usize,
+    pub start_col: usize,
+    pub end_line: usize,
+    pub end_col: usize,
+}
+
+impl ErrorSpan {
+    pub fn new(start_line: usize, start_col: usize, end_line: usize, end_col: usize) -> Self {
+        ErrorSpan {
+            start_line,
+            start_col,
+            end_line,
+            end_col,
+        }
+    }
+
+    /// The stretch of source a token covers - always at least one column wide,
+    /// so there's something to underline even for the end of the file
+    pub fn of_token(token: &Token) -> Self {
+        let start_col = token.column.max(1);
+        let (mut end_line, mut end_col) = (token.line, start_col);
+        if token.kind != TokenKind::Newline {
+            for ch in token.lexeme.chars() {
+                if ch == '\n' {
+                    end_line += 1;
+                    end_col = 1;
+                } else {
+                    end_col += 1;
+                }
+            }
+        }
+        if (end_line, end_col) == (token.line, start_col) {
+            end_col += 1;
+        }
+        ErrorSpan::new(token.line, start_col, end_line, end_col)
+    }
+
+    /// From the start of this span to the end of `other`
+    pub fn to(self, other: ErrorSpan) -> Self {
+        ErrorSpan::new(
+            self.start_line,
+            self.start_col,
+            other.end_line,
+            other.end_col,
+        )
+    }
+}
+
 /// Scots error messages - gie the user a guid tellin' aff!
 #[derive(Error, Debug, Clone, PartialEq)]
 #[allow(dead_code)]
@@ -9,6 +63,7 @@ pub enum HaversError {
         lexeme: String,
         line: usize,
         column: usize,
+        span: Option<ErrorSpan>,
     },
 
     #[error("Haud yer wheesht! Unexpected '{found}' at line {line} - ah wis expectin' {expected}")]
@@ -16,6 +71,7 @@ pub enum HaversError {
         expected: String,
         found: String,
         line: usize,
+        span: Option<ErrorSpan>,
     },
 
     #[error("Awa' an bile yer heid! '{name}' hasnae been defined yet at line {line}")]
@@ -49,7 +105,11 @@ pub enum HaversError {
     FileError { path: String, reason: String },
 
     #[error("Yer code's a richt guddle! Parser gave up at line {line}: {message}")]
-    ParseError { message: String, line: usize },
+    ParseError {
+        message: String,
+        line: usize,
+        span: Option<ErrorSpan>,
+    },
 
     #[error("Jings! Something went awfy wrang: {0}")]
     InternalError(String),
@@ -166,9 +226,207 @@ pub enum HaversError {
 
     #[error("Hurled at line {line}: {message}")]
     UserError { message: String, line: usize },
+
+    /// Raised by `boak(msg)` - a deliberate abort that `hae_a_bash` will not catch
+    #[error("Boak! The program gied up at line {line}: {message}")]
+    Boak { message: String, line: usize },
+
+    /// An error that escaped out of function calls, with the calls that led to it
+    #[error("{error}")]
+    WithBacktrace {
+        error: Box<HaversError>,
+        backtrace: String,
+    },
+
+    /// An error about a name that's a near miss for a known one
+    #[error("{error}. Did ye mean `{suggestion}`?")]
+    DidYeMean {
+        error: Box<HaversError>,
+        suggestion: String,
+    },
+}
+
+/// Broad categories of error, so tools can tell them apart without
+/// string-matching the (rather colourful) messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// The lexer could not make sense of the characters
+    LexError,
+    /// The source did not follow the grammar
+    ParseError,
+    /// A name that was not defined, or was defined twice
+    NameError,
+    /// A value of the wrong type for the operation
+    TypeError,
+    /// An index outside a list or string
+    IndexError,
+    /// A dictionary key that is not there
+    KeyError,
+    /// Division by zero, overflow and other number trouble
+    ArithmeticError,
+    /// A value of the right type but the wrong content (regex, JSON, format)
+    ValueError,
+    /// A failed `mak_siccar`
+    AssertionError,
+    /// Reading or writing files
+    IoError,
+    /// Modules that cannot be found or import each other
+    ImportError,
+    /// Ran out of stack, time or memory
+    ResourceError,
+    /// Raised on purpose by the program (`hurl`, `boak`)
+    UserError,
+    /// The compiler backends could not produce code
+    CompileError,
+    /// A bug in mdhavers itself
+    InternalError,
+    /// Any other failure while the program is running
+    RuntimeError,
+}
+
+impl ErrorKind {
+    /// The kind's name, e.g. `"NameError"`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorKind::LexError => "LexError",
+            ErrorKind::ParseError => "ParseError",
+            ErrorKind::NameError => "NameError",
+            ErrorKind::TypeError => "TypeError",
+            ErrorKind::IndexError => "IndexError",
+            ErrorKind::KeyError => "KeyError",
+            ErrorKind::ArithmeticError => "ArithmeticError",
+            ErrorKind::ValueError => "ValueError",
+            ErrorKind::AssertionError => "AssertionError",
+            ErrorKind::IoError => "IoError",
+            ErrorKind::ImportError => "ImportError",
+            ErrorKind::ResourceError => "ResourceError",
+            ErrorKind::UserError => "UserError",
+            ErrorKind::CompileError => "CompileError",
+            ErrorKind::InternalError => "InternalError",
+            ErrorKind::RuntimeError => "RuntimeError",
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
 impl HaversError {
+    /// Which broad category this error falls into
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            HaversError::UnkentToken { .. }
+            | HaversError::UnterminatedString { .. }
+            | HaversError::InvalidNumber { .. } => ErrorKind::LexError,
+            HaversError::UnexpectedToken { .. }
+            | HaversError::ParseError { .. }
+            | HaversError::DuplicateKey { .. }
+            | HaversError::BreakOutsideLoop { .. }
+            | HaversError::ContinueOutsideLoop { .. }
+            | HaversError::ReturnOutsideFunction { .. } => ErrorKind::ParseError,
+            HaversError::UndefinedVariable { .. }
+            | HaversError::AlreadyDefined { .. }
+            | HaversError::UndefinedProperty { .. }
+            | HaversError::PrivateMemberAccess { .. } => ErrorKind::NameError,
+            HaversError::TypeError { .. }
+            | HaversError::NotCallable { .. }
+            | HaversError::WrongArity { .. }
+            | HaversError::NotAnObject { .. }
+            | HaversError::NotAList { .. }
+            | HaversError::NotADict { .. }
+            | HaversError::NotIterable { .. }
+            | HaversError::IncomparableTypes { .. } => ErrorKind::TypeError,
+            HaversError::IndexOutOfBounds { .. }
+            | HaversError::NegativeIndexOutOfBounds { .. }
+            | HaversError::EmptyCollection { .. } => ErrorKind::IndexError,
+            HaversError::KeyNotFound { .. } => ErrorKind::KeyError,
+            HaversError::DivisionByZero { .. }
+            | HaversError::IntegerOverflow { .. }
+            | HaversError::InvalidNumberOperation { .. } => ErrorKind::ArithmeticError,
+            HaversError::InvalidRegex { .. }
+            | HaversError::FormatError { .. }
+            | HaversError::JsonError { .. } => ErrorKind::ValueError,
+            HaversError::AssertionFailed { .. } => ErrorKind::AssertionError,
+            HaversError::FileError { .. } => ErrorKind::IoError,
+            HaversError::ModuleNotFound { .. } | HaversError::CircularImport { .. } => {
+                ErrorKind::ImportError
+            }
+            HaversError::StackOverflow { .. }
+            | HaversError::InfiniteLoop { .. }
+            | HaversError::ExecutionTimeout { .. }
+            | HaversError::OutOfMemory { .. } => ErrorKind::ResourceError,
+            HaversError::UserError { .. } | HaversError::Boak { .. } => ErrorKind::UserError,
+            HaversError::CompileError(_) => ErrorKind::CompileError,
+            HaversError::InternalError(_) => ErrorKind::InternalError,
+            HaversError::InvalidOperation { .. }
+            | HaversError::PatternError { .. }
+            | HaversError::NonExhaustiveMatch { .. }
+            | HaversError::ImmutableVariable { .. } => ErrorKind::RuntimeError,
+            HaversError::WithBacktrace { error, .. } | HaversError::DidYeMean { error, .. } => {
+                error.kind()
+            }
+        }
+    }
+
+    /// The error's code, e.g. `"E0003"`, for looking it up with `mdhavers explain`
+    pub fn code(&self) -> &'static str {
+        match self {
+            HaversError::UnkentToken { .. } => "E0001",
+            HaversError::UnexpectedToken { .. } => "E0002",
+            HaversError::UndefinedVariable { .. } => "E0003",
+            HaversError::DivisionByZero { .. } => "E0004",
+            HaversError::TypeError { .. } => "E0005",
+            HaversError::NotCallable { .. } => "E0006",
+            HaversError::WrongArity { .. } => "E0007",
+            HaversError::IndexOutOfBounds { .. } => "E0008",
+            HaversError::FileError { .. } => "E0009",
+            HaversError::ParseError { .. } => "E0010",
+            HaversError::InternalError(_) => "E0011",
+            HaversError::CompileError(_) => "E0012",
+            HaversError::BreakOutsideLoop { .. } => "E0013",
+            HaversError::ContinueOutsideLoop { .. } => "E0014",
+            HaversError::StackOverflow { .. } => "E0015",
+            HaversError::ModuleNotFound { .. } => "E0016",
+            HaversError::UnterminatedString { .. } => "E0017",
+            HaversError::InvalidNumber { .. } => "E0018",
+            HaversError::AlreadyDefined { .. } => "E0019",
+            HaversError::NotAnObject { .. } => "E0020",
+            HaversError::UndefinedProperty { .. } => "E0021",
+            HaversError::InfiniteLoop { .. } => "E0022",
+            HaversError::NotAList { .. } => "E0023",
+            HaversError::NotADict { .. } => "E0024",
+            HaversError::KeyNotFound { .. } => "E0025",
+            HaversError::InvalidOperation { .. } => "E0026",
+            HaversError::CircularImport { .. } => "E0027",
+            HaversError::AssertionFailed { .. } => "E0028",
+            HaversError::ReturnOutsideFunction { .. } => "E0029",
+            HaversError::NotIterable { .. } => "E0030",
+            HaversError::PatternError { .. } => "E0031",
+            HaversError::IntegerOverflow { .. } => "E0032",
+            HaversError::NegativeIndexOutOfBounds { .. } => "E0033",
+            HaversError::EmptyCollection { .. } => "E0034",
+            HaversError::InvalidRegex { .. } => "E0035",
+            HaversError::FormatError { .. } => "E0036",
+            HaversError::JsonError { .. } => "E0037",
+            HaversError::IncomparableTypes { .. } => "E0038",
+            HaversError::InvalidNumberOperation { .. } => "E0039",
+            HaversError::NonExhaustiveMatch { .. } => "E0040",
+            HaversError::DuplicateKey { .. } => "E0041",
+            HaversError::ExecutionTimeout { .. } => "E0042",
+            HaversError::OutOfMemory { .. } => "E0043",
+            HaversError::PrivateMemberAccess { .. } => "E0044",
+            HaversError::ImmutableVariable { .. } => "E0045",
+            HaversError::UserError { .. } => "E0046",
+            HaversError::Boak { .. } => "E0047",
+            HaversError::WithBacktrace { error, .. } | HaversError::DidYeMean { error, .. } => {
+                error.code()
+            }
+        }
+    }
+
     pub fn line(&self) -> Option<usize> {
         match self {
             HaversError::UnkentToken { line, .. } => Some(*line),
@@ -212,6 +470,24 @@ impl HaversError {
             HaversError::PrivateMemberAccess { line, .. } => Some(*line),
             HaversError::ImmutableVariable { line, .. } => Some(*line),
             HaversError::UserError { line, .. } => Some(*line),
+            HaversError::Boak { line, .. } => Some(*line),
+            HaversError::WithBacktrace { error, .. } | HaversError::DidYeMean { error, .. } => {
+                error.line()
+            }
+            _ => None,
+        }
+    }
+
+    /// Exactly where in the source the error is, when the lexer or parser
+    /// knew it. Runtime errors only ever have a line.
+    pub fn span(&self) -> Option<ErrorSpan> {
+        match self {
+            HaversError::UnkentToken { span, .. }
+            | HaversError::UnexpectedToken { span, .. }
+            | HaversError::ParseError { span, .. } => *span,
+            HaversError::WithBacktrace { error, .. } | HaversError::DidYeMean { error, .. } => {
+                error.span()
+            }
             _ => None,
         }
     }
@@ -222,19 +498,23 @@ impl HaversError {
                 lexeme,
                 line: 0,
                 column,
+                span,
             } => HaversError::UnkentToken {
                 lexeme,
                 line,
                 column,
+                span,
             },
             HaversError::UnexpectedToken {
                 expected,
                 found,
                 line: 0,
+                span,
             } => HaversError::UnexpectedToken {
                 expected,
                 found,
                 line,
+                span,
             },
             HaversError::UndefinedVariable { name, line: 0 } => {
                 HaversError::UndefinedVariable { name, line }
@@ -258,9 +538,15 @@ impl HaversError {
                 size,
                 line: 0,
             } => HaversError::IndexOutOfBounds { index, size, line },
-            HaversError::ParseError { message, line: 0 } => {
-                HaversError::ParseError { message, line }
-            }
+            HaversError::ParseError {
+                message,
+                line: 0,
+                span,
+            } => HaversError::ParseError {
+                message,
+                line,
+                span,
+            },
             HaversError::BreakOutsideLoop { line: 0 } => HaversError::BreakOutsideLoop { line },
             HaversError::ContinueOutsideLoop { line: 0 } => {
                 HaversError::ContinueOutsideLoop { line }
@@ -333,9 +619,96 @@ impl HaversError {
                 HaversError::ImmutableVariable { name, line }
             }
             HaversError::UserError { message, line: 0 } => HaversError::UserError { message, line },
+            HaversError::Boak { message, line: 0 } => HaversError::Boak { message, line },
+            HaversError::WithBacktrace { error, backtrace } => HaversError::WithBacktrace {
+                error: Box::new(error.with_line_if_zero(line)),
+                backtrace,
+            },
+            HaversError::DidYeMean { error, suggestion } => HaversError::DidYeMean {
+                error: Box::new(error.with_line_if_zero(line)),
+                suggestion,
+            },
+            other => other,
+        }
+    }
+
+    /// The formatted call stack, if the error came out of a function call
+    pub fn backtrace(&self) -> Option<&str> {
+        match self {
+            HaversError::WithBacktrace { backtrace, .. } => Some(backtrace),
+            _ => None,
+        }
+    }
+
+    /// The error itself, with any backtrace or hint stripped off
+    pub fn root(&self) -> &HaversError {
+        match self {
+            HaversError::WithBacktrace { error, .. } | HaversError::DidYeMean { error, .. } => {
+                error.root()
+            }
             other => other,
         }
     }
+
+    /// Add a "did ye mean" hint for whichever of `candidates` is closest to
+    /// `name`, unless none is close enough or `get_error_suggestion`
+    /// already has advice for this error
+    pub fn with_did_ye_mean<'a>(
+        self,
+        name: &str,
+        candidates: impl IntoIterator<Item = &'a str>,
+    ) -> Self {
+        if get_error_suggestion(&self).is_some() {
+            return self;
+        }
+        match closest_name(name, candidates) {
+            Some(suggestion) => HaversError::DidYeMean {
+                error: Box::new(self),
+                suggestion: suggestion.to_string(),
+            },
+            None => self,
+        }
+    }
+}
+
+/// The most edits a name can be from a known one and still get suggested
+pub const MAX_SUGGESTION_DISTANCE: usize = 2;
+
+/// How many single-character inserts, deletes or swaps turn `a` into `b`
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                diagonal
+            } else {
+                1 + diagonal.min(above).min(row[j])
+            };
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+/// The candidate closest to `name`, if it's within `MAX_SUGGESTION_DISTANCE`
+/// edits. Short names get less slack (one edit per three characters), so
+/// `x` does not get `y` suggested. Ties go to the first alphabetically.
+pub fn closest_name<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    let limit = (name.chars().count() / 3).min(MAX_SUGGESTION_DISTANCE);
+    candidates
+        .into_iter()
+        .filter(|candidate| *candidate != name)
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= limit)
+        .min()
+        .map(|(_, candidate)| candidate)
 }
 
 /// Scots phrases fer random error decoration
@@ -486,7 +859,7 @@ pub type HaversResult<T> = Result<T, HaversError>;
 
 /// Get a helpful suggestion fer common errors
 pub fn get_error_suggestion(error: &HaversError) -> Option<&'static str> {
-    match error {
+    match error.root() {
         HaversError::UndefinedVariable { name, .. } => {
             // Check for common misspellings of keywords
             let name_lower = name.to_lowercase();
@@ -498,6 +871,7 @@ pub fn get_error_suggestion(error: &HaversError) -> Option<&'static str> {
                 "let" | "var" | "const" => Some("💡 Did ye mean 'ken'? Use 'ken x = 42' tae declare variables!"),
                 "print" | "println" | "console" | "echo" => Some("💡 Did ye mean 'blether'? That's how we print in mdhavers!"),
                 "return" => Some("💡 Did ye mean 'gie'? Use 'gie value' tae return fae a function!"),
+                "yield" => Some("💡 Did ye mean 'gie_back'? Use 'gie_back value' tae hand oot a value fae a generator!"),
                 "function" | "func" | "fn" | "def" => Some("💡 Did ye mean 'dae'? Use 'dae name() { }' tae define functions!"),
                 "null" | "nil" | "none" | "undefined" => Some("💡 Did ye mean 'naething'? That's oor word fer null!"),
                 "class" => Some("💡 Did ye mean 'kin'? Use 'kin ClassName { }' tae define classes!"),
@@ -514,20 +888,21 @@ pub fn get_error_suggestion(error: &HaversError) -> Option<&'static str> {
                 "map" => Some("💡 Did ye mean 'gaun'? Use 'gaun(list, |x| x * 2)' tae map!"),
                 "filter" => Some("💡 Did ye mean 'sieve'? Use 'sieve(list, |x| x > 0)' tae filter!"),
                 "reduce" | "fold" => Some("💡 Did ye mean 'tumble'? Use 'tumble(list, init, |acc, x| acc + x)'!"),
-                "length" | "size" | "count" => Some("💡 Did ye mean 'len'? Use 'len(list)' tae get the length!"),
+                "length" | "size" => Some("💡 Did ye mean 'len'? Use 'len(list)' tae get the length!"),
                 "type" | "typeof" => Some("💡 Did ye mean 'whit_kind'? Use 'whit_kind(x)' tae get the type!"),
                 "str" | "string" | "tostring" => Some("💡 Did ye mean 'tae_string'? Use 'tae_string(x)' tae convert!"),
                 "int" | "integer" | "toint" => Some("💡 Did ye mean 'tae_int'? Use 'tae_int(x)' tae convert!"),
                 "push" | "append" | "add" => Some("💡 Did ye mean 'shove'? Use 'shove(list, item)' tae add tae a list!"),
                 "pop" | "remove" => Some("💡 Did ye mean 'yank'? Use 'yank(list)' tae remove fae a list!"),
+                "insert" | "splice" => Some("💡 Did ye mean 'stick_in'? Use 'stick_in(list, index, item)' tae insert intae a list!"),
                 "input" | "read" | "readline" => Some("💡 Did ye mean 'speir'? Use 'speir(\"prompt\")' tae get input!"),
                 "struct" => Some("💡 Did ye mean 'thing'? Use 'thing Name { fields }' fer structs!"),
                 "trim" => Some("💡 Did ye mean 'wheesht'? Use 'wheesht(str)' tae trim whitespace!"),
                 "slice" | "substring" | "substr" => Some("💡 Did ye mean 'scran'? Use 'scran(str, start, end)' tae slice!"),
-                "find" | "indexof" => Some("💡 Did ye mean 'index_of'? Use 'index_of(str, substr)' tae find!"),
+                "indexof" => Some("💡 Did ye mean 'index_of'? Use 'index_of(str, substr)' tae find!"),
                 "random" | "rand" => Some("💡 Did ye mean 'jammy'? Use 'jammy(min, max)' fer random numbers!"),
-                "sleep" | "wait" | "delay" => Some("💡 Did ye mean 'snooze'? Use 'snooze(ms)' tae pause!"),
-                "now" | "time" | "timestamp" => Some("💡 Did ye mean 'noo' or 'the_noo'? That's how we get the time!"),
+                "wait" | "delay" => Some("💡 Did ye mean 'snooze'? Use 'snooze(ms)' tae pause!"),
+                "time" | "timestamp" => Some("💡 Did ye mean 'noo' or 'the_noo'? That's how we get the time!"),
                 "exit" | "quit" => Some("💡 Did ye mean 'awa'? Use 'awa(code)' tae exit the program!"),
                 "throw" | "raise" => Some("💡 Did ye mean 'fling'? Use 'fling \"error message\"' tae throw errors!"),
                 "lambda" | "arrow" => Some("💡 Use '|x| x * 2' fer lambdas - nae need fer a keyword!"),
@@ -656,8 +1031,471 @@ pub fn get_error_suggestion(error: &HaversError) -> Option<&'static str> {
     }
 }
 
+/// The long-form help for an error code, as shown by `mdhavers explain`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ErrorExplanation {
+    pub code: &'static str,
+    pub title: &'static str,
+    pub description: &'static str,
+    pub causes: &'static [&'static str],
+    /// A short program that does the job right
+    pub example: &'static str,
+}
+
+impl ErrorExplanation {
+    /// The whole explanation, ready to print
+    pub fn render(&self) -> String {
+        let mut out = format!(
+            "{}: {}\n\n{}\n\nCommon causes:\n",
+            self.code, self.title, self.description
+        );
+        for cause in self.causes {
+            out.push_str(&format!("  - {}\n", cause));
+        }
+        out.push_str("\nHere's how it should look:\n\n");
+        for line in self.example.lines() {
+            out.push_str(&format!("    {}\n", line));
+        }
+        out
+    }
+}
+
+/// Look up the help for a code like `E0003` (upper or lower case)
+pub fn explain_error(code: &str) -> Option<&'static ErrorExplanation> {
+    let code = code.trim().to_ascii_uppercase();
+    ERROR_EXPLANATIONS
+        .iter()
+        .find(|explanation| explanation.code == code)
+}
+
+/// Every error code, in order. `HaversError::code` returns these.
+pub static ERROR_EXPLANATIONS: &[ErrorExplanation] = &[
+    ErrorExplanation {
+        code: "E0001",
+        title: "Unkent character",
+        description: "The lexer found a character that isnae part o' the language, sae it couldnae turn the source intae tokens.",
+        causes: &[
+            "A stray symbol like `@`, `$` or a backtick",
+            "Curly quotes pasted in fae a word processor instead o' plain `\"`",
+        ],
+        example: "ken price = 5\nblether \"Cost: \" + tae_string(price)",
+    },
+    ErrorExplanation {
+        code: "E0002",
+        title: "Unexpected token",
+        description: "The parser wis partway through a statement an' found somethin' that cannae come next.",
+        causes: &[
+            "A missin' closin' `)`, `]` or `}`",
+            "A missin' comma atween list items or arguments",
+            "Two statements on the same line wi'oot a `;` atween them",
+        ],
+        example: "ken scores = [1, 2, 3]\nblether len(scores)",
+    },
+    ErrorExplanation {
+        code: "E0003",
+        title: "Undefined variable",
+        description: "A name wis used that hasnae been declared wi' `ken`, `dae` or `kin` in ony scope that can see it.",
+        causes: &[
+            "A typo in the name",
+            "Usin' a variable afore the `ken` that declares it",
+            "A word fae anither language, like `print` or `true`, instead o' `blether` or `aye`",
+        ],
+        example: "ken name = \"Morag\"\nblether name",
+    },
+    ErrorExplanation {
+        code: "E0004",
+        title: "Division by zero",
+        description: "A `/` or `%` had zero on the richt-hand side, an' there's nae sensible answer tae gie back.",
+        causes: &[
+            "Averagin' an empty list",
+            "A count or divisor that can be zero but isnae checked",
+        ],
+        example: "ken total = 10\nken count = 0\ngin count != 0 {\n    blether total / count\n} ither {\n    blether \"Naethin' tae average\"\n}",
+    },
+    ErrorExplanation {
+        code: "E0005",
+        title: "Type error",
+        description: "An operation got a value o' a type it cannae work wi', like subtractin' a string fae a number.",
+        causes: &[
+            "Arithmetic on a number that's still a string fae `speir` or a file",
+            "Passin' the wrang kind o' value tae a built-in",
+        ],
+        example: "ken answer = \"41\"\nblether tae_int(answer) + 1",
+    },
+    ErrorExplanation {
+        code: "E0006",
+        title: "No' a function",
+        description: "Somethin' wis called wi' `(...)` but it isnae a function, class or built-in.",
+        causes: &[
+            "A variable wi' the same name as a function hidin' it",
+            "Callin' the result o' a function that gied back a plain value",
+        ],
+        example: "ken greet = |name| \"Hullo, \" + name\nblether greet(\"Jock\")",
+    },
+    ErrorExplanation {
+        code: "E0007",
+        title: "Wrang number o' arguments",
+        description: "A function wis called wi' mair or fewer arguments than it taks.",
+        causes: &[
+            "Forgettin' an argument, or passin' an extra ane",
+            "Callin' a method wi'oot its arguments",
+        ],
+        example: "dae add(a, b) {\n    gie a + b\n}\nblether add(1, 2)",
+    },
+    ErrorExplanation {
+        code: "E0008",
+        title: "Index oot o' bounds",
+        description: "A list or string wis indexed past its end. Indices start at 0, sae the last ane is `len(x) - 1`.",
+        causes: &[
+            "An off-by-one loop that runs tae `len(x)` instead o' `len(x) - 1`",
+            "Indexin' an empty list",
+        ],
+        example: "ken items = [\"neep\", \"tattie\"]\nken i = 2\ngin i < len(items) {\n    blether items[i]\n}",
+    },
+    ErrorExplanation {
+        code: "E0009",
+        title: "File error",
+        description: "A file couldnae be read or written.",
+        causes: &[
+            "A path that's relative tae a different directory than ye think",
+            "A file that doesnae exist yet, or isnae readable",
+        ],
+        example: "gin file_exists(\"data.txt\") {\n    blether read_file(\"data.txt\")\n}",
+    },
+    ErrorExplanation {
+        code: "E0010",
+        title: "Parse error",
+        description: "The parser couldnae make sense o' the program's structure.",
+        causes: &[
+            "A block that's missin' its `{` or `}`",
+            "A keyword used whaur an expression belongs",
+        ],
+        example: "gin 1 < 2 {\n    blether \"aye\"\n}",
+    },
+    ErrorExplanation {
+        code: "E0011",
+        title: "Internal error",
+        description: "Somethin' went wrang inside mdhavers itsel'. This is a bug, no' a mistake in yer program.",
+        causes: &["A bug in mdhavers - please report it wi' the program that triggered it"],
+        example: "# Nae fix needed in yer code - report the bug\nblether \"Help ma boab\"",
+    },
+    ErrorExplanation {
+        code: "E0012",
+        title: "Compile error",
+        description: "A compiler backend (JavaScript, WebAssembly or LLVM) couldnae turn the program intae code.",
+        causes: &[
+            "A feature the backend doesnae support yet",
+            "A built-in that only exists in the interpreter",
+        ],
+        example: "# Run it wi' the interpreter instead:\n#   mdhavers run program.braw\nblether \"Awa' an' run\"",
+    },
+    ErrorExplanation {
+        code: "E0013",
+        title: "`brak` ootside a loop",
+        description: "`brak` jumps oot o' the nearest `whiles` or `fer` loop, sae it cannae be used ootside ane.",
+        causes: &[
+            "A `brak` in a function called fae a loop, rather than in the loop itsel'",
+            "Usin' `brak` tae leave a function - that's `gie`",
+        ],
+        example: "fer i in 1..10 {\n    gin i == 5 {\n        brak\n    }\n    blether i\n}",
+    },
+    ErrorExplanation {
+        code: "E0014",
+        title: "`haud` ootside a loop",
+        description: "`haud` skips tae the next turn o' the nearest `whiles` or `fer` loop, sae it cannae be used ootside ane.",
+        causes: &["A `haud` in a function called fae a loop, rather than in the loop itsel'"],
+        example: "fer i in 1..6 {\n    gin i % 2 == 0 {\n        haud\n    }\n    blether i\n}",
+    },
+    ErrorExplanation {
+        code: "E0015",
+        title: "Stack overflow",
+        description: "Function calls nested sae deep the interpreter ran oot o' room.",
+        causes: &[
+            "A recursive function wi' nae base case",
+            "A base case that's never reached",
+        ],
+        example: "dae factorial(n) {\n    gin n <= 1 {\n        gie 1\n    }\n    gie n * factorial(n - 1)\n}\nblether factorial(5)",
+    },
+    ErrorExplanation {
+        code: "E0016",
+        title: "Module no' found",
+        description: "A `fetch` named a module that couldnae be found.",
+        causes: &[
+            "A path that's no' relative tae the file daein' the fetch",
+            "A typo in the module name",
+        ],
+        example: "# Fetches lib/maths.braw next tae this file\nfetch \"lib/maths\"",
+    },
+    ErrorExplanation {
+        code: "E0017",
+        title: "Unterminated string",
+        description: "A string wis opened wi' a quote but never closed.",
+        causes: &[
+            "A missin' closin' `\"`",
+            "A quote inside the string that wisnae escaped wi' `\\\"`",
+        ],
+        example: "blether \"She said \\\"aye\\\"\"",
+    },
+    ErrorExplanation {
+        code: "E0018",
+        title: "Invalid number",
+        description: "A number literal couldnae be read.",
+        causes: &[
+            "Mair than ane decimal point, like `1.2.3`",
+            "A number too big tae fit in a 64-bit integer",
+        ],
+        example: "ken price = 1.25\nblether price",
+    },
+    ErrorExplanation {
+        code: "E0019",
+        title: "Already defined",
+        description: "A name wis declared twice whaur only ane declaration is allowed.",
+        causes: &["Usin' `ken` again tae change a variable - jist assign tae it"],
+        example: "ken count = 1\ncount = 2\nblether count",
+    },
+    ErrorExplanation {
+        code: "E0020",
+        title: "No' an object",
+        description: "A property wis read or set on a value that doesnae hae properties.",
+        causes: &[
+            "Usin' `.` on a number, string or `naething`",
+            "A variable that's `naething` because it wisnae set yet",
+        ],
+        example: "kin Dug {\n    dae init(name) {\n        masel.name = name\n    }\n}\nken rex = Dug(\"Rex\")\nblether rex.name",
+    },
+    ErrorExplanation {
+        code: "E0021",
+        title: "Undefined property",
+        description: "An object wis asked fer a property or method it doesnae hae.",
+        causes: &[
+            "A typo in the property name",
+            "A field that's only set in some code paths o' `init`",
+        ],
+        example: "kin Dug {\n    dae init() {\n        masel.legs = 4\n    }\n}\nblether Dug().legs",
+    },
+    ErrorExplanation {
+        code: "E0022",
+        title: "Infinite loop",
+        description: "A loop looked like it wid never finish.",
+        causes: &["A `whiles` condition that nothin' in the body ever changes"],
+        example: "ken i = 0\nwhiles i < 3 {\n    blether i\n    i = i + 1\n}",
+    },
+    ErrorExplanation {
+        code: "E0023",
+        title: "No' a list",
+        description: "An operation that needs a list got some ither kind o' value.",
+        causes: &["Passin' a string or dict tae a list function like `shove`"],
+        example: "ken items = []\nshove(items, 1)\nblether items",
+    },
+    ErrorExplanation {
+        code: "E0024",
+        title: "No' a dict",
+        description: "An operation that needs a dictionary got some ither kind o' value.",
+        causes: &["Passin' a list tae a dict function like `keys`"],
+        example: "ken ages = {\"Morag\": 30}\nblether keys(ages)",
+    },
+    ErrorExplanation {
+        code: "E0025",
+        title: "Key no' found",
+        description: "A dictionary wis asked fer a key it doesnae hae.",
+        causes: &[
+            "A typo in the key",
+            "Readin' a key that's only sometimes set",
+        ],
+        example: "ken ages = {\"Morag\": 30}\nblether dict_get(ages, \"Hamish\", 0)",
+    },
+    ErrorExplanation {
+        code: "E0026",
+        title: "Invalid operation",
+        description: "The values were the richt types but the operation isnae allowed on them.",
+        causes: &[
+            "Usin' an operator on a value that doesnae support it",
+            "Changin' a collection while loopin' ower it",
+        ],
+        example: "ken a = [1, 2]\nken b = [3]\nblether a + b",
+    },
+    ErrorExplanation {
+        code: "E0027",
+        title: "Circular import",
+        description: "Module A fetches B, which fetches A (maybe through ithers), sae neither can finish loadin'.",
+        causes: &["Twa modules that baith need somethin' fae the ither"],
+        example: "# Move whit baith need intae a third module\n# an' fetch that fae each o' them\nfetch \"shared\"",
+    },
+    ErrorExplanation {
+        code: "E0028",
+        title: "Assertion failed",
+        description: "A `mak_siccar` condition wis false, or a test helper like `expeck_error` didnae get whit it wanted.",
+        causes: &[
+            "A bug that the assertion caught - which is its job",
+            "An assertion that's checkin' the wrang thing",
+        ],
+        example: "ken age = 30\nmak_siccar age >= 0, \"Age cannae be negative\"",
+    },
+    ErrorExplanation {
+        code: "E0029",
+        title: "`gie` ootside a function",
+        description: "`gie` hands a value back fae a function, sae it cannae be used at the top level.",
+        causes: &["A `gie` left ower efter movin' code oot o' a function"],
+        example: "dae double(x) {\n    gie x * 2\n}\nblether double(21)",
+    },
+    ErrorExplanation {
+        code: "E0030",
+        title: "No' iterable",
+        description: "A `fer` loop wis gien a value it cannae step through.",
+        causes: &["Loopin' ower a number instead o' a range like `0..n`"],
+        example: "ken n = 3\nfer i in 0..n {\n    blether i\n}",
+    },
+    ErrorExplanation {
+        code: "E0031",
+        title: "Pattern error",
+        description: "A `keek` pattern couldnae be used tae match the value.",
+        causes: &["A pattern that doesnae fit the shape o' the value"],
+        example: "keek 2 {\n    whan 1 -> { blether \"ane\" }\n    whan _ -> { blether \"mony\" }\n}",
+    },
+    ErrorExplanation {
+        code: "E0032",
+        title: "Integer overflow",
+        description: "An integer calculation gied a result too big (or too wee) fer 64 bits.",
+        causes: &[
+            "Multiplyin' big numbers thegither, like a large factorial",
+            "Raisin' a number tae a big power",
+        ],
+        example: "# Floats trade precision fer range\nken big = 2.0 ** 100\nblether big",
+    },
+    ErrorExplanation {
+        code: "E0033",
+        title: "Negative index oot o' range",
+        description: "A negative index counts back fae the end, but this ane went past the start.",
+        causes: &["Usin' `-len(x) - 1` or lower as an index"],
+        example: "ken items = [1, 2, 3]\nblether items[-1]",
+    },
+    ErrorExplanation {
+        code: "E0034",
+        title: "Empty collection",
+        description: "An operation that needs at least ane item got an empty list.",
+        causes: &["Takin' the max, min or first item o' an empty list"],
+        example: "ken items = []\ngin len(items) > 0 {\n    blether max(items)\n}",
+    },
+    ErrorExplanation {
+        code: "E0035",
+        title: "Invalid regex",
+        description: "A regular expression couldnae be compiled.",
+        causes: &[
+            "An unclosed group or bracket",
+            "A special character like `.` or `(` that wisnae escaped",
+        ],
+        example: "blether regex_test(\"a.b\", \"a\\\\.b\")",
+    },
+    ErrorExplanation {
+        code: "E0036",
+        title: "Format error",
+        description: "A format string couldnae be filled in.",
+        causes: &[
+            "An unclosed `{` in an f-string",
+            "Fewer values than placeholders",
+        ],
+        example: "ken name = \"Morag\"\nblether f\"Hullo, {name}!\"",
+    },
+    ErrorExplanation {
+        code: "E0037",
+        title: "JSON error",
+        description: "Text that wis meant tae be JSON couldnae be parsed.",
+        causes: &[
+            "Single quotes instead o' double quotes",
+            "A trailin' comma efter the last item",
+        ],
+        example: "ken data = json_parse(\"{\\\"name\\\": \\\"Morag\\\"}\")\nblether data[\"name\"]",
+    },
+    ErrorExplanation {
+        code: "E0038",
+        title: "Incomparable types",
+        description: "Twa values were compared wi' `<`, `>`, `<=` or `>=` but they cannae be ordered against each ither.",
+        causes: &["Comparin' a number wi' a string that still needs converted"],
+        example: "ken answer = \"42\"\nblether tae_int(answer) > 10",
+    },
+    ErrorExplanation {
+        code: "E0039",
+        title: "Invalid number operation",
+        description: "A number operation got a value it cannae work wi', like a shift by mair than 63 bits.",
+        causes: &[
+            "Shiftin' by a negative amount or by 64 or mair",
+            "Takin' the square root o' a negative number",
+        ],
+        example: "blether 1 << 8",
+    },
+    ErrorExplanation {
+        code: "E0040",
+        title: "Non-exhaustive match",
+        description: "A `keek` didnae hae a `whan` that matched the value.",
+        causes: &["Nae `whan _` catch-all at the end"],
+        example: "keek 7 {\n    whan 1 -> { blether \"ane\" }\n    whan _ -> { blether \"somethin' else\" }\n}",
+    },
+    ErrorExplanation {
+        code: "E0041",
+        title: "Duplicate key",
+        description: "A dictionary literal had the same key mair than ance.",
+        causes: &["A copy-pasted entry that wisnae renamed"],
+        example: "ken ages = {\"Morag\": 30, \"Hamish\": 25}\nblether ages",
+    },
+    ErrorExplanation {
+        code: "E0042",
+        title: "Execution timeout",
+        description: "The program ran past its step limit, which usually means it's stuck in a loop.",
+        causes: &[
+            "A `whiles` loop whose condition never becomes false",
+            "Recursion that takes far mair steps than expected",
+        ],
+        example: "ken i = 0\nwhiles i < 10 {\n    i = i + 1\n}\nblether i",
+    },
+    ErrorExplanation {
+        code: "E0043",
+        title: "Oot o' memory",
+        description: "A value grew bigger than the memory guard allows.",
+        causes: &["A loop that keeps addin' tae a list or string an' never stops"],
+        example: "ken items = []\nfer i in 0..100 {\n    shove(items, i)\n}\nblether len(items)",
+    },
+    ErrorExplanation {
+        code: "E0044",
+        title: "Private member access",
+        description: "A member whose name starts wi' `_` wis used fae ootside its class.",
+        causes: &["Reachin' intae an object instead o' askin' it through a method"],
+        example: "kin Coonter {\n    dae init() {\n        masel._count = 0\n    }\n    dae count() {\n        gie masel._count\n    }\n}\nblether Coonter().count()",
+    },
+    ErrorExplanation {
+        code: "E0045",
+        title: "Immutable variable",
+        description: "A value that's set in stane wis assigned tae.",
+        causes: &["Changin' a constant - declare a new variable instead"],
+        example: "ken start = 1\nken next = start + 1\nblether next",
+    },
+    ErrorExplanation {
+        code: "E0046",
+        title: "Hurled error",
+        description: "The program hurled this error itsel', an' naethin' caught it.",
+        causes: &["A `hurl` wi' nae `hae_a_bash` roond it"],
+        example: "hae_a_bash {\n    hurl \"Somethin' went wrang\"\n} gin_it_gangs_wrang err {\n    blether err\n}",
+    },
+    ErrorExplanation {
+        code: "E0047",
+        title: "Boak",
+        description: "The program called `boak` tae gie up on purpose. It cannae be caught, sae the program stops wi' exit code 1.",
+        causes: &["A `boak` that wis reached - check the message fer why"],
+        example: "ken config = {\"port\": 8080}\ngin nae dict_has(config, \"port\") {\n    boak(\"Nae port set\")\n}\nblether config[\"port\"]",
+    },
+];
+
 /// A wee helper tae format errors bonnie-like
 pub fn format_error_context(source: &str, line: usize) -> String {
+    error_context(source, line, None)
+}
+
+/// Like `format_error_context`, but with a row of `^` under exactly where the
+/// error is. A span over several lines is underlined to the end of its first.
+pub fn format_error_span_context(source: &str, span: ErrorSpan) -> String {
+    error_context(source, span.start_line, Some(span))
+}
+
+fn error_context(source: &str, line: usize, span: Option<ErrorSpan>) -> String {
     let lines: Vec<&str> = source.lines().collect();
     if line == 0 || line > lines.len() {
         return String::new();
@@ -671,6 +1509,19 @@ pub fn format_error_context(source: &str, line: usize) -> String {
         result.push_str(&format!("  {} | {}\n", line - 1, lines[line_idx - 1]));
     }
     result.push_str(&format!("> {} | {}\n", line, lines[line_idx]));
+    if let Some(span) = span {
+        let width = if span.end_line == span.start_line {
+            span.end_col.saturating_sub(span.start_col)
+        } else {
+            (lines[line_idx].chars().count() + 1).saturating_sub(span.start_col)
+        };
+        let gutter = format!("> {} | ", line).len() + span.start_col.saturating_sub(1);
+        result.push_str(&format!(
+            "{}{}\n",
+            " ".repeat(gutter),
+            "^".repeat(width.max(1))
+        ));
+    }
     if line_idx + 1 < lines.len() {
         result.push_str(&format!("  {} | {}\n", line + 1, lines[line_idx + 1]));
     }
@@ -748,12 +1599,11 @@ mod tests {
             ("int", "tae_int"),
             ("push", "shove"),
             ("pop", "yank"),
+            ("insert", "stick_in"),
             ("input", "speir"),
             ("struct", "thing"),
             ("trim", "wheesht"),
             ("random", "jammy"),
-            ("sleep", "snooze"),
-            ("now", "noo"),
             ("exit", "awa"),
             ("throw", "fling"),
             ("extends", "frae"),
@@ -894,6 +1744,7 @@ mod tests {
             expected: "expression".to_string(),
             found: "}".to_string(),
             line: 1,
+            span: None,
         };
         let suggestion = get_error_suggestion(&err);
         assert!(suggestion.is_some());
@@ -903,6 +1754,7 @@ mod tests {
             expected: "expression".to_string(),
             found: "=".to_string(),
             line: 1,
+            span: None,
         };
         let suggestion = get_error_suggestion(&err);
         assert!(suggestion.is_some());
@@ -912,6 +1764,7 @@ mod tests {
             expected: "something".to_string(),
             found: ")".to_string(),
             line: 1,
+            span: None,
         };
         let suggestion = get_error_suggestion(&err);
         assert!(suggestion.is_some());
@@ -938,6 +1791,43 @@ mod tests {
         assert!(context.contains("> 2 |"));
     }
 
+    #[test]
+    fn test_format_error_span_context_underlines_the_span() {
+        let source = "ken x = 1\nken y = (x + ]\nken z = 3";
+        let context = format_error_span_context(source, ErrorSpan::new(2, 14, 2, 15));
+        assert_eq!(
+            context,
+            "  1 | ken x = 1\n> 2 | ken y = (x + ]\n                   ^\n  3 | ken z = 3\n"
+        );
+
+        // A span over several lines is underlined to the end of its first line
+        let context = format_error_span_context(source, ErrorSpan::new(1, 5, 2, 3));
+        assert!(context.contains("\n          ^^^^^\n"), "{context}");
+    }
+
+    #[test]
+    fn test_error_span_of_token_is_never_empty() {
+        let token = Token::new(
+            TokenKind::Identifier("haud".to_string()),
+            "haud".to_string(),
+            3,
+            7,
+        );
+        assert_eq!(ErrorSpan::of_token(&token), ErrorSpan::new(3, 7, 3, 11));
+        assert_eq!(ErrorSpan::of_token(&Token::eof(4)).end_col, 2);
+
+        let err = HaversError::ParseError {
+            message: "oops".to_string(),
+            line: 3,
+            span: Some(ErrorSpan::of_token(&token)),
+        };
+        assert_eq!(err.span(), Some(ErrorSpan::new(3, 7, 3, 11)));
+        assert_eq!(
+            err.with_line_if_zero(9).span(),
+            Some(ErrorSpan::new(3, 7, 3, 11))
+        );
+    }
+
     #[test]
     fn test_format_error_context_edge_cases() {
         // Test first line
@@ -966,7 +1856,8 @@ mod tests {
             HaversError::UnkentToken {
                 lexeme: "x".to_string(),
                 line: 5,
-                column: 3
+                column: 3,
+                span: None
             }
             .line(),
             Some(5)
@@ -976,7 +1867,8 @@ mod tests {
             HaversError::UnexpectedToken {
                 expected: "a".to_string(),
                 found: "b".to_string(),
-                line: 10
+                line: 10,
+                span: None
             }
             .line(),
             Some(10)
@@ -1035,7 +1927,8 @@ mod tests {
         assert_eq!(
             HaversError::ParseError {
                 message: "err".to_string(),
-                line: 9
+                line: 9,
+                span: None
             }
             .line(),
             Some(9)
@@ -1292,6 +2185,16 @@ mod tests {
         assert_eq!(err.line(), Some(123));
         assert!(get_error_suggestion(&err).is_none());
 
+        let err = HaversError::Boak {
+            message: "deliberate".to_string(),
+            line: 7,
+        };
+        assert_eq!(err.line(), Some(7));
+        assert_eq!(
+            err.to_string(),
+            "Boak! The program gied up at line 7: deliberate"
+        );
+
         let err = HaversError::TypeError {
             message: "cannae add integer tae string".to_string(),
             line: 1,
@@ -1318,4 +2221,133 @@ mod tests {
         };
         assert!(get_error_suggestion(&err).is_none());
     }
+
+    #[test]
+    fn test_error_kind_for_undefined_variable_and_syntax_mistake() {
+        let err = crate::run("blether nae_sic_thing").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::NameError);
+
+        let err = crate::parse("ken = 5").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ParseError);
+        assert_eq!(err.kind().to_string(), "ParseError");
+    }
+
+    #[test]
+    fn test_error_kind_categories() {
+        let cases = vec![
+            (
+                HaversError::UnkentToken {
+                    lexeme: "@".to_string(),
+                    line: 1,
+                    column: 1,
+                    span: None,
+                },
+                ErrorKind::LexError,
+            ),
+            (
+                HaversError::IndexOutOfBounds {
+                    index: 5,
+                    size: 1,
+                    line: 1,
+                },
+                ErrorKind::IndexError,
+            ),
+            (
+                HaversError::KeyNotFound {
+                    key: "k".to_string(),
+                    line: 1,
+                },
+                ErrorKind::KeyError,
+            ),
+            (
+                HaversError::DivisionByZero { line: 1 },
+                ErrorKind::ArithmeticError,
+            ),
+            (
+                HaversError::WrongArity {
+                    name: "f".to_string(),
+                    expected: 1,
+                    got: 2,
+                    line: 1,
+                },
+                ErrorKind::TypeError,
+            ),
+            (
+                HaversError::NonExhaustiveMatch { line: 1 },
+                ErrorKind::RuntimeError,
+            ),
+            (
+                HaversError::InternalError("oops".to_string()),
+                ErrorKind::InternalError,
+            ),
+        ];
+        for (err, kind) in cases {
+            assert_eq!(err.kind(), kind, "{err:?}");
+        }
+
+        // A backtrace does not hide the kind of the error underneath
+        let err = HaversError::WithBacktrace {
+            error: Box::new(HaversError::UndefinedVariable {
+                name: "x".to_string(),
+                line: 2,
+            }),
+            backtrace: "  at f (line 2)".to_string(),
+        };
+        assert_eq!(err.kind(), ErrorKind::NameError);
+    }
+
+    #[test]
+    fn test_error_codes_are_sequential_and_explained() {
+        for (i, explanation) in ERROR_EXPLANATIONS.iter().enumerate() {
+            assert_eq!(explanation.code, format!("E{:04}", i + 1));
+            assert!(!explanation.description.is_empty());
+            assert!(!explanation.causes.is_empty());
+            assert!(crate::parse(explanation.example).is_ok(), "{}", explanation.code);
+        }
+
+        let err = crate::run("blether nae_sic_thing").unwrap_err();
+        assert_eq!(err.code(), "E0003");
+        assert_eq!(explain_error("e0003").unwrap().title, "Undefined variable");
+        assert!(explain_error("E9999").is_none());
+    }
+
+    #[test]
+    fn test_closest_name_allows_small_typos_only() {
+        assert_eq!(edit_distance("blather", "blether"), 1);
+        assert_eq!(edit_distance("", "ken"), 3);
+        assert_eq!(edit_distance("gin", "gin"), 0);
+
+        let names = ["blether", "greeting", "gie"];
+        assert_eq!(closest_name("blather", names), Some("blether"));
+        assert_eq!(closest_name("gretin", names), Some("greeting"));
+        assert_eq!(closest_name("blathers", names), Some("blether"));
+        // Three edits is too many, and short names get less slack
+        assert_eq!(closest_name("blaithir", names), None);
+        assert_eq!(closest_name("ge", names), None);
+        assert_eq!(closest_name("gie", names), None);
+    }
+
+    #[test]
+    fn test_with_did_ye_mean_appends_the_hint() {
+        let err = HaversError::UndefinedVariable {
+            name: "greting".to_string(),
+            line: 2,
+        }
+        .with_did_ye_mean("greting", ["greeting", "len"]);
+        assert_eq!(
+            err.to_string(),
+            "Awa' an bile yer heid! 'greting' hasnae been defined yet at line 2. Did ye mean `greeting`?"
+        );
+        assert_eq!(err.kind(), ErrorKind::NameError);
+        assert_eq!(err.line(), Some(2));
+        assert!(matches!(err.root(), HaversError::UndefinedVariable { .. }));
+
+        // The old hand-written hints win over a guess
+        let err = HaversError::UndefinedVariable {
+            name: "print".to_string(),
+            line: 1,
+        }
+        .with_did_ye_mean("print", ["prin"]);
+        assert!(matches!(err, HaversError::UndefinedVariable { .. }));
+    }
 }
@@ -2,6 +2,7 @@
 //! Makes yer code look braw and proper!
 
 use crate::ast::*;
+use crate::lexer::Comment;
 
 /// Configuration fer the formatter
 #[allow(dead_code)]
@@ -26,6 +27,16 @@ pub struct Formatter {
     config: FormatterConfig,
     output: String,
     indent_level: usize,
+    /// Comments still waiting to be written, in source order
+    comments: Vec<Comment>,
+    next_comment: usize,
+    /// A trailing comment to hang on the end of the next line written
+    pending_trailing: Option<String>,
+    /// Columns of the statements we're inside, so we know which comments
+    /// at the tail of a block belong in it
+    stmt_columns: Vec<usize>,
+    /// Source line of whatever follows the statement being formatted
+    next_stmt_line: usize,
 }
 
 impl Default for Formatter {
@@ -44,15 +55,31 @@ impl Formatter {
             config,
             output: String::new(),
             indent_level: 0,
+            comments: Vec::new(),
+            next_comment: 0,
+            pending_trailing: None,
+            stmt_columns: Vec::new(),
+            next_stmt_line: usize::MAX,
         }
     }
 
+    /// Give the formatter the source's comments so they survive the round trip
+    pub fn with_comments(mut self, comments: Vec<Comment>) -> Self {
+        self.comments = comments;
+        self
+    }
+
     /// Format a whole program
     pub fn format(&mut self, program: &Program) -> String {
         self.output.clear();
         self.indent_level = 0;
+        self.next_comment = 0;
+        self.pending_trailing = None;
+        self.stmt_columns.clear();
+        self.next_stmt_line = usize::MAX;
 
         for (i, stmt) in program.statements.iter().enumerate() {
+            self.next_stmt_line = next_line(&program.statements, i, usize::MAX);
             self.format_stmt(stmt);
 
             // Add blank line between top-level declarations
@@ -67,6 +94,9 @@ impl Formatter {
             }
         }
 
+        // Whatever is left over sits at the end of the file
+        self.comments_before(usize::MAX);
+
         // Ensure file ends with newline
         if !self.output.ends_with('\n') {
             self.output.push('\n');
@@ -86,10 +116,86 @@ impl Formatter {
     fn writeln(&mut self, s: &str) {
         self.output.push_str(&self.indent());
         self.output.push_str(s);
+        self.newline();
+    }
+
+    /// End the current line, hanging any pending trailing comment on it first
+    fn newline(&mut self) {
+        if let Some(comment) = self.pending_trailing.take() {
+            self.output.push(' ');
+            self.output.push_str(&comment);
+        }
         self.output.push('\n');
     }
 
+    /// Write out every comment that comes before the given source line.
+    /// Standalone comments get a line of their own; trailing comments whose
+    /// line no statement starts on get tacked onto the last line written.
+    fn comments_before(&mut self, line: usize) {
+        while let Some(comment) = self.comments.get(self.next_comment) {
+            if comment.line >= line {
+                break;
+            }
+            let text = comment.text.clone();
+            if comment.trailing && !self.output.is_empty() {
+                let end = self.output.trim_end_matches('\n').len();
+                let tail = self.output.split_off(end);
+                self.output.push(' ');
+                self.output.push_str(&text);
+                self.output.push_str(&tail);
+            } else {
+                self.writeln(&text);
+            }
+            self.next_comment += 1;
+        }
+    }
+
+    /// Pick up the trailing comment on a statement's first line, if it has one
+    fn take_trailing(&mut self, line: usize) {
+        if let Some(comment) = self.comments.get(self.next_comment) {
+            if comment.trailing && comment.line == line {
+                self.pending_trailing = Some(comment.text.clone());
+                self.next_comment += 1;
+            }
+        }
+    }
+
+    /// Write out the comments at the tail of a block, before its closing
+    /// brace. They belong to the block if they come before whatever follows
+    /// it and sit on a line indented deeper than the statement that owns it.
+    fn comments_at_block_end(&mut self) {
+        let Some(&owner_column) = self.stmt_columns.last() else {
+            return;
+        };
+        while let Some(comment) = self.comments.get(self.next_comment) {
+            if comment.line >= self.next_stmt_line || comment.indent <= owner_column {
+                break;
+            }
+            self.comments_before(comment.line + 1);
+        }
+    }
+
+    /// Format a block's statements, then any comments at its tail
+    fn format_block_body(&mut self, statements: &[Stmt]) {
+        let outer = self.next_stmt_line;
+        for (i, s) in statements.iter().enumerate() {
+            self.next_stmt_line = next_line(statements, i, outer);
+            self.format_stmt(s);
+        }
+        self.next_stmt_line = outer;
+        self.comments_at_block_end();
+    }
+
     fn format_stmt(&mut self, stmt: &Stmt) {
+        let span = stmt.span();
+        self.comments_before(span.line);
+        self.take_trailing(span.line);
+        self.stmt_columns.push(span.column);
+        self.format_stmt_body(stmt);
+        self.stmt_columns.pop();
+    }
+
+    fn format_stmt_body(&mut self, stmt: &Stmt) {
         match stmt {
             Stmt::VarDecl {
                 name, initializer, ..
@@ -109,29 +215,15 @@ impl Formatter {
             Stmt::Block { statements, .. } => {
                 self.writeln("{");
                 self.indent_level += 1;
-                for s in statements {
-                    self.format_stmt(s);
-                }
+                self.format_block_body(statements);
                 self.indent_level -= 1;
                 self.writeln("}");
             }
 
-            Stmt::If {
-                condition,
-                then_branch,
-                else_branch,
-                ..
-            } => {
-                let cond = self.format_expr(condition);
+            Stmt::If { .. } | Stmt::IfLet { .. } => {
                 self.write(&self.indent());
-                self.write(&format!("gin {} ", cond));
-                self.format_stmt_inline(then_branch);
-
-                if let Some(else_stmt) = else_branch {
-                    self.write(" ither ");
-                    self.format_stmt_inline(else_stmt);
-                }
-                self.output.push('\n');
+                self.format_stmt_inline(stmt);
+                self.newline();
             }
 
             Stmt::While {
@@ -141,31 +233,39 @@ impl Formatter {
                 self.write(&self.indent());
                 self.write(&format!("whiles {} ", cond));
                 self.format_stmt_inline(body);
-                self.output.push('\n');
+                self.newline();
             }
 
             Stmt::For {
                 variable,
+                value_variable,
                 iterable,
                 body,
                 ..
             } => {
                 let iter = self.format_expr(iterable);
+                let names = match value_variable {
+                    Some(value_variable) => format!("{}, {}", variable, value_variable),
+                    None => variable.clone(),
+                };
                 self.write(&self.indent());
-                self.write(&format!("fer {} in {} ", variable, iter));
+                self.write(&format!("fer {} in {} ", names, iter));
                 self.format_stmt_inline(body);
-                self.output.push('\n');
+                self.newline();
             }
 
             Stmt::Function {
-                name, params, body, ..
+                name,
+                params,
+                body,
+                attributes,
+                ..
             } => {
+                self.format_attributes(attributes);
                 let params_str = self.format_params(params);
                 self.writeln(&format!("dae {}({}) {{", name, params_str));
                 self.indent_level += 1;
-                for s in body {
-                    self.format_stmt(s);
-                }
+                self.format_block_body(body);
                 self.indent_level -= 1;
                 self.writeln("}");
             }
@@ -194,8 +294,10 @@ impl Formatter {
                 name,
                 superclass,
                 methods,
+                attributes,
                 ..
             } => {
+                self.format_attributes(attributes);
                 let inheritance = if let Some(parent) = superclass {
                     format!(" fae {}", parent)
                 } else {
@@ -203,13 +305,17 @@ impl Formatter {
                 };
                 self.writeln(&format!("kin {}{} {{", name, inheritance));
                 self.indent_level += 1;
+                let outer = self.next_stmt_line;
                 for (i, method) in methods.iter().enumerate() {
+                    self.next_stmt_line = next_line(methods, i, outer);
                     self.format_stmt(method);
                     // Add blank line between methods
                     if i < methods.len() - 1 {
                         self.output.push('\n');
                     }
                 }
+                self.next_stmt_line = outer;
+                self.comments_at_block_end();
                 self.indent_level -= 1;
                 self.writeln("}");
             }
@@ -219,11 +325,31 @@ impl Formatter {
                 self.writeln(&format!("thing {} {{ {} }}", name, fields_str));
             }
 
-            Stmt::Import { path, alias, .. } => {
-                if let Some(a) = alias {
-                    self.writeln(&format!("fetch \"{}\" as {}", path, a));
+            Stmt::Import {
+                path,
+                alias,
+                only,
+                bring,
+                ..
+            } => {
+                let escaped = escape_string(path);
+                if let Some(names) = only {
+                    self.writeln(&format!("bring {} frae \"{}\"", names.join(", "), escaped));
+                } else if *bring {
+                    // The namespace is left out when it's the module's own name
+                    let stem = std::path::Path::new(path)
+                        .file_stem()
+                        .and_then(|stem| stem.to_str());
+                    match alias {
+                        Some(a) if stem != Some(a.as_str()) => {
+                            self.writeln(&format!("bring \"{}\" as {}", escaped, a))
+                        }
+                        _ => self.writeln(&format!("bring \"{}\"", escaped)),
+                    }
+                } else if let Some(a) = alias {
+                    self.writeln(&format!("fetch \"{}\" tae {}", escaped, a));
                 } else {
-                    self.writeln(&format!("fetch \"{}\"", path));
+                    self.writeln(&format!("fetch \"{}\"", escaped));
                 }
             }
 
@@ -238,16 +364,20 @@ impl Formatter {
                 self.format_stmt_inline(try_block);
                 self.write(&format!(" gin_it_gangs_wrang {} ", error_name));
                 self.format_stmt_inline(catch_block);
-                self.output.push('\n');
+                self.newline();
             }
 
             Stmt::Match { value, arms, .. } => {
                 let val = self.format_expr(value);
                 self.writeln(&format!("keek {} {{", val));
                 self.indent_level += 1;
-                for arm in arms {
+                let outer = self.next_stmt_line;
+                for (i, arm) in arms.iter().enumerate() {
+                    self.next_stmt_line = arms.get(i + 1).map_or(outer, |next| next.span.line);
                     self.format_match_arm(arm);
                 }
+                self.next_stmt_line = outer;
+                self.comments_at_block_end();
                 self.indent_level -= 1;
                 self.writeln("}");
             }
@@ -265,11 +395,18 @@ impl Formatter {
             }
 
             Stmt::Destructure {
-                patterns, value, ..
+                patterns,
+                value,
+                exact,
+                ..
             } => {
                 let patterns_str = self.format_destruct_patterns(patterns);
                 let val_str = self.format_expr(value);
-                self.writeln(&format!("ken [{}] = {}", patterns_str, val_str));
+                if *exact {
+                    self.writeln(&format!("ken {} = {}", patterns_str, val_str));
+                } else {
+                    self.writeln(&format!("ken [{}] = {}", patterns_str, val_str));
+                }
             }
 
             Stmt::Log {
@@ -303,6 +440,11 @@ impl Formatter {
                 let msg = self.format_expr(message);
                 self.writeln(&format!("hurl {}", msg));
             }
+
+            Stmt::Yield { value, .. } => {
+                let val = self.format_expr(value);
+                self.writeln(&format!("gie_back {}", val));
+            }
         }
     }
 
@@ -322,12 +464,43 @@ impl Formatter {
     /// Format a statement inline (without adding its own newline)
     fn format_stmt_inline(&mut self, stmt: &Stmt) {
         match stmt {
+            // `ither gin` chains stay flat rather than nesting blocks
+            Stmt::If {
+                condition,
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                let cond = self.format_expr(condition);
+                self.write(&format!("gin {} ", cond));
+                self.format_stmt_inline(then_branch);
+
+                if let Some(else_stmt) = else_branch {
+                    self.write(" ither ");
+                    self.format_stmt_inline(else_stmt);
+                }
+            }
+            Stmt::IfLet {
+                name,
+                value,
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                let value = self.format_expr(value);
+                self.write(&format!("gin ken {} = {} ", name, value));
+                self.format_stmt_inline(then_branch);
+
+                if let Some(else_stmt) = else_branch {
+                    self.write(" ither ");
+                    self.format_stmt_inline(else_stmt);
+                }
+            }
             Stmt::Block { statements, .. } => {
-                self.write("{\n");
+                self.write("{");
+                self.newline();
                 self.indent_level += 1;
-                for s in statements {
-                    self.format_stmt(s);
-                }
+                self.format_block_body(statements);
                 self.indent_level -= 1;
                 self.write(&self.indent());
                 self.write("}");
@@ -363,6 +536,7 @@ impl Formatter {
                 }
             }
             Stmt::Print { value, .. } => format!("blether {}", self.format_expr(value)),
+            Stmt::Yield { value, .. } => format!("gie_back {}", self.format_expr(value)),
             Stmt::Break { .. } => "brak".to_string(),
             Stmt::Continue { .. } => "haud".to_string(),
             _ => "...".to_string(), // Complex statements should use blocks
@@ -370,16 +544,28 @@ impl Formatter {
     }
 
     fn format_match_arm(&mut self, arm: &MatchArm) {
+        self.comments_before(arm.span.line);
+        self.take_trailing(arm.span.line);
+        self.stmt_columns.push(arm.span.column);
         let pattern = self.format_pattern(&arm.pattern);
         self.write(&self.indent());
         self.write(&format!("whan {} -> ", pattern));
-        self.format_stmt_inline(&arm.body);
-        self.output.push('\n');
+        // Arms without braces are written back without braces, otherwise a
+        // second pass would see a block and lay it out differently
+        match &arm.body {
+            Stmt::Block { .. } => self.format_stmt_inline(&arm.body),
+            body => {
+                let formatted = self.format_stmt_single(body);
+                self.write(&formatted);
+            }
+        }
+        self.newline();
+        self.stmt_columns.pop();
     }
 
     fn format_pattern(&self, pattern: &Pattern) -> String {
         match pattern {
-            Pattern::Literal(lit) => format!("{}", lit),
+            Pattern::Literal(lit) => format_literal(lit),
             Pattern::Identifier(name) => name.clone(),
             Pattern::Wildcard => "_".to_string(),
             Pattern::Range { start, end } => {
@@ -388,6 +574,23 @@ impl Formatter {
         }
     }
 
+    /// Write `@deprecated("...")` lines before a dae or kin
+    fn format_attributes(&mut self, attributes: &[Attribute]) {
+        for attr in attributes {
+            if attr.args.is_empty() {
+                self.writeln(&format!("@{}", attr.name));
+            } else {
+                let args = attr
+                    .args
+                    .iter()
+                    .map(|a| format!("\"{}\"", escape_string(a)))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                self.writeln(&format!("@{}({})", attr.name, args));
+            }
+        }
+    }
+
     /// Format function parameters, handling default values
     fn format_params(&self, params: &[Param]) -> String {
         params
@@ -406,7 +609,7 @@ impl Formatter {
     #[allow(clippy::only_used_in_recursion)]
     fn format_expr(&self, expr: &Expr) -> String {
         match expr {
-            Expr::Literal { value, .. } => format!("{}", value),
+            Expr::Literal { value, .. } => format_literal(value),
 
             Expr::Variable { name, .. } => name.clone(),
 
@@ -527,6 +730,25 @@ impl Formatter {
                 format!("[{}]", elems.join(", "))
             }
 
+            Expr::ListComp {
+                element, clauses, ..
+            } => {
+                let mut result = format!("[{}", self.format_expr(element));
+                for clause in clauses {
+                    match clause {
+                        CompClause::For { variable, iterable } => {
+                            let iterable = self.format_expr(iterable);
+                            result.push_str(&format!(" fer {} in {}", variable, iterable));
+                        }
+                        CompClause::If(condition) => {
+                            result.push_str(&format!(" gin {}", self.format_expr(condition)));
+                        }
+                    }
+                }
+                result.push(']');
+                result
+            }
+
             Expr::Dict { pairs, .. } => {
                 let kvs: Vec<String> = pairs
                     .iter()
@@ -539,10 +761,16 @@ impl Formatter {
                 start,
                 end,
                 inclusive,
+                step,
                 ..
             } => {
                 let op = if *inclusive { "..=" } else { ".." };
-                format!("{}{}{}", self.format_expr(start), op, self.format_expr(end))
+                let range = format!("{}{}{}", self.format_expr(start), op, self.format_expr(end));
+                if let Some(st) = step {
+                    format!("{} by {}", range, self.format_expr(st))
+                } else {
+                    range
+                }
             }
 
             Expr::Grouping { expr, .. } => {
@@ -564,10 +792,18 @@ impl Formatter {
                 let mut result = String::from("f\"");
                 for part in parts {
                     match part {
-                        FStringPart::Text(s) => result.push_str(s),
+                        FStringPart::Text(s) => {
+                            let text = escape_string(s).replace('{', "{{").replace('}', "}}");
+                            result.push_str(&text);
+                        }
                         FStringPart::Expr(e) => {
+                            // The f-string parser unescapes \" and \\ inside {}
+                            let inner = self
+                                .format_expr(e)
+                                .replace('\\', "\\\\")
+                                .replace('"', "\\\"");
                             result.push('{');
-                            result.push_str(&self.format_expr(e));
+                            result.push_str(&inner);
                             result.push('}');
                         }
                     }
@@ -598,23 +834,59 @@ impl Formatter {
                 )
             }
             Expr::BlockExpr { statements, .. } => {
-                let mut result = String::from("{\n");
+                // Lay out the body with a nested formatter so it's indented
+                // relative to where the block sits
+                let mut inner = Formatter::with_config(FormatterConfig {
+                    indent_size: self.config.indent_size,
+                    max_line_width: self.config.max_line_width,
+                });
+                inner.indent_level = self.indent_level + 1;
                 for stmt in statements {
-                    result.push_str("    ");
-                    result.push_str(&self.format_stmt_single(stmt));
-                    result.push('\n');
+                    inner.format_stmt(stmt);
                 }
-                result.push('}');
-                result
+                format!("{{\n{}{}}}", inner.output, self.indent())
             }
         }
     }
 }
 
+/// Format a literal so it lexes back to the same value
+fn format_literal(lit: &Literal) -> String {
+    match lit {
+        Literal::String(s) => format!("\"{}\"", escape_string(s)),
+        // Debug keeps the decimal point, so `1.0` does not come back as an integer
+        Literal::Float(n) => format!("{:?}", n),
+        other => format!("{}", other),
+    }
+}
+
+/// Escape a string's contents the way the parser will unescape them
+fn escape_string(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' => result.push_str("\\\\"),
+            '"' => result.push_str("\\\""),
+            '\n' => result.push_str("\\n"),
+            '\t' => result.push_str("\\t"),
+            '\r' => result.push_str("\\r"),
+            '\0' => result.push_str("\\0"),
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+/// Source line of the statement after `statements[i]`, or `outer` for the last
+fn next_line(statements: &[Stmt], i: usize, outer: usize) -> usize {
+    statements.get(i + 1).map_or(outer, |next| next.span().line)
+}
+
 /// Format source code (convenience function)
 pub fn format_source(source: &str) -> Result<String, crate::error::HaversError> {
-    let program = crate::parser::parse(source)?;
-    let mut formatter = Formatter::new();
+    let (tokens, comments) = crate::lexer::lex_with_comments(source)?;
+    let program = crate::parser::Parser::new(tokens).parse()?;
+    let mut formatter = Formatter::new().with_comments(comments);
     Ok(formatter.format(&program))
 }
 
@@ -743,6 +1015,14 @@ dae bar() { gie 2 }"#;
         assert!(result.contains("fer i in 0..10 {"));
     }
 
+    #[test]
+    fn test_format_for_key_value() {
+        let program = parse("fer k, v in d { blether k }").unwrap();
+        let mut formatter = Formatter::new();
+        let result = formatter.format(&program);
+        assert!(result.contains("fer k, v in d {"), "{result}");
+    }
+
     #[test]
     fn test_format_break() {
         let source = "whiles aye { brak }";
@@ -850,14 +1130,25 @@ kin B { dae bar() { gie 2 } }"#;
 
     #[test]
     fn test_format_import_with_alias() {
-        // Parser uses "tae" for aliases, but formatter outputs "as"
-        // This test verifies the formatter handles the alias case
+        // The alias is written back with "tae" so the output parses again
         let source = "fetch \"math\" tae m";
         let program = parse(source).unwrap();
         let mut formatter = Formatter::new();
         let result = formatter.format(&program);
-        // Note: formatter outputs "as" for Scots readability
-        assert!(result.contains("fetch \"math\" as m"));
+        assert!(result.contains("fetch \"math\" tae m"));
+    }
+
+    #[test]
+    fn test_format_bring() {
+        for source in [
+            "bring \"lib/maths\"",
+            "bring \"lib/maths\" as m",
+            "bring add, sub frae \"lib/maths\"",
+        ] {
+            let program = parse(source).unwrap();
+            let mut formatter = Formatter::new();
+            assert_eq!(formatter.format(&program).trim(), source);
+        }
     }
 
     // ==================== Try-Catch Tests ====================
@@ -1210,6 +1501,15 @@ hurl "boom"
         assert!(result.contains("...[3, 4]"));
     }
 
+    #[test]
+    fn test_format_list_comprehension() {
+        let source = "[ x*2 fer x in xs\n  gin x>0 fer y in 1..3 ]";
+        let program = parse(source).unwrap();
+        let mut formatter = Formatter::new();
+        let result = formatter.format(&program);
+        assert_eq!(result.trim(), "[x * 2 fer x in xs gin x > 0 fer y in 1..3]");
+    }
+
     #[test]
     fn test_format_pipe() {
         let source = "5 |> double |> triple";
@@ -1252,10 +1552,67 @@ ken x = {
         assert!(result.contains("brak"));
         assert!(result.contains("haud"));
         assert!(result.contains("gie 5"));
-        assert!(result.contains("..."));
+        assert!(result.contains("    gin aye {\n        blether \"x\"\n    }\n"));
         assert!(result.contains("\n    a\n"));
     }
 
+    #[test]
+    fn test_format_keeps_deprecated_attributes() {
+        let source = "@deprecated(\"use new_fn\")\ndae old_fn() { gie 1 }\n@deprecated kin Auld {}";
+        let result = format_source(source).unwrap();
+        assert!(result.contains("@deprecated(\"use new_fn\")\ndae old_fn() {\n"));
+        assert!(result.contains("@deprecated\nkin Auld {"));
+        assert_eq!(format_source(&result).unwrap(), result);
+    }
+
+    // ==================== Round-trip Tests ====================
+
+    #[test]
+    fn test_format_else_if_chain_stays_flat() {
+        let source = "gin x > 1 { blether 1 } ither gin x > 0 { blether 0 } ither { blether 2 }";
+        let result = format_source(source).unwrap();
+        assert!(result.contains("} ither gin x > 0 {"));
+        assert!(!result.contains("..."));
+    }
+
+    #[test]
+    fn test_format_if_let_round_trip() {
+        let source = "gin ken v = get(d, \"k\") { blether v } ither gin ken w = z { blether w } ither { blether 0 }";
+        let result = format_source(source).unwrap();
+        assert!(result.starts_with("gin ken v = get(d, \"k\") {\n"));
+        assert!(result.contains("} ither gin ken w = z {"));
+        assert_eq!(format_source(&result).unwrap(), result);
+    }
+
+    #[test]
+    fn test_format_match_arm_without_braces() {
+        let source =
+            "keek x {\n    whan 1 -> blether \"one\"\n    whan _ -> { blether \"other\" }\n}";
+        let result = format_source(source).unwrap();
+        assert!(result.contains("    whan 1 -> blether \"one\"\n"));
+        assert!(result.contains("    whan _ -> {\n        blether \"other\"\n    }\n"));
+    }
+
+    #[test]
+    fn test_format_string_escapes_round_trip() {
+        let source = r#"ken s = "tab\there \"quoted\" back\\slash\n""#;
+        let result = format_source(source).unwrap();
+        assert!(result.contains(r#""tab\there \"quoted\" back\\slash\n""#));
+    }
+
+    #[test]
+    fn test_format_fstring_escapes_round_trip() {
+        let source = r#"blether f"{{literal}} {d[\"key\"]}""#;
+        let result = format_source(source).unwrap();
+        assert!(result.contains(r#"f"{{literal}} {d[\"key\"]}""#));
+    }
+
+    #[test]
+    fn test_format_float_keeps_decimal_point() {
+        let result = format_source("ken x = 2.0").unwrap();
+        assert!(result.contains("ken x = 2.0"));
+    }
+
     // ==================== Block Statement Tests ====================
 
     #[test]
@@ -1346,4 +1703,40 @@ ken x = {
         let result = format_source("ken =");
         assert!(result.is_err());
     }
+
+    // ==================== Comment Tests ====================
+
+    #[test]
+    fn test_format_keeps_trailing_comment() {
+        let result = format_source("ken   x=1 # note").unwrap();
+        assert_eq!(result, "ken x = 1 # note\n");
+    }
+
+    #[test]
+    fn test_format_keeps_comment_above_function() {
+        let source = "ken x = 1\n# Says hullo\ndae greet(name) {\nblether name\n}\n";
+        let result = format_source(source).unwrap();
+        assert_eq!(
+            result,
+            "ken x = 1\n# Says hullo\ndae greet(name) {\n    blether name\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_format_keeps_comments_at_block_tails() {
+        let source =
+            "dae f() {\n  gin aye {\n    blether 1\n    # inner\n  } # efter\n  # tail\n}\n# end\n";
+        let result = format_source(source).unwrap();
+        assert_eq!(
+            result,
+            "dae f() {\n    gin aye {\n        blether 1\n        # inner\n    } # efter\n    # tail\n}\n# end\n"
+        );
+    }
+
+    #[test]
+    fn test_formatter_without_comments_leaves_them_out() {
+        let program = parse("ken x = 1 # gone").unwrap();
+        let mut formatter = Formatter::new();
+        assert_eq!(formatter.format(&program), "ken x = 1\n");
+    }
 }
@@ -0,0 +1,115 @@
+//! Light type inference from the shape of an expression
+//!
+//! The LLVM backend uses this to pick fast paths, and the type lint uses
+//! it to spot operations that are plainly wrong. Anything it cannot be
+//! sure about comes out as `VarType::Unknown`.
+
+use crate::ast::{BinaryOp, Expr, Literal};
+
+/// Inferred type for optimization
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VarType {
+    Unknown,
+    Int,
+    Float,
+    String,
+    Bool,
+    List,
+    Dict,
+}
+
+impl VarType {
+    /// The name the interpreter gives a value of this type
+    pub fn name(self) -> &'static str {
+        match self {
+            VarType::Unknown => "value",
+            VarType::Int => "integer",
+            VarType::Float => "float",
+            VarType::String => "string",
+            VarType::Bool => "bool",
+            VarType::List => "list",
+            VarType::Dict => "dict",
+        }
+    }
+}
+
+/// Work out an expression's type, asking `var_type` for any variable's type
+pub fn infer_expr_type(expr: &Expr, var_type: &dyn Fn(&str) -> VarType) -> VarType {
+    let infer = |expr: &Expr| infer_expr_type(expr, var_type);
+    match expr {
+        Expr::Literal { value, .. } => match value {
+            Literal::Integer(_) => VarType::Int,
+            Literal::Float(_) => VarType::Float,
+            Literal::String(_) => VarType::String,
+            Literal::Bool(_) => VarType::Bool,
+            Literal::Nil => VarType::Unknown,
+        },
+        Expr::Variable { name, .. } => var_type(name),
+        Expr::Binary {
+            left,
+            operator,
+            right,
+            ..
+        } => match operator {
+            BinaryOp::Add => {
+                let lt = infer(left);
+                let rt = infer(right);
+                if lt == VarType::Int && rt == VarType::Int {
+                    VarType::Int
+                } else if lt == VarType::Float || rt == VarType::Float {
+                    VarType::Float
+                } else if lt == VarType::String && rt == VarType::String {
+                    VarType::String
+                } else {
+                    VarType::Unknown
+                }
+            }
+            BinaryOp::Subtract | BinaryOp::Multiply | BinaryOp::Divide | BinaryOp::Modulo => {
+                let lt = infer(left);
+                let rt = infer(right);
+                if lt == VarType::Int && rt == VarType::Int {
+                    VarType::Int
+                } else if lt == VarType::Float || rt == VarType::Float {
+                    VarType::Float
+                } else {
+                    VarType::Unknown
+                }
+            }
+            // int ** int is only an int when the exponent's non-negative
+            BinaryOp::Power => {
+                let lt = infer(left);
+                let rt = infer(right);
+                if lt == VarType::Float || rt == VarType::Float {
+                    VarType::Float
+                } else {
+                    VarType::Unknown
+                }
+            }
+            BinaryOp::Less
+            | BinaryOp::LessEqual
+            | BinaryOp::Greater
+            | BinaryOp::GreaterEqual
+            | BinaryOp::Equal
+            | BinaryOp::NotEqual
+            | BinaryOp::In
+            | BinaryOp::NotIn => VarType::Bool,
+            BinaryOp::BitAnd
+            | BinaryOp::BitOr
+            | BinaryOp::BitXor
+            | BinaryOp::ShiftLeft
+            | BinaryOp::ShiftRight => {
+                let lt = infer(left);
+                let rt = infer(right);
+                if lt == VarType::Int && rt == VarType::Int {
+                    VarType::Int
+                } else {
+                    VarType::Unknown
+                }
+            }
+        },
+        Expr::List { .. } | Expr::ListComp { .. } => VarType::List,
+        Expr::Dict { .. } => VarType::Dict,
+        Expr::Unary { operand, .. } => infer(operand),
+        _ => VarType::Unknown,
+    }
+}
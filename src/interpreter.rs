@@ -3,10 +3,14 @@
 #[cfg(feature = "native")]
 use libsrtp::{MasterKey, ProtectionProfile, RecvSession, SendSession, StreamConfig};
 #[cfg(feature = "native")]
+use openssl::hash::MessageDigest;
+#[cfg(feature = "native")]
 use openssl::pkcs12::Pkcs12;
 #[cfg(feature = "native")]
 use openssl::pkey::PKey;
 #[cfg(feature = "native")]
+use openssl::sign::Signer;
+#[cfg(feature = "native")]
 use openssl::x509::X509;
 #[cfg(feature = "native")]
 use rustls::client::{ServerCertVerified, ServerCertVerifier};
@@ -19,17 +23,19 @@ use rustls::{
 use rustls_pemfile::{certs, pkcs8_private_keys, rsa_private_keys};
 use std::cell::RefCell;
 use std::collections::{HashMap, VecDeque};
-#[cfg(not(coverage))]
-use std::io;
 #[cfg(feature = "native")]
 use std::io::Read;
 use std::io::Write;
+use std::io::{self, BufRead};
 #[cfg(all(feature = "native", not(target_arch = "wasm32")))]
 use std::net::ToSocketAddrs;
 #[cfg(all(feature = "native", unix))]
 use std::os::unix::io::{FromRawFd, RawFd};
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
+
+use num_bigint::BigInt;
+use num_traits::{Signed, ToPrimitive, Zero};
 #[cfg(feature = "native")]
 use trust_dns_resolver::config::{ResolverConfig, ResolverOpts};
 #[cfg(feature = "native")]
@@ -37,7 +43,7 @@ use trust_dns_resolver::proto::rr::{RData, RecordType};
 #[cfg(feature = "native")]
 use trust_dns_resolver::Resolver;
 #[cfg(feature = "native")]
-use udp_dtls::{DtlsAcceptor, DtlsConnector, Identity, SrtpProfile, UdpChannel};
+use udp_dtls::{DtlsAcceptor, DtlsConnector, DtlsStream, Identity, SrtpProfile, UdpChannel};
 
 #[cfg(all(feature = "cli", not(coverage)))]
 use crossterm::{
@@ -49,7 +55,7 @@ use crate::ast::{LogLevel, *};
 use crate::error::{HaversError, HaversResult};
 use crate::logging;
 use crate::value::*;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Mutex, OnceLock};
 
 #[cfg(feature = "native")]
@@ -58,9 +64,406 @@ use std::sync::Arc;
 /// Whether crash handling is enabled (default: true)
 static CRASH_HANDLING_ENABLED: AtomicBool = AtomicBool::new(true);
 
+/// How many native built-ins are running right now, so the crash handler knows
+/// whether a panic came from inside one
+static NATIVE_CALL_DEPTH: AtomicUsize = AtomicUsize::new(0);
+
 /// Monotonic clock anchor for mono_ms/mono_ns
 static MONO_START: OnceLock<std::time::Instant> = OnceLock::new();
 
+/// Clamp `start..end` onto a sequence of `len` items: negative bounds count
+/// from the end, anything past either end is pulled back, and a start at or
+/// past the end gives an empty range. `None` for the end means "to the end".
+fn slice_bounds(len: usize, start: i64, end: Option<i64>) -> (usize, usize) {
+    let clamp = |i: i64| {
+        let i = if i < 0 { i + len as i64 } else { i };
+        i.clamp(0, len as i64) as usize
+    };
+    let start = clamp(start);
+    let end = end.map_or(len, clamp);
+    (start, end.max(start))
+}
+
+/// Write `n` in `base` (2-36) with lowercase digits; negatives get a leading `-`
+fn int_to_base(n: i64, base: u32) -> Result<String, String> {
+    if !(2..=36).contains(&base) {
+        return Err(format!("Base must be atween 2 an' 36, no' {}", base));
+    }
+    let mut rest = n.unsigned_abs();
+    let mut digits = Vec::new();
+    loop {
+        digits.push(std::char::from_digit((rest % base as u64) as u32, base).unwrap());
+        rest /= base as u64;
+        if rest == 0 {
+            break;
+        }
+    }
+    if n < 0 {
+        digits.push('-');
+    }
+    Ok(digits.into_iter().rev().collect())
+}
+
+/// assert(condition, message?): aye, or an AssertionFailed at the call's line
+fn check_assert(args: &[Value], line: usize) -> HaversResult<Value> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(HaversError::WrongArity {
+            name: "assert".to_string(),
+            expected: 1,
+            got: args.len(),
+            line,
+        });
+    }
+    if args[0].is_truthy() {
+        return Ok(Value::Bool(true));
+    }
+    let message = match args.get(1) {
+        Some(message) => message.to_string(),
+        None => "the condition wisnae aye".to_string(),
+    };
+    Err(HaversError::AssertionFailed {
+        message: format!("Assertion failed: {}", message),
+        line,
+    })
+}
+
+/// assert_eq(actual, expected): aye, or an AssertionFailed showing both
+fn check_assert_eq(args: &[Value], line: usize) -> HaversResult<Value> {
+    let [actual, expected] = args else {
+        return Err(HaversError::WrongArity {
+            name: "assert_eq".to_string(),
+            expected: 2,
+            got: args.len(),
+            line,
+        });
+    };
+    if actual == expected {
+        return Ok(Value::Bool(true));
+    }
+    Err(HaversError::AssertionFailed {
+        message: format!("Assertion failed: Expected {} but got {}", expected, actual),
+        line,
+    })
+}
+
+/// Membership for `in` and `contains()`: a list item, dict key, creel
+/// member, or substring
+fn contains_value(container: &Value, item: &Value) -> Result<bool, String> {
+    match container {
+        Value::List(list) => Ok(list.borrow().iter().any(|v| v == item)),
+        Value::Dict(dict) => Ok(dict.borrow().contains_key(item)),
+        Value::Set(set) => Ok(set.borrow().contains(item)),
+        Value::String(s) => match item {
+            Value::String(needle) => Ok(s.contains(needle.as_str())),
+            _ => Err(format!(
+                "Cannae look fer a {} in a string - only a string can be in a string",
+                item.type_name()
+            )),
+        },
+        _ => Err(format!(
+            "Cannae look inside a {} - it needs tae be a list, dict, creel or string",
+            container.type_name()
+        )),
+    }
+}
+
+/// Order two values `sort` has already checked are comparable; NaNs go last
+/// so the order is total
+fn sort_order(a: &Value, b: &Value) -> std::cmp::Ordering {
+    a.partial_cmp(b).unwrap_or_else(|| {
+        let is_nan = |v: &Value| matches!(v, Value::Float(f) if f.is_nan());
+        is_nan(a).cmp(&is_nan(b))
+    })
+}
+
+/// Check `min`/`max` were given two values that can be ordered
+fn ordered_pair<'a>(
+    name: &str,
+    a: &'a Value,
+    b: &'a Value,
+) -> Result<(&'a Value, &'a Value), String> {
+    if a.is_comparable_with(b) {
+        Ok((a, b))
+    } else {
+        Err(format!(
+            "{}() cannae compare {} wi' {}",
+            name,
+            a.type_name(),
+            b.type_name()
+        ))
+    }
+}
+
+/// `min`/`max`: pick the value ordered `wanted` against the rest, from two
+/// arguments or a single list. An empty list is the `empty` error.
+fn pick_extreme(
+    name: &str,
+    args: &[Value],
+    wanted: std::cmp::Ordering,
+    empty: &str,
+) -> Result<Value, String> {
+    let borrowed;
+    let items: &[Value] = match args {
+        [Value::List(list)] => {
+            borrowed = list.borrow();
+            &borrowed
+        }
+        [_, _] => args,
+        [other] => {
+            return Err(format!(
+                "{}() needs a list or twa values, no' a lane {}",
+                name,
+                other.type_name()
+            ))
+        }
+        _ => return Err(format!("{}() expects a list or twa values", name)),
+    };
+    let Some((first, rest)) = items.split_first() else {
+        return Err(empty.to_string());
+    };
+    let mut best = first;
+    for item in rest {
+        let (so_far, item) = ordered_pair(name, best, item)?;
+        if item.partial_cmp(so_far) == Some(wanted) {
+            best = item;
+        }
+    }
+    Ok(best.clone())
+}
+
+/// Flatten nested lists down `depth` levels, or all the way with `None`.
+/// Walks with its own stack so deep nesting cannot blow the Rust one, and
+/// stops on a list that contains itself instead of looping forever.
+fn flatten_list(
+    list: &Rc<RefCell<Vec<Value>>>,
+    depth: Option<usize>,
+) -> Result<Vec<Value>, String> {
+    let mut out = Vec::new();
+    let mut path = vec![Rc::as_ptr(list)];
+    let mut stack = vec![list.borrow().clone().into_iter()];
+    while let Some(items) = stack.last_mut() {
+        match items.next() {
+            Some(Value::List(inner)) if depth.is_none_or(|d| stack.len() <= d) => {
+                if path.contains(&Rc::as_ptr(&inner)) {
+                    return Err("Cannae flatten a list that contains itsel'".to_string());
+                }
+                path.push(Rc::as_ptr(&inner));
+                stack.push(inner.borrow().clone().into_iter());
+            }
+            Some(item) => out.push(item),
+            None => {
+                stack.pop();
+                path.pop();
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// format(template, ...): fill `{}` placeholders in order, with an optional
+/// `{:W}` width, `{:.P}` precision for numbers, or both as `{:W.P}`.
+/// Numbers line up to the right and other values to the left; `{{` and `}}`
+/// give literal braces.
+fn format_template(template: &str, args: &[Value]) -> Result<String, String> {
+    let mut out = String::new();
+    let mut next = 0;
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                out.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                out.push('}');
+            }
+            '}' => return Err("format() found a lone '}' - write '}}' fer a brace".to_string()),
+            '{' => {
+                let mut spec = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => spec.push(c),
+                        None => {
+                            return Err(
+                                "format() found a '{' that's never closed - write '{{' fer a brace"
+                                    .to_string(),
+                            )
+                        }
+                    }
+                }
+                let Some(value) = args.get(next) else {
+                    return Err(format!(
+                        "format() has mair placeholders than values - only gied {}",
+                        args.len()
+                    ));
+                };
+                next += 1;
+                out.push_str(&format_placeholder(&spec, value)?);
+            }
+            c => out.push(c),
+        }
+    }
+    if next < args.len() {
+        return Err(format!(
+            "format() wis gied {} values but the template only has {} placeholders",
+            args.len(),
+            next
+        ));
+    }
+    Ok(out)
+}
+
+/// Render one value for a `{...}` placeholder; `spec` is what's between the braces
+fn format_placeholder(spec: &str, value: &Value) -> Result<String, String> {
+    let bad_spec = || format!("format() disnae ken the placeholder '{{{}}}'", spec);
+    let spec = match spec.strip_prefix(':') {
+        Some(spec) => spec,
+        None if spec.is_empty() => "",
+        None => return Err(bad_spec()),
+    };
+    let (width, precision) = match spec.split_once('.') {
+        Some((width, precision)) => (width, Some(precision)),
+        None => (spec, None),
+    };
+    let width = match width {
+        "" => 0,
+        w => w.parse::<usize>().map_err(|_| bad_spec())?,
+    };
+    let is_number = matches!(value, Value::Integer(_) | Value::Float(_));
+    let text = match precision {
+        None => value.to_string(),
+        Some(p) => {
+            let decimals = p
+                .parse::<usize>()
+                .ok()
+                .filter(|d| *d as i64 <= MAX_FLOAT_DECIMALS)
+                .ok_or_else(bad_spec)?;
+            let x = match value {
+                Value::Float(f) => *f,
+                Value::Integer(n) => *n as f64,
+                _ => {
+                    return Err(format!(
+                        "format() precision is fer numbers, no' a {}",
+                        value.type_name()
+                    ))
+                }
+            };
+            format!("{:.*}", decimals, x)
+        }
+    };
+    Ok(if is_number {
+        format!("{:>1$}", text, width)
+    } else {
+        format!("{:<1$}", text, width)
+    })
+}
+
+/// Read a number written in `base` (2-36), with an optional sign
+fn int_from_base(text: &str, base: u32) -> Result<i64, String> {
+    if !(2..=36).contains(&base) {
+        return Err(format!("Base must be atween 2 an' 36, no' {}", base));
+    }
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text.strip_prefix('+').unwrap_or(text)),
+    };
+    if digits.is_empty() {
+        return Err(format!("Cannae read '{}' as a base {} number", text, base));
+    }
+    let mut value: i128 = 0;
+    for c in digits.chars() {
+        let digit = c.to_digit(base).ok_or_else(|| {
+            format!("'{}' isnae a base {} digit (in '{}')", c, base, text)
+        })?;
+        value = value * base as i128 + digit as i128;
+        if value > i64::MAX as i128 + 1 {
+            return Err(format!("'{}' is too big fer an integer", text));
+        }
+    }
+    let value = if negative { -value } else { value };
+    i64::try_from(value).map_err(|_| format!("'{}' is too big fer an integer", text))
+}
+
+/// The generator behind the random built-ins. Each interpreter has its own,
+/// so `seed_random` makes a run repeatable. SplitMix64: quick and evenly
+/// spread, but not fit for secrets.
+#[derive(Debug, Clone)]
+struct Prng {
+    state: u64,
+}
+
+impl Prng {
+    fn new(seed: u64) -> Self {
+        Prng { state: seed }
+    }
+
+    /// Seed from whatever the platform has to hand. The browser gets the
+    /// same start every time, so the playground seeds from Math.random.
+    fn from_entropy() -> Self {
+        use std::collections::hash_map::RandomState;
+        use std::hash::{BuildHasher, Hasher};
+        let mut hasher = RandomState::new().build_hasher();
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Ok(now) = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH) {
+            hasher.write_u128(now.as_nanos());
+        }
+        Prng::new(hasher.finish())
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in [0, 1)
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniform in [0, n) with no modulo bias; `n` must be above zero
+    fn below(&mut self, n: u64) -> u64 {
+        let zone = u64::MAX - u64::MAX % n;
+        loop {
+            let x = self.next_u64();
+            if x < zone {
+                return x % n;
+            }
+        }
+    }
+
+    /// Uniform in [min, max], both ends included
+    fn between(&mut self, min: i64, max: i64) -> i64 {
+        let span = max.wrapping_sub(min) as u64;
+        let offset = if span == u64::MAX {
+            self.next_u64()
+        } else {
+            self.below(span + 1)
+        };
+        min.wrapping_add(offset as i64)
+    }
+
+    /// Fisher-Yates, every order equally likely
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i as u64 + 1) as usize;
+            items.swap(i, j);
+        }
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            None
+        } else {
+            Some(&items[self.below(items.len() as u64) as usize])
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 #[cfg(all(feature = "native", unix))]
 enum SocketKind {
@@ -233,6 +636,22 @@ struct InterpreterGuard {
     prev: *mut Interpreter,
 }
 
+/// Marks a native built-in as running until it's dropped (even on unwind)
+struct NativeCallGuard;
+
+impl NativeCallGuard {
+    fn new() -> Self {
+        NATIVE_CALL_DEPTH.fetch_add(1, Ordering::Relaxed);
+        NativeCallGuard
+    }
+}
+
+impl Drop for NativeCallGuard {
+    fn drop(&mut self) {
+        NATIVE_CALL_DEPTH.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
 impl InterpreterGuard {
     fn new(interp: &mut Interpreter) -> Self {
         let ptr = interp as *mut Interpreter;
@@ -269,6 +688,37 @@ where
     })
 }
 
+/// Check a compose/pipe stage is a function that can be passed just the one
+/// argument, returning its name
+fn single_arg_stage(builtin: &str, stage: &Value) -> Result<String, String> {
+    let (name, fits) = match stage {
+        Value::Function(f) => (f.name.clone(), f.min_arity() <= 1 && f.max_arity() >= 1),
+        Value::NativeFunction(f) => (f.name.clone(), f.arity == 1 || f.arity == usize::MAX),
+        other => {
+            return Err(format!(
+                "{}() needs functions, no' a {}",
+                builtin,
+                other.type_name()
+            ))
+        }
+    };
+    if fits {
+        Ok(name)
+    } else {
+        Err(format!(
+            "{}() needs functions that tak one argument, but '{}' disnae",
+            builtin, name
+        ))
+    }
+}
+
+/// Draw from the running interpreter's generator (or a fresh one outside a run)
+fn with_rng<R>(f: impl FnOnce(&mut Prng) -> R) -> R {
+    let mut f = Some(f);
+    with_current_interpreter(|interp| (f.take().unwrap())(&mut interp.rng))
+        .unwrap_or_else(|| (f.take().unwrap())(&mut Prng::from_entropy()))
+}
+
 struct EnvSwapGuard {
     interp: *mut Interpreter,
     prev: Rc<RefCell<Environment>>,
@@ -319,6 +769,31 @@ impl Drop for CurrentDirGuard {
     }
 }
 
+/// Puts the tracer's quiet flag back the way a loop found it, however the
+/// loop is left
+struct TraceQuietGuard {
+    interp: *mut Interpreter,
+    prev: bool,
+}
+
+impl TraceQuietGuard {
+    fn new(interp: &mut Interpreter) -> Self {
+        TraceQuietGuard {
+            prev: interp.trace_quiet,
+            interp: interp as *mut Interpreter,
+        }
+    }
+}
+
+impl Drop for TraceQuietGuard {
+    fn drop(&mut self) {
+        // SAFETY: guard is scoped to a live Interpreter borrow.
+        unsafe {
+            (*self.interp).trace_quiet = self.prev;
+        }
+    }
+}
+
 struct StackFrameGuard;
 
 impl StackFrameGuard {
@@ -555,6 +1030,15 @@ struct TlsSession {
     client_config: Option<Arc<ClientConfig>>,
     server_config: Option<Arc<ServerConfig>>,
     stream: Option<TlsStream>,
+    handshake: Option<TlsHandshake>,
+    timeout_ms: u64,
+}
+
+/// A handshake started by tls_connect_start, stepped by the caller's own loop
+#[cfg(feature = "native")]
+struct TlsHandshake {
+    conn: rustls::Connection,
+    stream: std::net::TcpStream,
 }
 
 #[cfg(feature = "native")]
@@ -612,6 +1096,96 @@ fn remove_tls(id: i64) {
     reg.sessions.remove(&id);
 }
 
+/// A fresh client or server connection for this session's config
+#[cfg(all(feature = "native", unix))]
+fn tls_new_connection(session: &TlsSession) -> Result<rustls::Connection, String> {
+    match session.mode {
+        TlsMode::Client => {
+            let config = session
+                .client_config
+                .as_ref()
+                .ok_or("Missing client config")?
+                .clone();
+            let server_name = ServerName::try_from(session.server_name.as_str())
+                .map_err(|_| "Invalid server_name")?;
+            let conn = ClientConnection::new(config, server_name).map_err(|e| e.to_string())?;
+            Ok(rustls::Connection::Client(conn))
+        }
+        TlsMode::Server => {
+            let config = session
+                .server_config
+                .as_ref()
+                .ok_or("Missing server config")?
+                .clone();
+            let conn = ServerConnection::new(config).map_err(|e| e.to_string())?;
+            Ok(rustls::Connection::Server(conn))
+        }
+    }
+}
+
+/// Push a non-blocking handshake as far as the socket allows. Returns "done",
+/// or "want_read"/"want_write" when the caller should wait on the socket.
+#[cfg(all(feature = "native", unix))]
+fn tls_handshake_advance(hs: &mut TlsHandshake) -> Result<&'static str, String> {
+    let failed = |e: &dyn std::fmt::Display| format!("TLS handshake failed: {}", e);
+    loop {
+        // Send whatever we owe the peer before looking for more from them
+        while hs.conn.wants_write() {
+            match hs.conn.write_tls(&mut hs.stream) {
+                Ok(_) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => return Ok("want_write"),
+                Err(e) => return Err(failed(&e)),
+            }
+        }
+        if !hs.conn.is_handshaking() {
+            return Ok("done");
+        }
+        match hs.conn.read_tls(&mut hs.stream) {
+            Ok(0) => return Err("TLS handshake failed: peer closed the connection".to_string()),
+            Ok(_) => {
+                if let Err(e) = hs.conn.process_new_packets() {
+                    // Tell the peer why, if the socket will take it
+                    let _ = hs.conn.write_tls(&mut hs.stream);
+                    return Err(failed(&e));
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => return Ok("want_read"),
+            Err(e) => return Err(failed(&e)),
+        }
+    }
+}
+
+/// Step the session's pending handshake, handing the stream over to
+/// tls_send/tls_recv (back in blocking mode) once it's done
+#[cfg(all(feature = "native", unix))]
+fn tls_handshake_step_session(session: &mut TlsSession) -> Result<&'static str, String> {
+    let hs = session
+        .handshake
+        .as_mut()
+        .ok_or("Nae TLS handshake under way (call tls_connect_start first)")?;
+    let status = match tls_handshake_advance(hs) {
+        Ok(status) => status,
+        Err(e) => {
+            session.handshake = None;
+            return Err(e);
+        }
+    };
+    if status == "done" {
+        if let Some(TlsHandshake { conn, stream }) = session.handshake.take() {
+            let _ = stream.set_nonblocking(false);
+            session.stream = Some(match conn {
+                rustls::Connection::Client(conn) => {
+                    TlsStream::Client(StreamOwned::new(conn, stream))
+                }
+                rustls::Connection::Server(conn) => {
+                    TlsStream::Server(StreamOwned::new(conn, stream))
+                }
+            });
+        }
+    }
+    Ok(status)
+}
+
 #[cfg(feature = "native")]
 struct SrtpSession {
     send: SendSession,
@@ -656,6 +1230,56 @@ where
     f(session)
 }
 
+/// Replay window bounds libsrtp accepts, in packets.
+#[cfg(feature = "native")]
+const SRTP_MIN_WINDOW: i64 = 64;
+#[cfg(feature = "native")]
+const SRTP_MAX_WINDOW: i64 = 0x7fff;
+
+/// Optional stream settings from an `srtp_create` config. A starting ROC
+/// belongs to one receiving stream, so it needs the `ssrc` along with it.
+#[cfg(feature = "native")]
+struct SrtpStreamOpts {
+    ssrc: Option<u32>,
+    roc: Option<u32>,
+    window_size: Option<i64>,
+}
+
+#[cfg(feature = "native")]
+fn srtp_u32(name: &str, n: i64) -> Result<u32, String> {
+    u32::try_from(n).map_err(|_| format!("SRTP {} must be atween 0 an' {}", name, u32::MAX))
+}
+
+#[cfg(feature = "native")]
+fn srtp_stream_opts(dict: &DictValue) -> Result<SrtpStreamOpts, String> {
+    let int_field = |key: &str| match dict.get(&Value::String(key.to_string())) {
+        None | Some(Value::Nil) => Ok(None),
+        Some(Value::Integer(n)) => Ok(Some(*n)),
+        Some(_) => Err(format!("SRTP {} must be an integer", key)),
+    };
+    let ssrc = int_field("ssrc")?
+        .map(|n| srtp_u32("ssrc", n))
+        .transpose()?;
+    let roc = int_field("roc")?.map(|n| srtp_u32("roc", n)).transpose()?;
+    if roc.is_some() && ssrc.is_none() {
+        return Err("SRTP roc needs an ssrc tae go wi' it".to_string());
+    }
+    let window_size = int_field("window_size")?;
+    if let Some(n) = window_size {
+        if !(SRTP_MIN_WINDOW..=SRTP_MAX_WINDOW).contains(&n) {
+            return Err(format!(
+                "SRTP window_size must be atween {} an' {}",
+                SRTP_MIN_WINDOW, SRTP_MAX_WINDOW
+            ));
+        }
+    }
+    Ok(SrtpStreamOpts {
+        ssrc,
+        roc,
+        window_size,
+    })
+}
+
 #[cfg(feature = "native")]
 #[derive(Clone)]
 struct DtlsConfigData {
@@ -668,6 +1292,7 @@ struct DtlsConfigData {
     remote_host: Option<String>,
     remote_port: Option<u16>,
     srtp_profiles: Vec<SrtpProfile>,
+    timeout_ms: u64,
 }
 
 #[cfg(feature = "native")]
@@ -987,6 +1612,150 @@ fn tls_take_force_next_rsa_private_keys_fail() -> bool {
     })
 }
 
+/// A loop body as a list of statements
+fn loop_body(body: &Stmt) -> Vec<Stmt> {
+    match body {
+        Stmt::Block { statements, .. } => statements.clone(),
+        other => vec![other.clone()],
+    }
+}
+
+/// Resolve a script path against the interpreter's current directory
+fn script_path(path: &str) -> PathBuf {
+    let path = Path::new(path);
+    if path.is_absolute() {
+        return path.to_path_buf();
+    }
+    with_current_interpreter(|interp| interp.current_dir.join(path))
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+fn read_dir_names(name: &str, path: &str) -> Result<Vec<String>, String> {
+    let dir = script_path(path);
+    if !dir.is_dir() {
+        return Err(format!("{}() cannae read '{}' - it's no' a directory", name, path));
+    }
+    let entries = std::fs::read_dir(&dir)
+        .map_err(|e| format!("Couldnae read directory '{}': {}", path, e))?;
+    let mut names = Vec::new();
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("Couldnae read directory '{}': {}", path, e))?;
+        names.push(entry.file_name().to_string_lossy().to_string());
+    }
+    names.sort();
+    Ok(names)
+}
+
+/// Every file under `dir`, as paths relative to the root and joined with '/'
+fn walk_dir_files(dir: &Path, prefix: &str, out: &mut Vec<String>) -> Result<(), String> {
+    let mut entries = std::fs::read_dir(dir)
+        .and_then(|entries| entries.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| format!("Couldnae read directory '{}': {}", dir.display(), e))?;
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let rel = if prefix.is_empty() {
+            name
+        } else {
+            format!("{}/{}", prefix, name)
+        };
+        // file_type does not follow symlinks, so a linked loop cannot trap us
+        let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+        if is_dir {
+            walk_dir_files(&entry.path(), &rel, out)?;
+        } else {
+            out.push(rel);
+        }
+    }
+    Ok(())
+}
+
+fn hex_encode_bytes(data: &[u8]) -> String {
+    data.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Stamp the version nibble and RFC 9562 variant bits over 16 bytes, then
+/// write them in the canonical lowercase hyphenated form
+fn format_uuid(mut bytes: [u8; 16], version: u8) -> String {
+    bytes[6] = (bytes[6] & 0x0f) | (version << 4);
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex = hex_encode_bytes(&bytes);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+/// Sixteen random bytes for a UUID. Native builds read them from the OS
+/// CSPRNG, so seed_random cannot make UUIDs repeat. Builds without an OS
+/// source fall back to the interpreter's generator.
+fn random_uuid_bytes(name: &str) -> Result<[u8; 16], String> {
+    let mut bytes = [0u8; 16];
+    #[cfg(feature = "native")]
+    bytes.copy_from_slice(&secure_random_bytes(name, &Value::Integer(16))?);
+    #[cfg(not(feature = "native"))]
+    {
+        let _ = name;
+        with_rng(|rng| {
+            bytes[..8].copy_from_slice(&rng.next_u64().to_be_bytes());
+            bytes[8..].copy_from_slice(&rng.next_u64().to_be_bytes());
+        });
+    }
+    Ok(bytes)
+}
+
+fn hex_decode_str(text: &str) -> Result<Vec<u8>, String> {
+    if !text.len().is_multiple_of(2) {
+        return Err("hex_decode() needs an even number o' hex digits".to_string());
+    }
+    text.as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            std::str::from_utf8(pair)
+                .ok()
+                .and_then(|p| u8::from_str_radix(p, 16).ok())
+                .ok_or_else(|| {
+                    format!(
+                        "hex_decode() found '{}' - that's no' hex",
+                        String::from_utf8_lossy(pair)
+                    )
+                })
+        })
+        .collect()
+}
+
+/// Classic `hexdump -C` layout: offset, sixteen hex bytes, then the ASCII
+fn hexdump_bytes(data: &[u8]) -> String {
+    let mut lines = Vec::new();
+    for (row, chunk) in data.chunks(16).enumerate() {
+        let mut line = format!("{:08x}  ", row * 16);
+        for i in 0..16 {
+            match chunk.get(i) {
+                Some(b) => line.push_str(&format!("{:02x} ", b)),
+                None => line.push_str("   "),
+            }
+            if i == 7 {
+                line.push(' ');
+            }
+        }
+        line.push_str(" |");
+        for &b in chunk {
+            line.push(if b.is_ascii_graphic() || b == b' ' {
+                b as char
+            } else {
+                '.'
+            });
+        }
+        line.push('|');
+        lines.push(line);
+    }
+    lines.join("\n")
+}
+
 #[cfg(feature = "native")]
 fn result_ok(value: Value) -> Value {
     let mut dict = DictValue::new();
@@ -1009,10 +1778,47 @@ fn mono_ms_now() -> i64 {
     start.elapsed().as_millis() as i64
 }
 
+/// Upper bound for DNS lookups and TLS/DTLS handshakes when no `timeout_ms` is given.
 #[cfg(feature = "native")]
-fn make_resolver() -> Result<Resolver, String> {
-    #[cfg(all(test, feature = "native"))]
-    {
+const DEFAULT_NET_TIMEOUT_MS: u64 = 10_000;
+
+#[cfg(feature = "native")]
+fn io_timed_out(e: &std::io::Error) -> bool {
+    matches!(
+        e.kind(),
+        std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+    )
+}
+
+#[cfg(feature = "native")]
+fn resolve_timed_out(e: &trust_dns_resolver::error::ResolveError) -> bool {
+    use trust_dns_resolver::error::ResolveErrorKind;
+    use trust_dns_resolver::proto::error::ProtoErrorKind;
+    match e.kind() {
+        ResolveErrorKind::Timeout => true,
+        ResolveErrorKind::Io(io) => io_timed_out(io),
+        ResolveErrorKind::Proto(proto) => matches!(proto.kind(), ProtoErrorKind::Timeout),
+        _ => false,
+    }
+}
+
+#[cfg(feature = "native")]
+fn resolver_opts(mut opts: ResolverOpts, timeout_ms: u64) -> ResolverOpts {
+    opts.timeout = std::time::Duration::from_millis(timeout_ms);
+    opts.attempts = 1;
+    opts
+}
+
+#[cfg(feature = "native")]
+fn resolver_from_system_conf(timeout_ms: u64) -> std::io::Result<Resolver> {
+    let (config, opts) = trust_dns_resolver::system_conf::read_system_conf()?;
+    Resolver::new(config, resolver_opts(opts, timeout_ms))
+}
+
+#[cfg(feature = "native")]
+fn make_resolver(timeout_ms: u64) -> Result<Resolver, String> {
+    #[cfg(all(test, feature = "native"))]
+    {
         if dns_take_fail_next_resolver() {
             return Err("DNS resolver init failed: injected".to_string());
         }
@@ -1026,18 +1832,21 @@ fn make_resolver() -> Result<Resolver, String> {
                     "injected system conf error",
                 ))
             } else {
-                Resolver::from_system_conf()
+                resolver_from_system_conf(timeout_ms)
             }
         }
         #[cfg(not(all(test, feature = "native")))]
         {
-            Resolver::from_system_conf()
+            resolver_from_system_conf(timeout_ms)
         }
     };
 
     let resolver = match from_system_conf {
         Ok(resolver) => Ok(resolver),
-        Err(_) => Resolver::new(ResolverConfig::default(), ResolverOpts::default()),
+        Err(_) => Resolver::new(
+            ResolverConfig::default(),
+            resolver_opts(ResolverOpts::default(), timeout_ms),
+        ),
     };
     let resolver = {
         #[cfg(all(test, feature = "native"))]
@@ -1151,6 +1960,7 @@ struct TlsConfigData {
     ca_pem: Option<String>,
     cert_pem: Option<String>,
     key_pem: Option<String>,
+    timeout_ms: u64,
 }
 
 #[cfg(any(feature = "native", test))]
@@ -1203,6 +2013,116 @@ fn dict_get_u16(dict: &DictValue, key: &str) -> Option<u16> {
         })
 }
 
+#[cfg(feature = "native")]
+fn timeout_ms_from_value(value: Option<&Value>) -> Result<u64, String> {
+    match value {
+        None | Some(Value::Nil) => Ok(DEFAULT_NET_TIMEOUT_MS),
+        Some(Value::Integer(n)) if *n > 0 => Ok(*n as u64),
+        Some(Value::Float(f)) if *f > 0.0 => Ok((*f as u64).max(1)),
+        Some(_) => Err("timeout_ms must be a positive number".to_string()),
+    }
+}
+
+/// A digest built-in's name, its hex twin's name, and the OpenSSL digest behind them
+#[cfg(feature = "native")]
+type DigestEntry = (&'static str, &'static str, fn() -> MessageDigest);
+
+#[cfg(feature = "native")]
+fn digest_value(name: &str, md: MessageDigest, value: &Value) -> Result<Vec<u8>, String> {
+    let digest = match value {
+        Value::Bytes(b) => openssl::hash::hash(md, &b.borrow()),
+        Value::String(s) => openssl::hash::hash(md, s.as_bytes()),
+        _ => return Err(format!("{}() expects bytes or a string", name)),
+    };
+    digest
+        .map(|d| d.to_vec())
+        .map_err(|e| format!("{}() failed: {}", name, e))
+}
+
+#[cfg(feature = "native")]
+fn bytes_or_string_value(value: &Value) -> Option<Vec<u8>> {
+    match value {
+        Value::Bytes(b) => Some(b.borrow().clone()),
+        Value::String(s) => Some(s.as_bytes().to_vec()),
+        _ => None,
+    }
+}
+
+/// Most bytes a single random_bytes()/random_hex() call will return
+#[cfg(feature = "native")]
+const MAX_RANDOM_BYTES: i64 = 1 << 20;
+
+/// `n` bytes from the OS CSPRNG - unlike `with_rng`, seed_random cannot make these repeat
+#[cfg(feature = "native")]
+fn secure_random_bytes(name: &str, n: &Value) -> Result<Vec<u8>, String> {
+    let n = match n {
+        Value::Integer(n) => *n,
+        _ => return Err(format!("{}() expects an integer count", name)),
+    };
+    if !(0..=MAX_RANDOM_BYTES).contains(&n) {
+        return Err(format!(
+            "{}() count must be atween 0 an' {}, no' {}",
+            name, MAX_RANDOM_BYTES, n
+        ));
+    }
+    let mut bytes = vec![0u8; n as usize];
+    openssl::rand::rand_bytes(&mut bytes)
+        .map_err(|e| format!("{}() cannae get random bytes: {}", name, e))?;
+    Ok(bytes)
+}
+
+/// Longest key pbkdf2() will derive in one go
+#[cfg(feature = "native")]
+const MAX_DERIVED_KEY_BYTES: i64 = 1024;
+
+/// PBKDF2-HMAC-SHA256 over `pbkdf2(password, salt, iterations, length)`
+#[cfg(feature = "native")]
+fn pbkdf2_value(args: &[Value]) -> Result<Vec<u8>, String> {
+    let password =
+        bytes_or_string_value(&args[0]).ok_or("pbkdf2() expects password as bytes or a string")?;
+    let salt =
+        bytes_or_string_value(&args[1]).ok_or("pbkdf2() expects salt as bytes or a string")?;
+    let iterations = match &args[2] {
+        Value::Integer(n) if *n > 0 => *n as usize,
+        Value::Integer(_) => return Err("pbkdf2() needs at least 1 iteration".to_string()),
+        _ => return Err("pbkdf2() expects an integer iteration count".to_string()),
+    };
+    let length = match &args[3] {
+        Value::Integer(n) if (1..=MAX_DERIVED_KEY_BYTES).contains(n) => *n as usize,
+        _ => {
+            return Err(format!(
+                "pbkdf2() key length must be atween 1 an' {} bytes",
+                MAX_DERIVED_KEY_BYTES
+            ))
+        }
+    };
+    let mut key = vec![0u8; length];
+    openssl::pkcs5::pbkdf2_hmac(
+        &password,
+        &salt,
+        iterations,
+        MessageDigest::sha256(),
+        &mut key,
+    )
+    .map_err(|e| format!("pbkdf2() failed: {}", e))?;
+    Ok(key)
+}
+
+#[cfg(feature = "native")]
+fn hmac_sha256_value(name: &str, key: &Value, message: &Value) -> Result<Vec<u8>, String> {
+    let key = bytes_or_string_value(key)
+        .ok_or_else(|| format!("{}() expects key as bytes or a string", name))?;
+    let message = bytes_or_string_value(message)
+        .ok_or_else(|| format!("{}() expects message as bytes or a string", name))?;
+    PKey::hmac(&key)
+        .and_then(|pkey| {
+            let mut signer = Signer::new(MessageDigest::sha256(), &pkey)?;
+            signer.update(&message)?;
+            signer.sign_to_vec()
+        })
+        .map_err(|e| format!("{}() failed: {}", name, e))
+}
+
 #[cfg(feature = "native")]
 fn tls_config_from_value(value: &Value) -> Result<TlsConfigData, String> {
     if matches!(value, Value::Nil) {
@@ -1213,6 +2133,7 @@ fn tls_config_from_value(value: &Value) -> Result<TlsConfigData, String> {
             ca_pem: None,
             cert_pem: None,
             key_pem: None,
+            timeout_ms: DEFAULT_NET_TIMEOUT_MS,
         });
     }
     let dict = match value {
@@ -1239,6 +2160,8 @@ fn tls_config_from_value(value: &Value) -> Result<TlsConfigData, String> {
     let ca_pem = dict_get_string(&dict, "ca_pem").filter(|s| !s.is_empty());
     let cert_pem = dict_get_string(&dict, "cert_pem").filter(|s| !s.is_empty());
     let key_pem = dict_get_string(&dict, "key_pem").filter(|s| !s.is_empty());
+    let timeout_ms =
+        timeout_ms_from_value(dict.get(&Value::String("timeout_ms".to_string())))?;
 
     Ok(TlsConfigData {
         mode,
@@ -1247,6 +2170,7 @@ fn tls_config_from_value(value: &Value) -> Result<TlsConfigData, String> {
         ca_pem,
         cert_pem,
         key_pem,
+        timeout_ms,
     })
 }
 
@@ -1387,6 +2311,7 @@ fn dtls_config_from_value(value: &Value) -> Result<DtlsConfigData, String> {
             remote_host: None,
             remote_port: None,
             srtp_profiles: vec![SrtpProfile::Aes128CmSha180],
+            timeout_ms: DEFAULT_NET_TIMEOUT_MS,
         });
     }
     let dict = match value {
@@ -1429,6 +2354,8 @@ fn dtls_config_from_value(value: &Value) -> Result<DtlsConfigData, String> {
     if profiles.is_empty() {
         profiles.push(SrtpProfile::Aes128CmSha180);
     }
+    let timeout_ms =
+        timeout_ms_from_value(dict.get(&Value::String("timeout_ms".to_string())))?;
 
     Ok(DtlsConfigData {
         mode,
@@ -1440,9 +2367,29 @@ fn dtls_config_from_value(value: &Value) -> Result<DtlsConfigData, String> {
         remote_host,
         remote_port,
         srtp_profiles: profiles,
+        timeout_ms,
     })
 }
 
+/// The negotiated cipher suite and the peer certificate's SHA-256 fingerprint,
+/// written the way SDP `a=fingerprint` lines want it (`AB:CD:...`).
+#[cfg(feature = "native")]
+fn dtls_session_info<S>(stream: &DtlsStream<S>) -> (Option<String>, Option<String>) {
+    let ssl = stream.0.ssl();
+    let cipher = ssl.current_cipher().map(|c| c.name().to_string());
+    let fingerprint = ssl
+        .peer_certificate()
+        .and_then(|cert| cert.digest(MessageDigest::sha256()).ok())
+        .map(|digest| {
+            digest
+                .iter()
+                .map(|b| format!("{:02X}", b))
+                .collect::<Vec<_>>()
+                .join(":")
+        });
+    (cipher, fingerprint)
+}
+
 #[cfg(feature = "native")]
 fn identity_from_pem(cert_pem: &str, key_pem: &str) -> Result<Identity, String> {
     let cert =
@@ -1601,6 +2548,25 @@ impl std::fmt::Display for StackFrame {
     }
 }
 
+/// How many frames a backtrace shows before it summarises the rest
+pub const MAX_BACKTRACE_FRAMES: usize = 16;
+
+/// Format frames (innermost last) as a trace, most recent call first, the
+/// same shape `stacktrace()` gives. Over-deep stacks get cut off at
+/// `max_frames` with a "… N more frames" line.
+pub fn format_stack_trace(frames: &[StackFrame], max_frames: usize) -> String {
+    let mut lines: Vec<String> = frames
+        .iter()
+        .rev()
+        .take(max_frames)
+        .map(|f| f.to_string())
+        .collect();
+    if frames.len() > max_frames {
+        lines.push(format!("  … {} more frames", frames.len() - max_frames));
+    }
+    lines.join("\n")
+}
+
 /// Global shadow call stack for crash reporting
 static SHADOW_STACK: Mutex<Vec<StackFrame>> = Mutex::new(Vec::new());
 static CURRENT_STACK_FILE: Mutex<String> = Mutex::new(String::new());
@@ -1710,6 +2676,33 @@ pub fn is_crash_handling_enabled() -> bool {
     CRASH_HANDLING_ENABLED.load(Ordering::Relaxed)
 }
 
+/// Where a panic most likely came from, so the crash handler can tell the user
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PanicOrigin {
+    /// A built-in fell over on what the script passed it
+    UserCode,
+    /// The interpreter's own state is broken - a genuine bug
+    Internal,
+}
+
+/// Classify a panic message for the crash handler. Poisoned locks and RefCell
+/// borrow clashes mean the interpreter's state is corrupt no matter where they
+/// happen; any other panic inside a native built-in was set off by the script.
+#[cfg_attr(coverage, allow(dead_code))]
+pub fn classify_panic(message: &str) -> PanicOrigin {
+    classify_panic_at_depth(message, NATIVE_CALL_DEPTH.load(Ordering::Relaxed))
+}
+
+fn classify_panic_at_depth(message: &str, native_depth: usize) -> PanicOrigin {
+    const CORRUPTION_MARKERS: &[&str] = &["already borrowed", "already mutably borrowed", "poison"];
+    let message = message.to_lowercase();
+    if CORRUPTION_MARKERS.iter().any(|m| message.contains(m)) || native_depth == 0 {
+        PanicOrigin::Internal
+    } else {
+        PanicOrigin::UserCode
+    }
+}
+
 /// Get the global log level
 pub fn get_global_log_level() -> LogLevel {
     logging::get_global_log_level()
@@ -1777,12 +2770,27 @@ fn dict_get(dict: &DictValue, key: &str) -> Option<Value> {
     dict.get(&Value::String(key.to_string())).cloned()
 }
 
+/// What happened when a generator ran one of its statements
+enum GeneratorStep {
+    /// Carry on with whatever frame is on top
+    Next,
+    /// `gie_back` - pause here and pass this value out
+    Yield(Value),
+    /// `gie` - the generator's finished
+    Finish,
+    Break,
+    Continue,
+}
+
 /// Control flow signals
 #[derive(Debug)]
 enum ControlFlow {
     Return(Value),
     Break,
     Continue,
+    /// `gie` of a direct self-call - rerun the current function with these
+    /// arguments instead of recursing
+    TailCall(Vec<Value>),
 }
 
 /// Trace mode fer debugging - shows step-by-step execution
@@ -1797,11 +2805,31 @@ pub enum TraceMode {
     Verbose,
 }
 
+/// Callback that receives each `blether` line as it's printed
+pub type OutputSink = Box<dyn FnMut(&str)>;
+
+/// How many times a loop goes round before the tracer stops showing every step
+pub const DEFAULT_TRACE_LOOP_CAP: usize = 1000;
+
 /// The interpreter - runs mdhavers programs
 pub struct Interpreter {
     pub globals: Rc<RefCell<Environment>>,
     environment: Rc<RefCell<Environment>>,
     output: Vec<String>,
+    /// Where `blether` lines go the moment they're printed (stdout if unset)
+    output_sink: Option<OutputSink>,
+    /// Where `speir` reads its lines from (stdin if unset)
+    input_source: Option<Box<dyn BufRead>>,
+    /// Whether `blether` lines are also kept for get_output/take_output
+    keep_output: bool,
+    /// Lines written before the rest are dropped (None means no cap)
+    output_limit: Option<usize>,
+    /// Lines written since the output limit was last set
+    output_lines: usize,
+    /// Set once the output limit is hit and the truncation marker written
+    output_truncated: bool,
+    /// Generator for the random built-ins
+    rng: Prng,
     /// Cache of module exports keyed by canonical module path
     module_cache: HashMap<PathBuf, HashMap<String, Value>>,
     /// Stack of modules currently being loaded (for circular import detection)
@@ -1810,16 +2838,44 @@ pub struct Interpreter {
     current_dir: PathBuf,
     /// Whether the prelude has been loaded
     prelude_loaded: bool,
+    /// The globals as they were with just the built-ins and the prelude, for
+    /// reset_environment to put back
+    baseline_globals: HashMap<String, Value>,
     /// Trace mode fer debugging
     trace_mode: TraceMode,
     /// Current trace indentation level
     trace_depth: usize,
+    /// Where trace lines go (stderr if unset)
+    trace_sink: Option<OutputSink>,
+    /// Loop iterations traced in full before the tracer collapses to a
+    /// "still loopin'" line (0 means never collapse)
+    trace_loop_cap: usize,
+    /// Set while a loop is past the cap, so its body traces nothing
+    trace_quiet: bool,
     /// Logger configuration and sinks
     logger: logging::LoggerCore,
     /// Optional callback hook for log events
     log_callback: Option<Value>,
     /// Current source file name for log messages
     current_file: String,
+    /// This interpreter's own call stack: function name + call-site line
+    call_stack: Vec<StackFrame>,
+    /// The call stack at the point an error first escaped a function, kept
+    /// until it reaches `interpret` or gets caught
+    error_trace: Option<Vec<StackFrame>>,
+    /// The function whose body is running in tail position, if any; a `gie`
+    /// calling it again becomes a loop. Only ever compared, never dereferenced
+    tail_call_target: Option<*const HaversFunction>,
+    /// Statements and expressions run since the step limit was last set
+    steps: u64,
+    /// Steps allowed before the program is stopped with ExecutionTimeout
+    step_limit: Option<u64>,
+    /// The biggest any one list, dict, creel, string or bytes value may get
+    /// before the program is stopped with OutOfMemory
+    memory_guard: Option<usize>,
+    /// Whether integer arithmetic that overflows i64 carries on with big
+    /// integers instead of failing with IntegerOverflow
+    big_integers: bool,
 }
 
 impl Interpreter {
@@ -1835,6 +2891,9 @@ impl Interpreter {
         // Register graphics functions (if feature enabled)
         crate::graphics::register_graphics_functions(&globals);
 
+        // Register canvas drawing functions (stubs unless the feature is on)
+        crate::canvas::register_canvas_functions(&globals);
+
         // Check fer MDH_LOG or MDH_LOG_LEVEL environment variables
         #[cfg(not(target_arch = "wasm32"))]
         {
@@ -1864,19 +2923,38 @@ impl Interpreter {
             }
         };
 
+        let baseline_globals = globals.borrow().get_exports();
         Interpreter {
             globals: globals.clone(),
             environment: globals,
             output: Vec::new(),
+            output_sink: None,
+            input_source: None,
+            keep_output: true,
+            output_limit: None,
+            output_lines: 0,
+            output_truncated: false,
+            rng: Prng::from_entropy(),
             module_cache: HashMap::new(),
             module_in_progress: Vec::new(),
             current_dir,
             prelude_loaded: false,
+            baseline_globals,
             trace_mode: TraceMode::Off,
             trace_depth: 0,
+            trace_sink: None,
+            trace_loop_cap: DEFAULT_TRACE_LOOP_CAP,
+            trace_quiet: false,
             logger: logging::LoggerCore::new(),
             log_callback: None,
             current_file: "<repl>".to_string(),
+            call_stack: Vec::new(),
+            error_trace: None,
+            tail_call_target: None,
+            steps: 0,
+            step_limit: None,
+            memory_guard: None,
+            big_integers: false,
         }
     }
 
@@ -2130,22 +3208,123 @@ impl Interpreter {
         self.trace_mode
     }
 
+    /// Trace every step of the first `cap` iterations of a loop, then only
+    /// a "still loopin'" line every `cap` iterations after that (0 = no cap)
+    pub fn set_trace_loop_cap(&mut self, cap: usize) {
+        self.trace_loop_cap = cap;
+    }
+
+    /// Send trace lines to `sink` instead of stderr
+    pub fn set_trace_sink(&mut self, sink: OutputSink) {
+        self.trace_sink = Some(sink);
+    }
+
+    /// Stop the program with `HaversError::ExecutionTimeout` once it has run
+    /// more than `limit` statements and expressions (`None` lifts the limit).
+    /// The limit is checked before each statement - every loop, lambdas
+    /// included, runs at least one - and either way the count starts again
+    /// from zero.
+    pub fn set_step_limit(&mut self, limit: Option<u64>) {
+        self.step_limit = limit;
+        self.steps = 0;
+    }
+
+    /// Stop the program with `HaversError::OutOfMemory` once a built-in, an
+    /// operator or a list comprehension makes (or is passed) a list, dict,
+    /// creel or bytes value with more than `limit` items, or a string longer
+    /// than `limit` bytes (`None` lifts the guard)
+    pub fn set_memory_guard(&mut self, limit: Option<usize>) {
+        self.memory_guard = limit;
+    }
+
+    /// Let integer arithmetic that would overflow i64 carry on with big
+    /// integers (`Value::BigInt`) instead of failing with IntegerOverflow.
+    /// Results that fit back in an i64 are plain integers again.
+    pub fn set_big_integers(&mut self, enabled: bool) {
+        self.big_integers = enabled;
+    }
+
+    /// Statements and expressions run since the step limit was last set
+    pub fn steps_taken(&self) -> u64 {
+        self.steps
+    }
+
+    /// Count a step, saying whether that's over the limit
+    fn take_step(&mut self) -> bool {
+        self.steps += 1;
+        self.step_limit.is_some_and(|limit| self.steps > limit)
+    }
+
+    /// Fail if `value` is bigger than the memory guard allows
+    fn guard_memory(&self, value: &Value, line: usize) -> HaversResult<()> {
+        let Some(limit) = self.memory_guard else {
+            return Ok(());
+        };
+        let size = match value {
+            Value::String(s) => s.len(),
+            Value::List(items) => items.borrow().len(),
+            Value::Dict(dict) => dict.borrow().len(),
+            Value::Set(set) => set.borrow().len(),
+            Value::Bytes(bytes) => bytes.borrow().len(),
+            _ => return Ok(()),
+        };
+        if size > limit {
+            return Err(HaversError::OutOfMemory { line });
+        }
+        Ok(())
+    }
+
     /// Print a trace message with proper indentation and Scottish flair
-    fn trace(&self, msg: &str) {
-        if self.trace_mode != TraceMode::Off {
+    fn trace(&mut self, msg: &str) {
+        if self.trace_mode != TraceMode::Off && !self.trace_quiet {
             let indent = "  ".repeat(self.trace_depth);
-            eprintln!("\x1b[33m🏴󠁧󠁢󠁳󠁣󠁴󠁿 {}{}\x1b[0m", indent, msg);
+            self.emit_trace(format!("\x1b[33m🏴󠁧󠁢󠁳󠁣󠁴󠁿 {}{}\x1b[0m", indent, msg));
         }
     }
 
     /// Print a verbose trace message (only in verbose mode)
-    fn trace_verbose(&self, msg: &str) {
-        if self.trace_mode == TraceMode::Verbose {
+    fn trace_verbose(&mut self, msg: &str) {
+        if self.trace_mode == TraceMode::Verbose && !self.trace_quiet {
             let indent = "  ".repeat(self.trace_depth);
-            eprintln!("\x1b[36m   {}{}\x1b[0m", indent, msg);
+            self.emit_trace(format!("\x1b[36m   {}{}\x1b[0m", indent, msg));
         }
     }
 
+    fn emit_trace(&mut self, line: String) {
+        match self.trace_sink.as_mut() {
+            Some(sink) => sink(&line),
+            None => eprintln!("{}", line),
+        }
+    }
+
+    /// An undefined-variable error, with a hint when a name in scope is a near miss
+    fn undefined_variable(&self, name: &str, line: usize) -> HaversError {
+        let names = self.environment.borrow().names();
+        HaversError::UndefinedVariable {
+            name: name.to_string(),
+            line,
+        }
+        .with_did_ye_mean(name, names.iter().map(String::as_str))
+    }
+
+    /// Note the start of a loop iteration. Past the cap the loop goes quiet,
+    /// bar a "still loopin'" line every `cap` iterations. `outer_quiet` is
+    /// whether the loop was already quiet when it started.
+    fn trace_loop_iteration(&mut self, line: usize, iteration: usize, outer_quiet: bool) {
+        let cap = self.trace_loop_cap;
+        if self.trace_mode == TraceMode::Off || cap == 0 || iteration <= cap {
+            return;
+        }
+        if iteration == cap + 1 || iteration.is_multiple_of(cap) {
+            self.trace_quiet = outer_quiet;
+            self.trace(&format!(
+                "[line {}] … still loopin' (iteration {})",
+                line, iteration
+            ));
+        }
+        self.trace_quiet = true;
+    }
+
     /// Create an interpreter with a specific working directory
     #[allow(dead_code)]
     pub fn with_dir<P: AsRef<Path>>(dir: P) -> Self {
@@ -2236,6 +3415,7 @@ impl Interpreter {
                             self.execute_stmt(stmt)?;
                         }
                         self.prelude_loaded = true;
+                        self.baseline_globals = self.globals.borrow().get_exports();
                         return Ok(());
                     }
                     Err(e) => {
@@ -2243,6 +3423,7 @@ impl Interpreter {
                         return Err(HaversError::ParseError {
                             message: format!("Prelude has errors (this shouldnae happen!): {}", e),
                             line: 1,
+                            span: None,
                         });
                     }
                 }
@@ -2261,6 +3442,23 @@ impl Interpreter {
         self.prelude_loaded
     }
 
+    /// Forget everything the user's code defined - variables, functions,
+    /// classes and brought-in modules - but keep the built-ins, the prelude
+    /// (if it's loaded) and the settings like trace mode and the current
+    /// directory, so there's no prelude to load again. A built-in or
+    /// prelude name the user redefined gets its original back.
+    pub fn reset_environment(&mut self) {
+        self.globals
+            .borrow_mut()
+            .set_values(self.baseline_globals.clone());
+        self.environment = self.globals.clone();
+        self.module_cache.clear();
+        self.module_in_progress.clear();
+        self.call_stack.clear();
+        self.error_trace = None;
+        self.tail_call_target = None;
+    }
+
     fn define_natives(globals: &Rc<RefCell<Environment>>) {
         // get_key - read a single key press (raw input)
         // Not reliably testable under source-based coverage (non-TTY), so exclude from coverage builds.
@@ -2587,6 +3785,39 @@ impl Interpreter {
             ))),
         );
 
+        // hex_encode - bytes to a lowercase hex string
+        globals.borrow_mut().define(
+            "hex_encode".to_string(),
+            Value::NativeFunction(Rc::new(NativeFunction::new("hex_encode", 1, |args| {
+                match &args[0] {
+                    Value::Bytes(b) => Ok(Value::String(hex_encode_bytes(&b.borrow()))),
+                    _ => Err("hex_encode() expects bytes".to_string()),
+                }
+            }))),
+        );
+
+        // hex_decode - hex string back to bytes
+        globals.borrow_mut().define(
+            "hex_decode".to_string(),
+            Value::NativeFunction(Rc::new(NativeFunction::new("hex_decode", 1, |args| {
+                match &args[0] {
+                    Value::String(s) => Ok(Value::Bytes(Rc::new(RefCell::new(hex_decode_str(s)?)))),
+                    _ => Err("hex_decode() expects a string".to_string()),
+                }
+            }))),
+        );
+
+        // hexdump - offset/hex/ASCII view of a byte buffer
+        globals.borrow_mut().define(
+            "hexdump".to_string(),
+            Value::NativeFunction(Rc::new(NativeFunction::new("hexdump", 1, |args| {
+                match &args[0] {
+                    Value::Bytes(b) => Ok(Value::String(hexdump_bytes(&b.borrow()))),
+                    _ => Err("hexdump() expects bytes".to_string()),
+                }
+            }))),
+        );
+
         #[cfg(all(feature = "native", unix))]
         {
             // socket_udp - create UDP socket
@@ -3159,6 +4390,91 @@ impl Interpreter {
             );
         }
 
+        #[cfg(feature = "native")]
+        {
+            // sha256/sha1/md5(data) -> digest bytes, *_hex(data) -> lowercase hex string
+            let digests: [DigestEntry; 3] = [
+                ("sha256", "sha256_hex", MessageDigest::sha256),
+                ("sha1", "sha1_hex", MessageDigest::sha1),
+                ("md5", "md5_hex", MessageDigest::md5),
+            ];
+            for (name, hex_name, md) in digests {
+                globals.borrow_mut().define(
+                    name.to_string(),
+                    Value::NativeFunction(Rc::new(NativeFunction::new(name, 1, move |args| {
+                        let digest = digest_value(name, md(), &args[0])?;
+                        Ok(Value::Bytes(Rc::new(RefCell::new(digest))))
+                    }))),
+                );
+                globals.borrow_mut().define(
+                    hex_name.to_string(),
+                    Value::NativeFunction(Rc::new(NativeFunction::new(hex_name, 1, move |args| {
+                        let digest = digest_value(hex_name, md(), &args[0])?;
+                        Ok(Value::String(hex_encode_bytes(&digest)))
+                    }))),
+                );
+            }
+
+            // hmac_sha256(key, message) -> MAC bytes
+            globals.borrow_mut().define(
+                "hmac_sha256".to_string(),
+                Value::NativeFunction(Rc::new(NativeFunction::new("hmac_sha256", 2, |args| {
+                    let mac = hmac_sha256_value("hmac_sha256", &args[0], &args[1])?;
+                    Ok(Value::Bytes(Rc::new(RefCell::new(mac))))
+                }))),
+            );
+
+            // hmac_sha256_hex(key, message) -> lowercase hex string
+            globals.borrow_mut().define(
+                "hmac_sha256_hex".to_string(),
+                Value::NativeFunction(Rc::new(NativeFunction::new(
+                    "hmac_sha256_hex",
+                    2,
+                    |args| {
+                        let mac = hmac_sha256_value("hmac_sha256_hex", &args[0], &args[1])?;
+                        Ok(Value::String(hex_encode_bytes(&mac)))
+                    },
+                ))),
+            );
+
+            // ct_eq(a, b) -> bool, comparing contents in constant time
+            globals.borrow_mut().define(
+                "ct_eq".to_string(),
+                Value::NativeFunction(Rc::new(NativeFunction::new("ct_eq", 2, |args| {
+                    match (bytes_or_string_value(&args[0]), bytes_or_string_value(&args[1])) {
+                        (Some(a), Some(b)) => Ok(Value::Bool(
+                            a.len() == b.len() && openssl::memcmp::eq(&a, &b),
+                        )),
+                        _ => Err("ct_eq() expects bytes or strings".to_string()),
+                    }
+                }))),
+            );
+
+            // random_bytes(n) -> n bytes from the OS CSPRNG, random_hex(n) -> the same as hex
+            globals.borrow_mut().define(
+                "random_bytes".to_string(),
+                Value::NativeFunction(Rc::new(NativeFunction::new("random_bytes", 1, |args| {
+                    let bytes = secure_random_bytes("random_bytes", &args[0])?;
+                    Ok(Value::Bytes(Rc::new(RefCell::new(bytes))))
+                }))),
+            );
+            globals.borrow_mut().define(
+                "random_hex".to_string(),
+                Value::NativeFunction(Rc::new(NativeFunction::new("random_hex", 1, |args| {
+                    let bytes = secure_random_bytes("random_hex", &args[0])?;
+                    Ok(Value::String(hex_encode_bytes(&bytes)))
+                }))),
+            );
+
+            // pbkdf2(password, salt, iterations, length) -> derived key bytes (HMAC-SHA256)
+            globals.borrow_mut().define(
+                "pbkdf2".to_string(),
+                Value::NativeFunction(Rc::new(NativeFunction::new("pbkdf2", 4, |args| {
+                    Ok(Value::Bytes(Rc::new(RefCell::new(pbkdf2_value(&args)?))))
+                }))),
+            );
+        }
+
         #[cfg(feature = "native")]
         {
             // dns_lookup(host) -> result {ok,value:[ips]}
@@ -3181,10 +4497,13 @@ impl Interpreter {
                 }))),
             );
 
-            // dns_srv(service, domain) -> result {ok,value:[{priority,weight,port,target}]}
+            // dns_srv(service, domain, timeout_ms?) -> result {ok,value:[{priority,weight,port,target}]}
             globals.borrow_mut().define(
                 "dns_srv".to_string(),
-                Value::NativeFunction(Rc::new(NativeFunction::new("dns_srv", 2, |args| {
+                Value::NativeFunction(Rc::new(NativeFunction::new("dns_srv", usize::MAX, |args| {
+                    if args.len() < 2 || args.len() > 3 {
+                        return Err("dns_srv() expects 2 or 3 arguments".to_string());
+                    }
                     let service = match &args[0] {
                         Value::String(s) => s.clone(),
                         _ => return Err("dns_srv() expects service string".to_string()),
@@ -3200,12 +4519,17 @@ impl Interpreter {
                         let d = domain.trim_start_matches('.');
                         format!("{}.{}", s, d)
                     };
-                    let resolver = match make_resolver() {
+                    let timeout_ms = timeout_ms_from_value(args.get(2))
+                        .map_err(|e| format!("dns_srv() {}", e))?;
+                    let resolver = match make_resolver(timeout_ms) {
                         Ok(resolver) => resolver,
                         Err(e) => return Ok(result_err(format!("dns_srv() {}", e), -1)),
                     };
 	                    let lookup = match resolver_lookup(&resolver, name.as_str(), RecordType::SRV) {
 	                        Ok(lookup) => lookup,
+	                        Err(e) if resolve_timed_out(&e) => {
+	                            return Ok(result_err("Timed oot".to_string(), -1))
+	                        }
 	                        Err(e) => {
 	                            return Ok(result_err(
 	                                format!("dns_srv() DNS SRV lookup failed: {}", e),
@@ -3218,20 +4542,28 @@ impl Interpreter {
 	                }))),
 	            );
 
-            // dns_naptr(domain) -> result {ok,value:[{order,preference,flags,service,regexp,replacement}]}
+            // dns_naptr(domain, timeout_ms?) -> result {ok,value:[{order,preference,flags,service,regexp,replacement}]}
             globals.borrow_mut().define(
                 "dns_naptr".to_string(),
-                Value::NativeFunction(Rc::new(NativeFunction::new("dns_naptr", 1, |args| {
+                Value::NativeFunction(Rc::new(NativeFunction::new("dns_naptr", usize::MAX, |args| {
+                    if args.is_empty() || args.len() > 2 {
+                        return Err("dns_naptr() expects 1 or 2 arguments".to_string());
+                    }
                     let domain = match &args[0] {
                         Value::String(s) => s.clone(),
                         _ => return Err("dns_naptr() expects domain string".to_string()),
                     };
-                    let resolver = match make_resolver() {
+                    let timeout_ms = timeout_ms_from_value(args.get(1))
+                        .map_err(|e| format!("dns_naptr() {}", e))?;
+                    let resolver = match make_resolver(timeout_ms) {
                         Ok(resolver) => resolver,
                         Err(e) => return Ok(result_err(format!("dns_naptr() {}", e), -1)),
                     };
 	                    let lookup = match resolver_lookup(&resolver, domain.as_str(), RecordType::NAPTR) {
 	                        Ok(lookup) => lookup,
+	                        Err(e) if resolve_timed_out(&e) => {
+	                            return Ok(result_err("Timed oot".to_string(), -1))
+	                        }
 	                        Err(e) => {
 	                            return Ok(result_err(
 	                                format!("dns_naptr() DNS NAPTR lookup failed: {}", e),
@@ -3260,6 +4592,8 @@ impl Interpreter {
                             client_config: Some(client_config),
                             server_config: None,
                             stream: None,
+                            handshake: None,
+                            timeout_ms: cfg.timeout_ms,
                         }
                     } else {
                         let server_config = build_server_config(&cfg)?;
@@ -3269,6 +4603,8 @@ impl Interpreter {
                             client_config: None,
                             server_config: Some(server_config),
                             stream: None,
+                            handshake: None,
+                            timeout_ms: cfg.timeout_ms,
                         }
                     };
                     let id = register_tls(session);
@@ -3295,11 +4631,24 @@ impl Interpreter {
 	                    }
 	                    let mut stream = unsafe { std::net::TcpStream::from_raw_fd(dup_fd) };
 	                    let _ = stream.set_nonblocking(false);
+	                    let handshake_err = |e: std::io::Error| {
+	                        if io_timed_out(&e) {
+	                            "Timed oot".to_string()
+	                        } else {
+	                            format!("TLS handshake failed: {}", e)
+	                        }
+	                    };
 
 	                    let res = with_tls_mut(tls_id, move |session| {
 	                        if session.stream.is_some() {
 	                            return Err("TLS session already connected".to_string());
 	                        }
+	                        if session.handshake.is_some() {
+	                            return Err("TLS handshake already under way".to_string());
+	                        }
+	                        let timeout = Some(std::time::Duration::from_millis(session.timeout_ms));
+	                        let _ = stream.set_read_timeout(timeout);
+	                        let _ = stream.set_write_timeout(timeout);
 
 	                        match session.mode {
 	                            TlsMode::Client => {
@@ -3332,9 +4681,11 @@ impl Interpreter {
                                 };
 	                                while conn.is_handshaking() {
 	                                    if let Err(e) = conn.complete_io(&mut stream) {
-	                                        return Err(format!("TLS handshake failed: {}", e));
+	                                        return Err(handshake_err(e));
 	                                    }
 	                                }
+	                                let _ = stream.set_read_timeout(None);
+	                                let _ = stream.set_write_timeout(None);
 	                                session.stream =
 	                                    Some(TlsStream::Client(StreamOwned::new(conn, stream)));
 	                            }
@@ -3364,9 +4715,11 @@ impl Interpreter {
 	                                };
 	                                while conn.is_handshaking() {
 	                                    if let Err(e) = conn.complete_io(&mut stream) {
-	                                        return Err(format!("TLS handshake failed: {}", e));
+	                                        return Err(handshake_err(e));
 	                                    }
 	                                }
+	                                let _ = stream.set_read_timeout(None);
+	                                let _ = stream.set_write_timeout(None);
 	                                session.stream =
 	                                    Some(TlsStream::Server(StreamOwned::new(conn, stream)));
 	                            }
@@ -3381,6 +4734,69 @@ impl Interpreter {
                 }))),
             );
 
+            // tls_connect_start(tls, sock) -> result {ok,value:status}; the socket goes
+            // non-blocking and the caller drives the rest with tls_handshake_step
+            globals.borrow_mut().define(
+                "tls_connect_start".to_string(),
+                Value::NativeFunction(Rc::new(NativeFunction::new(
+                    "tls_connect_start",
+                    2,
+                    |args| {
+                        let tls_id = args[0]
+                            .as_integer()
+                            .ok_or("tls_connect_start() expects TLS handle")?;
+                        let sock_id = args[1]
+                            .as_integer()
+                            .ok_or("tls_connect_start() expects socket id")?;
+                        let entry = get_socket(sock_id).ok_or("Unknown socket handle")?;
+                        let dup_fd = unsafe { libc::dup(entry.fd) };
+                        if dup_fd < 0 {
+                            let err = std::io::Error::last_os_error();
+                            let code = err.raw_os_error().unwrap_or(-1) as i64;
+                            return Ok(result_err(err.to_string(), code));
+                        }
+                        let stream = unsafe { std::net::TcpStream::from_raw_fd(dup_fd) };
+
+                        let res = with_tls_mut(tls_id, move |session| {
+                            if session.stream.is_some() {
+                                return Err("TLS session already connected".to_string());
+                            }
+                            if session.handshake.is_some() {
+                                return Err("TLS handshake already under way".to_string());
+                            }
+                            let conn = tls_new_connection(session)?;
+                            stream
+                                .set_nonblocking(true)
+                                .map_err(|e| format!("TLS handshake failed: {}", e))?;
+                            session.handshake = Some(TlsHandshake { conn, stream });
+                            tls_handshake_step_session(session)
+                        });
+                        match res {
+                            Ok(status) => Ok(result_ok(Value::String(status.to_string()))),
+                            Err(e) => Ok(result_err(e, -1)),
+                        }
+                    },
+                ))),
+            );
+
+            // tls_handshake_step(tls) -> result {ok,value:"done"|"want_read"|"want_write"}
+            globals.borrow_mut().define(
+                "tls_handshake_step".to_string(),
+                Value::NativeFunction(Rc::new(NativeFunction::new(
+                    "tls_handshake_step",
+                    1,
+                    |args| {
+                        let tls_id = args[0]
+                            .as_integer()
+                            .ok_or("tls_handshake_step() expects TLS handle")?;
+                        match with_tls_mut(tls_id, tls_handshake_step_session) {
+                            Ok(status) => Ok(result_ok(Value::String(status.to_string()))),
+                            Err(e) => Ok(result_err(e, -1)),
+                        }
+                    },
+                ))),
+            );
+
             // tls_send(tls, bytes)
             globals.borrow_mut().define(
                 "tls_send".to_string(),
@@ -3546,6 +4962,13 @@ impl Interpreter {
 		                        }
 		                    };
 		                    if let Err(e) = socket.set_nonblocking(nonblocking) { return Ok(result_err(format!("DTLS socket setup failed: {}", e), -1)); }
+		                    let timeout = Some(std::time::Duration::from_millis(cfg.timeout_ms));
+		                    if let Err(e) = socket
+		                        .set_read_timeout(timeout)
+		                        .and_then(|_| socket.set_write_timeout(timeout))
+		                    {
+		                        return Ok(result_err(format!("DTLS socket setup failed: {}", e), -1));
+		                    }
 
                     let remote = if let (Some(host), Some(port)) =
                         (cfg.remote_host.clone(), cfg.remote_port)
@@ -3623,6 +5046,9 @@ impl Interpreter {
 		                                let selected = stream.selected_srtp_profile().ok().flatten();
 		                                (stream, selected)
 		                            }
+		                            Err(udp_dtls::HandshakeError::WouldBlock(_)) if !nonblocking => {
+		                                return Ok(result_err("Timed oot".to_string(), -1))
+		                            }
 		                            Err(err) => return Ok(result_err(format!("DTLS connect failed: {:?}", err), -1)),
 		                        }
 		                    } else {
@@ -3668,6 +5094,9 @@ impl Interpreter {
 		                                let selected = stream.selected_srtp_profile().ok().flatten();
 		                                (stream, selected)
 		                            }
+		                            Err(udp_dtls::HandshakeError::WouldBlock(_)) if !nonblocking => {
+		                                return Ok(result_err("Timed oot".to_string(), -1))
+		                            }
 		                            Err(err) => return Ok(result_err(format!("DTLS accept failed: {:?}", err), -1)),
 		                        }
 		                    };
@@ -3712,6 +5141,16 @@ impl Interpreter {
                         Value::String("salt_len".to_string()),
                         Value::Integer(salt_len as i64),
                     );
+                    let (cipher, fingerprint) = dtls_session_info(&stream);
+                    if let Some(cipher) = cipher {
+                        dict.set(Value::String("cipher".to_string()), Value::String(cipher));
+                    }
+                    if let Some(fingerprint) = fingerprint {
+                        dict.set(
+                            Value::String("fingerprint".to_string()),
+                            Value::String(fingerprint),
+                        );
+                    }
 
                     Ok(result_ok(Value::Dict(Rc::new(RefCell::new(dict)))))
                 }))),
@@ -3797,19 +5236,35 @@ impl Interpreter {
                         None => return Ok(result_err("Missing SRTP recv_salt".to_string(), -1)),
                     };
 
+                    let opts = match srtp_stream_opts(&dict) {
+                        Ok(opts) => opts,
+                        Err(e) => return Ok(result_err(e, -1)),
+                    };
+
                     let send_master = MasterKey::new(&send_key, &send_salt, &None);
                     let recv_master = MasterKey::new(&recv_key, &recv_salt, &None);
-                    let send_cfg = StreamConfig::new(vec![send_master], &profile, &profile);
-                    let recv_cfg = StreamConfig::new(vec![recv_master], &profile, &profile);
+                    let mut send_cfg = StreamConfig::new(vec![send_master], &profile, &profile);
+                    let mut recv_cfg = StreamConfig::new(vec![recv_master], &profile, &profile);
+                    if let Some(window_size) = opts.window_size {
+                        send_cfg.set_replay_window_size(window_size as u16);
+                        recv_cfg.set_replay_window_size(window_size as u16);
+                    }
 
                     let mut send = SendSession::new();
-                    if let Err(e) = send.add_stream(None, &send_cfg) {
+                    if let Err(e) = send.add_stream(opts.ssrc, &send_cfg) {
                         return Ok(result_err(format!("SRTP send session error: {}", e), -1));
                     }
                     let mut recv = RecvSession::new();
-                    if let Err(e) = recv.add_stream(None, &recv_cfg) {
+                    if let Err(e) = recv.add_stream(opts.ssrc, &recv_cfg) {
                         return Ok(result_err(format!("SRTP recv session error: {}", e), -1));
                     }
+                    // libsrtp only lets a receiver start at a later ROC; a sender
+                    // counts its own from the sequence numbers it protects
+                    if let (Some(ssrc), Some(roc)) = (opts.ssrc, opts.roc) {
+                        if let Err(e) = recv.set_roc(ssrc, roc) {
+                            return Ok(result_err(format!("SRTP recv roc error: {}", e), -1));
+                        }
+                    }
 
                     let id = register_srtp(SrtpSession { send, recv });
                     Ok(result_ok(Value::Integer(id)))
@@ -3863,6 +5318,33 @@ impl Interpreter {
                     }
                 }))),
             );
+
+            // srtp_set_roc(srtp, ssrc, roc) - move a receiving stream's rollover counter mid-call
+            globals.borrow_mut().define(
+                "srtp_set_roc".to_string(),
+                Value::NativeFunction(Rc::new(NativeFunction::new("srtp_set_roc", 3, |args| {
+                    let ctx_id = args[0]
+                        .as_integer()
+                        .ok_or("srtp_set_roc() expects SRTP handle")?;
+                    let (Value::Integer(ssrc), Value::Integer(roc)) = (&args[1], &args[2]) else {
+                        return Err("srtp_set_roc() expects integer ssrc an' roc".to_string());
+                    };
+                    let res = srtp_u32("ssrc", *ssrc)
+                        .and_then(|ssrc| Ok((ssrc, srtp_u32("roc", *roc)?)))
+                        .and_then(|(ssrc, roc)| {
+                            with_srtp_mut(ctx_id, |session| {
+                                session
+                                    .recv
+                                    .set_roc(ssrc, roc)
+                                    .map_err(|e| format!("SRTP set_roc failed: {}", e))
+                            })
+                        });
+                    match res {
+                        Ok(()) => Ok(result_ok(Value::Nil)),
+                        Err(e) => Ok(result_err(e, -1)),
+                    }
+                }))),
+            );
         }
 
         // event_loop_new() -> loop handle
@@ -4649,60 +6131,220 @@ impl Interpreter {
         // type - get type of value (whit_kind in Scots!)
         globals.borrow_mut().define(
             "whit_kind".to_string(),
-            Value::NativeFunction(Rc::new(NativeFunction::new(
-                "whit_kind",
-                1,
-                |args| match &args[0] {
-                    Value::NativeObject(obj) => Ok(Value::String(obj.type_name().to_string())),
-                    _ => Ok(Value::String(args[0].type_name().to_string())),
-                },
-            ))),
-        );
-
-        // str - convert to string (tae_string in Scots!)
-        globals.borrow_mut().define(
-            "tae_string".to_string(),
-            Value::NativeFunction(Rc::new(NativeFunction::new("tae_string", 1, |args| {
-                Ok(Value::String(format!("{}", args[0])))
+            Value::NativeFunction(Rc::new(NativeFunction::new("whit_kind", 1, |args| {
+                Ok(Value::String(args[0].kind_name()))
             }))),
         );
 
-        // int - convert to integer (tae_int in Scots!)
+        // airity - how many arguments a function needs (those with defaults
+        // are left out); nil for a built-in that takes any number
         globals.borrow_mut().define(
-            "tae_int".to_string(),
+            "airity".to_string(),
             Value::NativeFunction(Rc::new(NativeFunction::new(
-                "tae_int",
+                "airity",
                 1,
                 |args| match &args[0] {
-                    Value::Integer(n) => Ok(Value::Integer(*n)),
-                    Value::Float(f) => Ok(Value::Integer(*f as i64)),
-                    Value::String(s) => s
-                        .parse::<i64>()
-                        .map(Value::Integer)
-                        .map_err(|_| format!("Cannae turn '{}' intae an integer", s)),
-                    Value::Bool(b) => Ok(Value::Integer(if *b { 1 } else { 0 })),
-                    _ => Err(format!(
-                        "Cannae turn {} intae an integer",
-                        args[0].type_name()
+                    Value::Function(f) => Ok(Value::Integer(f.min_arity() as i64)),
+                    Value::NativeFunction(f) if f.arity == usize::MAX => Ok(Value::Nil),
+                    Value::NativeFunction(f) => Ok(Value::Integer(f.arity as i64)),
+                    other => Err(format!(
+                        "airity() needs a function, no' a {}",
+                        other.type_name()
                     )),
                 },
             ))),
         );
 
-        // float - convert to float (tae_float in Scots!)
-        globals.borrow_mut().define(
-            "tae_float".to_string(),
-            Value::NativeFunction(Rc::new(NativeFunction::new(
-                "tae_float",
-                1,
-                |args| match &args[0] {
-                    Value::Integer(n) => Ok(Value::Float(*n as f64)),
-                    Value::Float(f) => Ok(Value::Float(*f)),
-                    Value::String(s) => s
-                        .parse::<f64>()
-                        .map(Value::Float)
-                        .map_err(|_| format!("Cannae turn '{}' intae a float", s)),
-                    _ => Err(format!("Cannae turn {} intae a float", args[0].type_name())),
+        // pairt - partial application: pairt(f, a, b) returns a function that
+        // calls f(a, b, ...rest). Its airity is what's left to supply, or
+        // any number when f has defaults or takes any number itself
+        globals.borrow_mut().define(
+            "pairt".to_string(),
+            Value::NativeFunction(Rc::new(NativeFunction::new("pairt", usize::MAX, |args| {
+                let mut args = args.into_iter();
+                let func = args
+                    .next()
+                    .ok_or("pairt() needs a function an' the arguments tae fix")?;
+                let fixed: Vec<Value> = args.collect();
+                let (name, min, max) = match &func {
+                    Value::Function(f) => (f.name.clone(), f.min_arity(), f.max_arity()),
+                    Value::NativeFunction(f) => (f.name.clone(), f.arity, f.arity),
+                    other => {
+                        return Err(format!(
+                            "pairt() needs a function, no' a {}",
+                            other.type_name()
+                        ))
+                    }
+                };
+                if max != usize::MAX && fixed.len() > max {
+                    return Err(format!(
+                        "pairt() got {} arguments fer '{}', but it only taks {}",
+                        fixed.len(),
+                        name,
+                        max
+                    ));
+                }
+                // A variadic native stays variadic
+                let arity = if min == max && max != usize::MAX {
+                    max.saturating_sub(fixed.len())
+                } else {
+                    usize::MAX
+                };
+                let name = format!("pairt({})", name);
+                let inner_name = name.clone();
+                Ok(Value::NativeFunction(Rc::new(NativeFunction::new(
+                    &name,
+                    arity,
+                    move |rest| {
+                        let mut all = fixed.clone();
+                        all.extend(rest);
+                        let result = with_current_interpreter(|interp| {
+                            interp.call_value(func.clone(), all, 0)
+                        });
+                        match result {
+                            Some(Ok(val)) => Ok(val),
+                            Some(Err(err)) => Err(format!("{}", err)),
+                            None => Err(format!(
+                                "{}() is unavailable outside the interpreter",
+                                inner_name
+                            )),
+                        }
+                    },
+                ))))
+            }))),
+        );
+
+        // compose - compose(f, g) returns a function that works out f(g(x))
+        globals.borrow_mut().define(
+            "compose".to_string(),
+            Value::NativeFunction(Rc::new(NativeFunction::new("compose", 2, |args| {
+                let name = format!(
+                    "compose({}, {})",
+                    single_arg_stage("compose", &args[0])?,
+                    single_arg_stage("compose", &args[1])?
+                );
+                let (f, g) = (args[0].clone(), args[1].clone());
+                let inner_name = name.clone();
+                Ok(Value::NativeFunction(Rc::new(NativeFunction::new(
+                    &name,
+                    1,
+                    move |args| {
+                        let result = with_current_interpreter(|interp| {
+                            let inner = interp.call_value(g.clone(), args, 0)?;
+                            interp.call_value(f.clone(), vec![inner], 0)
+                        });
+                        match result {
+                            Some(Ok(val)) => Ok(val),
+                            Some(Err(err)) => Err(format!("{}", err)),
+                            None => Err(format!(
+                                "{}() is unavailable outside the interpreter",
+                                inner_name
+                            )),
+                        }
+                    },
+                ))))
+            }))),
+        );
+
+        // pipe - pipe(x, f, g, ...) passes x through each function in turn,
+        // just like x |> f |> g
+        globals.borrow_mut().define(
+            "pipe".to_string(),
+            Value::NativeFunction(Rc::new(NativeFunction::new("pipe", usize::MAX, |args| {
+                let mut args = args.into_iter();
+                let mut value = args
+                    .next()
+                    .ok_or("pipe() needs a value an' the functions tae pass it through")?;
+                let stages: Vec<Value> = args.collect();
+                for stage in &stages {
+                    single_arg_stage("pipe", stage)?;
+                }
+                let result = with_current_interpreter(|interp| {
+                    for stage in stages {
+                        value = interp.call_value(stage, vec![value], 0)?;
+                    }
+                    Ok::<Value, HaversError>(value)
+                });
+                match result {
+                    Some(Ok(val)) => Ok(val),
+                    Some(Err(err)) => Err(format!("{}", err)),
+                    None => Err("pipe() is unavailable outside the interpreter".to_string()),
+                }
+            }))),
+        );
+
+        // str - convert to string (tae_string in Scots!)
+        globals.borrow_mut().define(
+            "tae_string".to_string(),
+            Value::NativeFunction(Rc::new(NativeFunction::new("tae_string", 1, |args| {
+                Ok(Value::String(format!("{}", args[0])))
+            }))),
+        );
+
+        // int - convert to integer (tae_int in Scots!); floats are cut
+        // toward zero, and a string can be read in any base from 2 to 36
+        globals.borrow_mut().define(
+            "tae_int".to_string(),
+            Value::NativeFunction(Rc::new(NativeFunction::new(
+                "tae_int",
+                usize::MAX,
+                |args| {
+                    if args.is_empty() || args.len() > 2 {
+                        return Err("tae_int() expects 1 or 2 arguments".to_string());
+                    }
+                    if let Some(base) = args.get(1) {
+                        return match (&args[0], base) {
+                            (Value::String(s), Value::Integer(base)) => {
+                                let base = u32::try_from(*base).map_err(|_| {
+                                    format!("Base must be atween 2 an' 36, no' {}", base)
+                                })?;
+                                int_from_base(s, base).map(Value::Integer)
+                            }
+                            _ => Err("tae_int() wi' a base needs a string an' an integer base"
+                                .to_string()),
+                        };
+                    }
+                    match &args[0] {
+                        Value::Integer(_) | Value::BigInt(_) => Ok(args[0].clone()),
+                        // `as` would quietly clamp these, so say so instead
+                        Value::Float(f)
+                            if !f.is_finite() || *f >= i64::MAX as f64 || *f < i64::MIN as f64 =>
+                        {
+                            Err(
+                                "Cannae turn inf, NaN or a float that muckle intae an integer"
+                                    .to_string(),
+                            )
+                        }
+                        Value::Float(f) => Ok(Value::Integer(f.trunc() as i64)),
+                        Value::String(s) => s
+                            .parse::<i64>()
+                            .map(Value::Integer)
+                            .map_err(|_| format!("Cannae turn '{}' intae an integer", s)),
+                        Value::Bool(b) => Ok(Value::Integer(if *b { 1 } else { 0 })),
+                        _ => Err(format!(
+                            "Cannae turn {} intae an integer",
+                            args[0].type_name()
+                        )),
+                    }
+                },
+            ))),
+        );
+
+        // float - convert to float (tae_float in Scots!)
+        globals.borrow_mut().define(
+            "tae_float".to_string(),
+            Value::NativeFunction(Rc::new(NativeFunction::new(
+                "tae_float",
+                1,
+                |args| match &args[0] {
+                    Value::Integer(n) => Ok(Value::Float(*n as f64)),
+                    Value::BigInt(n) => Ok(Value::Float(n.to_f64().unwrap_or(f64::NAN))),
+                    Value::Float(f) => Ok(Value::Float(*f)),
+                    Value::String(s) => s
+                        .parse::<f64>()
+                        .map(Value::Float)
+                        .map_err(|_| format!("Cannae turn '{}' intae a float", s)),
+                    _ => Err(format!("Cannae turn {} intae a float", args[0].type_name())),
                 },
             ))),
         );
@@ -4734,6 +6376,73 @@ impl Interpreter {
             }))),
         );
 
+        // stick_in - insert at index, in place (Scots: stick it in)
+        globals.borrow_mut().define(
+            "stick_in".to_string(),
+            Value::NativeFunction(Rc::new(NativeFunction::new("stick_in", 3, |args| {
+                if let Value::List(list) = &args[0] {
+                    let idx = args[1]
+                        .as_integer()
+                        .ok_or("stick_in() needs an integer index")?;
+                    let len = list.borrow().len();
+                    // Inserting at len is fine - that's the end of the list.
+                    // Negative indices count from the end, like chynge
+                    let pos = if idx < 0 { len as i64 + idx } else { idx };
+                    if pos < 0 || pos > len as i64 {
+                        return Err(format!(
+                            "Index {} oot o' bounds fer stick_in on a list o' length {}",
+                            idx, len
+                        ));
+                    }
+                    list.borrow_mut().insert(pos as usize, args[2].clone());
+                    Ok(args[0].clone())
+                } else {
+                    Err("stick_in() needs a list".to_string())
+                }
+            }))),
+        );
+
+        // tak_oot - remove and return the item at an index, in place
+        globals.borrow_mut().define(
+            "tak_oot".to_string(),
+            Value::NativeFunction(Rc::new(NativeFunction::new("tak_oot", 2, |args| {
+                if let Value::List(list) = &args[0] {
+                    let idx = args[1]
+                        .as_integer()
+                        .ok_or("tak_oot() needs an integer index")?;
+                    let len = list.borrow().len();
+                    let pos = if idx < 0 { len as i64 + idx } else { idx };
+                    if pos < 0 || pos >= len as i64 {
+                        return Err(format!(
+                            "Index {} oot o' bounds fer tak_oot on a list o' length {}",
+                            idx, len
+                        ));
+                    }
+                    Ok(list.borrow_mut().remove(pos as usize))
+                } else {
+                    Err("tak_oot() needs a list".to_string())
+                }
+            }))),
+        );
+
+        // whaur - index of the first matching item, or -1
+        globals.borrow_mut().define(
+            "whaur".to_string(),
+            Value::NativeFunction(Rc::new(NativeFunction::new("whaur", 2, |args| {
+                if let Value::List(list) = &args[0] {
+                    Ok(Value::Integer(
+                        list.borrow()
+                            .iter()
+                            .position(|item| item == &args[1])
+                            .map(|i| i as i64)
+                            .unwrap_or(-1),
+                    ))
+                } else {
+                    Err("whaur() needs a list".to_string())
+                }
+            }))),
+        );
+
         // keys - get dictionary keys
         globals.borrow_mut().define(
             "keys".to_string(),
@@ -4760,13 +6469,23 @@ impl Interpreter {
             }))),
         );
 
-        // range - create a range
+        // range(start, end, step = 1) - create a range
         globals.borrow_mut().define(
             "range".to_string(),
-            Value::NativeFunction(Rc::new(NativeFunction::new("range", 2, |args| {
+            Value::NativeFunction(Rc::new(NativeFunction::new("range", usize::MAX, |args| {
+                if args.len() < 2 || args.len() > 3 {
+                    return Err("range() expects 2 or 3 arguments".to_string());
+                }
                 let start = args[0].as_integer().ok_or("range() expects integers")?;
                 let end = args[1].as_integer().ok_or("range() expects integers")?;
-                Ok(Interpreter::range_to_list(start, end, false))
+                let step = match args.get(2) {
+                    Some(v) => v.as_integer().ok_or("range() expects integers")?,
+                    None => 1,
+                };
+                if step == 0 {
+                    return Err("range() step cannae be zero".to_string());
+                }
+                Ok(Interpreter::range_to_list(start, end, step, false))
             }))),
         );
 
@@ -4775,38 +6494,39 @@ impl Interpreter {
             "abs".to_string(),
             Value::NativeFunction(Rc::new(NativeFunction::new("abs", 1, |args| {
                 match &args[0] {
-                    Value::Integer(n) => Ok(Value::Integer(n.abs())),
+                    Value::Integer(n) => n
+                        .checked_abs()
+                        .map(Value::Integer)
+                        .ok_or_else(|| "abs() overflowed - that number's too muckle".to_string()),
                     Value::Float(f) => Ok(Value::Float(f.abs())),
                     _ => Err("abs() expects a number".to_string()),
                 }
             }))),
         );
 
-        // min - minimum value
+        // min - the smaller of two values, or the smallest in a list
         globals.borrow_mut().define(
             "min".to_string(),
-            Value::NativeFunction(Rc::new(NativeFunction::new("min", 2, |args| {
-                match (&args[0], &args[1]) {
-                    (Value::Integer(a), Value::Integer(b)) => {
-                        Ok(Value::Integer(std::cmp::min(*a, *b)))
-                    }
-                    (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a.min(*b))),
-                    _ => Err("min() expects two numbers of the same type".to_string()),
-                }
+            Value::NativeFunction(Rc::new(NativeFunction::new("min", usize::MAX, |args| {
+                pick_extreme(
+                    "min",
+                    &args,
+                    std::cmp::Ordering::Less,
+                    "Cannae find the wee-est o' naething",
+                )
             }))),
         );
 
-        // max - maximum value
+        // max - the larger of two values, or the largest in a list
         globals.borrow_mut().define(
             "max".to_string(),
-            Value::NativeFunction(Rc::new(NativeFunction::new("max", 2, |args| {
-                match (&args[0], &args[1]) {
-                    (Value::Integer(a), Value::Integer(b)) => {
-                        Ok(Value::Integer(std::cmp::max(*a, *b)))
-                    }
-                    (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a.max(*b))),
-                    _ => Err("max() expects two numbers of the same type".to_string()),
-                }
+            Value::NativeFunction(Rc::new(NativeFunction::new("max", usize::MAX, |args| {
+                pick_extreme(
+                    "max",
+                    &args,
+                    std::cmp::Ordering::Greater,
+                    "Cannae find the muckle-est o' naething",
+                )
             }))),
         );
 
@@ -4852,18 +6572,59 @@ impl Interpreter {
             ))),
         );
 
+        // fmt_float - fixed number of decimals, e.g. fmt_float(3.14159, 2) -> "3.14"
+        globals.borrow_mut().define(
+            "fmt_float".to_string(),
+            Value::NativeFunction(Rc::new(NativeFunction::new("fmt_float", 2, |args| {
+                let x = match &args[0] {
+                    Value::Float(f) => *f,
+                    Value::Integer(n) => *n as f64,
+                    _ => return Err("fmt_float() expects a number".to_string()),
+                };
+                let decimals = match &args[1] {
+                    Value::Integer(n) if (0..=MAX_FLOAT_DECIMALS).contains(n) => *n as usize,
+                    Value::Integer(_) => {
+                        return Err(format!(
+                            "fmt_float() decimals must be between 0 an' {}",
+                            MAX_FLOAT_DECIMALS
+                        ))
+                    }
+                    _ => return Err("fmt_float() decimals must be an integer".to_string()),
+                };
+                Ok(Value::String(format!("{:.*}", decimals, x)))
+            }))),
+        );
+
+        // format - positional `{}` placeholders with width and precision
+        globals.borrow_mut().define(
+            "format".to_string(),
+            Value::NativeFunction(Rc::new(NativeFunction::new("format", usize::MAX, |args| {
+                match args.split_first() {
+                    Some((Value::String(template), rest)) => {
+                        format_template(template, rest).map(Value::String)
+                    }
+                    _ => Err("format() needs a template string first".to_string()),
+                }
+            }))),
+        );
+
         // sqrt
         globals.borrow_mut().define(
             "sqrt".to_string(),
-            Value::NativeFunction(Rc::new(NativeFunction::new(
-                "sqrt",
-                1,
-                |args| match &args[0] {
-                    Value::Float(f) => Ok(Value::Float(f.sqrt())),
-                    Value::Integer(n) => Ok(Value::Float((*n as f64).sqrt())),
-                    _ => Err("sqrt() expects a number".to_string()),
-                },
-            ))),
+            Value::NativeFunction(Rc::new(NativeFunction::new("sqrt", 1, |args| {
+                let x = match &args[0] {
+                    Value::Float(f) => *f,
+                    Value::Integer(n) => *n as f64,
+                    _ => return Err("sqrt() expects a number".to_string()),
+                };
+                if x < 0.0 {
+                    return Err(format!(
+                        "sqrt() cannae take the square root o' a negative number ({})",
+                        args[0]
+                    ));
+                }
+                Ok(Value::Float(x.sqrt()))
+            }))),
         );
 
         // set_log_level - set the logging level at runtime
@@ -4879,7 +6640,7 @@ impl Interpreter {
                             Ok(Value::Nil)
                         } else {
                             Err(format!(
-                                "Invalid log level '{}'. Use: wheesht, roar, holler, blether, mutter, or whisper",
+                                "Invalid log level '{}'. Use: wheesht, roar, holler, blether, mutter, or whisper (or error, warn, info, debug, trace)",
                                 s
                             ))
                         }
@@ -4985,6 +6746,70 @@ impl Interpreter {
             ))),
         );
 
+        // log_debug/log_info/log_warn/log_error(message, fields = {}, target = "")
+        // - the same as the log_mutter/log_blether/log_holler/log_roar statements
+        for (name, level) in [
+            ("log_debug", LogLevel::Mutter),
+            ("log_info", LogLevel::Blether),
+            ("log_warn", LogLevel::Holler),
+            ("log_error", LogLevel::Roar),
+        ] {
+            globals.borrow_mut().define(
+                name.to_string(),
+                Value::NativeFunction(Rc::new(NativeFunction::new(
+                    name,
+                    usize::MAX,
+                    move |args| {
+                        if args.is_empty() || args.len() > 3 {
+                            return Err(format!("{}() expects 1-3 arguments", name));
+                        }
+                        let (fields, target) = resolve_log_args(&args[1..])?;
+                        let mut args = args;
+                        let message = args.swap_remove(0);
+                        let result = with_current_interpreter(|interp| {
+                            interp.emit_log(level, message, fields, target, 0)
+                        });
+                        match result {
+                            Some(Ok(())) => Ok(Value::Nil),
+                            Some(Err(err)) => Err(format!("{}", err)),
+                            None => {
+                                Err(format!("{}() is unavailable outside the interpreter", name))
+                            }
+                        }
+                    },
+                ))),
+            );
+        }
+
+        // log_to_file(path) - send log lines to the end of a file, timestamped,
+        // instead of stderr
+        globals.borrow_mut().define(
+            "log_to_file".to_string(),
+            Value::NativeFunction(Rc::new(NativeFunction::new("log_to_file", 1, |args| {
+                let path = match &args[0] {
+                    Value::String(s) => s.clone(),
+                    _ => return Err("log_to_file() needs a file path".to_string()),
+                };
+                // Open it now, so a bad path is an error here and not a
+                // warning at the first log line
+                let file = std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&path)
+                    .map_err(|e| format!("log_to_file() cannae open '{}': {}", path, e))?;
+                with_current_interpreter(|interp| {
+                    interp.logger.timestamps = true;
+                    interp.logger.sinks = vec![logging::LogSink::File {
+                        path,
+                        append: true,
+                        file: Some(file),
+                    }];
+                })
+                .ok_or("log_to_file() is unavailable outside the interpreter")?;
+                Ok(Value::Nil)
+            }))),
+        );
+
         // log_init(config = {}) -> nil
         globals.borrow_mut().define(
             "log_init".to_string(),
@@ -5136,13 +6961,7 @@ impl Interpreter {
         globals.borrow_mut().define(
             "stacktrace".to_string(),
             Value::NativeFunction(Rc::new(NativeFunction::new("stacktrace", 0, |_args| {
-                let stack = get_stack_trace();
-                let trace = stack
-                    .iter()
-                    .rev()
-                    .map(|f| format!("  at {} ({}:{})", f.name, f.file, f.line))
-                    .collect::<Vec<_>>()
-                    .join("\n");
+                let trace = format_stack_trace(&get_stack_trace(), MAX_BACKTRACE_FRAMES);
                 Ok(Value::String(if trace.is_empty() {
                     "(no stack trace)".to_string()
                 } else {
@@ -5184,7 +7003,8 @@ impl Interpreter {
             }))),
         );
 
-        // join - join list into string
+        // join - join list into string; items that are not strings are turned
+        // into text the way tae_string does
         globals.borrow_mut().define(
             "join".to_string(),
             Value::NativeFunction(Rc::new(NativeFunction::new("join", 2, |args| {
@@ -5202,25 +7022,9 @@ impl Interpreter {
         // contains - check if list/string contains value
         globals.borrow_mut().define(
             "contains".to_string(),
-            Value::NativeFunction(Rc::new(NativeFunction::new(
-                "contains",
-                2,
-                |args| match &args[0] {
-                    Value::List(list) => {
-                        let found = list.borrow().iter().any(|v| v == &args[1]);
-                        Ok(Value::Bool(found))
-                    }
-                    Value::String(s) => {
-                        if let Value::String(needle) = &args[1] {
-                            Ok(Value::Bool(s.contains(needle.as_str())))
-                        } else {
-                            Err("contains() on string expects a string needle".to_string())
-                        }
-                    }
-                    Value::Dict(dict) => Ok(Value::Bool(dict.borrow().contains_key(&args[1]))),
-                    _ => Err("contains() expects a list, string, or dict".to_string()),
-                },
-            ))),
+            Value::NativeFunction(Rc::new(NativeFunction::new("contains", 2, |args| {
+                contains_value(&args[0], &args[1]).map(Value::Bool)
+            }))),
         );
 
         // reverse - reverse a list or string
@@ -5353,6 +7157,34 @@ impl Interpreter {
             }))),
         );
 
+        // slice - like xs[start..end]: negative indices count from the end, bounds
+        // clamp to the ends, and start >= end gives an empty result
+        globals.borrow_mut().define(
+            "slice".to_string(),
+            Value::NativeFunction(Rc::new(NativeFunction::new("slice", 3, |args| {
+                let start = args[1]
+                    .as_integer()
+                    .ok_or("slice() needs integer indices")?;
+                let end = match &args[2] {
+                    Value::Nil => None,
+                    v => Some(v.as_integer().ok_or("slice() needs integer indices")?),
+                };
+                match &args[0] {
+                    Value::List(list) => {
+                        let list = list.borrow();
+                        let (s, e) = slice_bounds(list.len(), start, end);
+                        Ok(Value::List(Rc::new(RefCell::new(list[s..e].to_vec()))))
+                    }
+                    Value::String(text) => {
+                        let chars: Vec<char> = text.chars().collect();
+                        let (s, e) = slice_bounds(chars.len(), start, end);
+                        Ok(Value::String(chars[s..e].iter().collect()))
+                    }
+                    _ => Err("slice() expects a list or string".to_string()),
+                }
+            }))),
+        );
+
         // sumaw - sum all numbers in a list (sum aw = sum all)
         globals.borrow_mut().define(
             "sumaw".to_string(),
@@ -5381,7 +7213,40 @@ impl Interpreter {
             }))),
         );
 
-        // coont - count occurrences in list or string
+        // sum - add up a list of numbers; an empty list sums to 0, and any
+        // float in it makes the total a float
+        globals.borrow_mut().define(
+            "sum".to_string(),
+            Value::NativeFunction(Rc::new(NativeFunction::new("sum", 1, |args| {
+                let Value::List(list) = &args[0] else {
+                    return Err("sum() needs a list".to_string());
+                };
+                let mut int_total: i64 = 0;
+                let mut float_total: Option<f64> = None;
+                for item in list.borrow().iter() {
+                    match (item, float_total.as_mut()) {
+                        (Value::Integer(n), Some(total)) => *total += *n as f64,
+                        (Value::Integer(n), None) => {
+                            int_total = int_total
+                                .checked_add(*n)
+                                .ok_or("sum() owerflowed - the total's ower big fer an integer")?;
+                        }
+                        (Value::Float(f), _) => {
+                            *float_total.get_or_insert(int_total as f64) += f;
+                        }
+                        _ => {
+                            return Err(format!(
+                                "sum() needs a list o' numbers, no' a {}",
+                                item.type_name()
+                            ))
+                        }
+                    }
+                }
+                Ok(float_total.map_or(Value::Integer(int_total), Value::Float))
+            }))),
+        );
+
+        // coont - count occurrences in list (nested values match by contents) or string
         globals.borrow_mut().define(
             "coont".to_string(),
             Value::NativeFunction(Rc::new(NativeFunction::new(
@@ -5389,7 +7254,7 @@ impl Interpreter {
                 2,
                 |args| match &args[0] {
                     Value::List(list) => {
-                        let count = list.borrow().iter().filter(|&x| x == &args[1]).count();
+                        let count = list.borrow().iter().filter(|x| x.deep_eq(&args[1])).count();
                         Ok(Value::Integer(count as i64))
                     }
                     Value::String(s) => {
@@ -5446,19 +7311,8 @@ impl Interpreter {
             "shuffle".to_string(),
             Value::NativeFunction(Rc::new(NativeFunction::new("shuffle", 1, |args| {
                 if let Value::List(list) = &args[0] {
-                    use std::time::{SystemTime, UNIX_EPOCH};
-                    let seed = SystemTime::now()
-                        .duration_since(UNIX_EPOCH)
-                        .unwrap()
-                        .as_nanos() as u64;
                     let mut shuffled = list.borrow().clone();
-                    // Simple Fisher-Yates shuffle with basic RNG
-                    let mut rng = seed;
-                    for i in (1..shuffled.len()).rev() {
-                        rng = rng.wrapping_mul(1103515245).wrapping_add(12345);
-                        let j = (rng as usize) % (i + 1);
-                        shuffled.swap(i, j);
-                    }
+                    with_rng(|rng| rng.shuffle(&mut shuffled));
                     Ok(Value::List(Rc::new(RefCell::new(shuffled))))
                 } else {
                     Err("shuffle() expects a list".to_string())
@@ -5472,14 +7326,16 @@ impl Interpreter {
             Value::NativeFunction(Rc::new(NativeFunction::new("sort", 1, |args| {
                 if let Value::List(list) = &args[0] {
                     let mut sorted = list.borrow().clone();
-                    sorted.sort_by(|a, b| match (a, b) {
-                        (Value::Integer(x), Value::Integer(y)) => x.cmp(y),
-                        (Value::Float(x), Value::Float(y)) => {
-                            x.partial_cmp(y).unwrap_or(std::cmp::Ordering::Equal)
+                    if let Some((first, rest)) = sorted.split_first() {
+                        if let Some(odd) = rest.iter().find(|v| !first.is_comparable_with(v)) {
+                            return Err(format!(
+                                "sort() cannae order a {} against a {}",
+                                first.type_name(),
+                                odd.type_name()
+                            ));
                         }
-                        (Value::String(x), Value::String(y)) => x.cmp(y),
-                        _ => std::cmp::Ordering::Equal,
-                    });
+                    }
+                    sorted.sort_by(sort_order);
                     Ok(Value::List(Rc::new(RefCell::new(sorted))))
                 } else {
                     Err("sort() expects a list".to_string())
@@ -5491,20 +7347,35 @@ impl Interpreter {
         globals.borrow_mut().define(
             "jammy".to_string(),
             Value::NativeFunction(Rc::new(NativeFunction::new("jammy", 2, |args| {
-                use std::time::{SystemTime, UNIX_EPOCH};
                 let min = args[0].as_integer().ok_or("jammy() needs integer bounds")?;
                 let max = args[1].as_integer().ok_or("jammy() needs integer bounds")?;
                 if min >= max {
                     return Err("jammy() needs min < max, ya numpty!".to_string());
                 }
-                let seed = SystemTime::now()
+                Ok(Value::Integer(with_rng(|rng| rng.between(min, max - 1))))
+            }))),
+        );
+
+        // uuid_v4 - a random UUID
+        globals.borrow_mut().define(
+            "uuid_v4".to_string(),
+            Value::NativeFunction(Rc::new(NativeFunction::new("uuid_v4", 0, |_args| {
+                Ok(Value::String(format_uuid(random_uuid_bytes("uuid_v4")?, 4)))
+            }))),
+        );
+
+        // uuid_v7 - a UUID that sorts by the millisecond it was made in
+        globals.borrow_mut().define(
+            "uuid_v7".to_string(),
+            Value::NativeFunction(Rc::new(NativeFunction::new("uuid_v7", 0, |_args| {
+                use std::time::{SystemTime, UNIX_EPOCH};
+                let millis = SystemTime::now()
                     .duration_since(UNIX_EPOCH)
-                    .unwrap()
-                    .as_nanos() as u64;
-                let rng = seed.wrapping_mul(1103515245).wrapping_add(12345);
-                let range = (max - min) as u64;
-                let result = min + ((rng % range) as i64);
-                Ok(Value::Integer(result))
+                    .map_err(|e| format!("uuid_v7() cannae read the clock: {}", e))?
+                    .as_millis() as u64;
+                let mut bytes = random_uuid_bytes("uuid_v7")?;
+                bytes[..6].copy_from_slice(&millis.to_be_bytes()[2..]);
+                Ok(Value::String(format_uuid(bytes, 7)))
             }))),
         );
 
@@ -5550,7 +7421,7 @@ impl Interpreter {
                     _ => return Err("is_a() needs a type name string".to_string()),
                 };
                 let matches = match type_name {
-                    "integer" | "int" => matches!(args[0], Value::Integer(_)),
+                    "integer" | "int" => matches!(args[0], Value::Integer(_) | Value::BigInt(_)),
                     "float" => matches!(args[0], Value::Float(_)),
                     "string" | "str" => matches!(args[0], Value::String(_)),
                     "bool" => matches!(args[0], Value::Bool(_)),
@@ -5562,7 +7433,15 @@ impl Interpreter {
                     }
                     "naething" | "nil" => matches!(args[0], Value::Nil),
                     "range" => matches!(args[0], Value::Range(_)),
-                    _ => false,
+                    "instance" => matches!(args[0], Value::Instance(_)),
+                    // Any other name is a kind or a class name; an instance
+                    // of a subclass counts as its parent class too
+                    class_name => match &args[0] {
+                        Value::Instance(instance) => {
+                            instance.borrow().class.is_or_inherits(class_name)
+                        }
+                        other => other.kind_name() == class_name,
+                    },
                 };
                 Ok(Value::Bool(matches))
             }))),
@@ -5695,6 +7574,22 @@ impl Interpreter {
             }))),
         );
 
+        // split_lines - split text into lines on \n or \r\n, without a trailing empty line
+        globals.borrow_mut().define(
+            "split_lines".to_string(),
+            Value::NativeFunction(Rc::new(NativeFunction::new("split_lines", 1, |args| {
+                if let Value::String(s) = &args[0] {
+                    let line_list: Vec<Value> = s
+                        .lines()
+                        .map(|line| Value::String(line.to_string()))
+                        .collect();
+                    Ok(Value::List(Rc::new(RefCell::new(line_list))))
+                } else {
+                    Err("split_lines() needs a string".to_string())
+                }
+            }))),
+        );
+
         // words - split string into words (on whitespace)
         globals.borrow_mut().define(
             "words".to_string(),
@@ -5852,14 +7747,7 @@ impl Interpreter {
             "flatten".to_string(),
             Value::NativeFunction(Rc::new(NativeFunction::new("flatten", 1, |args| {
                 if let Value::List(list) = &args[0] {
-                    let mut result = Vec::new();
-                    for item in list.borrow().iter() {
-                        if let Value::List(inner) = item {
-                            result.extend(inner.borrow().clone());
-                        } else {
-                            result.push(item.clone());
-                        }
-                    }
+                    let result = flatten_list(list, Some(1))?;
                     Ok(Value::List(Rc::new(RefCell::new(result))))
                 } else {
                     Err("flatten() needs a list".to_string())
@@ -5867,6 +7755,32 @@ impl Interpreter {
             }))),
         );
 
+        // flatten_deep - flatten up to `depth` levels; negative or left out means all the way
+        globals.borrow_mut().define(
+            "flatten_deep".to_string(),
+            Value::NativeFunction(Rc::new(NativeFunction::new(
+                "flatten_deep",
+                usize::MAX,
+                |args| {
+                    if args.is_empty() || args.len() > 2 {
+                        return Err("flatten_deep() expects 1 or 2 arguments".to_string());
+                    }
+                    let Value::List(list) = &args[0] else {
+                        return Err("flatten_deep() needs a list".to_string());
+                    };
+                    let depth = match args.get(1) {
+                        None | Some(Value::Nil) => None,
+                        Some(Value::Integer(n)) => usize::try_from(*n).ok(),
+                        Some(_) => {
+                            return Err("flatten_deep() depth must be an integer".to_string())
+                        }
+                    };
+                    let result = flatten_list(list, depth)?;
+                    Ok(Value::List(Rc::new(RefCell::new(result))))
+                },
+            ))),
+        );
+
         // zip - combine two lists into list of pairs
         globals.borrow_mut().define(
             "zip".to_string(),
@@ -6243,9 +8157,21 @@ impl Interpreter {
         );
 
         // pow - raise to a power (Scottish: mak it muckle!)
+        // Ints stay ints when the exponent is a small non-negative int
         globals.borrow_mut().define(
             "pow".to_string(),
             Value::NativeFunction(Rc::new(NativeFunction::new("pow", 2, |args| {
+                if let (Value::Integer(base), Value::Integer(exp)) = (&args[0], &args[1]) {
+                    if *exp >= 0 {
+                        return u32::try_from(*exp)
+                            .ok()
+                            .and_then(|exp| base.checked_pow(exp))
+                            .map(Value::Integer)
+                            .ok_or_else(|| {
+                                "pow() overflowed - that number's too muckle".to_string()
+                            });
+                    }
+                }
                 let base = match &args[0] {
                     Value::Float(f) => *f,
                     Value::Integer(n) => *n as f64,
@@ -6260,6 +8186,24 @@ impl Interpreter {
             }))),
         );
 
+        // fmod - floating-point remainder, keeping the sign of the first number
+        globals.borrow_mut().define(
+            "fmod".to_string(),
+            Value::NativeFunction(Rc::new(NativeFunction::new("fmod", 2, |args| {
+                let (a, b) = match (&args[0], &args[1]) {
+                    (Value::Integer(a), Value::Integer(b)) => (*a as f64, *b as f64),
+                    (Value::Integer(a), Value::Float(b)) => (*a as f64, *b),
+                    (Value::Float(a), Value::Integer(b)) => (*a, *b as f64),
+                    (Value::Float(a), Value::Float(b)) => (*a, *b),
+                    _ => return Err("fmod() needs twa numbers".to_string()),
+                };
+                if b == 0.0 {
+                    return Err("fmod() cannae divide by zero".to_string());
+                }
+                Ok(Value::Float(a % b))
+            }))),
+        );
+
         // asin - arc sine
         globals.borrow_mut().define(
             "asin".to_string(),
@@ -6415,19 +8359,8 @@ impl Interpreter {
             "blooter".to_string(),
             Value::NativeFunction(Rc::new(NativeFunction::new("blooter", 1, |args| {
                 if let Value::String(s) = &args[0] {
-                    use std::time::{SystemTime, UNIX_EPOCH};
-                    let seed = SystemTime::now()
-                        .duration_since(UNIX_EPOCH)
-                        .unwrap()
-                        .as_nanos() as u64;
                     let mut chars: Vec<char> = s.chars().collect();
-                    // Fisher-Yates shuffle
-                    let mut rng = seed;
-                    for i in (1..chars.len()).rev() {
-                        rng = rng.wrapping_mul(1103515245).wrapping_add(12345);
-                        let j = (rng as usize) % (i + 1);
-                        chars.swap(i, j);
-                    }
+                    with_rng(|rng| rng.shuffle(&mut chars));
                     Ok(Value::String(chars.into_iter().collect()))
                 } else {
                     Err("blooter() expects a string".to_string())
@@ -6787,16 +8720,15 @@ impl Interpreter {
             }))),
         );
 
-        // unique - remove duplicates from list (keeps first occurrence)
+        // unique - remove duplicates from list (keeps first occurrence); nested
+        // lists and dicts count as duplicates when their contents match
         globals.borrow_mut().define(
             "unique".to_string(),
             Value::NativeFunction(Rc::new(NativeFunction::new("unique", 1, |args| {
                 if let Value::List(list) = &args[0] {
-                    let mut seen = Vec::new();
-                    let mut result = Vec::new();
+                    let mut result: Vec<Value> = Vec::new();
                     for item in list.borrow().iter() {
-                        if !seen.contains(item) {
-                            seen.push(item.clone());
+                        if !result.iter().any(|kept| kept.deep_eq(item)) {
                             result.push(item.clone());
                         }
                     }
@@ -6807,6 +8739,40 @@ impl Interpreter {
             }))),
         );
 
+        // frequencies - tally each distinct item; keys come out in first-seen order
+        globals.borrow_mut().define(
+            "frequencies".to_string(),
+            Value::NativeFunction(Rc::new(NativeFunction::new("frequencies", 1, |args| {
+                let Value::List(list) = &args[0] else {
+                    return Err("frequencies() needs a list".to_string());
+                };
+                let mut tally = DictValue::new();
+                for item in list.borrow().iter() {
+                    match item {
+                        Value::Nil
+                        | Value::Bool(_)
+                        | Value::Integer(_)
+                        | Value::BigInt(_)
+                        | Value::Float(_)
+                        | Value::String(_)
+                        | Value::Range(_) => {}
+                        other => {
+                            return Err(format!(
+                                "frequencies() cannae tally a {} - it cannae be a dict key",
+                                other.type_name()
+                            ))
+                        }
+                    }
+                    let seen = match tally.get(item) {
+                        Some(Value::Integer(n)) => *n,
+                        _ => 0,
+                    };
+                    tally.set(item.clone(), Value::Integer(seen + 1));
+                }
+                Ok(Value::Dict(Rc::new(RefCell::new(tally))))
+            }))),
+        );
+
         // === File I/O Functions ===
 
         // scrieve - write to file (Scots: "write")
@@ -6903,7 +8869,6 @@ impl Interpreter {
         globals.borrow_mut().define(
             "haver".to_string(),
             Value::NativeFunction(Rc::new(NativeFunction::new("haver", 0, |_args| {
-                use std::time::{SystemTime, UNIX_EPOCH};
                 let havers = [
                     "Och, yer bum's oot the windae!",
                     "Awa' an bile yer heid!",
@@ -6921,13 +8886,8 @@ impl Interpreter {
                     "That's pure mince!",
                     "Jings, crivvens, help ma boab!",
                 ];
-                let seed = SystemTime::now()
-                    .duration_since(UNIX_EPOCH)
-                    .unwrap()
-                    .as_nanos() as u64;
-                let rng = seed.wrapping_mul(1103515245).wrapping_add(12345);
-                let idx = (rng as usize) % havers.len();
-                Ok(Value::String(havers[idx].to_string()))
+                let pick = with_rng(|rng| rng.pick(&havers).copied());
+                Ok(Value::String(pick.unwrap_or_default().to_string()))
             }))),
         );
 
@@ -6935,7 +8895,6 @@ impl Interpreter {
         globals.borrow_mut().define(
             "slainte".to_string(),
             Value::NativeFunction(Rc::new(NativeFunction::new("slainte", 0, |_args| {
-                use std::time::{SystemTime, UNIX_EPOCH};
                 let toasts = [
                     "Slàinte mhath! (Good health!)",
                     "Here's tae us, wha's like us? Gey few, and they're a' deid!",
@@ -6944,13 +8903,8 @@ impl Interpreter {
                     "May ye aye be happy, an' never drink frae a toom glass!",
                     "Here's tae the heath, the hill and the heather!",
                 ];
-                let seed = SystemTime::now()
-                    .duration_since(UNIX_EPOCH)
-                    .unwrap()
-                    .as_nanos() as u64;
-                let rng = seed.wrapping_mul(1103515245).wrapping_add(12345);
-                let idx = (rng as usize) % toasts.len();
-                Ok(Value::String(toasts[idx].to_string()))
+                let pick = with_rng(|rng| rng.pick(&toasts).copied());
+                Ok(Value::String(pick.unwrap_or_default().to_string()))
             }))),
         );
 
@@ -7009,22 +8963,13 @@ impl Interpreter {
         globals.borrow_mut().define(
             "bampot_mode".to_string(),
             Value::NativeFunction(Rc::new(NativeFunction::new("bampot_mode", 1, |args| {
-                use std::time::{SystemTime, UNIX_EPOCH};
                 if let Value::List(list) = &args[0] {
                     let mut items: Vec<Value> = list.borrow().clone();
-                    let seed = SystemTime::now()
-                        .duration_since(UNIX_EPOCH)
-                        .unwrap()
-                        .as_nanos() as u64;
-                    let mut rng = seed;
                     // Double shuffle for extra chaos!
-                    for _ in 0..2 {
-                        for i in (1..items.len()).rev() {
-                            rng = rng.wrapping_mul(1103515245).wrapping_add(12345);
-                            let j = (rng as usize) % (i + 1);
-                            items.swap(i, j);
-                        }
-                    }
+                    with_rng(|rng| {
+                        rng.shuffle(&mut items);
+                        rng.shuffle(&mut items);
+                    });
                     items.reverse(); // And reverse for good measure!
                     Ok(Value::List(Rc::new(RefCell::new(items))))
                 } else {
@@ -7342,6 +9287,26 @@ impl Interpreter {
             Value::String("__builtin_aw__".to_string()),
         );
 
+        // any / all / find - English names for ony, aw and hunt
+        globals.borrow_mut().define(
+            "any".to_string(),
+            Value::String("__builtin_any__".to_string()),
+        );
+        globals.borrow_mut().define(
+            "all".to_string(),
+            Value::String("__builtin_all__".to_string()),
+        );
+        globals.borrow_mut().define(
+            "find".to_string(),
+            Value::String("__builtin_find__".to_string()),
+        );
+
+        // count - how many elements match
+        globals.borrow_mut().define(
+            "count".to_string(),
+            Value::String("__builtin_count__".to_string()),
+        );
+
         // grup_up - group list elements by function result (Scots: group up)
         globals.borrow_mut().define(
             "grup_up".to_string(),
@@ -7593,20 +9558,11 @@ impl Interpreter {
         globals.borrow_mut().define(
             "dram".to_string(),
             Value::NativeFunction(Rc::new(NativeFunction::new("dram", 1, |args| {
-                use std::time::{SystemTime, UNIX_EPOCH};
                 let list = match &args[0] {
                     Value::List(l) => l.borrow().clone(),
                     _ => return Err("dram needs a list tae pick fae".to_string()),
                 };
-                if list.is_empty() {
-                    return Ok(Value::Nil);
-                }
-                let seed = SystemTime::now()
-                    .duration_since(UNIX_EPOCH)
-                    .unwrap()
-                    .as_nanos() as usize;
-                let idx = seed % list.len();
-                Ok(list[idx].clone())
+                Ok(with_rng(|rng| rng.pick(&list).cloned()).unwrap_or(Value::Nil))
             }))),
         );
 
@@ -7638,23 +9594,12 @@ impl Interpreter {
         globals.borrow_mut().define(
             "stooshie".to_string(),
             Value::NativeFunction(Rc::new(NativeFunction::new("stooshie", 1, |args| {
-                use std::time::{SystemTime, UNIX_EPOCH};
                 let s = match &args[0] {
                     Value::String(s) => s.clone(),
                     _ => return Err("stooshie needs a string".to_string()),
                 };
                 let mut chars: Vec<char> = s.chars().collect();
-                // Simple Fisher-Yates shuffle
-                let seed = SystemTime::now()
-                    .duration_since(UNIX_EPOCH)
-                    .unwrap()
-                    .as_nanos() as u64;
-                let mut rng = seed;
-                for i in (1..chars.len()).rev() {
-                    rng = rng.wrapping_mul(1103515245).wrapping_add(12345);
-                    let j = (rng as usize) % (i + 1);
-                    chars.swap(i, j);
-                }
+                with_rng(|rng| rng.shuffle(&mut chars));
                 Ok(Value::String(chars.into_iter().collect()))
             }))),
         );
@@ -7671,7 +9616,7 @@ impl Interpreter {
                     Value::Set(s) => format!("creel wi' {} items", s.borrow().len()),
                     Value::String(s) => format!("string o' {} characters", s.len()),
                     Value::Integer(n) => format!("integer: {}", n),
-                    Value::Float(f) => format!("float: {}", f),
+                    Value::Float(f) => format!("float: {}", format_float(*f)),
                     Value::Bool(b) => format!("boolean: {}", if *b { "aye" } else { "nae" }),
                     Value::Nil => "naething".to_string(),
                     Value::Function(f) => format!("function '{}'", f.name),
@@ -7688,24 +9633,11 @@ impl Interpreter {
         globals.borrow_mut().define(
             "sclaff".to_string(),
             Value::NativeFunction(Rc::new(NativeFunction::new("sclaff", 1, |args| {
-                fn flatten_recursive(val: &Value, result: &mut Vec<Value>) {
-                    match val {
-                        Value::List(l) => {
-                            for item in l.borrow().iter() {
-                                flatten_recursive(item, result);
-                            }
-                        }
-                        other => result.push(other.clone()),
-                    }
-                }
                 let list = match &args[0] {
                     Value::List(l) => l.clone(),
                     _ => return Err("sclaff needs a list".to_string()),
                 };
-                let mut result = Vec::new();
-                for item in list.borrow().iter() {
-                    flatten_recursive(item, &mut result);
-                }
+                let result = flatten_list(&list, None)?;
                 Ok(Value::List(Rc::new(RefCell::new(result))))
             }))),
         );
@@ -7748,6 +9680,72 @@ impl Interpreter {
             }))),
         );
 
+        // now - seconds since the Unix epoch, as a float
+        globals.borrow_mut().define(
+            "now".to_string(),
+            Value::NativeFunction(Rc::new(NativeFunction::new("now", 0, |_args| {
+                use std::time::{SystemTime, UNIX_EPOCH};
+                let duration = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+                Ok(Value::Float(duration.as_secs_f64()))
+            }))),
+        );
+
+        // now_ms - milliseconds since the Unix epoch, as an integer
+        globals.borrow_mut().define(
+            "now_ms".to_string(),
+            Value::NativeFunction(Rc::new(NativeFunction::new("now_ms", 0, |_args| {
+                use std::time::{SystemTime, UNIX_EPOCH};
+                let duration = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+                Ok(Value::Integer(duration.as_millis() as i64))
+            }))),
+        );
+
+        // sleep - pause for milliseconds (a no-op in the browser, which cannot block)
+        globals.borrow_mut().define(
+            "sleep".to_string(),
+            Value::NativeFunction(Rc::new(NativeFunction::new("sleep", 1, |args| {
+                let ms = match &args[0] {
+                    Value::Integer(n) => *n as f64,
+                    Value::Float(f) => *f,
+                    _ => return Err("sleep() needs a number o' milliseconds".to_string()),
+                };
+                if ms < 0.0 {
+                    return Err("Cannae sleep fer negative time, ya daftie!".to_string());
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                std::thread::sleep(std::time::Duration::from_secs_f64(ms / 1000.0));
+                Ok(Value::Nil)
+            }))),
+        );
+
+        // format_time - format epoch seconds as a UTC timestamp (strftime codes)
+        globals.borrow_mut().define(
+            "format_time".to_string(),
+            Value::NativeFunction(Rc::new(NativeFunction::new("format_time", 2, |args| {
+                use chrono::format::{Item, StrftimeItems};
+                use chrono::DateTime;
+                let (secs, nanos) = match &args[0] {
+                    Value::Integer(n) => (*n, 0),
+                    Value::Float(f) if f.is_finite() => {
+                        let whole = f.floor();
+                        (whole as i64, ((f - whole) * 1e9) as u32)
+                    }
+                    _ => return Err("format_time() needs epoch seconds as a number".to_string()),
+                };
+                let format = match &args[1] {
+                    Value::String(s) => s,
+                    _ => return Err("format_time() needs a format string".to_string()),
+                };
+                let items: Vec<Item> = StrftimeItems::new(format).collect();
+                if items.iter().any(|item| matches!(item, Item::Error)) {
+                    return Err(format!("format_time() doesnae ken the format '{}'", format));
+                }
+                let dt = DateTime::from_timestamp(secs, nanos)
+                    .ok_or("format_time() got a timestamp oot o' range")?;
+                Ok(Value::String(dt.format_with_items(items.iter()).to_string()))
+            }))),
+        );
+
         // stopwatch - time a function call and return [result, time_ms]
         globals.borrow_mut().define(
             "stopwatch".to_string(),
@@ -7962,14 +9960,86 @@ impl Interpreter {
             }))),
         );
 
-        // json_pretty - convert a value tae pretty-printed JSON string
+        // json_pretty - convert a value to pretty-printed JSON string, with an
+        // optional indent and sorted keys
         globals.borrow_mut().define(
             "json_pretty".to_string(),
-            Value::NativeFunction(Rc::new(NativeFunction::new("json_pretty", 1, |args| {
-                Ok(Value::String(value_to_json_pretty(&args[0], 0)))
+            Value::NativeFunction(Rc::new(NativeFunction::new(
+                "json_pretty",
+                usize::MAX,
+                json_pretty_value,
+            ))),
+        );
+
+        // json_query - dig into a parsed JSON value with a path like "a.b[0].c"
+        globals.borrow_mut().define(
+            "json_query".to_string(),
+            Value::NativeFunction(Rc::new(NativeFunction::new("json_query", 2, |args| {
+                let Value::String(path) = &args[1] else {
+                    return Err("json_query() needs the path as a string".to_string());
+                };
+                Ok(json_query_value(&args[0], &parse_json_path(path)?))
             }))),
         );
 
+        // csv_parse - CSV text into a list of rows, each a list of strings
+        globals.borrow_mut().define(
+            "csv_parse".to_string(),
+            Value::NativeFunction(Rc::new(NativeFunction::new(
+                "csv_parse",
+                usize::MAX,
+                |args| {
+                    if args.is_empty() || args.len() > 2 {
+                        return Err("csv_parse() expects 1 or 2 arguments".to_string());
+                    }
+                    let Value::String(text) = &args[0] else {
+                        return Err("csv_parse() expects a string".to_string());
+                    };
+                    let delim = csv_delimiter("csv_parse", args.get(1))?;
+                    let rows = csv_parse_rows(text, delim)?
+                        .into_iter()
+                        .map(|row| {
+                            let fields = row.into_iter().map(Value::String).collect();
+                            Value::List(Rc::new(RefCell::new(fields)))
+                        })
+                        .collect();
+                    Ok(Value::List(Rc::new(RefCell::new(rows))))
+                },
+            ))),
+        );
+
+        // csv_write - a list of rows back to CSV text, quoting where needed
+        globals.borrow_mut().define(
+            "csv_write".to_string(),
+            Value::NativeFunction(Rc::new(NativeFunction::new(
+                "csv_write",
+                usize::MAX,
+                |args| {
+                    if args.is_empty() || args.len() > 2 {
+                        return Err("csv_write() expects 1 or 2 arguments".to_string());
+                    }
+                    let Value::List(rows) = &args[0] else {
+                        return Err("csv_write() expects a list o' rows".to_string());
+                    };
+                    let delim = csv_delimiter("csv_write", args.get(1))?;
+                    let rows = rows
+                        .borrow()
+                        .iter()
+                        .map(|row| match row {
+                            Value::List(fields) => {
+                                Ok(fields.borrow().iter().map(|f| f.to_string()).collect())
+                            }
+                            _ => Err(format!(
+                                "csv_write() needs every row tae be a list, no' a {}",
+                                row.type_name()
+                            )),
+                        })
+                        .collect::<Result<Vec<Vec<String>>, String>>()?;
+                    Ok(Value::String(csv_write_rows(&rows, delim)))
+                },
+            ))),
+        );
+
         // ============================================================
         // BITWISE OPERATIONS - Fer aw yer binary fiddlin' needs!
         // ============================================================
@@ -8076,7 +10146,7 @@ impl Interpreter {
                 "tae_binary",
                 1,
                 |args| match &args[0] {
-                    Value::Integer(n) => Ok(Value::String(format!("{:b}", n))),
+                    Value::Integer(n) => int_to_base(*n, 2).map(Value::String),
                     _ => Err("tae_binary() needs an integer".to_string()),
                 },
             ))),
@@ -8089,7 +10159,7 @@ impl Interpreter {
                 "tae_hex",
                 1,
                 |args| match &args[0] {
-                    Value::Integer(n) => Ok(Value::String(format!("{:x}", n))),
+                    Value::Integer(n) => int_to_base(*n, 16).map(Value::String),
                     _ => Err("tae_hex() needs an integer".to_string()),
                 },
             ))),
@@ -8102,12 +10172,55 @@ impl Interpreter {
                 "tae_octal",
                 1,
                 |args| match &args[0] {
-                    Value::Integer(n) => Ok(Value::String(format!("{:o}", n))),
+                    Value::Integer(n) => int_to_base(*n, 8).map(Value::String),
                     _ => Err("tae_octal() needs an integer".to_string()),
                 },
             ))),
         );
 
+        // tae_oct - convert to octal string (alias for tae_octal)
+        globals.borrow_mut().define(
+            "tae_oct".to_string(),
+            Value::NativeFunction(Rc::new(NativeFunction::new(
+                "tae_oct",
+                1,
+                |args| match &args[0] {
+                    Value::Integer(n) => int_to_base(*n, 8).map(Value::String),
+                    _ => Err("tae_oct() needs an integer".to_string()),
+                },
+            ))),
+        );
+
+        // tae_base - write an integer in any base from 2 to 36
+        globals.borrow_mut().define(
+            "tae_base".to_string(),
+            Value::NativeFunction(Rc::new(NativeFunction::new("tae_base", 2, |args| {
+                match (&args[0], &args[1]) {
+                    (Value::Integer(n), Value::Integer(base)) => {
+                        let base = u32::try_from(*base)
+                            .map_err(|_| format!("Base must be atween 2 an' 36, no' {}", base))?;
+                        int_to_base(*n, base).map(Value::String)
+                    }
+                    _ => Err("tae_base() needs an integer an' a base".to_string()),
+                }
+            }))),
+        );
+
+        // frae_base - read a string written in any base from 2 to 36
+        globals.borrow_mut().define(
+            "frae_base".to_string(),
+            Value::NativeFunction(Rc::new(NativeFunction::new("frae_base", 2, |args| {
+                match (&args[0], &args[1]) {
+                    (Value::String(s), Value::Integer(base)) => {
+                        let base = u32::try_from(*base)
+                            .map_err(|_| format!("Base must be atween 2 an' 36, no' {}", base))?;
+                        int_from_base(s, base).map(Value::Integer)
+                    }
+                    _ => Err("frae_base() needs a string an' a base".to_string()),
+                }
+            }))),
+        );
+
         // fae_binary - parse binary string to integer
         globals.borrow_mut().define(
             "fae_binary".to_string(),
@@ -8161,19 +10274,40 @@ impl Interpreter {
             ))),
         );
 
-        // dict_get - get value with default (avoids crashes!)
+        // dict_get(d, key, default = naething) - get value with default (avoids crashes!)
         globals.borrow_mut().define(
             "dict_get".to_string(),
             Value::NativeFunction(Rc::new(NativeFunction::new(
                 "dict_get",
+                usize::MAX,
+                |args| {
+                    if args.len() < 2 || args.len() > 3 {
+                        return Err("dict_get() expects 2 or 3 arguments".to_string());
+                    }
+                    match &args[0] {
+                        Value::Dict(d) => Ok(d
+                            .borrow()
+                            .get(&args[1])
+                            .cloned()
+                            .unwrap_or_else(|| args.get(2).cloned().unwrap_or(Value::Nil))),
+                        _ => Err("dict_get() needs a dictionary".to_string()),
+                    }
+                },
+            ))),
+        );
+
+        // dict_set - set a key in place and return the same dict
+        globals.borrow_mut().define(
+            "dict_set".to_string(),
+            Value::NativeFunction(Rc::new(NativeFunction::new(
+                "dict_set",
                 3,
                 |args| match &args[0] {
-                    Value::Dict(d) => Ok(d
-                        .borrow()
-                        .get(&args[1])
-                        .cloned()
-                        .unwrap_or_else(|| args[2].clone())),
-                    _ => Err("dict_get() needs a dictionary".to_string()),
+                    Value::Dict(d) => {
+                        d.borrow_mut().set(args[1].clone(), args[2].clone());
+                        Ok(args[0].clone())
+                    }
+                    _ => Err("dict_set() needs a dictionary".to_string()),
                 },
             ))),
         );
@@ -8208,6 +10342,50 @@ impl Interpreter {
             ))),
         );
 
+        // get - look up a key, with a default for when it's not there
+        globals.borrow_mut().define(
+            "get".to_string(),
+            Value::NativeFunction(Rc::new(NativeFunction::new(
+                "get",
+                3,
+                |args| match &args[0] {
+                    Value::Dict(d) => Ok(d
+                        .borrow()
+                        .get(&args[1])
+                        .cloned()
+                        .unwrap_or_else(|| args[2].clone())),
+                    _ => Err("get() needs a dictionary".to_string()),
+                },
+            ))),
+        );
+
+        // has_key - is this key in the dictionary?
+        globals.borrow_mut().define(
+            "has_key".to_string(),
+            Value::NativeFunction(Rc::new(NativeFunction::new(
+                "has_key",
+                2,
+                |args| match &args[0] {
+                    Value::Dict(d) => Ok(Value::Bool(d.borrow().contains_key(&args[1]))),
+                    _ => Err("has_key() needs a dictionary".to_string()),
+                },
+            ))),
+        );
+
+        // remove - take a key out of the dictionary in place, returning its value
+        // (nil if the key was not there)
+        globals.borrow_mut().define(
+            "remove".to_string(),
+            Value::NativeFunction(Rc::new(NativeFunction::new(
+                "remove",
+                2,
+                |args| match &args[0] {
+                    Value::Dict(d) => Ok(d.borrow_mut().remove(&args[1]).unwrap_or(Value::Nil)),
+                    _ => Err("remove() needs a dictionary".to_string()),
+                },
+            ))),
+        );
+
         // dict_invert - swap keys and values
         globals.borrow_mut().define(
             "dict_invert".to_string(),
@@ -8451,45 +10629,61 @@ impl Interpreter {
         // MORE MATHEMATICAL FUNCTIONS
         // ============================================================
 
-        // sign - get sign of number (-1, 0, or 1)
+        // sign - get sign of number (-1, 0, or 1), a float for a float
         globals.borrow_mut().define(
             "sign".to_string(),
             Value::NativeFunction(Rc::new(NativeFunction::new(
                 "sign",
                 1,
                 |args| match &args[0] {
-                    Value::Integer(n) => Ok(Value::Integer(if *n > 0 {
-                        1
-                    } else if *n < 0 {
-                        -1
-                    } else {
-                        0
-                    })),
-                    Value::Float(f) => Ok(Value::Integer(if *f > 0.0 {
-                        1
+                    Value::Integer(n) => Ok(Value::Integer(n.signum())),
+                    Value::Float(f) => Ok(Value::Float(if *f > 0.0 {
+                        1.0
                     } else if *f < 0.0 {
-                        -1
+                        -1.0
+                    } else if f.is_nan() {
+                        f64::NAN
                     } else {
-                        0
+                        0.0
                     })),
                     _ => Err("sign() needs a number".to_string()),
                 },
             ))),
         );
 
-        // clamp - constrain a value between min and max
+        // clamp - constrain a value between lo and hi; ints stay ints, any
+        // float makes it a float
         globals.borrow_mut().define(
             "clamp".to_string(),
             Value::NativeFunction(Rc::new(NativeFunction::new("clamp", 3, |args| {
-                match (&args[0], &args[1], &args[2]) {
-                    (Value::Integer(n), Value::Integer(min), Value::Integer(max)) => {
-                        Ok(Value::Integer((*n).max(*min).min(*max)))
-                    }
-                    (Value::Float(n), Value::Float(min), Value::Float(max)) => {
-                        Ok(Value::Float(n.max(*min).min(*max)))
+                let bounds_error = || {
+                    format!(
+                        "clamp() needs lo no bigger than hi, but got {} an' {}",
+                        args[1], args[2]
+                    )
+                };
+                if let (Value::Integer(x), Value::Integer(lo), Value::Integer(hi)) =
+                    (&args[0], &args[1], &args[2])
+                {
+                    if lo > hi {
+                        return Err(bounds_error());
                     }
-                    _ => Err("clamp() needs three numbers o' the same type".to_string()),
+                    return Ok(Value::Integer(*x.clamp(lo, hi)));
+                }
+                let number = |v: &Value| match v {
+                    Value::Float(f) => Some(*f),
+                    Value::Integer(n) => Some(*n as f64),
+                    _ => None,
+                };
+                let (Some(x), Some(lo), Some(hi)) =
+                    (number(&args[0]), number(&args[1]), number(&args[2]))
+                else {
+                    return Err("clamp() needs three numbers".to_string());
+                };
+                if lo > hi || lo.is_nan() || hi.is_nan() {
+                    return Err(bounds_error());
                 }
+                Ok(Value::Float(x.clamp(lo, hi)))
             }))),
         );
 
@@ -8645,21 +10839,17 @@ impl Interpreter {
         // ASSERTION FUNCTIONS - Test yer code, ya numpty!
         // ============================================================
 
-        // assert - throw error if condition is false
+        // assert(condition, message?) - fail if the condition is not truthy
+        // (handled in call_builtin_hof, so the error has the call's line)
         globals.borrow_mut().define(
             "assert".to_string(),
-            Value::NativeFunction(Rc::new(NativeFunction::new("assert", 2, |args| {
-                let condition = args[0].is_truthy();
-                if !condition {
-                    let msg = match &args[1] {
-                        Value::String(s) => s.clone(),
-                        _ => format!("{}", args[1]),
-                    };
-                    Err(format!("Assertion failed: {}", msg))
-                } else {
-                    Ok(Value::Bool(true))
-                }
-            }))),
+            Value::String("__builtin_assert__".to_string()),
+        );
+
+        // assert_eq(actual, expected) - fail with both values if they differ
+        globals.borrow_mut().define(
+            "assert_eq".to_string(),
+            Value::String("__builtin_assert_eq__".to_string()),
         );
 
         // assert_equal - throw error if values are not equal
@@ -8696,6 +10886,12 @@ impl Interpreter {
             ))),
         );
 
+        // expeck_error(thunk, text?) - the thunk must fail (handled in call_builtin_hof)
+        globals.borrow_mut().define(
+            "expeck_error".to_string(),
+            Value::String("__builtin_expeck_error__".to_string()),
+        );
+
         // ============================================================
         // LIST STATISTICS - Fer number-crunchin'!
         // ============================================================
@@ -8800,25 +10996,14 @@ impl Interpreter {
                     if items.is_empty() {
                         return Err("Cannae find minimum o' empty list!".to_string());
                     }
-                    let mut min_val = items[0].clone();
+                    let mut min_val = &items[0];
                     for item in items.iter().skip(1) {
-                        match (&min_val, item) {
-                            (Value::Integer(a), Value::Integer(b)) => {
-                                if *b < *a {
-                                    min_val = item.clone();
-                                }
-                            }
-                            (Value::Float(a), Value::Float(b)) => {
-                                if *b < *a {
-                                    min_val = item.clone();
-                                }
-                            }
-                            _ => {
-                                return Err("minaw() needs a list o' comparable numbers".to_string())
-                            }
+                        let (best, item) = ordered_pair("minaw", min_val, item)?;
+                        if item < best {
+                            min_val = item;
                         }
                     }
-                    Ok(min_val)
+                    Ok(min_val.clone())
                 } else {
                     Err("minaw() needs a list".to_string())
                 }
@@ -8834,25 +11019,14 @@ impl Interpreter {
                     if items.is_empty() {
                         return Err("Cannae find maximum o' empty list!".to_string());
                     }
-                    let mut max_val = items[0].clone();
+                    let mut max_val = &items[0];
                     for item in items.iter().skip(1) {
-                        match (&max_val, item) {
-                            (Value::Integer(a), Value::Integer(b)) => {
-                                if *b > *a {
-                                    max_val = item.clone();
-                                }
-                            }
-                            (Value::Float(a), Value::Float(b)) => {
-                                if *b > *a {
-                                    max_val = item.clone();
-                                }
-                            }
-                            _ => {
-                                return Err("maxaw() needs a list o' comparable numbers".to_string())
-                            }
+                        let (best, item) = ordered_pair("maxaw", max_val, item)?;
+                        if item > best {
+                            max_val = item;
                         }
                     }
-                    Ok(max_val)
+                    Ok(max_val.clone())
                 } else {
                     Err("maxaw() needs a list".to_string())
                 }
@@ -8982,7 +11156,57 @@ impl Interpreter {
                     Value::String(s) => s.clone(),
                     _ => return Err("is_dir() needs a path string".to_string()),
                 };
-                Ok(Value::Bool(std::path::Path::new(&path).is_dir()))
+                Ok(Value::Bool(script_path(&path).is_dir()))
+            }))),
+        );
+
+        // is_file - check if path is a regular file
+        globals.borrow_mut().define(
+            "is_file".to_string(),
+            Value::NativeFunction(Rc::new(NativeFunction::new("is_file", 1, |args| {
+                let path = match &args[0] {
+                    Value::String(s) => s.clone(),
+                    _ => return Err("is_file() needs a path string".to_string()),
+                };
+                Ok(Value::Bool(script_path(&path).is_file()))
+            }))),
+        );
+
+        // read_dir - sorted entry names in a directory
+        globals.borrow_mut().define(
+            "read_dir".to_string(),
+            Value::NativeFunction(Rc::new(NativeFunction::new("read_dir", 1, |args| {
+                let path = match &args[0] {
+                    Value::String(s) => s.clone(),
+                    _ => return Err("read_dir() needs a directory path string".to_string()),
+                };
+                let names = read_dir_names("read_dir", &path)?
+                    .into_iter()
+                    .map(Value::String)
+                    .collect();
+                Ok(Value::List(Rc::new(RefCell::new(names))))
+            }))),
+        );
+
+        // walk_dir - every file under a directory, relative to it
+        globals.borrow_mut().define(
+            "walk_dir".to_string(),
+            Value::NativeFunction(Rc::new(NativeFunction::new("walk_dir", 1, |args| {
+                let path = match &args[0] {
+                    Value::String(s) => s.clone(),
+                    _ => return Err("walk_dir() needs a directory path string".to_string()),
+                };
+                let root = script_path(&path);
+                if !root.is_dir() {
+                    return Err(format!(
+                        "walk_dir() cannae read '{}' - it's no' a directory",
+                        path
+                    ));
+                }
+                let mut files = Vec::new();
+                walk_dir_files(&root, "", &mut files)?;
+                let files = files.into_iter().map(Value::String).collect();
+                Ok(Value::List(Rc::new(RefCell::new(files))))
             }))),
         );
 
@@ -9153,26 +11377,18 @@ impl Interpreter {
         // STDLIB EXPANSION - Math Functions
         // ============================================================
 
-        // random - random float between 0.0 and 1.0
+        // random - uniform float in [0.0, 1.0)
         globals.borrow_mut().define(
             "random".to_string(),
             Value::NativeFunction(Rc::new(NativeFunction::new("random", 0, |_args| {
-                use std::time::{SystemTime, UNIX_EPOCH};
-                let seed = SystemTime::now()
-                    .duration_since(UNIX_EPOCH)
-                    .unwrap()
-                    .as_nanos() as u64;
-                let rng = seed.wrapping_mul(1103515245).wrapping_add(12345);
-                let random_float = (rng as f64) / (u64::MAX as f64);
-                Ok(Value::Float(random_float))
+                Ok(Value::Float(with_rng(Prng::next_f64)))
             }))),
         );
 
-        // random_int - random integer in range (inclusive)
+        // random_int - uniform integer in [min, max], both ends included
         globals.borrow_mut().define(
             "random_int".to_string(),
             Value::NativeFunction(Rc::new(NativeFunction::new("random_int", 2, |args| {
-                use std::time::{SystemTime, UNIX_EPOCH};
                 let min = args[0]
                     .as_integer()
                     .ok_or("random_int() needs integer bounds")?;
@@ -9182,40 +11398,52 @@ impl Interpreter {
                 if min > max {
                     return Err("random_int() min must be <= max".to_string());
                 }
-                let seed = SystemTime::now()
-                    .duration_since(UNIX_EPOCH)
-                    .unwrap()
-                    .as_nanos() as u64;
-                let rng = seed.wrapping_mul(1103515245).wrapping_add(12345);
-                let range = (max - min + 1) as u64;
-                let result = min + ((rng % range) as i64);
-                Ok(Value::Integer(result))
+                Ok(Value::Integer(with_rng(|rng| rng.between(min, max))))
             }))),
         );
 
-        // random_choice - random element from list
+        // random_choice - any element of a list, each equally likely
+        // (nil for an empty list)
         globals.borrow_mut().define(
             "random_choice".to_string(),
             Value::NativeFunction(Rc::new(NativeFunction::new("random_choice", 1, |args| {
-                use std::time::{SystemTime, UNIX_EPOCH};
                 if let Value::List(list) = &args[0] {
                     let items = list.borrow();
-                    if items.is_empty() {
-                        return Ok(Value::Nil);
-                    }
-                    let seed = SystemTime::now()
-                        .duration_since(UNIX_EPOCH)
-                        .unwrap()
-                        .as_nanos() as u64;
-                    let rng = seed.wrapping_mul(1103515245).wrapping_add(12345);
-                    let idx = (rng as usize) % items.len();
-                    Ok(items[idx].clone())
+                    Ok(with_rng(|rng| rng.pick(&items).cloned()).unwrap_or(Value::Nil))
                 } else {
                     Err("random_choice() needs a list".to_string())
                 }
             }))),
         );
 
+        // seed_random - restart this interpreter's generator from a known seed
+        globals.borrow_mut().define(
+            "seed_random".to_string(),
+            Value::NativeFunction(Rc::new(NativeFunction::new("seed_random", 1, |args| {
+                let seed = args[0]
+                    .as_integer()
+                    .ok_or("seed_random() needs an integer seed")?;
+                with_rng(|rng| *rng = Prng::new(seed as u64));
+                Ok(Value::Nil)
+            }))),
+        );
+
+        // shaw / shaw_pretty - print a type-tagged dump of a value
+        for (name, pretty) in [("shaw", false), ("shaw_pretty", true)] {
+            globals.borrow_mut().define(
+                name.to_string(),
+                Value::NativeFunction(Rc::new(NativeFunction::new(name, 1, move |args| {
+                    let mut text = Some(args[0].shaw(pretty));
+                    if with_current_interpreter(|interp| interp.write_output(text.take().unwrap()))
+                        .is_none()
+                    {
+                        println!("{}", text.unwrap());
+                    }
+                    Ok(Value::Nil)
+                }))),
+            );
+        }
+
         // pi - return PI constant
         globals.borrow_mut().define(
             "pi".to_string(),
@@ -9734,6 +11962,12 @@ impl Interpreter {
             }))),
         );
 
+        // boak - abort the program with a message (handled in call_builtin_hof)
+        globals.borrow_mut().define(
+            "boak".to_string(),
+            Value::String("__builtin_boak__".to_string()),
+        );
+
         // args - get command line arguments
         globals.borrow_mut().define(
             "args".to_string(),
@@ -9764,6 +11998,10 @@ impl Interpreter {
                 };
                 std::env::set_current_dir(&path)
                     .map_err(|e| format!("Couldnae change tae directory '{}': {}", path, e))?;
+                // Keep relative paths in read_dir and friends in step
+                if let Ok(dir) = std::env::current_dir() {
+                    with_current_interpreter(|interp| interp.current_dir = dir);
+                }
                 Ok(Value::Nil)
             }))),
         );
@@ -9773,43 +12011,162 @@ impl Interpreter {
             "json_stringify_pretty".to_string(),
             Value::NativeFunction(Rc::new(NativeFunction::new(
                 "json_stringify_pretty",
-                1,
-                |args| Ok(Value::String(value_to_json_pretty(&args[0], 0))),
+                usize::MAX,
+                json_pretty_value,
             ))),
         );
     }
 
     /// Run a program
     pub fn interpret(&mut self, program: &Program) -> HaversResult<Value> {
+        self.error_trace = None;
         let mut result = Value::Nil;
         for stmt in &program.statements {
-            result = self.execute_stmt(stmt)?;
+            result = self
+                .execute_stmt(stmt)
+                .map_err(|e| self.attach_backtrace(e))?;
         }
         Ok(result)
     }
 
+    /// Wrap an error that came out of a function call with the stack it came from
+    fn attach_backtrace(&mut self, error: HaversError) -> HaversError {
+        match self.error_trace.take() {
+            Some(frames) if !frames.is_empty() && error.backtrace().is_none() => {
+                HaversError::WithBacktrace {
+                    error: Box::new(error),
+                    backtrace: format_stack_trace(&frames, MAX_BACKTRACE_FRAMES),
+                }
+            }
+            _ => error,
+        }
+    }
+
     /// Get captured output (for testing)
     #[allow(dead_code)]
     pub fn get_output(&self) -> &[String] {
         &self.output
     }
 
+    /// Hand back the captured output and empty the buffer, so the next run
+    /// on this interpreter starts fresh
+    pub fn take_output(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.output)
+    }
+
     /// Clear captured output
     #[allow(dead_code)]
     pub fn clear_output(&mut self) {
         self.output.clear();
     }
 
+    /// Hand each `blether` line to `sink` as soon as it's printed, instead of
+    /// writing it to stdout. The sink is called on this thread, in order, so
+    /// anything printed before an error has already been delivered.
+    pub fn set_output_sink(&mut self, sink: OutputSink) {
+        self.output_sink = Some(sink);
+    }
+
+    /// Go back to printing `blether` lines on stdout
+    pub fn clear_output_sink(&mut self) {
+        self.output_sink = None;
+    }
+
+    /// Feed `speir` from `source` instead of stdin, a line at a time. Once the
+    /// source runs dry `speir` returns nil.
+    pub fn set_input_source(&mut self, source: Box<dyn BufRead>) {
+        self.input_source = Some(source);
+    }
+
+    /// Show the prompt and read a line for `speir`; nil at the end of the input
+    fn speir(&mut self, prompt: &Value) -> HaversResult<Value> {
+        print!("{}", prompt);
+        io::stdout().flush().unwrap();
+
+        let mut input = String::new();
+        let read = match self.input_source.as_mut() {
+            Some(source) => source.read_line(&mut input),
+            #[cfg(not(coverage))]
+            None => io::stdin().read_line(&mut input),
+            #[cfg(coverage)]
+            None => {
+                return Err(HaversError::InternalError(
+                    "speir() input is disabled under coverage runs".to_string(),
+                ))
+            }
+        }
+        .map_err(|e| HaversError::InternalError(e.to_string()))?;
+
+        if read == 0 {
+            return Ok(Value::Nil);
+        }
+        Ok(Value::String(input.trim().to_string()))
+    }
+
+    /// Restart the random built-ins from `seed`, so a run can be repeated
+    pub fn seed_random(&mut self, seed: u64) {
+        self.rng = Prng::new(seed);
+    }
+
+    /// Whether to keep printed lines for get_output/take_output (on by
+    /// default). Turn it off for long runs that stream through a sink.
+    pub fn set_keep_output(&mut self, keep: bool) {
+        self.keep_output = keep;
+    }
+
+    /// Write at most `limit` printed lines; after that the rest are dropped
+    /// and a single "… output truncated efter N lines" line is written in
+    /// their place. The program keeps running (`None` lifts the cap), and
+    /// either way the count starts again from zero.
+    pub fn set_output_limit(&mut self, limit: Option<usize>) {
+        self.output_limit = limit;
+        self.output_lines = 0;
+        self.output_truncated = false;
+    }
+
+    /// Whether any output has been dropped by the output limit
+    pub fn output_truncated(&self) -> bool {
+        self.output_truncated
+    }
+
+    /// Send a printed line to the sink (or stdout) and keep it if asked
+    fn write_output(&mut self, output: String) {
+        if let Some(limit) = self.output_limit {
+            if self.output_truncated {
+                return;
+            }
+            if self.output_lines >= limit {
+                self.output_truncated = true;
+                let marker = format!("… output truncated efter {} lines", limit);
+                self.write_output_line(marker);
+                return;
+            }
+            self.output_lines += 1;
+        }
+        self.write_output_line(output);
+    }
+
+    fn write_output_line(&mut self, output: String) {
+        match self.output_sink.as_mut() {
+            Some(sink) => sink(&output),
+            None => println!("{}", output),
+        }
+        if self.keep_output {
+            self.output.push(output);
+        }
+    }
+
     /// Load a module fae a file
     #[cfg(target_arch = "wasm32")]
     fn load_module(
         &mut self,
         path: &str,
         alias: Option<&str>,
+        only: Option<&[String]>,
         span: Span,
     ) -> Result<Result<Value, ControlFlow>, HaversError> {
         if crate::tri::is_tri_module(path) {
-            return self.load_tri_module(alias, span);
+            return self.load_tri_module(alias, only, span);
         }
         Err(HaversError::InvalidOperation {
             operation: "module loading is not supported in this build".to_string(),
@@ -9823,10 +12180,11 @@ impl Interpreter {
         &mut self,
         path: &str,
         alias: Option<&str>,
+        only: Option<&[String]>,
         span: Span,
     ) -> Result<Result<Value, ControlFlow>, HaversError> {
         if crate::tri::is_tri_module(path) {
-            return self.load_tri_module(alias, span);
+            return self.load_tri_module(alias, only, span);
         }
         // Resolve the module path
         let module_path = self.resolve_module_path(path)?;
@@ -9846,7 +12204,7 @@ impl Interpreter {
 
         // Fast path: already cached → inject exports again for this import site.
         if let Some(exports) = self.module_cache.get(&module_path).cloned() {
-            self.inject_module_exports(exports, alias);
+            self.inject_module_exports(exports, alias, only, path, span)?;
             return Ok(Ok(Value::Nil));
         }
 
@@ -9860,6 +12218,7 @@ impl Interpreter {
         let program = crate::parser::parse(&source).map_err(|e| HaversError::ParseError {
             message: format!("Error in module '{}': {}", path, e),
             line: span.line,
+            span: None,
         })?;
 
         let _in_progress_guard = ModuleInProgressGuard::new(self, module_path.clone());
@@ -9869,7 +12228,7 @@ impl Interpreter {
             .map(|parent| CurrentDirGuard::new(self, parent.to_path_buf()));
         let exports = self.execute_module_program(&program)?;
         self.module_cache.insert(module_path, exports.clone());
-        self.inject_module_exports(exports, alias);
+        self.inject_module_exports(exports, alias, only, path, span)?;
         Ok(Ok(Value::Nil))
     }
 
@@ -9884,7 +12243,7 @@ impl Interpreter {
         {
             let _env_guard = EnvSwapGuard::new(self, module_env.clone());
             for stmt in &program.statements {
-                self.execute_stmt(stmt)?;
+                self.without_tail_calls(|interp| interp.execute_stmt(stmt))?;
             }
         }
 
@@ -9892,7 +12251,26 @@ impl Interpreter {
         Ok(exports)
     }
 
-    fn inject_module_exports(&mut self, exports: HashMap<String, Value>, alias: Option<&str>) {
+    fn inject_module_exports(
+        &mut self,
+        mut exports: HashMap<String, Value>,
+        alias: Option<&str>,
+        only: Option<&[String]>,
+        path: &str,
+        span: Span,
+    ) -> HaversResult<()> {
+        // `bring ... frae` takes just the names it asks for
+        if let Some(names) = only {
+            let mut picked = HashMap::new();
+            for name in names {
+                let value = exports.remove(name).ok_or_else(|| HaversError::TypeError {
+                    message: format!("There's nae '{}' in '{}' tae bring", name, path),
+                    line: span.line,
+                })?;
+                picked.insert(name.clone(), value);
+            }
+            exports = picked;
+        }
         // If there's an alias, create a namespace object.
         // Otherwise, export all defined names tae the current environment.
         if let Some(alias_name) = alias {
@@ -9909,13 +12287,21 @@ impl Interpreter {
                 self.environment.borrow_mut().define(name, value);
             }
         }
+        Ok(())
     }
 
     fn load_tri_module(
         &mut self,
         alias: Option<&str>,
+        only: Option<&[String]>,
         span: Span,
     ) -> Result<Result<Value, ControlFlow>, HaversError> {
+        if only.is_some() {
+            return Err(HaversError::TypeError {
+                message: "tri cannae be picked apart - bring \"tri\" as a namespace".to_string(),
+                line: span.line,
+            });
+        }
         let alias_name = alias.ok_or_else(|| HaversError::TypeError {
             message: "tri import requires an alias (fetch \"tri\" tae name)".to_string(),
             line: span.line,
@@ -9995,6 +12381,8 @@ impl Interpreter {
         match self.execute_stmt_with_control(stmt)? {
             Ok(value) => Ok(value),
             Err(ControlFlow::Return(value)) => Ok(value),
+            // Only made inside a function body, which always catches it first
+            Err(ControlFlow::TailCall(_)) => Ok(Value::Nil),
             Err(ControlFlow::Break) => Err(HaversError::BreakOutsideLoop {
                 line: stmt.span().line,
             }),
@@ -10008,6 +12396,11 @@ impl Interpreter {
         &mut self,
         stmt: &Stmt,
     ) -> HaversResult<Result<Value, ControlFlow>> {
+        if self.take_step() {
+            return Err(HaversError::ExecutionTimeout {
+                line: stmt.span().line,
+            });
+        }
         match stmt {
             Stmt::VarDecl {
                 name,
@@ -10070,6 +12463,36 @@ impl Interpreter {
                 }
             }
 
+            Stmt::IfLet {
+                name,
+                value,
+                then_branch,
+                else_branch,
+                span,
+            } => {
+                self.trace(&format!("[line {}] gin ken {} = ...", span.line, name));
+                let value = self.evaluate(value)?;
+                if !matches!(value, Value::Nil) {
+                    self.trace_verbose(&format!("→ {} is {} - takin' then branch", name, value));
+                    // The binding gets its own scope, wrapped around the then branch
+                    let env = Rc::new(RefCell::new(Environment::with_enclosing(
+                        self.environment.clone(),
+                    )));
+                    env.borrow_mut().define(name.clone(), value);
+                    let _env_guard = EnvSwapGuard::new(self, env);
+                    self.execute_stmt_with_control(then_branch)
+                } else if let Some(else_br) = else_branch {
+                    self.trace(&format!(
+                        "[line {}] value is naething - takin' ither branch",
+                        span.line
+                    ));
+                    self.execute_stmt_with_control(else_br)
+                } else {
+                    self.trace_verbose("→ value is naething, nae ither branch");
+                    Ok(Ok(Value::Nil))
+                }
+            }
+
             Stmt::While {
                 condition,
                 body,
@@ -10079,13 +12502,17 @@ impl Interpreter {
                     "[line {}] whiles (while) loop startin'",
                     span.line
                 ));
+                let quiet_guard = TraceQuietGuard::new(self);
+                let outer_quiet = self.trace_quiet;
                 let mut iteration = 0;
                 while self.evaluate(condition)?.is_truthy() {
                     iteration += 1;
+                    self.trace_loop_iteration(span.line, iteration, outer_quiet);
                     self.trace_verbose(&format!("→ loop iteration {}", iteration));
                     match self.execute_stmt_with_control(body)? {
                         Ok(_) => {}
                         Err(ControlFlow::Break) => {
+                            self.trace_quiet = outer_quiet;
                             self.trace(&format!(
                                 "[line {}] brak! (break) - leavin' loop",
                                 span.line
@@ -10097,8 +12524,12 @@ impl Interpreter {
                             continue;
                         }
                         Err(ControlFlow::Return(v)) => return Ok(Err(ControlFlow::Return(v))),
+                        Err(ControlFlow::TailCall(args)) => {
+                            return Ok(Err(ControlFlow::TailCall(args)))
+                        }
                     }
                 }
+                drop(quiet_guard);
                 self.trace(&format!(
                     "[line {}] whiles loop done after {} iterations",
                     span.line, iteration
@@ -10108,65 +12539,18 @@ impl Interpreter {
 
             Stmt::For {
                 variable,
+                value_variable,
                 iterable,
                 body,
                 span,
-            } => {
-                self.trace(&format!(
-                    "[line {}] fer (for) loop: {} in ...",
-                    span.line, variable
-                ));
-                let iter_value = self.evaluate(iterable)?;
-
-                let items: Vec<Value> = match iter_value {
-                    Value::Range(range) => range.iter().map(Value::Integer).collect(),
-                    Value::List(list) => list.borrow().clone(),
-                    Value::String(s) => s.chars().map(|c| Value::String(c.to_string())).collect(),
-                    _ => {
-                        return Err(HaversError::TypeError {
-                            message: format!("Cannae iterate ower a {}", iter_value.type_name()),
-                            line: span.line,
-                        });
-                    }
-                };
-
-                self.trace_verbose(&format!("→ iteratin' ower {} items", items.len()));
-                let mut iteration = 0;
-                for item in items {
-                    iteration += 1;
-                    self.trace_verbose(&format!(
-                        "→ iteration {}: {} = {}",
-                        iteration, variable, item
-                    ));
-                    self.environment.borrow_mut().define(variable.clone(), item);
-                    match self.execute_stmt_with_control(body)? {
-                        Ok(_) => {}
-                        Err(ControlFlow::Break) => {
-                            self.trace(&format!(
-                                "[line {}] brak! (break) - leavin' fer loop",
-                                span.line
-                            ));
-                            break;
-                        }
-                        Err(ControlFlow::Continue) => {
-                            self.trace_verbose("→ haud! (continue)");
-                            continue;
-                        }
-                        Err(ControlFlow::Return(v)) => return Ok(Err(ControlFlow::Return(v))),
-                    }
-                }
-                self.trace(&format!(
-                    "[line {}] fer loop done after {} iterations",
-                    span.line, iteration
-                ));
-                Ok(Ok(Value::Nil))
-            }
+            } => self.execute_for(variable, value_variable.as_deref(), iterable, body, *span),
 
             Stmt::Function {
                 name,
                 params,
                 body,
                 span,
+                ..
             } => {
                 self.trace(&format!(
                     "[line {}] dae (function) {} wi' {} params",
@@ -10196,6 +12580,13 @@ impl Interpreter {
             }
 
             Stmt::Return { value, span } => {
+                if let Some(args) = self.try_tail_call(value.as_ref())? {
+                    self.trace(&format!(
+                        "[line {}] gie (tail call) - reusin' the frame",
+                        span.line
+                    ));
+                    return Ok(Err(ControlFlow::TailCall(args)));
+                }
                 let ret_val = if let Some(expr) = value {
                     let v = self.evaluate(expr)?;
                     self.trace(&format!("[line {}] gie (return) {}", span.line, v));
@@ -10210,9 +12601,7 @@ impl Interpreter {
             Stmt::Print { value, span } => {
                 let val = self.evaluate(value)?;
                 self.trace(&format!("[line {}] blether (print): {}", span.line, val));
-                let output = format!("{}", val);
-                println!("{}", output);
-                self.output.push(output);
+                self.write_output(format!("{}", val));
                 Ok(Ok(Value::Nil))
             }
 
@@ -10231,18 +12620,16 @@ impl Interpreter {
                 superclass,
                 methods,
                 span,
+                ..
             } => {
                 self.trace(&format!(
                     "[line {}] kin (class) {} defined",
                     span.line, name
                 ));
                 let super_class = if let Some(super_name) = superclass {
-                    let super_val = self.environment.borrow().get(super_name).ok_or_else(|| {
-                        HaversError::UndefinedVariable {
-                            name: super_name.clone(),
-                            line: span.line,
-                        }
-                    })?;
+                    let super_val = self.environment.borrow().get(super_name);
+                    let super_val =
+                        super_val.ok_or_else(|| self.undefined_variable(super_name, span.line))?;
                     match super_val {
                         Value::Class(c) => Some(c),
                         _ => {
@@ -10305,7 +12692,13 @@ impl Interpreter {
                 Ok(Ok(Value::Nil))
             }
 
-            Stmt::Import { path, alias, span } => {
+            Stmt::Import {
+                path,
+                alias,
+                only,
+                span,
+                ..
+            } => {
                 let alias_str = alias
                     .as_ref()
                     .map(|a| format!(" as {}", a))
@@ -10314,7 +12707,7 @@ impl Interpreter {
                     "[line {}] fetch (import) \"{}\"{}",
                     span.line, path, alias_str
                 ));
-                self.load_module(path, alias.as_deref(), *span)
+                self.load_module(path, alias.as_deref(), only.as_deref(), *span)
             }
 
             Stmt::TryCatch {
@@ -10324,7 +12717,10 @@ impl Interpreter {
                 span,
             } => {
                 self.trace(&format!("[line {}] hae_a_bash (try) startin'", span.line));
-                match self.execute_stmt_with_control(try_block) {
+                // A `gie` in here must come back through the catch, so it's not a tail call
+                let outcome =
+                    self.without_tail_calls(|interp| interp.execute_stmt_with_control(try_block));
+                match outcome {
                     Ok(result) => {
                         self.trace(&format!(
                             "[line {}] try block succeeded - nae bother!",
@@ -10332,7 +12728,16 @@ impl Interpreter {
                         ));
                         Ok(result)
                     }
+                    // boak is a deliberate abort, not something to recover from,
+                    // and the embedder's step and memory limits cannot be dodged
+                    Err(
+                        e @ (HaversError::Boak { .. }
+                        | HaversError::ExecutionTimeout { .. }
+                        | HaversError::OutOfMemory { .. }),
+                    ) => Err(e),
                     Err(e) => {
+                        // It's handled, so its backtrace is no use to anybody
+                        self.error_trace = None;
                         self.trace(&format!(
                             "[line {}] gin_it_gangs_wrang (catch) - caught: {}",
                             span.line, e
@@ -10400,6 +12805,7 @@ impl Interpreter {
             Stmt::Destructure {
                 patterns,
                 value,
+                exact,
                 span,
             } => {
                 self.trace(&format!(
@@ -10441,6 +12847,18 @@ impl Interpreter {
                     0
                 };
 
+                // `ken a, b = ...` wants exactly as many values as it names
+                if *exact && rest_pos.is_none() && items.len() != patterns.len() {
+                    return Err(HaversError::TypeError {
+                        message: format!(
+                            "Cannae unpack {} values intae {} names",
+                            items.len(),
+                            patterns.len()
+                        ),
+                        line: span.line,
+                    });
+                }
+
                 // Check we have enough elements
                 let min_required = before_rest + after_rest;
                 if items.len() < min_required {
@@ -10508,6 +12926,13 @@ impl Interpreter {
                 Ok(Ok(Value::Nil))
             }
 
+            Stmt::Yield { span, .. } => Err(HaversError::TypeError {
+                message: "gie_back only works in a generator function's body, an' no' \
+                          inside hae_a_bash or keek"
+                    .to_string(),
+                line: span.line,
+            }),
+
             Stmt::Hurl { message, span } => {
                 let msg = self.evaluate(message)?;
                 let error_msg = match msg {
@@ -10559,41 +12984,50 @@ impl Interpreter {
             }
             Pattern::Identifier(_) => Ok(true), // Always matches, binds value
             Pattern::Wildcard => Ok(true),
+            // A float lands in the range by value, the same as it would with `<`
             Pattern::Range { start, end } => {
-                if let Value::Integer(n) = value {
-                    let start_val = self.evaluate(start)?;
-                    let end_val = self.evaluate(end)?;
-                    if let (Some(s), Some(e)) = (start_val.as_integer(), end_val.as_integer()) {
-                        Ok(*n >= s && *n < e)
-                    } else {
-                        Ok(false)
-                    }
-                } else {
-                    Ok(false)
+                if !matches!(value, Value::Integer(_) | Value::Float(_)) {
+                    return Ok(false);
                 }
+                let start_val = self.evaluate(start)?;
+                let end_val = self.evaluate(end)?;
+                let (Some(s), Some(e)) = (start_val.as_integer(), end_val.as_integer()) else {
+                    return Ok(false);
+                };
+                Ok(match value {
+                    Value::Integer(n) => *n >= s && *n < e,
+                    Value::Float(f) => *f >= s as f64 && *f < e as f64,
+                    _ => false,
+                })
             }
         }
     }
 
-    fn range_to_list(start: i64, end: i64, inclusive: bool) -> Value {
+    /// Build a range list. The direction comes from the bounds (10..1 counts
+    /// down), so only the size of `step` matters. Callers reject a zero step.
+    fn range_to_list(start: i64, end: i64, step: i64, inclusive: bool) -> Value {
+        let stride = step.unsigned_abs().max(1);
         let mut items = Vec::new();
-        if inclusive {
-            let mut i = start;
-            while i <= end {
-                items.push(Value::Integer(i));
-                i += 1;
+        let mut i = start as i128;
+        let end = end as i128;
+        if start as i128 <= end {
+            while i < end || (inclusive && i == end) {
+                items.push(Value::Integer(i as i64));
+                i += stride as i128;
             }
         } else {
-            let mut i = start;
-            while i < end {
-                items.push(Value::Integer(i));
-                i += 1;
+            while i > end || (inclusive && i == end) {
+                items.push(Value::Integer(i as i64));
+                i -= stride as i128;
             }
         }
         Value::List(Rc::new(RefCell::new(items)))
     }
 
     fn evaluate(&mut self, expr: &Expr) -> HaversResult<Value> {
+        // Only counted here; the limit's checked per statement, which keeps
+        // this frame (the deepest in any recursion) as small as it can be
+        self.steps += 1;
         match expr {
             Expr::Literal { value, .. } => Ok(match value {
                 Literal::Integer(n) => Value::Integer(*n),
@@ -10603,22 +13037,15 @@ impl Interpreter {
                 Literal::Nil => Value::Nil,
             }),
 
-            Expr::Variable { name, span } => self
-                .environment
-                .borrow()
-                .get(name)
-                .ok_or_else(|| HaversError::UndefinedVariable {
-                    name: name.clone(),
-                    line: span.line,
-                }),
+            Expr::Variable { name, span } => {
+                let value = self.environment.borrow().get(name);
+                value.ok_or_else(|| self.undefined_variable(name, span.line))
+            }
 
             Expr::Assign { name, value, span } => {
                 let val = self.evaluate(value)?;
                 if !self.environment.borrow_mut().assign(name, val.clone()) {
-                    return Err(HaversError::UndefinedVariable {
-                        name: name.clone(),
-                        line: span.line,
-                    });
+                    return Err(self.undefined_variable(name, span.line));
                 }
                 Ok(val)
             }
@@ -10656,17 +13083,7 @@ impl Interpreter {
             } => {
                 let val = self.evaluate(operand)?;
                 match operator {
-                    UnaryOp::Negate => match val {
-                        Value::Integer(n) => n
-                            .checked_neg()
-                            .map(Value::Integer)
-                            .ok_or(HaversError::IntegerOverflow { line: span.line }),
-                        Value::Float(f) => Ok(Value::Float(-f)),
-                        _ => Err(HaversError::TypeError {
-                            message: format!("Cannae negate a {}", val.type_name()),
-                            line: span.line,
-                        }),
-                    },
+                    UnaryOp::Negate => self.negate(val, span.line),
                     UnaryOp::Not => Ok(Value::Bool(!val.is_truthy())),
                 }
             }
@@ -11116,6 +13533,16 @@ impl Interpreter {
                 Ok(Value::List(Rc::new(RefCell::new(items))))
             }
 
+            Expr::ListComp {
+                element,
+                clauses,
+                span,
+            } => {
+                let mut items = Vec::new();
+                self.run_comprehension(element, clauses, span.line, &mut items)?;
+                Ok(Value::List(Rc::new(RefCell::new(items))))
+            }
+
             Expr::Dict { pairs, .. } => {
                 let mut map = DictValue::new();
                 for (key, value) in pairs {
@@ -11130,12 +13557,33 @@ impl Interpreter {
                 start,
                 end,
                 inclusive,
+                step,
                 ..
             } => {
                 let start_val = self.evaluate(start)?;
                 let end_val = self.evaluate(end)?;
+                let step_val = match step {
+                    Some(st) => match self.evaluate(st)?.as_integer() {
+                        Some(0) => {
+                            return Err(HaversError::InvalidOperation {
+                                operation: "A range step o' zero".to_string(),
+                                line: expr.span().line,
+                            })
+                        }
+                        Some(n) => n,
+                        None => {
+                            return Err(HaversError::TypeError {
+                                message: "Range step must be an integer".to_string(),
+                                line: expr.span().line,
+                            })
+                        }
+                    },
+                    None => 1,
+                };
                 match (start_val.as_integer(), end_val.as_integer()) {
-                    (Some(s), Some(e)) => Ok(Self::range_to_list(s, e, *inclusive)),
+                    (Some(s), Some(e)) => {
+                        Ok(Self::range_to_list(s, e, step_val, *inclusive))
+                    }
                     _ => Err(HaversError::TypeError {
                         message: "Range bounds must be integers".to_string(),
                         line: expr.span().line,
@@ -11161,7 +13609,7 @@ impl Interpreter {
 
                 // Create a function from the lambda
                 let func = HaversFunction::new(
-                    "<lambda>".to_string(),
+                    format!("<lambda@{}:{}>", span.line, span.column),
                     runtime_params,
                     vec![Stmt::Return {
                         value: Some((**body).clone()),
@@ -11183,27 +13631,8 @@ impl Interpreter {
             }
 
             Expr::Input { prompt, span: _ } => {
-                #[cfg(coverage)]
-                {
-                    let _ = self.evaluate(prompt)?;
-                    Err(HaversError::InternalError(
-                        "speir() input is disabled under coverage runs".to_string(),
-                    ))
-                }
-
-                #[cfg(not(coverage))]
-                {
-                    let prompt_val = self.evaluate(prompt)?;
-                    print!("{}", prompt_val);
-                    io::stdout().flush().unwrap();
-
-                    let mut input = String::new();
-                    io::stdin()
-                        .read_line(&mut input)
-                        .map_err(|e| HaversError::InternalError(e.to_string()))?;
-
-                    Ok(Value::String(input.trim().to_string()))
-                }
+                let prompt = self.evaluate(prompt)?;
+                self.speir(&prompt)
             }
 
             Expr::FString { parts, .. } => {
@@ -11252,12 +13681,16 @@ impl Interpreter {
                 // Execute statements and return the value from 'gie' if any
                 // Use execute_stmt_with_control to handle return properly
                 for stmt in statements {
-                    match self.execute_stmt_with_control(stmt)? {
+                    let flow =
+                        self.without_tail_calls(|interp| interp.execute_stmt_with_control(stmt))?;
+                    match flow {
                         Ok(_) => {}
                         Err(ControlFlow::Return(value)) => {
                             return Ok(value);
                         }
-                        Err(ControlFlow::Break) | Err(ControlFlow::Continue) => {
+                        Err(ControlFlow::Break)
+                        | Err(ControlFlow::Continue)
+                        | Err(ControlFlow::TailCall(_)) => {
                             // Propagate break/continue - shouldn't happen in block expr
                         }
                     }
@@ -11267,6 +13700,8 @@ impl Interpreter {
         }
     }
 
+    /// A binary operation, held to the memory guard since `+` and `*` can
+    /// build big strings and lists
     fn binary_op(
         &self,
         left: &Value,
@@ -11274,12 +13709,29 @@ impl Interpreter {
         right: &Value,
         line: usize,
     ) -> HaversResult<Value> {
+        let value = self.apply_binary_op(left, op, right, line)?;
+        self.guard_memory(&value, line)?;
+        Ok(value)
+    }
+
+    fn apply_binary_op(
+        &self,
+        left: &Value,
+        op: &BinaryOp,
+        right: &Value,
+        line: usize,
+    ) -> HaversResult<Value> {
+        if matches!(left, Value::BigInt(_)) || matches!(right, Value::BigInt(_)) {
+            if let Some(result) = self.big_binary_op(left, op, right, line) {
+                return result;
+            }
+        }
         match op {
             BinaryOp::Add => match (left, right) {
                 (Value::Integer(a), Value::Integer(b)) => a
                     .checked_add(*b)
                     .map(Value::Integer)
-                    .ok_or(HaversError::IntegerOverflow { line }),
+                    .map_or_else(|| self.overflowed(left, op, right, line), Ok),
                 (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a + b)),
                 (Value::Integer(a), Value::Float(b)) => Ok(Value::Float(*a as f64 + b)),
                 (Value::Float(a), Value::Integer(b)) => Ok(Value::Float(a + *b as f64)),
@@ -11301,7 +13753,7 @@ impl Interpreter {
                 (Value::Integer(a), Value::Integer(b)) => a
                     .checked_sub(*b)
                     .map(Value::Integer)
-                    .ok_or(HaversError::IntegerOverflow { line }),
+                    .map_or_else(|| self.overflowed(left, op, right, line), Ok),
                 (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a - b)),
                 (Value::Integer(a), Value::Float(b)) => Ok(Value::Float(*a as f64 - b)),
                 (Value::Float(a), Value::Integer(b)) => Ok(Value::Float(a - *b as f64)),
@@ -11319,7 +13771,7 @@ impl Interpreter {
                 (Value::Integer(a), Value::Integer(b)) => a
                     .checked_mul(*b)
                     .map(Value::Integer)
-                    .ok_or(HaversError::IntegerOverflow { line }),
+                    .map_or_else(|| self.overflowed(left, op, right, line), Ok),
                 (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a * b)),
                 (Value::Integer(a), Value::Float(b)) => Ok(Value::Float(*a as f64 * b)),
                 (Value::Float(a), Value::Integer(b)) => Ok(Value::Float(a * *b as f64)),
@@ -11368,7 +13820,7 @@ impl Interpreter {
                     (Value::Integer(a), Value::Integer(b)) => a
                         .checked_div(*b)
                         .map(Value::Integer)
-                        .ok_or(HaversError::IntegerOverflow { line }),
+                        .map_or_else(|| self.overflowed(left, op, right, line), Ok),
                     (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a / b)),
                     (Value::Integer(a), Value::Float(b)) => Ok(Value::Float(*a as f64 / b)),
                     (Value::Float(a), Value::Integer(b)) => Ok(Value::Float(a / *b as f64)),
@@ -11391,8 +13843,10 @@ impl Interpreter {
                     (Value::Integer(a), Value::Integer(b)) => a
                         .checked_rem(*b)
                         .map(Value::Integer)
-                        .ok_or(HaversError::IntegerOverflow { line }),
+                        .map_or_else(|| self.overflowed(left, op, right, line), Ok),
                     (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a % b)),
+                    (Value::Integer(a), Value::Float(b)) => Ok(Value::Float(*a as f64 % b)),
+                    (Value::Float(a), Value::Integer(b)) => Ok(Value::Float(a % *b as f64)),
                     _ => Err(HaversError::TypeError {
                         message: format!(
                             "Cannae get remainder o' {} by {}",
@@ -11404,63 +13858,183 @@ impl Interpreter {
                 }
             }
 
-            BinaryOp::Equal => Ok(Value::Bool(left == right)),
-            BinaryOp::NotEqual => Ok(Value::Bool(left != right)),
-
-            BinaryOp::Less => {
-                self.compare(left, right, |a, b| a < b, |a, b| a < b, |a, b| a < b, line)
-            }
-            BinaryOp::LessEqual => self.compare(
-                left,
-                right,
-                |a, b| a <= b,
-                |a, b| a <= b,
-                |a, b| a <= b,
-                line,
-            ),
-            BinaryOp::Greater => {
-                self.compare(left, right, |a, b| a > b, |a, b| a > b, |a, b| a > b, line)
-            }
-            BinaryOp::GreaterEqual => self.compare(
-                left,
-                right,
-                |a, b| a >= b,
-                |a, b| a >= b,
-                |a, b| a >= b,
-                line,
-            ),
+            // Ints stay ints for a non-negative exponent, same as pow()
+            BinaryOp::Power => match (left, right) {
+                (Value::Integer(a), Value::Integer(b)) if *b >= 0 => u32::try_from(*b)
+                    .ok()
+                    .and_then(|exp| a.checked_pow(exp))
+                    .map(Value::Integer)
+                    .map_or_else(|| self.overflowed(left, op, right, line), Ok),
+                (Value::Integer(a), Value::Integer(b)) => {
+                    Ok(Value::Float((*a as f64).powf(*b as f64)))
+                }
+                (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a.powf(*b))),
+                (Value::Integer(a), Value::Float(b)) => Ok(Value::Float((*a as f64).powf(*b))),
+                (Value::Float(a), Value::Integer(b)) => Ok(Value::Float(a.powf(*b as f64))),
+                _ => Err(HaversError::TypeError {
+                    message: format!(
+                        "Cannae raise {} tae the power o' {}",
+                        left.type_name(),
+                        right.type_name()
+                    ),
+                    line,
+                }),
+            },
+
+            BinaryOp::Equal => Ok(Value::Bool(left == right)),
+            BinaryOp::NotEqual => Ok(Value::Bool(left != right)),
+
+            BinaryOp::Less => self.compare(left, right, std::cmp::Ordering::is_lt, line),
+            BinaryOp::LessEqual => self.compare(left, right, std::cmp::Ordering::is_le, line),
+            BinaryOp::Greater => self.compare(left, right, std::cmp::Ordering::is_gt, line),
+            BinaryOp::GreaterEqual => self.compare(left, right, std::cmp::Ordering::is_ge, line),
+
+            BinaryOp::BitAnd
+            | BinaryOp::BitOr
+            | BinaryOp::BitXor
+            | BinaryOp::ShiftLeft
+            | BinaryOp::ShiftRight => {
+                let (a, b) = match (left, right) {
+                    (Value::Integer(a), Value::Integer(b)) => (*a, *b),
+                    _ => {
+                        return Err(HaversError::TypeError {
+                            message: format!(
+                                "Cannae use {} on {} an' {} - bitwise operators need integers",
+                                op,
+                                left.type_name(),
+                                right.type_name()
+                            ),
+                            line,
+                        })
+                    }
+                };
+                match op {
+                    BinaryOp::BitAnd => Ok(Value::Integer(a & b)),
+                    BinaryOp::BitOr => Ok(Value::Integer(a | b)),
+                    BinaryOp::BitXor => Ok(Value::Integer(a ^ b)),
+                    _ if !(0..=63).contains(&b) => Err(HaversError::InvalidNumberOperation {
+                        message: "Shift amount must be 0-63, ya numpty!".to_string(),
+                        line,
+                    }),
+                    BinaryOp::ShiftLeft => Ok(Value::Integer(a << b)),
+                    _ => Ok(Value::Integer(a >> b)),
+                }
+            }
+
+            BinaryOp::In | BinaryOp::NotIn => {
+                let found = contains_value(right, left)
+                    .map_err(|message| HaversError::TypeError { message, line })?;
+                Ok(Value::Bool(found == (*op == BinaryOp::In)))
+            }
         }
     }
 
-    fn compare<I, F, S>(
+    /// An i64 operation overflowed: with big integers on, do it again with them
+    fn overflowed(
         &self,
         left: &Value,
+        op: &BinaryOp,
         right: &Value,
-        int_cmp: I,
-        float_cmp: F,
-        str_cmp: S,
         line: usize,
-    ) -> HaversResult<Value>
-    where
-        I: Fn(i64, i64) -> bool,
-        F: Fn(f64, f64) -> bool,
-        S: Fn(&str, &str) -> bool,
-    {
-        match (left, right) {
-            (Value::Integer(a), Value::Integer(b)) => Ok(Value::Bool(int_cmp(*a, *b))),
-            (Value::Float(a), Value::Float(b)) => Ok(Value::Bool(float_cmp(*a, *b))),
-            (Value::Integer(a), Value::Float(b)) => Ok(Value::Bool(float_cmp(*a as f64, *b))),
-            (Value::Float(a), Value::Integer(b)) => Ok(Value::Bool(float_cmp(*a, *b as f64))),
-            (Value::String(a), Value::String(b)) => Ok(Value::Bool(str_cmp(a, b))),
+    ) -> HaversResult<Value> {
+        if !self.big_integers {
+            return Err(HaversError::IntegerOverflow { line });
+        }
+        self.big_binary_op(left, op, right, line)
+            .unwrap_or(Err(HaversError::IntegerOverflow { line }))
+    }
+
+    /// Arithmetic and comparison on integers of any size. Mixed with a float,
+    /// the integer becomes a float too. `None` means it's not a job for
+    /// big integers, so the usual rules (and type errors) apply.
+    fn big_binary_op(
+        &self,
+        left: &Value,
+        op: &BinaryOp,
+        right: &Value,
+        line: usize,
+    ) -> Option<HaversResult<Value>> {
+        let as_big = |value: &Value| match value {
+            Value::Integer(n) => Some(BigInt::from(*n)),
+            Value::BigInt(n) => Some((**n).clone()),
+            _ => None,
+        };
+        let (Some(a), Some(b)) = (as_big(left), as_big(right)) else {
+            let as_float = |value: &Value| match value {
+                Value::Float(f) => Some(*f),
+                Value::Integer(n) => Some(*n as f64),
+                Value::BigInt(n) => n.to_f64(),
+                _ => None,
+            };
+            let (a, b) = (as_float(left)?, as_float(right)?);
+            return Some(self.apply_binary_op(&Value::Float(a), op, &Value::Float(b), line));
+        };
+        let result = match op {
+            BinaryOp::Add => a + b,
+            BinaryOp::Subtract => a - b,
+            BinaryOp::Multiply => a * b,
+            BinaryOp::Divide | BinaryOp::Modulo if b.is_zero() => {
+                return Some(Err(HaversError::DivisionByZero { line }))
+            }
+            BinaryOp::Divide => a / b,
+            BinaryOp::Modulo => a % b,
+            BinaryOp::Power => match u32::try_from(&b) {
+                Ok(exp) => a.pow(exp),
+                Err(_) if b.is_negative() => {
+                    let (a, b) = (a.to_f64()?, b.to_f64()?);
+                    return Some(Ok(Value::Float(a.powf(b))));
+                }
+                Err(_) => return Some(Err(HaversError::IntegerOverflow { line })),
+            },
+            BinaryOp::Equal => return Some(Ok(Value::Bool(a == b))),
+            BinaryOp::NotEqual => return Some(Ok(Value::Bool(a != b))),
+            BinaryOp::Less => return Some(Ok(Value::Bool(a < b))),
+            BinaryOp::LessEqual => return Some(Ok(Value::Bool(a <= b))),
+            BinaryOp::Greater => return Some(Ok(Value::Bool(a > b))),
+            BinaryOp::GreaterEqual => return Some(Ok(Value::Bool(a >= b))),
+            _ => return None,
+        };
+        Some(Ok(Value::from_big(result)))
+    }
+
+    /// Unary minus; i64::MIN spills over into a big integer if they're on
+    fn negate(&self, val: Value, line: usize) -> HaversResult<Value> {
+        match val {
+            Value::Integer(n) => match n.checked_neg() {
+                Some(n) => Ok(Value::Integer(n)),
+                None if self.big_integers => Ok(Value::from_big(-BigInt::from(n))),
+                None => Err(HaversError::IntegerOverflow { line }),
+            },
+            Value::BigInt(n) => Ok(Value::from_big(-(*n).clone())),
+            Value::Float(f) => Ok(Value::Float(-f)),
             _ => Err(HaversError::TypeError {
+                message: format!("Cannae negate a {}", val.type_name()),
+                line,
+            }),
+        }
+    }
+
+    /// Order `left` against `right` by `Value`'s ordering and ask `test` about
+    /// the result. Values that cannot be ordered are a type error; a NaN
+    /// compares false against anything.
+    fn compare(
+        &self,
+        left: &Value,
+        right: &Value,
+        test: fn(std::cmp::Ordering) -> bool,
+        line: usize,
+    ) -> HaversResult<Value> {
+        if !left.is_comparable_with(right) {
+            return Err(HaversError::TypeError {
                 message: format!(
                     "Cannae compare {} wi' {}",
                     left.type_name(),
                     right.type_name()
                 ),
                 line,
-            }),
+            });
         }
+        Ok(Value::Bool(left.partial_cmp(right).is_some_and(test)))
     }
 
     /// Get the method name for operator overloading
@@ -11470,12 +14044,18 @@ impl Interpreter {
     /// - __times__ = multiply
     /// - __pairt__ = divide (part/divide)
     /// - __lave__ = modulo (what's left)
+    /// - __pooer__ = power
     /// - __same_as__ = equal
     /// - __differs_fae__ = not equal
     /// - __wee_er__ = less than (smaller)
     /// - __wee_er_or_same__ = less or equal
     /// - __muckle_er__ = greater than (bigger)
     /// - __muckle_er_or_same__ = greater or equal
+    /// - __baith__ = bitwise and (both)
+    /// - __either__ = bitwise or
+    /// - __yin_or_tither__ = bitwise xor (one or the other)
+    /// - __shove_left__ = left shift
+    /// - __shove_right__ = right shift
     fn operator_method_name(&self, op: &BinaryOp) -> String {
         match op {
             BinaryOp::Add => "__pit_thegither__".to_string(),
@@ -11483,12 +14063,21 @@ impl Interpreter {
             BinaryOp::Multiply => "__times__".to_string(),
             BinaryOp::Divide => "__pairt__".to_string(),
             BinaryOp::Modulo => "__lave__".to_string(),
+            BinaryOp::Power => "__pooer__".to_string(),
             BinaryOp::Equal => "__same_as__".to_string(),
             BinaryOp::NotEqual => "__differs_fae__".to_string(),
             BinaryOp::Less => "__wee_er__".to_string(),
             BinaryOp::LessEqual => "__wee_er_or_same__".to_string(),
             BinaryOp::Greater => "__muckle_er__".to_string(),
             BinaryOp::GreaterEqual => "__muckle_er_or_same__".to_string(),
+            BinaryOp::BitAnd => "__baith__".to_string(),
+            BinaryOp::BitOr => "__either__".to_string(),
+            BinaryOp::BitXor => "__yin_or_tither__".to_string(),
+            BinaryOp::ShiftLeft => "__shove_left__".to_string(),
+            BinaryOp::ShiftRight => "__shove_right__".to_string(),
+            // `in` asks the container on the right, not the item on the
+            // left, so there's no method the left operand could have
+            BinaryOp::In | BinaryOp::NotIn => String::new(),
         }
     }
 
@@ -11528,14 +14117,20 @@ impl Interpreter {
             method_env.borrow_mut().define(param.name.clone(), arg);
         }
 
+        if method.is_generator {
+            return Ok(self.make_generator(&method, method_env));
+        }
+
         // Execute the method body with our custom environment
-        let result = self.execute_block(&method.body, Some(method_env));
+        let result =
+            self.without_tail_calls(|interp| interp.execute_block(&method.body, Some(method_env)));
 
         match result {
             Ok(Ok(val)) => Ok(val),
             Ok(Err(ControlFlow::Return(val))) => Ok(val),
             Ok(Err(ControlFlow::Break)) => Ok(Value::Nil),
             Ok(Err(ControlFlow::Continue)) => Ok(Value::Nil),
+            Ok(Err(ControlFlow::TailCall(_))) => Ok(Value::Nil),
             Err(e) => Err(e),
         }
     }
@@ -11564,6 +14159,25 @@ impl Interpreter {
         Ok(args)
     }
 
+    /// Run a native function. Natives can grow their arguments in place
+    /// (shove) or hand back something big, so the memory guard checks both.
+    fn call_native(
+        &mut self,
+        native: &NativeFunction,
+        args: Vec<Value>,
+        line: usize,
+    ) -> HaversResult<Value> {
+        for arg in &args {
+            self.guard_memory(arg, line)?;
+        }
+        let result = {
+            let _native = NativeCallGuard::new();
+            (native.func)(args).map_err(HaversError::InternalError)?
+        };
+        self.guard_memory(&result, line)?;
+        Ok(result)
+    }
+
     fn call_value(&mut self, callee: Value, args: Vec<Value>, line: usize) -> HaversResult<Value> {
         let _guard = InterpreterGuard::new(self);
         match callee {
@@ -11577,7 +14191,7 @@ impl Interpreter {
                         line,
                     });
                 }
-                (native.func)(args).map_err(HaversError::InternalError)
+                self.call_native(&native, args, line)
             }
             Value::NativeObject(_) => Err(HaversError::TypeError {
                 message: "Cannae ca' a native object like a function".to_string(),
@@ -11748,11 +14362,12 @@ impl Interpreter {
                 Ok(Value::Nil)
             }
 
-            // hunt(list, func) - find first matching element
-            "__builtin_hunt__" => {
+            // hunt(list, func) / find(list, func) - find first matching element
+            "__builtin_hunt__" | "__builtin_find__" => {
+                let fname = name.trim_start_matches("__builtin_").trim_end_matches("__");
                 if args.len() != 2 {
                     return Err(HaversError::WrongArity {
-                        name: "hunt".to_string(),
+                        name: fname.to_string(),
                         expected: 2,
                         got: args.len(),
                         line,
@@ -11762,7 +14377,7 @@ impl Interpreter {
                     Value::List(l) => l.borrow().clone(),
                     _ => {
                         return Err(HaversError::TypeError {
-                            message: "hunt() expects a list as first argument".to_string(),
+                            message: format!("{}() expects a list as first argument", fname),
                             line,
                         })
                     }
@@ -11777,11 +14392,12 @@ impl Interpreter {
                 Ok(Value::Nil)
             }
 
-            // ony(list, func) - check if any element matches
-            "__builtin_ony__" => {
+            // ony(list, func) / any(list, func) - check if any element matches
+            "__builtin_ony__" | "__builtin_any__" => {
+                let fname = name.trim_start_matches("__builtin_").trim_end_matches("__");
                 if args.len() != 2 {
                     return Err(HaversError::WrongArity {
-                        name: "ony".to_string(),
+                        name: fname.to_string(),
                         expected: 2,
                         got: args.len(),
                         line,
@@ -11791,7 +14407,7 @@ impl Interpreter {
                     Value::List(l) => l.borrow().clone(),
                     _ => {
                         return Err(HaversError::TypeError {
-                            message: "ony() expects a list as first argument".to_string(),
+                            message: format!("{}() expects a list as first argument", fname),
                             line,
                         })
                     }
@@ -11806,11 +14422,12 @@ impl Interpreter {
                 Ok(Value::Bool(false))
             }
 
-            // aw(list, func) - check if all elements match
-            "__builtin_aw__" => {
+            // aw(list, func) / all(list, func) - check if all elements match
+            "__builtin_aw__" | "__builtin_all__" => {
+                let fname = name.trim_start_matches("__builtin_").trim_end_matches("__");
                 if args.len() != 2 {
                     return Err(HaversError::WrongArity {
-                        name: "aw".to_string(),
+                        name: fname.to_string(),
                         expected: 2,
                         got: args.len(),
                         line,
@@ -11820,7 +14437,7 @@ impl Interpreter {
                     Value::List(l) => l.borrow().clone(),
                     _ => {
                         return Err(HaversError::TypeError {
-                            message: "aw() expects a list as first argument".to_string(),
+                            message: format!("{}() expects a list as first argument", fname),
                             line,
                         })
                     }
@@ -11835,6 +14452,35 @@ impl Interpreter {
                 Ok(Value::Bool(true))
             }
 
+            // count(list, func) - how many elements match
+            "__builtin_count__" => {
+                if args.len() != 2 {
+                    return Err(HaversError::WrongArity {
+                        name: "count".to_string(),
+                        expected: 2,
+                        got: args.len(),
+                        line,
+                    });
+                }
+                let list = match &args[0] {
+                    Value::List(l) => l.borrow().clone(),
+                    _ => {
+                        return Err(HaversError::TypeError {
+                            message: "count() expects a list as first argument".to_string(),
+                            line,
+                        })
+                    }
+                };
+                let func = args[1].clone();
+                let mut total = 0;
+                for item in list {
+                    if self.call_value(func.clone(), vec![item], line)?.is_truthy() {
+                        total += 1;
+                    }
+                }
+                Ok(Value::Integer(total))
+            }
+
             // grup_up(list, func) - group elements by function result
             "__builtin_grup_up__" => {
                 if args.len() != 2 {
@@ -11908,6 +14554,88 @@ impl Interpreter {
                 ]))))
             }
 
+            // boak(msg) - give up with a HaversError rather than a Rust panic
+            "__builtin_boak__" => {
+                if args.len() != 1 {
+                    return Err(HaversError::WrongArity {
+                        name: "boak".to_string(),
+                        expected: 1,
+                        got: args.len(),
+                        line,
+                    });
+                }
+                let message = match &args[0] {
+                    Value::String(s) => s.clone(),
+                    v => format!("{}", v),
+                };
+                Err(HaversError::Boak { message, line })
+            }
+
+            "__builtin_assert__" => check_assert(&args, line),
+            "__builtin_assert_eq__" => check_assert_eq(&args, line),
+
+            // expeck_error(thunk, text?) - true if calling the thunk fails
+            // (with `text` somewhere in the message, when it's given)
+            "__builtin_expeck_error__" => {
+                if args.is_empty() || args.len() > 2 {
+                    return Err(HaversError::WrongArity {
+                        name: "expeck_error".to_string(),
+                        expected: 1,
+                        got: args.len(),
+                        line,
+                    });
+                }
+                let wanted = match args.get(1) {
+                    None => None,
+                    Some(Value::String(s)) => Some(s.clone()),
+                    Some(other) => {
+                        return Err(HaversError::TypeError {
+                            message: format!(
+                                "expeck_error() needs the message text as a string, no' a {}",
+                                other.type_name()
+                            ),
+                            line,
+                        })
+                    }
+                };
+                match self.call_value(args[0].clone(), Vec::new(), line) {
+                    // Same as hae_a_bash: these are never caught
+                    Err(e)
+                        if matches!(
+                            e.root(),
+                            HaversError::Boak { .. }
+                                | HaversError::ExecutionTimeout { .. }
+                                | HaversError::OutOfMemory { .. }
+                        ) =>
+                    {
+                        Err(e)
+                    }
+                    Err(e) => {
+                        self.error_trace = None;
+                        let message = e.to_string();
+                        match wanted {
+                            Some(text) if !message.contains(&text) => {
+                                Err(HaversError::AssertionFailed {
+                                    message: format!(
+                                        "expeck_error() wanted an error mentionin' '{}' but got: {}",
+                                        text, message
+                                    ),
+                                    line,
+                                })
+                            }
+                            _ => Ok(Value::Bool(true)),
+                        }
+                    }
+                    Ok(value) => Err(HaversError::AssertionFailed {
+                        message: format!(
+                            "expeck_error() wanted an error but it gied back {}",
+                            value
+                        ),
+                        line,
+                    }),
+                }
+            }
+
             _ => Err(HaversError::NotCallable {
                 name: name.to_string(),
                 line,
@@ -11962,7 +14690,69 @@ impl Interpreter {
         line: usize,
     ) -> HaversResult<Value> {
         let _stack_guard = StackFrameGuard::new(&func.name, line);
+        self.call_stack.push(StackFrame {
+            name: func.name.clone(),
+            file: self.current_file.clone(),
+            line,
+        });
+        let result = self.run_function_body(func, args, env);
+        // Note the stack at the first function the error escapes; a call that
+        // comes back fine means any earlier error got swallowed on the way
+        if result.is_err() {
+            if self.error_trace.is_none() {
+                self.error_trace = Some(self.call_stack.clone());
+            }
+        } else {
+            self.error_trace = None;
+        }
+        self.call_stack.pop();
+        result
+    }
+
+    fn run_function_body(
+        &mut self,
+        func: &HaversFunction,
+        mut args: Vec<Value>,
+        mut env: Rc<RefCell<Environment>>,
+    ) -> HaversResult<Value> {
+        if func.is_generator {
+            self.bind_params(func, &args, &env)?;
+            return Ok(self.make_generator(func, env));
+        }
+
+        loop {
+            self.bind_params(func, &args, &env)?;
+
+            let outer = self.tail_call_target.replace(func as *const HaversFunction);
+            let result = self.execute_block(&func.body, Some(env.clone()));
+            self.tail_call_target = outer;
+
+            match result? {
+                Ok(v) => return Ok(v),
+                Err(ControlFlow::Return(v)) => return Ok(v),
+                Err(ControlFlow::Break) | Err(ControlFlow::Continue) => return Ok(Value::Nil),
+                Err(ControlFlow::TailCall(next)) => {
+                    // Fresh frame for the next go, so closures made this time
+                    // around keep their own parameters
+                    let fresh = Rc::new(RefCell::new(Environment::with_enclosing(
+                        func.closure.clone().unwrap_or(self.globals.clone()),
+                    )));
+                    if let Some(masel) = env.borrow().get("masel") {
+                        fresh.borrow_mut().define("masel".to_string(), masel);
+                    }
+                    env = fresh;
+                    args = next;
+                }
+            }
+        }
+    }
 
+    fn bind_params(
+        &mut self,
+        func: &HaversFunction,
+        args: &[Value],
+        env: &Rc<RefCell<Environment>>,
+    ) -> HaversResult<()> {
         // Set up closure environment fer evaluating default values
         {
             let _env_guard = EnvSwapGuard::new(self, env.clone());
@@ -11981,13 +14771,505 @@ impl Interpreter {
                 env.borrow_mut().define(param.name.clone(), value);
             }
         }
+        Ok(())
+    }
+
+    /// If `gie`'s value is a direct call back into the function we're in,
+    /// evaluate its arguments so the caller can loop instead of recursing
+    fn try_tail_call(&mut self, value: Option<&Expr>) -> HaversResult<Option<Vec<Value>>> {
+        let Some(target) = self.tail_call_target else {
+            return Ok(None);
+        };
+        let Some(Expr::Call {
+            callee,
+            arguments,
+            span,
+        }) = value
+        else {
+            return Ok(None);
+        };
+        let Expr::Variable { name, .. } = callee.as_ref() else {
+            return Ok(None);
+        };
+        let func = match self.environment.borrow().get(name) {
+            Some(Value::Function(func)) if std::ptr::eq(Rc::as_ptr(&func), target) => func,
+            _ => return Ok(None),
+        };
+        // Arguments run in this frame, before it gets replaced
+        let args =
+            self.without_tail_calls(|interp| interp.evaluate_call_args(arguments, span.line))?;
+        if args.len() < func.min_arity() || args.len() > func.max_arity() {
+            // Let the normal call path report the arity error
+            return Ok(None);
+        }
+        Ok(Some(args))
+    }
+
+    /// Wrap up a call to a `gie_back` function. None of the body runs until
+    /// the generator is first resumed.
+    fn make_generator(&self, func: &HaversFunction, env: Rc<RefCell<Environment>>) -> Value {
+        Value::Generator(Rc::new(RefCell::new(HaversGenerator {
+            name: func.name.clone(),
+            frames: vec![GeneratorFrame::Block {
+                statements: Rc::new(func.body.clone()),
+                next: 0,
+                env,
+            }],
+            running: false,
+        })))
+    }
+
+    /// Run a `fer` loop. Kept out of `execute_stmt_with_control` so its
+    /// locals don't swell every frame of a deep recursion.
+    fn execute_for(
+        &mut self,
+        variable: &str,
+        value_variable: Option<&str>,
+        iterable: &Expr,
+        body: &Stmt,
+        span: Span,
+    ) -> HaversResult<Result<Value, ControlFlow>> {
+        self.trace(&format!(
+            "[line {}] fer (for) loop: {} in ...",
+            span.line, variable
+        ));
+        let iter_value = self.evaluate(iterable)?;
+
+        let mut source = self.for_source(iter_value, value_variable.is_some(), span.line)?;
+        match &source {
+            ForSource::Items(items) => {
+                self.trace_verbose(&format!("→ iteratin' ower {} items", items.len()))
+            }
+            ForSource::Generator(_) => self.trace_verbose("→ iteratin' ower a generator"),
+        }
+        let quiet_guard = TraceQuietGuard::new(self);
+        let outer_quiet = self.trace_quiet;
+        let mut iteration = 0;
+        while let Some(item) = self.next_for_item(&mut source, span.line)? {
+            iteration += 1;
+            self.trace_loop_iteration(span.line, iteration, outer_quiet);
+            self.trace_verbose(&format!(
+                "→ iteration {}: {} = {}",
+                iteration, variable, item
+            ));
+            // Each iteration gets its own binding, so closures made in the
+            // body keep the value from their own pass. The outer one still
+            // holds the last value once the loop is done.
+            let iteration_env = Rc::new(RefCell::new(Environment::with_enclosing(
+                self.environment.clone(),
+            )));
+            for (name, value) in Self::for_bindings(variable, value_variable, item, span.line)? {
+                iteration_env
+                    .borrow_mut()
+                    .define(name.clone(), value.clone());
+                self.environment.borrow_mut().define(name, value);
+            }
+            let flow = {
+                let _env_guard = EnvSwapGuard::new(self, iteration_env);
+                self.execute_stmt_with_control(body)
+            };
+            match flow? {
+                Ok(_) => {}
+                Err(ControlFlow::Break) => {
+                    self.trace_quiet = outer_quiet;
+                    self.trace(&format!(
+                        "[line {}] brak! (break) - leavin' fer loop",
+                        span.line
+                    ));
+                    break;
+                }
+                Err(ControlFlow::Continue) => {
+                    self.trace_verbose("→ haud! (continue)");
+                    continue;
+                }
+                Err(ControlFlow::Return(v)) => return Ok(Err(ControlFlow::Return(v))),
+                Err(ControlFlow::TailCall(args)) => return Ok(Err(ControlFlow::TailCall(args))),
+            }
+        }
+        drop(quiet_guard);
+        self.trace(&format!(
+            "[line {}] fer loop done after {} iterations",
+            span.line, iteration
+        ));
+        Ok(Ok(Value::Nil))
+    }
+
+    /// What a `fer` loop steps through. With `pairs` (the `fer k, v` form) a
+    /// dict yields its `[key, value]` pairs in order.
+    fn for_source(&self, value: Value, pairs: bool, line: usize) -> HaversResult<ForSource> {
+        let items: Vec<Value> = match value {
+            Value::Dict(dict) if pairs => dict
+                .borrow()
+                .iter()
+                .map(|(k, v)| Value::List(Rc::new(RefCell::new(vec![k.clone(), v.clone()]))))
+                .collect(),
+            Value::Range(range) => range.iter().map(Value::Integer).collect(),
+            Value::List(list) => list.borrow().clone(),
+            Value::String(s) => s.chars().map(|c| Value::String(c.to_string())).collect(),
+            Value::Generator(gen) => return Ok(ForSource::Generator(gen)),
+            _ => {
+                return Err(HaversError::TypeError {
+                    message: format!("Cannae iterate ower a {}", value.type_name()),
+                    line,
+                });
+            }
+        };
+        Ok(ForSource::Items(items.into_iter()))
+    }
+
+    /// The names a `fer` loop binds for one item. The `fer k, v` form unpacks
+    /// each item as a pair.
+    fn for_bindings(
+        variable: &str,
+        value_variable: Option<&str>,
+        item: Value,
+        line: usize,
+    ) -> HaversResult<Vec<(String, Value)>> {
+        let Some(value_variable) = value_variable else {
+            return Ok(vec![(variable.to_string(), item)]);
+        };
+        let pair = match &item {
+            Value::List(list) => list.borrow().clone(),
+            _ => {
+                return Err(HaversError::TypeError {
+                    message: format!("Cannae unpack a {} intae 2 names", item.type_name()),
+                    line,
+                });
+            }
+        };
+        match <[Value; 2]>::try_from(pair) {
+            Ok([key, value]) => Ok(vec![
+                (variable.to_string(), key),
+                (value_variable.to_string(), value),
+            ]),
+            Err(pair) => Err(HaversError::TypeError {
+                message: format!("Cannae unpack {} values intae 2 names", pair.len()),
+                line,
+            }),
+        }
+    }
 
-        match self.execute_block(&func.body, Some(env))? {
-            Ok(v) => Ok(v),
-            Err(ControlFlow::Return(v)) => Ok(v),
-            Err(ControlFlow::Break) | Err(ControlFlow::Continue) => Ok(Value::Nil),
+    fn next_for_item(
+        &mut self,
+        source: &mut ForSource,
+        line: usize,
+    ) -> HaversResult<Option<Value>> {
+        match source {
+            ForSource::Items(items) => Ok(items.next()),
+            ForSource::Generator(gen) => {
+                let gen = gen.clone();
+                self.resume_generator(&gen, line)
+            }
         }
     }
+
+    /// Work through a comprehension's clauses left to right, like nested
+    /// loops and ifs, pushing `element` for every pass that gets past the guards.
+    /// Each variable is bound in its own scope, so it never leaks out.
+    fn run_comprehension(
+        &mut self,
+        element: &Expr,
+        clauses: &[CompClause],
+        line: usize,
+        items: &mut Vec<Value>,
+    ) -> HaversResult<()> {
+        match clauses.split_first() {
+            None => {
+                items.push(self.evaluate(element)?);
+                if self.memory_guard.is_some_and(|limit| items.len() > limit) {
+                    return Err(HaversError::OutOfMemory { line });
+                }
+            }
+            Some((CompClause::If(condition), rest)) => {
+                if self.evaluate(condition)?.is_truthy() {
+                    self.run_comprehension(element, rest, line, items)?;
+                }
+            }
+            Some((CompClause::For { variable, iterable }, rest)) => {
+                let iter_value = self.evaluate(iterable)?;
+                let mut source = self.for_source(iter_value, false, line)?;
+                while let Some(item) = self.next_for_item(&mut source, line)? {
+                    let item_env = Rc::new(RefCell::new(Environment::with_enclosing(
+                        self.environment.clone(),
+                    )));
+                    item_env.borrow_mut().define(variable.clone(), item);
+                    let _env_guard = EnvSwapGuard::new(self, item_env);
+                    self.run_comprehension(element, rest, line, items)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Run a generator on to its next `gie_back`. None means it's finished -
+    /// it ran off the end of its body, hit a `gie`, or raised an error last time.
+    fn resume_generator(
+        &mut self,
+        gen: &Rc<RefCell<HaversGenerator>>,
+        line: usize,
+    ) -> HaversResult<Option<Value>> {
+        let mut frames = {
+            let mut state = gen.borrow_mut();
+            if state.running {
+                return Err(HaversError::TypeError {
+                    message: format!(
+                        "Generator '{}' is already runnin' - it cannae resume itsel",
+                        state.name
+                    ),
+                    line,
+                });
+            }
+            state.running = true;
+            std::mem::take(&mut state.frames)
+        };
+
+        let result = self.without_tail_calls(|interp| interp.run_generator(&mut frames));
+
+        let mut state = gen.borrow_mut();
+        state.running = false;
+        match result {
+            Ok(Some(value)) => {
+                state.frames = frames;
+                Ok(Some(value))
+            }
+            // Finished or failed, it stays finished
+            other => other,
+        }
+    }
+
+    fn run_generator(&mut self, frames: &mut Vec<GeneratorFrame>) -> HaversResult<Option<Value>> {
+        loop {
+            let Some(frame) = frames.last_mut() else {
+                return Ok(None);
+            };
+            let step = match frame {
+                GeneratorFrame::Block {
+                    statements,
+                    next,
+                    env,
+                } => {
+                    if *next >= statements.len() {
+                        frames.pop();
+                        continue;
+                    }
+                    let statements = statements.clone();
+                    let env = env.clone();
+                    let stmt = &statements[*next];
+                    *next += 1;
+                    self.generator_stmt(stmt, env, frames)?
+                }
+                GeneratorFrame::While {
+                    condition,
+                    body,
+                    env,
+                } => {
+                    let (condition, body, env) = (condition.clone(), body.clone(), env.clone());
+                    let keep_going = {
+                        let _env_guard = EnvSwapGuard::new(self, env.clone());
+                        self.evaluate(&condition)?.is_truthy()
+                    };
+                    if keep_going {
+                        frames.push(GeneratorFrame::Block {
+                            statements: body,
+                            next: 0,
+                            env: Rc::new(RefCell::new(Environment::with_enclosing(env))),
+                        });
+                    } else {
+                        frames.pop();
+                    }
+                    GeneratorStep::Next
+                }
+                GeneratorFrame::For {
+                    variable,
+                    value_variable,
+                    source,
+                    body,
+                    env,
+                } => {
+                    let line = body.first().map_or(0, |stmt| stmt.span().line);
+                    match self.next_for_item(source, line)? {
+                        Some(item) => {
+                            let iteration_env =
+                                Rc::new(RefCell::new(Environment::with_enclosing(env.clone())));
+                            for (name, value) in
+                                Self::for_bindings(variable, value_variable.as_deref(), item, line)?
+                            {
+                                iteration_env
+                                    .borrow_mut()
+                                    .define(name.clone(), value.clone());
+                                env.borrow_mut().define(name, value);
+                            }
+                            let body = body.clone();
+                            frames.push(GeneratorFrame::Block {
+                                statements: body,
+                                next: 0,
+                                env: iteration_env,
+                            });
+                        }
+                        None => {
+                            frames.pop();
+                        }
+                    }
+                    GeneratorStep::Next
+                }
+            };
+
+            match step {
+                GeneratorStep::Next => {}
+                GeneratorStep::Yield(value) => return Ok(Some(value)),
+                GeneratorStep::Finish => {
+                    frames.clear();
+                    return Ok(None);
+                }
+                GeneratorStep::Break => {
+                    // Drop everything up to and including the innermost loop
+                    while let Some(frame) = frames.pop() {
+                        if !matches!(frame, GeneratorFrame::Block { .. }) {
+                            break;
+                        }
+                    }
+                }
+                GeneratorStep::Continue => {
+                    while matches!(frames.last(), Some(GeneratorFrame::Block { .. })) {
+                        frames.pop();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Run one statement of a generator body. Statements with no `gie_back`
+    /// in them run as normal; the rest push frames so they can be paused.
+    fn generator_stmt(
+        &mut self,
+        stmt: &Stmt,
+        env: Rc<RefCell<Environment>>,
+        frames: &mut Vec<GeneratorFrame>,
+    ) -> HaversResult<GeneratorStep> {
+        // hae_a_bash and keek run straight through, so a gie_back in them
+        // lands on the "not in a generator" error
+        let pausable = matches!(
+            stmt,
+            Stmt::Yield { .. }
+                | Stmt::Block { .. }
+                | Stmt::If { .. }
+                | Stmt::IfLet { .. }
+                | Stmt::While { .. }
+                | Stmt::For { .. }
+        );
+        if !pausable || !stmt.contains_yield() {
+            let flow = {
+                let _env_guard = EnvSwapGuard::new(self, env);
+                self.execute_stmt_with_control(stmt)?
+            };
+            return Ok(match flow {
+                Ok(_) => GeneratorStep::Next,
+                Err(ControlFlow::Return(_)) | Err(ControlFlow::TailCall(_)) => {
+                    GeneratorStep::Finish
+                }
+                Err(ControlFlow::Break) => GeneratorStep::Break,
+                Err(ControlFlow::Continue) => GeneratorStep::Continue,
+            });
+        }
+
+        match stmt {
+            Stmt::Yield { value, span } => {
+                let value = {
+                    let _env_guard = EnvSwapGuard::new(self, env);
+                    self.evaluate(value)?
+                };
+                self.trace(&format!("[line {}] gie_back (yield) {}", span.line, value));
+                Ok(GeneratorStep::Yield(value))
+            }
+            Stmt::Block { statements, .. } => {
+                frames.push(GeneratorFrame::Block {
+                    statements: Rc::new(statements.clone()),
+                    next: 0,
+                    env: Rc::new(RefCell::new(Environment::with_enclosing(env))),
+                });
+                Ok(GeneratorStep::Next)
+            }
+            Stmt::If {
+                condition,
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                let truthy = {
+                    let _env_guard = EnvSwapGuard::new(self, env.clone());
+                    self.evaluate(condition)?.is_truthy()
+                };
+                if truthy {
+                    self.generator_stmt(then_branch, env, frames)
+                } else if let Some(else_branch) = else_branch {
+                    self.generator_stmt(else_branch, env, frames)
+                } else {
+                    Ok(GeneratorStep::Next)
+                }
+            }
+            Stmt::IfLet {
+                name,
+                value,
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                let value = {
+                    let _env_guard = EnvSwapGuard::new(self, env.clone());
+                    self.evaluate(value)?
+                };
+                if !matches!(value, Value::Nil) {
+                    let scope = Rc::new(RefCell::new(Environment::with_enclosing(env)));
+                    scope.borrow_mut().define(name.clone(), value);
+                    self.generator_stmt(then_branch, scope, frames)
+                } else if let Some(else_branch) = else_branch {
+                    self.generator_stmt(else_branch, env, frames)
+                } else {
+                    Ok(GeneratorStep::Next)
+                }
+            }
+            Stmt::While {
+                condition, body, ..
+            } => {
+                frames.push(GeneratorFrame::While {
+                    condition: Rc::new(condition.clone()),
+                    body: Rc::new(loop_body(body)),
+                    env,
+                });
+                Ok(GeneratorStep::Next)
+            }
+            Stmt::For {
+                variable,
+                value_variable,
+                iterable,
+                body,
+                span,
+            } => {
+                let iter_value = {
+                    let _env_guard = EnvSwapGuard::new(self, env.clone());
+                    self.evaluate(iterable)?
+                };
+                let source = self.for_source(iter_value, value_variable.is_some(), span.line)?;
+                frames.push(GeneratorFrame::For {
+                    variable: variable.clone(),
+                    value_variable: value_variable.clone(),
+                    source,
+                    body: Rc::new(loop_body(body)),
+                    env,
+                });
+                Ok(GeneratorStep::Next)
+            }
+            _ => unreachable!("only pausable statements get this far"),
+        }
+    }
+
+    /// Run `f` with tail calls switched off - for code that's not in tail position
+    fn without_tail_calls<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+        let outer = self.tail_call_target.take();
+        let result = f(self);
+        self.tail_call_target = outer;
+        result
+    }
 }
 
 impl Default for Interpreter {
@@ -12252,11 +15534,12 @@ fn value_to_json(value: &Value) -> String {
         Value::Bool(true) => "true".to_string(),
         Value::Bool(false) => "false".to_string(),
         Value::Integer(n) => n.to_string(),
+        Value::BigInt(n) => n.to_string(),
         Value::Float(f) => {
             if f.is_nan() || f.is_infinite() {
                 "null".to_string()
             } else {
-                f.to_string()
+                format_float(*f)
             }
         }
         Value::String(s) => json_escape_string(s),
@@ -12282,60 +15565,106 @@ fn value_to_json(value: &Value) -> String {
     }
 }
 
-/// Convert a mdhavers Value to a pretty-printed JSON string
-fn value_to_json_pretty(value: &Value, indent: usize) -> String {
-    let ws = "  ".repeat(indent);
-    let ws_inner = "  ".repeat(indent + 1);
+/// How `json_pretty` lays out its output
+struct JsonStyle {
+    /// What each nesting level is indented by; `None` keeps it on one line
+    indent: Option<String>,
+    /// Write dict keys in sorted order so the output diffs cleanly
+    sort_keys: bool,
+}
 
-    match value {
-        Value::Nil => "null".to_string(),
-        Value::Bool(true) => "true".to_string(),
-        Value::Bool(false) => "false".to_string(),
-        Value::Integer(n) => n.to_string(),
-        Value::Float(f) => {
-            if f.is_nan() || f.is_infinite() {
-                "null".to_string()
-            } else {
-                f.to_string()
-            }
+/// Widest indent json_pretty will take as a number of spaces
+const MAX_JSON_INDENT: i64 = 16;
+
+/// Read json_pretty's optional indent and sort_keys arguments
+fn json_style(args: &[Value]) -> Result<JsonStyle, String> {
+    let indent = match args.get(1) {
+        None => Some("  ".to_string()),
+        Some(Value::Nil) => None,
+        Some(Value::Integer(n)) if (0..=MAX_JSON_INDENT).contains(n) => {
+            Some(" ".repeat(*n as usize))
         }
-        Value::String(s) => json_escape_string(s),
-        Value::List(l) => {
-            let items = l.borrow();
-            if items.is_empty() {
-                "[]".to_string()
-            } else {
-                let formatted: Vec<String> = items
-                    .iter()
-                    .map(|v| format!("{}{}", ws_inner, value_to_json_pretty(v, indent + 1)))
-                    .collect();
-                format!("[\n{}\n{}]", formatted.join(",\n"), ws)
+        Some(Value::String(unit)) => {
+            if !unit.chars().all(|c| c == ' ' || c == '\t') {
+                return Err("json_pretty() indent string can only hae spaces an' tabs".to_string());
             }
+            Some(unit.clone())
+        }
+        Some(_) => {
+            return Err(format!(
+                "json_pretty() indent must be naething, 0 tae {} spaces, or a string o' spaces an' tabs",
+                MAX_JSON_INDENT
+            ))
+        }
+    };
+    let sort_keys = match args.get(2) {
+        None | Some(Value::Nil) => false,
+        Some(Value::Bool(b)) => *b,
+        Some(_) => return Err("json_pretty() sort_keys must be aye or nae".to_string()),
+    };
+    Ok(JsonStyle { indent, sort_keys })
+}
+
+/// json_pretty(value, indent?, sort_keys?)
+fn json_pretty_value(args: Vec<Value>) -> Result<Value, String> {
+    if args.is_empty() || args.len() > 3 {
+        return Err("json_pretty() expects 1 tae 3 arguments".to_string());
+    }
+    let style = json_style(&args)?;
+    Ok(Value::String(value_to_json_pretty(&args[0], &style, 0)))
+}
+
+/// Convert a mdhavers Value to a JSON string laid out the way `style` says
+fn value_to_json_pretty(value: &Value, style: &JsonStyle, depth: usize) -> String {
+    let layout = |parts: Vec<String>, open: char, close: char| match &style.indent {
+        Some(unit) if !parts.is_empty() => {
+            let inner = unit.repeat(depth + 1);
+            let lines: Vec<String> = parts.iter().map(|p| format!("{}{}", inner, p)).collect();
+            format!(
+                "{}\n{}\n{}{}",
+                open,
+                lines.join(",\n"),
+                unit.repeat(depth),
+                close
+            )
         }
+        _ => format!("{}{}{}", open, parts.join(", "), close),
+    };
+
+    match value {
+        Value::List(l) => layout(
+            l.borrow()
+                .iter()
+                .map(|v| value_to_json_pretty(v, style, depth + 1))
+                .collect(),
+            '[',
+            ']',
+        ),
         Value::Dict(d) => {
-            let dict = d.borrow();
-            if dict.is_empty() {
-                "{}".to_string()
-            } else {
-                let formatted: Vec<String> = dict
-                    .iter()
-                    .map(|(k, v)| {
-                        let key_json = match k {
-                            Value::String(s) => json_escape_string(s),
-                            _ => json_escape_string(&format!("{}", k)),
-                        };
-                        format!(
-                            "{}{}: {}",
-                            ws_inner,
-                            key_json,
-                            value_to_json_pretty(v, indent + 1)
-                        )
-                    })
-                    .collect();
-                format!("{{\n{}\n{}}}", formatted.join(",\n"), ws)
+            let mut pairs: Vec<(String, String)> = d
+                .borrow()
+                .iter()
+                .map(|(k, v)| {
+                    let key = match k {
+                        Value::String(s) => s.clone(),
+                        _ => format!("{}", k),
+                    };
+                    (key, value_to_json_pretty(v, style, depth + 1))
+                })
+                .collect();
+            if style.sort_keys {
+                pairs.sort_by(|a, b| a.0.cmp(&b.0));
             }
+            layout(
+                pairs
+                    .into_iter()
+                    .map(|(k, v)| format!("{}: {}", json_escape_string(&k), v))
+                    .collect(),
+                '{',
+                '}',
+            )
         }
-        _ => format!("\"{}\"", format!("{}", value).replace('\"', "\\\"")),
+        _ => value_to_json(value),
     }
 }
 
@@ -12352,11 +15681,272 @@ fn json_escape_string(s: &str) -> String {
             c if c.is_control() => {
                 result.push_str(&format!("\\u{:04x}", c as u32));
             }
-            c => result.push(c),
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+/// One step of a json_query path
+#[derive(Debug, Clone, PartialEq)]
+enum JsonPathStep {
+    Key(String),
+    Index(i64),
+    All,
+}
+
+/// Split a json_query path like `user.addresses[0].city`, `items[*].name` or
+/// `meta["dotted.key"]` into steps - keep in step with the runtime's `mdh_json_path`
+fn parse_json_path(path: &str) -> Result<Vec<JsonPathStep>, String> {
+    let chars: Vec<char> = path.chars().collect();
+    let unclosed = || format!("json_query() path '{}' has a '[' that's never closed", path);
+    let empty_key = || format!("json_query() path '{}' has an empty key", path);
+    let mut steps = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] == '[' {
+            i += 1;
+            if let Some(&quote) = chars.get(i).filter(|c| matches!(c, '"' | '\'')) {
+                let end = chars[i + 1..]
+                    .iter()
+                    .position(|&c| c == quote)
+                    .ok_or_else(unclosed)?;
+                let key: String = chars[i + 1..i + 1 + end].iter().collect();
+                steps.push(JsonPathStep::Key(key));
+                i += end + 2;
+            } else {
+                let end = chars[i..]
+                    .iter()
+                    .position(|&c| c == ']')
+                    .ok_or_else(unclosed)?;
+                let inner: String = chars[i..i + end].iter().collect();
+                let inner = inner.trim();
+                steps.push(if inner == "*" {
+                    JsonPathStep::All
+                } else {
+                    JsonPathStep::Index(inner.parse().map_err(|_| {
+                        format!(
+                            "json_query() path '{}' has '[{}]' - use a number, * or a quoted key",
+                            path, inner
+                        )
+                    })?)
+                });
+                i += end;
+            }
+            if chars.get(i) != Some(&']') {
+                return Err(unclosed());
+            }
+            i += 1;
+        } else {
+            let start = i;
+            while i < chars.len() && chars[i] != '.' && chars[i] != '[' {
+                i += 1;
+            }
+            if start == i {
+                return Err(empty_key());
+            }
+            let key: String = chars[start..i].iter().collect();
+            steps.push(if key == "*" {
+                JsonPathStep::All
+            } else {
+                JsonPathStep::Key(key)
+            });
+        }
+        if chars.get(i) == Some(&'.') {
+            i += 1;
+            if i == chars.len() {
+                return Err(empty_key());
+            }
+        }
+    }
+    Ok(steps)
+}
+
+/// One step down: a dict key, or a list index (negatives count from the end)
+fn json_path_step(value: &Value, step: &JsonPathStep) -> Option<Value> {
+    let index = match (value, step) {
+        (Value::Dict(dict), JsonPathStep::Key(key)) => {
+            return dict.borrow().get(&Value::String(key.clone())).cloned();
+        }
+        (Value::List(_), JsonPathStep::Index(i)) => *i,
+        (Value::List(_), JsonPathStep::Key(key)) => key.parse().ok()?,
+        _ => return None,
+    };
+    let Value::List(list) = value else {
+        return None;
+    };
+    let list = list.borrow();
+    let index = if index < 0 {
+        list.len() as i64 + index
+    } else {
+        index
+    };
+    usize::try_from(index)
+        .ok()
+        .and_then(|i| list.get(i))
+        .cloned()
+}
+
+/// Follow the steps through a parsed JSON tree. A path without wildcards returns
+/// the value or nil; with a wildcard it returns a flat list of every match
+/// that isn't nil.
+fn json_query_value(value: &Value, steps: &[JsonPathStep]) -> Value {
+    fn collect(value: &Value, steps: &[JsonPathStep], found: &mut Vec<Value>) {
+        let Some((step, rest)) = steps.split_first() else {
+            if !matches!(value, Value::Nil) {
+                found.push(value.clone());
+            }
+            return;
+        };
+        if *step != JsonPathStep::All {
+            if let Some(next) = json_path_step(value, step) {
+                collect(&next, rest, found);
+            }
+            return;
+        }
+        let children: Vec<Value> = match value {
+            Value::List(list) => list.borrow().clone(),
+            Value::Dict(dict) => dict.borrow().values().cloned().collect(),
+            _ => return,
+        };
+        for child in &children {
+            collect(child, rest, found);
+        }
+    }
+
+    if steps.contains(&JsonPathStep::All) {
+        let mut found = Vec::new();
+        collect(value, steps, &mut found);
+        return Value::List(Rc::new(RefCell::new(found)));
+    }
+    let mut current = value.clone();
+    for step in steps {
+        match json_path_step(&current, step) {
+            Some(next) => current = next,
+            None => return Value::Nil,
+        }
+    }
+    current
+}
+
+/// The delimiter for csv_parse/csv_write: a comma unless another single
+/// character is given (a tab for TSV, say)
+fn csv_delimiter(name: &str, arg: Option<&Value>) -> Result<char, String> {
+    let Some(arg) = arg else {
+        return Ok(',');
+    };
+    let mut chars = match arg {
+        Value::String(s) => s.chars(),
+        _ => return Err(format!("{}() delimiter must be a string", name)),
+    };
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if !matches!(c, '"' | '\n' | '\r') => Ok(c),
+        _ => Err(format!(
+            "{}() delimiter must be a single character (no' a quote or newline)",
+            name
+        )),
+    }
+}
+
+/// Split CSV text into rows of fields. Quoted fields can hold the
+/// delimiter, newlines, and `""` for a quote; a quote anywhere else is an error.
+fn csv_parse_rows(text: &str, delim: char) -> Result<Vec<Vec<String>>, String> {
+    let mut rows = Vec::new();
+    if text.is_empty() {
+        return Ok(rows);
+    }
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut line = 1;
+    let mut chars = text.chars().peekable();
+    loop {
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            let start = line;
+            loop {
+                match chars.next() {
+                    Some('"') if chars.peek() == Some(&'"') => {
+                        chars.next();
+                        field.push('"');
+                    }
+                    Some('"') => break,
+                    Some(c) => {
+                        if c == '\n' {
+                            line += 1;
+                        }
+                        field.push(c);
+                    }
+                    None => {
+                        return Err(format!(
+                            "csv_parse() found a quoted field on line {} that's never closed",
+                            start
+                        ))
+                    }
+                }
+            }
+            if matches!(chars.peek(), Some(&c) if c != delim && c != '\n' && c != '\r') {
+                return Err(format!(
+                    "csv_parse() found stray text efter a closin' quote on line {}",
+                    line
+                ));
+            }
+        } else {
+            while let Some(&c) = chars.peek() {
+                if c == delim || c == '\n' || c == '\r' {
+                    break;
+                }
+                if c == '"' {
+                    return Err(format!(
+                        "csv_parse() found a quote in the middle o' an unquoted field on line {}",
+                        line
+                    ));
+                }
+                field.push(c);
+                chars.next();
+            }
+        }
+        row.push(std::mem::take(&mut field));
+
+        match chars.next() {
+            Some(c) if c == delim => continue,
+            Some('\r') if chars.peek() == Some(&'\n') => {
+                chars.next();
+            }
+            Some(_) => {}
+            None => {
+                rows.push(row);
+                return Ok(rows);
+            }
+        }
+        line += 1;
+        rows.push(std::mem::take(&mut row));
+        if chars.peek().is_none() {
+            return Ok(rows);
         }
     }
-    result.push('"');
-    result
+}
+
+/// Write rows as CSV, quoting only the fields that need it, with a newline
+/// after every row
+fn csv_write_rows(rows: &[Vec<String>], delim: char) -> String {
+    let mut out = String::new();
+    for row in rows {
+        for (i, field) in row.iter().enumerate() {
+            if i > 0 {
+                out.push(delim);
+            }
+            if field.contains([delim, '"', '\n', '\r']) {
+                out.push('"');
+                out.push_str(&field.replace('"', "\"\""));
+                out.push('"');
+            } else {
+                out.push_str(field);
+            }
+        }
+        out.push('\n');
+    }
+    out
 }
 
 	#[cfg(test)]
@@ -12530,6 +16120,7 @@ blether m2["a"]
                 initializer: None,
                 span: Span::new(1, 1),
             }],
+            attributes: Vec::new(),
             span: Span::new(1, 1),
 	        };
 
@@ -12670,6 +16261,7 @@ blether m2["a"]
             remote_host: Some("127.0.0.1".to_string()),
             remote_port: Some(9999),
             srtp_profiles: Vec::new(),
+            timeout_ms: DEFAULT_NET_TIMEOUT_MS,
         });
         assert_result_err((dtls_handshake.func)(vec![Value::Integer(dtls_id), Value::Integer(bad_udp)]).unwrap());
 
@@ -12713,6 +16305,8 @@ blether m2["a"]
 	            client_config: None,
 	            server_config: None,
 	            stream: None,
+	            handshake: None,
+	            timeout_ms: DEFAULT_NET_TIMEOUT_MS,
 	        });
 		        let err = unwrap_result_err_str(
 		            (tls_connect.func)(vec![Value::Integer(tls_client), Value::Integer(sock_id)]).unwrap(),
@@ -12729,6 +16323,8 @@ blether m2["a"]
 	            client_config: None,
 	            server_config: None,
 	            stream: None,
+	            handshake: None,
+	            timeout_ms: DEFAULT_NET_TIMEOUT_MS,
 	        });
 		        let err = unwrap_result_err_str(
 		            (tls_connect.func)(vec![Value::Integer(tls_server), Value::Integer(sock_id)]).unwrap(),
@@ -13250,6 +16846,7 @@ blether r["error"]
             cert_pem: Some(cert_pem.clone()),
             // Deliberately pass no private key; we inject the rsa_private_keys failure.
             key_pem: Some(cert_pem),
+            timeout_ms: DEFAULT_NET_TIMEOUT_MS,
         };
         let err = build_server_config(&cfg).unwrap_err();
         assert!(err.contains("Invalid server key:"));
@@ -13294,6 +16891,7 @@ blether r["error"]
             ca_pem: None,
             cert_pem: Some(cert_pem),
             key_pem: Some(key_pem),
+            timeout_ms: DEFAULT_NET_TIMEOUT_MS,
         };
         let _ = build_server_config(&cfg).unwrap();
     }
@@ -13311,6 +16909,7 @@ blether r["error"]
             ca_pem: None,
             cert_pem: None,
             key_pem: Some("not used".to_string()),
+            timeout_ms: DEFAULT_NET_TIMEOUT_MS,
 	        };
 	        let err = build_server_config(&missing_cert).unwrap_err();
 	        let msg = err.clone();
@@ -13325,6 +16924,7 @@ blether r["error"]
             ca_pem: None,
             cert_pem: Some(cert_pem.clone()),
             key_pem: None,
+            timeout_ms: DEFAULT_NET_TIMEOUT_MS,
 	        };
 	        let err = build_server_config(&missing_key).unwrap_err();
 	        let msg = err.clone();
@@ -13340,6 +16940,7 @@ blether r["error"]
             ca_pem: None,
             cert_pem: Some(cert_pem.clone()),
             key_pem: Some(cert_pem),
+            timeout_ms: DEFAULT_NET_TIMEOUT_MS,
 	        };
 	        let err = build_server_config(&no_private_key).unwrap_err();
 	        let msg = err.clone();
@@ -13590,6 +17191,7 @@ blether r["error"]
             ca_pem: None,
             cert_pem: None,
             key_pem: None,
+            timeout_ms: DEFAULT_NET_TIMEOUT_MS,
         };
         let server_cfg = TlsConfigData {
             mode: TlsMode::Server,
@@ -13598,6 +17200,7 @@ blether r["error"]
             ca_pem: None,
             cert_pem: Some(cert_pem),
             key_pem: Some(key_pem),
+            timeout_ms: DEFAULT_NET_TIMEOUT_MS,
         };
 
         let client_config = build_client_config(&client_cfg).unwrap();
@@ -13636,6 +17239,8 @@ blether r["error"]
             client_config: Some(client_config),
             server_config: None,
             stream: Some(TlsStream::Client(StreamOwned::new(client_conn, client_tcp))),
+            handshake: None,
+            timeout_ms: DEFAULT_NET_TIMEOUT_MS,
         });
         let server_tls_id = register_tls(TlsSession {
             mode: TlsMode::Server,
@@ -13643,6 +17248,8 @@ blether r["error"]
             client_config: None,
             server_config: Some(server_config),
             stream: Some(TlsStream::Server(StreamOwned::new(server_conn, server_tcp))),
+            handshake: None,
+            timeout_ms: DEFAULT_NET_TIMEOUT_MS,
         });
 
         fn dict_value(result: Value) -> Option<Rc<RefCell<DictValue>>> {
@@ -13714,6 +17321,124 @@ blether r["error"]
         (tls_close.func)(vec![Value::Integer(server_tls_id)]).unwrap();
     }
 
+    #[cfg(all(feature = "native", unix))]
+    #[test]
+    fn tls_handshake_can_be_stepped_fae_the_caller_loop() {
+        use std::os::unix::io::IntoRawFd;
+        use std::time::{Duration, Instant};
+
+        let interp = Interpreter::new();
+        let globals = interp.globals.clone();
+        let tls_connect_start = native_from_globals(&globals, "tls_connect_start");
+        let tls_handshake_step = native_from_globals(&globals, "tls_handshake_step");
+        let tls_send = native_from_globals(&globals, "tls_send");
+        let tls_recv = native_from_globals(&globals, "tls_recv");
+        let tls_close = native_from_globals(&globals, "tls_close");
+        let socket_close = native_from_globals(&globals, "socket_close");
+
+        let cert = generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        let tls_cfg = |mode, cert_pem, key_pem| TlsConfigData {
+            mode,
+            server_name: "localhost".to_string(),
+            insecure: true,
+            ca_pem: None,
+            cert_pem,
+            key_pem,
+            timeout_ms: DEFAULT_NET_TIMEOUT_MS,
+        };
+        let client_cfg = tls_cfg(TlsMode::Client, None, None);
+        let server_cfg = tls_cfg(
+            TlsMode::Server,
+            Some(cert.serialize_pem().unwrap()),
+            Some(cert.serialize_private_key_pem()),
+        );
+        let session = |mode, client_config, server_config| TlsSession {
+            mode,
+            server_name: "localhost".to_string(),
+            client_config,
+            server_config,
+            stream: None,
+            handshake: None,
+            timeout_ms: DEFAULT_NET_TIMEOUT_MS,
+        };
+        let client_tls = register_tls(session(
+            TlsMode::Client,
+            Some(build_client_config(&client_cfg).unwrap()),
+            None,
+        ));
+        let server_tls = register_tls(session(
+            TlsMode::Server,
+            None,
+            Some(build_server_config(&server_cfg).unwrap()),
+        ));
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let client_tcp = std::net::TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (server_tcp, _) = listener.accept().unwrap();
+        let client_sock = register_socket(client_tcp.into_raw_fd(), SocketKind::Tcp);
+        let server_sock = register_socket(server_tcp.into_raw_fd(), SocketKind::Tcp);
+
+        fn status(result: Value) -> Result<String, String> {
+            let Value::Dict(dict) = result else {
+                return Err("expected a result dict".to_string());
+            };
+            let dict = dict.borrow();
+            if dict_get_bool(&dict, "ok") == Some(true) {
+                Ok(dict_get_string(&dict, "value").unwrap_or_default())
+            } else {
+                Err(dict_get_string(&dict, "error").unwrap_or_default())
+            }
+        }
+
+        let start = |tls, sock| {
+            status(
+                (tls_connect_start.func)(vec![Value::Integer(tls), Value::Integer(sock)]).unwrap(),
+            )
+        };
+        // Nobody has answered the ClientHello yet
+        let mut client = start(client_tls, client_sock).unwrap();
+        assert_eq!(client, "want_read");
+        let mut server = start(server_tls, server_sock).unwrap();
+        let err = start(client_tls, client_sock).unwrap_err();
+        assert!(err.contains("already under way"), "{err}");
+
+        let step = |tls| status((tls_handshake_step.func)(vec![Value::Integer(tls)]).unwrap());
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while (client != "done" || server != "done") && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(1));
+            if client != "done" {
+                client = step(client_tls).unwrap();
+            }
+            if server != "done" {
+                server = step(server_tls).unwrap();
+            }
+        }
+        assert_eq!((client.as_str(), server.as_str()), ("done", "done"));
+        assert!(step(client_tls).unwrap_err().contains("Nae TLS handshake"));
+
+        // After that it's an ordinary blocking session
+        let sent = (tls_send.func)(vec![
+            Value::Integer(client_tls),
+            Value::Bytes(Rc::new(RefCell::new(b"ping".to_vec()))),
+        ])
+        .unwrap();
+        assert!(status(sent).is_ok());
+        let Value::Dict(recv) =
+            (tls_recv.func)(vec![Value::Integer(server_tls), Value::Integer(4)]).unwrap()
+        else {
+            panic!("expected a result dict");
+        };
+        assert_eq!(
+            dict_get_bytes(&recv.borrow(), "value"),
+            Some(b"ping".to_vec())
+        );
+
+        (tls_close.func)(vec![Value::Integer(client_tls)]).unwrap();
+        (tls_close.func)(vec![Value::Integer(server_tls)]).unwrap();
+        (socket_close.func)(vec![Value::Integer(client_sock)]).unwrap();
+        (socket_close.func)(vec![Value::Integer(server_sock)]).unwrap();
+    }
+
     #[test]
     fn shadow_stack_helpers_cover_lock_success_path_for_coverage() {
         push_stack_frame("test_frame", 1);
@@ -14041,6 +17766,33 @@ result
         assert_eq!(result, Value::Integer(1));
     }
 
+    #[test]
+    fn test_if_let_binds_only_in_then_branch() {
+        let result = run(r#"
+ken d = {"a": 1}
+ken seen = []
+gin ken x = get(d, "a", naething) { shove(seen, x) } ither { shove(seen, "nane") }
+gin ken x = get(d, "z", naething) { shove(seen, x) } ither { shove(seen, "nane") }
+gin ken zero = 0 { shove(seen, zero) }
+seen
+"#)
+        .unwrap();
+        assert_eq!(
+            result,
+            Value::List(Rc::new(RefCell::new(vec![
+                Value::Integer(1),
+                Value::String("nane".to_string()),
+                Value::Integer(0),
+            ])))
+        );
+
+        // x is only in scope inside the then branch
+        assert!(run("gin ken x = naething { } ither { blether x }").is_err());
+        assert!(run("gin ken x = 1 { }\nx").is_err());
+        let shadowed = run("ken x = 5\ngin ken x = 7 { }\nx").unwrap();
+        assert_eq!(shadowed, Value::Integer(5));
+    }
+
     #[test]
     fn test_while_loop() {
         let result = run(r#"
@@ -14132,6 +17884,27 @@ d["a"]
         assert!(run("undefined_var").is_err());
     }
 
+    #[test]
+    fn test_functions_print_their_name_an_arity() {
+        let shown = |source: &str| match run(source).unwrap() {
+            Value::String(s) => s,
+            other => panic!("expected a string, got {:?}", other),
+        };
+        assert_eq!(
+            shown("dae add(a, b) { gie a + b }\ntae_string(add)"),
+            "<dae add/2>"
+        );
+        assert_eq!(
+            shown("ken double = |x| x * 2\ntae_string(double)"),
+            "<lambda@1:14/1>"
+        );
+        // The same lambda gets the same name every time it's made
+        assert_eq!(
+            shown("dae maker() { gie |a, b| a }\nf\"{maker()} {maker()}\""),
+            "<lambda@1:19/2> <lambda@1:19/2>"
+        );
+    }
+
     #[test]
     fn test_lambda() {
         // Basic lambda
@@ -14216,6 +17989,37 @@ d["a"]
         );
     }
 
+    #[test]
+    fn test_any_all_find_count() {
+        let result = run(r#"
+ken nums = [1, 2, 3, 4]
+[any(nums, |x| x > 3), any([], |x| aye), all(nums, |x| x > 0), all(nums, |x| x > 1),
+ find(nums, |x| x % 2 == 0), find(nums, |x| x > 9), count(nums, |x| x % 2 == 0), count([], |x| aye)]
+"#)
+        .unwrap();
+        assert_eq!(result.to_string(), "[aye, nae, aye, nae, 2, naething, 2, 0]");
+
+        let err = run("any(42, |x| aye)").unwrap_err();
+        assert!(err.to_string().contains("any() expects a list"), "{err}");
+        assert!(run("count([1])").is_err());
+    }
+
+    #[test]
+    fn test_any_all_find_stop_at_the_decisive_element() {
+        for (call, expected) in [
+            ("any(nums, check)", "[aye, [1, 2]]"),
+            ("all(nums, check)", "[nae, [1]]"),
+            ("find(nums, check)", "[2, [1, 2]]"),
+            ("count(nums, check)", "[3, [1, 2, 3, 4]]"),
+        ] {
+            let source = format!(
+                "ken seen = []\ndae check(x) {{\n    shove(seen, x)\n    gie x > 1\n}}\nken nums = [1, 2, 3, 4]\n[{}, seen]",
+                call
+            );
+            assert_eq!(run(&source).unwrap().to_string(), expected, "{call}");
+        }
+    }
+
     #[test]
     fn test_pattern_matching() {
         let result = run(r#"
@@ -14667,11 +18471,540 @@ f"The answer is {x * 2}"
         interp.set_trace_mode(TraceMode::Verbose);
         assert_eq!(interp.trace_mode(), TraceMode::Verbose);
 
-        // Code should still execute correctly with trace on
-        interp.set_trace_mode(TraceMode::Off); // Turn off to avoid stderr output
-        let program = crate::parser::parse("ken x = 42\ngin x > 10 { x = x * 2 }\nx").unwrap();
-        let result = interp.interpret(&program).unwrap();
-        assert_eq!(result, Value::Integer(84));
+        // Code should still execute correctly with trace on
+        interp.set_trace_mode(TraceMode::Off); // Turn off to avoid stderr output
+        let program = crate::parser::parse("ken x = 42\ngin x > 10 { x = x * 2 }\nx").unwrap();
+        let result = interp.interpret(&program).unwrap();
+        assert_eq!(result, Value::Integer(84));
+    }
+
+    #[test]
+    fn test_airity_counts_required_params() {
+        let source = r#"
+dae naen() { gie 1 }
+dae twa(a, b) { gie a + b }
+dae wi_default(a, b = 2) { gie a + b }
+ken lam = |x, y, z| x + y + z
+[airity(naen), airity(twa), airity(wi_default), airity(lam), airity(len), airity(range)]
+"#;
+        let result = run(source).unwrap();
+        let list = result.as_list().expect("Expected list");
+        assert_eq!(
+            *list.borrow(),
+            vec![
+                Value::Integer(0),
+                Value::Integer(2),
+                Value::Integer(1),
+                Value::Integer(3),
+                Value::Integer(1),
+                Value::Nil,
+            ]
+        );
+
+        let err = run("airity(42)").unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("airity() needs a function, no' a integer"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_pairt_fixes_leading_arguments() {
+        let source = r#"
+dae sum3(a, b, c) { gie a * 100 + b * 10 + c }
+ken add1 = pairt(sum3, 1)
+ken add12 = pairt(sum3, 1, 2)
+ken chained = pairt(add1, 2)
+[add1(2, 3), add12(3), chained(4), airity(add1), airity(add12), airity(pairt(sum3, 1, 2, 3)), pairt(sum3, 1, 2, 3)()]
+"#;
+        let result = run(source).unwrap();
+        let list = result.as_list().expect("Expected list");
+        assert_eq!(
+            *list.borrow(),
+            vec![
+                Value::Integer(123),
+                Value::Integer(123),
+                Value::Integer(124),
+                Value::Integer(2),
+                Value::Integer(1),
+                Value::Integer(0),
+                Value::Integer(123),
+            ]
+        );
+
+        // Lambdas and built-ins work too, and the wrong count still errors
+        assert_eq!(
+            run("ken f = pairt(|x, y| x - y, 10)\nf(3)").unwrap(),
+            Value::Integer(7)
+        );
+        assert_eq!(run("pairt(max, 4)(9)").unwrap(), Value::Integer(9));
+        assert!(run("pairt(|x, y| x - y, 10)(1, 2)").is_err());
+
+        let err = run("dae sum3(a, b, c) { gie a }\npairt(sum3, 1, 2, 3, 4)").unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("pairt() got 4 arguments fer 'sum3', but it only taks 3"),
+            "{err}"
+        );
+        assert!(run("pairt(42, 1)").is_err());
+    }
+
+    #[test]
+    fn test_compose_and_pipe_match_the_pipe_operator() {
+        let source = r#"
+dae add1(x) { gie x + 1 }
+dae double(x) { gie x * 2 }
+ken both = compose(add1, double)
+[both(5), 5 |> double |> add1, pipe(5, double, add1), pipe(5), pipe(-3, abs, |x| x * 10)]
+"#;
+        let result = run(source).unwrap();
+        let list = result.as_list().expect("Expected list");
+        assert_eq!(
+            *list.borrow(),
+            vec![
+                Value::Integer(11),
+                Value::Integer(11),
+                Value::Integer(11),
+                Value::Integer(5),
+                Value::Integer(30),
+            ]
+        );
+
+        // Functions with a default past the first still fit
+        assert_eq!(
+            run("dae scale(x, times = 3) { gie x * times }\ncompose(scale, scale)(2)").unwrap(),
+            Value::Integer(18)
+        );
+
+        let err = run("pipe(1, |x| x, 42)").unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("pipe() needs functions, no' a integer"),
+            "{err}"
+        );
+        let err = run("compose(|a, b| a + b, |x| x)").unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("compose() needs functions that tak one argument"),
+            "{err}"
+        );
+        assert!(run("compose(|x| x)").is_err());
+    }
+
+    #[test]
+    fn test_expeck_error_passes_when_the_thunk_fails() {
+        assert_eq!(run("expeck_error(|| 1 / 0)").unwrap(), Value::Bool(true));
+        assert_eq!(
+            run("expeck_error(|| 1 / 0, \"divide by zero\")").unwrap(),
+            Value::Bool(true)
+        );
+        let source = r#"
+dae check_age(age) {
+    gin age < 0 { hurl "Age cannae be negative" }
+    gie age
+}
+expeck_error(|| check_age(-1), "negative")
+"#;
+        assert_eq!(run(source).unwrap(), Value::Bool(true));
+    }
+
+    #[test]
+    fn test_expeck_error_fails_when_the_thunk_succeeds() {
+        let err = run("expeck_error(|| 1 + 1)").unwrap_err();
+        assert!(
+            matches!(err, HaversError::AssertionFailed { line: 1, .. }),
+            "{err:?}"
+        );
+        assert!(err.to_string().contains("gied back 2"), "{err}");
+
+        // The wrong error is a failure too
+        let err = run("expeck_error(|| 1 / 0, \"nae such text\")").unwrap_err();
+        assert!(
+            err.to_string().contains("mentionin' 'nae such text'"),
+            "{err}"
+        );
+
+        // boak still gets out, and the arguments are checked
+        assert!(matches!(
+            run("expeck_error(|| boak(\"awa\"))").unwrap_err().root(),
+            HaversError::Boak { .. }
+        ));
+        assert!(run("expeck_error()").is_err());
+        assert!(run("expeck_error(|| 1 / 0, 5)").is_err());
+    }
+
+    #[test]
+    fn test_step_limit_stops_a_runaway_loop() {
+        let program = parse("ken n = 0\nwhiles aye {\n    n = n + 1\n}\n").unwrap();
+        let mut interp = Interpreter::new();
+        interp.set_step_limit(Some(1_000));
+        let err = interp.interpret(&program).unwrap_err();
+        assert!(
+            matches!(err, HaversError::ExecutionTimeout { .. }),
+            "{err:?}"
+        );
+        // Caught at the first statement past the limit
+        let taken = interp.steps_taken();
+        assert!((1_001..1_010).contains(&taken), "{taken}");
+
+        // hae_a_bash cannot swallow it
+        let program =
+            parse("whiles aye {\n    hae_a_bash { ken x = 1 } gin_it_gangs_wrang e { }\n}\n")
+                .unwrap();
+        interp.set_step_limit(Some(500));
+        let err = interp.interpret(&program).unwrap_err();
+        assert!(matches!(err, HaversError::ExecutionTimeout { .. }));
+    }
+
+    #[test]
+    fn test_step_limit_lets_short_programs_finish() {
+        let program =
+            parse("ken total = 0\nfer i in 1..10 {\n    total = total + i\n}\ntotal").unwrap();
+        let mut interp = Interpreter::new();
+        interp.set_step_limit(Some(1_000));
+        assert_eq!(interp.interpret(&program).unwrap(), Value::Integer(45));
+        let taken = interp.steps_taken();
+        assert!(taken > 9 && taken <= 1_000, "{taken}");
+
+        // Exactly enough steps is fine; half as many is not
+        interp.set_step_limit(Some(taken));
+        assert_eq!(interp.interpret(&program).unwrap(), Value::Integer(45));
+        interp.set_step_limit(Some(taken / 2));
+        assert!(matches!(
+            interp.interpret(&program).unwrap_err(),
+            HaversError::ExecutionTimeout { .. }
+        ));
+
+        // Lifting the limit still counts, for profiling
+        interp.set_step_limit(None);
+        interp.interpret(&program).unwrap();
+        assert_eq!(interp.steps_taken(), taken);
+    }
+
+    #[test]
+    fn test_reset_environment_keeps_the_prelude() {
+        let mut interp = Interpreter::new();
+        interp.load_prelude().unwrap();
+        interp
+            .interpret(&parse("ken haggis_tally = 12\ndae wee(a, b) { gie \"mine\" }").unwrap())
+            .unwrap();
+        assert_eq!(
+            interp.interpret(&parse("haggis_tally").unwrap()).unwrap(),
+            Value::Integer(12)
+        );
+
+        interp.reset_environment();
+        assert!(interp.has_prelude());
+        let err = interp
+            .interpret(&parse("haggis_tally").unwrap())
+            .unwrap_err();
+        assert!(
+            matches!(err.root(), HaversError::UndefinedVariable { .. }),
+            "{err}"
+        );
+        // The prelude's own `wee` is back, and the built-ins are still there
+        assert_eq!(
+            interp.interpret(&parse("wee(3, 5)").unwrap()).unwrap(),
+            Value::Integer(3)
+        );
+        assert_eq!(
+            interp.interpret(&parse("len([1, 2])").unwrap()).unwrap(),
+            Value::Integer(2)
+        );
+    }
+
+    #[test]
+    fn test_output_limit_truncates_a_chatty_program() {
+        let program = parse("fer i in 0..1000000 {\n    blether i\n}\ngie \"done\"").unwrap();
+        let mut interp = Interpreter::new();
+        interp.set_output_limit(Some(100));
+        assert_eq!(
+            interp.interpret(&program).unwrap(),
+            Value::String("done".to_string())
+        );
+        let output = interp.take_output();
+        assert_eq!(output.len(), 101);
+        assert_eq!(output[99], "99");
+        assert_eq!(output[100], "… output truncated efter 100 lines");
+        assert!(interp.output_truncated());
+
+        // Setting the limit again starts the count over
+        interp.set_output_limit(Some(2));
+        assert!(!interp.output_truncated());
+        interp
+            .interpret(&parse("blether 1\nblether 2").unwrap())
+            .unwrap();
+        assert_eq!(interp.take_output(), vec!["1", "2"]);
+        assert!(!interp.output_truncated());
+    }
+
+    #[test]
+    fn test_memory_guard_stops_a_growing_list() {
+        let program = parse("ken xs = []\nwhiles aye {\n    shove(xs, 1)\n}\n").unwrap();
+        let mut interp = Interpreter::new();
+        interp.set_memory_guard(Some(100));
+        let err = interp.interpret(&program).unwrap_err();
+        assert!(
+            matches!(err, HaversError::OutOfMemory { line: 3 }),
+            "{err:?}"
+        );
+
+        let program = parse("ken s = \"ab\" * 100\nlen(s)").unwrap();
+        let mut interp = Interpreter::new();
+        interp.set_memory_guard(Some(200));
+        assert_eq!(interp.interpret(&program).unwrap(), Value::Integer(200));
+        interp.set_memory_guard(Some(199));
+        assert!(matches!(
+            interp.interpret(&program).unwrap_err(),
+            HaversError::OutOfMemory { line: 1 }
+        ));
+    }
+
+    fn run_big(source: &str) -> HaversResult<Value> {
+        let program = parse(source).unwrap();
+        let mut interp = Interpreter::new();
+        interp.set_big_integers(true);
+        interp.interpret(&program)
+    }
+
+    #[test]
+    fn test_big_integers_compute_fifty_factorial_exactly() {
+        let source = r#"
+ken acc = 1
+fer i in 1..51 {
+    acc = acc * i
+}
+acc
+"#;
+        let result = run_big(source).unwrap();
+        assert!(matches!(result, Value::BigInt(_)), "{result:?}");
+        assert_eq!(
+            result.to_string(),
+            "30414093201713378043612608166064768844377641568960512000000000000"
+        );
+        // Off by default, the overflow is still an error
+        assert!(matches!(
+            run(source),
+            Err(HaversError::IntegerOverflow { .. })
+        ));
+    }
+
+    #[test]
+    fn test_big_integers_work_wi_the_usual_operators() {
+        let big = "ken big = 2 ** 64\n";
+        let check = |expr: &str| run_big(&format!("{big}{expr}")).unwrap();
+        assert_eq!(
+            check("tae_string(big)"),
+            Value::String("18446744073709551616".to_string())
+        );
+        assert_eq!(
+            check("whit_kind(big)"),
+            Value::String("integer".to_string())
+        );
+        assert_eq!(check("big > 9223372036854775807"), Value::Bool(true));
+        assert_eq!(check("big == 2 ** 64"), Value::Bool(true));
+        assert_eq!(check("big % 10"), Value::Integer(6));
+        assert_eq!(check("-big < 0"), Value::Bool(true));
+        assert_eq!(check("big / 2.0"), Value::Float(9223372036854775808.0));
+        // Back under i64::MAX it's a plain integer again
+        assert_eq!(check("big - big + 1"), Value::Integer(1));
+        assert_eq!(check("big / (big / 4)"), Value::Integer(4));
+        assert!(matches!(
+            run_big(&format!("{big}big / 0")),
+            Err(HaversError::DivisionByZero { .. })
+        ));
+        assert!(run_big(&format!("{big}big & 1")).is_err());
+    }
+
+    #[test]
+    fn test_dict_get_defaults_tae_naething() {
+        let source = r#"
+ken d = {"a": 1}
+[dict_get(d, "a"), dict_get(d, "b"), dict_get(d, "b", 7), dict_get(d, "a", 7)]
+"#;
+        let result = run(source).unwrap();
+        let list = result.as_list().expect("Expected list");
+        assert_eq!(
+            *list.borrow(),
+            vec![
+                Value::Integer(1),
+                Value::Nil,
+                Value::Integer(7),
+                Value::Integer(1),
+            ]
+        );
+        assert!(run("dict_get({})").is_err());
+        assert!(run("dict_get({}, 1, 2, 3)").is_err());
+        assert!(run("dict_get([1], 0)").is_err());
+    }
+
+    #[test]
+    fn test_dict_set_updates_in_place() {
+        let source = r#"
+ken d = {"a": 1}
+ken same = dict_set(d, "b", 2)
+dict_set(d, "a", 10)
+[d["a"], d["b"], same["a"], len(keys(d))]
+"#;
+        let result = run(source).unwrap();
+        let list = result.as_list().expect("Expected list");
+        assert_eq!(
+            *list.borrow(),
+            vec![
+                Value::Integer(10),
+                Value::Integer(2),
+                Value::Integer(10),
+                Value::Integer(2),
+            ]
+        );
+        assert!(run("dict_set([], 0, 1)").is_err());
+    }
+
+    #[test]
+    fn test_dict_has_and_remove() {
+        let source = r#"
+ken d = {"a": 1, "b": 2}
+ken gone = dict_remove(d, "a")
+ken same = dict_remove(d, "nae_such_key")
+[dict_has(d, "a"), dict_has(gone, "a"), dict_has(gone, "b"), len(keys(same)), len(keys(d))]
+"#;
+        let result = run(source).unwrap();
+        let list = result.as_list().expect("Expected list");
+        assert_eq!(
+            *list.borrow(),
+            vec![
+                Value::Bool(true),
+                Value::Bool(false),
+                Value::Bool(true),
+                Value::Integer(2),
+                Value::Integer(2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dict_merge_second_wins() {
+        let source = r#"
+ken a = {"x": 1, "y": 2}
+ken b = {"y": 20, "z": 30}
+ken m = dict_merge(a, b)
+[m["x"], m["y"], m["z"], a["y"], len(keys(a))]
+"#;
+        let result = run(source).unwrap();
+        let list = result.as_list().expect("Expected list");
+        assert_eq!(
+            *list.borrow(),
+            vec![
+                Value::Integer(1),
+                Value::Integer(20),
+                Value::Integer(30),
+                Value::Integer(2),
+                Value::Integer(2),
+            ]
+        );
+        assert!(run("dict_merge({}, [1])").is_err());
+    }
+
+    #[test]
+    fn test_list_comprehensions() {
+        let list_of = |source: &str| format!("{}", run(source).unwrap());
+
+        assert_eq!(list_of("[x * 2 fer x in [1, 2, 3]]"), "[2, 4, 6]");
+        assert_eq!(list_of("[x fer x in [3, -1, 4, -5] gin x > 0]"), "[3, 4]");
+        assert_eq!(
+            list_of("[[a, b] fer a in 1..3 fer b in \"xy\" gin a != b]"),
+            "[[1, x], [1, y], [2, x], [2, y]]"
+        );
+        // A guard between the clauses can see the variables before it
+        assert_eq!(
+            list_of("[a * 10 + b fer a in 0..4 gin a % 2 == 1 fer b in 0..a]"),
+            "[10, 30, 31, 32]"
+        );
+        assert_eq!(list_of("[x fer x in []]"), "[]");
+    }
+
+    #[test]
+    fn test_list_comprehension_variable_is_scoped() {
+        let source = r#"
+ken x = "ootside"
+ken fs = [|| x * 10 fer x in [1, 2]]
+[x, fs[0](), fs[1]()]
+"#;
+        assert_eq!(format!("{}", run(source).unwrap()), "[ootside, 10, 20]");
+
+        let err = run("ken ys = [x fer x in [1]]\nx").unwrap_err();
+        assert!(matches!(err.root(), HaversError::UndefinedVariable { .. }), "{err}");
+        assert!(run("[x fer x in 42]").is_err());
+    }
+
+    #[test]
+    fn test_undefined_variable_suggests_a_near_miss() {
+        let err = run("ken greeting = \"hi\"\nblether greting").unwrap_err();
+        assert!(
+            err.to_string().ends_with("Did ye mean `greeting`?"),
+            "{err}"
+        );
+        assert!(
+            matches!(err.root(), HaversError::UndefinedVariable { name, .. } if name == "greting")
+        );
+
+        // Built-ins and names in enclosing scopes count, and assignment gets hints too
+        let err = run("dae f() { ken total = 0\ntotl = 1 }\nf()").unwrap_err();
+        assert!(err.to_string().contains("Did ye mean `total`?"), "{err}");
+        let err = run("lenn([1])").unwrap_err();
+        assert!(err.to_string().contains("Did ye mean `len`?"), "{err}");
+
+        // Nothing close, no hint
+        let err = run("blether zzzzzz").unwrap_err();
+        assert!(!err.to_string().contains("Did ye mean"), "{err}");
+    }
+
+    #[test]
+    fn test_trace_loop_cap_collapses_long_loops() {
+        fn trace_lines(source: &str, cap: Option<usize>) -> (Vec<String>, Value) {
+            let lines = Rc::new(RefCell::new(Vec::new()));
+            let sink = lines.clone();
+            let mut interp = Interpreter::new();
+            interp.set_trace_mode(TraceMode::Verbose);
+            interp.set_trace_sink(Box::new(move |line| {
+                sink.borrow_mut().push(line.to_string())
+            }));
+            if let Some(cap) = cap {
+                interp.set_trace_loop_cap(cap);
+            }
+            let program = crate::parser::parse(source).unwrap();
+            let result = interp.interpret(&program).unwrap();
+            let lines = lines.borrow().clone();
+            (lines, result)
+        }
+
+        let source = "ken total = 0\nken i = 0\nwhiles i < 10000 {\n    total = total + i\n    i = i + 1\n}\ntotal";
+        let (lines, result) = trace_lines(source, None);
+        assert_eq!(result, Value::Integer(49995000));
+        assert!(lines.len() < 10_000, "{} trace lines", lines.len());
+        let still: Vec<_> = lines
+            .iter()
+            .filter(|l| l.contains("still loopin'"))
+            .collect();
+        assert_eq!(still.len(), 10, "{still:?}");
+        assert!(still[0].contains("(iteration 1001)"));
+        assert!(still[9].contains("(iteration 10000)"));
+        assert!(lines
+            .iter()
+            .any(|l| l.contains("whiles loop done after 10000 iterations")));
+
+        // Nested loops stay quiet inside a loop that's past its cap, and a
+        // zero cap traces every step
+        let nested = "ken n = 0\nfer i in 0..50 {\n    fer j in 0..3 { n = n + 1 }\n}\nn";
+        let (capped, result) = trace_lines(nested, Some(5));
+        assert_eq!(result, Value::Integer(150));
+        let (full, _) = trace_lines(nested, Some(0));
+        assert!(capped.len() * 4 < full.len(), "{} vs {}", capped.len(), full.len());
+        assert!(!full.iter().any(|l| l.contains("still loopin'")));
+        assert!(capped
+            .iter()
+            .any(|l| l.contains("fer loop done after 50 iterations")));
     }
 
     #[test]
@@ -14758,6 +19091,24 @@ f"The answer is {x * 2}"
         assert!(run("tae_int([1, 2, 3])").is_err());
     }
 
+    #[test]
+    fn test_tae_int_truncates_toward_zero() {
+        assert_eq!(run("tae_int(3.9)").unwrap(), Value::Integer(3));
+        assert_eq!(run("tae_int(-3.9)").unwrap(), Value::Integer(-3));
+        assert!(run("tae_int(tae_float(\"inf\"))").is_err());
+        assert!(run("tae_int(1e19)").is_err());
+    }
+
+    #[test]
+    fn test_tae_int_with_a_base() {
+        assert_eq!(run("tae_int(\"ff\", 16)").unwrap(), Value::Integer(255));
+        assert_eq!(run("tae_int(\"-101\", 2)").unwrap(), Value::Integer(-5));
+        assert!(run("tae_int(\"12\", 2)").is_err());
+        assert!(run("tae_int(\"ff\", 37)").is_err());
+        assert!(run("tae_int(255, 16)").is_err());
+        assert!(run("tae_int()").is_err());
+    }
+
     #[test]
     fn test_tae_float_from_int() {
         assert_eq!(run("tae_float(42)").unwrap(), Value::Float(42.0));
@@ -14776,9 +19127,24 @@ f"The answer is {x * 2}"
     #[test]
     fn test_tae_float_error() {
         assert!(run("tae_float(\"xyz\")").is_err());
+        assert!(run("tae_float(\"\")").is_err());
+        assert!(run("tae_float(\" 1.5\")").is_err());
+        assert!(run("tae_float(naething)").is_err());
         assert!(run("tae_float([1, 2, 3])").is_err());
     }
 
+    #[test]
+    fn test_tae_bool_follows_the_truthiness_rules() {
+        for falsy in ["\"\"", "[]", "0", "0.0", "naething", "nae"] {
+            let result = run(&format!("tae_bool({})", falsy)).unwrap();
+            assert_eq!(result, Value::Bool(false), "{falsy}");
+        }
+        for truthy in ["\"0\"", "[0]", "-1", "0.5", "aye"] {
+            let result = run(&format!("tae_bool({})", truthy)).unwrap();
+            assert_eq!(result, Value::Bool(true), "{truthy}");
+        }
+    }
+
     #[test]
     fn test_shove_error() {
         assert!(run("shove(42, 1)").is_err());
@@ -14790,6 +19156,56 @@ f"The answer is {x * 2}"
         assert!(run("yank(42)").is_err());
     }
 
+    #[test]
+    fn test_stick_in_head_middle_and_end() {
+        let result = run(r#"
+ken l = [2, 4]
+stick_in(l, 0, 1)
+stick_in(l, 2, 3)
+stick_in(l, len(l), 6)
+stick_in(l, -1, 5)
+l
+"#)
+        .unwrap();
+        assert_eq!(result.to_string(), "[1, 2, 3, 4, 5, 6]");
+    }
+
+    #[test]
+    fn test_stick_in_returns_the_same_list() {
+        let result = run("ken l = [1]\nken m = stick_in(l, 1, 2)\nshove(m, 3)\nlen(l)").unwrap();
+        assert_eq!(result, Value::Integer(3));
+    }
+
+    #[test]
+    fn test_tak_oot_removes_and_returns() {
+        let result = run(r#"
+ken l = ["a", "b", "c", "d"]
+ken first = tak_oot(l, 0)
+ken last = tak_oot(l, -1)
+[first, last, l]
+"#)
+        .unwrap();
+        assert_eq!(result.to_string(), "[a, d, [b, c]]");
+    }
+
+    #[test]
+    fn test_whaur_finds_first_match() {
+        assert_eq!(run("whaur([1, 2, 3, 2], 2)").unwrap(), Value::Integer(1));
+        assert_eq!(run("whaur([[1], [2]], [2])").unwrap(), Value::Integer(1));
+        assert_eq!(run("whaur([1, 2], 9)").unwrap(), Value::Integer(-1));
+    }
+
+    #[test]
+    fn test_list_mutation_errors() {
+        assert!(run("stick_in([1, 2], 3, 0)").is_err());
+        assert!(run("stick_in([1, 2], -3, 0)").is_err());
+        assert!(run("stick_in(42, 0, 0)").is_err());
+        assert!(run("tak_oot([1, 2], 2)").is_err());
+        assert!(run("tak_oot([1, 2], -3)").is_err());
+        assert!(run("tak_oot([], 0)").is_err());
+        assert!(run("whaur(\"abc\", \"b\")").is_err());
+    }
+
     #[test]
     fn test_keys_values() {
         let result = run(r#"keys({"a": 1, "b": 2})"#).unwrap();
@@ -14926,6 +19342,26 @@ f"The answer is {x * 2}"
         assert_eq!(run(r#"coont("hello", "l")"#).unwrap(), Value::Integer(2));
     }
 
+    #[test]
+    fn test_coont_an_frequencies() {
+        assert_eq!(
+            run("coont([[1], 2, [1], 3, [1]], [1])").unwrap(),
+            Value::Integer(3)
+        );
+        // Keys come out in the order each item was first seen
+        assert_eq!(
+            run("tae_string(frequencies([3, 1, 1, 2, 3, 3]))").unwrap(),
+            Value::String(r#"{"3": 3, "1": 2, "2": 1}"#.to_string())
+        );
+        assert_eq!(
+            run("tae_string(frequencies([1, 1, 2, 3, 3, 3]))").unwrap(),
+            Value::String(r#"{"1": 2, "2": 1, "3": 3}"#.to_string())
+        );
+        assert_eq!(run("len(frequencies([]))").unwrap(), Value::Integer(0));
+        let err = run("frequencies([1, [2]])").unwrap_err();
+        assert!(err.to_string().contains("cannae tally a list"), "{err}");
+    }
+
     #[test]
     fn test_wheesht_upper_lower() {
         assert_eq!(
@@ -15100,6 +19536,49 @@ count
         assert_eq!(result, Value::Integer(5));
     }
 
+    #[test]
+    fn test_for_key_value() {
+        let result = run(r#"
+ken stock = {"neeps": 3, "tatties": 5}
+ken names = ""
+ken total = 0
+fer name, count in stock {
+    names = names + name + " "
+    total = total + count
+}
+fer a, b in [[1, 2], [3, 4]] {
+    total = total + a * b
+}
+names + tae_string(total)
+"#)
+        .unwrap();
+        assert_eq!(result, Value::String("neeps tatties 22".to_string()));
+
+        // Generators step through pairs the same way
+        let result = run(r#"
+dae doubled(d) {
+    fer k, v in d {
+        gie_back v * 2
+    }
+}
+ken out = []
+fer v in doubled({"a": 1, "b": 2}) {
+    shove(out, v)
+}
+out
+"#)
+        .unwrap();
+        assert_eq!(result.to_string(), "[2, 4]");
+
+        for source in ["fer a, b in [[1, 2, 3]] { }", "fer a, b in [1] { }"] {
+            let err = run(source).unwrap_err();
+            assert!(matches!(err, HaversError::TypeError { .. }), "{err:?}");
+            assert!(err.to_string().contains("Cannae unpack"), "{err}");
+        }
+        // A single name still can't loop over a dict
+        assert!(run("fer k in {\"a\": 1} { }").is_err());
+    }
+
     // ==================== Assert Statement ====================
 
     #[test]
@@ -15118,6 +19597,80 @@ count
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_assert_and_assert_eq_builtins() {
+        assert_eq!(run("assert(1 < 2)").unwrap(), Value::Bool(true));
+        assert_eq!(run("assert(aye, \"fine\")").unwrap(), Value::Bool(true));
+        assert_eq!(run("assert_eq(2 + 2, 4)").unwrap(), Value::Bool(true));
+
+        let err = run("ken x = 1\nassert(x > 2, \"x is ower wee\")").unwrap_err();
+        let HaversError::AssertionFailed { message, line } = &err else {
+            panic!("expected an assertion failure, got {:?}", err);
+        };
+        assert_eq!((message.as_str(), *line), ("Assertion failed: x is ower wee", 2));
+
+        let err = run("\nassert_eq(2 + 2, 5)").unwrap_err();
+        let HaversError::AssertionFailed { message, line } = &err else {
+            panic!("expected an assertion failure, got {:?}", err);
+        };
+        assert_eq!(
+            (message.as_str(), *line),
+            ("Assertion failed: Expected 5 but got 4", 2)
+        );
+
+        // Caught like any other error
+        let caught = run("hae_a_bash { assert_eq([1], [2]) } gin_it_gangs_wrang e { e }").unwrap();
+        assert!(caught.to_string().contains("Expected [2] but got [1]"), "{}", caught);
+
+        assert!(matches!(
+            run("assert_eq(1)").unwrap_err(),
+            HaversError::WrongArity { .. }
+        ));
+    }
+
+    #[test]
+    fn test_format_builtin() {
+        let fmt = |code: &str| run(code).unwrap().to_string();
+        assert_eq!(
+            fmt(r#"format("{} has {} coos", "Hamish", 3)"#),
+            "Hamish has 3 coos"
+        );
+        assert_eq!(
+            fmt(r#"format("{} an' {}", [1, 2], naething)"#),
+            "[1, 2] an' naething"
+        );
+
+        // Width: numbers to the right, the rest to the left
+        assert_eq!(fmt(r#"format("[{:5}]", 42)"#), "[   42]");
+        assert_eq!(fmt(r#"format("[{:5}]", "ab")"#), "[ab   ]");
+        assert_eq!(fmt(r#"format("[{:2}]", "lang")"#), "[lang]");
+
+        // Precision
+        assert_eq!(fmt(r#"format("{:.2}", 3.14159)"#), "3.14");
+        assert_eq!(fmt(r#"format("{:.1}", 7)"#), "7.0");
+        assert_eq!(fmt(r#"format("[{:8.3}]", 2.5)"#), "[   2.500]");
+
+        // Escaped braces
+        assert_eq!(fmt(r#"format("{{{}}} }}", 1)"#), "{1} }");
+        assert_eq!(fmt(r#"format("{{}}")"#), "{}");
+
+        for (code, expected) in [
+            (r#"format("{} {}", 1)"#, "mair placeholders than values"),
+            (
+                r#"format("{}", 1, 2)"#,
+                "gied 2 values but the template only has 1",
+            ),
+            (r#"format("{:x}", 1)"#, "disnae ken the placeholder '{:x}'"),
+            (r#"format("{:.2}", "toast")"#, "precision is fer numbers"),
+            (r#"format("{", 1)"#, "never closed"),
+            (r#"format("}")"#, "lone '}'"),
+            (r#"format(42)"#, "template string"),
+        ] {
+            let err = run(code).unwrap_err().to_string();
+            assert!(err.contains(expected), "{}: {}", code, err);
+        }
+    }
+
     // ==================== Spread Operator ====================
 
     #[test]
@@ -15181,6 +19734,132 @@ d["b"]
         );
     }
 
+    // ==================== JSON Pretty ====================
+
+    #[test]
+    fn test_json_pretty_picks_the_indent_an_sorts_keys() {
+        let doc = r#"ken d = {"zeta": 1, "alpha": [1, {"b": naething, "a": aye}], "mid": {}}"#;
+        let pretty = |args: &str| match run(&format!("{}\njson_pretty(d{})", doc, args)).unwrap() {
+            Value::String(s) => s,
+            other => panic!("expected a string, got {:?}", other),
+        };
+        assert!(pretty("").starts_with("{\n  \"zeta\": 1,\n  \"alpha\": [\n    1,"));
+        assert_eq!(
+            pretty(", naething, aye"),
+            r#"{"alpha": [1, {"a": true, "b": null}], "mid": {}, "zeta": 1}"#
+        );
+        assert_eq!(
+            pretty(", 4, aye"),
+            "{\n    \"alpha\": [\n        1,\n        {\n            \"a\": true,\n            \"b\": null\n        }\n    ],\n    \"mid\": {},\n    \"zeta\": 1\n}"
+        );
+        assert!(pretty(", \"\\t\"").contains("\n\t\t{\n\t\t\t\"b\": null,"));
+
+        for bad in [", \"x\"", ", 17", ", 2, 1"] {
+            assert!(
+                run(&format!("{}\njson_pretty(d{})", doc, bad)).is_err(),
+                "{bad}"
+            );
+        }
+    }
+
+    // ==================== JSON Query ====================
+
+    #[test]
+    fn test_json_query_digs_through_nested_values() {
+        let doc = r#"ken doc = json_parse("{\"user\": {\"name\": \"Morag\", \"addresses\": [{\"city\": \"Perth\"}, {\"city\": \"Oban\", \"zip\": null}]}, \"a.b\": 1}")"#;
+        let query = |path: &str| run(&format!("{}\njson_query(doc, \"{}\")", doc, path)).unwrap();
+        assert_eq!(query("user.name"), Value::String("Morag".to_string()));
+        assert_eq!(
+            query("user.addresses[0].city"),
+            Value::String("Perth".to_string())
+        );
+        assert_eq!(
+            query("user.addresses[-1].city"),
+            Value::String("Oban".to_string())
+        );
+        assert_eq!(
+            query("user.addresses.1.city"),
+            query("user.addresses[1].city")
+        );
+        assert_eq!(query("[\\\"a.b\\\"]"), Value::Integer(1));
+        assert_eq!(
+            run(&format!("{}\njson_query(doc, \"\") == doc", doc)).unwrap(),
+            Value::Bool(true)
+        );
+        assert_eq!(
+            query("user.addresses[*].city"),
+            run(r#"["Perth", "Oban"]"#).unwrap()
+        );
+        // Wildcards drop the misses, so only the one zip (and it's null) - none
+        assert_eq!(query("user.addresses[*].zip"), run("[]").unwrap());
+
+        // Missing keys, indices off the end, and stepping into a string are nil
+        assert_eq!(query("user.age"), Value::Nil);
+        assert_eq!(query("user.addresses[2].city"), Value::Nil);
+        assert_eq!(query("user.addresses[-3]"), Value::Nil);
+        assert_eq!(query("user.name.first"), Value::Nil);
+
+        assert!(parse_json_path("user[0").is_err());
+        assert!(parse_json_path("user..name").is_err());
+        assert!(parse_json_path("user.").is_err());
+        assert!(parse_json_path("user[one]").is_err());
+    }
+
+    // ==================== CSV Functions ====================
+
+    #[test]
+    fn test_csv_round_trip() {
+        let rows: Vec<Vec<String>> = [
+            vec!["name", "note", "score"],
+            vec!["Morag", "", "9"],
+            vec!["Hamish", "twa\nlines", "7"],
+            vec!["", "tab\there", "\"quoted\""],
+        ]
+        .iter()
+        .map(|row| row.iter().map(|f| f.to_string()).collect())
+        .collect();
+
+        for delim in [',', '\t'] {
+            let text = csv_write_rows(&rows, delim);
+            assert_eq!(csv_parse_rows(&text, delim).unwrap(), rows, "{:?}", text);
+        }
+
+        let result = run(r#"
+ken rows = [["a", "b"], [1, 2.5]]
+csv_parse(csv_write(rows)) == [["a", "b"], ["1", "2.5"]]
+"#)
+        .unwrap();
+        assert_eq!(result, Value::Bool(true));
+    }
+
+    #[test]
+    fn test_csv_field_wi_a_comma_an_a_quote() {
+        let written = run(r#"csv_write([["a,b", "say \"hi\"", "plain"]])"#).unwrap();
+        assert_eq!(
+            written,
+            Value::String("\"a,b\",\"say \"\"hi\"\"\",plain\n".to_string())
+        );
+
+        let parsed = run(r#"csv_parse("\"a,b\",\"say \"\"hi\"\"\"\r\nx,y")"#).unwrap();
+        let expected = run(r#"[["a,b", "say \"hi\""], ["x", "y"]]"#).unwrap();
+        assert_eq!(parsed, expected);
+
+        // TSV leaves commas alone
+        let tsv = run(r#"csv_parse("a,b\tc", "\t")"#).unwrap();
+        assert_eq!(tsv, run(r#"[["a,b", "c"]]"#).unwrap());
+
+        for (text, expected) in [
+            ("a,\"open\nb", "never closed"),
+            ("\"ab\"c,d", "stray text efter a closin' quote on line 1"),
+            ("a,b\nc\"d", "middle o' an unquoted field on line 2"),
+        ] {
+            let err = csv_parse_rows(text, ',').unwrap_err();
+            assert!(err.contains(expected), "{}: {}", text, err);
+        }
+        assert!(run(r#"csv_parse("a", ",,")"#).is_err());
+        assert!(run(r#"csv_write([1, 2])"#).is_err());
+    }
+
     // ==================== JSON Functions ====================
 
     #[test]
@@ -15529,8 +20208,10 @@ keek x {
 
     #[test]
     fn test_sqrt_error_negative() {
-        let f = run("sqrt(-1)").unwrap().as_float().expect("Expected float");
-        assert!(f.is_nan());
+        let err = run("sqrt(-1)").unwrap_err();
+        assert!(err.to_string().contains("negative"), "{}", err);
+        assert!(run("sqrt(-0.5)").is_err());
+        assert_eq!(run("sqrt(0)").unwrap(), Value::Float(0.0));
     }
 
     #[test]
@@ -15895,6 +20576,36 @@ d["name"]
         assert_eq!(result, Value::String("Alice".to_string()));
     }
 
+    #[test]
+    fn test_dict_get_has_key_and_remove() {
+        let result = run(r#"
+ken d = {"a": 1, "b": naething, "c": 3}
+[get(d, "a", 0), get(d, "b", 0), get(d, "z", 0), has_key(d, "b"), has_key(d, "z")]
+"#)
+        .unwrap();
+        assert_eq!(result.to_string(), "[1, naething, 0, aye, nae]");
+
+        // remove works in place and keeps the rest in insertion order
+        let result = run(r#"
+ken d = {"a": 1, "b": 2, "c": 3, "d": 4}
+ken gone = remove(d, "b")
+ken missing = remove(d, "z")
+d["e"] = 5
+[gone, missing, keys(d), values(d), len(d)]
+"#)
+        .unwrap();
+        assert_eq!(
+            result.to_string(),
+            "[2, naething, [a, c, d, e], [1, 3, 4, 5], 4]"
+        );
+
+        // Plain indexing on a missing key is still an error
+        assert!(run(r#"{"a": 1}["z"]"#).is_err());
+        assert!(run("get([1], 0, 0)").is_err());
+        assert!(run("has_key(42, 0)").is_err());
+        assert!(run(r#"remove("abc", "a")"#).is_err());
+    }
+
     // ==================== String Operations ====================
 
     #[test]
@@ -15957,6 +20668,56 @@ whit_kind(foo)
         assert_eq!(result, Value::String("function".to_string()));
     }
 
+    #[test]
+    fn test_whit_kind_names_every_variant() {
+        let result = run(r#"
+kin Dug { dae init() { masel.n = 1 } }
+thing Pt { x, y }
+dae gen() { gie_back 1 }
+ken values = [1, 1.5, "s", aye, naething, [], {}, creel([]), bytes(2),
+              gen, |x| x, len, Dug, Dug(), Pt, gen()]
+ken kinds = []
+fer v in values { shove(kinds, whit_kind(v)) }
+kinds
+"#)
+        .unwrap();
+        let expected = [
+            "integer",
+            "float",
+            "string",
+            "bool",
+            "naething",
+            "list",
+            "dict",
+            "creel",
+            "bytes",
+            "function",
+            "function",
+            "native function",
+            "class",
+            "Dug",
+            "struct",
+            "generator",
+        ];
+        let Value::List(kinds) = result else {
+            panic!("expected a list of kinds");
+        };
+        let kinds: Vec<String> = kinds.borrow().iter().map(|k| k.to_string()).collect();
+        assert_eq!(kinds, expected);
+    }
+
+    #[test]
+    fn test_is_a_class_names() {
+        let result = run(r#"
+kin Beast { dae init() { masel.n = 1 } }
+kin Dug fae Beast { }
+ken d = Dug()
+[is_a(d, "Dug"), is_a(d, "Beast"), is_a(d, "instance"), is_a(d, "Cat"), is_a(creel([]), "creel")]
+"#)
+        .unwrap();
+        assert_eq!(result.to_string(), "[aye, aye, aye, nae, aye]");
+    }
+
     // ==================== Pipe Operator ====================
 
     #[test]
@@ -16029,6 +20790,54 @@ hae_a_bash {
         assert_eq!(result, Value::String("caught".to_string()));
     }
 
+    #[test]
+    fn test_boak_is_not_caught() {
+        let err = run(r#"
+hae_a_bash {
+    boak("deliberate")
+} gin_it_gangs_wrang e {
+    "caught"
+}
+"#)
+        .unwrap_err();
+        assert_eq!(
+            err,
+            HaversError::Boak {
+                message: "deliberate".to_string(),
+                line: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn test_boak_wrong_arity() {
+        let err = run("boak()").unwrap_err();
+        assert!(matches!(err, HaversError::WrongArity { expected: 1, .. }));
+    }
+
+    #[test]
+    fn test_classify_panic() {
+        assert_eq!(
+            classify_panic_at_depth("index out of bounds", 1),
+            PanicOrigin::UserCode
+        );
+        assert_eq!(
+            classify_panic_at_depth("index out of bounds", 0),
+            PanicOrigin::Internal
+        );
+        assert_eq!(
+            classify_panic_at_depth("already borrowed: BorrowMutError", 1),
+            PanicOrigin::Internal
+        );
+        assert_eq!(
+            classify_panic_at_depth(
+                "called `Result::unwrap()` on an `Err` value: PoisonError { .. }",
+                2
+            ),
+            PanicOrigin::Internal
+        );
+    }
+
     // ==================== Assert Tests ====================
 
     #[test]
@@ -16145,6 +20954,104 @@ apply(|n| n * n, 4)
     fn test_mixed_numeric_comparison() {
         let result = run("3 == 3.0").unwrap();
         assert_eq!(result, Value::Bool(true));
+        assert_eq!(run("5 < 5.5").unwrap(), Value::Bool(true));
+        assert_eq!(run("5.0 != 5").unwrap(), Value::Bool(false));
+        assert_eq!(run("5.5 >= 5").unwrap(), Value::Bool(true));
+        assert_eq!(run("[1, 2.0] == [1.0, 2]").unwrap(), Value::Bool(true));
+        assert_eq!(run("contains([1, 2], 2.0)").unwrap(), Value::Bool(true));
+        assert_eq!(
+            run_big("contains([2 ** 64], 18446744073709551616.0)").unwrap(),
+            Value::Bool(true)
+        );
+
+        // Range patterns weigh a float by value too
+        let source = r#"
+ken seen = []
+fer x in [0.5, 2.5, 5.0] {
+    keek x {
+        whan 1..5 -> { shove(seen, "in") }
+        whan _ -> { shove(seen, "oot") }
+    }
+}
+join(seen, ",")
+"#;
+        assert_eq!(
+            run(source).unwrap(),
+            Value::String("oot,in,oot".to_string())
+        );
+    }
+
+    #[test]
+    fn test_ordering_mixes_numbers_but_no_kinds() {
+        assert_eq!(run("2 < 2.5 an 3.0 <= 3").unwrap(), Value::Bool(true));
+        assert_eq!(run("\"apple\" < \"banana\"").unwrap(), Value::Bool(true));
+        assert_eq!(
+            run("sort([3, 1.5, -2, 2.0])").unwrap().to_string(),
+            "[-2, 1.5, 2.0, 3]"
+        );
+        assert_eq!(run("min(2, 1.5)").unwrap(), Value::Float(1.5));
+        assert_eq!(run("max(2, 1.5)").unwrap(), Value::Integer(2));
+        assert_eq!(
+            run("max(\"haggis\", \"neeps\")").unwrap(),
+            Value::String("neeps".to_string())
+        );
+        // A NaN compares false both ways
+        let nan = "ken inf = 1e308 * 10.0\nken nan = inf - inf\n";
+        assert_eq!(
+            run(&format!("{nan}nan < 1 or nan >= 1")).unwrap(),
+            Value::Bool(false)
+        );
+        assert_eq!(
+            run(&format!("{nan}sort([nan, 2, 1])")).unwrap().to_string(),
+            "[1, 2, NaN]"
+        );
+
+        let err = run("1 < \"2\"").unwrap_err();
+        assert!(matches!(err, HaversError::TypeError { .. }), "{err:?}");
+        assert!(err
+            .to_string()
+            .contains("Cannae compare integer wi' string"));
+        assert!(run("sort([1, \"a\", 2])")
+            .unwrap_err()
+            .to_string()
+            .contains("cannae order"));
+        assert!(run("min(1, naething)").is_err());
+        assert!(run("max([1], [2])").is_err());
+    }
+
+    #[test]
+    fn test_min_max_sum_average_over_lists() {
+        assert_eq!(run("min([3, 1.5, 2])").unwrap(), Value::Float(1.5));
+        assert_eq!(run("max([3, 1.5, 2])").unwrap(), Value::Integer(3));
+        assert_eq!(
+            run("max([\"haggis\", \"neeps\", \"tatties\"])").unwrap(),
+            Value::String("tatties".to_string())
+        );
+        assert!(run("min([])")
+            .unwrap_err()
+            .to_string()
+            .contains("Cannae find the wee-est o' naething"));
+        assert!(run("max([])")
+            .unwrap_err()
+            .to_string()
+            .contains("Cannae find the muckle-est o' naething"));
+        assert!(run("min([1, \"a\"])").is_err());
+
+        assert_eq!(run("sum([])").unwrap(), Value::Integer(0));
+        assert_eq!(run("sum([1, 2, 3])").unwrap(), Value::Integer(6));
+        assert_eq!(run("sum([1, 2.5, 3])").unwrap(), Value::Float(6.5));
+        assert!(run("sum([1, \"a\"])").is_err());
+        assert!(run("sum([9223372036854775807, 1])")
+            .unwrap_err()
+            .to_string()
+            .contains("owerflowed"));
+
+        assert_eq!(run("average([1, 2])").unwrap(), Value::Float(1.5));
+        assert_eq!(
+            run("average([1, 2.5, 3])").unwrap(),
+            Value::Float(6.5 / 3.0)
+        );
+        assert!(run("average([])").is_err());
     }
 
     #[test]
@@ -16321,6 +21228,29 @@ result
         assert_eq!(result, Value::Bool(true));
     }
 
+    #[test]
+    fn test_in_operator() {
+        let truth = |source: &str| run(source).unwrap();
+        assert_eq!(truth("2 in [1, 2, 3]"), Value::Bool(true));
+        assert_eq!(truth("5 in [1, 2, 3]"), Value::Bool(false));
+        assert_eq!(truth(r#""a" in {"a": 1}"#), Value::Bool(true));
+        assert_eq!(truth(r#"1 in {"a": 1}"#), Value::Bool(false));
+        assert_eq!(truth("3 in creel([1, 2, 3])"), Value::Bool(true));
+        assert_eq!(truth(r#""ell" in "hello""#), Value::Bool(true));
+        assert_eq!(truth("5 nae in [1, 2, 3]"), Value::Bool(true));
+        assert_eq!(truth("2 !in [1, 2, 3]"), Value::Bool(false));
+        assert_eq!(truth("1 + 1 in [2] an 3 in [3]"), Value::Bool(true));
+        assert_eq!(truth("contains(creel([1]), 1)"), Value::Bool(true));
+        assert_eq!(
+            truth("ken n = 0\nfer x in [1, 2] { gin x in [2] { n = n + x } }\nn"),
+            Value::Integer(2)
+        );
+
+        let err = run("1 in 42").unwrap_err();
+        assert!(matches!(err, HaversError::TypeError { .. }), "{err:?}");
+        assert!(run(r#"1 in "abc""#).is_err());
+    }
+
     #[test]
     fn test_sort_integers() {
         let result = run("sort([3, 1, 2])").unwrap();
@@ -16875,8 +21805,45 @@ ken l = [1, 2, 3, 4, 5, 6]
 l[0:6:2]
         "#)
         .unwrap();
-        let items = result.as_list().expect("Expected list");
-        assert_eq!(items.borrow().len(), 3); // 1, 3, 5
+        let items = result.as_list().expect("Expected list");
+        assert_eq!(items.borrow().len(), 3); // 1, 3, 5
+    }
+
+    #[test]
+    fn test_range_slices_clamp_and_count_from_the_end() {
+        let result = run(r#"
+ken l = [1, 2, 3, 4, 5]
+[l[1..4], l[-2..], l[..2], l[..], l[-10..2], l[3..99], l[4..1], l[7..], l[..-4]]
+        "#)
+        .unwrap();
+        assert_eq!(
+            result.to_string(),
+            "[[2, 3, 4], [4, 5], [1, 2], [1, 2, 3, 4, 5], [1, 2], [4, 5], [], [], [1]]"
+        );
+
+        let result = run(r#"
+ken s = "braw day"
+[s[0..4], s[-3..], s[5..2], s[..-4]]
+        "#)
+        .unwrap();
+        assert_eq!(result.to_string(), "[braw, day, , braw]");
+
+        // Slicing returns a new list
+        let result = run("ken l = [1, 2, 3]\nken m = l[..]\nshove(m, 4)\nlen(l)").unwrap();
+        assert_eq!(result, Value::Integer(3));
+    }
+
+    #[test]
+    fn test_slice_builtin_matches_range_slices() {
+        let result = run(r#"
+ken l = [1, 2, 3, 4, 5]
+[slice(l, 1, 4), slice(l, -2, naething), slice(l, -10, 2), slice(l, 3, 99), slice(l, 4, 1),
+ slice("héllo", 1, -1)]
+        "#)
+        .unwrap();
+        assert_eq!(result.to_string(), "[[2, 3, 4], [4, 5], [1, 2], [4, 5], [], éll]");
+        assert!(run("slice(42, 0, 1)").is_err());
+        assert!(run("slice([1], \"a\", 1)").is_err());
     }
 
     #[test]
@@ -17121,6 +22088,32 @@ ken [a, b, c] = [1, 2]
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_tuple_return_destructures_intae_names() {
+        let result = run(r#"
+dae divmod(a, b) {
+    gie a / b, a % b
+}
+ken q, r = divmod(17, 5)
+ken _, last = divmod(9, 4)
+ken head, ...tail = [1, 2, 3]
+[q, r, last, head, len(tail), divmod(7, 2)]
+"#)
+        .unwrap();
+        assert_eq!(result.to_string(), "[3, 2, 1, 1, 2, [3, 1]]");
+
+        // The unbracketed form wants the count to match, both ways
+        for source in [
+            "dae pair() { gie 1, 2 }\nken a, b, c = pair()",
+            "dae pair() { gie 1, 2 }\nken a = 0\nken x, _ = [1, 2, 3]",
+        ] {
+            let err = run(source).unwrap_err();
+            assert!(matches!(err, HaversError::TypeError { .. }), "{err:?}");
+            assert!(err.to_string().contains("Cannae unpack"), "{err}");
+        }
+        assert!(run("ken a, b = 5").is_err());
+    }
+
     // ==================== More Native Function Tests ====================
 
     #[test]
@@ -17175,6 +22168,100 @@ l[0]
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_seed_random_repeats_the_sequence() {
+        let source = r#"
+seed_random(42)
+[random(), random_int(1, 6), random_choice(["a", "b", "c"]), shuffle([1, 2, 3, 4, 5])]
+"#;
+        let first = run(source).unwrap();
+        assert_eq!(run(source).unwrap().to_string(), first.to_string());
+        assert_ne!(
+            run(&source.replace("42", "43")).unwrap().to_string(),
+            first.to_string()
+        );
+
+        // Seeding through the API matches seeding from a script
+        let mut interp = Interpreter::new();
+        interp.seed_random(42);
+        let program = parse("[random(), random_int(1, 6)]").unwrap();
+        let from_api = interp.interpret(&program).unwrap().to_string();
+        assert!(first.to_string().starts_with(from_api.trim_end_matches(']')));
+    }
+
+    #[test]
+    fn test_uuids_are_canonical_an_differ() {
+        let result = run("[uuid_v4(), uuid_v4(), uuid_v7(), uuid_v7()]").unwrap();
+        let ids: Vec<String> = result
+            .as_list()
+            .unwrap()
+            .borrow()
+            .iter()
+            .map(|v| v.to_string())
+            .collect();
+        for (id, version) in ids.iter().zip(['4', '4', '7', '7']) {
+            let groups: Vec<&str> = id.split('-').collect();
+            let lens: Vec<usize> = groups.iter().map(|g| g.len()).collect();
+            assert_eq!(lens, [8, 4, 4, 4, 12], "{}", id);
+            assert!(
+                id.chars()
+                    .all(|c| c == '-' || c.is_ascii_digit() || ('a'..='f').contains(&c)),
+                "{}",
+                id
+            );
+            assert!(groups[2].starts_with(version), "{}", id);
+            assert!("89ab".contains(&groups[3][..1]), "{}", id);
+        }
+        assert_ne!(ids[0], ids[1]);
+        assert_ne!(ids[2], ids[3]);
+
+        // A v7 starts with the millisecond clock, so later ones never sort earlier
+        let now_ms = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+        let stamp = u64::from_str_radix(&ids[2].replace('-', "")[..12], 16).unwrap();
+        assert!(stamp <= now_ms && now_ms - stamp < 60_000, "{}", ids[2]);
+    }
+
+    #[test]
+    #[cfg(feature = "native")]
+    fn test_seed_random_does_not_repeat_uuids() {
+        let source = "seed_random(42)\n[uuid_v4(), uuid_v7()]";
+        let first = run(source).unwrap().to_string();
+        let second = run(source).unwrap().to_string();
+        assert_ne!(first, second);
+
+        let mut interp = Interpreter::new();
+        interp.seed_random(42);
+        let program = parse("uuid_v4()").unwrap();
+        let from_api = interp.interpret(&program).unwrap().to_string();
+        assert!(!first.contains(&from_api), "{} repeated in {}", from_api, first);
+    }
+
+    #[test]
+    fn test_random_distributions_stay_in_bounds() {
+        let result = run(r#"
+seed_random(7)
+ken lo = 10
+ken hi = -10
+ken seen = {}
+fer i in 0..2000 {
+    ken n = random_int(1, 3)
+    seen[n] = aye
+    ken f = random()
+    gin f < lo { lo = f }
+    gin f > hi { hi = f }
+}
+[len(seen), seen[1] an seen[2] an seen[3], lo >= 0.0, hi < 1.0, random_int(5, 5)]
+"#)
+        .unwrap();
+        assert_eq!(result.to_string(), "[3, aye, aye, aye, 5]");
+        assert_eq!(run("random_choice([])").unwrap(), Value::Nil);
+        assert!(run("random_int(3, 1)").is_err());
+        assert!(run("seed_random(\"braw\")").is_err());
+    }
+
     #[test]
     fn test_the_noo() {
         let result = run("the_noo()").unwrap();
@@ -17311,6 +22398,32 @@ is_a(foo, "function")
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_split_lines_handles_crlf_an_a_trailing_newline() {
+        assert_eq!(
+            run(r#"join(split_lines("a\r\nb\nc\r\n"), "|")"#).unwrap(),
+            Value::String("a|b|c".to_string())
+        );
+        assert_eq!(
+            run(r#"len(split_lines("a\n\nb\n"))"#).unwrap(),
+            Value::Integer(3)
+        );
+        assert_eq!(run(r#"len(split_lines(""))"#).unwrap(), Value::Integer(0));
+        assert!(run("split_lines(42)").is_err());
+    }
+
+    #[test]
+    fn test_join_an_empty_list_an_non_strings() {
+        assert_eq!(
+            run(r#"join([], ", ")"#).unwrap(),
+            Value::String(String::new())
+        );
+        assert_eq!(
+            run(r#"join([1, 2.5, naething], "-")"#).unwrap(),
+            Value::String("1-2.5-naething".to_string())
+        );
+    }
+
     #[test]
     fn test_is_space() {
         let result = run(r#"is_space("   ")"#).unwrap();
@@ -17379,6 +22492,42 @@ is_a(foo, "function")
         assert_eq!(result, Value::Integer(4));
     }
 
+    #[test]
+    fn test_reverse_unique_flatten_leave_input_alone() {
+        let result = run(r#"
+ken xs = [[1, 2], 3, [1, 2], {"a": [1]}, {"a": [1]}, [], 3]
+ken out = [reverse(xs), unique(xs), flatten(xs)]
+[len(xs), xs[0], out]
+"#)
+        .unwrap();
+        assert_eq!(
+            result.to_string(),
+            "[7, [1, 2], [[3, [], {\"a\": [1]}, {\"a\": [1]}, [1, 2], 3, [1, 2]], \
+             [[1, 2], 3, {\"a\": [1]}, []], \
+             [1, 2, 3, 1, 2, {\"a\": [1]}, {\"a\": [1]}, 3]]]"
+        );
+    }
+
+    #[test]
+    fn test_flatten_deep_to_a_depth_or_aw_the_way() {
+        let show = |code: &str| run(code).unwrap().to_string();
+        assert_eq!(show("flatten([[1, [2]], [3]])"), "[1, [2], 3]");
+        assert_eq!(show("flatten_deep([[1, [2, [3]]], 4])"), "[1, 2, 3, 4]");
+        assert_eq!(show("flatten_deep([[1, [2, [3]]], 4], -1)"), "[1, 2, 3, 4]");
+        assert_eq!(
+            show("flatten_deep([[1, [2, [3]]], 4], 2)"),
+            "[1, 2, [3], 4]"
+        );
+        assert_eq!(show("flatten_deep([[1, [2]]], 0)"), "[[1, [2]]]");
+        assert_eq!(
+            show(r#"flatten_deep(["a", [1, naething], aye, [[2.5]]])"#),
+            "[a, 1, naething, aye, 2.5]"
+        );
+
+        let err = run("ken xs = [1]\nshove(xs, xs)\nflatten_deep(xs)").unwrap_err();
+        assert!(err.to_string().contains("contains itsel'"), "{err}");
+    }
+
     #[test]
     fn test_flatten_error_non_list() {
         let result = run(r#"flatten("abc")"#);
@@ -17606,7 +22755,9 @@ len(parts[0])
     #[test]
     fn test_sign_float() {
         let result = run("sign(-3.14)").unwrap();
-        assert_eq!(result, Value::Integer(-1));
+        assert_eq!(result, Value::Float(-1.0));
+        assert_eq!(run("sign(0.0)").unwrap(), Value::Float(0.0));
+        assert_eq!(run("sign(2.5)").unwrap(), Value::Float(1.0));
     }
 
     #[test]
@@ -17621,6 +22772,22 @@ len(parts[0])
         assert_eq!(result, Value::Float(0.0));
     }
 
+    #[test]
+    fn test_clamp_below_within_an_above() {
+        assert_eq!(run("clamp(-3, 0, 10)").unwrap(), Value::Integer(0));
+        assert_eq!(run("clamp(4, 0, 10)").unwrap(), Value::Integer(4));
+        assert_eq!(run("clamp(15, 0, 10)").unwrap(), Value::Integer(10));
+
+        // Any float in the mix returns a float
+        assert_eq!(run("clamp(15, 0, 2.5)").unwrap(), Value::Float(2.5));
+        assert_eq!(run("clamp(1, 0.0, 10)").unwrap(), Value::Float(1.0));
+
+        let err = run("clamp(5, 10, 0)").unwrap_err().to_string();
+        assert!(err.contains("lo no bigger than hi"), "{}", err);
+        assert!(run("clamp(0.5, 1.0, 0.0)").is_err());
+        assert!(run(r#"clamp("a", 0, 1)"#).is_err());
+    }
+
     #[test]
     fn test_lerp() {
         let result = run("lerp(0.0, 10.0, 0.5)").unwrap();
@@ -17884,6 +23051,52 @@ len(parts[0])
         assert!(ts > 0);
     }
 
+    #[test]
+    fn test_now_is_monotonic_and_sleep_pauses() {
+        let result = run("ken a = now()\nsleep(5)\nken b = now()\n[a, b, now_ms()]").unwrap();
+        let Value::List(items) = result else {
+            panic!("Expected list");
+        };
+        let items = items.borrow();
+        let (Value::Float(a), Value::Float(b)) = (&items[0], &items[1]) else {
+            panic!("now() should gie floats");
+        };
+        assert!(*a > 1.0e9);
+        assert!(b >= a);
+        assert!(b - a >= 0.004, "sleep(5) barely paused: {}", b - a);
+        assert!(matches!(items[2], Value::Integer(ms) if ms >= (*a * 1000.0) as i64));
+        assert!(run("sleep(-1)").is_err());
+    }
+
+    #[test]
+    fn test_format_time() {
+        let result = run(r#"format_time(0, "%Y-%m-%d %H:%M:%S")"#).unwrap();
+        assert_eq!(result.as_string(), Some("1970-01-01 00:00:00"));
+        let result = run(r#"format_time(86400.5, "%d %b %H:%M:%S%.3f")"#).unwrap();
+        assert_eq!(result.as_string(), Some("02 Jan 00:00:00.500"));
+        assert!(run(r#"format_time(0, "%Q")"#).is_err());
+        assert!(run(r#"format_time("noo", "%Y")"#).is_err());
+    }
+
+    #[test]
+    fn test_shaw_writes_tagged_dumps_and_stops_at_cycles() {
+        let program = parse(
+            r#"
+ken a = [1]
+shove(a, a)
+shaw(a)
+shaw_pretty({"n": 1})
+"#,
+        )
+        .unwrap();
+        let mut interp = Interpreter::new();
+        interp.interpret(&program).unwrap();
+        assert_eq!(
+            interp.get_output(),
+            ["List[2]: [Int:1, …]", "Dict[1]: {\n  Str:\"n\": Int:1\n}"]
+        );
+    }
+
     #[test]
     fn test_braw_time() {
         let result = run("braw_time()").unwrap();
@@ -18116,6 +23329,381 @@ d["a"]
         assert_eq!(result, Value::Float(0.0));
     }
 
+    #[test]
+    fn test_modulo_mixed_int_and_float() {
+        assert_eq!(run("7.5 % 2").unwrap(), Value::Float(1.5));
+        assert_eq!(run("7 % 2.5").unwrap(), Value::Float(2.0));
+    }
+
+    #[test]
+    fn test_power_operator() {
+        assert_eq!(run("2 ** 10").unwrap(), Value::Integer(1024));
+        assert_eq!(run("2 ** -1").unwrap(), Value::Float(0.5));
+        assert_eq!(run("9.0 ** 0.5").unwrap(), Value::Float(3.0));
+        // Right-associative, and tighter than unary minus
+        assert_eq!(run("2 ** 3 ** 2").unwrap(), Value::Integer(512));
+        assert_eq!(run("-2 ** 2").unwrap(), Value::Integer(-4));
+        assert_eq!(run("2 * 3 ** 2").unwrap(), Value::Integer(18));
+        assert!(matches!(
+            run("2 ** 64"),
+            Err(HaversError::IntegerOverflow { .. })
+        ));
+        assert!(run(r#""a" ** 2"#).is_err());
+    }
+
+    #[test]
+    fn test_bitwise_operators() {
+        assert_eq!(run("6 & 3").unwrap(), Value::Integer(2));
+        assert_eq!(run("6 | 3").unwrap(), Value::Integer(7));
+        assert_eq!(run("6 ^ 3").unwrap(), Value::Integer(5));
+        assert_eq!(run("1 << 4").unwrap(), Value::Integer(16));
+        assert_eq!(run("-16 >> 2").unwrap(), Value::Integer(-4));
+        // Bitwise binds tighter than comparisons, shifts looser than +
+        assert_eq!(run("6 & 3 == 2").unwrap(), Value::Bool(true));
+        assert_eq!(run("1 << 4 == 16").unwrap(), Value::Bool(true));
+        assert_eq!(run("1 << 2 + 1").unwrap(), Value::Integer(8));
+        assert_eq!(run("1 | 2 ^ 3 & 1").unwrap(), Value::Integer(3));
+        // A leading | still starts a lambda
+        assert_eq!(run("ken f = |x| x | 1\nf(4)").unwrap(), Value::Integer(5));
+        assert!(matches!(
+            run("1 << 64"),
+            Err(HaversError::InvalidNumberOperation { .. })
+        ));
+        assert!(matches!(
+            run("1.5 & 1"),
+            Err(HaversError::TypeError { .. })
+        ));
+        assert!(matches!(
+            run(r#""a" | 1"#),
+            Err(HaversError::TypeError { .. })
+        ));
+    }
+
+    #[test]
+    fn test_closures_capture_loop_bindings_per_iteration() {
+        // Each fer iteration is its own binding
+        assert_eq!(
+            run(r#"
+ken fns = []
+fer i in 0..3 {
+    shove(fns, || i)
+}
+[fns[0](), fns[1](), fns[2](), i]
+"#)
+            .unwrap()
+            .to_string(),
+            "[0, 1, 2, 2]"
+        );
+        // ...but captures are still live references, not copies
+        assert_eq!(
+            run(r#"
+ken count = 0
+ken bump = || count = count + 1
+ken peek = || count
+bump()
+bump()
+count = count + 10
+peek()
+"#)
+            .unwrap(),
+            Value::Integer(12)
+        );
+    }
+
+    #[test]
+    fn test_float_display_matches_json_and_fmt_float() {
+        assert_eq!(
+            run(r#"
+ken x = 0.1 + 0.2
+[tae_string(x), json_stringify([x, 5.0, 5, 1e16]), fmt_float(x, 2), fmt_float(2, 3)]
+"#)
+            .unwrap()
+            .to_string(),
+            "[0.30000000000000004, [0.30000000000000004, 5.0, 5, 1e16], 0.30, 2.000]"
+        );
+        assert_eq!(
+            run(r#"f"{2.5 * 2} {10.0 / 4} {fmt_float(-1.005, 0)}""#).unwrap(),
+            Value::String("5.0 2.5 -1".to_string())
+        );
+        assert!(run("fmt_float(1.5, -1)").is_err());
+        assert!(run("fmt_float(1.5, 101)").is_err());
+        assert!(run(r#"fmt_float("1.5", 2)"#).is_err());
+    }
+
+    #[test]
+    fn test_generators_yield_lazily_with_gie_back() {
+        // A range generator, driven by fer
+        assert_eq!(
+            run(r#"
+dae range_by(start, stop, step) {
+    ken i = start
+    whiles i < stop {
+        gie_back i
+        i = i + step
+    }
+}
+ken seen = []
+fer x in range_by(0, 10, 3) {
+    shove(seen, x)
+}
+seen
+"#)
+            .unwrap()
+            .to_string(),
+            "[0, 3, 6, 9]"
+        );
+        // Nothing runs until the first item is asked for, brak leaves it paused,
+        // and a finished generator yields nothing more
+        assert_eq!(
+            run(r#"
+ken log = []
+dae naturals() {
+    shove(log, "started")
+    ken n = 0
+    whiles aye {
+        n = n + 1
+        gin n % 2 == 0 {
+            haud
+        }
+        gie_back n
+    }
+}
+ken gen = naturals()
+ken before = len(log)
+ken first = []
+fer x in gen {
+    shove(first, x)
+    gin len(first) == 3 {
+        brak
+    }
+}
+ken rest = []
+fer x in gen {
+    shove(rest, x)
+    gin len(rest) == 2 {
+        brak
+    }
+}
+[before, log, first, rest]
+"#)
+            .unwrap()
+            .to_string(),
+            "[0, [started], [1, 3, 5], [7, 9]]"
+        );
+        // gie finishes early, generators can drive generators, and methods
+        // can be generators too
+        assert_eq!(
+            run(r#"
+dae up_tae(n) {
+    fer i in 1..100 {
+        gin i > n {
+            gie "ignored"
+        }
+        gie_back i
+    }
+}
+dae squares(src) {
+    fer x in src {
+        gie_back x * x
+    }
+}
+kin Bag {
+    dae init(items) {
+        masel.items = items
+    }
+    dae each() {
+        fer item in masel.items {
+            gie_back item
+        }
+    }
+}
+ken gen = squares(up_tae(4))
+ken out = []
+fer x in gen {
+    shove(out, x)
+}
+fer x in gen {
+    shove(out, "again")
+}
+fer x in Bag(["a", "b"]).each() {
+    shove(out, x)
+}
+out
+"#)
+            .unwrap()
+            .to_string(),
+            "[1, 4, 9, 16, a, b]"
+        );
+    }
+
+    #[test]
+    fn test_generator_errors() {
+        // gie_back outside a generator body
+        assert!(matches!(
+            run("gie_back 1"),
+            Err(HaversError::TypeError { .. })
+        ));
+        let err = run(r#"
+dae bad() {
+    hae_a_bash {
+        gie_back 1
+    } gin_it_gangs_wrang e {
+        hurl e
+    }
+}
+fer x in bad() {}
+"#)
+        .unwrap_err();
+        assert!(err.to_string().contains("gie_back"), "{err}");
+        // An error in the body reaches the fer loop and finishes the generator
+        assert_eq!(
+            run(r#"
+dae flaky() {
+    gie_back 1
+    hurl "oops"
+    gie_back 2
+}
+ken gen = flaky()
+ken out = []
+hae_a_bash {
+    fer x in gen {
+        shove(out, x)
+    }
+} gin_it_gangs_wrang e {
+    shove(out, "caught")
+}
+fer x in gen {
+    shove(out, x)
+}
+out
+"#)
+            .unwrap()
+            .to_string(),
+            "[1, caught]"
+        );
+        // A generator cannot resume itself
+        assert!(run(r#"
+ken gen = naething
+dae selfish() {
+    fer x in gen {
+        gie_back x
+    }
+}
+gen = selfish()
+fer x in gen {}
+"#)
+        .is_err());
+        assert_eq!(
+            run("dae g() { gie_back 1 }\nwhit_kind(g())").unwrap(),
+            Value::String("generator".to_string())
+        );
+    }
+
+    #[test]
+    fn test_tail_call_self_recursion_reuses_frame() {
+        // Deep enough to overflow the Rust stack if each call recursed
+        assert_eq!(
+            run(r#"
+dae countdoon(n) {
+    gin n == 0 {
+        gie "done"
+    }
+    gie countdoon(n - 1)
+}
+countdoon(100000)
+"#)
+            .unwrap(),
+            Value::String("done".to_string())
+        );
+        assert_eq!(
+            run(r#"
+dae sum_tae(n, acc = 0) {
+    gin n == 0 { gie acc }
+    gie sum_tae(n - 1, acc + n)
+}
+sum_tae(100000)
+"#)
+            .unwrap(),
+            Value::Integer(5000050000)
+        );
+        // Each pass gets its own frame, so closures keep their own n
+        assert_eq!(
+            run(r#"
+ken fns = []
+dae collect(n) {
+    gin n == 0 { gie fns }
+    shove(fns, || n)
+    gie collect(n - 1)
+}
+collect(3)
+[fns[0](), fns[1](), fns[2]()]
+"#)
+            .unwrap()
+            .to_string(),
+            "[3, 2, 1]"
+        );
+        // A gie inside hae_a_bash is not a tail call - the catch still sees the error
+        assert_eq!(
+            run(r#"
+dae risky(n) {
+    gin n == 0 { hurl "boom" }
+    hae_a_bash {
+        gie risky(n - 1)
+    } gin_it_gangs_wrang e {
+        gie "caught at " + tae_string(n)
+    }
+}
+risky(2)
+"#)
+            .unwrap(),
+            Value::String("caught at 1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_hex_helpers() {
+        assert_eq!(
+            run(r#"hex_encode(bytes_from_string("Och!"))"#).unwrap(),
+            Value::String("4f636821".to_string())
+        );
+        assert_eq!(
+            run(
+                r#"
+ken b = bytes(4)
+bytes_set(b, 0, 0)
+bytes_set(b, 1, 127)
+bytes_set(b, 2, 128)
+bytes_set(b, 3, 255)
+ken back = hex_decode(hex_encode(b))
+[hex_encode(back), bytes_len(back), bytes_get(back, 3)]
+"#
+            )
+            .unwrap()
+            .to_string(),
+            "[007f80ff, 4, 255]"
+        );
+        assert_eq!(
+            run(r#"hex_encode(hex_decode("DEADbeef"))"#).unwrap(),
+            Value::String("deadbeef".to_string())
+        );
+        assert!(run(r#"hex_decode("abc")"#).is_err());
+        assert!(run(r#"hex_decode("zz")"#).is_err());
+        assert!(run(r#"hex_encode("abc")"#).is_err());
+
+        assert_eq!(
+            run(r#"hexdump(bytes_from_string("Braw bricht nicht!\n\t"))"#).unwrap(),
+            Value::String(
+                "00000000  42 72 61 77 20 62 72 69  63 68 74 20 6e 69 63 68  |Braw bricht nich|\n\
+                 00000010  74 21 0a 09                                       |t!..|"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            run("hexdump(bytes(0))").unwrap(),
+            Value::String(String::new())
+        );
+    }
+
     #[test]
     fn test_division_by_zero_float() {
         let result = run("5.0 / 0.0");
@@ -18273,6 +23861,28 @@ l[0]
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_tae_base_and_frae_base() {
+        let result =
+            run("[tae_base(255, 16), tae_base(10, 2), tae_base(-255, 16), tae_oct(8), tae_hex(-1), tae_base(0, 36)]")
+                .unwrap();
+        assert_eq!(result.to_string(), "[ff, 1010, -ff, 10, -1, 0]");
+
+        for n in [0, 1, -1, 35, 36, 1_234_567_890, i64::MAX] {
+            let source = format!("frae_base(tae_base({n}, 36), 36)");
+            assert_eq!(run(&source).unwrap(), Value::Integer(n), "{source}");
+        }
+        let min = int_to_base(i64::MIN, 36).unwrap();
+        assert_eq!(int_from_base(&min, 36), Ok(i64::MIN));
+        assert_eq!(run(r#"frae_base("-FF", 16)"#).unwrap(), Value::Integer(-255));
+
+        assert!(run("tae_base(10, 1)").is_err());
+        assert!(run("tae_base(10, 37)").is_err());
+        assert!(run(r#"frae_base("12", 2)"#).is_err());
+        assert!(run(r#"frae_base("", 10)"#).is_err());
+        assert!(run(r#"frae_base("zzzzzzzzzzzzzz", 36)"#).is_err());
+    }
+
     // ==================== Statistics Functions ====================
 
     #[test]
@@ -19265,6 +24875,94 @@ obj["a"]
         assert_eq!(result, Value::Float(8.0));
     }
 
+    #[test]
+    fn test_math_builtins_keep_ints_and_floats_apart() {
+        let kind = |src: &str| run(&format!("whit_kind({})", src)).unwrap().to_string();
+        assert_eq!(kind("abs(-3)"), "integer");
+        assert_eq!(kind("abs(-3.5)"), "float");
+        assert_eq!(kind("pow(2, 10)"), "integer");
+        assert_eq!(kind("pow(2, -1)"), "float");
+        assert_eq!(kind("pow(2.0, 2)"), "float");
+        assert_eq!(kind("sqrt(16)"), "float");
+        assert_eq!(kind("floor(3.7)"), "integer");
+        assert_eq!(kind("fmod(7, 3)"), "float");
+        assert_eq!(run("pow(2, -1)").unwrap(), Value::Float(0.5));
+        assert_eq!(run("fmod(-7.5, 2)").unwrap(), Value::Float(-1.5));
+    }
+
+    #[test]
+    fn test_math_builtin_errors() {
+        assert!(run("pow(10, 40)").is_err());
+        assert!(run("abs(-9223372036854775807 - 1)").is_err());
+        assert!(run("fmod(1, 0)").is_err());
+        assert!(run("fmod(\"a\", 1)").is_err());
+    }
+
+    #[test]
+    fn test_nested_error_carries_backtrace() {
+        let err = run(r#"
+dae inner() {
+    gie 1 / 0
+}
+dae middle() {
+    gie inner()
+}
+dae outer() {
+    gie middle()
+}
+outer()
+"#)
+        .unwrap_err();
+        assert!(matches!(
+            err.root(),
+            HaversError::DivisionByZero { line: 3 }
+        ));
+        assert_eq!(err.line(), Some(3));
+        let trace = err.backtrace().expect("backtrace");
+        let lines: Vec<&str> = trace.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "  at inner (<repl>:6)",
+                "  at middle (<repl>:9)",
+                "  at outer (<repl>:11)"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_backtrace_is_capped_and_caught_errors_leave_none() {
+        let err = run(r#"
+dae doon(n) {
+    gin n == 0 {
+        gie 1 / 0
+    }
+    // Not a tail call, so every frame stays on the stack
+    ken r = doon(n - 1)
+    gie r
+}
+doon(30)
+"#)
+        .unwrap_err();
+        let trace = err.backtrace().expect("backtrace");
+        assert_eq!(trace.lines().count(), MAX_BACKTRACE_FRAMES + 1);
+        assert!(trace.ends_with(&format!("  … {} more frames", 31 - MAX_BACKTRACE_FRAMES)));
+
+        let err = run(r#"
+dae boom() {
+    gie 1 / 0
+}
+hae_a_bash {
+    boom()
+} gin_it_gangs_wrang e {
+    blether e
+}
+ken x = naething + 1
+"#)
+        .unwrap_err();
+        assert!(err.backtrace().is_none());
+    }
+
     #[test]
     fn test_gcd_function() {
         let result = run("gcd(48, 18)").unwrap();
@@ -19946,6 +25644,7 @@ soond_steek()
             ca_pem: None,
             cert_pem: None,
             key_pem: None,
+            timeout_ms: DEFAULT_NET_TIMEOUT_MS,
         };
         let _ = build_client_config(&cfg).unwrap();
 
@@ -19970,7 +25669,7 @@ soond_steek()
 
 		    #[test]
 		    fn test_range_to_list_inclusive() {
-		        let items = Interpreter::range_to_list(1, 3, true)
+		        let items = Interpreter::range_to_list(1, 3, 1, true)
 		            .as_list()
 		            .expect("expected list")
 		            .clone();
@@ -1,8 +1,21 @@
 use logos::Logos;
 
-use crate::error::{HaversError, HaversResult};
+use crate::error::{ErrorSpan, HaversError, HaversResult};
 use crate::token::{Token, TokenKind};
 
+/// A comment the lexer stepped over, kept so the formatter can put it back
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comment {
+    /// The comment text, including its `#` or `//` marker
+    pub text: String,
+    pub line: usize,
+    pub column: usize,
+    /// Column of the first thing on the comment's line
+    pub indent: usize,
+    /// True when there's code before the comment on the same line
+    pub trailing: bool,
+}
+
 /// The lexer - turns source code intae tokens
 pub struct Lexer<'source> {
     source: &'source str,
@@ -10,6 +23,9 @@ pub struct Lexer<'source> {
     line: usize,
     column: usize,
     cursor: usize,
+    comments: Vec<Comment>,
+    after_code: bool,
+    line_indent: usize,
 }
 
 impl<'source> Lexer<'source> {
@@ -20,9 +36,39 @@ impl<'source> Lexer<'source> {
             line: 1,
             column: 1,
             cursor: 0,
+            comments: Vec::new(),
+            after_code: false,
+            line_indent: 1,
         }
     }
 
+    /// The comments seen so far, in source order
+    pub fn comments(&self) -> &[Comment] {
+        &self.comments
+    }
+
+    /// Logos skips comments, so the only place they can hide is the gap
+    /// between the last token and the next one. Newlines are tokens, so a
+    /// gap never holds more than one line's worth.
+    fn collect_comment(&mut self, end: usize) {
+        let gap = &self.source[self.cursor..end];
+        let Some(offset) = gap.find(['#', '/']) else {
+            return;
+        };
+        let column = self.column + gap[..offset].chars().count();
+        self.comments.push(Comment {
+            text: gap[offset..].trim_end().to_string(),
+            line: self.line,
+            column,
+            indent: if self.after_code {
+                self.line_indent
+            } else {
+                column
+            },
+            trailing: self.after_code,
+        });
+    }
+
     fn advance_to(&mut self, pos: usize) {
         let slice = &self.source[self.cursor..pos];
         for ch in slice.chars() {
@@ -42,6 +88,7 @@ impl<'source> Lexer<'source> {
 
         while let Some(result) = self.logos.next() {
             let span = self.logos.span();
+            self.collect_comment(span.start);
             self.advance_to(span.start);
             let token_line = self.line;
             let token_column = self.column;
@@ -49,13 +96,24 @@ impl<'source> Lexer<'source> {
 
             match result {
                 Ok(kind) => {
+                    if !self.after_code {
+                        self.line_indent = token_column;
+                    }
+                    self.after_code = kind != TokenKind::Newline;
                     tokens.push(Token::new(kind, lexeme, token_line, token_column));
                 }
                 Err(_) => {
+                    let span = ErrorSpan::new(
+                        token_line,
+                        token_column,
+                        token_line,
+                        token_column + lexeme.chars().count().max(1),
+                    );
                     return Err(HaversError::UnkentToken {
                         lexeme,
                         line: token_line,
                         column: token_column,
+                        span: Some(span),
                     });
                 }
             }
@@ -63,6 +121,8 @@ impl<'source> Lexer<'source> {
             self.advance_to(span.end);
         }
 
+        self.collect_comment(self.source.len());
+
         // Add EOF token
         tokens.push(Token::eof(self.line));
 
@@ -76,6 +136,13 @@ pub fn lex(source: &str) -> HaversResult<Vec<Token>> {
     lexer.tokenize()
 }
 
+/// Lex a string, keeping the comments as well
+pub fn lex_with_comments(source: &str) -> HaversResult<(Vec<Token>, Vec<Comment>)> {
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize()?;
+    Ok((tokens, lexer.comments))
+}
+
 #[cfg(test)]
 #[allow(clippy::approx_constant)]
 mod tests {
@@ -181,14 +248,15 @@ greet(message)
 
     #[test]
     fn test_invalid_token_error() {
-        let err = lex("@").unwrap_err();
+        let err = lex("$").unwrap_err();
         assert!(matches!(
             err,
             HaversError::UnkentToken {
                 lexeme,
                 line: 1,
-                column: 1
-            } if lexeme == "@"
+                column: 1,
+                ..
+            } if lexeme == "$"
         ));
     }
 
@@ -205,14 +273,34 @@ greet(message)
 
     #[test]
     fn test_unicode_columns_count_chars_not_bytes() {
-        let err = lex("\"é\" @").unwrap_err();
+        let err = lex("\"é\" $").unwrap_err();
         assert!(matches!(
             err,
             HaversError::UnkentToken {
                 lexeme,
                 line: 1,
-                column: 5
-            } if lexeme == "@"
+                column: 5,
+                ..
+            } if lexeme == "$"
         ));
     }
+
+    #[test]
+    fn test_comments_are_collected_as_trivia() {
+        let source = "# top\nken x = \"# no\" # note\n    // deep\n";
+        let (tokens, comments) = lex_with_comments(source).unwrap();
+        assert!(tokens
+            .iter()
+            .any(|t| t.kind == TokenKind::String("# no".to_string())));
+        assert_eq!(comments.len(), 3);
+        assert_eq!(comments[0].text, "# top");
+        assert!(!comments[0].trailing);
+        assert_eq!(comments[1].text, "# note");
+        assert_eq!((comments[1].line, comments[1].column), (2, 16));
+        assert_eq!(comments[1].indent, 1);
+        assert!(comments[1].trailing);
+        assert_eq!(comments[2].text, "// deep");
+        assert_eq!((comments[2].line, comments[2].indent), (3, 5));
+        assert!(!comments[2].trailing);
+    }
 }
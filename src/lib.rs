@@ -7,12 +7,15 @@
 
 pub mod ast;
 pub mod audio;
+pub mod canvas;
 pub mod compiler;
 pub mod error;
 pub mod formatter;
 pub mod graphics;
+pub mod infer;
 pub mod interpreter;
 pub mod lexer;
+pub mod lint;
 pub mod logging;
 pub mod parser;
 pub mod token;
@@ -28,9 +31,9 @@ pub mod wasm_runner;
 pub mod llvm;
 
 // Re-export commonly used types
-pub use error::{HaversError, HaversResult};
+pub use error::{ErrorKind, HaversError, HaversResult};
 pub use interpreter::Interpreter;
-pub use parser::parse;
+pub use parser::{parse, parse_all};
 pub use value::Value;
 
 // LLVM compiler re-export
@@ -66,15 +69,48 @@ pub fn run_with_output(source: &str) -> HaversResult<(Value, Vec<String>)> {
     let program = parse(source)?;
     let mut interpreter = Interpreter::new();
     let result = interpreter.interpret(&program)?;
-    let output = interpreter.get_output().to_vec();
+    let output = interpreter.take_output();
     Ok((result, output))
 }
 
+/// Everything a run produced, for embedders that want more than the value
+#[derive(Debug, Clone)]
+pub struct RunReport {
+    /// The value of the last expression
+    pub value: Value,
+    /// The value's type, as `whit_kind` names it
+    pub value_kind: String,
+    /// Every line printed with `blether`
+    pub output: Vec<String>,
+    /// How many top-level statements the program has
+    pub stmt_count: usize,
+}
+
+/// Run mdhavers source code and report the result, its kind, and the output
+pub fn run_structured(source: &str) -> HaversResult<RunReport> {
+    let program = parse(source)?;
+    let mut interpreter = Interpreter::new();
+    let value = interpreter.interpret(&program)?;
+    Ok(RunReport {
+        value_kind: value.kind_name(),
+        value,
+        output: interpreter.take_output(),
+        stmt_count: program.statements.len(),
+    })
+}
+
 /// Compile mdhavers source code to JavaScript
 pub fn compile_to_js(source: &str) -> HaversResult<String> {
     compiler::compile(source)
 }
 
+/// Compile mdhavers source code to JavaScript plus a V3 source map (JSON).
+/// The map names its source `input.braw`; use
+/// `compiler::compile_with_source_map` to pick a different name.
+pub fn compile_to_js_with_map(source: &str) -> HaversResult<(String, String)> {
+    compiler::compile_with_source_map(source, "input.braw")
+}
+
 /// Compile mdhavers source code to WebAssembly Text format
 pub fn compile_to_wat(source: &str) -> HaversResult<String> {
     wasm_compiler::compile_to_wat(source)
@@ -93,6 +129,40 @@ pub fn format_source(source: &str) -> HaversResult<String> {
     formatter::format_source(source)
 }
 
+/// The crate version, straight from Cargo.toml
+pub fn version() -> &'static str {
+    env!("CARGO_PKG_VERSION")
+}
+
+/// What this build of mdhavers was built with
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct BuildInfo {
+    pub version: &'static str,
+    /// Built with the `llvm` feature (native compilation)
+    pub llvm: bool,
+    /// Built with the `wasm_runner` feature
+    pub wasm_runner: bool,
+    /// The target triple, e.g. `x86_64-unknown-linux-gnu`
+    pub target: &'static str,
+}
+
+impl BuildInfo {
+    /// The build info as a JSON object
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("build info is aye valid JSON")
+    }
+}
+
+/// Report the version, the optional backends compiled in, and the target
+pub fn build_info() -> BuildInfo {
+    BuildInfo {
+        version: version(),
+        llvm: cfg!(feature = "llvm"),
+        wasm_runner: cfg!(feature = "wasm_runner"),
+        target: env!("MDHAVERS_TARGET"),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -150,6 +220,28 @@ mod tests {
         assert!(output.is_empty());
     }
 
+    #[test]
+    fn test_run_structured_reports_everything() {
+        let report = run_structured(
+            r#"
+            blether "Hello"
+            blether "World"
+            [1, 2]
+        "#,
+        )
+        .unwrap();
+        assert_eq!(
+            report.value,
+            Value::List(std::rc::Rc::new(std::cell::RefCell::new(vec![
+                Value::Integer(1),
+                Value::Integer(2)
+            ])))
+        );
+        assert_eq!(report.value_kind, "list");
+        assert_eq!(report.output, vec!["Hello", "World"]);
+        assert_eq!(report.stmt_count, 3);
+    }
+
     #[test]
     fn test_compile_to_js_simple() {
         let js = compile_to_js("ken x = 42").unwrap();
@@ -170,6 +262,14 @@ mod tests {
         assert!(js.contains("function greet"));
     }
 
+    #[test]
+    fn test_compile_to_js_with_map() {
+        let (js, map) = compile_to_js_with_map("ken x = 42").unwrap();
+        assert_eq!(js, compile_to_js("ken x = 42").unwrap());
+        assert!(map.contains("\"version\":3"));
+        assert!(map.contains("input.braw"));
+    }
+
     #[test]
     fn test_compile_to_js_error() {
         // Invalid syntax
@@ -246,6 +346,24 @@ mod tests {
         assert_eq!(result, Value::String("Hello World".to_string()));
     }
 
+    #[test]
+    fn test_version_and_build_info_match_the_build() {
+        assert_eq!(version(), env!("CARGO_PKG_VERSION"));
+        let manifest = include_str!("../Cargo.toml");
+        assert!(manifest.contains(&format!("version = \"{}\"", version())));
+
+        let info = build_info();
+        assert_eq!(info.version, version());
+        assert_eq!(info.llvm, cfg!(feature = "llvm"));
+        assert_eq!(info.wasm_runner, cfg!(feature = "wasm_runner"));
+        assert!(!info.target.is_empty());
+
+        let json: serde_json::Value = serde_json::from_str(&info.to_json()).unwrap();
+        assert_eq!(json["version"], version());
+        assert_eq!(json["llvm"], cfg!(feature = "llvm"));
+        assert_eq!(json["target"], info.target);
+    }
+
     #[cfg(feature = "llvm")]
     #[test]
     fn test_compile_to_llvm_ir_smoke() {
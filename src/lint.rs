@@ -0,0 +1,830 @@
+//! Static warnings for a parsed program
+//!
+//! Nothing here stops a program from running - these are the small nudges
+//! `mdhavers check` and the LSP show alongside real errors.
+
+use std::collections::HashMap;
+
+use crate::ast::*;
+use crate::infer::{infer_expr_type, VarType};
+
+/// A warning tied to a stretch of the source. Lines and columns count
+/// from 1, and the end is just past the last character.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+    pub message: String,
+}
+
+/// Lint a parsed program: calls to a `@deprecated` dae or kin, local
+/// `ken` variables that are never read, and statements that come after a
+/// `gie`, `brak`, `haud` or `hurl` in the same block. `source` is the text
+/// the program was parsed from, used to work out where each warning ends.
+///
+/// Top-level `ken`s are never flagged - another file can `fetch` them, and
+/// the prelude's own names live there too.
+pub fn check_program(program: &Program, source: &str) -> Vec<Warning> {
+    Linter::run(program, source, None).warnings
+}
+
+/// Flag operations that are plainly the wrong type: adding a string to a
+/// number, indexing a number, or calling something that is not a function.
+/// These only fire when the types are certain - from literals, or from a
+/// variable bound exactly once (by a `ken` of a known type) in the whole
+/// program - and are warnings, not errors, since coercion might still
+/// make the program work.
+pub fn check_types(program: &Program, source: &str) -> Vec<Warning> {
+    let census = Linter::run(program, source, None).bindings;
+    let types = census
+        .into_iter()
+        .filter_map(|(name, bound)| match bound[..] {
+            [ty] if ty != VarType::Unknown => Some((name, ty)),
+            _ => None,
+        })
+        .collect();
+    Linter::run(program, source, Some(types)).type_warnings
+}
+
+/// Gather deprecated function and class names (methods are reached through
+/// an object, so they cannot be matched by name alone)
+fn collect_deprecated(statements: &[Stmt], found: &mut HashMap<String, String>) {
+    for stmt in statements {
+        match stmt {
+            Stmt::Function {
+                name,
+                body,
+                attributes,
+                ..
+            } => {
+                if let Some(reason) = Attribute::deprecation(attributes) {
+                    found.insert(name.clone(), reason);
+                }
+                collect_deprecated(body, found);
+            }
+            Stmt::Class {
+                name, attributes, ..
+            } => {
+                if let Some(reason) = Attribute::deprecation(attributes) {
+                    found.insert(name.clone(), reason);
+                }
+            }
+            Stmt::Block { statements, .. } => collect_deprecated(statements, found),
+            _ => {}
+        }
+    }
+}
+
+/// A name bound inside a function or block
+struct Local {
+    name: String,
+    /// Where a `ken` declared it; parameters and loop variables have none
+    /// and are never flagged
+    declared: Option<Span>,
+    read: bool,
+}
+
+struct Linter<'a> {
+    lines: Vec<&'a str>,
+    deprecated: HashMap<String, String>,
+    warnings: Vec<Warning>,
+    /// Set inside an f-string, whose inner spans count from the string itself
+    fstring_span: Option<Span>,
+    /// Locals in scope, innermost last (empty at the top level)
+    scopes: Vec<Vec<Local>>,
+    /// Every name bound anywhere in the program, with the type of each binding
+    bindings: HashMap<String, Vec<VarType>>,
+    /// Known variable types; the type checks only run when this is set
+    types: Option<HashMap<String, VarType>>,
+    type_warnings: Vec<Warning>,
+}
+
+impl<'a> Linter<'a> {
+    fn run(program: &Program, source: &'a str, types: Option<HashMap<String, VarType>>) -> Self {
+        let mut deprecated = HashMap::new();
+        collect_deprecated(&program.statements, &mut deprecated);
+
+        let mut linter = Linter {
+            lines: source.lines().collect(),
+            deprecated,
+            warnings: Vec::new(),
+            fstring_span: None,
+            scopes: Vec::new(),
+            bindings: HashMap::new(),
+            types,
+            type_warnings: Vec::new(),
+        };
+        linter.stmts(&program.statements);
+        for found in [&mut linter.warnings, &mut linter.type_warnings] {
+            found.sort_by_key(|warning| (warning.line, warning.column));
+        }
+        linter
+    }
+
+    fn bind(&mut self, name: &str, ty: VarType) {
+        self.bindings.entry(name.to_string()).or_default().push(ty);
+    }
+
+    fn type_of(&self, expr: &Expr) -> VarType {
+        match &self.types {
+            Some(types) => infer_expr_type(expr, &|name| {
+                types.get(name).copied().unwrap_or(VarType::Unknown)
+            }),
+            None => VarType::Unknown,
+        }
+    }
+
+    fn warn_type(&mut self, start: Span, end_column: usize, message: String) {
+        let start = self.fstring_span.unwrap_or(start);
+        self.type_warnings.push(Warning {
+            line: start.line,
+            column: start.column,
+            end_line: start.line,
+            end_column: end_column.max(start.column + 1),
+            message,
+        });
+    }
+
+    /// Warn if `object` is plainly a number or a bool
+    fn check_indexable(&mut self, object: &Expr) {
+        let ty = self.type_of(object);
+        if matches!(ty, VarType::Int | VarType::Float | VarType::Bool) {
+            let span = object.span();
+            let end_column = self.word_end(span.line, span.column);
+            self.warn_type(span, end_column, format!("Cannae index a {}", ty.name()));
+        }
+    }
+
+    /// A call by name: a read of the name, and a warning if it's deprecated
+    fn flag(&mut self, name: &str, span: Span) {
+        self.read(name);
+        let span = self.fstring_span.unwrap_or(span);
+        if let Some(reason) = self.deprecated.get(name) {
+            let message = if reason.is_empty() {
+                format!("'{}' is deprecated", name)
+            } else {
+                format!("'{}' is deprecated: {}", name, reason)
+            };
+            let end_column = self.word_end(span.line, span.column);
+            self.warn(span, span.line, end_column, message);
+        }
+    }
+
+    fn warn(&mut self, start: Span, end_line: usize, end_column: usize, message: String) {
+        self.warnings.push(Warning {
+            line: start.line,
+            column: start.column,
+            end_line,
+            end_column,
+            message,
+        });
+    }
+
+    fn line_text(&self, line: usize) -> &str {
+        self.lines
+            .get(line.saturating_sub(1))
+            .copied()
+            .unwrap_or("")
+    }
+
+    /// The column just past the name starting at `column` (at least one on)
+    fn word_end(&self, line: usize, column: usize) -> usize {
+        let len = self
+            .line_text(line)
+            .chars()
+            .skip(column.saturating_sub(1))
+            .take_while(|c| c.is_alphanumeric() || *c == '_')
+            .count();
+        column + len.max(1)
+    }
+
+    fn scoped(&mut self, names: &[&str], body: impl FnOnce(&mut Self)) {
+        self.scopes.push(Vec::new());
+        for name in names {
+            self.bind(name, VarType::Unknown);
+            self.declare(name, None);
+        }
+        body(self);
+        self.end_scope();
+    }
+
+    fn declare(&mut self, name: &str, declared: Option<Span>) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.push(Local {
+                name: name.to_string(),
+                declared,
+                read: false,
+            });
+        }
+    }
+
+    fn read(&mut self, name: &str) {
+        let local = self
+            .scopes
+            .iter_mut()
+            .rev()
+            .flat_map(|scope| scope.iter_mut().rev())
+            .find(|local| local.name == name);
+        if let Some(local) = local {
+            local.read = true;
+        }
+    }
+
+    fn end_scope(&mut self) {
+        for local in self.scopes.pop().unwrap_or_default() {
+            let Some(span) = local.declared else {
+                continue;
+            };
+            if local.read || local.name.starts_with('_') {
+                continue;
+            }
+            // Point at the name itself, not the `ken` before it
+            let text = self.line_text(span.line);
+            let skipped = span.column.saturating_sub(1) + "ken".len();
+            let gap = text
+                .chars()
+                .skip(skipped)
+                .take_while(|c| c.is_whitespace())
+                .count();
+            let start = Span::new(span.line, skipped + gap + 1);
+            let end_column = start.column + local.name.chars().count();
+            let message = format!("'{}' is declared but never used", local.name);
+            self.warn(start, span.line, end_column, message);
+        }
+    }
+
+    fn stmts(&mut self, statements: &[Stmt]) {
+        for stmt in statements {
+            self.stmt(stmt);
+        }
+
+        let exit = statements.iter().position(|stmt| {
+            matches!(
+                stmt,
+                Stmt::Return { .. }
+                    | Stmt::Break { .. }
+                    | Stmt::Continue { .. }
+                    | Stmt::Hurl { .. }
+            )
+        });
+        if let Some(exit) = exit {
+            if let (Some(first), Some(last)) = (statements.get(exit + 1), statements.last()) {
+                let keyword = match statements[exit] {
+                    Stmt::Return { .. } => "gie",
+                    Stmt::Break { .. } => "brak",
+                    Stmt::Continue { .. } => "haud",
+                    _ => "hurl",
+                };
+                let end_line = last.span().line;
+                let end_column = self.line_text(end_line).chars().count() + 1;
+                let message = format!("Unreachable code efter `{}`", keyword);
+                self.warn(first.span(), end_line, end_column, message);
+            }
+        }
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::VarDecl {
+                name,
+                initializer,
+                span,
+            } => {
+                if let Some(init) = initializer {
+                    self.expr(init);
+                }
+                // Judged from the initialiser alone, so the census does not
+                // depend on the order names were bound in
+                let ty = match initializer {
+                    Some(init) => infer_expr_type(init, &|_| VarType::Unknown),
+                    None => VarType::Unknown,
+                };
+                self.bind(name, ty);
+                self.declare(name, Some(*span));
+            }
+            Stmt::Destructure {
+                patterns, value, ..
+            } => {
+                self.expr(value);
+                for pattern in patterns {
+                    if let DestructPattern::Variable(name) | DestructPattern::Rest(name) = pattern {
+                        self.bind(name, VarType::Unknown);
+                        self.declare(name, None);
+                    }
+                }
+            }
+            Stmt::Expression { expr, .. }
+            | Stmt::Print { value: expr, .. }
+            | Stmt::Hurl { message: expr, .. }
+            | Stmt::Yield { value: expr, .. } => self.expr(expr),
+            Stmt::Block { statements, .. } => self.scoped(&[], |l| l.stmts(statements)),
+            Stmt::If {
+                condition,
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                self.expr(condition);
+                self.stmt(then_branch);
+                if let Some(else_branch) = else_branch {
+                    self.stmt(else_branch);
+                }
+            }
+            Stmt::IfLet {
+                value,
+                then_branch,
+                else_branch,
+
+                name,
+                ..
+            } => {
+                self.expr(value);
+                self.scoped(&[name.as_str()], |l| l.stmt(then_branch));
+                if let Some(else_branch) = else_branch {
+                    self.stmt(else_branch);
+                }
+            }
+            Stmt::While {
+                condition, body, ..
+            } => {
+                self.expr(condition);
+                self.stmt(body);
+            }
+            Stmt::For {
+                variable,
+                value_variable,
+                iterable,
+                body,
+                ..
+            } => {
+                self.expr(iterable);
+                let mut names = vec![variable.as_str()];
+                names.extend(value_variable.as_deref());
+                self.scoped(&names, |l| l.stmt(body));
+            }
+            Stmt::Function {
+                name, params, body, ..
+            } => {
+                self.bind(name, VarType::Unknown);
+                for param in params {
+                    if let Some(default) = &param.default {
+                        self.expr(default);
+                    }
+                }
+                let names: Vec<&str> = params.iter().map(|p| p.name.as_str()).collect();
+                self.scoped(&names, |l| l.stmts(body));
+            }
+            Stmt::Class { name, methods, .. } => {
+                self.bind(name, VarType::Unknown);
+                self.stmts(methods);
+            }
+            Stmt::Struct { name, .. }
+            | Stmt::Import {
+                alias: Some(name), ..
+            } => self.bind(name, VarType::Unknown),
+            Stmt::Import {
+                only: Some(names), ..
+            } => {
+                for name in names {
+                    self.bind(name, VarType::Unknown);
+                }
+            }
+            Stmt::Return { value, .. } => {
+                if let Some(value) = value {
+                    self.expr(value);
+                }
+            }
+            Stmt::TryCatch {
+                try_block,
+                error_name,
+                catch_block,
+                ..
+            } => {
+                self.stmt(try_block);
+                self.scoped(&[error_name.as_str()], |l| l.stmt(catch_block));
+            }
+            Stmt::Match { value, arms, .. } => {
+                self.expr(value);
+                for arm in arms {
+                    match &arm.pattern {
+                        Pattern::Range { start, end } => {
+                            self.expr(start);
+                            self.expr(end);
+                            self.stmt(&arm.body);
+                        }
+                        Pattern::Identifier(name) => {
+                            self.scoped(&[name.as_str()], |l| l.stmt(&arm.body))
+                        }
+                        Pattern::Literal(_) | Pattern::Wildcard => self.stmt(&arm.body),
+                    }
+                }
+            }
+            Stmt::Assert {
+                condition, message, ..
+            } => {
+                self.expr(condition);
+                if let Some(message) = message {
+                    self.expr(message);
+                }
+            }
+            Stmt::Log {
+                message, extras, ..
+            } => {
+                self.expr(message);
+                for extra in extras {
+                    self.expr(extra);
+                }
+            }
+            Stmt::Break { .. } | Stmt::Continue { .. } | Stmt::Import { .. } => {}
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Call {
+                callee, arguments, ..
+            } => {
+                let ty = self.type_of(callee);
+                if ty != VarType::Unknown {
+                    let span = callee.span();
+                    let end_column = self.word_end(span.line, span.column);
+                    self.warn_type(span, end_column, format!("Cannae call a {}", ty.name()));
+                }
+                if let Expr::Variable { name, span } = callee.as_ref() {
+                    self.flag(name, *span);
+                } else {
+                    self.expr(callee);
+                }
+                for arg in arguments {
+                    self.expr(arg);
+                }
+            }
+            Expr::Pipe { left, right, .. } => {
+                self.expr(left);
+                // `x |> auld_fn` calls auld_fn just the same
+                if let Expr::Variable { name, span } = right.as_ref() {
+                    self.flag(name, *span);
+                } else {
+                    self.expr(right);
+                }
+            }
+            Expr::Variable { name, .. } => self.read(name),
+            Expr::Literal { .. } | Expr::Masel { .. } => {}
+            Expr::Assign { name, value, .. } => {
+                self.bind(name, VarType::Unknown);
+                self.expr(value);
+            }
+            Expr::Binary {
+                left,
+                operator,
+                right,
+                span,
+            } => {
+                if *operator == BinaryOp::Add {
+                    let (lt, rt) = (self.type_of(left), self.type_of(right));
+                    let number = |ty| matches!(ty, VarType::Int | VarType::Float | VarType::Bool);
+                    if (lt == VarType::String && number(rt))
+                        || (number(lt) && rt == VarType::String)
+                    {
+                        let message = format!(
+                            "Addin' a {} an' a {} sticks them thegither as text",
+                            lt.name(),
+                            rt.name()
+                        );
+                        self.warn_type(*span, span.column + 1, message);
+                    }
+                }
+                self.expr(left);
+                self.expr(right);
+            }
+            Expr::Logical { left, right, .. } => {
+                self.expr(left);
+                self.expr(right);
+            }
+            Expr::Unary { operand: inner, .. }
+            | Expr::Get { object: inner, .. }
+            | Expr::Grouping { expr: inner, .. }
+            | Expr::Input { prompt: inner, .. }
+            | Expr::Spread { expr: inner, .. } => self.expr(inner),
+            Expr::Lambda { params, body, .. } => {
+                let names: Vec<&str> = params.iter().map(String::as_str).collect();
+                self.scoped(&names, |l| l.expr(body));
+            }
+            Expr::Set { object, value, .. } => {
+                self.expr(object);
+                self.expr(value);
+            }
+            Expr::Index { object, index, .. } => {
+                self.check_indexable(object);
+                self.expr(object);
+                self.expr(index);
+            }
+            Expr::IndexSet {
+                object,
+                index,
+                value,
+                ..
+            } => {
+                self.check_indexable(object);
+                self.expr(object);
+                self.expr(index);
+                self.expr(value);
+            }
+            Expr::Slice {
+                object,
+                start,
+                end,
+                step,
+                ..
+            } => {
+                self.check_indexable(object);
+                self.expr(object);
+                for part in [start, end, step].into_iter().flatten() {
+                    self.expr(part);
+                }
+            }
+            Expr::List { elements, .. } => {
+                for element in elements {
+                    self.expr(element);
+                }
+            }
+            Expr::ListComp {
+                element, clauses, ..
+            } => self.scoped(&[], |l| {
+                for clause in clauses {
+                    match clause {
+                        CompClause::For { variable, iterable } => {
+                            l.expr(iterable);
+                            l.bind(variable, VarType::Unknown);
+                            l.declare(variable, None);
+                        }
+                        CompClause::If(condition) => l.expr(condition),
+                    }
+                }
+                l.expr(element);
+            }),
+            Expr::Dict { pairs, .. } => {
+                for (key, value) in pairs {
+                    self.expr(key);
+                    self.expr(value);
+                }
+            }
+            Expr::Range {
+                start, end, step, ..
+            } => {
+                self.expr(start);
+                self.expr(end);
+                if let Some(step) = step {
+                    self.expr(step);
+                }
+            }
+            Expr::BlockExpr { statements, .. } => self.scoped(&[], |l| l.stmts(statements)),
+            Expr::FString { parts, span } => {
+                let outer = self.fstring_span.replace(*span);
+                for part in parts {
+                    if let FStringPart::Expr(inner) = part {
+                        self.expr(inner);
+                    }
+                }
+                self.fstring_span = outer;
+            }
+            Expr::Ternary {
+                condition,
+                then_expr,
+                else_expr,
+                ..
+            } => {
+                self.expr(condition);
+                self.expr(then_expr);
+                self.expr(else_expr);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+
+    fn warnings(source: &str) -> Vec<Warning> {
+        check_program(&parse(source).unwrap(), source)
+    }
+
+    #[test]
+    fn test_deprecated_calls_warn_once_per_call_site() {
+        let source = r#"@deprecated("use new_fn instead")
+dae old_fn(x) {
+    gie x
+}
+dae new_fn(x) {
+    gie x
+}
+ken a = old_fn(1)
+blether f"{old_fn(2)} {new_fn(3)}"
+"#;
+        let found = warnings(source);
+        assert_eq!(found.len(), 2, "{found:?}");
+        assert_eq!(found[0].line, 8);
+        assert_eq!(found[0].column, 9);
+        assert_eq!(
+            found[0].message,
+            "'old_fn' is deprecated: use new_fn instead"
+        );
+        // Calls inside an f-string point at the string
+        assert_eq!((found[1].line, found[1].column), (9, 9));
+    }
+
+    #[test]
+    fn test_deprecated_classes_pipes_and_bare_attributes() {
+        let source = r#"@deprecated
+kin Auld {}
+@deprecated("nae mair")
+dae shout(x) { gie x }
+ken a = Auld()
+ken b = 1 |> shout
+ken c = [1, 2] |> map(shout)
+"#;
+        let found = warnings(source);
+        let messages: Vec<_> = found.iter().map(|w| w.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            ["'Auld' is deprecated", "'shout' is deprecated: nae mair",]
+        );
+    }
+
+    #[test]
+    fn test_no_warnings_without_deprecations() {
+        assert!(warnings("dae f() { gie 1 }\nf()\n").is_empty());
+    }
+
+    #[test]
+    fn test_unused_local_warns_exactly_once() {
+        let source = r#"dae f(x) {
+    ken used = x + 1
+    ken  unused = 2
+    gie used
+}
+blether f(1)
+"#;
+        let found = warnings(source);
+        assert_eq!(
+            found,
+            vec![Warning {
+                line: 3,
+                column: 10,
+                end_line: 3,
+                end_column: 16,
+                message: "'unused' is declared but never used".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_reads_anywhere_in_scope_count_as_uses() {
+        let source = r#"ken top = 1
+dae f(items) {
+    ken total = 0
+    ken scale = 2
+    ken label = "sum"
+    ken _skipped = 0
+    fer item in items {
+        total = total + item
+    }
+    ken doubled = [n * scale fer n in items]
+    ken show = |n| f"{label}: {n}"
+    gie show(total) + len(doubled)
+}
+"#;
+        assert_eq!(warnings(source), vec![]);
+    }
+
+    #[test]
+    fn test_shadowed_locals_are_tracked_separately() {
+        let source = r#"dae f() {
+    ken x = 1
+    {
+        ken x = 2
+        blether x
+    }
+}
+"#;
+        let messages: Vec<_> = warnings(source)
+            .into_iter()
+            .map(|w| (w.line, w.message))
+            .collect();
+        assert_eq!(
+            messages,
+            [(2, "'x' is declared but never used".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_code_efter_gie_or_brak_is_unreachable() {
+        let source = r#"dae f(x) {
+    gin x > 1 {
+        gie 1
+        blether "never"
+        blether "nor this"
+    }
+    whiles aye {
+        brak
+        blether "stuck"
+    }
+    gie 0
+}
+"#;
+        let found = warnings(source);
+        let spans: Vec<_> = found
+            .iter()
+            .map(|w| {
+                (
+                    w.line,
+                    w.column,
+                    w.end_line,
+                    w.end_column,
+                    w.message.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            spans,
+            [
+                (4, 9, 5, 27, "Unreachable code efter `gie`"),
+                (9, 9, 9, 24, "Unreachable code efter `brak`"),
+            ]
+        );
+    }
+
+    fn type_warnings(source: &str) -> Vec<(usize, usize, usize, String)> {
+        check_types(&parse(source).unwrap(), source)
+            .into_iter()
+            .map(|w| (w.line, w.column, w.end_column, w.message))
+            .collect()
+    }
+
+    #[test]
+    fn test_adding_a_string_tae_a_number_warns() {
+        assert_eq!(
+            type_warnings("blether \"a\" + 1\n"),
+            [(
+                1,
+                13,
+                14,
+                "Addin' a string an' a integer sticks them thegither as text".to_string()
+            )]
+        );
+        assert_eq!(type_warnings("ken n = 2.5\nblether n + \"p\"\n").len(), 1);
+        // No check for plain lint
+        assert!(warnings("blether \"a\" + 1\n").is_empty());
+    }
+
+    #[test]
+    fn test_indexing_a_number_warns() {
+        assert_eq!(
+            type_warnings("ken x = 5\nx[0]\n"),
+            [(2, 1, 2, "Cannae index a integer".to_string())]
+        );
+        assert_eq!(type_warnings("ken x = 5\nx[0] = 1\nx[1:]\n").len(), 2);
+    }
+
+    #[test]
+    fn test_calling_a_literal_warns() {
+        assert_eq!(
+            type_warnings("ken greeting = \"hi\"\ngreeting()\n5()\n"),
+            [
+                (2, 1, 9, "Cannae call a string".to_string()),
+                (3, 1, 2, "Cannae call a integer".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_type_checks_only_fire_when_certain() {
+        let source = r#"ken a = "x" + "y"
+ken b = 1 + 2.5
+ken x = 5
+x = [1, 2]
+blether x[0]
+ken y = 5
+dae f(y) { gie y[0] }
+ken z = 5
+ken z = "now a string"
+blether z[0]
+ken w = b
+blether w[0]
+ken q = mystery()
+blether q + "!"
+"#;
+        assert_eq!(type_warnings(source), vec![]);
+    }
+
+    #[test]
+    fn test_prelude_is_warning_free() {
+        let source = include_str!("../stdlib/prelude.braw");
+        assert_eq!(warnings(source), vec![]);
+        assert_eq!(type_warnings(source), vec![]);
+    }
+}
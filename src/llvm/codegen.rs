@@ -22,6 +22,7 @@ use inkwell::attributes::{Attribute, AttributeLoc};
 use inkwell::basic_block::BasicBlock;
 use inkwell::builder::Builder;
 use inkwell::context::Context;
+use inkwell::debug_info::DILocation;
 use inkwell::module::{Linkage, Module};
 use inkwell::types::BasicMetadataTypeEnum;
 use inkwell::values::{
@@ -31,11 +32,12 @@ use inkwell::AddressSpace;
 use inkwell::IntPredicate;
 
 use crate::ast::{
-    BinaryOp, DestructPattern, Expr, FStringPart, Literal, LogLevel, LogicalOp, MatchArm, Pattern,
-    Program, Span, Stmt, UnaryOp,
+    BinaryOp, CompClause, DestructPattern, Expr, FStringPart, Literal, LogLevel, LogicalOp,
+    MatchArm, Pattern, Program, Span, Stmt, UnaryOp,
 };
 use crate::error::HaversError;
 
+use super::debug_info::DebugInfo;
 use super::types::{MdhTypes, ValueTag};
 
 // Coverage note: llvm-cov counts each `*_or_else(|| ...)` closure as a separate function.
@@ -99,6 +101,7 @@ struct LibcFunctions<'ctx> {
     type_error: FunctionValue<'ctx>,
     type_of: FunctionValue<'ctx>,
     to_string: FunctionValue<'ctx>,
+    shaw: FunctionValue<'ctx>,
     to_int: FunctionValue<'ctx>,
     to_float: FunctionValue<'ctx>,
     native_get: FunctionValue<'ctx>,
@@ -195,6 +198,8 @@ struct LibcFunctions<'ctx> {
     dns_naptr: FunctionValue<'ctx>,
     tls_client_new: FunctionValue<'ctx>,
     tls_connect: FunctionValue<'ctx>,
+    tls_connect_start: FunctionValue<'ctx>,
+    tls_handshake_step: FunctionValue<'ctx>,
     tls_send: FunctionValue<'ctx>,
     tls_recv: FunctionValue<'ctx>,
     tls_close: FunctionValue<'ctx>,
@@ -203,6 +208,7 @@ struct LibcFunctions<'ctx> {
     srtp_create: FunctionValue<'ctx>,
     srtp_protect: FunctionValue<'ctx>,
     srtp_unprotect: FunctionValue<'ctx>,
+    srtp_set_roc: FunctionValue<'ctx>,
     event_loop_new: FunctionValue<'ctx>,
     event_loop_stop: FunctionValue<'ctx>,
     event_watch_read: FunctionValue<'ctx>,
@@ -240,6 +246,10 @@ struct LibcFunctions<'ctx> {
     random: FunctionValue<'ctx>,
     jammy: FunctionValue<'ctx>,
     random_int: FunctionValue<'ctx>,
+    seed_random: FunctionValue<'ctx>,
+    sign: FunctionValue<'ctx>,
+    clamp: FunctionValue<'ctx>,
+    lerp: FunctionValue<'ctx>,
     term_width: FunctionValue<'ctx>,
     term_height: FunctionValue<'ctx>,
     // Dict/Creel runtime functions
@@ -264,6 +274,9 @@ struct LibcFunctions<'ctx> {
     list_dir: FunctionValue<'ctx>,
     make_dir: FunctionValue<'ctx>,
     is_dir: FunctionValue<'ctx>,
+    is_file: FunctionValue<'ctx>,
+    read_dir: FunctionValue<'ctx>,
+    walk_dir: FunctionValue<'ctx>,
     slurp: FunctionValue<'ctx>,
     scrieve: FunctionValue<'ctx>,
     scrieve_append: FunctionValue<'ctx>,
@@ -271,6 +284,7 @@ struct LibcFunctions<'ctx> {
     words: FunctionValue<'ctx>,
     // Environment/system runtime functions
     set_args: FunctionValue<'ctx>,
+    set_function_table: FunctionValue<'ctx>,
     args: FunctionValue<'ctx>,
     cwd: FunctionValue<'ctx>,
     chdir: FunctionValue<'ctx>,
@@ -283,6 +297,7 @@ struct LibcFunctions<'ctx> {
     // Date/time runtime functions
     date_now: FunctionValue<'ctx>,
     date_format: FunctionValue<'ctx>,
+    format_time: FunctionValue<'ctx>,
     date_parse: FunctionValue<'ctx>,
     date_add: FunctionValue<'ctx>,
     date_diff: FunctionValue<'ctx>,
@@ -294,10 +309,37 @@ struct LibcFunctions<'ctx> {
     regex_replace: FunctionValue<'ctx>,
     regex_replace_first: FunctionValue<'ctx>,
     regex_split: FunctionValue<'ctx>,
+    sha256: FunctionValue<'ctx>,
+    sha1: FunctionValue<'ctx>,
+    md5: FunctionValue<'ctx>,
+    sha256_hex: FunctionValue<'ctx>,
+    sha1_hex: FunctionValue<'ctx>,
+    md5_hex: FunctionValue<'ctx>,
+    hmac_sha256: FunctionValue<'ctx>,
+    hmac_sha256_hex: FunctionValue<'ctx>,
+    ct_eq: FunctionValue<'ctx>,
+    random_bytes: FunctionValue<'ctx>,
+    random_hex: FunctionValue<'ctx>,
+    pbkdf2: FunctionValue<'ctx>,
+    hex_encode: FunctionValue<'ctx>,
+    hex_decode: FunctionValue<'ctx>,
+    hexdump: FunctionValue<'ctx>,
+    // Float formatting
+    format_float: FunctionValue<'ctx>,
+    fmt_float: FunctionValue<'ctx>,
+    format: FunctionValue<'ctx>,
     // JSON runtime functions
     json_parse: FunctionValue<'ctx>,
     json_stringify: FunctionValue<'ctx>,
     json_pretty: FunctionValue<'ctx>,
+    json_stringify_opts: FunctionValue<'ctx>,
+    json_query: FunctionValue<'ctx>,
+    // CSV runtime functions
+    csv_parse: FunctionValue<'ctx>,
+    csv_write: FunctionValue<'ctx>,
+    // UUID runtime functions
+    uuid_v4: FunctionValue<'ctx>,
+    uuid_v7: FunctionValue<'ctx>,
     // Misc parity helpers
     is_a: FunctionValue<'ctx>,
     wrang_sort: FunctionValue<'ctx>,
@@ -369,6 +411,8 @@ struct LibcFunctions<'ctx> {
     tak: FunctionValue<'ctx>,
     pair_up: FunctionValue<'ctx>,
     tae_binary: FunctionValue<'ctx>,
+    tae_base: FunctionValue<'ctx>,
+    frae_base: FunctionValue<'ctx>,
     fae_binary: FunctionValue<'ctx>,
     fae_hex: FunctionValue<'ctx>,
     ltrim: FunctionValue<'ctx>,
@@ -392,6 +436,7 @@ struct LibcFunctions<'ctx> {
     chynge: FunctionValue<'ctx>,
     // Testing runtime functions
     assert_fn: FunctionValue<'ctx>,
+    assert_eq: FunctionValue<'ctx>,
     skip: FunctionValue<'ctx>,
     stacktrace: FunctionValue<'ctx>,
     // Exceptions (try/catch/hurl)
@@ -434,6 +479,7 @@ struct LibcFunctions<'ctx> {
     dict_get_default: FunctionValue<'ctx>,
     dict_merge: FunctionValue<'ctx>,
     dict_remove: FunctionValue<'ctx>,
+    dict_pop: FunctionValue<'ctx>,
     dict_invert: FunctionValue<'ctx>,
     fae_pairs: FunctionValue<'ctx>,
     // Range
@@ -454,17 +500,7 @@ struct LibcFunctions<'ctx> {
     ends_with: FunctionValue<'ctx>,
 }
 
-/// Inferred type for optimization
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum VarType {
-    Unknown,
-    Int,
-    Float,
-    String,
-    Bool,
-    List,
-    Dict,
-}
+pub use crate::infer::VarType;
 
 #[derive(Debug)]
 struct ImportBindings<'ctx> {
@@ -558,6 +594,9 @@ pub struct CodeGen<'ctx> {
     /// Counter for generating unique lambda names
     lambda_counter: u32,
 
+    /// Source names and arities of compiled functions, for printing them
+    function_names: Vec<(FunctionValue<'ctx>, String, usize)>,
+
     /// Counter for generating unique pipe temporaries
     pipe_tmp_counter: u32,
     /// Counter for generating unique import prefixes
@@ -578,6 +617,16 @@ pub struct CodeGen<'ctx> {
     /// Source file path for resolving imports
     source_path: Option<PathBuf>,
 
+    /// Trap on integer overflow in +, - and * instead of wrapping
+    checked_arithmetic: bool,
+
+    /// Emit DWARF debug info (set before compile)
+    emit_debug_info: bool,
+    /// Seed fed to __mdh_seed_random at the top of main, if any
+    random_seed: Option<u64>,
+    /// Debug info builder state, present while compiling with -g
+    debug_info: Option<DebugInfo<'ctx>>,
+
     /// Imported modules (to avoid duplicate imports)
     imported_modules: HashSet<PathBuf>,
     /// Import alias names mapped to exported symbol names
@@ -589,7 +638,6 @@ pub struct CodeGen<'ctx> {
 
     /// Format strings for printf
     fmt_int: inkwell::values::GlobalValue<'ctx>,
-    fmt_float: inkwell::values::GlobalValue<'ctx>,
     fmt_string: inkwell::values::GlobalValue<'ctx>,
     fmt_true: inkwell::values::GlobalValue<'ctx>,
     fmt_false: inkwell::values::GlobalValue<'ctx>,
@@ -621,7 +669,6 @@ impl<'ctx> CodeGen<'ctx> {
 
         // Create format strings
         let fmt_int = Self::create_global_string(&module, context, "%lld", "fmt_int");
-        let fmt_float = Self::create_global_string(&module, context, "%g", "fmt_float");
         let fmt_string = Self::create_global_string(&module, context, "%s", "fmt_string");
         let fmt_true = Self::create_global_string(&module, context, "aye", "fmt_true");
         let fmt_false = Self::create_global_string(&module, context, "nae", "fmt_false");
@@ -652,6 +699,7 @@ impl<'ctx> CodeGen<'ctx> {
             try_depth: 0,
             in_user_function: false,
             lambda_counter: 0,
+            function_names: Vec::new(),
             pipe_tmp_counter: 0,
             import_unique_counter: 0,
             classes: HashMap::new(),
@@ -659,12 +707,15 @@ impl<'ctx> CodeGen<'ctx> {
             current_masel: None,
             current_class: None,
             source_path: None,
+            checked_arithmetic: false,
+            emit_debug_info: false,
+            random_seed: None,
+            debug_info: None,
             imported_modules: HashSet::new(),
             import_alias_exports: HashMap::new(),
             import_alias_bindings: HashMap::new(),
             import_alias_functions: HashMap::new(),
             fmt_int,
-            fmt_float,
             fmt_string,
             fmt_true,
             fmt_false,
@@ -686,6 +737,79 @@ impl<'ctx> CodeGen<'ctx> {
         self.source_path = Some(resolved);
     }
 
+    /// Make integer +, - and * bail out on overflow instead of wrapping
+    pub fn set_checked_arithmetic(&mut self, checked: bool) {
+        self.checked_arithmetic = checked;
+    }
+
+    /// Emit DWARF line tables and variable info so gdb/lldb can follow the source
+    pub fn set_debug_info(&mut self, enabled: bool) {
+        self.emit_debug_info = enabled;
+    }
+
+    /// Seed the runtime RNG before any user code runs
+    pub fn set_random_seed(&mut self, seed: Option<u64>) {
+        self.random_seed = seed;
+    }
+
+    /// Give a function body its subprogram and point the builder at it.
+    /// Returns the caller's location so it can be put back afterwards.
+    fn debug_enter_function(&mut self, function: FunctionValue<'ctx>) -> Option<DILocation<'ctx>> {
+        let debug = self.debug_info.as_ref()?;
+        let saved = self.builder.get_current_debug_location();
+        let line = saved.map(|loc| loc.get_line()).unwrap_or(1);
+        let subprogram = debug.attach_function(function, line);
+        let location = debug.location(self.context, line, subprogram);
+        self.builder.set_current_debug_location(location);
+        saved
+    }
+
+    fn debug_leave_function(&mut self, saved: Option<DILocation<'ctx>>) {
+        if self.debug_info.is_none() {
+            return;
+        }
+        match saved {
+            Some(location) => self.builder.set_current_debug_location(location),
+            None => self.builder.unset_current_debug_location(),
+        }
+    }
+
+    /// Tag everything emitted from here on with the given source line
+    fn debug_set_line(&mut self, line: usize) {
+        let Some(debug) = self.debug_info.as_ref() else {
+            return;
+        };
+        match self.current_function.and_then(|f| f.get_subprogram()) {
+            Some(subprogram) => {
+                let location = debug.location(self.context, line.max(1) as u32, subprogram);
+                self.builder.set_current_debug_location(location);
+            }
+            None => self.builder.unset_current_debug_location(),
+        }
+    }
+
+    /// Describe a freshly declared `ken` variable
+    fn debug_declare_variable(&mut self, name: &str) {
+        let Some(debug) = self.debug_info.as_mut() else {
+            return;
+        };
+        let (Some(function), Some(location), Some(block)) = (
+            self.current_function,
+            self.builder.get_current_debug_location(),
+            self.builder.get_insert_block(),
+        ) else {
+            return;
+        };
+        if block.get_terminator().is_some() {
+            return;
+        }
+        if let Some(&storage) = self.variables.get(name) {
+            debug.declare_local(function, name, storage, location, block);
+        } else if let Some(global) = self.module.get_global(name) {
+            debug.declare_global(self.context, global, location.get_line());
+        }
+    }
+
     #[cfg(coverage)]
     pub fn coverage_current_function_none_branch(
         &self,
@@ -927,6 +1051,12 @@ impl<'ctx> CodeGen<'ctx> {
         let to_string =
             module.add_function("__mdh_to_string", type_of_type, Some(Linkage::External));
 
+        // __mdh_shaw(MdhValue, i64 pretty) -> MdhValue (string)
+        let shaw_type = types
+            .value_type
+            .fn_type(&[types.value_type.into(), i64_type.into()], false);
+        let shaw = module.add_function("__mdh_shaw", shaw_type, Some(Linkage::External));
+
         // __mdh_to_int(MdhValue) -> MdhValue (int)
         let to_int = module.add_function("__mdh_to_int", type_of_type, Some(Linkage::External));
 
@@ -1461,9 +1591,9 @@ impl<'ctx> CodeGen<'ctx> {
             module.add_function("__mdh_tcp_recv", socket_2_type, Some(Linkage::External));
         let dns_lookup =
             module.add_function("__mdh_dns_lookup", socket_1_type, Some(Linkage::External));
-        let dns_srv = module.add_function("__mdh_dns_srv", socket_2_type, Some(Linkage::External));
+        let dns_srv = module.add_function("__mdh_dns_srv", socket_3_type, Some(Linkage::External));
         let dns_naptr =
-            module.add_function("__mdh_dns_naptr", socket_1_type, Some(Linkage::External));
+            module.add_function("__mdh_dns_naptr", socket_2_type, Some(Linkage::External));
         let tls_client_new = module.add_function(
             "__mdh_tls_client_new",
             socket_1_type,
@@ -1471,6 +1601,16 @@ impl<'ctx> CodeGen<'ctx> {
         );
         let tls_connect =
             module.add_function("__mdh_tls_connect", socket_2_type, Some(Linkage::External));
+        let tls_connect_start = module.add_function(
+            "__mdh_tls_connect_start",
+            socket_2_type,
+            Some(Linkage::External),
+        );
+        let tls_handshake_step = module.add_function(
+            "__mdh_tls_handshake_step",
+            socket_1_type,
+            Some(Linkage::External),
+        );
         let tls_send =
             module.add_function("__mdh_tls_send", socket_2_type, Some(Linkage::External));
         let tls_recv =
@@ -1496,6 +1636,8 @@ impl<'ctx> CodeGen<'ctx> {
             socket_2_type,
             Some(Linkage::External),
         );
+        let srtp_set_roc =
+            module.add_function("__mdh_srtp_set_roc", socket_3_type, Some(Linkage::External));
 
         let event_loop_new = module.add_function(
             "__mdh_event_loop_new",
@@ -1634,6 +1776,26 @@ impl<'ctx> CodeGen<'ctx> {
         let random_int =
             module.add_function("__mdh_random_int", random_val_type, Some(Linkage::External));
 
+        // __mdh_seed_random(seed) -> MdhValue (nil)
+        let seed_random_type = types
+            .value_type
+            .fn_type(&[types.value_type.into()], false);
+        let seed_random =
+            module.add_function("__mdh_seed_random", seed_random_type, Some(Linkage::External));
+
+        // __mdh_sign(x), __mdh_clamp(x, lo, hi), __mdh_lerp(a, b, t) -> MdhValue
+        let sign = module.add_function("__mdh_sign", seed_random_type, Some(Linkage::External));
+        let num_3_type = types.value_type.fn_type(
+            &[
+                types.value_type.into(),
+                types.value_type.into(),
+                types.value_type.into(),
+            ],
+            false,
+        );
+        let clamp = module.add_function("__mdh_clamp", num_3_type, Some(Linkage::External));
+        let lerp = module.add_function("__mdh_lerp", num_3_type, Some(Linkage::External));
+
         // __mdh_term_width() -> MdhValue
         let term_size_type = types.value_type.fn_type(&[], false);
         let term_width =
@@ -1768,6 +1930,16 @@ impl<'ctx> CodeGen<'ctx> {
         // __mdh_is_dir(path) -> MdhValue (bool)
         let is_dir = module.add_function("__mdh_is_dir", file_exists_type, Some(Linkage::External));
 
+        // __mdh_is_file(path) -> MdhValue (bool)
+        let is_file =
+            module.add_function("__mdh_is_file", file_exists_type, Some(Linkage::External));
+
+        // __mdh_read_dir(path) / __mdh_walk_dir(path) -> MdhValue (list)
+        let read_dir =
+            module.add_function("__mdh_read_dir", file_exists_type, Some(Linkage::External));
+        let walk_dir =
+            module.add_function("__mdh_walk_dir", file_exists_type, Some(Linkage::External));
+
         // __mdh_slurp(path) -> MdhValue (string)
         let slurp_type = types.value_type.fn_type(&[types.value_type.into()], false);
         let slurp = module.add_function("__mdh_slurp", slurp_type, Some(Linkage::External));
@@ -1801,6 +1973,20 @@ impl<'ctx> CodeGen<'ctx> {
         let set_args =
             module.add_function("__mdh_set_args", set_args_type, Some(Linkage::External));
 
+        // __mdh_set_function_table(table, count) -> void
+        let set_function_table_type = void_type.fn_type(
+            &[
+                i64_type.ptr_type(AddressSpace::default()).into(),
+                i64_type.into(),
+            ],
+            false,
+        );
+        let set_function_table = module.add_function(
+            "__mdh_set_function_table",
+            set_function_table_type,
+            Some(Linkage::External),
+        );
+
         // __mdh_args() -> MdhValue (list)
         let args_type = types.value_type.fn_type(&[], false);
         let args = module.add_function("__mdh_args", args_type, Some(Linkage::External));
@@ -1855,6 +2041,11 @@ impl<'ctx> CodeGen<'ctx> {
             date_format_type,
             Some(Linkage::External),
         );
+        let format_time = module.add_function(
+            "__mdh_format_time",
+            date_format_type,
+            Some(Linkage::External),
+        );
         let date_parse = module.add_function(
             "__mdh_date_parse",
             date_format_type,
@@ -1910,6 +2101,72 @@ impl<'ctx> CodeGen<'ctx> {
             Some(Linkage::External),
         );
 
+        // Digest functions
+        let digest_type = types.value_type.fn_type(&[types.value_type.into()], false);
+        let sha256 = module.add_function("__mdh_sha256", digest_type, Some(Linkage::External));
+        let sha1 = module.add_function("__mdh_sha1", digest_type, Some(Linkage::External));
+        let md5 = module.add_function("__mdh_md5", digest_type, Some(Linkage::External));
+        let sha256_hex =
+            module.add_function("__mdh_sha256_hex", digest_type, Some(Linkage::External));
+        let sha1_hex =
+            module.add_function("__mdh_sha1_hex", digest_type, Some(Linkage::External));
+        let md5_hex = module.add_function("__mdh_md5_hex", digest_type, Some(Linkage::External));
+        let digest_2_type = types
+            .value_type
+            .fn_type(&[types.value_type.into(), types.value_type.into()], false);
+        let hmac_sha256 =
+            module.add_function("__mdh_hmac_sha256", digest_2_type, Some(Linkage::External));
+        let hmac_sha256_hex = module.add_function(
+            "__mdh_hmac_sha256_hex",
+            digest_2_type,
+            Some(Linkage::External),
+        );
+        let ct_eq = module.add_function("__mdh_ct_eq", digest_2_type, Some(Linkage::External));
+        let random_bytes =
+            module.add_function("__mdh_random_bytes", digest_type, Some(Linkage::External));
+        let random_hex =
+            module.add_function("__mdh_random_hex", digest_type, Some(Linkage::External));
+        let pbkdf2_type = types.value_type.fn_type(
+            &[
+                types.value_type.into(),
+                types.value_type.into(),
+                types.value_type.into(),
+                types.value_type.into(),
+            ],
+            false,
+        );
+        let pbkdf2 = module.add_function("__mdh_pbkdf2", pbkdf2_type, Some(Linkage::External));
+
+        // Hex helpers
+        let hex_1_type = types.value_type.fn_type(&[types.value_type.into()], false);
+        let hex_encode =
+            module.add_function("__mdh_hex_encode", hex_1_type, Some(Linkage::External));
+        let hex_decode =
+            module.add_function("__mdh_hex_decode", hex_1_type, Some(Linkage::External));
+        let hexdump = module.add_function("__mdh_hexdump", hex_1_type, Some(Linkage::External));
+
+        // Float formatting - the same text the interpreter gives
+        let format_float_type = void_type.fn_type(
+            &[
+                context.f64_type().into(),
+                i8_ptr.into(),
+                i64_type.into(),
+            ],
+            false,
+        );
+        let format_float = module.add_function(
+            "__mdh_format_float",
+            format_float_type,
+            Some(Linkage::External),
+        );
+        let fmt_float_type = types
+            .value_type
+            .fn_type(&[types.value_type.into(), types.value_type.into()], false);
+        let fmt_float =
+            module.add_function("__mdh_fmt_float", fmt_float_type, Some(Linkage::External));
+        // format(template, values) - the values come packed in a list
+        let format = module.add_function("__mdh_format", fmt_float_type, Some(Linkage::External));
+
         // JSON functions
         let json_1_type = types.value_type.fn_type(&[types.value_type.into()], false);
         let json_parse =
@@ -1918,6 +2175,36 @@ impl<'ctx> CodeGen<'ctx> {
             module.add_function("__mdh_json_stringify", json_1_type, Some(Linkage::External));
         let json_pretty =
             module.add_function("__mdh_json_pretty", json_1_type, Some(Linkage::External));
+        let json_2_type = types
+            .value_type
+            .fn_type(&[types.value_type.into(), types.value_type.into()], false);
+        let json_query =
+            module.add_function("__mdh_json_query", json_2_type, Some(Linkage::External));
+        let json_3_type = types.value_type.fn_type(
+            &[
+                types.value_type.into(),
+                types.value_type.into(),
+                types.value_type.into(),
+            ],
+            false,
+        );
+        let json_stringify_opts = module.add_function(
+            "__mdh_json_stringify_opts",
+            json_3_type,
+            Some(Linkage::External),
+        );
+
+        // CSV functions - the delimiter is nil when the caller leaves it out
+        let csv_type = types
+            .value_type
+            .fn_type(&[types.value_type.into(), types.value_type.into()], false);
+        let csv_parse = module.add_function("__mdh_csv_parse", csv_type, Some(Linkage::External));
+        let csv_write = module.add_function("__mdh_csv_write", csv_type, Some(Linkage::External));
+
+        // UUID functions
+        let uuid_type = types.value_type.fn_type(&[], false);
+        let uuid_v4 = module.add_function("__mdh_uuid_v4", uuid_type, Some(Linkage::External));
+        let uuid_v7 = module.add_function("__mdh_uuid_v7", uuid_type, Some(Linkage::External));
 
         // Misc parity helpers
         let is_a_type = types
@@ -2205,6 +2492,17 @@ impl<'ctx> CodeGen<'ctx> {
         let tae_binary =
             module.add_function("__mdh_tae_binary", tae_binary_type, Some(Linkage::External));
 
+        // __mdh_tae_base(n, base) -> MdhValue (string)
+        let tae_base_type = types
+            .value_type
+            .fn_type(&[types.value_type.into(), types.value_type.into()], false);
+        let tae_base =
+            module.add_function("__mdh_tae_base", tae_base_type, Some(Linkage::External));
+
+        // __mdh_frae_base(str, base) -> MdhValue (int)
+        let frae_base =
+            module.add_function("__mdh_frae_base", tae_base_type, Some(Linkage::External));
+
         // __mdh_fae_binary(str) -> MdhValue (int) - parse binary string
         let fae_binary_type = types.value_type.fn_type(&[types.value_type.into()], false);
         let fae_binary =
@@ -2355,6 +2653,10 @@ impl<'ctx> CodeGen<'ctx> {
             .fn_type(&[types.value_type.into(), types.value_type.into()], false);
         let assert_fn = module.add_function("__mdh_assert", assert_type, Some(Linkage::External));
 
+        // __mdh_assert_eq(actual, expected) -> MdhValue (aye)
+        let assert_eq =
+            module.add_function("__mdh_assert_eq", assert_type, Some(Linkage::External));
+
         // __mdh_skip(reason) -> MdhValue (nil)
         let skip_type = types.value_type.fn_type(&[types.value_type.into()], false);
         let skip = module.add_function("__mdh_skip", skip_type, Some(Linkage::External));
@@ -2631,6 +2933,8 @@ impl<'ctx> CodeGen<'ctx> {
             dict_remove_type,
             Some(Linkage::External),
         );
+        let dict_pop =
+            module.add_function("__mdh_dict_pop", dict_remove_type, Some(Linkage::External));
 
         let dict_invert_type = types.value_type.fn_type(&[types.value_type.into()], false);
         let dict_invert = module.add_function(
@@ -2703,6 +3007,7 @@ impl<'ctx> CodeGen<'ctx> {
             type_error,
             type_of,
             to_string,
+            shaw,
             to_int,
             to_float,
             native_get,
@@ -2798,6 +3103,8 @@ impl<'ctx> CodeGen<'ctx> {
             dns_naptr,
             tls_client_new,
             tls_connect,
+            tls_connect_start,
+            tls_handshake_step,
             tls_send,
             tls_recv,
             tls_close,
@@ -2806,6 +3113,7 @@ impl<'ctx> CodeGen<'ctx> {
             srtp_create,
             srtp_protect,
             srtp_unprotect,
+            srtp_set_roc,
             event_loop_new,
             event_loop_stop,
             event_watch_read,
@@ -2843,6 +3151,10 @@ impl<'ctx> CodeGen<'ctx> {
             random,
             jammy,
             random_int,
+            seed_random,
+            sign,
+            clamp,
+            lerp,
             term_width,
             term_height,
             empty_dict,
@@ -2865,12 +3177,16 @@ impl<'ctx> CodeGen<'ctx> {
             list_dir,
             make_dir,
             is_dir,
+            is_file,
+            read_dir,
+            walk_dir,
             slurp,
             scrieve,
             scrieve_append,
             lines,
             words,
             set_args,
+            set_function_table,
             args,
             cwd,
             chdir,
@@ -2882,6 +3198,7 @@ impl<'ctx> CodeGen<'ctx> {
             shell_status,
             date_now,
             date_format,
+            format_time,
             date_parse,
             date_add,
             date_diff,
@@ -2892,9 +3209,33 @@ impl<'ctx> CodeGen<'ctx> {
             regex_replace,
             regex_replace_first,
             regex_split,
+            sha256,
+            sha1,
+            md5,
+            sha256_hex,
+            sha1_hex,
+            md5_hex,
+            hmac_sha256,
+            hmac_sha256_hex,
+            ct_eq,
+            random_bytes,
+            random_hex,
+            pbkdf2,
+            hex_encode,
+            hex_decode,
+            hexdump,
+            format_float,
+            fmt_float,
+            format,
             json_parse,
             json_stringify,
             json_pretty,
+            json_stringify_opts,
+            json_query,
+            csv_parse,
+            csv_write,
+            uuid_v4,
+            uuid_v7,
             is_a,
             wrang_sort,
             numpty_check,
@@ -2963,6 +3304,8 @@ impl<'ctx> CodeGen<'ctx> {
             tak,
             pair_up,
             tae_binary,
+            tae_base,
+            frae_base,
             fae_binary,
             fae_hex,
             ltrim,
@@ -2985,6 +3328,7 @@ impl<'ctx> CodeGen<'ctx> {
             average,
             chynge,
             assert_fn,
+            assert_eq,
             skip,
             stacktrace,
             jmp_buf_size,
@@ -3021,6 +3365,7 @@ impl<'ctx> CodeGen<'ctx> {
             dict_get_default,
             dict_merge,
             dict_remove,
+            dict_pop,
             dict_invert,
             fae_pairs,
             range,
@@ -3074,6 +3419,14 @@ impl<'ctx> CodeGen<'ctx> {
 
     /// Compile a complete program
     pub fn compile(&mut self, program: &Program) -> Result<(), HaversError> {
+        if self.emit_debug_info {
+            self.debug_info = Some(DebugInfo::new(
+                self.context,
+                &self.module,
+                self.source_path.as_deref(),
+            ));
+        }
+
         // First pass: declare all functions and store default parameter values
         for stmt in &program.statements {
             if let Stmt::Function { name, params, .. } = stmt {
@@ -3098,6 +3451,7 @@ impl<'ctx> CodeGen<'ctx> {
         let entry = self.context.append_basic_block(main_fn, "entry");
         self.builder.position_at_end(entry);
         self.current_function = Some(main_fn);
+        self.debug_enter_function(main_fn);
 
         // Pass argv into the runtime so args() can work.
         let argc = main_fn.get_nth_param(0).unwrap().into_int_value();
@@ -3106,6 +3460,13 @@ impl<'ctx> CodeGen<'ctx> {
             .build_call(self.libc.set_args, &[argc.into(), argv.into()], "set_args")
             .unwrap();
 
+        if let Some(seed) = self.random_seed {
+            let seed = self.make_int(self.types.i64_type.const_int(seed, false))?;
+            self.builder
+                .build_call(self.libc.seed_random, &[seed.into()], "seed_random")
+                .unwrap();
+        }
+
         // Predefine some globals expected by the comprehensive test suite.
         // These are used by a few ignored tests and should be safe defaults.
         let zero = self.make_int(self.types.i64_type.const_int(0, false)).unwrap();
@@ -3157,9 +3518,67 @@ impl<'ctx> CodeGen<'ctx> {
             .build_return(Some(&self.types.i32_type.const_int(0, false)))
             .unwrap();
 
+        // The function table is only complete once everything is compiled, so
+        // the call that passes it to the runtime goes back at the top of main
+        if let Some(first) = entry.get_first_instruction() {
+            self.builder.position_before(&first);
+            self.emit_function_table();
+        }
+
+        if let Some(debug) = &self.debug_info {
+            debug.finalize();
+        }
+
         Ok(())
     }
 
+    /// Emit the (fn pointer, name, arity) table the runtime uses to print
+    /// functions as `<dae add/2>`, and pass it over
+    fn emit_function_table(&self) {
+        let i64_type = self.types.i64_type;
+        let mut entries = Vec::with_capacity(self.function_names.len() * 3);
+        for (index, (function, name, arity)) in self.function_names.iter().enumerate() {
+            let name_global = Self::create_global_string(
+                &self.module,
+                self.context,
+                name,
+                &format!("__mdh_fn_name_{}", index),
+            );
+            entries.push(
+                function
+                    .as_global_value()
+                    .as_pointer_value()
+                    .const_to_int(i64_type),
+            );
+            entries.push(name_global.as_pointer_value().const_to_int(i64_type));
+            entries.push(i64_type.const_int(*arity as u64, false));
+        }
+        let table = self.module.add_global(
+            i64_type.array_type(entries.len() as u32),
+            None,
+            "__mdh_function_table",
+        );
+        table.set_linkage(Linkage::Private);
+        table.set_constant(true);
+        table.set_initializer(&i64_type.const_array(&entries));
+        let table_ptr = self
+            .builder
+            .build_pointer_cast(
+                table.as_pointer_value(),
+                i64_type.ptr_type(AddressSpace::default()),
+                "fn_table",
+            )
+            .unwrap();
+        let count = i64_type.const_int(self.function_names.len() as u64, false);
+        self.builder
+            .build_call(
+                self.libc.set_function_table,
+                &[table_ptr.into(), count.into()],
+                "",
+            )
+            .unwrap();
+    }
+
     /// Declare a function (first pass)
     fn declare_function(&mut self, name: &str, param_count: usize) {
         self.declare_function_with_captures(name, param_count, &[]);
@@ -4098,61 +4517,12 @@ impl<'ctx> CodeGen<'ctx> {
 
     /// Infer the type of an expression for optimization purposes
     fn infer_expr_type(&self, expr: &Expr) -> VarType {
-        match expr {
-            Expr::Literal { value, .. } => match value {
-                Literal::Integer(_) => VarType::Int,
-                Literal::Float(_) => VarType::Float,
-                Literal::String(_) => VarType::String,
-                Literal::Bool(_) => VarType::Bool,
-                Literal::Nil => VarType::Unknown,
-            },
-            Expr::Variable { name, .. } => self
-                .var_types
+        crate::infer::infer_expr_type(expr, &|name| {
+            self.var_types
                 .get(name)
                 .copied()
-                .unwrap_or(VarType::Unknown),
-            Expr::Binary {
-                left,
-                operator,
-                right,
-                ..
-            } => match operator {
-                BinaryOp::Add => {
-                    let lt = self.infer_expr_type(left);
-                    let rt = self.infer_expr_type(right);
-                    if lt == VarType::Int && rt == VarType::Int {
-                        VarType::Int
-                    } else if lt == VarType::Float || rt == VarType::Float {
-                        VarType::Float
-                    } else if lt == VarType::String && rt == VarType::String {
-                        VarType::String
-                    } else {
-                        VarType::Unknown
-                    }
-                }
-                BinaryOp::Subtract | BinaryOp::Multiply | BinaryOp::Divide | BinaryOp::Modulo => {
-                    let lt = self.infer_expr_type(left);
-                    let rt = self.infer_expr_type(right);
-                    if lt == VarType::Int && rt == VarType::Int {
-                        VarType::Int
-                    } else if lt == VarType::Float || rt == VarType::Float {
-                        VarType::Float
-                    } else {
-                        VarType::Unknown
-                    }
-                }
-                BinaryOp::Less
-                | BinaryOp::LessEqual
-                | BinaryOp::Greater
-                | BinaryOp::GreaterEqual
-                | BinaryOp::Equal
-                | BinaryOp::NotEqual => VarType::Bool,
-            },
-            Expr::List { .. } => VarType::List,
-            Expr::Dict { .. } => VarType::Dict,
-            Expr::Unary { operand, .. } => self.infer_expr_type(operand),
-            _ => VarType::Unknown,
-        }
+                .unwrap_or(VarType::Unknown)
+        })
     }
 
     /// Compile an integer expression directly to i64, bypassing MdhValue boxing.
@@ -4210,13 +4580,9 @@ impl<'ctx> CodeGen<'ctx> {
 
                     if let (Some(l), Some(r)) = (left_i64, right_i64) {
                         let result = match operator {
-                            BinaryOp::Add => self.builder.build_int_add(l, r, "add_i64").unwrap(),
-                            BinaryOp::Subtract => {
-                                self.builder.build_int_sub(l, r, "sub_i64").unwrap()
-                            }
-                            BinaryOp::Multiply => {
-                                self.builder.build_int_mul(l, r, "mul_i64").unwrap()
-                            }
+                            BinaryOp::Add => self.build_arith_i64(*operator, l, r, "add_i64"),
+                            BinaryOp::Subtract => self.build_arith_i64(*operator, l, r, "sub_i64"),
+                            BinaryOp::Multiply => self.build_arith_i64(*operator, l, r, "mul_i64"),
                             BinaryOp::Divide => self
                                 .builder
                                 .build_int_signed_div(l, r, "div_i64")
@@ -4225,6 +4591,11 @@ impl<'ctx> CodeGen<'ctx> {
                                 .builder
                                 .build_int_signed_rem(l, r, "mod_i64")
                                 .unwrap(),
+                            BinaryOp::BitAnd
+                            | BinaryOp::BitOr
+                            | BinaryOp::BitXor
+                            | BinaryOp::ShiftLeft
+                            | BinaryOp::ShiftRight => self.build_bitwise_i64(*operator, l, r),
                             _ => return None,
                         };
                         return Some(result);
@@ -4404,10 +4775,7 @@ impl<'ctx> CodeGen<'ctx> {
 
         // int + int
         self.builder.position_at_end(int_int);
-        let int_sum = self
-            .builder
-            .build_int_add(left_data, right_data, "sum")
-            .unwrap();
+        let int_sum = self.build_arith_i64(BinaryOp::Add, left_data, right_data, "sum");
         let int_result = self.make_int(int_sum).unwrap();
         self.builder.build_unconditional_branch(merge).unwrap();
         let int_block = self.builder.get_insert_block().unwrap();
@@ -4645,10 +5013,7 @@ impl<'ctx> CodeGen<'ctx> {
 
         // int - int
         self.builder.position_at_end(int_int);
-        let int_diff = self
-            .builder
-            .build_int_sub(left_data, right_data, "diff")
-            .unwrap();
+        let int_diff = self.build_arith_i64(BinaryOp::Subtract, left_data, right_data, "diff");
         let int_result = self.make_int(int_diff).unwrap();
         self.builder.build_unconditional_branch(merge).unwrap();
         let int_block = self.builder.get_insert_block().unwrap();
@@ -4893,10 +5258,7 @@ impl<'ctx> CodeGen<'ctx> {
 
         // intlike * intlike
         self.builder.position_at_end(int_int);
-        let int_prod = self
-            .builder
-            .build_int_mul(left_data, right_data, "prod")
-            .unwrap();
+        let int_prod = self.build_arith_i64(BinaryOp::Multiply, left_data, right_data, "prod");
         let int_result = self.make_int(int_prod).unwrap();
         self.builder.build_unconditional_branch(merge).unwrap();
         let int_block = self.builder.get_insert_block().unwrap();
@@ -4941,39 +5303,525 @@ impl<'ctx> CodeGen<'ctx> {
             )
             .unwrap()
             .into_float_value();
-        let float_prod = self
+        let float_prod = self
+            .builder
+            .build_float_mul(left_f, right_f, "fprod")
+            .unwrap();
+        let float_result = self.make_float(float_prod).unwrap();
+        self.builder.build_unconditional_branch(merge).unwrap();
+        let float_block = self.builder.get_insert_block().unwrap();
+
+        // ===== Error =====
+        self.builder.position_at_end(error_case);
+        let error_result = self.make_nil();
+        self.builder.build_unconditional_branch(merge).unwrap();
+        let error_block = self.builder.get_insert_block().unwrap();
+
+        // Merge
+        self.builder.position_at_end(merge);
+        let phi = self
+            .builder
+            .build_phi(self.types.value_type, "mul_result")
+            .unwrap();
+        phi.add_incoming(&[
+            (&empty_result, empty_block),
+            (&repeat_result, repeat_block),
+            (&int_result, int_block),
+            (&float_result, float_block),
+            (&error_result, error_block),
+        ]);
+
+        Ok(phi.as_basic_value())
+    }
+
+    /// Divide two values
+    fn inline_div(
+        &mut self,
+        left: BasicValueEnum<'ctx>,
+        right: BasicValueEnum<'ctx>,
+    ) -> Result<BasicValueEnum<'ctx>, HaversError> {
+        let left_tag = self.extract_tag(left).unwrap();
+        let right_tag = self.extract_tag(right).unwrap();
+        let left_data = self.extract_data(left).unwrap();
+        let right_data = self.extract_data(right).unwrap();
+
+        let function = self.current_function.unwrap();
+        let int_int = self.context.append_basic_block(function, "div_int");
+        let float_case = self.context.append_basic_block(function, "div_float");
+        let merge = self.context.append_basic_block(function, "div_merge");
+
+        let int_tag = self
+            .types
+            .i8_type
+            .const_int(ValueTag::Int.as_u8() as u64, false);
+        let bool_tag = self
+            .types
+            .i8_type
+            .const_int(ValueTag::Bool.as_u8() as u64, false);
+        let float_tag = self
+            .types
+            .i8_type
+            .const_int(ValueTag::Float.as_u8() as u64, false);
+
+        let left_is_int = self
+            .builder
+            .build_int_compare(IntPredicate::EQ, left_tag, int_tag, "l_int")
+            .unwrap();
+        let right_is_int = self
+            .builder
+            .build_int_compare(IntPredicate::EQ, right_tag, int_tag, "r_int")
+            .unwrap();
+        let left_is_bool = self
+            .builder
+            .build_int_compare(IntPredicate::EQ, left_tag, bool_tag, "l_bool")
+            .unwrap();
+        let right_is_bool = self
+            .builder
+            .build_int_compare(IntPredicate::EQ, right_tag, bool_tag, "r_bool")
+            .unwrap();
+        let left_is_intlike = self
+            .builder
+            .build_or(left_is_int, left_is_bool, "l_intlike")
+            .unwrap();
+        let right_is_intlike = self
+            .builder
+            .build_or(right_is_int, right_is_bool, "r_intlike")
+            .unwrap();
+        let both_int = self
+            .builder
+            .build_and(left_is_intlike, right_is_intlike, "both_intlike")
+            .unwrap();
+
+        self.builder
+            .build_conditional_branch(both_int, int_int, float_case)
+            .unwrap();
+
+        // int / int
+        self.builder.position_at_end(int_int);
+        let int_quot = self
+            .builder
+            .build_int_signed_div(left_data, right_data, "quot")
+            .unwrap();
+        let int_result = self.make_int(int_quot).unwrap();
+        self.builder.build_unconditional_branch(merge).unwrap();
+        let int_block = self.builder.get_insert_block().unwrap();
+
+        // float case
+        self.builder.position_at_end(float_case);
+        let left_is_float = self
+            .builder
+            .build_int_compare(IntPredicate::EQ, left_tag, float_tag, "lf")
+            .unwrap();
+        let right_is_float = self
+            .builder
+            .build_int_compare(IntPredicate::EQ, right_tag, float_tag, "rf")
+            .unwrap();
+        let left_f = self
+            .builder
+            .build_select(
+                left_is_float,
+                BasicValueEnum::FloatValue(
+                    self.builder
+                        .build_bitcast(left_data, self.types.f64_type, "lf")
+                        .unwrap()
+                        .into_float_value(),
+                ),
+                BasicValueEnum::FloatValue(
+                    self.builder
+                        .build_signed_int_to_float(left_data, self.types.f64_type, "li2f")
+                        .unwrap(),
+                ),
+                "left_as_float",
+            )
+            .unwrap()
+            .into_float_value();
+        let right_f = self
+            .builder
+            .build_select(
+                right_is_float,
+                BasicValueEnum::FloatValue(
+                    self.builder
+                        .build_bitcast(right_data, self.types.f64_type, "rf")
+                        .unwrap()
+                        .into_float_value(),
+                ),
+                BasicValueEnum::FloatValue(
+                    self.builder
+                        .build_signed_int_to_float(right_data, self.types.f64_type, "ri2f")
+                        .unwrap(),
+                ),
+                "right_as_float",
+            )
+            .unwrap()
+            .into_float_value();
+        let float_quot = self
+            .builder
+            .build_float_div(left_f, right_f, "fquot")
+            .unwrap();
+        let float_result = self.make_float(float_quot).unwrap();
+        self.builder.build_unconditional_branch(merge).unwrap();
+        let float_block = self.builder.get_insert_block().unwrap();
+
+        // Merge
+        self.builder.position_at_end(merge);
+        let phi = self
+            .builder
+            .build_phi(self.types.value_type, "div_result")
+            .unwrap();
+        phi.add_incoming(&[(&int_result, int_block), (&float_result, float_block)]);
+
+        Ok(phi.as_basic_value())
+    }
+
+    /// Modulo two values - ints use srem, anything with a float uses frem
+    fn inline_mod(
+        &mut self,
+        left: BasicValueEnum<'ctx>,
+        right: BasicValueEnum<'ctx>,
+    ) -> Result<BasicValueEnum<'ctx>, HaversError> {
+        let left_tag = self.extract_tag(left).unwrap();
+        let right_tag = self.extract_tag(right).unwrap();
+        let left_data = self.extract_data(left).unwrap();
+        let right_data = self.extract_data(right).unwrap();
+
+        let function = self.current_function.unwrap();
+        let int_int = self.context.append_basic_block(function, "mod_int");
+        let int_ok = self.context.append_basic_block(function, "mod_int_ok");
+        let int_zero = self.context.append_basic_block(function, "mod_int_zero");
+        let float_case = self.context.append_basic_block(function, "mod_float");
+        let merge = self.context.append_basic_block(function, "mod_merge");
+
+        let int_tag = self
+            .types
+            .i8_type
+            .const_int(ValueTag::Int.as_u8() as u64, false);
+        let bool_tag = self
+            .types
+            .i8_type
+            .const_int(ValueTag::Bool.as_u8() as u64, false);
+        let float_tag = self
+            .types
+            .i8_type
+            .const_int(ValueTag::Float.as_u8() as u64, false);
+
+        // Check if both are int-like (int or bool)
+        let left_is_int = self
+            .builder
+            .build_int_compare(IntPredicate::EQ, left_tag, int_tag, "l_int")
+            .unwrap();
+        let right_is_int = self
+            .builder
+            .build_int_compare(IntPredicate::EQ, right_tag, int_tag, "r_int")
+            .unwrap();
+        let left_is_bool = self
+            .builder
+            .build_int_compare(IntPredicate::EQ, left_tag, bool_tag, "l_bool")
+            .unwrap();
+        let right_is_bool = self
+            .builder
+            .build_int_compare(IntPredicate::EQ, right_tag, bool_tag, "r_bool")
+            .unwrap();
+        let left_is_intlike = self
+            .builder
+            .build_or(left_is_int, left_is_bool, "l_intlike")
+            .unwrap();
+        let right_is_intlike = self
+            .builder
+            .build_or(right_is_int, right_is_bool, "r_intlike")
+            .unwrap();
+        let both_int = self
+            .builder
+            .build_and(left_is_intlike, right_is_intlike, "both_intlike")
+            .unwrap();
+
+        self.builder
+            .build_conditional_branch(both_int, int_int, float_case)
+            .unwrap();
+
+        // int % int - guard zero like the fast path so srem cannot trap
+        self.builder.position_at_end(int_int);
+        let zero = self.types.i64_type.const_int(0, false);
+        let is_zero = self
+            .builder
+            .build_int_compare(IntPredicate::EQ, right_data, zero, "mod_is_zero")
+            .unwrap();
+        self.builder
+            .build_conditional_branch(is_zero, int_zero, int_ok)
+            .unwrap();
+
+        self.builder.position_at_end(int_zero);
+        let zero_result = self.make_int(zero).unwrap();
+        self.builder.build_unconditional_branch(merge).unwrap();
+        let zero_block = self.builder.get_insert_block().unwrap();
+
+        self.builder.position_at_end(int_ok);
+        let int_rem = self
+            .builder
+            .build_int_signed_rem(left_data, right_data, "rem")
+            .unwrap();
+        let int_result = self.make_int(int_rem).unwrap();
+        self.builder.build_unconditional_branch(merge).unwrap();
+        let int_block = self.builder.get_insert_block().unwrap();
+
+        // float % float (or int % float) - frem has C fmod semantics
+        self.builder.position_at_end(float_case);
+        let left_is_float = self
+            .builder
+            .build_int_compare(IntPredicate::EQ, left_tag, float_tag, "lf")
+            .unwrap();
+        let right_is_float = self
+            .builder
+            .build_int_compare(IntPredicate::EQ, right_tag, float_tag, "rf")
+            .unwrap();
+        let left_f = self
+            .builder
+            .build_select(
+                left_is_float,
+                BasicValueEnum::FloatValue(
+                    self.builder
+                        .build_bitcast(left_data, self.types.f64_type, "lf")
+                        .unwrap()
+                        .into_float_value(),
+                ),
+                BasicValueEnum::FloatValue(
+                    self.builder
+                        .build_signed_int_to_float(left_data, self.types.f64_type, "li2f")
+                        .unwrap(),
+                ),
+                "left_as_float",
+            )
+            .unwrap()
+            .into_float_value();
+        let right_f = self
+            .builder
+            .build_select(
+                right_is_float,
+                BasicValueEnum::FloatValue(
+                    self.builder
+                        .build_bitcast(right_data, self.types.f64_type, "rf")
+                        .unwrap()
+                        .into_float_value(),
+                ),
+                BasicValueEnum::FloatValue(
+                    self.builder
+                        .build_signed_int_to_float(right_data, self.types.f64_type, "ri2f")
+                        .unwrap(),
+                ),
+                "right_as_float",
+            )
+            .unwrap()
+            .into_float_value();
+        let float_rem = self
             .builder
-            .build_float_mul(left_f, right_f, "fprod")
+            .build_float_rem(left_f, right_f, "frem")
             .unwrap();
-        let float_result = self.make_float(float_prod).unwrap();
+        let float_result = self.make_float(float_rem).unwrap();
         self.builder.build_unconditional_branch(merge).unwrap();
         let float_block = self.builder.get_insert_block().unwrap();
 
-        // ===== Error =====
-        self.builder.position_at_end(error_case);
-        let error_result = self.make_nil();
-        self.builder.build_unconditional_branch(merge).unwrap();
-        let error_block = self.builder.get_insert_block().unwrap();
-
         // Merge
         self.builder.position_at_end(merge);
         let phi = self
             .builder
-            .build_phi(self.types.value_type, "mul_result")
+            .build_phi(self.types.value_type, "mod_result")
             .unwrap();
         phi.add_incoming(&[
-            (&empty_result, empty_block),
-            (&repeat_result, repeat_block),
             (&int_result, int_block),
+            (&zero_result, zero_block),
             (&float_result, float_block),
-            (&error_result, error_block),
         ]);
 
         Ok(phi.as_basic_value())
     }
 
-    /// Divide two values
-    fn inline_div(
+    /// Apply +, - or * to two raw i64s. With checked arithmetic on, this goes
+    /// through the llvm.s*.with.overflow intrinsics and exits on overflow.
+    fn build_arith_i64(
+        &self,
+        op: BinaryOp,
+        l: IntValue<'ctx>,
+        r: IntValue<'ctx>,
+        name: &str,
+    ) -> IntValue<'ctx> {
+        if !self.checked_arithmetic {
+            return match op {
+                BinaryOp::Add => self.builder.build_int_add(l, r, name).unwrap(),
+                BinaryOp::Subtract => self.builder.build_int_sub(l, r, name).unwrap(),
+                _ => self.builder.build_int_mul(l, r, name).unwrap(),
+            };
+        }
+
+        let intrinsic = match op {
+            BinaryOp::Add => "llvm.sadd.with.overflow.i64",
+            BinaryOp::Subtract => "llvm.ssub.with.overflow.i64",
+            _ => "llvm.smul.with.overflow.i64",
+        };
+        let i64_type = self.types.i64_type;
+        let overflow_fn = self.module.get_function(intrinsic).unwrap_or_else(|| {
+            let pair_type = self
+                .context
+                .struct_type(&[i64_type.into(), self.types.bool_type.into()], false);
+            let fn_type = pair_type.fn_type(&[i64_type.into(), i64_type.into()], false);
+            self.module.add_function(intrinsic, fn_type, None)
+        });
+
+        let pair = self
+            .builder
+            .build_call(overflow_fn, &[l.into(), r.into()], "checked_pair")
+            .unwrap()
+            .try_as_basic_value()
+            .left()
+            .unwrap()
+            .into_struct_value();
+        let result = self
+            .builder
+            .build_extract_value(pair, 0, name)
+            .unwrap()
+            .into_int_value();
+        let overflowed = self
+            .builder
+            .build_extract_value(pair, 1, "overflowed")
+            .unwrap()
+            .into_int_value();
+
+        let function = self.current_function.unwrap();
+        let overflow_block = self.context.append_basic_block(function, "int_overflow");
+        let ok_block = self.context.append_basic_block(function, "int_no_overflow");
+        self.builder
+            .build_conditional_branch(overflowed, overflow_block, ok_block)
+            .unwrap();
+
+        // Overflow: tell the user via och() and bail out
+        self.builder.position_at_end(overflow_block);
+        let msg = self
+            .builder
+            .build_global_string_ptr("Yer number's too muckle - it's overflowed!", "overflow_msg")
+            .unwrap();
+        let msg_val = self.make_string(msg.as_pointer_value()).unwrap();
+        let och_val = self
+            .builder
+            .build_call(self.libc.och, &[msg_val.into()], "overflow_och")
+            .unwrap()
+            .try_as_basic_value()
+            .left()
+            .unwrap();
+        self.builder
+            .build_call(self.libc.blether, &[och_val.into()], "")
+            .unwrap();
+        let exit_code = self.context.i32_type().const_int(1, false);
+        self.builder
+            .build_call(self.libc.exit, &[exit_code.into()], "")
+            .unwrap();
+        self.builder.build_unreachable().unwrap();
+
+        self.builder.position_at_end(ok_block);
+        result
+    }
+
+    /// Apply a bitwise operator to two raw i64s. Shift amounts are masked
+    /// to 0-63 so an oversized shift cannot produce poison.
+    fn build_bitwise_i64(
+        &self,
+        op: BinaryOp,
+        l: IntValue<'ctx>,
+        r: IntValue<'ctx>,
+    ) -> IntValue<'ctx> {
+        let shift_mask = self.types.i64_type.const_int(63, false);
+        match op {
+            BinaryOp::BitAnd => self.builder.build_and(l, r, "bit_and").unwrap(),
+            BinaryOp::BitOr => self.builder.build_or(l, r, "bit_or").unwrap(),
+            BinaryOp::BitXor => self.builder.build_xor(l, r, "bit_xor").unwrap(),
+            BinaryOp::ShiftLeft => {
+                let amount = self.builder.build_and(r, shift_mask, "shl_amount").unwrap();
+                self.builder.build_left_shift(l, amount, "shl").unwrap()
+            }
+            BinaryOp::ShiftRight => {
+                let amount = self.builder.build_and(r, shift_mask, "shr_amount").unwrap();
+                self.builder.build_right_shift(l, amount, true, "shr").unwrap()
+            }
+            _ => unreachable!("build_bitwise_i64 only handles bitwise operators"),
+        }
+    }
+
+    /// Bitwise `&`, `|`, `^`, `<<` and `>>` - integers only, anything
+    /// else is a type error
+    fn inline_bitwise(
+        &mut self,
+        op: BinaryOp,
+        left: BasicValueEnum<'ctx>,
+        right: BasicValueEnum<'ctx>,
+    ) -> Result<BasicValueEnum<'ctx>, HaversError> {
+        let function = self.current_function.unwrap();
+        let left_tag = self.extract_tag(left)?;
+        let right_tag = self.extract_tag(right)?;
+        let left_data = self.extract_data(left)?;
+        let right_data = self.extract_data(right)?;
+
+        let int_tag = self
+            .types
+            .i8_type
+            .const_int(ValueTag::Int.as_u8() as u64, false);
+        let left_is_int = self
+            .builder
+            .build_int_compare(IntPredicate::EQ, left_tag, int_tag, "bit_left_is_int")
+            .unwrap();
+        let right_is_int = self
+            .builder
+            .build_int_compare(IntPredicate::EQ, right_tag, int_tag, "bit_right_is_int")
+            .unwrap();
+        let both_int = self
+            .builder
+            .build_and(left_is_int, right_is_int, "bit_both_int")
+            .unwrap();
+
+        let int_block = self.context.append_basic_block(function, "bit_int");
+        let error_block = self.context.append_basic_block(function, "bit_type_error");
+        let merge_block = self.context.append_basic_block(function, "bit_merge");
+        self.builder
+            .build_conditional_branch(both_int, int_block, error_block)
+            .unwrap();
+
+        self.builder.position_at_end(int_block);
+        let result = self.build_bitwise_i64(op, left_data, right_data);
+        self.builder
+            .build_unconditional_branch(merge_block)
+            .unwrap();
+        let int_end = self.builder.get_insert_block().unwrap();
+
+        self.builder.position_at_end(error_block);
+        let op_name = self
+            .builder
+            .build_global_string_ptr(&op.to_string(), "bit_op_name")
+            .unwrap();
+        self.builder
+            .build_call(
+                self.libc.type_error,
+                &[
+                    op_name.as_pointer_value().into(),
+                    left_tag.into(),
+                    right_tag.into(),
+                ],
+                "",
+            )
+            .unwrap();
+        let zero = self.types.i64_type.const_int(0, false);
+        self.builder
+            .build_unconditional_branch(merge_block)
+            .unwrap();
+        let error_end = self.builder.get_insert_block().unwrap();
+
+        self.builder.position_at_end(merge_block);
+        let phi = self
+            .builder
+            .build_phi(self.types.i64_type, "bit_result")
+            .unwrap();
+        phi.add_incoming(&[(&result, int_end), (&zero, error_end)]);
+        self.make_int(phi.as_basic_value().into_int_value())
+    }
+
+    /// Raise a value to a power (`**`) - ints stay ints for a
+    /// non-negative exponent, like the `pow` built-in
+    fn inline_power(
         &mut self,
         left: BasicValueEnum<'ctx>,
         right: BasicValueEnum<'ctx>,
@@ -4984,9 +5832,9 @@ impl<'ctx> CodeGen<'ctx> {
         let right_data = self.extract_data(right).unwrap();
 
         let function = self.current_function.unwrap();
-        let int_int = self.context.append_basic_block(function, "div_int");
-        let float_case = self.context.append_basic_block(function, "div_float");
-        let merge = self.context.append_basic_block(function, "div_merge");
+        let int_case = self.context.append_basic_block(function, "pow_int");
+        let float_case = self.context.append_basic_block(function, "pow_float");
+        let merge = self.context.append_basic_block(function, "pow_merge");
 
         let int_tag = self
             .types
@@ -5029,23 +5877,17 @@ impl<'ctx> CodeGen<'ctx> {
             .builder
             .build_and(left_is_intlike, right_is_intlike, "both_intlike")
             .unwrap();
-
-        self.builder
-            .build_conditional_branch(both_int, int_int, float_case)
+        let zero = self.types.i64_type.const_int(0, false);
+        let exp_non_negative = self
+            .builder
+            .build_int_compare(IntPredicate::SGE, right_data, zero, "exp_non_neg")
             .unwrap();
-
-        // int / int
-        self.builder.position_at_end(int_int);
-        let int_quot = self
+        let stays_int = self
             .builder
-            .build_int_signed_div(left_data, right_data, "quot")
+            .build_and(both_int, exp_non_negative, "pow_stays_int")
             .unwrap();
-        let int_result = self.make_int(int_quot).unwrap();
-        self.builder.build_unconditional_branch(merge).unwrap();
-        let int_block = self.builder.get_insert_block().unwrap();
 
-        // float case
-        self.builder.position_at_end(float_case);
+        // Promote both to f64 up front; the int path converts back at the end
         let left_is_float = self
             .builder
             .build_int_compare(IntPredicate::EQ, left_tag, float_tag, "lf")
@@ -5092,11 +5934,38 @@ impl<'ctx> CodeGen<'ctx> {
             )
             .unwrap()
             .into_float_value();
-        let float_quot = self
+
+        let pow_fn = self.get_or_create_intrinsic(
+            "llvm.pow.f64",
+            self.types.f64_type.into(),
+            &[self.types.f64_type.into(), self.types.f64_type.into()],
+        );
+        let powered = self
             .builder
-            .build_float_div(left_f, right_f, "fquot")
+            .build_call(pow_fn, &[left_f.into(), right_f.into()], "powered")
+            .unwrap()
+            .try_as_basic_value()
+            .left()
+            .unwrap()
+            .into_float_value();
+
+        self.builder
+            .build_conditional_branch(stays_int, int_case, float_case)
             .unwrap();
-        let float_result = self.make_float(float_quot).unwrap();
+
+        // int ** non-negative int
+        self.builder.position_at_end(int_case);
+        let int_pow = self
+            .builder
+            .build_float_to_signed_int(powered, self.types.i64_type, "pow_i64")
+            .unwrap();
+        let int_result = self.make_int(int_pow).unwrap();
+        self.builder.build_unconditional_branch(merge).unwrap();
+        let int_block = self.builder.get_insert_block().unwrap();
+
+        // Anything else is a float
+        self.builder.position_at_end(float_case);
+        let float_result = self.make_float(powered).unwrap();
         self.builder.build_unconditional_branch(merge).unwrap();
         let float_block = self.builder.get_insert_block().unwrap();
 
@@ -5104,28 +5973,13 @@ impl<'ctx> CodeGen<'ctx> {
         self.builder.position_at_end(merge);
         let phi = self
             .builder
-            .build_phi(self.types.value_type, "div_result")
+            .build_phi(self.types.value_type, "pow_result")
             .unwrap();
         phi.add_incoming(&[(&int_result, int_block), (&float_result, float_block)]);
 
         Ok(phi.as_basic_value())
     }
 
-    /// Modulo two values
-    fn inline_mod(
-        &mut self,
-        left: BasicValueEnum<'ctx>,
-        right: BasicValueEnum<'ctx>,
-    ) -> Result<BasicValueEnum<'ctx>, HaversError> {
-        let left_data = self.extract_data(left).unwrap();
-        let right_data = self.extract_data(right).unwrap();
-        let rem = self
-            .builder
-            .build_int_signed_rem(left_data, right_data, "rem")
-            .unwrap();
-        self.make_int(rem)
-    }
-
     /// Compare two values for equality
     fn inline_eq(
         &mut self,
@@ -6096,31 +6950,33 @@ impl<'ctx> CodeGen<'ctx> {
         Ok(eq_val)
     }
 
-    /// Compare two values: greater than or equal (returns raw i1 bool)
+    /// Compare two values: greater than or equal (returns raw i1 bool).
+    /// Goes through inline_ge so a float is weighed by value, not by its bits.
     fn inline_ge_raw(
         &mut self,
         left: BasicValueEnum<'ctx>,
         right: BasicValueEnum<'ctx>,
     ) -> Result<IntValue<'ctx>, HaversError> {
-        let left_data = self.extract_data(left).unwrap();
-        let right_data = self.extract_data(right).unwrap();
+        let ge = self.inline_ge(left, right)?;
+        let ge_data = self.extract_data(ge).unwrap();
         Ok(self
             .builder
-            .build_int_compare(IntPredicate::SGE, left_data, right_data, "ge_raw")
+            .build_int_truncate(ge_data, self.types.bool_type, "ge_raw")
             .unwrap())
     }
 
-    /// Compare two values: less than (returns raw i1 bool)
+    /// Compare two values: less than (returns raw i1 bool).
+    /// Goes through inline_lt so a float is weighed by value, not by its bits.
     fn inline_lt_raw(
         &mut self,
         left: BasicValueEnum<'ctx>,
         right: BasicValueEnum<'ctx>,
     ) -> Result<IntValue<'ctx>, HaversError> {
-        let left_data = self.extract_data(left).unwrap();
-        let right_data = self.extract_data(right).unwrap();
+        let lt = self.inline_lt(left, right)?;
+        let lt_data = self.extract_data(lt).unwrap();
         Ok(self
             .builder
-            .build_int_compare(IntPredicate::SLT, left_data, right_data, "lt_raw")
+            .build_int_truncate(lt_data, self.types.bool_type, "lt_raw")
             .unwrap())
     }
 
@@ -6217,7 +7073,6 @@ impl<'ctx> CodeGen<'ctx> {
         let print_nil = self.context.append_basic_block(function, "print_nil");
         let print_bool = self.context.append_basic_block(function, "print_bool");
         let print_int = self.context.append_basic_block(function, "print_int");
-        let print_float = self.context.append_basic_block(function, "print_float");
         let print_string = self.context.append_basic_block(function, "print_string");
         let print_default = self.context.append_basic_block(function, "print_default");
         let print_done = self.context.append_basic_block(function, "print_done");
@@ -6234,15 +7089,12 @@ impl<'ctx> CodeGen<'ctx> {
             .types
             .i8_type
             .const_int(ValueTag::Int.as_u8() as u64, false);
-        let float_tag = self
-            .types
-            .i8_type
-            .const_int(ValueTag::Float.as_u8() as u64, false);
         let string_tag = self
             .types
             .i8_type
             .const_int(ValueTag::String.as_u8() as u64, false);
 
+        // Floats go through the runtime so they print the same as tae_string
         self.builder
             .build_switch(
                 tag,
@@ -6251,7 +7103,6 @@ impl<'ctx> CodeGen<'ctx> {
                     (nil_tag, print_nil),
                     (bool_tag, print_bool),
                     (int_tag, print_int),
-                    (float_tag, print_float),
                     (string_tag, print_string),
                 ],
             )
@@ -6291,18 +7142,6 @@ impl<'ctx> CodeGen<'ctx> {
             .unwrap();
         self.builder.build_unconditional_branch(print_done).unwrap();
 
-        // Print float
-        self.builder.position_at_end(print_float);
-        let float_fmt = self.get_string_ptr(self.fmt_float);
-        let float_val = self
-            .builder
-            .build_bitcast(data, self.types.f64_type, "f")
-            .unwrap();
-        self.builder
-            .build_call(self.libc.printf, &[float_fmt.into(), float_val.into()], "")
-            .unwrap();
-        self.builder.build_unconditional_branch(print_done).unwrap();
-
         // Print string
         self.builder.position_at_end(print_string);
         let str_fmt = self.get_string_ptr(self.fmt_string);
@@ -6470,7 +7309,7 @@ impl<'ctx> CodeGen<'ctx> {
         self.builder.build_unconditional_branch(str_merge).unwrap();
         let int_block = self.builder.get_insert_block().unwrap();
 
-        // float -> format with snprintf
+        // float -> canonical text from the runtime
         self.builder.position_at_end(str_float);
         let float_buf = self
             .builder
@@ -6480,23 +7319,14 @@ impl<'ctx> CodeGen<'ctx> {
             .left()
             .unwrap()
             .into_pointer_value();
-        let float_fmt = self
-            .builder
-            .build_global_string_ptr("%g", "float_fmt")
-            .unwrap();
         let float_val = self
             .builder
             .build_bitcast(data, self.types.f64_type, "f")
             .unwrap();
         self.builder
             .build_call(
-                self.libc.snprintf,
-                &[
-                    float_buf.into(),
-                    buf_size.into(),
-                    float_fmt.as_pointer_value().into(),
-                    float_val.into(),
-                ],
+                self.libc.format_float,
+                &[float_val.into(), float_buf.into(), buf_size.into()],
                 "",
             )
             .unwrap();
@@ -9870,6 +10700,18 @@ impl<'ctx> CodeGen<'ctx> {
     // ========== Statement Compilation ==========
 
     fn compile_stmt(&mut self, stmt: &Stmt) -> Result<(), HaversError> {
+        if self.debug_info.is_none() {
+            return self.compile_stmt_inner(stmt);
+        }
+        self.debug_set_line(stmt.span().line);
+        self.compile_stmt_inner(stmt)?;
+        if let Stmt::VarDecl { name, .. } = stmt {
+            self.debug_declare_variable(name);
+        }
+        Ok(())
+    }
+
+    fn compile_stmt_inner(&mut self, stmt: &Stmt) -> Result<(), HaversError> {
         match stmt {
             Stmt::VarDecl {
                 name, initializer, ..
@@ -10169,10 +11011,29 @@ impl<'ctx> CodeGen<'ctx> {
                 ..
             } => self.compile_if(condition, then_branch, else_branch.as_deref()),
 
+            Stmt::IfLet {
+                name,
+                value,
+                then_branch,
+                else_branch,
+                span,
+            } => {
+                let lowered =
+                    Stmt::lower_if_let(name, value, then_branch, else_branch.as_deref(), *span);
+                self.compile_stmt(&lowered)
+            }
+
             Stmt::While {
                 condition, body, ..
             } => self.compile_while(condition, body),
 
+            Stmt::For {
+                value_variable: Some(_),
+                ..
+            } => Err(HaversError::CompileError(
+                "fer k, v loops arenae supported in native builds yet".to_string(),
+            )),
+
             Stmt::For {
                 variable,
                 iterable,
@@ -10235,7 +11096,9 @@ impl<'ctx> CodeGen<'ctx> {
 
             Stmt::Struct { name, fields, .. } => self.compile_struct_decl(name, fields),
 
-	            Stmt::Import { path, alias, .. } => {
+	            Stmt::Import {
+	                path, alias, only, ..
+	            } => {
 	                let is_tri = path == "tri" || path == "tri.braw";
 	                if is_tri {
 	                    let alias_name = alias.as_deref().ok_or_else(|| {
@@ -10255,6 +11118,21 @@ impl<'ctx> CodeGen<'ctx> {
 	                }
 	                let before = self.capture_import_bindings();
 	                self.compile_import(path, alias.is_some())?;
+                // `bring ... frae` keeps only the names it asked for
+                if let Some(names) = only {
+                    let exports_all = self.collect_import_exports(path)?;
+                    if let Some(missing) = names.iter().find(|name| !exports_all.contains(name)) {
+                        return Err(HaversError::CompileError(format!(
+                            "There's nae '{}' in '{}' tae bring",
+                            missing, path
+                        )));
+                    }
+                    let unpicked: Vec<String> = exports_all
+                        .into_iter()
+                        .filter(|name| !names.contains(name))
+                        .collect();
+                    self.hide_imported_exports(&unpicked, &before);
+                }
                 if let Some(alias_name) = alias {
                     let exports_all = self.collect_import_exports(path)?;
                     let exports_public = exports_all.clone();
@@ -10293,6 +11171,10 @@ impl<'ctx> CodeGen<'ctx> {
                 ..
             } => self.compile_try_catch(try_block, error_name, catch_block),
 
+            Stmt::Yield { .. } => Err(HaversError::CompileError(
+                "gie_back (generators) isnae supported in native builds yet".to_string(),
+            )),
+
             Stmt::Hurl { message, .. } => {
                 let msg = self.compile_expr(message)?;
                 self.builder
@@ -10311,46 +11193,56 @@ impl<'ctx> CodeGen<'ctx> {
                 if matches!(level, LogLevel::Wheesht) {
                     return Ok(());
                 }
-                let level_val =
-                    self.make_int(self.types.i64_type.const_int(*level as u64, false)).unwrap();
-                let msg_val = self.compile_expr(message)?;
-                let fields_val = if let Some(extra) = extras.first() {
-                    self.compile_expr(extra)?
-                } else {
-                    self.make_nil()
-                };
-                let target_val = if let Some(extra) = extras.get(1) {
-                    self.compile_expr(extra)?
-                } else {
-                    self.make_nil()
-                };
-                let file_str = if let Some(path) = &self.source_path {
-                    path.to_string_lossy().to_string()
-                } else {
-                    String::new()
-                };
-                let file_val = self.compile_string_literal(&file_str).unwrap();
-                let line_val =
-                    self.make_int(self.types.i64_type.const_int(span.line as u64, false)).unwrap();
-                self.builder
-                    .build_call(
-                        self.libc.log_event,
-                        &[
-                            level_val.into(),
-                            msg_val.into(),
-                            fields_val.into(),
-                            target_val.into(),
-                            file_val.into(),
-                            line_val.into(),
-                        ],
-                        "",
-                    )
-                    .unwrap();
-                Ok(())
+                self.compile_log_event(*level, message, extras, span.line)
             }
         }
     }
 
+    /// Pass a log line to `__mdh_log_event` - shared by the log_* statements
+    /// and the log_debug/log_info/log_warn/log_error builtins
+    fn compile_log_event(
+        &mut self,
+        level: LogLevel,
+        message: &Expr,
+        extras: &[Expr],
+        line: usize,
+    ) -> Result<(), HaversError> {
+        let level_val = self.make_int(self.types.i64_type.const_int(level as u64, false)).unwrap();
+        let msg_val = self.compile_expr(message)?;
+        let fields_val = if let Some(extra) = extras.first() {
+            self.compile_expr(extra)?
+        } else {
+            self.make_nil()
+        };
+        let target_val = if let Some(extra) = extras.get(1) {
+            self.compile_expr(extra)?
+        } else {
+            self.make_nil()
+        };
+        let file_str = if let Some(path) = &self.source_path {
+            path.to_string_lossy().to_string()
+        } else {
+            String::new()
+        };
+        let file_val = self.compile_string_literal(&file_str).unwrap();
+        let line_val = self.make_int(self.types.i64_type.const_int(line as u64, false)).unwrap();
+        self.builder
+            .build_call(
+                self.libc.log_event,
+                &[
+                    level_val.into(),
+                    msg_val.into(),
+                    fields_val.into(),
+                    target_val.into(),
+                    file_val.into(),
+                    line_val.into(),
+                ],
+                "",
+            )
+            .unwrap();
+        Ok(())
+    }
+
     // ========== Expression Compilation ==========
 
     fn compile_expr(&mut self, expr: &Expr) -> Result<BasicValueEnum<'ctx>, HaversError> {
@@ -10870,6 +11762,7 @@ impl<'ctx> CodeGen<'ctx> {
                 start,
                 end,
                 inclusive,
+                step,
                 ..
             } => {
                 // Materialize range values as a list so ranges can be stored/indexed.
@@ -10879,15 +11772,27 @@ impl<'ctx> CodeGen<'ctx> {
                 let start_i64 = self.coerce_i64(start_val, "range")?;
                 let end_i64 = self.coerce_i64(end_val, "range")?;
 
-                // Default step is 1
-                let step = self.types.i64_type.const_int(1, false);
+                // Signed step: negative when the range counts down
+                let step = self.compile_range_delta(start_i64, end_i64, step.as_deref())?;
 
                 // Interpreter semantics: inclusive ranges yield the end value too.
-                // Our runtime range helper is end-exclusive, so translate `..=` as end+1.
+                // Our runtime range helper is end-exclusive, so translate `..=` as
+                // one past the end in the direction of travel.
                 let end_for_call = if *inclusive {
                     let one = self.types.i64_type.const_int(1, false);
+                    let minus_one = self.types.i64_type.const_all_ones();
+                    let zero = self.types.i64_type.const_int(0, false);
+                    let is_descending = self
+                        .builder
+                        .build_int_compare(IntPredicate::SLT, step, zero, "range_is_desc")
+                        .unwrap();
+                    let nudge = self
+                        .builder
+                        .build_select(is_descending, minus_one, one, "range_end_nudge")
+                        .unwrap()
+                        .into_int_value();
                     self.builder
-                        .build_int_add(end_i64, one, "range_end_inclusive")
+                        .build_int_add(end_i64, nudge, "range_end_inclusive")
                         .unwrap()
                 } else {
                     end_i64
@@ -10934,7 +11839,7 @@ impl<'ctx> CodeGen<'ctx> {
                 Ok(result)
             }
 
-            Expr::Lambda { params, body, .. } => self.compile_lambda(params, body),
+            Expr::Lambda { params, body, span } => self.compile_lambda(params, body, *span),
 
             Expr::Masel { .. } => self.compile_masel(),
 
@@ -10970,9 +11875,88 @@ impl<'ctx> CodeGen<'ctx> {
             } => self.compile_slice_expr(object, start.as_ref(), end.as_ref(), step.as_ref()),
 
             Expr::BlockExpr { statements, .. } => self.compile_block_expr(statements),
+
+            Expr::ListComp {
+                element,
+                clauses,
+                span,
+            } => {
+                // Lower to loops that shove into a fresh list, then read it back
+                let result = format!("__comp_{}", self.lambda_counter);
+                self.lambda_counter += 1;
+                for stmt in Self::comprehension_stmts(&result, element, clauses, *span) {
+                    self.compile_stmt(&stmt)?;
+                }
+                self.compile_expr(&Expr::Variable {
+                    name: result,
+                    span: *span,
+                })
+            }
         }
     }
 
+    /// The statements a list comprehension stands for: `ken result = []`, then
+    /// a `fer` loop per `fer` clause and a `gin` per guard, nested in order,
+    /// with `shove(result, element)` at the heart
+    fn comprehension_stmts(
+        result: &str,
+        element: &Expr,
+        clauses: &[CompClause],
+        span: Span,
+    ) -> Vec<Stmt> {
+        let mut body = Stmt::Expression {
+            expr: Expr::Call {
+                callee: Box::new(Expr::Variable {
+                    name: "shove".to_string(),
+                    span,
+                }),
+                arguments: vec![
+                    Expr::Variable {
+                        name: result.to_string(),
+                        span,
+                    },
+                    element.clone(),
+                ],
+                span,
+            },
+            span,
+        };
+        for clause in clauses.iter().rev() {
+            body = match clause {
+                CompClause::For { variable, iterable } => Stmt::For {
+                    variable: variable.clone(),
+                    value_variable: None,
+                    iterable: iterable.clone(),
+                    body: Box::new(Stmt::Block {
+                        statements: vec![body],
+                        span,
+                    }),
+                    span,
+                },
+                CompClause::If(condition) => Stmt::If {
+                    condition: condition.clone(),
+                    then_branch: Box::new(Stmt::Block {
+                        statements: vec![body],
+                        span,
+                    }),
+                    else_branch: None,
+                    span,
+                },
+            };
+        }
+        vec![
+            Stmt::VarDecl {
+                name: result.to_string(),
+                initializer: Some(Expr::List {
+                    elements: Vec::new(),
+                    span,
+                }),
+                span,
+            },
+            body,
+        ]
+    }
+
     fn compile_literal(&mut self, literal: &Literal) -> Result<BasicValueEnum<'ctx>, HaversError> {
         match literal {
             Literal::Nil => Ok(self.make_nil()),
@@ -11019,7 +12003,12 @@ impl<'ctx> CodeGen<'ctx> {
                 | BinaryOp::Subtract
                 | BinaryOp::Multiply
                 | BinaryOp::Divide
-                | BinaryOp::Modulo => {
+                | BinaryOp::Modulo
+                | BinaryOp::BitAnd
+                | BinaryOp::BitOr
+                | BinaryOp::BitXor
+                | BinaryOp::ShiftLeft
+                | BinaryOp::ShiftRight => {
                     return self.compile_binary_int_fast(left, op, right);
                 }
                 _ => {} // Comparisons already optimized via compile_condition_direct
@@ -11042,12 +12031,23 @@ impl<'ctx> CodeGen<'ctx> {
             BinaryOp::Multiply => self.inline_mul(left_val, right_val),
             BinaryOp::Divide => self.inline_div(left_val, right_val),
             BinaryOp::Modulo => self.inline_mod(left_val, right_val),
+            BinaryOp::Power => self.inline_power(left_val, right_val),
             BinaryOp::Equal => self.inline_eq(left_val, right_val),
             BinaryOp::NotEqual => self.inline_ne(left_val, right_val),
             BinaryOp::Less => self.inline_lt(left_val, right_val),
             BinaryOp::LessEqual => self.inline_le(left_val, right_val),
             BinaryOp::Greater => self.inline_gt(left_val, right_val),
             BinaryOp::GreaterEqual => self.inline_ge(left_val, right_val),
+            BinaryOp::In => self.inline_contains(right_val, left_val),
+            BinaryOp::NotIn => {
+                let found = self.inline_contains(right_val, left_val)?;
+                self.inline_not(found)
+            }
+            BinaryOp::BitAnd
+            | BinaryOp::BitOr
+            | BinaryOp::BitXor
+            | BinaryOp::ShiftLeft
+            | BinaryOp::ShiftRight => self.inline_bitwise(op, left_val, right_val),
         }
     }
 
@@ -11075,18 +12075,9 @@ impl<'ctx> CodeGen<'ctx> {
 
         // Perform operation directly on i64
         let result = match op {
-            BinaryOp::Add => self
-                .builder
-                .build_int_add(left_data, right_data, "add_fast")
-                .unwrap(),
-            BinaryOp::Subtract => self
-                .builder
-                .build_int_sub(left_data, right_data, "sub_fast")
-                .unwrap(),
-            BinaryOp::Multiply => self
-                .builder
-                .build_int_mul(left_data, right_data, "mul_fast")
-                .unwrap(),
+            BinaryOp::Add => self.build_arith_i64(op, left_data, right_data, "add_fast"),
+            BinaryOp::Subtract => self.build_arith_i64(op, left_data, right_data, "sub_fast"),
+            BinaryOp::Multiply => self.build_arith_i64(op, left_data, right_data, "mul_fast"),
 	            BinaryOp::Divide | BinaryOp::Modulo => {
                 // Prevent SIGFPE traps and allow try/catch to handle the error.
                 let function = self.current_function.unwrap();
@@ -11135,6 +12126,11 @@ impl<'ctx> CodeGen<'ctx> {
                 phi.add_incoming(&[(&zero_result, zero_end), (&ok_result, ok_end)]);
 	                phi.as_basic_value().into_int_value()
 	            }
+            BinaryOp::BitAnd
+            | BinaryOp::BitOr
+            | BinaryOp::BitXor
+            | BinaryOp::ShiftLeft
+            | BinaryOp::ShiftRight => self.build_bitwise_i64(op, left_data, right_data),
 	            _ => {
 	                return Err(HaversError::CompileError(
 	                    "compile_binary_int_fast called with non-int op".to_string(),
@@ -12030,6 +13026,17 @@ impl<'ctx> CodeGen<'ctx> {
                         .compile_expr(&args[0])
                         .and_then(|arg| self.inline_tae_string(arg));
                 }
+                // tae_int(s, base) reads the string in that base, like frae_base
+                "tae_int" if args.len() == 2 => {
+                    return self.compile_runtime_call_value_with_arity_call_name(
+                        self.libc.frae_base,
+                        args,
+                        2,
+                        "tae_int",
+                        "tae_int_base_result",
+                        "frae_base returned void",
+                    );
+                }
                 "tae_int" | "tae_nummer" | "parse_int" | "to_int" | "int" => {
                     if args.len() != 1 {
                         return Err(HaversError::CompileError(
@@ -12345,19 +13352,40 @@ impl<'ctx> CodeGen<'ctx> {
                     );
                 }
                 "dns_srv" => {
-                    return self.compile_runtime_call_value_with_arity(
+                    if args.len() < 2 || args.len() > 3 {
+                        return Err(HaversError::CompileError(
+                            "dns_srv expects 2 or 3 arguments".to_string(),
+                        ));
+                    }
+                    let service = self.compile_expr(&args[0])?;
+                    let domain = self.compile_expr(&args[1])?;
+                    let timeout_ms = if args.len() == 3 {
+                        self.compile_expr(&args[2])?
+                    } else {
+                        self.make_nil()
+                    };
+                    return self.build_call_basic_value(
                         self.libc.dns_srv,
-                        args,
-                        2,
+                        &[service.into(), domain.into(), timeout_ms.into()],
                         "dns_srv",
                         "dns_srv returned void",
                     );
                 }
                 "dns_naptr" => {
-                    return self.compile_runtime_call_value_with_arity(
+                    if args.is_empty() || args.len() > 2 {
+                        return Err(HaversError::CompileError(
+                            "dns_naptr expects 1 or 2 arguments".to_string(),
+                        ));
+                    }
+                    let domain = self.compile_expr(&args[0])?;
+                    let timeout_ms = if args.len() == 2 {
+                        self.compile_expr(&args[1])?
+                    } else {
+                        self.make_nil()
+                    };
+                    return self.build_call_basic_value(
                         self.libc.dns_naptr,
-                        args,
-                        1,
+                        &[domain.into(), timeout_ms.into()],
                         "dns_naptr",
                         "dns_naptr returned void",
                     );
@@ -12380,6 +13408,24 @@ impl<'ctx> CodeGen<'ctx> {
                         "tls_connect returned void",
                     );
                 }
+                "tls_connect_start" => {
+                    return self.compile_runtime_call_value_with_arity(
+                        self.libc.tls_connect_start,
+                        args,
+                        2,
+                        "tls_connect_start",
+                        "tls_connect_start returned void",
+                    );
+                }
+                "tls_handshake_step" => {
+                    return self.compile_runtime_call_value_with_arity(
+                        self.libc.tls_handshake_step,
+                        args,
+                        1,
+                        "tls_handshake_step",
+                        "tls_handshake_step returned void",
+                    );
+                }
                 "tls_send" => {
                     return self.compile_runtime_call_value_with_arity(
                         self.libc.tls_send,
@@ -12452,6 +13498,15 @@ impl<'ctx> CodeGen<'ctx> {
                         "srtp_unprotect returned void",
                     );
                 }
+                "srtp_set_roc" => {
+                    return self.compile_runtime_call_value_with_arity(
+                        self.libc.srtp_set_roc,
+                        args,
+                        3,
+                        "srtp_set_roc",
+                        "srtp_set_roc returned void",
+                    );
+                }
                 "event_loop_new" => {
                     return self.compile_runtime_call_value_with_arity(
                         self.libc.event_loop_new,
@@ -13047,16 +14102,16 @@ impl<'ctx> CodeGen<'ctx> {
                             "clamp expects 3 arguments (value, min, max)".to_string(),
                         ));
                     }
-                    // clamp(x, min, max) = min(max(x, min_val), max_val)
-                    return self.compile_expr(&args[0]).and_then(|val| {
-                        self.compile_expr(&args[1]).and_then(|min_val| {
-                            self.compile_expr(&args[2]).and_then(|max_val| {
-                                self.inline_max(val, min_val).and_then(|clamped_low| {
-                                    self.inline_min(clamped_low, max_val)
-                                })
-                            })
-                        })
-                    });
+                    // The runtime checks min <= max and keeps ints as ints
+                    let val = self.compile_expr(&args[0])?;
+                    let min_val = self.compile_expr(&args[1])?;
+                    let max_val = self.compile_expr(&args[2])?;
+                    return self.build_call_basic_value(
+                        self.libc.clamp,
+                        &[val.into(), min_val.into(), max_val.into()],
+                        "clamp_result",
+                        "clamp returned void",
+                    );
                 }
                 "floor" => {
                     if args.len() != 1 {
@@ -13536,6 +14591,36 @@ impl<'ctx> CodeGen<'ctx> {
                         "is_dir returned void",
                     );
                 }
+                "is_file" => {
+                    return self.compile_runtime_call_value_with_arity_call_name(
+                        self.libc.is_file,
+                        args,
+                        1,
+                        "is_file",
+                        "is_file_result",
+                        "is_file returned void",
+                    );
+                }
+                "read_dir" => {
+                    return self.compile_runtime_call_value_with_arity_call_name(
+                        self.libc.read_dir,
+                        args,
+                        1,
+                        "read_dir",
+                        "read_dir_result",
+                        "read_dir returned void",
+                    );
+                }
+                "walk_dir" => {
+                    return self.compile_runtime_call_value_with_arity_call_name(
+                        self.libc.walk_dir,
+                        args,
+                        1,
+                        "walk_dir",
+                        "walk_dir_result",
+                        "walk_dir returned void",
+                    );
+                }
                 "slurp" => {
                     return self.compile_runtime_call_value_with_arity_call_name(
                         self.libc.slurp,
@@ -13708,6 +14793,16 @@ impl<'ctx> CodeGen<'ctx> {
                         "date_format returned void",
                     );
                 }
+                "format_time" => {
+                    return self.compile_runtime_call_value_with_arity_call_name(
+                        self.libc.format_time,
+                        args,
+                        2,
+                        "format_time",
+                        "format_time_result",
+                        "format_time returned void",
+                    );
+                }
                 "date_parse" => {
                     return self.compile_runtime_call_value_with_arity_call_name(
                         self.libc.date_parse,
@@ -13819,6 +14914,167 @@ impl<'ctx> CodeGen<'ctx> {
                         )
                     });
                 }
+                // Digests
+                "sha256" => {
+                    return self.compile_runtime_call_value_with_arity(
+                        self.libc.sha256,
+                        args,
+                        1,
+                        "sha256",
+                        "sha256 returned void",
+                    );
+                }
+                "sha1" => {
+                    return self.compile_runtime_call_value_with_arity(
+                        self.libc.sha1,
+                        args,
+                        1,
+                        "sha1",
+                        "sha1 returned void",
+                    );
+                }
+                "md5" => {
+                    return self.compile_runtime_call_value_with_arity(
+                        self.libc.md5,
+                        args,
+                        1,
+                        "md5",
+                        "md5 returned void",
+                    );
+                }
+                "sha256_hex" => {
+                    return self.compile_runtime_call_value_with_arity(
+                        self.libc.sha256_hex,
+                        args,
+                        1,
+                        "sha256_hex",
+                        "sha256_hex returned void",
+                    );
+                }
+                "sha1_hex" => {
+                    return self.compile_runtime_call_value_with_arity(
+                        self.libc.sha1_hex,
+                        args,
+                        1,
+                        "sha1_hex",
+                        "sha1_hex returned void",
+                    );
+                }
+                "md5_hex" => {
+                    return self.compile_runtime_call_value_with_arity(
+                        self.libc.md5_hex,
+                        args,
+                        1,
+                        "md5_hex",
+                        "md5_hex returned void",
+                    );
+                }
+                "hmac_sha256" => {
+                    return self.compile_runtime_call_value_with_arity(
+                        self.libc.hmac_sha256,
+                        args,
+                        2,
+                        "hmac_sha256",
+                        "hmac_sha256 returned void",
+                    );
+                }
+                "hmac_sha256_hex" => {
+                    return self.compile_runtime_call_value_with_arity(
+                        self.libc.hmac_sha256_hex,
+                        args,
+                        2,
+                        "hmac_sha256_hex",
+                        "hmac_sha256_hex returned void",
+                    );
+                }
+                "ct_eq" => {
+                    return self.compile_runtime_call_value_with_arity(
+                        self.libc.ct_eq,
+                        args,
+                        2,
+                        "ct_eq",
+                        "ct_eq returned void",
+                    );
+                }
+                "random_bytes" => {
+                    return self.compile_runtime_call_value_with_arity(
+                        self.libc.random_bytes,
+                        args,
+                        1,
+                        "random_bytes",
+                        "random_bytes returned void",
+                    );
+                }
+                "random_hex" => {
+                    return self.compile_runtime_call_value_with_arity(
+                        self.libc.random_hex,
+                        args,
+                        1,
+                        "random_hex",
+                        "random_hex returned void",
+                    );
+                }
+                "pbkdf2" => {
+                    return self.compile_runtime_call_value_with_arity(
+                        self.libc.pbkdf2,
+                        args,
+                        4,
+                        "pbkdf2",
+                        "pbkdf2 returned void",
+                    );
+                }
+                // Hex helpers
+                "hex_encode" => {
+                    return self.compile_runtime_call_value_with_arity(
+                        self.libc.hex_encode,
+                        args,
+                        1,
+                        "hex_encode",
+                        "hex_encode returned void",
+                    );
+                }
+                "hex_decode" => {
+                    return self.compile_runtime_call_value_with_arity(
+                        self.libc.hex_decode,
+                        args,
+                        1,
+                        "hex_decode",
+                        "hex_decode returned void",
+                    );
+                }
+                "hexdump" => {
+                    return self.compile_runtime_call_value_with_arity(
+                        self.libc.hexdump,
+                        args,
+                        1,
+                        "hexdump",
+                        "hexdump returned void",
+                    );
+                }
+                "fmt_float" => {
+                    return self.compile_runtime_call_value_with_arity(
+                        self.libc.fmt_float,
+                        args,
+                        2,
+                        "fmt_float",
+                        "fmt_float returned void",
+                    );
+                }
+                "format" => {
+                    let Some((template, values)) = args.split_first() else {
+                        return Err(HaversError::CompileError(
+                            "format expects a template string".to_string(),
+                        ));
+                    };
+                    let template = self.compile_expr(template)?;
+                    let values = self.compile_list(values)?;
+                    return self.build_call_basic_value(
+                        self.libc.format,
+                        &[template.into(), values.into()],
+                        "format",
+                        "format returned void",
+                    );
+                }
                 // Misc parity helpers
                 "is_a" => {
                     return self.compile_runtime_call_value_with_arity_call_name(
@@ -14361,6 +15617,26 @@ impl<'ctx> CodeGen<'ctx> {
                         "pair_up returned void",
                     );
                 }
+                "tae_base" => {
+                    return self.compile_runtime_call_value_with_arity_call_name(
+                        self.libc.tae_base,
+                        args,
+                        2,
+                        "tae_base",
+                        "tae_base_result",
+                        "tae_base returned void",
+                    );
+                }
+                "frae_base" => {
+                    return self.compile_runtime_call_value_with_arity_call_name(
+                        self.libc.frae_base,
+                        args,
+                        2,
+                        "frae_base",
+                        "frae_base_result",
+                        "frae_base returned void",
+                    );
+                }
                 "tae_binary" | "to_binary" => {
                     return self.compile_runtime_call_value_with_arity_call_name(
                         self.libc.tae_binary,
@@ -14405,14 +15681,26 @@ impl<'ctx> CodeGen<'ctx> {
                 }
                 // Testing builtins
                 "assert" => {
-                    // Interpreter: assert(condition, msg) -> aye (or aborts).
-                    if args.len() != 2 {
+                    // Interpreter: assert(condition, msg?) -> aye, or raises an error.
+                    if args.is_empty() || args.len() > 2 {
                         return Err(HaversError::CompileError(
-                            "assert expects 2 arguments".to_string(),
+                            "assert expects 1 or 2 arguments".to_string(),
                         ));
                     }
                     let cond = self.compile_expr(&args[0])?;
-                    let msg = self.compile_expr(&args[1])?;
+                    let msg = match args.get(1) {
+                        Some(msg) => self.compile_expr(msg)?,
+                        None => {
+                            let default = self
+                                .builder
+                                .build_global_string_ptr(
+                                    "the condition wisnae aye",
+                                    "assert_default",
+                                )
+                                .unwrap();
+                            self.make_string(default.as_pointer_value())?
+                        }
+                    };
 
                     let truthy = self.is_truthy(cond).unwrap();
                     let cond_bool = self.make_bool(truthy).unwrap();
@@ -14432,6 +15720,27 @@ impl<'ctx> CodeGen<'ctx> {
                     let one = self.types.i64_type.const_int(1, false);
                     return self.make_bool(one);
                 }
+                "assert_eq" => {
+                    if args.len() != 2 {
+                        return Err(HaversError::CompileError(
+                            "assert_eq expects 2 arguments".to_string(),
+                        ));
+                    }
+                    let actual = self.compile_expr(&args[0])?;
+                    let expected = self.compile_expr(&args[1])?;
+                    let result = self
+                        .builder
+                        .build_call(
+                            self.libc.assert_eq,
+                            &[actual.into(), expected.into()],
+                            "assert_eq_call",
+                        )
+                        .unwrap()
+                        .try_as_basic_value()
+                        .left()
+                        .compile_ok_or("assert_eq returned void").unwrap();
+                    return Ok(result);
+                }
                 "skip" => {
                     if args.len() != 1 {
                         return Err(HaversError::CompileError(
@@ -15177,17 +16486,17 @@ impl<'ctx> CodeGen<'ctx> {
                     return Ok(result);
                 }
                 "range" => {
-                    if args.len() != 2 {
+                    if args.len() != 2 && args.len() != 3 {
                         return Err(HaversError::CompileError(
-                            "range expects 2 arguments".to_string(),
+                            "range expects 2 or 3 arguments".to_string(),
                         ));
                     }
                     let start = self.compile_expr(&args[0])?;
                     let end = self.compile_expr(&args[1])?;
-                    let step = self.types.i64_type.const_int(1, false);
                     // Use runtime function
                     let start_i64 = self.coerce_i64(start, "range")?;
                     let end_i64 = self.coerce_i64(end, "range")?;
+                    let step = self.compile_range_delta(start_i64, end_i64, args.get(2))?;
                     let result = self
                         .builder
                         .build_call(
@@ -15218,6 +16527,34 @@ impl<'ctx> CodeGen<'ctx> {
                     }
                     return self.inline_tick();
                 }
+                "now" => {
+                    if !args.is_empty() {
+                        return Err(HaversError::CompileError(
+                            "now expects 0 arguments".to_string(),
+                        ));
+                    }
+                    // now() - seconds since epoch as a float, from the nanosecond clock
+                    let ns_val = self.inline_tick()?;
+                    let ns_data = self.extract_data(ns_val).unwrap();
+                    let ns_float = self
+                        .builder
+                        .build_signed_int_to_float(ns_data, self.types.f64_type, "now_ns")
+                        .unwrap();
+                    let billion = self.types.f64_type.const_float(1_000_000_000.0);
+                    let secs = self
+                        .builder
+                        .build_float_div(ns_float, billion, "now_secs")
+                        .unwrap();
+                    return self.make_float(secs);
+                }
+                "now_ms" => {
+                    if !args.is_empty() {
+                        return Err(HaversError::CompileError(
+                            "now_ms expects 0 arguments".to_string(),
+                        ));
+                    }
+                    return self.inline_noo();
+                }
                 "mono_ms" => {
                     if !args.is_empty() {
                         return Err(HaversError::CompileError(
@@ -15876,6 +17213,16 @@ impl<'ctx> CodeGen<'ctx> {
                         .compile_ok_or("random_int returned void").unwrap();
                     return Ok(result);
                 }
+                "seed_random" => {
+                    return self.compile_runtime_call_value_with_arity_call_name(
+                        self.libc.seed_random,
+                        args,
+                        1,
+                        "seed_random",
+                        "seed_random_result",
+                        "seed_random returned void",
+                    );
+                }
                 "random" => {
                     if !args.is_empty() {
                         return Err(HaversError::CompileError(
@@ -16172,6 +17519,16 @@ impl<'ctx> CodeGen<'ctx> {
                     let x_arg = self.compile_expr(&args[1])?;
                     return self.inline_atan2(y_arg, x_arg);
                 }
+                "fmod" => {
+                    if args.len() != 2 {
+                        return Err(HaversError::CompileError(
+                            "fmod expects 2 arguments".to_string(),
+                        ));
+                    }
+                    let a_arg = self.compile_expr(&args[0])?;
+                    let b_arg = self.compile_expr(&args[1])?;
+                    return self.inline_fmod(a_arg, b_arg);
+                }
                 "asin" => {
                     if args.len() != 1 {
                         return Err(HaversError::CompileError(
@@ -17194,6 +18551,28 @@ impl<'ctx> CodeGen<'ctx> {
 
                     return self.make_list(new_struct);
                 }
+                "shaw" | "shaw_pretty" => {
+                    // shaw(val) - print a type-tagged dump built by the runtime
+                    if args.len() != 1 {
+                        return Err(HaversError::CompileError(format!(
+                            "{} expects 1 argument",
+                            name
+                        )));
+                    }
+                    let arg = self.compile_expr(&args[0])?;
+                    let pretty = self
+                        .context
+                        .i64_type()
+                        .const_int((name.as_str() == "shaw_pretty") as u64, false);
+                    let text = self.build_call_basic_value(
+                        self.libc.shaw,
+                        &[arg.into(), pretty.into()],
+                        "shaw_result",
+                        "shaw returned void",
+                    )?;
+                    self.inline_blether(text)?;
+                    return Ok(self.make_nil());
+                }
                 "inspect" | "debug" => {
                     // inspect(val) - print debug info about value
                     if args.len() != 1 {
@@ -17247,12 +18626,26 @@ impl<'ctx> CodeGen<'ctx> {
                     return Ok(result);
                 }
                 "json_pretty" | "json_stringify_pretty" => {
-                    if args.len() != 1 {
+                    if args.is_empty() || args.len() > 3 {
                         return Err(HaversError::CompileError(
-                            "json_pretty expects 1 argument".to_string(),
+                            "json_pretty expects 1 tae 3 arguments".to_string(),
                         ));
                     }
                     let value = self.compile_expr(&args[0])?;
+                    if args.len() > 1 {
+                        // A chosen indent (and maybe sorted keys) goes through the options entry point
+                        let indent = self.compile_expr(&args[1])?;
+                        let sort_keys = match args.get(2) {
+                            Some(sort_keys) => self.compile_expr(sort_keys)?,
+                            None => self.make_nil(),
+                        };
+                        return self.build_call_basic_value(
+                            self.libc.json_stringify_opts,
+                            &[value.into(), indent.into(), sort_keys.into()],
+                            "json_pretty_result",
+                            "json_pretty returned void",
+                        );
+                    }
                     let result = self
                         .builder
                         .build_call(self.libc.json_pretty, &[value.into()], "json_pretty_result")
@@ -17262,6 +18655,53 @@ impl<'ctx> CodeGen<'ctx> {
                         .compile_ok_or("json_pretty returned void").unwrap();
                     return Ok(result);
                 }
+                "json_query" => {
+                    return self.compile_runtime_call_value_with_arity(
+                        self.libc.json_query,
+                        args,
+                        2,
+                        "json_query",
+                        "json_query returned void",
+                    );
+                }
+                "csv_parse" | "csv_write" => {
+                    if args.is_empty() || args.len() > 2 {
+                        return Err(HaversError::CompileError(format!(
+                            "{} expects 1 or 2 arguments",
+                            name
+                        )));
+                    }
+                    let first = self.compile_expr(&args[0])?;
+                    let delim = match args.get(1) {
+                        Some(delim) => self.compile_expr(delim)?,
+                        None => self.make_nil(),
+                    };
+                    let func = if name == "csv_parse" {
+                        self.libc.csv_parse
+                    } else {
+                        self.libc.csv_write
+                    };
+                    return self.build_call_basic_value(
+                        func,
+                        &[first.into(), delim.into()],
+                        name,
+                        "csv returned void",
+                    );
+                }
+                "uuid_v4" | "uuid_v7" => {
+                    if !args.is_empty() {
+                        return Err(HaversError::CompileError(format!(
+                            "{} takes nae arguments",
+                            name
+                        )));
+                    }
+                    let func = if name == "uuid_v4" {
+                        self.libc.uuid_v4
+                    } else {
+                        self.libc.uuid_v7
+                    };
+                    return self.build_call_basic_value(func, &[], name, "uuid returned void");
+                }
                 "template_render" => {
                     // template_render(template, ctx) - render template with context (placeholder)
                     if args.len() != 2 {
@@ -18082,36 +19522,19 @@ impl<'ctx> CodeGen<'ctx> {
                     return Ok(result.as_basic_value());
                 }
                 "sign" | "signum" => {
-                    // sign(n) - return -1, 0, or 1
+                    // sign(n) - return -1, 0, or 1 (a float for a float)
                     if args.len() != 1 {
                         return Err(HaversError::CompileError(
                             "sign expects 1 argument".to_string(),
                         ));
                     }
                     let arg = self.compile_expr(&args[0])?;
-                    let data = self.extract_data(arg).unwrap();
-                    let zero = self.types.i64_type.const_int(0, false);
-                    let is_neg = self
-                        .builder
-                        .build_int_compare(inkwell::IntPredicate::SLT, data, zero, "is_neg")
-                        .unwrap();
-                    let is_pos = self
-                        .builder
-                        .build_int_compare(inkwell::IntPredicate::SGT, data, zero, "is_pos")
-                        .unwrap();
-                    let neg_one = self.types.i64_type.const_int((-1i64) as u64, true);
-                    let one = self.types.i64_type.const_int(1, false);
-                    let tmp = self
-                        .builder
-                        .build_select(is_pos, one, zero, "sign_tmp")
-                        .unwrap()
-                        .into_int_value();
-                    let result = self
-                        .builder
-                        .build_select(is_neg, neg_one, tmp, "sign_result")
-                        .unwrap()
-                        .into_int_value();
-                    return self.make_int(result);
+                    return self.build_call_basic_value(
+                        self.libc.sign,
+                        &[arg.into()],
+                        "sign_result",
+                        "sign returned void",
+                    );
                 }
                 "glaikit" | "silly" => {
                     if args.len() != 1 {
@@ -18378,7 +19801,7 @@ impl<'ctx> CodeGen<'ctx> {
                         .compile_ok_or("wrang_sort returned void").unwrap();
                     return Ok(result);
                 }
-                "tae_octal" | "to_octal" => {
+                "tae_octal" | "tae_oct" | "to_octal" => {
                     // tae_octal(n) - convert to octal string
                     if args.len() != 1 {
                         return Err(HaversError::CompileError(
@@ -18708,14 +20131,25 @@ impl<'ctx> CodeGen<'ctx> {
                     return Ok(self.make_nil());
                 }
                 "dict_get" | "get" => {
-                    if args.len() != 3 {
-                        return Err(HaversError::CompileError(
-                            "dict_get expects 3 arguments".to_string(),
-                        ));
+                    // dict_get's default is optional and falls back to nil
+                    let arity_ok = if name == "dict_get" {
+                        args.len() == 2 || args.len() == 3
+                    } else {
+                        args.len() == 3
+                    };
+                    if !arity_ok {
+                        return Err(HaversError::CompileError(format!(
+                            "{} expects {} arguments",
+                            name,
+                            if name == "dict_get" { "2 or 3" } else { "3" }
+                        )));
                     }
                     let dict_val = self.compile_expr(&args[0])?;
                     let key_val = self.compile_expr(&args[1])?;
-                    let default_val = self.compile_expr(&args[2])?;
+                    let default_val = match args.get(2) {
+                        Some(arg) => self.compile_expr(arg)?,
+                        None => self.make_nil(),
+                    };
                     let result = self
                         .builder
                         .build_call(
@@ -19013,6 +20447,36 @@ impl<'ctx> CodeGen<'ctx> {
                         .compile_ok_or("scunner_check returned void").unwrap();
                     return Ok(result);
                 }
+                "dict_set" => {
+                    if args.len() != 3 {
+                        return Err(HaversError::CompileError(
+                            "dict_set expects 3 arguments".to_string(),
+                        ));
+                    }
+                    let dict_val = self.compile_expr(&args[0])?;
+                    let key_val = self.compile_expr(&args[1])?;
+                    let value_val = self.compile_expr(&args[2])?;
+                    let result = self
+                        .builder
+                        .build_call(
+                            self.libc.dict_set,
+                            &[dict_val.into(), key_val.into(), value_val.into()],
+                            "dict_set_result",
+                        )
+                        .unwrap()
+                        .try_as_basic_value()
+                        .left()
+                        .compile_ok_or("dict_set returned void").unwrap();
+                    // A new key can move the dict, so write it back to the variable
+                    if let Expr::Variable { name, .. } = &args[0] {
+                        if let Some(&ptr) = self.variables.get(name) {
+                            self.builder.build_store(ptr, result).unwrap();
+                        } else if let Some(&ptr) = self.globals.get(name) {
+                            self.builder.build_store(ptr, result).unwrap();
+                        }
+                    }
+                    return Ok(result);
+                }
                 "dict_remove" => {
                     if args.len() != 2 {
                         return Err(HaversError::CompileError(
@@ -19034,6 +20498,15 @@ impl<'ctx> CodeGen<'ctx> {
                         .compile_ok_or("dict_remove returned void").unwrap();
                     return Ok(result);
                 }
+                "remove" => {
+                    return self.compile_runtime_call_value_with_arity(
+                        self.libc.dict_pop,
+                        args,
+                        2,
+                        "remove",
+                        "dict_pop returned void",
+                    );
+                }
                 "scots_miles_tae_km" | "miles_to_km" => {
                     // Convert miles to km (1.609344)
                     if args.len() != 1 {
@@ -19220,10 +20693,20 @@ impl<'ctx> CodeGen<'ctx> {
                     return Ok(self.make_nil());
                 }
                 "log_debug" | "log_info" | "log_warn" | "log_error" => {
-                    // Logging functions: no-op (but still evaluate the argument for side effects)
-                    if !args.is_empty() {
-                        let _ = self.compile_expr(&args[0])?;
+                    // The same as the log_mutter/log_blether/log_holler/log_roar statements
+                    if args.is_empty() || args.len() > 3 {
+                        return Err(HaversError::CompileError(format!(
+                            "{} expects 1-3 arguments",
+                            name
+                        )));
                     }
+                    let level = match name.as_str() {
+                        "log_debug" => LogLevel::Mutter,
+                        "log_info" => LogLevel::Blether,
+                        "log_warn" => LogLevel::Holler,
+                        _ => LogLevel::Roar,
+                    };
+                    self.compile_log_event(level, &args[0], &args[1..], callee.span().line)?;
                     return Ok(self.make_nil());
                 }
                 "promise_new" | "promise_resolve" | "promise_reject" | "promise_then"
@@ -20726,7 +22209,7 @@ impl<'ctx> CodeGen<'ctx> {
                     return self.compile_string_literal("");
                 }
                 "lerp" | "linear_interpolate" => {
-                    // lerp(a, b, t) - linear interpolation: a + t * (b - a)
+                    // lerp(a, b, t) - a + (b - a) * t, always a float
                     if args.len() != 3 {
                         return Err(HaversError::CompileError(
                             "lerp expects 3 arguments (a, b, t)".to_string(),
@@ -20735,84 +22218,12 @@ impl<'ctx> CodeGen<'ctx> {
                     let a_val = self.compile_expr(&args[0])?;
                     let b_val = self.compile_expr(&args[1])?;
                     let t_val = self.compile_expr(&args[2])?;
-                    let a_tag = self.extract_tag(a_val).unwrap();
-                    let a_data = self.extract_data(a_val).unwrap();
-                    let b_data = self.extract_data(b_val).unwrap();
-                    let t_data = self.extract_data(t_val).unwrap();
-
-                    let float_tag = self.types.i8_type.const_int(3, false);
-                    let is_float = self
-                        .builder
-                        .build_int_compare(IntPredicate::EQ, a_tag, float_tag, "is_float")
-                        .unwrap();
-
-                    let function = self.current_function.unwrap();
-                    let float_block = self.context.append_basic_block(function, "lerp_float");
-                    let int_block = self.context.append_basic_block(function, "lerp_int");
-                    let done_block = self.context.append_basic_block(function, "lerp_done");
-
-                    self.builder
-                        .build_conditional_branch(is_float, float_block, int_block)
-                        .unwrap();
-
-                    // Float case: a + t * (b - a)
-                    self.builder.position_at_end(float_block);
-                    let a_f = self
-                        .builder
-                        .build_bitcast(a_data, self.types.f64_type, "a_f")
-                        .unwrap()
-                        .into_float_value();
-                    let b_f = self
-                        .builder
-                        .build_bitcast(b_data, self.types.f64_type, "b_f")
-                        .unwrap()
-                        .into_float_value();
-                    let t_f = self
-                        .builder
-                        .build_bitcast(t_data, self.types.f64_type, "t_f")
-                        .unwrap()
-                        .into_float_value();
-                    let diff_f = self.builder.build_float_sub(b_f, a_f, "diff_f").unwrap();
-                    let scaled_f = self
-                        .builder
-                        .build_float_mul(t_f, diff_f, "scaled_f")
-                        .unwrap();
-                    let result_f = self
-                        .builder
-                        .build_float_add(a_f, scaled_f, "result_f")
-                        .unwrap();
-                    let float_result = self.make_float(result_f).unwrap();
-                    self.builder.build_unconditional_branch(done_block).unwrap();
-                    let float_block_end = self.builder.get_insert_block().unwrap();
-
-                    // Int case: a + t * (b - a) as integers
-                    self.builder.position_at_end(int_block);
-                    let diff_i = self
-                        .builder
-                        .build_int_sub(b_data, a_data, "diff_i")
-                        .unwrap();
-                    let scaled_i = self
-                        .builder
-                        .build_int_mul(t_data, diff_i, "scaled_i")
-                        .unwrap();
-                    let result_i = self
-                        .builder
-                        .build_int_add(a_data, scaled_i, "result_i")
-                        .unwrap();
-                    let int_result = self.make_int(result_i).unwrap();
-                    self.builder.build_unconditional_branch(done_block).unwrap();
-                    let int_block_end = self.builder.get_insert_block().unwrap();
-
-                    self.builder.position_at_end(done_block);
-                    let phi = self
-                        .builder
-                        .build_phi(self.types.value_type, "lerp_result")
-                        .unwrap();
-                    phi.add_incoming(&[
-                        (&float_result, float_block_end),
-                        (&int_result, int_block_end),
-                    ]);
-                    return Ok(phi.as_basic_value());
+                    return self.build_call_basic_value(
+                        self.libc.lerp,
+                        &[a_val.into(), b_val.into(), t_val.into()],
+                        "lerp_result",
+                        "lerp returned void",
+                    );
                 }
                 "clamp_value" => {
                     // clamp_value(val, min, max) - clamp value between min and max
@@ -21350,10 +22761,11 @@ impl<'ctx> CodeGen<'ctx> {
             start,
             end,
             inclusive,
+            step,
             ..
         } = iterable
         {
-            return self.compile_for_range(variable, start, end, *inclusive, body);
+            return self.compile_for_range(variable, start, end, step.as_deref(), *inclusive, body);
         }
         // For-each loop over list or string (runtime check)
         self.compile_for_iterable(variable, iterable, body)
@@ -21664,11 +23076,80 @@ impl<'ctx> CodeGen<'ctx> {
         Ok(())
     }
 
+    /// Work out the signed per-iteration delta for a range. The direction comes
+    /// from the bounds (10..1 counts down); an explicit step only sets the stride,
+    /// and a zero step is an error just as in the interpreter.
+    fn compile_range_delta(
+        &mut self,
+        start: IntValue<'ctx>,
+        end: IntValue<'ctx>,
+        step: Option<&Expr>,
+    ) -> Result<IntValue<'ctx>, HaversError> {
+        let zero = self.types.i64_type.const_int(0, false);
+        let stride = match step {
+            Some(step_expr) => {
+                let step_val = self.compile_expr(step_expr)?;
+                let step_i64 = self.coerce_i64(step_val, "range step")?;
+
+                let function = self.current_function.unwrap();
+                let zero_block = self.context.append_basic_block(function, "range_step_zero");
+                let ok_block = self.context.append_basic_block(function, "range_step_ok");
+                let is_zero = self
+                    .builder
+                    .build_int_compare(IntPredicate::EQ, step_i64, zero, "range_step_is_zero")
+                    .unwrap();
+                self.builder
+                    .build_conditional_branch(is_zero, zero_block, ok_block)
+                    .unwrap();
+
+                self.builder.position_at_end(zero_block);
+                let msg = self
+                    .compile_string_literal("Range step cannae be zero")
+                    .unwrap();
+                let _ = self
+                    .builder
+                    .build_call(self.libc.hurl, &[msg.into()], "range_step_hurl")
+                    .unwrap();
+                self.builder.build_unconditional_branch(ok_block).unwrap();
+
+                self.builder.position_at_end(ok_block);
+                let negated = self
+                    .builder
+                    .build_int_neg(step_i64, "range_step_neg")
+                    .unwrap();
+                let is_negative = self
+                    .builder
+                    .build_int_compare(IntPredicate::SLT, step_i64, zero, "range_step_is_neg")
+                    .unwrap();
+                self.builder
+                    .build_select(is_negative, negated, step_i64, "range_stride")
+                    .unwrap()
+                    .into_int_value()
+            }
+            None => self.types.i64_type.const_int(1, false),
+        };
+
+        let is_descending = self
+            .builder
+            .build_int_compare(IntPredicate::SGT, start, end, "range_descending")
+            .unwrap();
+        let neg_stride = self
+            .builder
+            .build_int_neg(stride, "range_neg_stride")
+            .unwrap();
+        Ok(self
+            .builder
+            .build_select(is_descending, neg_stride, stride, "range_delta")
+            .unwrap()
+            .into_int_value())
+    }
+
     fn compile_for_range(
         &mut self,
         variable: &str,
         start: &Expr,
         end: &Expr,
+        step: Option<&Expr>,
         inclusive: bool,
         body: &Stmt,
     ) -> Result<(), HaversError> {
@@ -21679,6 +23160,12 @@ impl<'ctx> CodeGen<'ctx> {
 
         let start_data = self.coerce_i64(start_val, "range")?;
         let end_data = self.coerce_i64(end_val, "range")?;
+        let delta = self.compile_range_delta(start_data, end_data, step)?;
+        let zero = self.types.i64_type.const_int(0, false);
+        let is_descending = self
+            .builder
+            .build_int_compare(IntPredicate::SLT, delta, zero, "for_descending")
+            .unwrap();
 
         // Create loop variable
         let var_alloca = self.create_entry_block_alloca(variable);
@@ -21714,14 +23201,24 @@ impl<'ctx> CodeGen<'ctx> {
             .build_load(self.types.i64_type, counter_alloca, "current")
             .unwrap()
             .into_int_value();
-        let cmp = if inclusive {
-            self.builder
-                .build_int_compare(IntPredicate::SLE, current, end_data, "cmp")
+        let (up_pred, down_pred) = if inclusive {
+            (IntPredicate::SLE, IntPredicate::SGE)
         } else {
-            self.builder
-                .build_int_compare(IntPredicate::SLT, current, end_data, "cmp")
-        }
-        .unwrap();
+            (IntPredicate::SLT, IntPredicate::SGT)
+        };
+        let cmp_up = self
+            .builder
+            .build_int_compare(up_pred, current, end_data, "cmp_up")
+            .unwrap();
+        let cmp_down = self
+            .builder
+            .build_int_compare(down_pred, current, end_data, "cmp_down")
+            .unwrap();
+        let cmp = self
+            .builder
+            .build_select(is_descending, cmp_down, cmp_up, "cmp")
+            .unwrap()
+            .into_int_value();
         self.builder
             .build_conditional_branch(cmp, body_block, after_block)
             .unwrap();
@@ -21747,10 +23244,9 @@ impl<'ctx> CodeGen<'ctx> {
             .build_load(self.types.i64_type, counter_alloca, "current_incr")
             .unwrap()
             .into_int_value();
-        let one = self.types.i64_type.const_int(1, false);
         let next = self
             .builder
-            .build_int_add(current_in_incr, one, "next")
+            .build_int_add(current_in_incr, delta, "next")
             .unwrap();
         self.builder.build_store(counter_alloca, next).unwrap();
 
@@ -21775,6 +23271,8 @@ impl<'ctx> CodeGen<'ctx> {
             .get(name)
             .copied()
             .expect("Function not declared");
+        self.function_names
+            .push((function, name.to_string(), params.len()));
 
         let entry = self.context.append_basic_block(function, "entry");
 
@@ -21792,6 +23290,7 @@ impl<'ctx> CodeGen<'ctx> {
 
         self.builder.position_at_end(entry);
         self.current_function = Some(function);
+        let saved_debug_location = self.debug_enter_function(function);
         self.in_user_function = true;
         // Avoid leaking an outer method's `masel` into a nested function body; captured `masel`
         // will explicitly set this when present.
@@ -21919,6 +23418,7 @@ impl<'ctx> CodeGen<'ctx> {
 
         // Restore state - all shadow maps to prevent cross-function leakage
         self.current_function = saved_function;
+        self.debug_leave_function(saved_debug_location);
         self.variables = saved_variables;
         self.var_types = saved_var_types;
         self.int_shadows = saved_int_shadows;
@@ -21973,6 +23473,17 @@ impl<'ctx> CodeGen<'ctx> {
                     self.predeclare_locals_stmt(e)?;
                 }
             }
+            Stmt::IfLet {
+                name,
+                value,
+                then_branch,
+                else_branch,
+                span,
+            } => {
+                let lowered =
+                    Stmt::lower_if_let(name, value, then_branch, else_branch.as_deref(), *span);
+                self.predeclare_locals_stmt(&lowered)?;
+            }
             Stmt::While { body, .. } => {
                 self.predeclare_locals_stmt(body)?;
             }
@@ -25485,9 +26996,14 @@ impl<'ctx> CodeGen<'ctx> {
                     }
                 }
             }
-            Expr::Range { start, end, .. } => {
+            Expr::Range {
+                start, end, step, ..
+            } => {
                 self.collect_free_vars(start, bound, free);
                 self.collect_free_vars(end, bound, free);
+                if let Some(st) = step {
+                    self.collect_free_vars(st, bound, free);
+                }
             }
             Expr::Pipe { left, right, .. } => {
                 self.collect_free_vars(left, bound, free);
@@ -25509,6 +27025,17 @@ impl<'ctx> CodeGen<'ctx> {
                     self.collect_free_vars_stmt(stmt, &mut block_bound, free);
                 }
             }
+            Expr::ListComp {
+                element,
+                clauses,
+                span,
+            } => {
+                // The comprehension variables are bound by the loops it lowers to
+                let mut comp_bound = bound.clone();
+                for stmt in Self::comprehension_stmts("__comp", element, clauses, *span) {
+                    self.collect_free_vars_stmt(&stmt, &mut comp_bound, free);
+                }
+            }
             // Expressions without sub-expressions that don't reference variables
             Expr::Literal { .. } | Expr::Masel { .. } => {}
         }
@@ -25574,8 +27101,12 @@ impl<'ctx> CodeGen<'ctx> {
                     || end.as_ref().map_or(false, |e| self.expr_uses_masel(e))
                     || step.as_ref().map_or(false, |e| self.expr_uses_masel(e))
             }
-            Expr::Range { start, end, .. } => {
-                self.expr_uses_masel(start) || self.expr_uses_masel(end)
+            Expr::Range {
+                start, end, step, ..
+            } => {
+                self.expr_uses_masel(start)
+                    || self.expr_uses_masel(end)
+                    || step.as_ref().map_or(false, |e| self.expr_uses_masel(e))
             }
             Expr::Pipe { left, right, .. } => {
                 self.expr_uses_masel(left) || self.expr_uses_masel(right)
@@ -25592,6 +27123,16 @@ impl<'ctx> CodeGen<'ctx> {
             Expr::BlockExpr { statements, .. } => {
                 statements.iter().any(|stmt| self.stmt_uses_masel(stmt))
             }
+            Expr::ListComp {
+                element, clauses, ..
+            } => {
+                self.expr_uses_masel(element)
+                    || clauses.iter().any(|clause| match clause {
+                        CompClause::For { iterable: expr, .. } | CompClause::If(expr) => {
+                            self.expr_uses_masel(expr)
+                        }
+                    })
+            }
         }
     }
 
@@ -25616,6 +27157,18 @@ impl<'ctx> CodeGen<'ctx> {
                         .as_ref()
                         .map_or(false, |e| self.stmt_uses_masel(e))
             }
+            Stmt::IfLet {
+                value,
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                self.expr_uses_masel(value)
+                    || self.stmt_uses_masel(then_branch)
+                    || else_branch
+                        .as_ref()
+                        .map_or(false, |e| self.stmt_uses_masel(e))
+            }
             Stmt::While {
                 condition, body, ..
             } => self.expr_uses_masel(condition) || self.stmt_uses_masel(body),
@@ -25684,6 +27237,17 @@ impl<'ctx> CodeGen<'ctx> {
                     self.collect_free_vars_stmt(else_stmt, bound, free);
                 }
             }
+            Stmt::IfLet {
+                name,
+                value,
+                then_branch,
+                else_branch,
+                span,
+            } => {
+                let lowered =
+                    Stmt::lower_if_let(name, value, then_branch, else_branch.as_deref(), *span);
+                self.collect_free_vars_stmt(&lowered, bound, free);
+            }
             Stmt::Block { statements, .. } => {
                 for s in statements {
                     self.collect_free_vars_stmt(s, bound, free);
@@ -25767,7 +27331,7 @@ impl<'ctx> CodeGen<'ctx> {
                     self.collect_free_vars(extra, bound, free);
                 }
             }
-            Stmt::Hurl { message, .. } => {
+            Stmt::Hurl { message, .. } | Stmt::Yield { value: message, .. } => {
                 self.collect_free_vars(message, bound, free);
             }
             // Statements that don't contain expressions with variables
@@ -25818,6 +27382,7 @@ impl<'ctx> CodeGen<'ctx> {
         &mut self,
         params: &[String],
         body: &Expr,
+        span: Span,
     ) -> Result<BasicValueEnum<'ctx>, HaversError> {
         // Generate unique lambda name
         let lambda_name = format!("__lambda_{}", self.lambda_counter);
@@ -25867,6 +27432,11 @@ impl<'ctx> CodeGen<'ctx> {
             .collect();
         let fn_type = self.types.value_type.fn_type(&param_types, false);
         let lambda_fn = self.module.add_function(&lambda_name, fn_type, None);
+        self.function_names.push((
+            lambda_fn,
+            format!("<lambda@{}:{}>", span.line, span.column),
+            params.len(),
+        ));
 
         // Save current state
         let saved_function = self.current_function;
@@ -25882,6 +27452,7 @@ impl<'ctx> CodeGen<'ctx> {
 
         // Set up lambda function
         self.current_function = Some(lambda_fn);
+        let saved_debug_location = self.debug_enter_function(lambda_fn);
         let entry = self.context.append_basic_block(lambda_fn, "entry");
         self.builder.position_at_end(entry);
 
@@ -25950,6 +27521,7 @@ impl<'ctx> CodeGen<'ctx> {
 
         // Restore state
         self.current_function = saved_function;
+        self.debug_leave_function(saved_debug_location);
         self.variables = saved_variables;
         self.var_types = saved_var_types;
         self.int_shadows = saved_int_shadows;
@@ -30131,6 +31703,7 @@ impl<'ctx> CodeGen<'ctx> {
 
         self.builder.position_at_end(entry);
         self.current_function = Some(function);
+        let saved_debug_location = self.debug_enter_function(function);
         self.in_user_function = true;
 
         // Clear scope state for the constructor
@@ -30174,6 +31747,7 @@ impl<'ctx> CodeGen<'ctx> {
 
         // Restore state
         self.current_function = saved_function;
+        self.debug_leave_function(saved_debug_location);
         self.variables = saved_variables;
         self.var_types = saved_var_types;
         self.int_shadows = saved_int_shadows;
@@ -31230,6 +32804,7 @@ impl<'ctx> CodeGen<'ctx> {
         let old_in_user_function = self.in_user_function;
 
         self.current_function = Some(function);
+        let saved_debug_location = self.debug_enter_function(function);
         self.in_user_function = true;
 
         // Create entry block
@@ -31327,6 +32902,7 @@ impl<'ctx> CodeGen<'ctx> {
 
         // Restore state - IMPORTANT: restore ALL shadow maps to prevent cross-method leakage
         self.current_function = old_function;
+        self.debug_leave_function(saved_debug_location);
         self.variables = old_variables;
         self.int_shadows = old_int_shadows;
         self.list_ptr_shadows = old_list_ptr_shadows;
@@ -33177,6 +34753,103 @@ impl<'ctx> CodeGen<'ctx> {
         self.make_float(result)
     }
 
+    /// fmod(a, b) - float remainder, sign follows a (LLVM's frem is C fmod)
+    fn inline_fmod(
+        &mut self,
+        a_val: BasicValueEnum<'ctx>,
+        b_val: BasicValueEnum<'ctx>,
+    ) -> Result<BasicValueEnum<'ctx>, HaversError> {
+        let float_tag = self
+            .types
+            .i8_type
+            .const_int(ValueTag::Float.as_u8() as u64, false);
+        let f64_type = self.context.f64_type();
+
+        // Convert each to float: if Float, bitcast; if Int, sitofp
+        let mut operands = Vec::with_capacity(2);
+        for (val, name) in [(a_val, "fmod_a"), (b_val, "fmod_b")] {
+            let tag = self.extract_tag(val).unwrap();
+            let data = self.extract_data(val).unwrap();
+            let is_float = self
+                .builder
+                .build_int_compare(
+                    IntPredicate::EQ,
+                    tag,
+                    float_tag,
+                    &format!("{}_is_float", name),
+                )
+                .unwrap();
+            let as_float = self
+                .builder
+                .build_bitcast(data, f64_type, &format!("{}_as_float", name))
+                .unwrap()
+                .into_float_value();
+            let int_to_float = self
+                .builder
+                .build_signed_int_to_float(data, f64_type, &format!("{}_int_to_float", name))
+                .unwrap();
+            let float_val = self
+                .builder
+                .build_select(
+                    is_float,
+                    BasicValueEnum::FloatValue(as_float),
+                    BasicValueEnum::FloatValue(int_to_float),
+                    name,
+                )
+                .unwrap()
+                .into_float_value();
+            operands.push(float_val);
+        }
+
+        // A zero divisor is an error, like in the interpreter, instead of frem's NaN
+        let function = self.current_function.unwrap();
+        let zero_block = self.context.append_basic_block(function, "fmod_zero");
+        let ok_block = self.context.append_basic_block(function, "fmod_ok");
+        let merge_block = self.context.append_basic_block(function, "fmod_merge");
+        let is_zero = self
+            .builder
+            .build_float_compare(
+                inkwell::FloatPredicate::OEQ,
+                operands[1],
+                f64_type.const_float(0.0),
+                "fmod_is_zero",
+            )
+            .unwrap();
+        self.builder
+            .build_conditional_branch(is_zero, zero_block, ok_block)
+            .unwrap();
+
+        self.builder.position_at_end(zero_block);
+        let zero_msg = self.compile_string_literal("fmod() cannae divide by zero")?;
+        self.builder
+            .build_call(self.libc.hurl, &[zero_msg.into()], "fmod_hurl")
+            .unwrap();
+        let zero_val = self.make_nil();
+        self.builder
+            .build_unconditional_branch(merge_block)
+            .unwrap();
+        let zero_end = self.builder.get_insert_block().unwrap();
+
+        self.builder.position_at_end(ok_block);
+        let result = self
+            .builder
+            .build_float_rem(operands[0], operands[1], "fmod_result")
+            .unwrap();
+        let ok_val = self.make_float(result)?;
+        self.builder
+            .build_unconditional_branch(merge_block)
+            .unwrap();
+        let ok_end = self.builder.get_insert_block().unwrap();
+
+        self.builder.position_at_end(merge_block);
+        let phi = self
+            .builder
+            .build_phi(self.types.value_type, "fmod_value")
+            .unwrap();
+        phi.add_incoming(&[(&zero_val, zero_end), (&ok_val, ok_end)]);
+        Ok(phi.as_basic_value())
+    }
+
     /// snooze(ms) - sleep for given milliseconds
     fn inline_snooze(
         &mut self,
@@ -34989,6 +36662,7 @@ impl<'ctx> CodeGen<'ctx> {
                 default: None,
             }],
             body: Vec::new(),
+            attributes: Vec::new(),
             span: Span::new(1, 1),
         }];
 
@@ -35349,6 +37023,8 @@ impl<'ctx> CodeGen<'ctx> {
         let stmt = Stmt::Import {
             path: "tri".to_string(),
             alias: Some("tri".to_string()),
+            only: None,
+            bring: false,
             span: Span::new(1, 1),
         };
 
@@ -35587,6 +37263,7 @@ impl<'ctx> CodeGen<'ctx> {
                 span,
             }),
             inclusive: true,
+            step: None,
             span,
         };
 
@@ -35969,6 +37646,7 @@ impl<'ctx> CodeGen<'ctx> {
                     }),
                     span,
                 }],
+                attributes: Vec::new(),
                 span,
             };
             let outer_body = vec![
@@ -36015,6 +37693,7 @@ impl<'ctx> CodeGen<'ctx> {
                     }),
                     span,
                 }],
+                attributes: Vec::new(),
                 span,
             }];
 
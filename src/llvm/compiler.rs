@@ -137,6 +137,9 @@ impl Drop for BuildStatusGuard {
 pub struct LLVMCompiler {
     // Configuration options
     opt_level: OptimizationLevel,
+    checked_arithmetic: bool,
+    debug_info: bool,
+    random_seed: Option<u64>,
 }
 
 impl LLVMCompiler {
@@ -148,6 +151,9 @@ impl LLVMCompiler {
     pub fn new() -> Self {
         LLVMCompiler {
             opt_level: OptimizationLevel::Default,
+            checked_arithmetic: false,
+            debug_info: false,
+            random_seed: None,
         }
     }
 
@@ -162,13 +168,54 @@ impl LLVMCompiler {
         self
     }
 
+    /// Trap on integer overflow in +, - and * instead of wrapping silently
+    pub fn with_checked_arithmetic(mut self, checked: bool) -> Self {
+        self.checked_arithmetic = checked;
+        self
+    }
+
+    /// Emit DWARF debug info (line numbers and `ken` variables) for gdb/lldb
+    pub fn with_debug_info(mut self, enabled: bool) -> Self {
+        self.debug_info = enabled;
+        self
+    }
+
+    /// Seed the runtime's random numbers at startup so every run of the
+    /// binary gives the same sequence. `None` leaves it seeded from the clock.
+    pub fn with_random_seed(mut self, seed: Option<u64>) -> Self {
+        self.random_seed = seed;
+        self
+    }
+
     /// Compile to LLVM IR (text format)
     pub fn compile_to_ir(&self, program: &Program) -> Result<String, HaversError> {
         let context = Context::create();
         let mut codegen = CodeGen::new(&context, "mdhavers_module");
+        codegen.set_checked_arithmetic(self.checked_arithmetic);
+        codegen.set_debug_info(self.debug_info);
+        codegen.set_random_seed(self.random_seed);
+
+        codegen.compile(program)?;
+
+        Ok(codegen.get_module().print_to_string().to_string())
+    }
+
+    /// Compile to LLVM IR after the optimisation passes for this compiler's
+    /// `-O` level, so it matches what ends up in the native binary. At `-O0`
+    /// it's the same raw IR as `compile_to_ir`.
+    pub fn compile_to_optimized_ir(&self, program: &Program) -> Result<String, HaversError> {
+        let context = Context::create();
+        let mut codegen = CodeGen::new(&context, "mdhavers_module");
+        codegen.set_checked_arithmetic(self.checked_arithmetic);
+        codegen.set_debug_info(self.debug_info);
+        codegen.set_random_seed(self.random_seed);
 
         codegen.compile(program)?;
 
+        if !matches!(self.opt_level, OptimizationLevel::None) {
+            self.run_optimization_passes(codegen.get_module())?;
+        }
+
         Ok(codegen.get_module().print_to_string().to_string())
     }
 
@@ -205,6 +252,9 @@ impl LLVMCompiler {
 
         let context = Context::create();
         let mut codegen = CodeGen::new(&context, "mdhavers_module");
+        codegen.set_checked_arithmetic(self.checked_arithmetic);
+        codegen.set_debug_info(self.debug_info);
+        codegen.set_random_seed(self.random_seed);
         if let Some(path) = source_path {
             codegen.set_source_path(path);
         }
@@ -282,7 +332,11 @@ impl LLVMCompiler {
 
         // First compile to object file
         let obj_path = output_path.with_extension("o");
-        let compiler = LLVMCompiler::new().with_optimization(opt_level);
+        let compiler = LLVMCompiler::new()
+            .with_optimization(opt_level)
+            .with_checked_arithmetic(self.checked_arithmetic)
+            .with_debug_info(self.debug_info)
+            .with_random_seed(self.random_seed);
         if let Err(err) = compiler.compile_to_object_with_source_status(
             program,
             &obj_path,
@@ -584,6 +638,99 @@ mod tests {
         assert!(ir.contains("body"));
     }
 
+    #[test]
+    fn test_compile_float_modulo_and_power() {
+        let source = r#"
+            ken a = 7.5
+            ken b = 2
+            blether a % b
+            blether a ** b
+        "#;
+
+        let program = parse(source).unwrap();
+        let compiler = LLVMCompiler::new();
+        let ir = compiler.compile_to_ir(&program).unwrap();
+
+        assert!(ir.contains("frem double"));
+        assert!(ir.contains("@llvm.pow.f64"));
+    }
+
+    #[test]
+    fn test_compile_bitwise_operators() {
+        let source = r#"
+            ken a = 6
+            ken b = 3
+            blether a & b
+            blether a | b
+            blether a ^ b
+            blether a << b
+            blether a >> b
+        "#;
+
+        let program = parse(source).unwrap();
+        let compiler = LLVMCompiler::new();
+        let ir = compiler.compile_to_ir(&program).unwrap();
+
+        assert!(ir.contains("and i64"));
+        assert!(ir.contains("or i64"));
+        assert!(ir.contains("xor i64"));
+        assert!(ir.contains("shl i64"));
+        assert!(ir.contains("ashr i64"));
+    }
+
+    #[test]
+    fn test_compile_with_debug_info_emits_dwarf_and_verifies() {
+        let source = r#"ken total = 0
+dae add(a, b) {
+    ken sum = a + b
+    gie sum
+}
+total = add(1, 2)
+blether total
+"#;
+
+        let program = parse(source).unwrap();
+        let ir = LLVMCompiler::new()
+            .with_debug_info(true)
+            .compile_to_ir(&program)
+            .unwrap();
+
+        assert!(ir.contains("!DICompileUnit"));
+        assert!(ir.contains("!DISubprogram(name: \"main\""));
+        assert!(ir.contains("!DISubprogram(name: \"add\""));
+        assert!(ir.contains("!DILocalVariable(name: \"sum\""));
+        assert!(ir.contains("!DIGlobalVariable(name: \"total\""));
+        assert!(ir.contains("!DILocation(line: 3"));
+        assert!(ir.contains("\"Debug Info Version\""));
+
+        let plain = LLVMCompiler::new().compile_to_ir(&program).unwrap();
+        assert!(!plain.contains("!DICompileUnit"));
+
+        let context = Context::create();
+        let mut codegen = CodeGen::new(&context, "debug_info_verify");
+        codegen.set_debug_info(true);
+        codegen.compile(&program).unwrap();
+        codegen
+            .get_module()
+            .verify()
+            .expect("module should verify wi' debug info");
+    }
+
+    #[test]
+    fn test_compile_string_ordering_uses_strcmp() {
+        let source = r#"
+            blether "apple" < "banana"
+        "#;
+
+        let program = parse(source).unwrap();
+        let compiler = LLVMCompiler::new();
+        let ir = compiler.compile_to_ir(&program).unwrap();
+
+        assert!(ir.contains("call i32 @strcmp"));
+        // The integer path is still there for non-string operands
+        assert!(ir.contains("icmp slt i64"));
+    }
+
     #[test]
     fn test_compile_audio_builtins() {
         let source = r#"
@@ -735,6 +882,40 @@ mod tests {
         assert!(obj_path.exists());
     }
 
+    #[test]
+    fn test_compile_to_optimized_ir_folds_at_o2() {
+        let program = parse(
+            r#"
+dae sum() {
+    ken a = 2 * 3
+    ken b = a + 4
+    ken c = b * 10
+    gie c - 1
+}
+blether sum()
+"#,
+        )
+        .unwrap();
+
+        let raw = LLVMCompiler::new()
+            .with_optimization(0)
+            .compile_to_optimized_ir(&program)
+            .unwrap();
+        let optimized = LLVMCompiler::new()
+            .with_optimization(2)
+            .compile_to_optimized_ir(&program)
+            .unwrap();
+
+        // -O0 is the untouched IR
+        assert_eq!(raw, LLVMCompiler::new().compile_to_ir(&program).unwrap());
+        assert!(
+            optimized.len() < raw.len(),
+            "-O2 IR ({} bytes) should be shorter than -O0 IR ({} bytes)",
+            optimized.len(),
+            raw.len()
+        );
+    }
+
     #[test]
     fn test_run_optimization_passes_invalid_module_errors() {
         let context = Context::create();
@@ -0,0 +1,224 @@
+//! DWARF debug info for native builds
+//!
+//! Emits a compile unit, a subprogram per function, line locations from
+//! statement spans and local variable info for `ken` declarations, so
+//! gdb/lldb can step through .braw source.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use inkwell::basic_block::BasicBlock;
+use inkwell::context::Context;
+use inkwell::debug_info::{
+    AsDIScope, DICompileUnit, DIFile, DIFlags, DIFlagsConstants, DILocation, DISubprogram,
+    DISubroutineType, DIType, DWARFEmissionKind, DWARFSourceLanguage, DebugInfoBuilder,
+};
+use inkwell::module::{FlagBehavior, Module};
+use inkwell::values::{FunctionValue, GlobalValue, PointerValue};
+
+const DW_ATE_SIGNED: u32 = 0x05;
+const DW_ATE_UNSIGNED_CHAR: u32 = 0x08;
+
+/// Debug info state for a single module
+pub struct DebugInfo<'ctx> {
+    builder: DebugInfoBuilder<'ctx>,
+    compile_unit: DICompileUnit<'ctx>,
+    file: DIFile<'ctx>,
+    /// DWARF view of the MdhValue struct: { i8 tag, i64 data }
+    value_type: DIType<'ctx>,
+    /// Routine type shared by every function (we only need line tables)
+    routine_type: DISubroutineType<'ctx>,
+    /// Variables already described, keyed by function and name
+    declared: HashSet<(String, String)>,
+}
+
+impl<'ctx> DebugInfo<'ctx> {
+    pub fn new(context: &'ctx Context, module: &Module<'ctx>, source_path: Option<&Path>) -> Self {
+        let (filename, directory) = match source_path {
+            Some(path) => (
+                path.file_name()
+                    .map(|f| f.to_string_lossy().to_string())
+                    .unwrap_or_else(|| "main.braw".to_string()),
+                path.parent()
+                    .map(|d| d.to_string_lossy().to_string())
+                    .unwrap_or_else(|| ".".to_string()),
+            ),
+            None => ("main.braw".to_string(), ".".to_string()),
+        };
+
+        // Without this flag LLVM quietly drops the lot
+        let version = context.i32_type().const_int(3, false);
+        module.add_basic_value_flag("Debug Info Version", FlagBehavior::Warning, version);
+        let dwarf = context.i32_type().const_int(4, false);
+        module.add_basic_value_flag("Dwarf Version", FlagBehavior::Warning, dwarf);
+
+        let (builder, compile_unit) = module.create_debug_info_builder(
+            true,
+            DWARFSourceLanguage::C,
+            &filename,
+            &directory,
+            "mdhavers",
+            false,
+            "",
+            0,
+            "",
+            DWARFEmissionKind::Full,
+            0,
+            false,
+            false,
+            "",
+            "",
+        );
+        let file = compile_unit.get_file();
+
+        let tag_type = builder
+            .create_basic_type("u8", 8, DW_ATE_UNSIGNED_CHAR, DIFlags::PUBLIC)
+            .unwrap()
+            .as_type();
+        let data_type = builder
+            .create_basic_type("i64", 64, DW_ATE_SIGNED, DIFlags::PUBLIC)
+            .unwrap()
+            .as_type();
+        let scope = compile_unit.as_debug_info_scope();
+        let members = [
+            builder
+                .create_member_type(scope, "tag", file, 0, 8, 8, 0, DIFlags::PUBLIC, tag_type)
+                .as_type(),
+            builder
+                .create_member_type(
+                    scope,
+                    "data",
+                    file,
+                    0,
+                    64,
+                    64,
+                    64,
+                    DIFlags::PUBLIC,
+                    data_type,
+                )
+                .as_type(),
+        ];
+        let value_type = builder
+            .create_struct_type(
+                scope,
+                "MdhValue",
+                file,
+                0,
+                128,
+                64,
+                DIFlags::PUBLIC,
+                None,
+                &members,
+                0,
+                None,
+                "MdhValue",
+            )
+            .as_type();
+        let routine_type =
+            builder.create_subroutine_type(file, Some(value_type), &[], DIFlags::PUBLIC);
+
+        DebugInfo {
+            builder,
+            compile_unit,
+            file,
+            value_type,
+            routine_type,
+            declared: HashSet::new(),
+        }
+    }
+
+    /// Give a function its subprogram (once) and return it
+    pub fn attach_function(&self, function: FunctionValue<'ctx>, line: u32) -> DISubprogram<'ctx> {
+        if let Some(subprogram) = function.get_subprogram() {
+            return subprogram;
+        }
+        let name = function.get_name().to_string_lossy().to_string();
+        let subprogram = self.builder.create_function(
+            self.compile_unit.as_debug_info_scope(),
+            &name,
+            None,
+            self.file,
+            line,
+            self.routine_type,
+            false,
+            true,
+            line,
+            DIFlags::PUBLIC,
+            false,
+        );
+        function.set_subprogram(subprogram);
+        subprogram
+    }
+
+    pub fn location(
+        &self,
+        context: &'ctx Context,
+        line: u32,
+        subprogram: DISubprogram<'ctx>,
+    ) -> DILocation<'ctx> {
+        self.builder
+            .create_debug_location(context, line, 0, subprogram.as_debug_info_scope(), None)
+    }
+
+    /// Describe a local `ken` variable living in an alloca
+    pub fn declare_local(
+        &mut self,
+        function: FunctionValue<'ctx>,
+        name: &str,
+        storage: PointerValue<'ctx>,
+        location: DILocation<'ctx>,
+        block: BasicBlock<'ctx>,
+    ) {
+        let Some(subprogram) = function.get_subprogram() else {
+            return;
+        };
+        let key = (
+            function.get_name().to_string_lossy().to_string(),
+            name.to_string(),
+        );
+        if !self.declared.insert(key) {
+            return;
+        }
+        let variable = self.builder.create_auto_variable(
+            subprogram.as_debug_info_scope(),
+            name,
+            self.file,
+            location.get_line(),
+            self.value_type,
+            true,
+            DIFlags::ZERO,
+            64,
+        );
+        self.builder
+            .insert_declare_at_end(storage, Some(variable), None, location, block);
+    }
+
+    /// Describe a top-level `ken` variable living in an LLVM global
+    pub fn declare_global(&mut self, context: &'ctx Context, global: GlobalValue<'ctx>, line: u32) {
+        let name = global.get_name().to_string_lossy().to_string();
+        if !self.declared.insert((String::new(), name.clone())) {
+            return;
+        }
+        let expression = self.builder.create_global_variable_expression(
+            self.compile_unit.as_debug_info_scope(),
+            &name,
+            &name,
+            self.file,
+            line,
+            self.value_type,
+            false,
+            None,
+            None,
+            64,
+        );
+        global.set_metadata(
+            expression.as_metadata_value(context),
+            context.get_kind_id("dbg"),
+        );
+    }
+
+    /// Resolve forward references - must run before the module is verified or emitted
+    pub fn finalize(&self) {
+        self.builder.finalize();
+    }
+}
@@ -10,6 +10,7 @@
 pub mod builtins;
 pub mod codegen;
 pub mod compiler;
+pub mod debug_info;
 #[allow(dead_code)]
 pub mod runtime;
 #[allow(dead_code)]
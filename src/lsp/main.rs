@@ -250,22 +250,24 @@ fn publish_diagnostics(
 
     let lsp_diagnostics: Vec<Diagnostic> = diagnostics
         .into_iter()
-        .map(|(line, col, message, severity)| Diagnostic {
-            range: Range {
-                start: Position {
-                    line: line.saturating_sub(1) as u32,
-                    character: col.saturating_sub(1) as u32,
-                },
-                end: Position {
-                    line: line.saturating_sub(1) as u32,
-                    character: (col + 10) as u32, // Approximate end
+        .map(
+            |(line, col, end_line, end_col, message, severity)| Diagnostic {
+                range: Range {
+                    start: Position {
+                        line: line.saturating_sub(1) as u32,
+                        character: col.saturating_sub(1) as u32,
+                    },
+                    end: Position {
+                        line: end_line.saturating_sub(1) as u32,
+                        character: end_col.saturating_sub(1) as u32,
+                    },
                 },
+                severity: Some(diagnostic_severity(severity.as_str())),
+                source: Some("mdhavers".to_string()),
+                message,
+                ..Default::default()
             },
-            severity: Some(diagnostic_severity(severity.as_str())),
-            source: Some("mdhavers".to_string()),
-            message,
-            ..Default::default()
-        })
+        )
         .collect();
 
     let notification = lsp_server::Notification::new(
@@ -6,43 +6,69 @@
 use mdhavers::lexer;
 use mdhavers::HaversError;
 
+/// A diagnostic: (line, column, end line, end column, message, severity).
+/// Lines and columns count from 1, and the end is just past the last character.
+pub type Diagnostic = (usize, usize, usize, usize, String, String);
+
 /// Get diagnostics fer a piece o' mdhavers code
-/// Returns a list of (line, column, message, severity)
-pub fn get_diagnostics(source: &str) -> Vec<(usize, usize, String, String)> {
+pub fn get_diagnostics(source: &str) -> Vec<Diagnostic> {
     let mut diagnostics = Vec::new();
 
     // Lex using the real mdhavers lexer first (best source of line/column info).
     if let Err(err) = lexer::lex(source) {
-        diagnostics.push(error_to_diagnostic(err));
+        diagnostics.push(error_to_diagnostic(err, source));
         return diagnostics;
     }
 
-    // Parse using the real mdhavers parser.
-    if let Err(err) = mdhavers::parse(source) {
-        diagnostics.push(error_to_diagnostic(err));
+    // Parse using the real mdhavers parser (reporting every error it finds),
+    // then lint what parsed, types included.
+    let (program, errors) = mdhavers::parse_all(source);
+    for err in errors {
+        diagnostics.push(error_to_diagnostic(err, source));
+    }
+    if let Some(program) = program {
+        let mut warnings = mdhavers::lint::check_program(&program, source);
+        warnings.extend(mdhavers::lint::check_types(&program, source));
+        warnings.sort_by_key(|warning| (warning.line, warning.column));
+        for warning in warnings {
+            diagnostics.push((
+                warning.line,
+                warning.column,
+                warning.end_line,
+                warning.end_column,
+                warning.message,
+                "warning".to_string(),
+            ));
+        }
     }
 
     diagnostics
 }
 
-fn error_to_diagnostic(err: HaversError) -> (usize, usize, String, String) {
-    match &err {
-        HaversError::UnkentToken { line, column, .. } => {
-            (*line, *column, format!("{err}"), "error".to_string())
-        }
-        HaversError::UnexpectedToken { line, .. } => {
-            (*line, 1, format!("{err}"), "error".to_string())
-        }
-        HaversError::ParseError { line, .. } => (*line, 1, format!("{err}"), "error".to_string()),
-        _ => (
-            err.line().unwrap_or(1),
-            1,
-            format!("{err}"),
+fn error_to_diagnostic(err: HaversError, source: &str) -> Diagnostic {
+    let message = format!("{err}");
+    match err.span() {
+        Some(span) => (
+            span.start_line,
+            span.start_col,
+            span.end_line,
+            span.end_col,
+            message,
             "error".to_string(),
         ),
+        // Only the line is known, so mark the whole of it
+        None => {
+            let line = err.line().unwrap_or(1);
+            let end = source_line(source, line).chars().count() + 1;
+            (line, 1, line, end.max(2), message, "error".to_string())
+        }
     }
 }
 
+fn source_line(source: &str, line: usize) -> &str {
+    source.lines().nth(line.saturating_sub(1)).unwrap_or("")
+}
+
 /// Get documentation fer a keyword or builtin
 pub fn get_keyword_info(keyword: &str) -> Option<String> {
     match keyword {
@@ -50,11 +76,13 @@ pub fn get_keyword_info(keyword: &str) -> Option<String> {
         "ken" => Some("**ken** - Variable declaration\n\n```mdhavers\nken x = 42\nken name = \"Angus\"\n```\n\nLike `let` or `var` in other languages. From Scots \"I ken\" meaning \"I know\".".to_string()),
         "gin" => Some("**gin** - If statement\n\n```mdhavers\ngin x > 10 {\n    blether \"Big number!\"\n}\n```\n\nConditional execution. From Scots \"gin\" meaning \"if\".".to_string()),
         "ither" => Some("**ither** - Else clause\n\n```mdhavers\ngin x > 10 {\n    blether \"Big\"\n} ither {\n    blether \"Wee\"\n}\n```\n\nFrom Scots \"ither\" meaning \"other\".".to_string()),
+        "by" => Some("**by** - Range step\n\n```mdhavers\nfer i in 0..10 by 2 {\n    blether i\n}\n```\n\nSets the stride o' a range. The direction aye comes fae the bounds.".to_string()),
         "than" => Some("**than** - Then (for ternary expressions)\n\n```mdhavers\nken result = gin x > 0 than \"positive\" ither \"negative\"\n```\n\nUsed in ternary/conditional expressions.".to_string()),
         "whiles" => Some("**whiles** - While loop\n\n```mdhavers\nwhiles x < 10 {\n    blether x\n    x = x + 1\n}\n```\n\nFrom Scots \"whiles\" meaning \"while\".".to_string()),
         "fer" => Some("**fer** - For loop\n\n```mdhavers\nfer i in 1..10 {\n    blether i\n}\n\nfer item in my_list {\n    blether item\n}\n```\n\nIterate over ranges or collections.".to_string()),
         "dae" => Some("**dae** - Function definition\n\n```mdhavers\ndae greet(name) {\n    blether f\"Hullo {name}!\"\n}\n\ndae add(a, b = 0) {  # with default\n    gie a + b\n}\n```\n\nFrom Scots \"dae\" meaning \"do\".".to_string()),
         "gie" => Some("**gie** - Return from function\n\n```mdhavers\ndae square(x) {\n    gie x * x\n}\n```\n\nFrom Scots \"gie\" meaning \"give\".".to_string()),
+        "gie_back" => Some("**gie_back** - Yield from a generator\n\n```mdhavers\ndae count_tae(n) {\n    ken i = 1\n    whiles i <= n {\n        gie_back i\n        i = i + 1\n    }\n}\n\nfer x in count_tae(3) {\n    blether x\n}\n```\n\nA function wi' `gie_back` in it hands back a generator that pauses at each `gie_back`.".to_string()),
         "blether" => Some("**blether** - Print to output\n\n```mdhavers\nblether \"Hullo warld!\"\nblether f\"The answer is {42}\"\n```\n\nFrom Scots \"blether\" meaning \"chat\" or \"talk\".".to_string()),
         "speir" => Some("**speir** - Get user input\n\n```mdhavers\nken name = speir \"Whit's yer name? \"\n```\n\nFrom Scots \"speir\" meaning \"ask\".".to_string()),
         "aye" => Some("**aye** - Boolean true\n\n```mdhavers\nken happy = aye\n```\n\nFrom Scots \"aye\" meaning \"yes\".".to_string()),
@@ -68,41 +96,62 @@ pub fn get_keyword_info(keyword: &str) -> Option<String> {
         "thing" => Some("**thing** - Struct definition\n\n```mdhavers\nthing Point { x, y }\nken p = Point { x: 10, y: 20 }\n```\n\nDefines a simple data structure.".to_string()),
         "masel" => Some("**masel** - Self reference in classes\n\n```mdhavers\nkin Counter {\n    dae increment() {\n        masel.count = masel.count + 1\n    }\n}\n```\n\nFrom Scots \"masel\" meaning \"myself\".".to_string()),
         "fetch" => Some("**fetch** - Import a module\n\n```mdhavers\nfetch \"utils\"\nfetch \"math\" tae maths\n```\n\nImport code from another file.".to_string()),
+        "bring" => Some("**bring** - Import a module intae a namespace\n\n```mdhavers\nbring \"mathlib\"\nbring \"mathlib\" as m\nbring add, sub frae \"mathlib\"\n```\n\nThe namespace is the file name unless ye gie ane wi' `as`.".to_string()),
         "hae_a_bash" => Some("**hae_a_bash** - Try block\n\n```mdhavers\nhae_a_bash {\n    # risky code\n} gin_it_gangs_wrang e {\n    blether f\"Error: {e}\"\n}\n```\n\nFrom Scots \"hae a bash\" meaning \"give it a try\".".to_string()),
         "gin_it_gangs_wrang" => Some("**gin_it_gangs_wrang** - Catch block\n\n```mdhavers\nhae_a_bash {\n    ken x = 1 / 0\n} gin_it_gangs_wrang e {\n    blether \"Oops!\"\n}\n```\n\nFrom Scots \"gin it gangs wrang\" meaning \"if it goes wrong\".".to_string()),
         "keek" => Some("**keek** - Match/switch statement\n\n```mdhavers\nkeek value {\n    whan 1 -> blether \"One\"\n    whan 2 -> blether \"Two\"\n    whan _ -> blether \"Something else\"\n}\n```\n\nFrom Scots \"keek\" meaning \"peek\" or \"look\".".to_string()),
         "whan" => Some("**whan** - Case in match statement\n\n```mdhavers\nkeek x {\n    whan 1 -> blether \"One\"\n    whan 2 -> blether \"Two\"\n}\n```\n\nFrom Scots \"whan\" meaning \"when\".".to_string()),
         "mak_siccar" => Some("**mak_siccar** - Assert\n\n```mdhavers\nmak_siccar x > 0, \"x must be positive!\"\n```\n\nFrom Scots \"mak siccar\" meaning \"make sure\" - famously said by Robert the Bruce!".to_string()),
-        "in" => Some("**in** - Used in for loops\n\n```mdhavers\nfer item in list {\n    blether item\n}\n```".to_string()),
+        "in" => Some("**in** - Used in fer loops, an' tae check membership (`x in xs`, `x nae in xs`)\n\n```mdhavers\nfer item in list {\n    blether item\n}\nblether 2 in [1, 2, 3]  # aye\n```".to_string()),
         "is" => Some("**is** - Type checking\n\n```mdhavers\ngin x is \"integer\" {\n    blether \"It's a number!\"\n}\n```".to_string()),
         "fae" => Some("**fae** - From (used in imports and inheritance)\n\n```mdhavers\nkin Dog fae Animal {\n    # Dog inherits fae Animal\n}\n```\n\nFrom Scots \"fae\" meaning \"from\".".to_string()),
         "tae" => Some("**tae** - To (used in imports for aliasing)\n\n```mdhavers\nfetch \"math\" tae maths\n```\n\nFrom Scots \"tae\" meaning \"to\".".to_string()),
 
         // Built-in functions
         "len" => Some("**len(x)** - Get the length\n\n```mdhavers\nken size = len([1, 2, 3])  # 3\nken chars = len(\"hello\")   # 5\n```".to_string()),
+        "airity" => Some("**airity(fn)** - How mony arguments a function needs (parameters wi' defaults are no' counted)\n\n```mdhavers\nblether airity(|a, b| a + b)   # 2\n```\n\nGies `naething` fer built-ins that tak ony number.".to_string()),
         "whit_kind" => Some("**whit_kind(x)** - Get the type of a value\n\n```mdhavers\nblether whit_kind(42)      # \"integer\"\nblether whit_kind(\"hi\")    # \"string\"\nblether whit_kind([1,2])   # \"list\"\n```\n\nFrom Scots \"whit kind\" meaning \"what type\".".to_string()),
+        "shaw" | "shaw_pretty" => Some("**shaw(x)** / **shaw_pretty(x)** - Print a dump o' a value wi' its types\n\n```mdhavers\nshaw([1, \"x\"])  # List[2]: [Int:1, Str:\"x\"]\n```\n\n`shaw_pretty` puts each element on its ain indented line. A container inside itsel shows as `…`.".to_string()),
         "tae_string" => Some("**tae_string(x)** - Convert to string\n\n```mdhavers\nken s = tae_string(42)  # \"42\"\n```".to_string()),
-        "tae_int" => Some("**tae_int(x)** - Convert to integer\n\n```mdhavers\nken n = tae_int(\"42\")   # 42\nken m = tae_int(3.14)   # 3\n```".to_string()),
+        "tae_int" => Some("**tae_int(x, base?)** - Convert to integer (floats are cut toward zero)\n\n```mdhavers\nken n = tae_int(\"42\")       # 42\nken m = tae_int(-3.9)       # -3\nken h = tae_int(\"ff\", 16)   # 255\n```".to_string()),
         "tae_float" => Some("**tae_float(x)** - Convert to float\n\n```mdhavers\nken f = tae_float(\"3.14\")  # 3.14\n```".to_string()),
+        "tae_bool" => Some("**tae_bool(x)** - Convert to boolean by truthiness\n\n```mdhavers\ntae_bool(\"\")    # nae\ntae_bool([0])   # aye\n```".to_string()),
         "shove" => Some("**shove(list, item)** - Add item to end of list\n\n```mdhavers\nken nums = [1, 2]\nshove(nums, 3)  # [1, 2, 3]\n```\n\nFrom Scots \"shove\" meaning \"push\".".to_string()),
         "yank" => Some("**yank(list)** - Remove and return last item\n\n```mdhavers\nken nums = [1, 2, 3]\nken last = yank(nums)  # 3, nums is now [1, 2]\n```\n\nFrom Scots \"yank\" meaning \"pull\".".to_string()),
+        "stick_in" => Some("**stick_in(list, index, item)** - Insert item at index, in place\n\n```mdhavers\nken nums = [1, 3]\nstick_in(nums, 1, 2)  # nums is now [1, 2, 3]\n```\n\nGies back the same list. Negative indices count fae the end.".to_string()),
+        "tak_oot" => Some("**tak_oot(list, index)** - Remove and return the item at index\n\n```mdhavers\nken nums = [1, 2, 3]\nken first = tak_oot(nums, 0)  # 1, nums is now [2, 3]\n```\n\nFrom Scots \"tak oot\" meaning \"take out\".".to_string()),
+        "whaur" => Some("**whaur(list, item)** - Index o' the first matching item, or -1\n\n```mdhavers\nwhaur([\"a\", \"b\"], \"b\")  # 1\n```\n\nFrom Scots \"whaur\" meaning \"where\".".to_string()),
         "heid" => Some("**heid(list)** - Get first element\n\n```mdhavers\nken first = heid([1, 2, 3])  # 1\n```\n\nFrom Scots \"heid\" meaning \"head\".".to_string()),
         "tail" => Some("**tail(list)** - Get all but first element\n\n```mdhavers\nken rest = tail([1, 2, 3])  # [2, 3]\n```".to_string()),
         "bum" => Some("**bum(list)** - Get last element\n\n```mdhavers\nken last = bum([1, 2, 3])  # 3\n```\n\nFrom Scots \"bum\" meaning \"bottom\".".to_string()),
-        "range" => Some("**range(start, end)** - Create a range\n\n```mdhavers\nken nums = range(1, 5)  # [1, 2, 3, 4]\n```".to_string()),
+        "range" => Some("**range(start, end, step)** - Create a range\n\n```mdhavers\nken nums = range(1, 5)  # [1, 2, 3, 4]\nken evens = range(0, 10, 2)  # [0, 2, 4, 6, 8]\nken doon = range(5, 1)  # [5, 4, 3, 2]\n```".to_string()),
         "keys" => Some("**keys(dict)** - Get dictionary keys\n\n```mdhavers\nken k = keys({a: 1, b: 2})  # [\"a\", \"b\"]\n```".to_string()),
         "values" => Some("**values(dict)** - Get dictionary values\n\n```mdhavers\nken v = values({a: 1, b: 2})  # [1, 2]\n```".to_string()),
+        "get" => Some("**get(dict, key, default)** - Look up a key, or get the default if it's missin'\n\n```mdhavers\nken city = get(person, \"city\", \"Unknown\")\n```".to_string()),
+        "has_key" => Some("**has_key(dict, key)** - Check if a dictionary has a key\n\n```mdhavers\ngin has_key(person, \"city\") {\n    blether person[\"city\"]\n}\n```".to_string()),
+        "remove" => Some("**remove(dict, key)** - Remove a key in place an' return its value (naething if it wisnae there)\n\n```mdhavers\nken age = remove(person, \"age\")\n```".to_string()),
+        "dict_get" => Some("**dict_get(dict, key, default)** - Look up a key; the default is optional an' falls back tae naething\n\n```mdhavers\nken city = dict_get(person, \"city\", \"Unknown\")\n```".to_string()),
+        "dict_set" => Some("**dict_set(dict, key, value)** - Set a key in place an' return the same dict\n\n```mdhavers\ndict_set(person, \"city\", \"Glesga\")\n```".to_string()),
+        "dict_has" => Some("**dict_has(dict, key)** - Check if a dictionary has a key\n\n```mdhavers\ngin dict_has(person, \"city\") {\n    blether person[\"city\"]\n}\n```".to_string()),
+        "dict_remove" => Some("**dict_remove(dict, key)** - A copy o' the dict wi'oot the key (a missin' key is fine)\n\n```mdhavers\nken anon = dict_remove(person, \"name\")\n```".to_string()),
+        "dict_merge" => Some("**dict_merge(a, b)** - A new dict wi' the keys o' baith; `b` wins on clashes\n\n```mdhavers\nken settings = dict_merge(defaults, overrides)\n```".to_string()),
         "abs" => Some("**abs(x)** - Absolute value\n\n```mdhavers\nken n = abs(-42)  # 42\n```".to_string()),
         "min" => Some("**min(a, b)** - Minimum of two values\n\n```mdhavers\nken m = min(3, 7)  # 3\n```".to_string()),
         "max" => Some("**max(a, b)** - Maximum of two values\n\n```mdhavers\nken m = max(3, 7)  # 7\n```".to_string()),
         "floor" => Some("**floor(x)** - Round down\n\n```mdhavers\nken n = floor(3.7)  # 3\n```".to_string()),
         "ceil" => Some("**ceil(x)** - Round up\n\n```mdhavers\nken n = ceil(3.2)  # 4\n```".to_string()),
         "round" => Some("**round(x)** - Round to nearest integer\n\n```mdhavers\nken n = round(3.5)  # 4\n```".to_string()),
-        "sqrt" => Some("**sqrt(x)** - Square root\n\n```mdhavers\nken r = sqrt(16)  # 4.0\n```".to_string()),
+        "format" => Some("**format(template, ...)** - Fill `{}` placeholders in order; `{:W}` pads tae a width, `{:.P}` gies a number P decimals, an' `{{`/`}}` are literal braces\n\n```mdhavers\nblether format(\"{:6.2}|\", 3.14159)  #   3.14|\n```".to_string()),
+        "fmt_float" => Some("**fmt_float(x, decimals)** - Format a number wi' a fixed number o' decimals\n\n```mdhavers\nblether fmt_float(3.14159, 2)  # 3.14\n```".to_string()),
+        "sqrt" => Some("**sqrt(x)** - Square root\n\n```mdhavers\nken r = sqrt(16)  # 4.0\n```\n\nA negative number is an error.".to_string()),
+        "pow" => Some("**pow(base, exp)** - Raise tae a power\n\n```mdhavers\nken n = pow(2, 10)  # 1024\nken h = pow(2, -1)  # 0.5\n```\n\nInts stay ints when the exponent's a non-negative int.".to_string()),
+        "fmod" => Some("**fmod(a, b)** - Float remainder\n\n```mdhavers\nken r = fmod(7.5, 2)  # 1.5\nken s = fmod(-7, 3)   # -1.0\n```\n\nThe sign follows the first number.".to_string()),
         "split" => Some("**split(string, delimiter)** - Split string\n\n```mdhavers\nken words = split(\"a,b,c\", \",\")  # [\"a\", \"b\", \"c\"]\n```".to_string()),
         "join" => Some("**join(list, delimiter)** - Join list to string\n\n```mdhavers\nken s = join([\"a\", \"b\"], \"-\")  # \"a-b\"\n```".to_string()),
         "contains" => Some("**contains(haystack, needle)** - Check if contains\n\n```mdhavers\ncontains(\"hello\", \"ell\")  # aye\ncontains([1,2,3], 2)       # aye\n```".to_string()),
         "reverse" => Some("**reverse(list)** - Reverse a list\n\n```mdhavers\nken r = reverse([1, 2, 3])  # [3, 2, 1]\n```".to_string()),
+        "unique" => Some("**unique(list)** - Drop duplicates, keepin' the first o' each\n\n```mdhavers\nken u = unique([1, [2], 1, [2]])  # [1, [2]]\n```\n\nNested lists an' dicts match by their contents.".to_string()),
+        "flatten" => Some("**flatten(list)** - Flatten one level o' nestin'\n\n```mdhavers\nken f = flatten([[1, 2], 3, [[4]]])  # [1, 2, 3, [4]]\n```".to_string()),
         "sort" => Some("**sort(list)** - Sort a list\n\n```mdhavers\nken s = sort([3, 1, 2])  # [1, 2, 3]\n```".to_string()),
         "upper" => Some("**upper(string)** - Convert to uppercase\n\n```mdhavers\nken u = upper(\"hello\")  # \"HELLO\"\n```".to_string()),
         "lower" => Some("**lower(string)** - Convert to lowercase\n\n```mdhavers\nken l = lower(\"HELLO\")  # \"hello\"\n```".to_string()),
@@ -112,9 +161,26 @@ pub fn get_keyword_info(keyword: &str) -> Option<String> {
         "tumble" => Some("**tumble(list, init, fn)** - Reduce/fold list\n\n```mdhavers\nken sum = tumble([1,2,3], 0, |acc, x| acc + x)  # 6\n```\n\nFrom Scots \"tumble\" meaning \"to roll up\".".to_string()),
         "aw" => Some("**aw(list, fn)** - Check if all elements satisfy predicate\n\n```mdhavers\nken all_pos = aw([1,2,3], |x| x > 0)  # aye\n```\n\nFrom Scots \"aw\" meaning \"all\".".to_string()),
         "ony" => Some("**ony(list, fn)** - Check if any element satisfies predicate\n\n```mdhavers\nken has_neg = ony([1,-2,3], |x| x < 0)  # aye\n```\n\nFrom Scots \"ony\" meaning \"any\".".to_string()),
+        "slice" => Some("**slice(x, start, end)** - Slice a list or string\n\n```mdhavers\nken last_two = slice([1,2,3,4], -2, naething)  # [3, 4]\n```\n\nSame as `x[start..end]`: negative bounds count fae the end an' ootside bounds are clamped.".to_string()),
         "hunt" => Some("**hunt(list, fn)** - Find first element satisfying predicate\n\n```mdhavers\nken first_even = hunt([1,2,3,4], |x| x % 2 == 0)  # 2\n```\n\nFrom Scots \"hunt\" meaning \"search\".".to_string()),
+        "any" => Some("**any(list, fn)** - Check if any element satisfies predicate\n\n```mdhavers\nken has_neg = any([1,-2,3], |x| x < 0)  # aye\n```\n\nStops at the first match. Same as `ony`.".to_string()),
+        "all" => Some("**all(list, fn)** - Check if all elements satisfy predicate\n\n```mdhavers\nken all_pos = all([1,2,3], |x| x > 0)  # aye\n```\n\nStops at the first miss. Same as `aw`.".to_string()),
+        "find" => Some("**find(list, fn)** - Find first element satisfying predicate\n\n```mdhavers\nken first_even = find([1,2,3,4], |x| x % 2 == 0)  # 2\n```\n\nGies `naething` if nane match. Same as `hunt`.".to_string()),
+        "pairt" => Some("**pairt(fn, args...)** - Fix the first arguments o' a function\n\n```mdhavers\nken add10 = pairt(|a, b| a + b, 10)\nblether add10(5)  # 15\n```".to_string()),
+        "expeck_error" => Some("**expeck_error(f, text)** - Call `f` an' fail unless it hurls an error (mentionin' `text`, if ye gie it)\n\n```mdhavers\nexpeck_error(|| 1 / 0)\nexpeck_error(|| check_age(-1), \"negative\")\n```".to_string()),
+        "compose" => Some("**compose(f, g)** - A function that works oot `f(g(x))`\n\n```mdhavers\nken inc_then_double = compose(|x| x * 2, |x| x + 1)\nblether inc_then_double(5)  # 12\n```".to_string()),
+        "pipe" => Some("**pipe(x, fns...)** - Pass a value through each function in turn, like `x |> f |> g`\n\n```mdhavers\nblether pipe(5, |x| x * 2, |x| x + 1)  # 11\n```".to_string()),
+        "count" => Some("**count(list, fn)** - Count elements satisfying predicate\n\n```mdhavers\nken evens = count([1,2,3,4], |x| x % 2 == 0)  # 2\n```".to_string()),
         "noo" => Some("**noo()** - Current timestamp in milliseconds\n\n```mdhavers\nken start = noo()\n# ... do stuff ...\nken elapsed = noo() - start\n```\n\nFrom Scots \"noo\" meaning \"now\".".to_string()),
         "bide" => Some("**bide(ms)** - Sleep for milliseconds\n\n```mdhavers\nbide(1000)  # wait 1 second\n```\n\nFrom Scots \"bide\" meaning \"wait\".".to_string()),
+        "now" => Some("**now()** - Seconds since the Unix epoch, as a float\n\n```mdhavers\nken start = now()\nsleep(250)\nblether now() - start  # about 0.25\n```".to_string()),
+        "now_ms" => Some("**now_ms()** - Milliseconds since the Unix epoch, as an integer".to_string()),
+        "sleep" => Some("**sleep(ms)** - Pause for milliseconds\n\nDoes nothing in the browser playground, which cannae block.".to_string()),
+        "format_time" => Some("**format_time(secs, fmt)** - Format epoch seconds as a UTC timestamp\n\n```mdhavers\nformat_time(0, \"%Y-%m-%d %H:%M\")  # \"1970-01-01 00:00\"\n```\n\nUses strftime codes like `%Y`, `%m`, `%d`, `%H`, `%M`, `%S`.".to_string()),
+        "random" => Some("**random()** - Uniform float from 0.0 up to (but not including) 1.0\n\nCall `seed_random(n)` first for the same numbers every run.".to_string()),
+        "random_int" => Some("**random_int(min, max)** - Uniform integer with both ends included\n\n```mdhavers\nken roll = random_int(1, 6)\n```".to_string()),
+        "random_choice" => Some("**random_choice(list)** - Any element of a list, each equally likely\n\nGives `naething` for an empty list.".to_string()),
+        "seed_random" => Some("**seed_random(n)** - Restart the random generator from a known seed\n\n```mdhavers\nseed_random(42)\nblether random_int(1, 6)  # same every run\n```".to_string()),
         "jammy" => Some("**jammy(min, max)** - Random integer in range\n\n```mdhavers\nken lucky = jammy(1, 100)  # random 1-99\n```\n\nFrom Scots \"jammy\" meaning \"lucky\".".to_string()),
         "clype" => Some("**clype(msg)** - Print debug message to stderr\n\n```mdhavers\nclype(\"Debug info here\")\n```\n\nFrom Scots \"clype\" meaning \"to tell tales\".".to_string()),
 
@@ -125,6 +191,15 @@ pub fn get_keyword_info(keyword: &str) -> Option<String> {
         "chuck_oot" => Some("**chuck_oot(creel, item)** - Remove from set\n\n```mdhavers\nchuck_oot(my_set, 42)\n```".to_string()),
 
         // Audio (soond/muisic/midi)
+        "canvas" => Some("**canvas(w, h)** - Make a transparent RGBA canvas\n\n```mdhavers\nken c = canvas(64, 64)\ncanvas_circle(c, 32, 32, 10, \"reid\")\nsave_png(c, \"braw.png\")\n```\n\nNeeds the `canvas` feature.".to_string()),
+        "canvas_set" => Some("**canvas_set(c, x, y, colour)** - Paint one pixel".to_string()),
+        "canvas_get" => Some("**canvas_get(c, x, y)** - The pixel's colour as `[r, g, b, a]`".to_string()),
+        "canvas_fill" => Some("**canvas_fill(c, colour)** - Paint the hale canvas".to_string()),
+        "canvas_line" => Some("**canvas_line(c, x0, y0, x1, y1, colour)** - Draw a straight line".to_string()),
+        "canvas_rect" => Some("**canvas_rect(c, x, y, w, h, colour)** - Draw a filled rectangle".to_string()),
+        "canvas_circle" => Some("**canvas_circle(c, cx, cy, r, colour)** - Draw a filled circle".to_string()),
+        "save_png" => Some("**save_png(canvas, path)** - Write a canvas tae a PNG file\n\n**save_png(path, w, h, pixels)** - Write a flat `[r, g, b, a, ...]` list (w * h * 4 integers) tae a PNG file".to_string()),
+        "load_png" => Some("**load_png(path)** - Read an 8-bit PNG intae a new canvas".to_string()),
         "soond_stairt" => Some("**soond_stairt()** - Start the audio device.".to_string()),
         "soond_steek" => Some("**soond_steek()** - Shut the audio device and unload all audio.".to_string()),
         "soond_wheesht" => Some("**soond_wheesht(aye|nae)** - Mute or unmute master audio.".to_string()),
@@ -172,6 +247,13 @@ pub fn get_keyword_info(keyword: &str) -> Option<String> {
         "midi_pit_luid" => Some("**midi_pit_luid(handle, v)** - Set MIDI volume (0..1).".to_string()),
         "midi_pit_pan" => Some("**midi_pit_pan(handle, pan)** - Set MIDI pan (-1..1).".to_string()),
         "midi_pit_rin_roond" => Some("**midi_pit_rin_roond(handle, aye|nae)** - Loop MIDI.".to_string()),
+        "render_midi" => Some("**render_midi(soundfont, path, sample_rate)** - Render MIDI tae interleaved stereo f32 bytes (soundfont or naething).".to_string()),
+        "save_wav" => Some("**save_wav(samples, sample_rate, path)** - Write a 16-bit WAV. Bytes fae render_midi are stereo; a list is mono, a list o' pairs stereo.\n\n**save_wav(path, sample_rate, channels, samples)** - Write a flat list o' interleaved samples in [-1, 1] wi' ony channel count.".to_string()),
+        "log_debug" => Some("**log_debug(msg, fields?, target?)** - Log at debug (mutter) level.".to_string()),
+        "log_info" => Some("**log_info(msg, fields?, target?)** - Log at info (blether) level.".to_string()),
+        "log_warn" => Some("**log_warn(msg, fields?, target?)** - Log at warn (holler) level.".to_string()),
+        "log_error" => Some("**log_error(msg, fields?, target?)** - Log at error (roar) level.".to_string()),
+        "log_to_file" => Some("**log_to_file(path)** - Append timestamped log lines tae a file instead o' stderr.".to_string()),
 
         _ => None,
     }
@@ -202,6 +284,11 @@ pub fn get_keywords_and_builtins() -> Vec<(String, String, String)> {
             "keyword".to_string(),
             "Then (ternary)".to_string(),
         ),
+        (
+            "by".to_string(),
+            "keyword".to_string(),
+            "Range step".to_string(),
+        ),
         (
             "whiles".to_string(),
             "keyword".to_string(),
@@ -222,6 +309,11 @@ pub fn get_keywords_and_builtins() -> Vec<(String, String, String)> {
             "keyword".to_string(),
             "Return statement".to_string(),
         ),
+        (
+            "gie_back".to_string(),
+            "keyword".to_string(),
+            "Yield from a generator".to_string(),
+        ),
         (
             "blether".to_string(),
             "keyword".to_string(),
@@ -287,6 +379,11 @@ pub fn get_keywords_and_builtins() -> Vec<(String, String, String)> {
             "keyword".to_string(),
             "Import module".to_string(),
         ),
+        (
+            "bring".to_string(),
+            "keyword".to_string(),
+            "Import module intae a namespace".to_string(),
+        ),
         (
             "hae_a_bash".to_string(),
             "keyword".to_string(),
@@ -343,6 +440,21 @@ pub fn get_keywords_and_builtins() -> Vec<(String, String, String)> {
             "function".to_string(),
             "Get type of value".to_string(),
         ),
+        (
+            "airity".to_string(),
+            "function".to_string(),
+            "Count a function's required arguments".to_string(),
+        ),
+        (
+            "shaw".to_string(),
+            "function".to_string(),
+            "Print a type-tagged dump of a value".to_string(),
+        ),
+        (
+            "shaw_pretty".to_string(),
+            "function".to_string(),
+            "Print an indented type-tagged dump".to_string(),
+        ),
         (
             "tae_string".to_string(),
             "function".to_string(),
@@ -358,6 +470,11 @@ pub fn get_keywords_and_builtins() -> Vec<(String, String, String)> {
             "function".to_string(),
             "Convert to float".to_string(),
         ),
+        (
+            "tae_bool".to_string(),
+            "function".to_string(),
+            "Convert to boolean".to_string(),
+        ),
         (
             "shove".to_string(),
             "function".to_string(),
@@ -368,6 +485,21 @@ pub fn get_keywords_and_builtins() -> Vec<(String, String, String)> {
             "function".to_string(),
             "Remove from list (pop)".to_string(),
         ),
+        (
+            "stick_in".to_string(),
+            "function".to_string(),
+            "Insert intae list at index".to_string(),
+        ),
+        (
+            "tak_oot".to_string(),
+            "function".to_string(),
+            "Remove fae list at index".to_string(),
+        ),
+        (
+            "whaur".to_string(),
+            "function".to_string(),
+            "Index o' first match in list".to_string(),
+        ),
         (
             "heid".to_string(),
             "function".to_string(),
@@ -398,6 +530,46 @@ pub fn get_keywords_and_builtins() -> Vec<(String, String, String)> {
             "function".to_string(),
             "Dictionary values".to_string(),
         ),
+        (
+            "get".to_string(),
+            "function".to_string(),
+            "Dictionary lookup wi' a default".to_string(),
+        ),
+        (
+            "has_key".to_string(),
+            "function".to_string(),
+            "Does the dictionary have this key?".to_string(),
+        ),
+        (
+            "remove".to_string(),
+            "function".to_string(),
+            "Remove a dictionary key, returnin' its value".to_string(),
+        ),
+        (
+            "dict_get".to_string(),
+            "function".to_string(),
+            "Dictionary lookup wi' an optional default".to_string(),
+        ),
+        (
+            "dict_set".to_string(),
+            "function".to_string(),
+            "Set a dictionary key in place".to_string(),
+        ),
+        (
+            "dict_has".to_string(),
+            "function".to_string(),
+            "Does the dictionary have this key?".to_string(),
+        ),
+        (
+            "dict_remove".to_string(),
+            "function".to_string(),
+            "Copy a dictionary wi'oot a key".to_string(),
+        ),
+        (
+            "dict_merge".to_string(),
+            "function".to_string(),
+            "Merge two dictionaries, second wins".to_string(),
+        ),
         (
             "abs".to_string(),
             "function".to_string(),
@@ -433,6 +605,26 @@ pub fn get_keywords_and_builtins() -> Vec<(String, String, String)> {
             "function".to_string(),
             "Square root".to_string(),
         ),
+        (
+            "fmt_float".to_string(),
+            "function".to_string(),
+            "Format a float wi' fixed decimals".to_string(),
+        ),
+        (
+            "format".to_string(),
+            "function".to_string(),
+            "Fill {} placeholders wi' values".to_string(),
+        ),
+        (
+            "pow".to_string(),
+            "function".to_string(),
+            "Power".to_string(),
+        ),
+        (
+            "fmod".to_string(),
+            "function".to_string(),
+            "Float remainder".to_string(),
+        ),
         (
             "split".to_string(),
             "function".to_string(),
@@ -453,6 +645,16 @@ pub fn get_keywords_and_builtins() -> Vec<(String, String, String)> {
             "function".to_string(),
             "Reverse list".to_string(),
         ),
+        (
+            "unique".to_string(),
+            "function".to_string(),
+            "Drop duplicates (keeps order)".to_string(),
+        ),
+        (
+            "flatten".to_string(),
+            "function".to_string(),
+            "Flatten one level".to_string(),
+        ),
         (
             "sort".to_string(),
             "function".to_string(),
@@ -503,6 +705,51 @@ pub fn get_keywords_and_builtins() -> Vec<(String, String, String)> {
             "function".to_string(),
             "Find first".to_string(),
         ),
+        (
+            "slice".to_string(),
+            "function".to_string(),
+            "Slice list/string (like xs[a..b])".to_string(),
+        ),
+        (
+            "any".to_string(),
+            "function".to_string(),
+            "Any satisfy (stops at first match)".to_string(),
+        ),
+        (
+            "all".to_string(),
+            "function".to_string(),
+            "All satisfy (stops at first miss)".to_string(),
+        ),
+        (
+            "find".to_string(),
+            "function".to_string(),
+            "First match or naething".to_string(),
+        ),
+        (
+            "count".to_string(),
+            "function".to_string(),
+            "Count matches".to_string(),
+        ),
+        (
+            "pairt".to_string(),
+            "function".to_string(),
+            "Partially apply a function".to_string(),
+        ),
+        (
+            "expeck_error".to_string(),
+            "function".to_string(),
+            "Assert that callin' a function fails".to_string(),
+        ),
+        (
+            "compose".to_string(),
+            "function".to_string(),
+            "Compose two functions".to_string(),
+        ),
+        (
+            "pipe".to_string(),
+            "function".to_string(),
+            "Pass a value through functions".to_string(),
+        ),
         (
             "noo".to_string(),
             "function".to_string(),
@@ -513,11 +760,51 @@ pub fn get_keywords_and_builtins() -> Vec<(String, String, String)> {
             "function".to_string(),
             "Sleep (ms)".to_string(),
         ),
+        (
+            "now".to_string(),
+            "function".to_string(),
+            "Seconds since the epoch (float)".to_string(),
+        ),
+        (
+            "now_ms".to_string(),
+            "function".to_string(),
+            "Milliseconds since the epoch".to_string(),
+        ),
+        (
+            "sleep".to_string(),
+            "function".to_string(),
+            "Sleep (ms)".to_string(),
+        ),
+        (
+            "format_time".to_string(),
+            "function".to_string(),
+            "Format epoch seconds as UTC".to_string(),
+        ),
         (
             "jammy".to_string(),
             "function".to_string(),
             "Random number".to_string(),
         ),
+        (
+            "random".to_string(),
+            "function".to_string(),
+            "Random float in [0, 1)".to_string(),
+        ),
+        (
+            "random_int".to_string(),
+            "function".to_string(),
+            "Random int, both ends included".to_string(),
+        ),
+        (
+            "random_choice".to_string(),
+            "function".to_string(),
+            "Random element of a list".to_string(),
+        ),
+        (
+            "seed_random".to_string(),
+            "function".to_string(),
+            "Seed the random generator".to_string(),
+        ),
         (
             "clype".to_string(),
             "function".to_string(),
@@ -543,6 +830,51 @@ pub fn get_keywords_and_builtins() -> Vec<(String, String, String)> {
             "function".to_string(),
             "Remove from set".to_string(),
         ),
+        (
+            "canvas".to_string(),
+            "function".to_string(),
+            "New RGBA canvas (w, h)".to_string(),
+        ),
+        (
+            "canvas_set".to_string(),
+            "function".to_string(),
+            "Paint one canvas pixel".to_string(),
+        ),
+        (
+            "canvas_get".to_string(),
+            "function".to_string(),
+            "Canvas pixel as [r, g, b, a]".to_string(),
+        ),
+        (
+            "canvas_fill".to_string(),
+            "function".to_string(),
+            "Fill the whole canvas".to_string(),
+        ),
+        (
+            "canvas_line".to_string(),
+            "function".to_string(),
+            "Draw a line on a canvas".to_string(),
+        ),
+        (
+            "canvas_rect".to_string(),
+            "function".to_string(),
+            "Draw a filled rectangle on a canvas".to_string(),
+        ),
+        (
+            "canvas_circle".to_string(),
+            "function".to_string(),
+            "Draw a filled circle on a canvas".to_string(),
+        ),
+        (
+            "save_png".to_string(),
+            "function".to_string(),
+            "Save a canvas as PNG".to_string(),
+        ),
+        (
+            "load_png".to_string(),
+            "function".to_string(),
+            "Load a PNG into a canvas".to_string(),
+        ),
         (
             "soond_stairt".to_string(),
             "function".to_string(),
@@ -768,6 +1100,41 @@ pub fn get_keywords_and_builtins() -> Vec<(String, String, String)> {
             "function".to_string(),
             "Loop MIDI".to_string(),
         ),
+        (
+            "render_midi".to_string(),
+            "function".to_string(),
+            "Render MIDI to stereo f32 sample bytes".to_string(),
+        ),
+        (
+            "save_wav".to_string(),
+            "function".to_string(),
+            "Write samples to a WAV file".to_string(),
+        ),
+        (
+            "log_debug".to_string(),
+            "function".to_string(),
+            "Log a debug message".to_string(),
+        ),
+        (
+            "log_info".to_string(),
+            "function".to_string(),
+            "Log an info message".to_string(),
+        ),
+        (
+            "log_warn".to_string(),
+            "function".to_string(),
+            "Log a warning".to_string(),
+        ),
+        (
+            "log_error".to_string(),
+            "function".to_string(),
+            "Log an error".to_string(),
+        ),
+        (
+            "log_to_file".to_string(),
+            "function".to_string(),
+            "Append log lines to a file".to_string(),
+        ),
     ]
 }
 
@@ -789,7 +1156,102 @@ mod tests {
         let source = "gin x > 0 {\n    blether x\n";
         let diagnostics = get_diagnostics(source);
         assert!(!diagnostics.is_empty());
-        assert!(diagnostics.iter().any(|d| d.3 == "error"));
+        assert!(diagnostics.iter().any(|d| d.5 == "error"));
+    }
+
+    #[test]
+    fn test_get_diagnostics_warns_at_each_deprecated_call() {
+        let source = "@deprecated(\"use new_fn instead\")\ndae old_fn() {\n    gie 1\n}\nold_fn()\nken y = old_fn() + 1\n";
+        let diagnostics = get_diagnostics(source);
+        assert_eq!(
+            diagnostics,
+            vec![
+                (
+                    5,
+                    1,
+                    5,
+                    7,
+                    "'old_fn' is deprecated: use new_fn instead".to_string(),
+                    "warning".to_string()
+                ),
+                (
+                    6,
+                    9,
+                    6,
+                    15,
+                    "'old_fn' is deprecated: use new_fn instead".to_string(),
+                    "warning".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get_diagnostics_warns_about_unused_locals_an_dead_code() {
+        let source = "dae f(x) {\n    ken spare = 1\n    gie x\n    blether x\n}\nblether f(2)\n";
+        let diagnostics = get_diagnostics(source);
+        assert_eq!(
+            diagnostics,
+            vec![
+                (
+                    2,
+                    9,
+                    2,
+                    14,
+                    "'spare' is declared but never used".to_string(),
+                    "warning".to_string()
+                ),
+                (
+                    4,
+                    5,
+                    4,
+                    14,
+                    "Unreachable code efter `gie`".to_string(),
+                    "warning".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get_diagnostics_includes_type_warnings() {
+        let diagnostics = get_diagnostics("ken x = 5\nblether x[0]\nblether \"a\" + 1\n");
+        let found: Vec<_> = diagnostics
+            .iter()
+            .map(|d| (d.0, d.1, d.4.as_str(), d.5.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (2, 9, "Cannae index a integer", "warning"),
+                (
+                    3,
+                    13,
+                    "Addin' a string an' a integer sticks them thegither as text",
+                    "warning"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get_diagnostics_underlines_the_exact_token() {
+        let diagnostics = get_diagnostics("ken x = 1\nken y = (x + ]\n");
+        assert_eq!(diagnostics.len(), 1, "{diagnostics:?}");
+        let (line, col, end_line, end_col, _, severity) = &diagnostics[0];
+        assert_eq!((*line, *col, *end_line, *end_col), (2, 14, 2, 15));
+        assert_eq!(severity, "error");
+
+        let diagnostics = get_diagnostics("ken s = \"ok\" $oops");
+        let (line, col, end_line, end_col, _, _) = &diagnostics[0];
+        assert_eq!((*line, *col, *end_line, *end_col), (1, 14, 1, 15));
+    }
+
+    #[test]
+    fn test_get_diagnostics_reports_every_syntax_error() {
+        let diagnostics = get_diagnostics("ken = 1\nblether 2\nken y = (1 + ]\n");
+        let lines: Vec<_> = diagnostics.iter().map(|d| (d.0, d.5.as_str())).collect();
+        assert_eq!(lines, vec![(1, "error"), (3, "error")]);
     }
 
     #[test]
@@ -798,16 +1260,16 @@ mod tests {
             message: "nope".to_string(),
             line: 3,
         };
-        let (line, col, _message, severity) = error_to_diagnostic(err);
-        assert_eq!(line, 3);
-        assert_eq!(col, 1);
+        let (line, col, end_line, end_col, _message, severity) =
+            error_to_diagnostic(err, "ken a = 1\n\nken c = a / 0\n");
+        assert_eq!((line, col, end_line, end_col), (3, 1, 3, 14));
         assert_eq!(severity, "error");
     }
 
     #[test]
     fn test_error_to_diagnostic_uses_line_default_when_unknown_for_coverage() {
         let err = HaversError::InternalError("boom".to_string());
-        let (line, col, _message, severity) = error_to_diagnostic(err);
+        let (line, col, _end_line, _end_col, _message, severity) = error_to_diagnostic(err, "");
         assert_eq!(line, 1);
         assert_eq!(col, 1);
         assert_eq!(severity, "error");
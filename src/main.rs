@@ -1,4 +1,5 @@
 use std::fs;
+use std::io::{self, IsTerminal};
 use std::path::PathBuf;
 use std::process;
 
@@ -7,8 +8,8 @@ use colored::*;
 use rustyline::error::ReadlineError;
 use rustyline::DefaultEditor;
 
-use mdhavers::compiler::compile;
-use mdhavers::error::{format_error_context, random_scots_exclamation};
+use mdhavers::compiler::{compile, compile_with_source_map};
+use mdhavers::error::{format_error_context, format_error_span_context, random_scots_exclamation};
 use mdhavers::formatter;
 use mdhavers::lexer;
 use mdhavers::parser::parse;
@@ -18,7 +19,9 @@ use mdhavers::Value;
 
 // Crash handler helpers are excluded from source-based coverage runs.
 #[cfg(not(coverage))]
-use mdhavers::interpreter::{is_crash_handling_enabled, print_stack_trace};
+use mdhavers::interpreter::{
+    classify_panic, is_crash_handling_enabled, print_stack_trace, PanicOrigin,
+};
 
 /// Initialize crash handlers for graceful error reporting
 #[cfg(not(coverage))]
@@ -27,23 +30,39 @@ fn setup_crash_handlers() {
     let default_panic = std::panic::take_hook();
     std::panic::set_hook(Box::new(move |panic_info| {
         if is_crash_handling_enabled() {
-            eprintln!("\n{}", "🏴󠁧󠁢󠁳󠁣󠁴󠁿 Och noo! The program crashed!".red().bold());
+            let message = if let Some(message) = panic_info.payload().downcast_ref::<&str>() {
+                Some(message.to_string())
+            } else {
+                panic_info.payload().downcast_ref::<String>().cloned()
+            };
+            let origin = classify_panic(message.as_deref().unwrap_or(""));
+
+            let headline = match origin {
+                PanicOrigin::UserCode => {
+                    "🏴󠁧󠁢󠁳󠁣󠁴󠁿 Och noo! A built-in fell ower on whit yer script gied it!"
+                }
+                PanicOrigin::Internal => "🏴󠁧󠁢󠁳󠁣󠁴󠁿 Och noo! The interpreter itsel' has crashed!",
+            };
+            eprintln!("\n{}", headline.red().bold());
             if let Some(location) = panic_info.location() {
                 eprintln!(
                     "{}",
                     format!("Panic at {}:{}", location.file(), location.line()).red()
                 );
             }
-            if let Some(message) = panic_info.payload().downcast_ref::<&str>() {
-                eprintln!("{}", format!("Message: {}", message).red());
-            } else if let Some(message) = panic_info.payload().downcast_ref::<String>() {
+            if let Some(message) = &message {
                 eprintln!("{}", format!("Message: {}", message).red());
             }
             print_stack_trace();
-            eprintln!(
-                "\n{}",
-                "This shouldnae hae happened! Please report this issue.".yellow()
-            );
+            let advice = match origin {
+                PanicOrigin::UserCode => {
+                    "Check the arguments ye passed near the tap o' the stack trace. \
+                     If they look fine, please report this issue. \
+                     (Use boak(msg) tae stop a program on purpose.)"
+                }
+                PanicOrigin::Internal => "This shouldnae hae happened! Please report this issue.",
+            };
+            eprintln!("\n{}", advice.yellow());
         }
         default_panic(panic_info);
     }));
@@ -58,7 +77,7 @@ fn setup_crash_handlers() {}
 #[derive(Parser)]
 #[command(name = "mdhavers")]
 #[command(author = "Arthur")]
-#[command(version = "0.1.0")]
+#[command(version = mdhavers::version())]
 #[command(about = "A Scots programming language - pure havers, but working havers!", long_about = None)]
 struct Cli {
     #[command(subcommand)]
@@ -67,6 +86,10 @@ struct Cli {
     /// Run a .braw file directly
     #[arg(value_name = "FILE")]
     file: Option<PathBuf>,
+
+    /// Seed the random built-ins so the same seed gives the same run
+    #[arg(long, global = true, value_name = "N")]
+    seed: Option<u64>,
 }
 
 #[derive(Subcommand)]
@@ -75,6 +98,18 @@ enum Commands {
     Run {
         /// The .braw file to run
         file: PathBuf,
+
+        /// Skip loading the prelude (only native built-ins are available)
+        #[arg(long)]
+        no_prelude: bool,
+
+        /// Let integers grow past 64 bits instead of failing on overflow
+        #[arg(long)]
+        big_ints: bool,
+
+        /// Stop printing after this many lines (the program keeps running)
+        #[arg(long, value_name = "N")]
+        max_output: Option<usize>,
     },
 
     /// Compile a .braw program to JavaScript
@@ -85,15 +120,41 @@ enum Commands {
         /// Output file (defaults to <input>.js)
         #[arg(short, long)]
         output: Option<PathBuf>,
+
+        /// Also write a source map to <output>.map
+        #[arg(long)]
+        sourcemap: bool,
     },
 
     /// Start the interactive REPL
-    Repl,
+    Repl {
+        /// Skip loading the prelude (only native built-ins are available)
+        #[arg(long)]
+        no_prelude: bool,
+    },
 
     /// Check a .braw file for errors without running it
     Check {
         /// The .braw file to check
         file: PathBuf,
+
+        /// Print errors and warnings as a JSON array instead
+        #[arg(long)]
+        json: bool,
+
+        /// Only report errors, not the lint warnings
+        #[arg(long)]
+        no_warnings: bool,
+
+        /// Also warn about operations that are plainly the wrong type
+        #[arg(long)]
+        lint: bool,
+    },
+
+    /// Explain an error code (like E0003) in more detail
+    Explain {
+        /// The error code, as shown in square brackets on the error
+        code: String,
     },
 
     /// Format a .braw file (pretty print)
@@ -117,6 +178,10 @@ enum Commands {
     Ast {
         /// The .braw file to parse
         file: PathBuf,
+
+        /// Dump the AST as JSON instead of the debug listing
+        #[arg(long)]
+        json: bool,
     },
 
     /// Trace a .braw program (step-by-step execution wi' Scottish commentary)
@@ -137,6 +202,14 @@ enum Commands {
         /// Output file (defaults to <input>.wat)
         #[arg(short, long)]
         output: Option<PathBuf>,
+
+        /// Export the host memory as well as every top-level function
+        #[arg(long)]
+        export_all: bool,
+
+        /// Assemble straight to a binary .wasm (implied by a .wasm output)
+        #[arg(long)]
+        binary: bool,
     },
 
     /// Run a .wat or .wasm file using the built-in host runner
@@ -144,6 +217,10 @@ enum Commands {
     WasmRun {
         /// The .wat or .wasm file to run
         file: PathBuf,
+
+        /// Arguments passed to the module's args()
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
     },
 
     /// Build a native executable using LLVM
@@ -156,12 +233,20 @@ enum Commands {
         output: Option<PathBuf>,
 
         /// Optimization level (0-3)
-        #[arg(short = 'O', long, default_value = "2")]
+        #[arg(short = 'O', long, visible_alias = "opt", default_value = "2")]
         opt_level: u8,
 
-        /// Emit LLVM IR instead of native binary
-        #[arg(long)]
+        /// Emit LLVM IR instead of native binary (after the -O passes; -O0 gives the raw IR)
+        #[arg(long, visible_alias = "dump-ir")]
         emit_llvm: bool,
+
+        /// Trap on integer overflow instead of wrapping (slower)
+        #[arg(long)]
+        checked: bool,
+
+        /// Emit debug info (line numbers and variables) for gdb/lldb
+        #[arg(short = 'g', long)]
+        debug: bool,
     },
 }
 
@@ -171,31 +256,68 @@ fn main() {
 
     let cli = Cli::parse();
 
+    let seed = cli.seed;
     let result = match cli.command {
-        Some(Commands::Run { file }) => run_file(&file),
-        Some(Commands::Compile { file, output }) => compile_file(&file, output),
-        Some(Commands::Repl) => run_repl(),
-        Some(Commands::Check { file }) => check_file(&file),
+        Some(Commands::Run {
+            file,
+            no_prelude,
+            big_ints,
+            max_output,
+        }) => run_file(
+            &file,
+            &RunOptions {
+                no_prelude,
+                big_ints,
+                max_output,
+                seed,
+            },
+        ),
+        Some(Commands::Compile {
+            file,
+            output,
+            sourcemap,
+        }) => compile_file(&file, output, sourcemap),
+        Some(Commands::Repl { no_prelude }) => run_repl(no_prelude, seed),
+        Some(Commands::Check {
+            file,
+            json,
+            no_warnings,
+            lint,
+        }) => check_file(&file, json, !no_warnings, lint),
+        Some(Commands::Explain { code }) => explain_code(&code),
         Some(Commands::Format { file, check }) => format_file(&file, check),
         Some(Commands::Tokens { file }) => show_tokens(&file),
-        Some(Commands::Ast { file }) => show_ast(&file),
-        Some(Commands::Trace { file, verbose }) => trace_file(&file, verbose),
-        Some(Commands::Wasm { file, output }) => compile_wasm(&file, output),
+        Some(Commands::Ast { file, json }) => show_ast(&file, json),
+        Some(Commands::Trace { file, verbose }) => trace_file(&file, verbose, seed),
+        Some(Commands::Wasm {
+            file,
+            output,
+            export_all,
+            binary,
+        }) => compile_wasm(&file, output, export_all, binary),
         #[cfg(feature = "wasm_runner")]
-        Some(Commands::WasmRun { file }) => mdhavers::wasm_runner::run_wasm_file(&file),
+        Some(Commands::WasmRun { file, args }) => run_wasm(&file, &args),
         Some(Commands::Build {
             file,
             output,
             opt_level,
             emit_llvm,
-        }) => build_native(&file, output, opt_level, emit_llvm),
+            checked,
+            debug,
+        }) => build_native(&file, output, opt_level, emit_llvm, checked, debug, seed),
         None => {
             // If a file is provided directly, run it
             if let Some(file) = cli.file {
-                run_file(&file)
+                run_file(
+                    &file,
+                    &RunOptions {
+                        seed,
+                        ..RunOptions::default()
+                    },
+                )
             } else {
                 // Otherwise, start REPL
-                run_repl()
+                run_repl(false, seed)
             }
         }
     };
@@ -206,13 +328,53 @@ fn main() {
     }
 }
 
-fn run_file(path: &PathBuf) -> Result<(), String> {
+/// Run a WASM module, print what its `main` returned and exit with its code
+#[cfg(feature = "wasm_runner")]
+fn run_wasm(path: &std::path::Path, args: &[String]) -> Result<(), String> {
+    let result = mdhavers::wasm_runner::run_wasm_file(path, args)?;
+    if let Some(value) = result.value {
+        println!("{}", value);
+    }
+    if result.exit_code != 0 {
+        process::exit(result.exit_code);
+    }
+    Ok(())
+}
+
+/// Settings for running a file, filled in by `run` and by the bare `mdhavers <file>` form
+#[derive(Debug, Default)]
+struct RunOptions {
+    /// Skip loading the prelude
+    no_prelude: bool,
+    /// Let integers grow past 64 bits instead of failing on overflow
+    big_ints: bool,
+    /// Stop printing after this many lines
+    max_output: Option<usize>,
+    /// Seed for the random built-ins
+    seed: Option<u64>,
+}
+
+fn run_file(path: &PathBuf, options: &RunOptions) -> Result<(), String> {
     let source = read_file(path)?;
     let program = match parse(&source) {
         Ok(p) => p,
         Err(e) => return Err(format_parse_error(&source, e)),
     };
     let mut interpreter = Interpreter::new();
+    interpreter.set_big_integers(options.big_ints);
+    interpreter.set_output_limit(options.max_output);
+    if let Some(seed) = options.seed {
+        interpreter.seed_random(seed);
+    }
+
+    // Print each line as it comes and don't keep them - nobody reads the buffer
+    interpreter.set_output_sink(Box::new(|line| println!("{}", line)));
+    interpreter.set_keep_output(false);
+
+    // Piped or redirected stdin feeds `speir` line by line, ending in nil
+    if !io::stdin().is_terminal() {
+        interpreter.set_input_source(Box::new(io::stdin().lock()));
+    }
 
     // Set the current file name fer logging
     let filename = path
@@ -228,8 +390,10 @@ fn run_file(path: &PathBuf) -> Result<(), String> {
     }
 
     // Load the prelude (standard utility functions)
-    if let Err(e) = interpreter.load_prelude() {
-        return Err(format!("Error loading prelude: {}", e));
+    if !options.no_prelude {
+        if let Err(e) = interpreter.load_prelude() {
+            return Err(format!("Error loading prelude: {}", e));
+        }
     }
 
     if let Err(e) = interpreter.interpret(&program) {
@@ -239,7 +403,7 @@ fn run_file(path: &PathBuf) -> Result<(), String> {
     Ok(())
 }
 
-fn trace_file(path: &PathBuf, verbose: bool) -> Result<(), String> {
+fn trace_file(path: &PathBuf, verbose: bool, seed: Option<u64>) -> Result<(), String> {
     use mdhavers::interpreter::TraceMode;
 
     let source = read_file(path)?;
@@ -248,6 +412,9 @@ fn trace_file(path: &PathBuf, verbose: bool) -> Result<(), String> {
         Err(e) => return Err(format_parse_error(&source, e)),
     };
     let mut interpreter = Interpreter::new();
+    if let Some(seed) = seed {
+        interpreter.seed_random(seed);
+    }
 
     // Set the trace mode
     interpreter.set_trace_mode(if verbose {
@@ -301,12 +468,8 @@ fn trace_file(path: &PathBuf, verbose: bool) -> Result<(), String> {
     Ok(())
 }
 
-fn compile_file(path: &PathBuf, output: Option<PathBuf>) -> Result<(), String> {
+fn compile_file(path: &PathBuf, output: Option<PathBuf>, sourcemap: bool) -> Result<(), String> {
     let source = read_file(path)?;
-    let js_code = match compile(&source) {
-        Ok(js) => js,
-        Err(e) => return Err(format_parse_error(&source, e)),
-    };
 
     let output_path = output.unwrap_or_else(|| {
         let mut p = path.clone();
@@ -314,6 +477,32 @@ fn compile_file(path: &PathBuf, output: Option<PathBuf>) -> Result<(), String> {
         p
     });
 
+    let js_code = if sourcemap {
+        let file_name = |p: &PathBuf| {
+            p.file_name()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or(p.display().to_string())
+        };
+        let (mut js, map) = match compile_with_source_map(&source, &file_name(path)) {
+            Ok(compiled) => compiled,
+            Err(e) => return Err(format_parse_error(&source, e)),
+        };
+
+        let mut map_path = output_path.clone().into_os_string();
+        map_path.push(".map");
+        let map_path = PathBuf::from(map_path);
+        if let Err(e) = fs::write(&map_path, map) {
+            return Err(format!("Cannae write tae {}: {}", map_path.display(), e));
+        }
+        js.push_str(&format!("//# sourceMappingURL={}\n", file_name(&map_path)));
+        js
+    } else {
+        match compile(&source) {
+            Ok(js) => js,
+            Err(e) => return Err(format_parse_error(&source, e)),
+        }
+    };
+
     if let Err(e) = fs::write(&output_path, &js_code) {
         return Err(format!("Cannae write tae {}: {}", output_path.display(), e));
     }
@@ -328,19 +517,59 @@ fn compile_file(path: &PathBuf, output: Option<PathBuf>) -> Result<(), String> {
     Ok(())
 }
 
-fn compile_wasm(path: &PathBuf, output: Option<PathBuf>) -> Result<(), String> {
+fn compile_wasm(
+    path: &PathBuf,
+    output: Option<PathBuf>,
+    export_all: bool,
+    binary: bool,
+) -> Result<(), String> {
     let source = read_file(path)?;
-    let wat_code = match wasm_compiler::compile_to_wat(&source) {
+    let wat_code = match parse(&source).and_then(|program| {
+        wasm_compiler::WasmCompiler::new()
+            .with_export_all(export_all)
+            .compile(&program)
+    }) {
         Ok(wat) => wat,
         Err(e) => return Err(format_parse_error(&source, e)),
     };
 
-    let output_path = output.unwrap_or_else(|| {
+    let binary = binary
+        || output
+            .as_ref()
+            .is_some_and(|p| p.extension().is_some_and(|ext| ext == "wasm"));
+    let mut output_path = output.unwrap_or_else(|| {
         let mut p = path.clone();
-        p.set_extension("wat");
+        p.set_extension(if binary { "wasm" } else { "wat" });
         p
     });
 
+    if binary {
+        match wasm_compiler::assemble_wat(&wat_code) {
+            Ok(bytes) => {
+                if let Err(e) = fs::write(&output_path, bytes) {
+                    return Err(format!("Cannae write tae {}: {}", output_path.display(), e));
+                }
+                println!(
+                    "{} Compiled {} tae WebAssembly (binary)",
+                    "Braw!".green().bold(),
+                    path.display()
+                );
+                println!("  {} {}", "Output:".dimmed(), output_path.display());
+                return Ok(());
+            }
+            Err(e) => {
+                // Still give them the WAT, just not under a .wasm name
+                output_path.set_extension("wat");
+                eprintln!(
+                    "{} Couldnae assemble binary WASM ({}) - writin' WAT tae {} instead",
+                    "Warning:".yellow().bold(),
+                    e,
+                    output_path.display()
+                );
+            }
+        }
+    }
+
     if let Err(e) = fs::write(&output_path, &wat_code) {
         return Err(format!("Cannae write tae {}: {}", output_path.display(), e));
     }
@@ -352,8 +581,12 @@ fn compile_wasm(path: &PathBuf, output: Option<PathBuf>) -> Result<(), String> {
     );
     println!("  {} {}", "Output:".dimmed(), output_path.display());
     println!();
-    println!("{}", "Tae convert tae binary WASM, use:".dimmed());
-    println!("  {} wat2wasm {}", "$".dimmed(), output_path.display());
+    println!("{}", "Tae get binary WASM instead, use:".dimmed());
+    println!(
+        "  {} mdhavers wasm {} --binary",
+        "$".dimmed(),
+        path.display()
+    );
 
     Ok(())
 }
@@ -364,6 +597,9 @@ fn build_native(
     _output: Option<PathBuf>,
     _opt_level: u8,
     _emit_llvm: bool,
+    _checked: bool,
+    _debug: bool,
+    _seed: Option<u64>,
 ) -> Result<(), String> {
     use colored::Colorize;
     eprintln!("{}", "═".repeat(60).yellow());
@@ -416,6 +652,9 @@ fn build_native(
     output: Option<PathBuf>,
     opt_level: u8,
     emit_llvm: bool,
+    checked: bool,
+    debug: bool,
+    seed: Option<u64>,
 ) -> Result<(), String> {
     let source = read_file(path)?;
     let program = match parse(&source) {
@@ -425,8 +664,12 @@ fn build_native(
 
     if emit_llvm {
         // Emit LLVM IR
-        let compiler = mdhavers::LLVMCompiler::new().with_optimization(opt_level);
-        let ir = match compiler.compile_to_ir(&program) {
+        let compiler = mdhavers::LLVMCompiler::new()
+            .with_optimization(opt_level)
+            .with_checked_arithmetic(checked)
+            .with_debug_info(debug)
+            .with_random_seed(seed);
+        let ir = match compiler.compile_to_optimized_ir(&program) {
             Ok(ir) => ir,
             Err(e) => return Err(format!("{}", e)),
         };
@@ -455,7 +698,10 @@ fn build_native(
             p
         });
 
-        let compiler = mdhavers::LLVMCompiler::new();
+        let compiler = mdhavers::LLVMCompiler::new()
+            .with_checked_arithmetic(checked)
+            .with_debug_info(debug)
+            .with_random_seed(seed);
         if let Err(e) =
             compiler.compile_to_native_with_source(&program, &output_path, opt_level, Some(path))
         {
@@ -528,7 +774,7 @@ fn repl_needs_more_input(buffer: &str) -> bool {
     in_string || braces > 0 || brackets > 0 || parens > 0
 }
 
-fn run_repl() -> Result<(), String> {
+fn run_repl(no_prelude: bool, seed: Option<u64>) -> Result<(), String> {
     use mdhavers::interpreter::TraceMode;
 
     println!("{}", "═".repeat(50).cyan());
@@ -564,13 +810,18 @@ fn run_repl() -> Result<(), String> {
     }
 
     let mut interpreter = Interpreter::new();
+    if let Some(seed) = seed {
+        interpreter.seed_random(seed);
+    }
     let mut trace_enabled = false;
     let mut verbose_trace = false;
     let mut buffer = String::new();
 
     // Load the prelude fer REPL users
-    if let Err(e) = interpreter.load_prelude() {
-        eprintln!("{}: Couldnae load prelude: {}", "Warning".yellow(), e);
+    if !no_prelude {
+        if let Err(e) = interpreter.load_prelude() {
+            eprintln!("{}: Couldnae load prelude: {}", "Warning".yellow(), e);
+        }
     }
 
     loop {
@@ -636,9 +887,9 @@ fn run_repl() -> Result<(), String> {
                             continue;
                         }
                         ":reset" | "reset" => {
-                            interpreter = Interpreter::new();
-                            if let Err(e) = interpreter.load_prelude() {
-                                eprintln!("{}: Couldnae load prelude: {}", "Warning".yellow(), e);
+                            interpreter.reset_environment();
+                            if let Some(seed) = seed {
+                                interpreter.seed_random(seed);
                             }
                             buffer.clear();
                             trace_enabled = false;
@@ -781,6 +1032,7 @@ fn print_repl_help() {
     println!("  {}  - while loop", "whiles".green());
     println!("  {}  - for loop", "fer".green());
     println!("  {}  - return from function (give)", "gie".green());
+    println!("  {}  - yield from a generator (give back)", "gie_back".green());
     println!("  {}  - print output (chat)", "blether".green());
     println!("  {}  - get user input (ask)", "speir".green());
     println!("  {}  - define a function (do)", "dae".green());
@@ -1070,8 +1322,11 @@ fn print_environment(interpreter: &Interpreter) {
     println!();
 }
 
-fn check_file(path: &PathBuf) -> Result<(), String> {
+fn check_file(path: &PathBuf, json: bool, warnings: bool, types: bool) -> Result<(), String> {
     let source = read_file(path)?;
+    if json {
+        return check_file_json(&source, warnings, types);
+    }
 
     // Lex
     let tokens = match lexer::lex(&source) {
@@ -1081,12 +1336,22 @@ fn check_file(path: &PathBuf) -> Result<(), String> {
     println!("{} Lexing passed ({} tokens)", "✓".green(), tokens.len());
 
     // Parse
-    let _program = match parse(&source) {
+    let program = match parse(&source) {
         Ok(p) => p,
         Err(e) => return Err(format_parse_error(&source, e)),
     };
     println!("{} Parsing passed", "✓".green());
 
+    for warning in lint_warnings(&program, &source, warnings, types) {
+        println!(
+            "{} line {}:{}: {}",
+            "Warning:".yellow().bold(),
+            warning.line,
+            warning.column,
+            warning.message
+        );
+    }
+
     println!(
         "\n{} {} looks braw!",
         "Bonnie!".green().bold(),
@@ -1096,6 +1361,73 @@ fn check_file(path: &PathBuf) -> Result<(), String> {
     Ok(())
 }
 
+/// The warnings `check` should show, in source order
+fn lint_warnings(
+    program: &mdhavers::ast::Program,
+    source: &str,
+    warnings: bool,
+    types: bool,
+) -> Vec<mdhavers::lint::Warning> {
+    let mut found = Vec::new();
+    if warnings {
+        found.extend(mdhavers::lint::check_program(program, source));
+        if types {
+            found.extend(mdhavers::lint::check_types(program, source));
+        }
+    }
+    found.sort_by_key(|warning| (warning.line, warning.column));
+    found
+}
+
+/// `check --json`: one object per error or warning, exit 1 if there's an error
+fn check_file_json(source: &str, warnings: bool, types: bool) -> Result<(), String> {
+    let entry = |line: usize, column: usize, severity: &str, message: String| {
+        serde_json::json!({
+            "line": line,
+            "column": column,
+            "severity": severity,
+            "message": message,
+        })
+    };
+
+    let (entries, failed) = match lexer::lex(source).and_then(|_| parse(source)) {
+        Ok(program) => {
+            let warnings = lint_warnings(&program, source, warnings, types)
+                .into_iter()
+                .map(|w| {
+                    let mut warning = entry(w.line, w.column, "warning", w.message);
+                    warning["end_line"] = w.end_line.into();
+                    warning["end_column"] = w.end_column.into();
+                    warning
+                })
+                .collect::<Vec<_>>();
+            (warnings, false)
+        }
+        Err(e) => {
+            let mut error = match e.span() {
+                // Errors from the lexer and parser know exactly where they end
+                Some(span) => {
+                    let mut error = entry(span.start_line, span.start_col, "error", e.to_string());
+                    error["end_line"] = span.end_line.into();
+                    error["end_column"] = span.end_col.into();
+                    error
+                }
+                None => entry(e.line().unwrap_or(1), 1, "error", e.to_string()),
+            };
+            error["code"] = e.code().into();
+            (vec![error], true)
+        }
+    };
+
+    let dump = serde_json::to_string_pretty(&entries)
+        .map_err(|e| format!("Couldnae serialise the diagnostics: {}", e))?;
+    println!("{}", dump);
+    if failed {
+        process::exit(1);
+    }
+    Ok(())
+}
+
 fn format_file(path: &PathBuf, check_only: bool) -> Result<(), String> {
     let source = read_file(path)?;
 
@@ -1160,13 +1492,20 @@ fn show_tokens(path: &PathBuf) -> Result<(), String> {
     Ok(())
 }
 
-fn show_ast(path: &PathBuf) -> Result<(), String> {
+fn show_ast(path: &PathBuf, json: bool) -> Result<(), String> {
     let source = read_file(path)?;
     let program = match parse(&source) {
         Ok(p) => p,
         Err(e) => return Err(format_parse_error(&source, e)),
     };
 
+    if json {
+        let dump = serde_json::to_string_pretty(&program)
+            .map_err(|e| format!("Couldnae serialise the AST: {}", e))?;
+        println!("{}", dump);
+        return Ok(());
+    }
+
     println!("{}", "AST:".cyan().bold());
     println!("{}", "─".repeat(50));
 
@@ -1202,10 +1541,35 @@ fn read_file(path: &PathBuf) -> Result<String, String> {
     }
 }
 
+/// Point at `mdhavers explain` for the long-form help
+fn explain_hint(error: &mdhavers::HaversError) -> String {
+    format!(
+        "\nRun `mdhavers explain {}` fer mair aboot this error.",
+        error.code()
+    )
+}
+
+/// `explain`: print the long-form help for an error code
+fn explain_code(code: &str) -> Result<(), String> {
+    match mdhavers::error::explain_error(code) {
+        Some(explanation) => {
+            print!("{}", explanation.render());
+            Ok(())
+        }
+        None => Err(format!(
+            "There's nae such code as '{}' - error codes look like E0003",
+            code
+        )),
+    }
+}
+
 fn format_parse_error(source: &str, error: mdhavers::HaversError) -> String {
-    let mut msg = format!("{}", error);
+    let mut msg = format!("[{}] {}", error.code(), error);
 
-    if let Some(line) = error.line() {
+    if let Some(span) = error.span() {
+        msg.push_str("\n\n");
+        msg.push_str(&format_error_span_context(source, span));
+    } else if let Some(line) = error.line() {
         msg.push_str("\n\n");
         msg.push_str(&format_error_context(source, line));
     }
@@ -1215,23 +1579,31 @@ fn format_parse_error(source: &str, error: mdhavers::HaversError) -> String {
         msg.push('\n');
         msg.push_str(suggestion);
     }
+    msg.push_str(&explain_hint(&error));
 
     msg
 }
 
 fn format_runtime_error(source: &str, error: mdhavers::HaversError) -> String {
-    let mut msg = format!("{}", error);
+    let mut msg = format!("[{}] {}", error.code(), error);
 
     if let Some(line) = error.line() {
         msg.push_str("\n\n");
         msg.push_str(&format_error_context(source, line));
     }
 
+    // Show the calls that led here when the error came out of a function
+    if let Some(backtrace) = error.backtrace() {
+        msg.push_str("\n\nBacktrace (most recent call first):\n");
+        msg.push_str(backtrace);
+    }
+
     // Add helpful suggestion if available
     if let Some(suggestion) = mdhavers::error::get_error_suggestion(&error) {
         msg.push('\n');
         msg.push_str(suggestion);
     }
+    msg.push_str(&explain_hint(&error));
 
     msg
 }
@@ -1257,7 +1629,7 @@ fn format_runtime_error(source: &str, error: mdhavers::HaversError) -> String {
         let dir = tempdir().expect("tempdir");
         let path = dir.path().join("hello.braw");
         std::fs::write(&path, "blether 1\n").expect("write file");
-        run_file(&path).expect("run file");
+        run_file(&path, &RunOptions::default()).expect("run file");
     }
 
     #[test]
@@ -1265,7 +1637,7 @@ fn format_runtime_error(source: &str, error: mdhavers::HaversError) -> String {
         let dir = tempdir().expect("tempdir");
         let path = dir.path().join("hello.braw");
         std::fs::write(&path, "blether 1\n").expect("write file");
-        trace_file(&path, false).expect("trace file");
+        trace_file(&path, false, None).expect("trace file");
     }
 
     #[test]
@@ -1308,8 +1680,8 @@ fn format_runtime_error(source: &str, error: mdhavers::HaversError) -> String {
         std::fs::write(&filename, "blether 1\n").expect("write file");
 
         let path = PathBuf::from(&filename);
-        run_file(&path).expect("run file");
-        trace_file(&path, false).expect("trace file");
+        run_file(&path, &RunOptions::default()).expect("run file");
+        trace_file(&path, false, None).expect("trace file");
 
         std::fs::remove_file(&filename).expect("cleanup file");
     }
@@ -1325,4 +1697,15 @@ fn format_runtime_error(source: &str, error: mdhavers::HaversError) -> String {
         let msg = format_runtime_error("", err);
         assert!(msg.contains("Cannae find module"));
     }
+
+    #[test]
+    fn format_runtime_error_shows_backtrace() {
+        let err = mdhavers::HaversError::WithBacktrace {
+            error: Box::new(mdhavers::HaversError::DivisionByZero { line: 1 }),
+            backtrace: "  at inner (prog.braw:1)".to_string(),
+        };
+        let msg = format_runtime_error("gie 1 / 0\n", err);
+        assert!(msg.contains("divide by zero"));
+        assert!(msg.contains("Backtrace (most recent call first):\n  at inner (prog.braw:1)"));
+    }
 }
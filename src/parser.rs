@@ -1,16 +1,26 @@
 use crate::ast::{LogLevel, *};
-use crate::error::{HaversError, HaversResult};
-use crate::token::{Token, TokenKind};
+use crate::error::{ErrorSpan, HaversError, HaversResult};
+use crate::token::{Token, TokenKind, KEYWORDS};
+
+/// The most errors `parse_all` reports, so one mistake cannot bury you in
+/// a cascade of them
+pub const MAX_PARSE_ERRORS: usize = 10;
 
 /// The parser - turns tokens intae an AST
 pub struct Parser {
     tokens: Vec<Token>,
     current: usize,
+    /// Where the `..` of a `xs[a..b]` slice sits, so `a` does not swallow it as a range
+    slice_dots: Option<usize>,
 }
 
 impl Parser {
     pub fn new(tokens: Vec<Token>) -> Self {
-        Parser { tokens, current: 0 }
+        Parser {
+            tokens,
+            current: 0,
+            slice_dots: None,
+        }
     }
 
     /// Parse the tokens intae a program
@@ -27,10 +37,95 @@ impl Parser {
         Ok(Program::new(statements))
     }
 
+    /// Parse the tokens, carrying on past errors to collect as many as
+    /// possible (up to `MAX_PARSE_ERRORS`). You only get a program back if
+    /// there were no errors at all.
+    pub fn parse_all(&mut self) -> (Option<Program>, Vec<HaversError>) {
+        let mut statements = Vec::new();
+        let mut errors = Vec::new();
+
+        self.skip_newlines();
+
+        while !self.is_at_end() {
+            let start = self.current;
+            match self.declaration() {
+                Ok(stmt) => statements.push(stmt),
+                Err(err) => {
+                    errors.push(err);
+                    if errors.len() >= MAX_PARSE_ERRORS {
+                        break;
+                    }
+                    self.synchronize(start);
+                }
+            }
+            self.skip_newlines();
+        }
+
+        if errors.is_empty() {
+            (Some(Program::new(statements)), errors)
+        } else {
+            (None, errors)
+        }
+    }
+
+    /// Skip the rest of a broken statement that started at `start`: past any
+    /// brackets it left open, then to the end of the line (or a `;` or `}`)
+    fn synchronize(&mut self, start: usize) {
+        let mut depth = 0usize;
+        for token in &self.tokens[start..self.current] {
+            match token.kind {
+                TokenKind::LeftBrace | TokenKind::LeftParen | TokenKind::LeftBracket => depth += 1,
+                TokenKind::RightBrace | TokenKind::RightParen | TokenKind::RightBracket => {
+                    depth = depth.saturating_sub(1)
+                }
+                _ => {}
+            }
+        }
+
+        while !self.is_at_end() {
+            match self.peek().kind {
+                TokenKind::Newline | TokenKind::Semicolon if depth == 0 => {
+                    self.advance();
+                    return;
+                }
+                TokenKind::LeftBrace | TokenKind::LeftParen | TokenKind::LeftBracket => depth += 1,
+                TokenKind::RightBrace if depth == 0 => {
+                    self.advance();
+                    return;
+                }
+                TokenKind::RightBrace | TokenKind::RightParen | TokenKind::RightBracket => {
+                    depth = depth.saturating_sub(1)
+                }
+                _ => {}
+            }
+            self.advance();
+        }
+    }
+
     // === Declaration parsing ===
 
     fn declaration(&mut self) -> HaversResult<Stmt> {
-        if self.check(&TokenKind::Ken) {
+        let start = self.current;
+        self.bare_declaration()
+            .map_err(|err| self.with_keyword_hint(err, start))
+    }
+
+    /// When a statement fails right after its first word, and that word
+    /// is a near miss for a keyword (`blather "hi"`), say which keyword
+    fn with_keyword_hint(&self, err: HaversError, start: usize) -> HaversError {
+        if self.current != start + 1 {
+            return err;
+        }
+        match &self.tokens[start].kind {
+            TokenKind::Identifier(name) => err.with_did_ye_mean(name, KEYWORDS.iter().copied()),
+            _ => err,
+        }
+    }
+
+    fn bare_declaration(&mut self) -> HaversResult<Stmt> {
+        if self.check(&TokenKind::At) {
+            self.attributed_declaration()
+        } else if self.check(&TokenKind::Ken) {
             self.var_declaration()
         } else if self.check(&TokenKind::Dae) {
             self.function_declaration()
@@ -40,6 +135,8 @@ impl Parser {
             self.struct_declaration()
         } else if self.check(&TokenKind::Fetch) {
             self.import_declaration()
+        } else if self.check(&TokenKind::Bring) {
+            self.bring_declaration()
         } else {
             self.statement()
         }
@@ -54,7 +151,15 @@ impl Parser {
             return self.destructure_declaration(span);
         }
 
+        // Or a tuple: ken a, b = ... (which can start with `_` or `...rest`)
+        if self.check(&TokenKind::Underscore) || self.check(&TokenKind::DotDotDot) {
+            return self.tuple_declaration(span, Vec::new());
+        }
+
         let name = self.expect_identifier("variable name")?;
+        if self.check(&TokenKind::Comma) {
+            return self.tuple_declaration(span, vec![DestructPattern::Variable(name)]);
+        }
 
         let initializer = if self.match_token(&TokenKind::Equals) {
             Some(self.expression()?)
@@ -79,26 +184,7 @@ impl Parser {
         let mut seen_rest = false;
 
         while !self.check(&TokenKind::RightBracket) && !self.is_at_end() {
-            // Check fer rest pattern: ...name
-            if self.match_token(&TokenKind::DotDotDot) {
-                if seen_rest {
-                    return Err(HaversError::ParseError {
-                        message: "Ye can only hae ane rest pattern (...) in a destructure"
-                            .to_string(),
-                        line: span.line,
-                    });
-                }
-                let name = self.expect_identifier("rest variable name")?;
-                patterns.push(DestructPattern::Rest(name));
-                seen_rest = true;
-            } else if self.match_token(&TokenKind::Underscore) {
-                // Ignore pattern: _
-                patterns.push(DestructPattern::Ignore);
-            } else {
-                // Regular variable
-                let name = self.expect_identifier("variable name")?;
-                patterns.push(DestructPattern::Variable(name));
-            }
+            patterns.push(self.destruct_pattern(&mut seen_rest, span)?);
 
             if !self.match_token(&TokenKind::Comma) {
                 break;
@@ -115,10 +201,119 @@ impl Parser {
         Ok(Stmt::Destructure {
             patterns,
             value,
+            exact: false,
+            span,
+        })
+    }
+
+    /// Parse a tuple destructure after the `patterns` already read:
+    /// ken a, b = f(). Unlike the bracketed form the count must match.
+    fn tuple_declaration(
+        &mut self,
+        span: Span,
+        mut patterns: Vec<DestructPattern>,
+    ) -> HaversResult<Stmt> {
+        let mut seen_rest = false;
+        if patterns.is_empty() {
+            patterns.push(self.destruct_pattern(&mut seen_rest, span)?);
+        }
+        while self.match_token(&TokenKind::Comma) {
+            patterns.push(self.destruct_pattern(&mut seen_rest, span)?);
+        }
+
+        self.expect(&TokenKind::Equals, "=")?;
+        let value = self.expression()?;
+        self.expect_statement_end()?;
+
+        Ok(Stmt::Destructure {
+            patterns,
+            value,
+            exact: true,
             span,
         })
     }
 
+    /// Parse one piece of a destructuring pattern: `name`, `_` or `...rest`
+    fn destruct_pattern(
+        &mut self,
+        seen_rest: &mut bool,
+        span: Span,
+    ) -> HaversResult<DestructPattern> {
+        // Check for rest pattern: ...name
+        if self.match_token(&TokenKind::DotDotDot) {
+            if *seen_rest {
+                return Err(HaversError::ParseError {
+                    message: "Ye can only hae ane rest pattern (...) in a destructure".to_string(),
+                    line: span.line,
+                    span: self.previous_error_span(),
+                });
+            }
+            *seen_rest = true;
+            let name = self.expect_identifier("rest variable name")?;
+            Ok(DestructPattern::Rest(name))
+        } else if self.match_token(&TokenKind::Underscore) {
+            // Ignore pattern: _
+            Ok(DestructPattern::Ignore)
+        } else {
+            // Regular variable
+            let name = self.expect_identifier("variable name")?;
+            Ok(DestructPattern::Variable(name))
+        }
+    }
+
+    /// Parse `@name` or `@name("arg", ...)` attributes, then the `dae` or `kin`
+    /// they belong to
+    fn attributed_declaration(&mut self) -> HaversResult<Stmt> {
+        let mut attributes = Vec::new();
+        while self.check(&TokenKind::At) {
+            let span = self.current_span();
+            self.advance(); // consume '@'
+            let name = self.expect_identifier("attribute name")?;
+            if name != "deprecated" {
+                return Err(HaversError::ParseError {
+                    message: format!("Unkent attribute '@{}' - only @deprecated is allowed", name),
+                    line: span.line,
+                    span: self.previous_error_span(),
+                });
+            }
+
+            let mut args = Vec::new();
+            if self.match_token(&TokenKind::LeftParen) {
+                while !self.check(&TokenKind::RightParen) && !self.is_at_end() {
+                    args.push(self.expect_string("attribute argument string")?);
+                    if !self.match_token(&TokenKind::Comma) {
+                        break;
+                    }
+                }
+                self.expect(&TokenKind::RightParen, ")")?;
+            }
+            attributes.push(Attribute { name, args, span });
+            self.skip_newlines();
+        }
+
+        let mut decl = if self.check(&TokenKind::Dae) {
+            self.function_declaration()?
+        } else if self.check(&TokenKind::Kin) {
+            self.class_declaration()?
+        } else {
+            return Err(HaversError::ParseError {
+                message: "Attributes can only gang afore a 'dae' or 'kin'".to_string(),
+                line: self.current_line(),
+                span: self.peek_error_span(),
+            });
+        };
+        match &mut decl {
+            Stmt::Function {
+                attributes: slot, ..
+            }
+            | Stmt::Class {
+                attributes: slot, ..
+            } => *slot = attributes,
+            _ => unreachable!("only dae an' kin take attributes"),
+        }
+        Ok(decl)
+    }
+
     fn function_declaration(&mut self) -> HaversResult<Stmt> {
         let span = self.current_span();
         self.advance(); // consume 'dae'
@@ -145,6 +340,7 @@ impl Parser {
                                 "Och! Params wi'oot defaults cannae come efter params wi' defaults"
                                     .to_string(),
                             line: span.line,
+                            span: self.previous_error_span(),
                         });
                     }
                     None
@@ -171,6 +367,7 @@ impl Parser {
             name,
             params,
             body,
+            attributes: Vec::new(),
             span,
         })
     }
@@ -199,6 +396,7 @@ impl Parser {
                 return Err(HaversError::ParseError {
                     message: "Expected method definition in class".to_string(),
                     line: self.current_line(),
+                    span: self.peek_error_span(),
                 });
             }
             self.skip_newlines();
@@ -210,6 +408,7 @@ impl Parser {
             name,
             superclass,
             methods,
+            attributes: Vec::new(),
             span,
         })
     }
@@ -251,7 +450,85 @@ impl Parser {
 
         self.expect_statement_end()?;
 
-        Ok(Stmt::Import { path, alias, span })
+        Ok(Stmt::Import {
+            path,
+            alias,
+            only: None,
+            bring: false,
+            span,
+        })
+    }
+
+    /// `bring "module"`, `bring "module" as m`, or `bring add, sub frae "module"`
+    fn bring_declaration(&mut self) -> HaversResult<Stmt> {
+        let span = self.current_span();
+        self.advance(); // consume 'bring'
+
+        let (path, alias, only) = if matches!(
+            self.peek().kind,
+            TokenKind::String(_) | TokenKind::SingleQuoteString(_)
+        ) {
+            let path = self.expect_string("module path")?;
+            let alias = if self.match_contextual("as") {
+                self.expect_identifier("namespace name")?
+            } else {
+                self.module_namespace(&path)?
+            };
+            (path, Some(alias), None)
+        } else {
+            let mut names = vec![self.expect_identifier("name tae bring")?];
+            while self.match_token(&TokenKind::Comma) {
+                names.push(self.expect_identifier("name tae bring")?);
+            }
+            self.expect(&TokenKind::Fae, "frae")?;
+            let path = self.expect_string("module path")?;
+            (path, None, Some(names))
+        };
+
+        self.expect_statement_end()?;
+
+        Ok(Stmt::Import {
+            path,
+            alias,
+            only,
+            bring: true,
+            span,
+        })
+    }
+
+    /// The namespace `bring "path"` uses: the file's name without its extension
+    fn module_namespace(&self, path: &str) -> HaversResult<String> {
+        let stem = std::path::Path::new(path)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or_default();
+        let is_identifier = stem
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_alphabetic() || c == '_')
+            && stem.chars().all(|c| c.is_alphanumeric() || c == '_');
+        if is_identifier {
+            Ok(stem.to_string())
+        } else {
+            Err(HaversError::ParseError {
+                message: format!(
+                    "'{}' cannae be a namespace - gie it a name wi' bring \"{}\" as name",
+                    stem, path
+                ),
+                line: self.current_line(),
+                span: self.previous_error_span(),
+            })
+        }
+    }
+
+    /// Consume an identifier that's only a keyword in this spot, like `as`
+    fn match_contextual(&mut self, word: &str) -> bool {
+        if matches!(&self.peek().kind, TokenKind::Identifier(name) if name == word) {
+            self.advance();
+            true
+        } else {
+            false
+        }
     }
 
     // === Statement parsing ===
@@ -289,6 +566,8 @@ impl Parser {
             self.log_statement(LogLevel::Roar)
         } else if self.check(&TokenKind::Hurl) {
             self.hurl_statement()
+        } else if self.check(&TokenKind::GieBack) {
+            self.yield_statement()
         } else if self.check(&TokenKind::LeftBrace) {
             self.block()
         } else {
@@ -300,6 +579,15 @@ impl Parser {
         let span = self.current_span();
         self.advance(); // consume 'gin'
 
+        // gin ken x = value { ... } binds x when the value is not nil
+        let binding = if self.match_token(&TokenKind::Ken) {
+            let name = self.expect_identifier("variable name")?;
+            self.expect(&TokenKind::Equals, "=")?;
+            Some(name)
+        } else {
+            None
+        };
+
         let condition = self.expression()?;
         self.skip_newlines();
         let then_branch = Box::new(self.block()?);
@@ -316,11 +604,20 @@ impl Parser {
             None
         };
 
-        Ok(Stmt::If {
-            condition,
-            then_branch,
-            else_branch,
-            span,
+        Ok(match binding {
+            Some(name) => Stmt::IfLet {
+                name,
+                value: condition,
+                then_branch,
+                else_branch,
+                span,
+            },
+            None => Stmt::If {
+                condition,
+                then_branch,
+                else_branch,
+                span,
+            },
         })
     }
 
@@ -339,11 +636,44 @@ impl Parser {
         })
     }
 
+    /// The rest of a list comprehension once its element is parsed: one or
+    /// more `fer x in xs` clauses, any of them followed by `gin` guards
+    fn list_comprehension(&mut self, element: Expr, span: Span) -> HaversResult<Expr> {
+        let mut clauses = Vec::new();
+        loop {
+            self.skip_newlines();
+            if self.match_token(&TokenKind::Fer) {
+                let variable = self.expect_identifier("comprehension variable efter 'fer'")?;
+                self.expect(&TokenKind::In, "'in' efter the comprehension variable")?;
+                let iterable = self.expression()?;
+                clauses.push(CompClause::For { variable, iterable });
+            } else if self.match_token(&TokenKind::Gin) {
+                clauses.push(CompClause::If(self.expression()?));
+            } else {
+                break;
+            }
+        }
+        self.expect(
+            &TokenKind::RightBracket,
+            "']' tae close the comprehension (or anither 'fer' or 'gin')",
+        )?;
+        Ok(Expr::ListComp {
+            element: Box::new(element),
+            clauses,
+            span,
+        })
+    }
+
     fn for_statement(&mut self) -> HaversResult<Stmt> {
         let span = self.current_span();
         self.advance(); // consume 'fer'
 
         let variable = self.expect_identifier("loop variable")?;
+        let value_variable = if self.match_token(&TokenKind::Comma) {
+            Some(self.expect_identifier("loop variable")?)
+        } else {
+            None
+        };
         self.expect(&TokenKind::In, "in")?;
         let iterable = self.expression()?;
         self.skip_newlines();
@@ -351,6 +681,7 @@ impl Parser {
 
         Ok(Stmt::For {
             variable,
+            value_variable,
             iterable,
             body,
             span,
@@ -364,7 +695,17 @@ impl Parser {
         let value = if self.check(&TokenKind::Newline) || self.check(&TokenKind::Eof) {
             None
         } else {
-            Some(self.expression()?)
+            let first = self.expression()?;
+            if self.check(&TokenKind::Comma) {
+                // gie a, b returns a list, ready for ken a, b = ...
+                let mut elements = vec![first];
+                while self.match_token(&TokenKind::Comma) {
+                    elements.push(self.expression()?);
+                }
+                Some(Expr::List { elements, span })
+            } else {
+                Some(first)
+            }
         };
 
         self.expect_statement_end()?;
@@ -490,6 +831,16 @@ impl Parser {
         Ok(Stmt::Hurl { message, span })
     }
 
+    fn yield_statement(&mut self) -> HaversResult<Stmt> {
+        let span = self.current_span();
+        self.advance(); // consume 'gie_back'
+
+        let value = self.expression()?;
+        self.expect_statement_end()?;
+
+        Ok(Stmt::Yield { value, span })
+    }
+
     fn match_arm(&mut self) -> HaversResult<MatchArm> {
         let span = self.current_span();
         self.expect(&TokenKind::Whan, "whan")?;
@@ -599,12 +950,14 @@ impl Parser {
                             next_token.kind
                         ),
                         line: next_token.line,
+                        span: self.peek_error_span(),
                     }),
                 }
             }
             _ => Err(HaversError::ParseError {
                 message: format!("Expected pattern, got {}", token.kind),
                 line: token.line,
+                span: self.peek_error_span(),
             }),
         }
     }
@@ -643,7 +996,9 @@ impl Parser {
     }
 
     fn assignment(&mut self) -> HaversResult<Expr> {
+        let start = self.current;
         let expr = self.pipe_expr()?;
+        let target_end = self.current;
 
         if self.match_token(&TokenKind::Equals) {
             let span = self.current_span();
@@ -679,6 +1034,7 @@ impl Parser {
                     return Err(HaversError::ParseError {
                         message: "Invalid assignment target".to_string(),
                         line: span.line,
+                        span: self.span_between(start, target_end - 1),
                     });
                 }
             }
@@ -718,6 +1074,7 @@ impl Parser {
                     return Err(HaversError::ParseError {
                         message: "Invalid compound assignment target".to_string(),
                         line: span.line,
+                        span: self.span_between(start, target_end - 1),
                     });
                 }
             }
@@ -847,7 +1204,7 @@ impl Parser {
     }
 
     fn comparison(&mut self) -> HaversResult<Expr> {
-        let mut expr = self.term()?;
+        let mut expr = self.bit_or()?;
 
         loop {
             let op = if self.match_token(&TokenKind::Less) {
@@ -858,6 +1215,107 @@ impl Parser {
                 BinaryOp::Greater
             } else if self.match_token(&TokenKind::GreaterEquals) {
                 BinaryOp::GreaterEqual
+            } else if self.match_token(&TokenKind::In) {
+                BinaryOp::In
+            } else if (self.check(&TokenKind::Nae) || self.check(&TokenKind::Bang))
+                && self.next_is_in()
+            {
+                // `nae in` and `!in` are the one operator
+                self.advance();
+                self.advance();
+                BinaryOp::NotIn
+            } else {
+                break;
+            };
+
+            let span = self
+                .previous()
+                .map(|t| Span::new(t.line, t.column))
+                .unwrap_or(self.current_span());
+            let right = self.bit_or()?;
+            expr = Expr::Binary {
+                left: Box::new(expr),
+                operator: op,
+                right: Box::new(right),
+                span,
+            };
+        }
+
+        Ok(expr)
+    }
+
+    /// Bitwise `|` sits between comparisons and `^`, like in Python
+    fn bit_or(&mut self) -> HaversResult<Expr> {
+        let mut expr = self.bit_xor()?;
+
+        while self.match_token(&TokenKind::Pipe) {
+            let span = self
+                .previous()
+                .map(|t| Span::new(t.line, t.column))
+                .unwrap_or(self.current_span());
+            let right = self.bit_xor()?;
+            expr = Expr::Binary {
+                left: Box::new(expr),
+                operator: BinaryOp::BitOr,
+                right: Box::new(right),
+                span,
+            };
+        }
+
+        Ok(expr)
+    }
+
+    /// Bitwise `^`
+    fn bit_xor(&mut self) -> HaversResult<Expr> {
+        let mut expr = self.bit_and()?;
+
+        while self.match_token(&TokenKind::Caret) {
+            let span = self
+                .previous()
+                .map(|t| Span::new(t.line, t.column))
+                .unwrap_or(self.current_span());
+            let right = self.bit_and()?;
+            expr = Expr::Binary {
+                left: Box::new(expr),
+                operator: BinaryOp::BitXor,
+                right: Box::new(right),
+                span,
+            };
+        }
+
+        Ok(expr)
+    }
+
+    /// Bitwise `&`
+    fn bit_and(&mut self) -> HaversResult<Expr> {
+        let mut expr = self.shift()?;
+
+        while self.match_token(&TokenKind::Ampersand) {
+            let span = self
+                .previous()
+                .map(|t| Span::new(t.line, t.column))
+                .unwrap_or(self.current_span());
+            let right = self.shift()?;
+            expr = Expr::Binary {
+                left: Box::new(expr),
+                operator: BinaryOp::BitAnd,
+                right: Box::new(right),
+                span,
+            };
+        }
+
+        Ok(expr)
+    }
+
+    /// Bit shifts `<<` and `>>` bind looser than `+` and `-`
+    fn shift(&mut self) -> HaversResult<Expr> {
+        let mut expr = self.term()?;
+
+        loop {
+            let op = if self.match_token(&TokenKind::LessLess) {
+                BinaryOp::ShiftLeft
+            } else if self.match_token(&TokenKind::GreaterGreater) {
+                BinaryOp::ShiftRight
             } else {
                 break;
             };
@@ -984,7 +1442,36 @@ impl Parser {
             });
         }
 
-        self.call()
+        self.power()
+    }
+
+    /// `**` binds tighter than unary minus on its left (`-2 ** 2` is -4)
+    /// and is right-associative (`2 ** 3 ** 2` is 2 ** 9)
+    fn power(&mut self) -> HaversResult<Expr> {
+        let expr = self.call()?;
+
+        if self.match_token(&TokenKind::StarStar) {
+            let span = self
+                .previous()
+                .map(|t| Span::new(t.line, t.column))
+                .unwrap_or(self.current_span());
+            let right = self.unary()?;
+            return Ok(Expr::Binary {
+                left: Box::new(expr),
+                operator: BinaryOp::Power,
+                right: Box::new(right),
+                span,
+            });
+        }
+
+        Ok(expr)
+    }
+
+    /// Whether the token after the current one is `in`
+    fn next_is_in(&self) -> bool {
+        self.tokens
+            .get(self.current + 1)
+            .is_some_and(|next| next.kind == TokenKind::In)
     }
 
     /// Check if `nae` is followed by something that could be an operand
@@ -1031,7 +1518,9 @@ impl Parser {
                 let span = self.current_span();
 
                 // Check fer slice syntax: [start:end:step], [:end], [start:], [::step], etc.
-                if self.check(&TokenKind::Colon) {
+                if let Some(dots) = self.find_slice_dots() {
+                    expr = self.range_slice(expr, dots, span)?;
+                } else if self.check(&TokenKind::Colon) {
                     // [:end] or [:] or [:end:step] or [::step]
                     self.advance(); // consume the first colon
 
@@ -1112,10 +1601,76 @@ impl Parser {
         Ok(expr)
     }
 
+    /// Find a `..` at the top level of the brackets we've just opened
+    fn find_slice_dots(&self) -> Option<usize> {
+        let mut depth = 0usize;
+        for (i, token) in self.tokens.iter().enumerate().skip(self.current) {
+            match token.kind {
+                TokenKind::LeftParen | TokenKind::LeftBracket | TokenKind::LeftBrace => depth += 1,
+                TokenKind::RightParen | TokenKind::RightBrace => depth = depth.checked_sub(1)?,
+                TokenKind::RightBracket => {
+                    if depth == 0 {
+                        return None;
+                    }
+                    depth -= 1;
+                }
+                TokenKind::DotDot | TokenKind::DotDotEquals if depth == 0 => return Some(i),
+                TokenKind::Eof => return None,
+                _ => {}
+            }
+        }
+        None
+    }
+
+    /// Parse the inside of `xs[start..end]`, either bound optional
+    fn range_slice(&mut self, object: Expr, dots: usize, span: Span) -> HaversResult<Expr> {
+        let start = if self.current == dots {
+            None
+        } else {
+            let outer = self.slice_dots.replace(dots);
+            let start = self.expression();
+            self.slice_dots = outer;
+            Some(Box::new(start?))
+        };
+
+        if self.current != dots {
+            return Err(HaversError::UnexpectedToken {
+                expected: "..".to_string(),
+                found: self.peek().kind.to_string(),
+                line: self.current_line(),
+                span: self.peek_error_span(),
+            });
+        }
+        if self.check(&TokenKind::DotDotEquals) {
+            return Err(HaversError::ParseError {
+                message: "Slices tak '..' no' '..=' - the end is aye left oot".to_string(),
+                line: self.current_line(),
+                span: self.peek_error_span(),
+            });
+        }
+        self.advance(); // consume '..'
+
+        let end = if self.check(&TokenKind::RightBracket) {
+            None
+        } else {
+            Some(Box::new(self.expression()?))
+        };
+        self.expect(&TokenKind::RightBracket, "]")?;
+
+        Ok(Expr::Slice {
+            object: Box::new(object),
+            start,
+            end,
+            step: None,
+            span,
+        })
+    }
+
     fn finish_call(&mut self, callee: Expr) -> HaversResult<Expr> {
         let span = callee.span();
         let mut arguments = Vec::new();
 
+        self.skip_newlines();
         if !self.check(&TokenKind::RightParen) {
             loop {
                 // Check for spread operator in function arguments
@@ -1129,9 +1684,15 @@ impl Parser {
                 } else {
                     arguments.push(self.expression()?);
                 }
+                self.skip_newlines();
                 if !self.match_token(&TokenKind::Comma) {
                     break;
                 }
+                // Allow trailing comma: f(1, 2,)
+                self.skip_newlines();
+                if self.check(&TokenKind::RightParen) {
+                    break;
+                }
             }
         }
 
@@ -1242,7 +1803,13 @@ impl Parser {
                                 span: spread_span,
                             });
                         } else {
-                            elements.push(self.expression()?);
+                            let element = self.expression()?;
+                            self.skip_newlines();
+                            // [x * 2 fer x in xs] is a comprehension, not a literal
+                            if elements.is_empty() && self.check(&TokenKind::Fer) {
+                                return self.list_comprehension(element, span);
+                            }
+                            elements.push(element);
                         }
                         self.skip_newlines(); // Allow newline after element
                         if !self.match_token(&TokenKind::Comma) {
@@ -1357,32 +1924,38 @@ impl Parser {
             _ => Err(HaversError::ParseError {
                 message: format!("Unexpected token: {}", token.kind),
                 line: token.line,
+                span: self.peek_error_span(),
             }),
         }
     }
 
     fn maybe_range(&mut self, start_expr: Expr) -> HaversResult<Expr> {
-        if self.match_token(&TokenKind::DotDotEquals) {
-            let span = start_expr.span();
-            let end = self.term()?;
-            Ok(Expr::Range {
-                start: Box::new(start_expr),
-                end: Box::new(end),
-                inclusive: true,
-                span,
-            })
+        if self.slice_dots == Some(self.current) {
+            return Ok(start_expr);
+        }
+        let inclusive = if self.match_token(&TokenKind::DotDotEquals) {
+            true
         } else if self.match_token(&TokenKind::DotDot) {
-            let span = start_expr.span();
-            let end = self.term()?;
-            Ok(Expr::Range {
-                start: Box::new(start_expr),
-                end: Box::new(end),
-                inclusive: false,
-                span,
-            })
+            false
         } else {
-            Ok(start_expr)
-        }
+            return Ok(start_expr);
+        };
+
+        let span = start_expr.span();
+        let end = self.term()?;
+        // Optional stride: 0..10 by 2
+        let step = if self.match_token(&TokenKind::By) {
+            Some(Box::new(self.term()?))
+        } else {
+            None
+        };
+        Ok(Expr::Range {
+            start: Box::new(start_expr),
+            end: Box::new(end),
+            inclusive,
+            step,
+            span,
+        })
     }
 
     // === Helper methods ===
@@ -1438,6 +2011,7 @@ impl Parser {
                 expected: expected.to_string(),
                 found: self.peek().kind.to_string(),
                 line: self.peek().line,
+                span: self.peek_error_span(),
             })
         }
     }
@@ -1453,6 +2027,7 @@ impl Parser {
                 expected: context.to_string(),
                 found: token.kind.to_string(),
                 line: token.line,
+                span: self.peek_error_span(),
             })
         }
     }
@@ -1468,6 +2043,7 @@ impl Parser {
                 expected: context.to_string(),
                 found: token.kind.to_string(),
                 line: token.line,
+                span: self.peek_error_span(),
             })
         }
     }
@@ -1502,6 +2078,7 @@ impl Parser {
                 | TokenKind::Kin
                 | TokenKind::Thing
                 | TokenKind::Fetch
+                | TokenKind::Bring
                 | TokenKind::LogWhisper
                 | TokenKind::LogMutter
                 | TokenKind::LogBlether
@@ -1515,6 +2092,7 @@ impl Parser {
             expected: "newline or ';'".to_string(),
             found: self.peek().kind.to_string(),
             line: self.peek().line,
+            span: self.peek_error_span(),
         })
     }
 
@@ -1531,6 +2109,33 @@ impl Parser {
         self.peek().line
     }
 
+    /// The span of the token at `index`. The end-of-file token has no
+    /// place of its own, so it sits just past the last real token.
+    fn token_error_span(&self, index: usize) -> ErrorSpan {
+        let index = index.min(self.tokens.len() - 1);
+        let token = &self.tokens[index];
+        if token.kind == TokenKind::Eof && index > 0 {
+            let last = ErrorSpan::of_token(&self.tokens[index - 1]);
+            return ErrorSpan::new(last.end_line, last.end_col, last.end_line, last.end_col + 1);
+        }
+        ErrorSpan::of_token(token)
+    }
+
+    /// Where the token at the cursor is, for errors about it
+    fn peek_error_span(&self) -> Option<ErrorSpan> {
+        Some(self.token_error_span(self.current))
+    }
+
+    /// Where the token just consumed is
+    fn previous_error_span(&self) -> Option<ErrorSpan> {
+        Some(self.token_error_span(self.current.saturating_sub(1)))
+    }
+
+    /// From the start of token `first` to the end of token `last`
+    fn span_between(&self, first: usize, last: usize) -> Option<ErrorSpan> {
+        Some(self.token_error_span(first).to(self.token_error_span(last)))
+    }
+
     /// Parse an f-string like f"Hello {name}!" into parts
     fn parse_fstring(&mut self, content: &str, span: Span) -> HaversResult<Expr> {
         let mut parts = Vec::new();
@@ -1678,6 +2283,15 @@ pub fn parse(source: &str) -> HaversResult<Program> {
     parser.parse()
 }
 
+/// Parse source code, reporting every syntax error (up to `MAX_PARSE_ERRORS`)
+/// instead of stopping at the first
+pub fn parse_all(source: &str) -> (Option<Program>, Vec<HaversError>) {
+    match crate::lexer::lex(source) {
+        Ok(tokens) => Parser::new(tokens).parse_all(),
+        Err(err) => (None, vec![err]),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1750,6 +2364,7 @@ mod tests {
                 name: String::new(),
                 params: Vec::new(),
                 body: Vec::new(),
+                attributes: Vec::new(),
                 span: DUMMY_SPAN,
             },
         );
@@ -1793,6 +2408,7 @@ mod tests {
             &program.statements[0],
             Stmt::For {
                 variable: String::new(),
+                value_variable: None,
                 iterable: dummy_expr(),
                 body: dummy_block_stmt(),
                 span: DUMMY_SPAN,
@@ -1832,6 +2448,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_call_trailing_comma_and_newlines() {
+        let program = parse("blether max(\n    1,\n    2,\n)").unwrap();
+        let Stmt::Print { value, .. } = &program.statements[0] else {
+            panic!("expected print");
+        };
+        let Expr::Call { arguments, .. } = value else {
+            panic!("expected call");
+        };
+        assert_eq!(arguments.len(), 2);
+    }
+
     #[test]
     fn test_multiline_list() {
         let program = parse("ken arr = [\n  1,\n  2,\n  3\n]").unwrap();
@@ -1978,11 +2606,43 @@ mod tests {
                 name: String::new(),
                 superclass: None,
                 methods: Vec::new(),
+                attributes: Vec::new(),
                 span: DUMMY_SPAN,
             },
         );
     }
 
+    #[test]
+    fn test_deprecated_attribute_is_recorded() {
+        let program = parse(
+            "@deprecated(\"use new_fn\")\ndae old_fn() {\n  gie 1\n}\n@deprecated kin Auld {}",
+        )
+        .unwrap();
+        assert_eq!(program.statements.len(), 2);
+        let Stmt::Function { attributes, .. } = &program.statements[0] else {
+            panic!("expected a dae");
+        };
+        assert_eq!(attributes[0].name, "deprecated");
+        assert_eq!(attributes[0].args, ["use new_fn"]);
+        assert_eq!(attributes[0].span.line, 1);
+        let Stmt::Class { attributes, .. } = &program.statements[1] else {
+            panic!("expected a kin");
+        };
+        assert_eq!(Attribute::deprecation(attributes), Some(String::new()));
+    }
+
+    #[test]
+    fn test_attribute_errors() {
+        for (source, expected) in [
+            ("@shiny dae f() {}", "Unkent attribute '@shiny'"),
+            ("@deprecated ken x = 1", "afore a 'dae' or 'kin'"),
+            ("@deprecated(42) dae f() {}", "attribute argument string"),
+        ] {
+            let err = parse(source).unwrap_err().to_string();
+            assert!(err.contains(expected), "{source}: {err}");
+        }
+    }
+
     #[test]
     fn test_spread_operator() {
         let program = parse("ken arr = [...other, 4, 5]").unwrap();
@@ -1995,6 +2655,43 @@ mod tests {
         assert_eq!(program.statements.len(), 1);
     }
 
+    #[test]
+    fn test_range_slice_syntax() {
+        let slice_bounds = |source: &str| {
+            let program = parse(source).unwrap();
+            let Stmt::Expression {
+                expr: Expr::Slice {
+                    start, end, step, ..
+                },
+                ..
+            } = &program.statements[0]
+            else {
+                panic!("{source} should be a slice");
+            };
+            assert!(step.is_none());
+            (start.is_some(), end.is_some())
+        };
+        assert_eq!(slice_bounds("xs[1..4]"), (true, true));
+        assert_eq!(slice_bounds("xs[-2..]"), (true, false));
+        assert_eq!(slice_bounds("xs[..n - 1]"), (false, true));
+        assert_eq!(slice_bounds("xs[..]"), (false, false));
+        assert_eq!(slice_bounds("xs[len(ys) - 2..f(0..3)]"), (true, true));
+
+        // A range in its own brackets is still an index
+        let program = parse("xs[(0..3)]").unwrap();
+        assert!(matches!(
+            &program.statements[0],
+            Stmt::Expression {
+                expr: Expr::Index { .. },
+                ..
+            }
+        ));
+        assert!(parse("xs[0..=2]")
+            .unwrap_err()
+            .to_string()
+            .contains("'..='"));
+    }
+
     #[test]
     fn test_pipe_operator() {
         let program = parse("ken result = x |> f |> g").unwrap();
@@ -2007,6 +2704,42 @@ mod tests {
     fn test_destructure_multiple_rest_error() {
         let result = parse("ken [...a, ...b] = [1, 2, 3]");
         assert!(result.is_err());
+        assert!(parse("ken ...a, ...b = [1, 2, 3]").is_err());
+    }
+
+    #[test]
+    fn test_tuple_gie_an_ken() {
+        let program = parse("ken q, _, ...r = f()").unwrap();
+        let Stmt::Destructure {
+            patterns, exact, ..
+        } = &program.statements[0]
+        else {
+            panic!("expected a destructure: {:?}", program.statements[0]);
+        };
+        assert!(*exact);
+        assert!(matches!(
+            patterns.as_slice(),
+            [
+                DestructPattern::Variable(_),
+                DestructPattern::Ignore,
+                DestructPattern::Rest(_)
+            ]
+        ));
+
+        let program = parse("dae f() {\n    gie 1, 2 + 3\n}").unwrap();
+        let Stmt::Function { body, .. } = &program.statements[0] else {
+            panic!("expected a function");
+        };
+        assert!(matches!(
+            &body[0],
+            Stmt::Return {
+                value: Some(Expr::List { elements, .. }),
+                ..
+            } if elements.len() == 2
+        ));
+
+        assert!(parse("ken a, = f()").is_err());
+        assert!(parse("ken a, b").is_err());
     }
 
     #[test]
@@ -2143,6 +2876,7 @@ mod tests {
             HaversError::ParseError {
                 message: "Expected method definition in class".to_string(),
                 line: 1,
+                span: Some(ErrorSpan::new(1, 9, 1, 12)),
             }
         );
     }
@@ -2172,6 +2906,7 @@ mod tests {
             HaversError::ParseError {
                 message: String::new(),
                 line: 0,
+                span: None,
             },
         );
     }
@@ -2184,6 +2919,7 @@ mod tests {
             HaversError::ParseError {
                 message: String::new(),
                 line: 0,
+                span: None,
             },
         );
     }
@@ -2196,6 +2932,7 @@ mod tests {
             HaversError::ParseError {
                 message: "Invalid compound assignment target".to_string(),
                 line: 1,
+                span: Some(ErrorSpan::new(1, 1, 1, 2)),
             }
         );
     }
@@ -2222,6 +2959,101 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_error_span_points_at_the_offending_token() {
+        let err = parse("ken x =\n").unwrap_err();
+        assert_eq!(err.span(), Some(ErrorSpan::new(1, 8, 1, 9)));
+
+        let err = parse("ken x = (1 + ]").unwrap_err();
+        assert_eq!(err.span(), Some(ErrorSpan::new(1, 14, 1, 15)));
+
+        let err = parse("ken x = 1\n(a + b) = 3").unwrap_err();
+        assert_eq!(err.span(), Some(ErrorSpan::new(2, 1, 2, 8)));
+    }
+
+    #[test]
+    fn test_parse_all_reports_independent_errors() {
+        let source = "ken a = 1\nken = 2\ndae f(x) {\n    gie x +\n}\nken ok = f(a)\n";
+        let (program, errors) = parse_all(source);
+        assert!(program.is_none());
+        let lines: Vec<_> = errors.iter().map(|e| e.line()).collect();
+        assert_eq!(lines, vec![Some(2), Some(4)], "{errors:?}");
+
+        // A clean file still gives a program, same as `parse`
+        let (program, errors) = parse_all("ken a = 1\nblether a\n");
+        assert!(errors.is_empty());
+        assert_eq!(program.unwrap().statements.len(), 2);
+
+        // Lex errors come back on their own
+        let (program, errors) = parse_all("ken a = $");
+        assert!(program.is_none());
+        assert!(matches!(errors[..], [HaversError::UnkentToken { .. }]));
+    }
+
+    #[test]
+    fn test_misspelt_keyword_gets_a_hint() {
+        let err = parse("blather \"hi\"").unwrap_err();
+        assert!(err.to_string().ends_with("Did ye mean `blether`?"), "{err}");
+        assert_eq!(err.span(), Some(ErrorSpan::new(1, 9, 1, 13)));
+
+        let err = parse("dae f() {\n    fir i in 0..3 { blether i }\n}").unwrap_err();
+        assert!(err.to_string().contains("Did ye mean `fer`?"), "{err}");
+
+        // An error further on in the statement is not blamed on the first word
+        let err = parse("blather = (1 + ]").unwrap_err();
+        assert!(!err.to_string().contains("Did ye mean"), "{err}");
+    }
+
+    #[test]
+    fn test_list_comprehension_clauses_in_order() {
+        let program = parse("[x * y fer x in xs gin x > 0 fer y in 1..3]").unwrap();
+        let Stmt::Expression {
+            expr: Expr::ListComp { clauses, .. },
+            ..
+        } = &program.statements[0]
+        else {
+            panic!("expected a comprehension: {:?}", program.statements[0]);
+        };
+        assert!(matches!(
+            clauses[..],
+            [
+                CompClause::For { ref variable, .. },
+                CompClause::If(_),
+                CompClause::For { .. }
+            ] if variable == "x"
+        ));
+
+        // A list that merely starts with a value is still a literal
+        let program = parse("[x, fer_real]").unwrap();
+        assert!(matches!(
+            &program.statements[0],
+            Stmt::Expression {
+                expr: Expr::List { .. },
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_malformed_list_comprehension_errors() {
+        for (source, expected) in [
+            ("[x fer 1 in xs]", "comprehension variable efter 'fer'"),
+            ("[x fer x xs]", "'in' efter the comprehension variable"),
+            ("[x fer x in xs, 2]", "']' tae close the comprehension"),
+        ] {
+            let err = parse(source).unwrap_err();
+            assert!(err.to_string().contains(expected), "{source}: {err}");
+        }
+    }
+
+    #[test]
+    fn test_parse_all_caps_the_error_count() {
+        let source = "ken = 1\n".repeat(MAX_PARSE_ERRORS * 3);
+        let (_, errors) = parse_all(&source);
+        assert_eq!(errors.len(), MAX_PARSE_ERRORS);
+        assert_eq!(errors.last().unwrap().line(), Some(MAX_PARSE_ERRORS));
+    }
+
     #[test]
     fn test_import_requires_string_path() {
         let err = parse("fetch 123").unwrap_err();
@@ -2231,6 +3063,7 @@ mod tests {
                 expected: "module path".to_string(),
                 found: "123".to_string(),
                 line: 1,
+                span: Some(ErrorSpan::new(1, 7, 1, 10)),
             }
         );
     }
@@ -2298,6 +3131,7 @@ mod tests {
                 lexeme: String::new(),
                 line: 0,
                 column: 0,
+                span: None,
             },
         );
     }
@@ -2344,11 +3178,46 @@ mod tests {
             Stmt::Import {
                 path: String::new(),
                 alias: None,
+                only: None,
+                bring: false,
                 span: DUMMY_SPAN,
             },
         );
     }
 
+    #[test]
+    fn test_bring_statements() {
+        let import = |source: &str| {
+            let program = parse(source).unwrap();
+            let Stmt::Import {
+                path, alias, only, ..
+            } = &program.statements[0]
+            else {
+                panic!("{source} should be an import");
+            };
+            (path.clone(), alias.clone(), only.clone())
+        };
+        assert_eq!(
+            import(r#"bring "lib/mathlib""#),
+            ("lib/mathlib".to_string(), Some("mathlib".to_string()), None)
+        );
+        assert_eq!(
+            import(r#"bring "mathlib" as m"#),
+            ("mathlib".to_string(), Some("m".to_string()), None)
+        );
+        assert_eq!(
+            import(r#"bring add, sub frae "mathlib""#),
+            (
+                "mathlib".to_string(),
+                None,
+                Some(vec!["add".to_string(), "sub".to_string()])
+            )
+        );
+        assert!(parse(r#"bring "my-lib""#).is_err());
+        assert!(parse(r#"bring "my-lib" as lib"#).is_ok());
+        assert!(parse("ken as = 1").is_ok());
+    }
+
     #[test]
     fn test_try_catch() {
         let program = parse("hae_a_bash { risky() } gin_it_gangs_wrang e { blether e }").unwrap();
@@ -2426,6 +3295,30 @@ mod tests {
         assert_eq!(program.statements.len(), 1);
     }
 
+    #[test]
+    fn test_range_with_step() {
+        let program = parse("ken r = 10..=0 by -2").unwrap();
+        match &program.statements[0] {
+            Stmt::VarDecl {
+                initializer:
+                    Some(Expr::Range {
+                        inclusive, step, ..
+                    }),
+                ..
+            } => {
+                assert!(*inclusive);
+                assert!(matches!(
+                    step.as_deref(),
+                    Some(Expr::Unary {
+                        operator: UnaryOp::Negate,
+                        ..
+                    })
+                ));
+            }
+            other => panic!("expected range initializer, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_slice_with_step() {
         let program = parse("ken s = arr[::2]").unwrap();
@@ -2528,6 +3421,7 @@ mod tests {
             &program.statements[0],
             Stmt::For {
                 variable: String::new(),
+                value_variable: None,
                 iterable: dummy_expr(),
                 body: dummy_block_stmt(),
                 span: DUMMY_SPAN,
@@ -2535,6 +3429,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_for_key_value_parse() {
+        let program = parse("fer k, v in d { blether k }").unwrap();
+        let Stmt::For {
+            variable,
+            value_variable,
+            ..
+        } = &program.statements[0]
+        else {
+            panic!("expected a fer loop, got {:?}", program.statements[0]);
+        };
+        assert_eq!(variable, "k");
+        assert_eq!(value_variable.as_deref(), Some("v"));
+
+        assert!(parse("fer k, in d { blether k }").is_err());
+    }
+
     #[test]
     fn test_break_statement() {
         let program = parse("whiles aye { brak }").unwrap();
@@ -2566,12 +3477,54 @@ mod tests {
         assert_eq!(program.statements.len(), 1);
     }
 
+    #[test]
+    fn test_if_let_binding() {
+        let program = parse(
+            "gin ken x = get(d, \"k\") {\n  blether x\n} ither gin ken y = z {\n  blether y\n}",
+        )
+        .unwrap();
+        let Stmt::IfLet {
+            name, else_branch, ..
+        } = &program.statements[0]
+        else {
+            panic!("Expected IfLet, got {:?}", program.statements[0]);
+        };
+        assert_eq!(name, "x");
+        assert!(matches!(
+            else_branch.as_deref(),
+            Some(Stmt::IfLet { name, .. }) if name == "y"
+        ));
+
+        assert!(parse("gin ken = 1 { }").is_err());
+        assert!(parse("gin ken x 1 { }").is_err());
+    }
+
     #[test]
     fn test_if_else_if() {
         let program = parse("gin x > 0 { blether \"pos\" } ither gin x < 0 { blether \"neg\" } ither { blether \"zero\" }").unwrap();
         assert_eq!(program.statements.len(), 1);
     }
 
+    #[test]
+    fn test_in_operators() {
+        let operator = |source: &str| {
+            let program = parse(source).unwrap();
+            let Stmt::Expression {
+                expr: Expr::Binary { operator, .. },
+                ..
+            } = &program.statements[0]
+            else {
+                panic!("{source} should be a binary expression");
+            };
+            *operator
+        };
+        assert_eq!(operator("x in xs"), BinaryOp::In);
+        assert_eq!(operator("x nae in xs"), BinaryOp::NotIn);
+        assert_eq!(operator("x !in xs"), BinaryOp::NotIn);
+        assert_eq!(operator("x + 1 in xs"), BinaryOp::In);
+        assert!(parse("fer x in xs { blether x }").is_ok());
+    }
+
     // ==================== Error Recovery Tests ====================
 
     #[test]
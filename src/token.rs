@@ -22,6 +22,10 @@ pub enum TokenKind {
     #[token("than")]
     Than,
 
+    /// by - range step (fer i in 0..10 by 2)
+    #[token("by")]
+    By,
+
     /// whiles - while loop
     #[token("whiles")]
     Whiles,
@@ -34,6 +38,10 @@ pub enum TokenKind {
     #[token("gie")]
     Gie,
 
+    /// gie_back - yield a value from a generator
+    #[token("gie_back")]
+    GieBack,
+
     /// blether - print (chat/talk)
     #[token("blether")]
     Blether,
@@ -44,6 +52,7 @@ pub enum TokenKind {
 
     /// fae - from
     #[token("fae")]
+    #[token("frae")]
     Fae,
 
     /// tae - to
@@ -84,6 +93,10 @@ pub enum TokenKind {
     #[token("fetch")]
     Fetch,
 
+    /// bring - import into a namespace
+    #[token("bring")]
+    Bring,
+
     /// kin - class (family/type)
     #[token("kin")]
     Kin,
@@ -198,6 +211,9 @@ pub enum TokenKind {
     #[token("*")]
     Star,
 
+    #[token("**")]
+    StarStar,
+
     #[token("/")]
     Slash,
 
@@ -225,6 +241,18 @@ pub enum TokenKind {
     #[token(">=")]
     GreaterEquals,
 
+    #[token("<<")]
+    LessLess,
+
+    #[token(">>")]
+    GreaterGreater,
+
+    #[token("&")]
+    Ampersand,
+
+    #[token("^")]
+    Caret,
+
     #[token("!")]
     Bang,
 
@@ -292,6 +320,9 @@ pub enum TokenKind {
     #[token("|")]
     Pipe,
 
+    #[token("@")]
+    At, // Attributes: @deprecated("...") before a dae or kin
+
     // Newlines are significant in mdhavers (like Python)
     #[token("\n")]
     Newline,
@@ -312,9 +343,11 @@ impl fmt::Display for TokenKind {
             TokenKind::Gin => write!(f, "gin"),
             TokenKind::Ither => write!(f, "ither"),
             TokenKind::Than => write!(f, "than"),
+            TokenKind::By => write!(f, "by"),
             TokenKind::Whiles => write!(f, "whiles"),
             TokenKind::Fer => write!(f, "fer"),
             TokenKind::Gie => write!(f, "gie"),
+            TokenKind::GieBack => write!(f, "gie_back"),
             TokenKind::Blether => write!(f, "blether"),
             TokenKind::Speir => write!(f, "speir"),
             TokenKind::Fae => write!(f, "fae"),
@@ -327,6 +360,7 @@ impl fmt::Display for TokenKind {
             TokenKind::Dae => write!(f, "dae"),
             TokenKind::Thing => write!(f, "thing"),
             TokenKind::Fetch => write!(f, "fetch"),
+            TokenKind::Bring => write!(f, "bring"),
             TokenKind::Kin => write!(f, "kin"),
             TokenKind::Brak => write!(f, "brak"),
             TokenKind::Haud => write!(f, "haud"),
@@ -353,6 +387,7 @@ impl fmt::Display for TokenKind {
             TokenKind::Plus => write!(f, "+"),
             TokenKind::Minus => write!(f, "-"),
             TokenKind::Star => write!(f, "*"),
+            TokenKind::StarStar => write!(f, "**"),
             TokenKind::Slash => write!(f, "/"),
             TokenKind::Percent => write!(f, "%"),
             TokenKind::Equals => write!(f, "="),
@@ -362,6 +397,10 @@ impl fmt::Display for TokenKind {
             TokenKind::LessEquals => write!(f, "<="),
             TokenKind::Greater => write!(f, ">"),
             TokenKind::GreaterEquals => write!(f, ">="),
+            TokenKind::LessLess => write!(f, "<<"),
+            TokenKind::GreaterGreater => write!(f, ">>"),
+            TokenKind::Ampersand => write!(f, "&"),
+            TokenKind::Caret => write!(f, "^"),
             TokenKind::Bang => write!(f, "!"),
             TokenKind::PlusEquals => write!(f, "+="),
             TokenKind::MinusEquals => write!(f, "-="),
@@ -384,6 +423,7 @@ impl fmt::Display for TokenKind {
             TokenKind::Arrow => write!(f, "->"),
             TokenKind::PipeForward => write!(f, "|>"),
             TokenKind::Pipe => write!(f, "|"),
+            TokenKind::At => write!(f, "@"),
             TokenKind::Newline => write!(f, "newline"),
             TokenKind::Comment => write!(f, "comment"),
             TokenKind::Eof => write!(f, "end of file"),
@@ -391,6 +431,54 @@ impl fmt::Display for TokenKind {
     }
 }
 
+/// Every word the lexer treats as a keyword, for "did ye mean" hints
+pub const KEYWORDS: &[&str] = &[
+    "ken",
+    "gin",
+    "ither",
+    "than",
+    "by",
+    "whiles",
+    "fer",
+    "gie",
+    "gie_back",
+    "blether",
+    "speir",
+    "fae",
+    "frae",
+    "tae",
+    "an",
+    "or",
+    "nae",
+    "aye",
+    "naething",
+    "nil",
+    "nowt",
+    "dae",
+    "thing",
+    "fetch",
+    "bring",
+    "kin",
+    "brak",
+    "haud",
+    "haud_yer_wheesht",
+    "gang_on",
+    "in",
+    "is",
+    "masel",
+    "hae_a_bash",
+    "gin_it_gangs_wrang",
+    "keek",
+    "whan",
+    "mak_siccar",
+    "log_whisper",
+    "log_mutter",
+    "log_blether",
+    "log_holler",
+    "log_roar",
+    "hurl",
+];
+
 /// A token wi' its position in the source
 #[derive(Debug, Clone, PartialEq)]
 pub struct Token {
@@ -431,15 +519,28 @@ impl fmt::Display for Token {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_keywords_list_matches_the_lexer() {
+        for keyword in KEYWORDS {
+            let tokens = crate::lexer::lex(keyword).unwrap();
+            assert!(
+                !matches!(tokens[0].kind, TokenKind::Identifier(_)),
+                "'{keyword}' lexes as an identifier"
+            );
+        }
+    }
+
     #[test]
     fn test_token_kind_display_keywords() {
         assert_eq!(format!("{}", TokenKind::Ken), "ken");
         assert_eq!(format!("{}", TokenKind::Gin), "gin");
         assert_eq!(format!("{}", TokenKind::Ither), "ither");
         assert_eq!(format!("{}", TokenKind::Than), "than");
+        assert_eq!(format!("{}", TokenKind::By), "by");
         assert_eq!(format!("{}", TokenKind::Whiles), "whiles");
         assert_eq!(format!("{}", TokenKind::Fer), "fer");
         assert_eq!(format!("{}", TokenKind::Gie), "gie");
+        assert_eq!(format!("{}", TokenKind::GieBack), "gie_back");
         assert_eq!(format!("{}", TokenKind::Blether), "blether");
         assert_eq!(format!("{}", TokenKind::Speir), "speir");
         assert_eq!(format!("{}", TokenKind::Fae), "fae");
@@ -452,6 +553,7 @@ mod tests {
         assert_eq!(format!("{}", TokenKind::Dae), "dae");
         assert_eq!(format!("{}", TokenKind::Thing), "thing");
         assert_eq!(format!("{}", TokenKind::Fetch), "fetch");
+        assert_eq!(format!("{}", TokenKind::Bring), "bring");
         assert_eq!(format!("{}", TokenKind::Kin), "kin");
         assert_eq!(format!("{}", TokenKind::Brak), "brak");
         assert_eq!(format!("{}", TokenKind::Haud), "haud");
@@ -502,6 +604,7 @@ mod tests {
         assert_eq!(format!("{}", TokenKind::Plus), "+");
         assert_eq!(format!("{}", TokenKind::Minus), "-");
         assert_eq!(format!("{}", TokenKind::Star), "*");
+        assert_eq!(format!("{}", TokenKind::StarStar), "**");
         assert_eq!(format!("{}", TokenKind::Slash), "/");
         assert_eq!(format!("{}", TokenKind::Percent), "%");
         assert_eq!(format!("{}", TokenKind::Equals), "=");
@@ -511,6 +614,10 @@ mod tests {
         assert_eq!(format!("{}", TokenKind::LessEquals), "<=");
         assert_eq!(format!("{}", TokenKind::Greater), ">");
         assert_eq!(format!("{}", TokenKind::GreaterEquals), ">=");
+        assert_eq!(format!("{}", TokenKind::LessLess), "<<");
+        assert_eq!(format!("{}", TokenKind::GreaterGreater), ">>");
+        assert_eq!(format!("{}", TokenKind::Ampersand), "&");
+        assert_eq!(format!("{}", TokenKind::Caret), "^");
         assert_eq!(format!("{}", TokenKind::Bang), "!");
         assert_eq!(format!("{}", TokenKind::PlusEquals), "+=");
         assert_eq!(format!("{}", TokenKind::MinusEquals), "-=");
@@ -533,6 +640,7 @@ mod tests {
         assert_eq!(format!("{}", TokenKind::RightBracket), "]");
         assert_eq!(format!("{}", TokenKind::Comma), ",");
         assert_eq!(format!("{}", TokenKind::Colon), ":");
+        assert_eq!(format!("{}", TokenKind::At), "@");
         assert_eq!(format!("{}", TokenKind::Semicolon), ";");
         assert_eq!(format!("{}", TokenKind::Arrow), "->");
         assert_eq!(format!("{}", TokenKind::PipeForward), "|>");
@@ -1,9 +1,13 @@
 use std::any::Any;
 use std::cell::RefCell;
+use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::fmt;
 use std::rc::Rc;
 
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
+
 use crate::ast::{Expr, Stmt};
 use crate::error::HaversResult;
 
@@ -12,6 +16,7 @@ pub enum ValueKey {
     Nil,
     Bool(bool),
     Int(i64),
+    BigInt(String),
     Float(u64),
     String(String),
     List(usize),
@@ -24,6 +29,7 @@ pub enum ValueKey {
     Instance(usize),
     Struct(usize),
     NativeObject(usize),
+    Generator(usize),
     Range {
         start: i64,
         end: i64,
@@ -47,6 +53,9 @@ pub trait NativeObject: fmt::Debug {
 pub enum Value {
     /// Integer number
     Integer(i64),
+    /// An integer too big for i64, made when arithmetic overflows with big
+    /// integers switched on. Always normalised: it never holds an i64-sized value.
+    BigInt(Rc<BigInt>),
     /// Floating point number
     Float(f64),
     /// String
@@ -79,12 +88,14 @@ pub enum Value {
     /// Byte buffer
     #[allow(dead_code)]
     Bytes(Rc<RefCell<Vec<u8>>>),
+    /// A paused generator function, driven by `fer`
+    Generator(Rc<RefCell<HaversGenerator>>),
 }
 
 impl Value {
     pub fn type_name(&self) -> &'static str {
         match self {
-            Value::Integer(_) => "integer",
+            Value::Integer(_) | Value::BigInt(_) => "integer",
             Value::Float(_) => "float",
             Value::String(_) => "string",
             Value::Bool(_) => "bool",
@@ -100,6 +111,25 @@ impl Value {
             Value::Struct(_) => "struct",
             Value::Range(_) => "range",
             Value::NativeObject(_) => "native object",
+            Value::Generator(_) => "generator",
+        }
+    }
+
+    /// An integer of any size: it stays a plain `Integer` if it fits in i64
+    pub fn from_big(n: BigInt) -> Value {
+        match i64::try_from(&n) {
+            Ok(small) => Value::Integer(small),
+            Err(_) => Value::BigInt(Rc::new(n)),
+        }
+    }
+
+    /// The name `whit_kind` gives back: an instance reports its class name
+    /// and a native object its own type name
+    pub fn kind_name(&self) -> String {
+        match self {
+            Value::Instance(instance) => instance.borrow().class.name.clone(),
+            Value::NativeObject(obj) => obj.type_name().to_string(),
+            _ => self.type_name().to_string(),
         }
     }
 
@@ -117,6 +147,15 @@ impl Value {
         }
     }
 
+    /// Whether `<`, `>`, sort, min and max can order this against `other`:
+    /// two numbers (ints, big ints and floats mixed) or two strings
+    pub fn is_comparable_with(&self, other: &Value) -> bool {
+        let is_number =
+            |v: &Value| matches!(v, Value::Integer(_) | Value::BigInt(_) | Value::Float(_));
+        matches!((self, other), (Value::String(_), Value::String(_)))
+            || (is_number(self) && is_number(other))
+    }
+
     pub fn as_integer(&self) -> Option<i64> {
         match self {
             Value::Integer(n) => Some(*n),
@@ -187,6 +226,7 @@ impl Value {
             Value::Nil => ValueKey::Nil,
             Value::Bool(b) => ValueKey::Bool(*b),
             Value::Integer(n) => ValueKey::Int(*n),
+            Value::BigInt(n) => ValueKey::BigInt(n.to_string()),
             Value::Float(f) => ValueKey::Float(f.to_bits()),
             Value::String(s) => ValueKey::String(s.clone()),
             Value::List(l) => ValueKey::List(Rc::as_ptr(l) as usize),
@@ -201,6 +241,7 @@ impl Value {
             Value::NativeObject(obj) => {
                 ValueKey::NativeObject(Rc::as_ptr(obj) as *const () as usize)
             }
+            Value::Generator(gen) => ValueKey::Generator(Rc::as_ptr(gen) as usize),
             Value::Range(r) => ValueKey::Range {
                 start: r.start,
                 end: r.end,
@@ -208,13 +249,172 @@ impl Value {
             },
         }
     }
+
+    /// Structural equality: lists, dicts and sets match when their contents
+    /// do, at any depth. `==` still compares dicts and sets by identity.
+    pub fn deep_eq(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::List(a), Value::List(b)) => {
+                if Rc::ptr_eq(a, b) {
+                    return true;
+                }
+                let (a, b) = (a.borrow(), b.borrow());
+                a.len() == b.len() && a.iter().zip(b.iter()).all(|(x, y)| x.deep_eq(y))
+            }
+            (Value::Dict(a), Value::Dict(b)) => {
+                if Rc::ptr_eq(a, b) {
+                    return true;
+                }
+                let (a, b) = (a.borrow(), b.borrow());
+                a.len() == b.len()
+                    && a.iter()
+                        .all(|(k, v)| b.get(k).is_some_and(|other| v.deep_eq(other)))
+            }
+            (Value::Set(a), Value::Set(b)) => {
+                let (a, b) = (a.borrow(), b.borrow());
+                a.len() == b.len() && a.iter().all(|v| b.contains(v))
+            }
+            _ => self == other,
+        }
+    }
+
+    /// A type-tagged dump for `shaw`, e.g. `List[2]: [Int:1, Str:"x"]`.
+    /// With `pretty`, every element gets its own indented line. A container
+    /// found inside itself prints as `…` instead of recursing forever.
+    pub fn shaw(&self, pretty: bool) -> String {
+        let mut out = String::new();
+        self.shaw_into(&mut out, pretty, 0, &mut Vec::new());
+        out
+    }
+
+    fn shaw_into(&self, out: &mut String, pretty: bool, depth: usize, path: &mut Vec<usize>) {
+        type Entries = Vec<(Option<String>, Value)>;
+        let (id, header, open, close, entries): (usize, String, char, char, Entries) = match self {
+            Value::List(items) => {
+                let list = items.borrow();
+                (
+                    Rc::as_ptr(items) as *const () as usize,
+                    format!("List[{}]", list.len()),
+                    '[',
+                    ']',
+                    list.iter().map(|v| (None, v.clone())).collect(),
+                )
+            }
+            Value::Dict(dict) => {
+                let map = dict.borrow();
+                (
+                    Rc::as_ptr(dict) as *const () as usize,
+                    format!("Dict[{}]", map.len()),
+                    '{',
+                    '}',
+                    map.iter()
+                        .map(|(k, v)| (Some(k.shaw(false)), v.clone()))
+                        .collect(),
+                )
+            }
+            Value::Set(set) => {
+                let set = set.borrow();
+                let mut items: Vec<Value> = set.iter().cloned().collect();
+                items.sort_by_key(|v| v.to_string());
+                (
+                    0,
+                    format!("Creel[{}]", items.len()),
+                    '{',
+                    '}',
+                    items.into_iter().map(|v| (None, v)).collect(),
+                )
+            }
+            Value::Instance(inst) => {
+                let instance = inst.borrow();
+                let mut fields: Vec<_> = instance.fields.iter().collect();
+                fields.sort_by(|a, b| a.0.cmp(b.0));
+                (
+                    Rc::as_ptr(inst) as *const () as usize,
+                    format!("Instance {}", instance.class.name),
+                    '{',
+                    '}',
+                    fields
+                        .into_iter()
+                        .map(|(name, v)| (Some(name.clone()), v.clone()))
+                        .collect(),
+                )
+            }
+            Value::Integer(n) => return out.push_str(&format!("Int:{}", n)),
+            Value::BigInt(n) => return out.push_str(&format!("Int:{}", n)),
+            Value::Float(f) => return out.push_str(&format!("Float:{}", format_float(*f))),
+            Value::String(s) => return out.push_str(&format!("Str:{:?}", s)),
+            Value::Bool(b) => return out.push_str(if *b { "Bool:aye" } else { "Bool:nae" }),
+            Value::Nil => return out.push_str("Naething"),
+            Value::Bytes(bytes) => {
+                return out.push_str(&format!("Bytes[{}]", bytes.borrow().len()))
+            }
+            Value::Range(_) => return out.push_str(&format!("Range:{}", self)),
+            Value::Function(func) => return out.push_str(&format!("Dae:{}", func.name)),
+            Value::NativeFunction(func) => {
+                return out.push_str(&format!("NativeDae:{}", func.name))
+            }
+            Value::Class(class) => return out.push_str(&format!("Kin:{}", class.name)),
+            Value::Struct(def) => return out.push_str(&format!("Thing:{}", def.name)),
+            Value::Generator(gen) => {
+                return out.push_str(&format!("Generator:{}", gen.borrow().name))
+            }
+            Value::NativeObject(obj) => {
+                return out.push_str(&format!("Native:{}", obj.to_string()))
+            }
+        };
+
+        if path.contains(&id) {
+            out.push('…');
+            return;
+        }
+        out.push_str(&header);
+        out.push_str(": ");
+        out.push(open);
+        path.push(id);
+        let count = entries.len();
+        for (i, (key, value)) in entries.into_iter().enumerate() {
+            if pretty {
+                out.push('\n');
+                out.push_str(&"  ".repeat(depth + 1));
+            } else if i > 0 {
+                out.push_str(", ");
+            }
+            if let Some(key) = key {
+                out.push_str(&key);
+                out.push_str(": ");
+            }
+            value.shaw_into(out, pretty, depth + 1, path);
+            if pretty && i + 1 < count {
+                out.push(',');
+            }
+        }
+        path.pop();
+        if pretty && count > 0 {
+            out.push('\n');
+            out.push_str(&"  ".repeat(depth));
+        }
+        out.push(close);
+    }
+}
+
+/// The one true way to turn a float into text - display, JSON and the
+/// native runtime all agree on it. You get the shortest digits that read back
+/// as the same float, and whole floats keep their `.0` (`5.0`, not `5`) so
+/// they cannot be mistaken for ints. Very big or small numbers use an exponent
+/// (`1e16`, `1e-7`).
+pub fn format_float(f: f64) -> String {
+    format!("{:?}", f)
 }
 
+/// Most decimals `fmt_float` will give
+pub const MAX_FLOAT_DECIMALS: i64 = 100;
+
 impl fmt::Display for Value {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Value::Integer(n) => write!(f, "{}", n),
-            Value::Float(n) => write!(f, "{}", n),
+            Value::BigInt(n) => write!(f, "{}", n),
+            Value::Float(n) => write!(f, "{}", format_float(*n)),
             Value::String(s) => write!(f, "{}", s),
             Value::Bool(true) => write!(f, "aye"),
             Value::Bool(false) => write!(f, "nae"),
@@ -249,13 +449,14 @@ impl fmt::Display for Value {
                 let len = bytes.borrow().len();
                 write!(f, "bytes[{}]", len)
             }
-            Value::Function(func) => write!(f, "<dae {}>", func.name),
+            Value::Function(func) => func.fmt(f),
             Value::NativeFunction(func) => write!(f, "<native dae {}>", func.name),
             Value::Class(class) => write!(f, "<kin {}>", class.name),
             Value::Instance(inst) => write!(f, "<{} instance>", inst.borrow().class.name),
             Value::Struct(s) => write!(f, "<thing {}>", s.name),
             Value::Range(r) => write!(f, "{}..{}", r.start, r.end),
             Value::NativeObject(obj) => write!(f, "{}", obj.to_string()),
+            Value::Generator(gen) => write!(f, "<generator {}>", gen.borrow().name),
         }
     }
 }
@@ -264,9 +465,14 @@ impl PartialEq for Value {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (Value::Integer(a), Value::Integer(b)) => a == b,
+            (Value::BigInt(a), Value::BigInt(b)) => a == b,
             (Value::Float(a), Value::Float(b)) => a == b,
-            (Value::Integer(a), Value::Float(b)) => (*a as f64) == *b,
-            (Value::Float(a), Value::Integer(b)) => *a == (*b as f64),
+            (Value::Integer(a), Value::Float(b)) | (Value::Float(b), Value::Integer(a)) => {
+                cmp_int_float(*a, *b) == Some(Ordering::Equal)
+            }
+            (Value::BigInt(a), Value::Float(b)) | (Value::Float(b), Value::BigInt(a)) => {
+                a.to_f64() == Some(*b)
+            }
             (Value::String(a), Value::String(b)) => a == b,
             (Value::Bool(a), Value::Bool(b)) => a == b,
             (Value::Nil, Value::Nil) => true,
@@ -280,11 +486,55 @@ impl PartialEq for Value {
             (Value::Instance(a), Value::Instance(b)) => Rc::ptr_eq(a, b),
             (Value::Struct(a), Value::Struct(b)) => Rc::ptr_eq(a, b),
             (Value::NativeObject(a), Value::NativeObject(b)) => Rc::ptr_eq(a, b),
+            (Value::Generator(a), Value::Generator(b)) => Rc::ptr_eq(a, b),
             _ => false,
         }
     }
 }
 
+/// Numbers order by value, with ints, big ints and floats mixed freely, and
+/// strings order lexicographically, byte by byte. Any other pairing has no
+/// order, and neither does NaN.
+impl PartialOrd for Value {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Value::Integer(a), Value::Integer(b)) => Some(a.cmp(b)),
+            (Value::BigInt(a), Value::BigInt(b)) => Some(a.cmp(b)),
+            (Value::Integer(a), Value::BigInt(b)) => Some(BigInt::from(*a).cmp(b)),
+            (Value::BigInt(a), Value::Integer(b)) => Some((**a).cmp(&BigInt::from(*b))),
+            (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+            (Value::Float(a), Value::Float(b)) => a.partial_cmp(b),
+            (Value::Integer(a), Value::Float(b)) => cmp_int_float(*a, *b),
+            (Value::Float(a), Value::Integer(b)) => cmp_int_float(*b, *a).map(Ordering::reverse),
+            (Value::BigInt(a), Value::Float(b)) => a.to_f64()?.partial_cmp(b),
+            (Value::Float(a), Value::BigInt(b)) => a.partial_cmp(&b.to_f64()?),
+            _ => None,
+        }
+    }
+}
+
+/// Compare an integer with a float exactly. Casting the integer to f64 would
+/// round it once it is past 2^53, so compare the float's whole part first and
+/// then its fraction.
+fn cmp_int_float(i: i64, f: f64) -> Option<Ordering> {
+    // 2^63 is the first float past i64::MAX; -2^63 is exactly i64::MIN
+    const TWO_POW_63: f64 = 9_223_372_036_854_775_808.0;
+    if f.is_nan() {
+        return None;
+    }
+    if f >= TWO_POW_63 {
+        return Some(Ordering::Less);
+    }
+    if f < -TWO_POW_63 {
+        return Some(Ordering::Greater);
+    }
+    let whole = f.trunc();
+    match i.cmp(&(whole as i64)) {
+        Ordering::Equal => 0.0.partial_cmp(&(f - whole)),
+        ord => Some(ord),
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct DictValue {
     index: HashMap<ValueKey, usize>,
@@ -466,6 +716,8 @@ pub struct HaversFunction {
     pub params: Vec<FunctionParam>,
     pub body: Vec<Stmt>,
     pub closure: Option<Rc<RefCell<Environment>>>,
+    /// The body uses `gie_back`, so calling it makes a generator
+    pub is_generator: bool,
 }
 
 impl HaversFunction {
@@ -475,11 +727,13 @@ impl HaversFunction {
         body: Vec<Stmt>,
         closure: Option<Rc<RefCell<Environment>>>,
     ) -> Self {
+        let is_generator = body.iter().any(Stmt::contains_yield);
         HaversFunction {
             name,
             params,
             body,
             closure,
+            is_generator,
         }
     }
 
@@ -494,6 +748,59 @@ impl HaversFunction {
     }
 }
 
+/// `<dae add/2>`, or `<lambda@3:9/1>` for a lambda - named by where it's written
+/// so the same lambda always prints the same. Keep in step with the native runtime.
+impl fmt::Display for HaversFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.name.strip_suffix('>') {
+            Some(lambda) => write!(f, "{}/{}>", lambda, self.params.len()),
+            None => write!(f, "<dae {}/{}>", self.name, self.params.len()),
+        }
+    }
+}
+
+/// A generator: a function body paused at a `gie_back`, ready to pick up again
+#[derive(Debug)]
+pub struct HaversGenerator {
+    pub name: String,
+    /// Where to resume, innermost last. Empty once the generator has finished.
+    pub frames: Vec<GeneratorFrame>,
+    /// Set while the body is running, so a generator cannot resume itself
+    pub running: bool,
+}
+
+/// One level of paused control flow inside a generator
+#[derive(Debug)]
+pub enum GeneratorFrame {
+    /// Statements still to run, in their own scope
+    Block {
+        statements: Rc<Vec<Stmt>>,
+        next: usize,
+        env: Rc<RefCell<Environment>>,
+    },
+    /// A `whiles` loop with a `gie_back` in its body
+    While {
+        condition: Rc<Expr>,
+        body: Rc<Vec<Stmt>>,
+        env: Rc<RefCell<Environment>>,
+    },
+    /// A `fer` loop with a `gie_back` in its body
+    For {
+        variable: String,
+        value_variable: Option<String>,
+        source: ForSource,
+        body: Rc<Vec<Stmt>>,
+        env: Rc<RefCell<Environment>>,
+    },
+}
+
+/// What a `fer` loop is stepping through
+#[derive(Debug)]
+pub enum ForSource {
+    Items(std::vec::IntoIter<Value>),
+    Generator(Rc<RefCell<HaversGenerator>>),
+}
+
 /// A native/built-in function
 pub struct NativeFunction {
     pub name: String,
@@ -537,6 +844,15 @@ impl HaversClass {
         }
     }
 
+    /// Whether this class is `name` or inherits from it
+    pub fn is_or_inherits(&self, name: &str) -> bool {
+        self.name == name
+            || self
+                .superclass
+                .as_ref()
+                .is_some_and(|superclass| superclass.is_or_inherits(name))
+    }
+
     pub fn find_method(&self, name: &str) -> Option<Rc<HaversFunction>> {
         if let Some(method) = self.methods.get(name) {
             return Some(method.clone());
@@ -691,11 +1007,25 @@ impl Environment {
         false
     }
 
+    /// Every name visible from here, including those in enclosing scopes
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.values.keys().cloned().collect();
+        if let Some(enclosing) = &self.enclosing {
+            names.extend(enclosing.borrow().names());
+        }
+        names
+    }
+
     /// Get all values defined in this environment (not including enclosing)
     /// Used fer module exports
     pub fn get_exports(&self) -> HashMap<String, Value> {
         self.values.clone()
     }
+
+    /// Swap every value defined in this environment for `values`
+    pub fn set_values(&mut self, values: HashMap<String, Value>) {
+        self.values = values;
+    }
 }
 
 impl Default for Environment {
@@ -821,6 +1151,62 @@ mod tests {
         assert!(Value::Nil.as_native_function().is_none());
     }
 
+    #[test]
+    fn test_value_deep_eq_compares_contents() {
+        let dict = |pairs: &[(&str, Value)]| {
+            let mut d = DictValue::new();
+            for (k, v) in pairs {
+                d.set(Value::String(k.to_string()), v.clone());
+            }
+            Value::Dict(Rc::new(RefCell::new(d)))
+        };
+        let list = |items: Vec<Value>| Value::List(Rc::new(RefCell::new(items)));
+
+        let a = dict(&[("x", list(vec![Value::Integer(1)])), ("y", Value::Nil)]);
+        let b = dict(&[("y", Value::Nil), ("x", list(vec![Value::Float(1.0)]))]);
+        assert!(a.deep_eq(&b));
+        assert_ne!(a, b, "== on dicts stays identity");
+        assert!(!a.deep_eq(&dict(&[("x", list(vec![Value::Integer(2)]))])));
+        assert!(list(vec![a.clone()]).deep_eq(&list(vec![b])));
+        assert!(!list(vec![Value::Integer(1)]).deep_eq(&list(vec![])));
+
+        let set = |items: &[i64]| {
+            let mut s = SetValue::new();
+            for i in items {
+                s.insert(Value::Integer(*i));
+            }
+            Value::Set(Rc::new(RefCell::new(s)))
+        };
+        assert!(set(&[1, 2]).deep_eq(&set(&[2, 1])));
+        assert!(!set(&[1, 2]).deep_eq(&set(&[1, 3])));
+        assert!(Value::String("a".into()).deep_eq(&Value::String("a".into())));
+    }
+
+    #[test]
+    fn test_value_shaw_tags_nested_values() {
+        let mut d = DictValue::new();
+        d.set(
+            Value::String("k".to_string()),
+            Value::List(Rc::new(RefCell::new(vec![Value::Bool(true), Value::Nil]))),
+        );
+        let value = Value::List(Rc::new(RefCell::new(vec![
+            Value::Integer(1),
+            Value::Float(2.5),
+            Value::String("x".to_string()),
+            Value::Dict(Rc::new(RefCell::new(d))),
+        ])));
+        assert_eq!(
+            value.shaw(false),
+            r#"List[4]: [Int:1, Float:2.5, Str:"x", Dict[1]: {Str:"k": List[2]: [Bool:aye, Naething]}]"#
+        );
+        assert_eq!(
+            value.shaw(true),
+            "List[4]: [\n  Int:1,\n  Float:2.5,\n  Str:\"x\",\n  Dict[1]: {\n    Str:\"k\": List[2]: [\n      Bool:aye,\n      Naething\n    ]\n  }\n]"
+        );
+        let empty = Value::List(Rc::new(RefCell::new(Vec::new())));
+        assert_eq!(empty.shaw(true), "List[0]: []");
+    }
+
     // ==================== Value::is_truthy() Tests ====================
 
     #[test]
@@ -919,6 +1305,23 @@ mod tests {
 
     // ==================== Value Display Tests ====================
 
+    #[test]
+    fn test_format_float_is_shortest_round_trip() {
+        assert_eq!(format_float(0.1 + 0.2), "0.30000000000000004");
+        assert_eq!(format_float(5.0), "5.0");
+        assert_eq!(format_float(-0.0), "-0.0");
+        assert_eq!(format_float(0.1), "0.1");
+        assert_eq!(format_float(1e16), "1e16");
+        assert_eq!(format_float(1e15), "1000000000000000.0");
+        assert_eq!(format_float(1.5e-7), "1.5e-7");
+        assert_eq!(format_float(f64::NAN), "NaN");
+        assert_eq!(format_float(f64::NEG_INFINITY), "-inf");
+        for x in [0.1 + 0.2, 1.0 / 3.0, f64::MAX, f64::MIN_POSITIVE, 123456789.125] {
+            assert_eq!(format_float(x).parse::<f64>().unwrap(), x);
+        }
+        assert_eq!(format!("{}", Value::Float(2.0)), "2.0");
+    }
+
     #[test]
     fn test_value_display_primitives() {
         assert_eq!(format!("{}", Value::Integer(42)), "42");
@@ -985,9 +1388,25 @@ mod tests {
 
     #[test]
     fn test_value_display_function() {
-        let func = HaversFunction::new("add".to_string(), vec![], vec![], None);
+        let param = |name: &str| FunctionParam {
+            name: name.to_string(),
+            default: None,
+        };
+        let func = HaversFunction::new(
+            "add".to_string(),
+            vec![param("a"), param("b")],
+            vec![],
+            None,
+        );
         let val = Value::Function(Rc::new(func));
-        assert_eq!(format!("{}", val), "<dae add>");
+        assert_eq!(format!("{}", val), "<dae add/2>");
+
+        let lambda =
+            HaversFunction::new("<lambda@3:9>".to_string(), vec![param("x")], vec![], None);
+        assert_eq!(
+            format!("{}", Value::Function(Rc::new(lambda))),
+            "<lambda@3:9/1>"
+        );
     }
 
     #[test]
@@ -1103,6 +1522,53 @@ mod tests {
         assert_ne!(Value::Nil, Value::Bool(false));
     }
 
+    #[test]
+    fn test_value_ordering() {
+        use std::cmp::Ordering;
+
+        assert!(Value::Integer(2) < Value::Float(2.5));
+        assert!(Value::Float(-0.5) < Value::Integer(0));
+        assert_eq!(
+            Value::Integer(3).partial_cmp(&Value::Float(3.0)),
+            Some(Ordering::Equal)
+        );
+        let big = Value::from_big(BigInt::from(i64::MAX) * 4);
+        assert!(Value::Integer(i64::MAX) < big);
+        assert!(big > Value::Float(1e18));
+
+        // Past 2^53 an f64 cannot hold every integer, so the compare must be exact
+        assert!(Value::Integer(9_007_199_254_740_993) > Value::Float(9_007_199_254_740_992.0));
+        assert!(Value::Float(9_007_199_254_740_992.0) < Value::Integer(9_007_199_254_740_993));
+        assert_ne!(
+            Value::Integer(9_007_199_254_740_993),
+            Value::Float(9_007_199_254_740_992.0)
+        );
+        // 9.223372036854775807e18 rounds to 2^63, one past i64::MAX
+        let two_pow_63 = Value::Float(9_223_372_036_854_775_808.0);
+        assert_ne!(Value::Integer(i64::MAX), two_pow_63);
+        assert!(Value::Integer(i64::MAX) < two_pow_63);
+        assert_eq!(
+            Value::Integer(i64::MIN),
+            Value::Float(-9_223_372_036_854_775_808.0)
+        );
+        assert!(Value::Integer(-3) < Value::Float(-2.5));
+        assert!(Value::Integer(-2) > Value::Float(-2.5));
+        assert!(Value::Integer(1) < Value::Float(f64::INFINITY));
+        assert!(Value::Integer(1) > Value::Float(f64::NEG_INFINITY));
+        assert!(Value::String("apple".to_string()) < Value::String("banana".to_string()));
+        assert!(Value::String("Zebra".to_string()) < Value::String("apple".to_string()));
+
+        // No order between different kinds, or with a NaN
+        assert_eq!(
+            Value::Integer(1).partial_cmp(&Value::String("1".to_string())),
+            None
+        );
+        assert_eq!(Value::Nil.partial_cmp(&Value::Nil), None);
+        assert_eq!(Value::Float(f64::NAN).partial_cmp(&Value::Integer(1)), None);
+        assert!(Value::Float(f64::NAN).is_comparable_with(&Value::Integer(1)));
+        assert!(!Value::Bool(true).is_comparable_with(&Value::Bool(false)));
+    }
+
     // ==================== FunctionParam Tests ====================
 
     #[test]
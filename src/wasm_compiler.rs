@@ -199,6 +199,8 @@ const AUDIO_IMPORTS: &[(&str, &str)] = &[
 #[derive(Debug, Default)]
 struct WasmImportRequirements {
     needs_tri_module: bool,
+    /// `args()` is called and not shadowed by a `dae` of the same name
+    needs_args: bool,
     audio_imports: BTreeSet<String>,
 }
 
@@ -255,6 +257,18 @@ impl WasmImportRequirements {
                     self.scan_stmt(else_branch, defined_functions);
                 }
             }
+            Stmt::IfLet {
+                value,
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                self.scan_expr(value, defined_functions);
+                self.scan_stmt(then_branch, defined_functions);
+                if let Some(else_branch) = else_branch {
+                    self.scan_stmt(else_branch, defined_functions);
+                }
+            }
             Stmt::While {
                 condition, body, ..
             } => {
@@ -317,7 +331,9 @@ impl WasmImportRequirements {
                     self.scan_expr(expr, defined_functions);
                 }
             }
-            Stmt::Hurl { message, .. } => self.scan_expr(message, defined_functions),
+            Stmt::Hurl { message, .. } | Stmt::Yield { value: message, .. } => {
+                self.scan_expr(message, defined_functions)
+            }
             Stmt::Break { .. }
             | Stmt::Continue { .. }
             | Stmt::Class { .. }
@@ -339,6 +355,9 @@ impl WasmImportRequirements {
             } => {
                 if let Expr::Variable { name, .. } = callee.as_ref() {
                     self.add_audio_import(name, defined_functions);
+                    if name == "args" && !defined_functions.contains(name) {
+                        self.needs_args = true;
+                    }
                 }
                 self.scan_expr(callee, defined_functions);
                 for arg in arguments {
@@ -387,15 +406,32 @@ impl WasmImportRequirements {
                     self.scan_expr(expr, defined_functions);
                 }
             }
+            Expr::ListComp {
+                element, clauses, ..
+            } => {
+                for clause in clauses {
+                    match clause {
+                        CompClause::For { iterable: expr, .. } | CompClause::If(expr) => {
+                            self.scan_expr(expr, defined_functions)
+                        }
+                    }
+                }
+                self.scan_expr(element, defined_functions);
+            }
             Expr::Dict { pairs, .. } => {
                 for (k, v) in pairs {
                     self.scan_expr(k, defined_functions);
                     self.scan_expr(v, defined_functions);
                 }
             }
-            Expr::Range { start, end, .. } => {
+            Expr::Range {
+                start, end, step, ..
+            } => {
                 self.scan_expr(start, defined_functions);
                 self.scan_expr(end, defined_functions);
+                if let Some(expr) = step {
+                    self.scan_expr(expr, defined_functions);
+                }
             }
             Expr::Grouping { expr, .. } => self.scan_expr(expr, defined_functions),
             Expr::Lambda { body, .. } => self.scan_expr(body, defined_functions),
@@ -438,11 +474,27 @@ pub struct WasmCompiler {
     local_vars: Vec<String>,
     func_params: Vec<String>,
     string_data: Vec<String>,
+    /// How deep we are in `fer` loops, so nested loops get their own temps
+    for_depth: usize,
+    /// Export the host memory along with the functions
+    export_all: bool,
+    /// `args()` goes to the host import instead of a compiled function
+    host_args: bool,
 }
 
 const TMP_LOGIC: &str = "__mdh$tmp0";
 const TMP_BUILD: &str = "__mdh$tmp1";
 
+/// Per-loop temps for a `fer` over a range: the end bound, the stride, and
+/// whether we're counting down
+fn for_temps(depth: usize) -> [String; 3] {
+    [
+        format!("__mdh$for_end{}", depth),
+        format!("__mdh$for_step{}", depth),
+        format!("__mdh$for_down{}", depth),
+    ]
+}
+
 impl Default for WasmCompiler {
     fn default() -> Self {
         Self::new()
@@ -457,14 +509,24 @@ impl WasmCompiler {
             local_vars: Vec::new(),
             func_params: Vec::new(),
             string_data: Vec::new(),
+            for_depth: 0,
+            export_all: false,
+            host_args: false,
         }
     }
 
+    /// Export everything the module has, memory included, not just the functions
+    pub fn with_export_all(mut self, export_all: bool) -> Self {
+        self.export_all = export_all;
+        self
+    }
+
     /// Compile a program tae WAT (WebAssembly Text Format)
     pub fn compile(&mut self, program: &Program) -> HaversResult<String> {
         self.output.clear();
         self.string_data.clear();
         let import_requirements = WasmImportRequirements::from_program(program);
+        self.host_args = import_requirements.needs_args;
 
         // Start the module
         self.emit("(module");
@@ -536,6 +598,9 @@ impl WasmCompiler {
                 "(import \"env\" \"__mdh_tri_module\" (func $mdh_tri_module (result i64)))",
             );
         }
+        if import_requirements.needs_args {
+            self.emit_line("(import \"env\" \"__mdh_args\" (func $mdh_args (result i64)))");
+        }
 
         if !import_requirements.audio_imports.is_empty() {
             self.emit_line("");
@@ -564,14 +629,24 @@ impl WasmCompiler {
             self.compile_function(func)?;
         }
 
-        // Compile main code as start function
-        if !main_stmts.is_empty() {
-            self.compile_main(&main_stmts)?;
-        }
+        // Compile main code as start function - always, even when it's empty,
+        // so there's always a `main` to export
+        self.compile_main(&main_stmts)?;
 
-        // Export the main function
+        // Export the main function, then every top-level `dae` by name so
+        // JavaScript can call them
         self.emit_line("");
         self.emit_line("(export \"main\" (func $main))");
+        for func in &functions {
+            if let Stmt::Function { name, .. } = func {
+                if name != "main" {
+                    self.emit_line(&format!("(export \"{}\" (func ${}))", name, name));
+                }
+            }
+        }
+        if self.export_all {
+            self.emit_line("(export \"memory\" (memory 0))");
+        }
 
         // Add string data section if we have strings
         if !self.string_data.is_empty() {
@@ -712,6 +787,17 @@ impl WasmCompiler {
                     self.collect_locals_stmt(eb);
                 }
             }
+            Stmt::IfLet {
+                name,
+                value,
+                then_branch,
+                else_branch,
+                span,
+            } => {
+                let lowered =
+                    Stmt::lower_if_let(name, value, then_branch, else_branch.as_deref(), *span);
+                self.collect_locals_stmt(&lowered);
+            }
             Stmt::While { body, .. } => {
                 self.collect_locals_stmt(body);
             }
@@ -719,7 +805,12 @@ impl WasmCompiler {
                 if !self.func_params.contains(variable) && !self.local_vars.contains(variable) {
                     self.local_vars.push(variable.clone());
                 }
+                for temp in for_temps(self.for_depth) {
+                    self.ensure_temp_local(&temp);
+                }
+                self.for_depth += 1;
                 self.collect_locals_stmt(body);
+                self.for_depth -= 1;
             }
             Stmt::Import {
                 alias: Some(name), ..
@@ -846,6 +937,19 @@ impl WasmCompiler {
                 self.emit_line(")");
             }
 
+            // Locals are per function here, so lean on the plain-statement form
+            Stmt::IfLet {
+                name,
+                value,
+                then_branch,
+                else_branch,
+                span,
+            } => {
+                let lowered =
+                    Stmt::lower_if_let(name, value, then_branch, else_branch.as_deref(), *span);
+                self.compile_stmt(&lowered)?;
+            }
+
             Stmt::While {
                 condition, body, ..
             } => {
@@ -872,6 +976,21 @@ impl WasmCompiler {
                 self.emit_line(")");
             }
 
+            Stmt::For {
+                variable,
+                value_variable,
+                iterable,
+                body,
+                ..
+            } => {
+                if value_variable.is_some() {
+                    return Err(HaversError::InternalError(
+                        "fer k, v loops arenae supported in WASM the noo".to_string(),
+                    ));
+                }
+                self.compile_for_range(variable, iterable, body)?;
+            }
+
             Stmt::Return { value, .. } => {
                 if let Some(val) = value {
                     self.compile_expr(val)?;
@@ -920,7 +1039,135 @@ impl WasmCompiler {
         Ok(())
     }
 
+    /// `fer var in start..end by step` as a `loop`. Like the interpreter,
+    /// the direction comes from the bounds and the step is a stride, so
+    /// `10..0 by 2` counts down. `haud` jumps to the increment.
+    fn compile_for_range(
+        &mut self,
+        variable: &str,
+        iterable: &Expr,
+        body: &Stmt,
+    ) -> HaversResult<()> {
+        let Expr::Range {
+            start,
+            end,
+            inclusive,
+            step,
+            ..
+        } = iterable
+        else {
+            return Err(HaversError::InternalError(
+                "Only ranges can be looped ower wi' fer in WASM the noo".to_string(),
+            ));
+        };
+        let [end_var, step_var, down_var] = for_temps(self.for_depth);
+
+        self.compile_expr(start)?;
+        self.emit_line(&format!("(local.set ${})", variable));
+        self.compile_expr(end)?;
+        self.emit_line(&format!("(local.set ${})", end_var));
+
+        if let Some(step) = step {
+            self.compile_expr(step)?;
+            self.emit_line(&format!("(local.set ${})", step_var));
+            // A zero step would never finish - trap like the interpreter errors
+            self.emit_line(&format!("(local.get ${})", step_var));
+            self.emit_line("(i64.const 0)");
+            self.emit_line("(call $mdh_make_int)");
+            self.emit_line("(call $mdh_eq)");
+            self.emit_line("(call $mdh_truthy)");
+            self.emit_line("(if (then (unreachable)))");
+            // Only the size of the step matters
+            self.emit_line(&format!("(local.get ${})", step_var));
+            self.emit_line("(i64.const 0)");
+            self.emit_line("(call $mdh_make_int)");
+            self.emit_line("(call $mdh_lt)");
+            self.emit_line("(call $mdh_truthy)");
+            self.emit_line(&format!(
+                "(if (then (local.set ${} (call $mdh_neg (local.get ${})))))",
+                step_var, step_var
+            ));
+        } else {
+            self.emit_line("(i64.const 1)");
+            self.emit_line("(call $mdh_make_int)");
+            self.emit_line(&format!("(local.set ${})", step_var));
+        }
+
+        self.emit_line(&format!("(local.get ${})", variable));
+        self.emit_line(&format!("(local.get ${})", end_var));
+        self.emit_line("(call $mdh_gt)");
+        self.emit_line("(call $mdh_truthy)");
+        self.emit_line("(i64.extend_i32_u)");
+        self.emit_line(&format!("(local.set ${})", down_var));
+
+        let (past_down, past_up) = if *inclusive {
+            ("$mdh_lt", "$mdh_gt")
+        } else {
+            ("$mdh_le", "$mdh_ge")
+        };
+
+        self.emit_line("(block $break");
+        self.indent += 1;
+        self.emit_line("(loop $fer");
+        self.indent += 1;
+
+        // Stop once we've gone past the end
+        self.emit_line(&format!("(local.get ${})", down_var));
+        self.emit_line("(i32.wrap_i64)");
+        self.emit_line("(if (result i64)");
+        self.indent += 1;
+        for (arm, compare) in [("then", past_down), ("else", past_up)] {
+            self.emit_line(&format!(
+                "({} (call {} (local.get ${}) (local.get ${})))",
+                arm, compare, variable, end_var
+            ));
+        }
+        self.indent -= 1;
+        self.emit_line(")");
+        self.emit_line("(call $mdh_truthy)");
+        self.emit_line("(br_if $break)");
+
+        self.emit_line("(block $continue");
+        self.indent += 1;
+        self.for_depth += 1;
+        let result = self.compile_stmt(body);
+        self.for_depth -= 1;
+        result?;
+        self.indent -= 1;
+        self.emit_line(")");
+
+        // Step towards the end
+        self.emit_line(&format!("(local.get ${})", down_var));
+        self.emit_line("(i32.wrap_i64)");
+        self.emit_line("(if (result i64)");
+        self.indent += 1;
+        for (arm, op) in [("then", "$mdh_sub"), ("else", "$mdh_add")] {
+            self.emit_line(&format!(
+                "({} (call {} (local.get ${}) (local.get ${})))",
+                arm, op, variable, step_var
+            ));
+        }
+        self.indent -= 1;
+        self.emit_line(")");
+        self.emit_line(&format!("(local.set ${})", variable));
+        self.emit_line("(br $fer)");
+
+        self.indent -= 1;
+        self.emit_line(")");
+        self.indent -= 1;
+        self.emit_line(")");
+        Ok(())
+    }
+
     fn compile_expr(&mut self, expr: &Expr) -> HaversResult<()> {
+        // Arithmetic we can see is float at compile time runs as native f64
+        // ops, then gets boxed like any other value
+        if matches!(expr, Expr::Binary { .. } | Expr::Unary { .. }) && is_static_float(expr) {
+            self.compile_f64(expr);
+            self.emit_line("(call $mdh_make_float)");
+            return Ok(());
+        }
+
         match expr {
             Expr::Literal { value, .. } => match value {
                 Literal::Integer(n) => {
@@ -958,6 +1205,22 @@ impl WasmCompiler {
                 right,
                 ..
             } => {
+                if matches!(
+                    operator,
+                    BinaryOp::Power
+                        | BinaryOp::BitAnd
+                        | BinaryOp::BitOr
+                        | BinaryOp::BitXor
+                        | BinaryOp::ShiftLeft
+                        | BinaryOp::ShiftRight
+                        | BinaryOp::In
+                        | BinaryOp::NotIn
+                ) {
+                    return Err(HaversError::CompileError(format!(
+                        "The {} operator isnae supported in WASM yet!",
+                        operator
+                    )));
+                }
                 self.compile_expr(left)?;
                 self.compile_expr(right)?;
 
@@ -967,6 +1230,14 @@ impl WasmCompiler {
                     BinaryOp::Multiply => self.emit_line("(call $mdh_mul)"),
                     BinaryOp::Divide => self.emit_line("(call $mdh_div)"),
                     BinaryOp::Modulo => self.emit_line("(call $mdh_mod)"),
+                    BinaryOp::Power
+                    | BinaryOp::BitAnd
+                    | BinaryOp::BitOr
+                    | BinaryOp::BitXor
+                    | BinaryOp::ShiftLeft
+                    | BinaryOp::ShiftRight
+                    | BinaryOp::In
+                    | BinaryOp::NotIn => unreachable!("rejected above"),
                     BinaryOp::Equal => self.emit_line("(call $mdh_eq)"),
                     BinaryOp::NotEqual => self.emit_line("(call $mdh_ne)"),
                     BinaryOp::Less => self.emit_line("(call $mdh_lt)"),
@@ -1060,6 +1331,13 @@ impl WasmCompiler {
                 } else if let Expr::Variable { name, .. } = callee.as_ref() {
                     if self.is_local_or_param(name) {
                         self.emit_value_call(callee, arguments)?;
+                    } else if name == "args" && self.host_args {
+                        if !arguments.is_empty() {
+                            return Err(HaversError::InternalError(
+                                "args() doesnae take ony arguments".to_string(),
+                            ));
+                        }
+                        self.emit_line("(call $mdh_args)");
                     } else {
                         // Direct function call (compiled function)
                         for arg in arguments {
@@ -1134,6 +1412,41 @@ impl WasmCompiler {
         Ok(())
     }
 
+    /// Push a raw f64 for an expression `is_static_float` has vetted
+    fn compile_f64(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Literal {
+                value: Literal::Float(f),
+                ..
+            } => self.emit_line(&format!("(f64.const {})", f)),
+            Expr::Literal {
+                value: Literal::Integer(n),
+                ..
+            } => self.emit_line(&format!("(f64.const {})", *n as f64)),
+            Expr::Grouping { expr, .. } => self.compile_f64(expr),
+            Expr::Unary { operand, .. } => {
+                self.compile_f64(operand);
+                self.emit_line("(f64.neg)");
+            }
+            Expr::Binary {
+                left,
+                operator,
+                right,
+                ..
+            } => {
+                self.compile_f64(left);
+                self.compile_f64(right);
+                match operator {
+                    BinaryOp::Add => self.emit_line("(f64.add)"),
+                    BinaryOp::Subtract => self.emit_line("(f64.sub)"),
+                    BinaryOp::Multiply => self.emit_line("(f64.mul)"),
+                    _ => self.emit_line("(f64.div)"),
+                }
+            }
+            _ => unreachable!("compile_f64 on an expression that isnae static float"),
+        }
+    }
+
     fn emit(&mut self, s: &str) {
         self.output.push_str(&"  ".repeat(self.indent));
         self.output.push_str(s);
@@ -1145,6 +1458,79 @@ impl WasmCompiler {
     }
 }
 
+/// Is this expression float arithmetic we can work out at compile time?
+/// Every operator needs a float on at least one side (an int on the other
+/// gets promoted, same as the interpreter), so int-only sums keep their
+/// integer semantics. Division only counts when the divisor is a nonzero
+/// literal, so dividing by zero still gets the runtime's error.
+fn is_static_float(expr: &Expr) -> bool {
+    match expr {
+        Expr::Literal {
+            value: Literal::Float(_),
+            ..
+        } => true,
+        Expr::Grouping { expr, .. } => is_static_float(expr),
+        Expr::Unary {
+            operator: UnaryOp::Negate,
+            operand,
+            ..
+        } => is_static_float(operand),
+        Expr::Binary {
+            left,
+            operator,
+            right,
+            ..
+        } => {
+            let operator_ok = match operator {
+                BinaryOp::Add | BinaryOp::Subtract | BinaryOp::Multiply => true,
+                BinaryOp::Divide => nonzero_number_literal(right),
+                _ => false,
+            };
+            operator_ok
+                && (is_static_float(left) || is_int_literal(left))
+                && (is_static_float(right) || is_int_literal(right))
+                && (is_static_float(left) || is_static_float(right))
+        }
+        _ => false,
+    }
+}
+
+fn is_int_literal(expr: &Expr) -> bool {
+    match expr {
+        Expr::Literal {
+            value: Literal::Integer(_),
+            ..
+        } => true,
+        Expr::Grouping { expr, .. }
+        | Expr::Unary {
+            operator: UnaryOp::Negate,
+            operand: expr,
+            ..
+        } => is_int_literal(expr),
+        _ => false,
+    }
+}
+
+fn nonzero_number_literal(expr: &Expr) -> bool {
+    match expr {
+        Expr::Literal {
+            value: Literal::Integer(n),
+            ..
+        } => *n != 0,
+        Expr::Literal {
+            value: Literal::Float(f),
+            ..
+        } => *f != 0.0,
+        Expr::Grouping { expr, .. }
+        | Expr::Unary {
+            operator: UnaryOp::Negate,
+            operand: expr,
+            ..
+        } => nonzero_number_literal(expr),
+        _ => false,
+    }
+}
+
 /// Escape a string fer WAT data section
 fn escape_wat_string(s: &str) -> String {
     let mut result = String::new();
@@ -1169,6 +1555,26 @@ pub fn compile_to_wat(source: &str) -> HaversResult<String> {
     compiler.compile(&program)
 }
 
+/// The first eight bytes of every binary module: `\0asm` and version 1
+#[cfg(feature = "wat")]
+const WASM_HEADER: [u8; 8] = [0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00];
+
+/// Assemble WAT into a binary `.wasm` module, so you don't need `wat2wasm`
+#[cfg(feature = "wat")]
+pub fn assemble_wat(wat: &str) -> Result<Vec<u8>, String> {
+    let bytes = wat::parse_str(wat).map_err(|e| e.to_string())?;
+    if !bytes.starts_with(&WASM_HEADER) {
+        return Err("the assembled module doesnae start wi' a WASM header".to_string());
+    }
+    Ok(bytes)
+}
+
+/// Assemble WAT into a binary `.wasm` module, so you don't need `wat2wasm`
+#[cfg(not(feature = "wat"))]
+pub fn assemble_wat(_wat: &str) -> Result<Vec<u8>, String> {
+    Err("this build o' mdhavers wis made wi'oot the `wat` assembler".to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1385,11 +1791,59 @@ mod tests {
                 blether i
             }
         "#;
-        let result = compile_to_wat(source);
-        // For loops may not be supported in WASM yet
+        let wat = compile_to_wat(source).unwrap();
+        assert!(wat.contains("(local $i i64)"));
+        assert!(wat.contains("(loop $fer"));
+        assert!(wat.contains("(block $continue"));
+        assert!(wat.contains("(br_if $break)"));
+        assert!(wat.contains("(br $fer)"));
+        assert!(wat.contains("(call $mdh_ge (local.get $i) (local.get $__mdh$for_end0))"));
+    }
+
+    #[test]
+    fn test_for_loop_inclusive_and_stepped_wasm() {
+        let wat = compile_to_wat("fer i in 10..=0 by 2 {\n    blether i\n}").unwrap();
+        assert!(wat.contains("(call $mdh_lt (local.get $i) (local.get $__mdh$for_end0))"));
+        assert!(wat.contains("(unreachable)"));
+        assert!(wat.contains("(local.set $__mdh$for_step0 (call $mdh_neg"));
+    }
+
+    #[test]
+    fn test_nested_for_loops_get_their_own_temps_wasm() {
+        let source = "fer i in 0..3 {\n    fer j in 0..i {\n        blether j\n    }\n}";
+        let wat = compile_to_wat(source).unwrap();
+        assert!(wat.contains("(local $__mdh$for_end0 i64)"));
+        assert!(wat.contains("(local $__mdh$for_end1 i64)"));
+        assert!(wat.contains("(local.set $__mdh$for_end1)"));
+    }
+
+    #[test]
+    fn test_for_over_non_range_wasm_returns_error() {
+        let result = compile_to_wat("fer x in [1, 2] {\n    blether x\n}");
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_recursive_fib_wasm() {
+        let source = r#"
+dae fib(n) {
+    gin n < 2 {
+        gie n
+    }
+    gie fib(n - 1) + fib(n - 2)
+}
+ken total = 0
+fer i in 0..10 {
+    total = total + fib(i)
+}
+blether total
+"#;
+        let wat = compile_to_wat(source).unwrap();
+        let fib = &wat[wat.find("(func $fib").unwrap()..wat.find("(func $main").unwrap()];
+        assert!(fib.contains("(call $fib)"));
+        assert!(wat.contains("(loop $fer"));
+    }
+
     #[test]
     fn test_return_wasm() {
         let source = r#"
@@ -1442,6 +1896,38 @@ mod tests {
         assert!(result.contains("(module"));
     }
 
+    #[test]
+    fn test_float_arithmetic_wasm_uses_f64_ops() {
+        let result = compile_to_wat("blether 1.5 + 2.5").unwrap();
+        assert!(result.contains("(f64.const 1.5)"));
+        assert!(result.contains("(f64.add)"));
+        assert!(result.contains("(call $mdh_make_float)"));
+        assert!(!result.contains("(call $mdh_add)"));
+
+        // Mixed int/float promotes the int
+        let result = compile_to_wat("blether (2 * -1.5) - 1").unwrap();
+        assert!(result.contains("(f64.const 2)"));
+        assert!(result.contains("(f64.neg)"));
+        assert!(result.contains("(f64.mul)"));
+        assert!(result.contains("(f64.sub)"));
+    }
+
+    #[test]
+    fn test_float_arithmetic_wasm_falls_back_to_host() {
+        // Int-only sums keep integer semantics
+        let result = compile_to_wat("blether 7 / 2").unwrap();
+        assert!(result.contains("(call $mdh_div)"));
+        assert!(!result.contains("f64.div"));
+
+        // A divisor that might be zero is left to the runtime
+        let result = compile_to_wat("blether 1.5 / (0.5 - 0.5)").unwrap();
+        assert!(result.contains("(call $mdh_div)"));
+
+        // Variables could hold anything
+        let result = compile_to_wat("ken x = 1.5\nblether x + 1.0").unwrap();
+        assert!(result.contains("(call $mdh_add)"));
+    }
+
     #[test]
     fn test_string_wasm() {
         let source = r#"ken s = "Hello""#;
@@ -1780,11 +2266,75 @@ mod tests {
         assert!(wat.contains("(call $soond_stairt)"));
     }
 
+    #[test]
+    fn test_top_level_functions_are_exported() {
+        let source = r#"
+            dae add(a, b) {
+                gie a + b
+            }
+            blether add(1, 2)
+        "#;
+        let wat = compile_to_wat(source).unwrap();
+        assert!(wat.contains("(export \"main\" (func $main))"));
+        assert!(wat.contains("(export \"add\" (func $add))"));
+        assert!(wat.contains("(call $add"));
+        assert!(!wat.contains("(export \"memory\""));
+    }
+
+    #[test]
+    fn test_functions_only_still_get_a_main() {
+        let wat = compile_to_wat("dae twice(x) { gie x * 2 }").unwrap();
+        assert!(wat.contains("(func $main (result i64)"));
+        assert!(wat.contains("(export \"main\" (func $main))"));
+        assert!(wat.contains("(export \"twice\" (func $twice))"));
+    }
+
+    #[test]
+    fn test_export_all_exports_memory() {
+        let program = crate::parser::parse("dae twice(x) { gie x * 2 }").unwrap();
+        let wat = WasmCompiler::new()
+            .with_export_all(true)
+            .compile(&program)
+            .unwrap();
+        assert!(wat.contains("(export \"twice\" (func $twice))"));
+        assert!(wat.contains("(export \"memory\" (memory 0))"));
+    }
+
+    #[cfg(feature = "wat")]
+    #[test]
+    fn test_assemble_wat_produces_a_binary_module() {
+        let wat = compile_to_wat("dae add(a, b) { gie a + b }\nblether add(1, 2)").unwrap();
+        let bytes = assemble_wat(&wat).unwrap();
+        assert!(bytes.starts_with(&WASM_HEADER));
+        assert!(assemble_wat("(module (func $oops (result i64)").is_err());
+    }
+
+    #[cfg(not(feature = "wat"))]
+    #[test]
+    fn test_assemble_wat_without_assembler_errors() {
+        let err = assemble_wat("(module)").unwrap_err();
+        assert!(err.contains("`wat` assembler"));
+    }
+
     #[test]
     fn test_unused_imports_not_emitted() {
         let wat = compile_to_wat("blether 1").unwrap();
         assert!(!wat.contains("(import \"env\" \"__mdh_tri_module\""));
         assert!(!wat.contains(";; Audio imports"));
         assert!(!wat.contains("(import \"env\" \"soond_stairt\""));
+        assert!(!wat.contains("(import \"env\" \"__mdh_args\""));
+    }
+
+    #[test]
+    fn test_args_comes_fae_the_host_unless_shadowed() {
+        let wat = compile_to_wat("ken argv = args()\ngie argv").unwrap();
+        assert!(wat.contains("(import \"env\" \"__mdh_args\" (func $mdh_args (result i64)))"));
+        assert!(wat.contains("(call $mdh_args)"));
+
+        let wat = compile_to_wat("dae args() { gie 1 }\ngie args()").unwrap();
+        assert!(!wat.contains("__mdh_args"));
+        assert!(wat.contains("(call $args)"));
+
+        assert!(compile_to_wat("args(1)").is_err());
     }
 }
@@ -108,7 +108,8 @@ impl HostStore {
             Some(HostValue::Bool(true)) => "aye".to_string(),
             Some(HostValue::Bool(false)) => "nae".to_string(),
             Some(HostValue::Int(n)) => n.to_string(),
-            Some(HostValue::Float(f)) => f.to_string(),
+            // Same as the interpreter: shortest round-trip form, whole floats keep `.0`
+            Some(HostValue::Float(f)) => crate::value::format_float(*f),
             Some(HostValue::String(s)) => s.clone(),
             Some(HostValue::List(items)) => {
                 let mut parts = Vec::new();
@@ -139,17 +140,33 @@ impl HostStore {
 struct HostState {
     store: HostStore,
     tri_modules: HashSet<Handle>,
+    args: Vec<String>,
+    /// Every line `blether` has printed, in order
+    output: Vec<String>,
 }
 
 impl HostState {
-    fn new() -> Self {
+    fn new(args: Vec<String>) -> Self {
         HostState {
             store: HostStore::new(),
             tri_modules: HashSet::new(),
+            args,
+            output: Vec::new(),
         }
     }
 }
 
+/// What a module's `main` returned when it ran to the end
+#[derive(Debug, Clone, PartialEq)]
+pub struct WasmRunResult {
+    /// The result value as `blether` would show it, or `None` for nil
+    pub value: Option<String>,
+    /// An integer result is the exit code; anything else exits with 0
+    pub exit_code: i32,
+    /// The lines the module printed through `env.__mdh_blether`
+    pub output: Vec<String>,
+}
+
 fn read_memory_string(caller: &Caller<'_, HostState>, mem: &Memory, ptr: i32, len: i32) -> String {
     let data = mem.data(caller);
     let start = ptr.max(0) as usize;
@@ -606,7 +623,10 @@ fn eq_values(store: &HostStore, a: Handle, b: Handle) -> bool {
     }
 }
 
-pub fn run_wasm_file(path: &Path) -> Result<(), String> {
+/// Run a .wat or .wasm module's `main` (or `_start`), passing it `args` as
+/// the tail of its `args()` list - the head is the module path, like argv.
+/// Lines it `blether`s are printed as they come and collected in the result.
+pub fn run_wasm_file(path: &Path, args: &[String]) -> Result<WasmRunResult, String> {
     let engine = Engine::default();
 
     let module = match path.extension().and_then(|s| s.to_str()) {
@@ -617,7 +637,9 @@ pub fn run_wasm_file(path: &Path) -> Result<(), String> {
         _ => Module::from_file(&engine, path).map_err(|e| e.to_string())?,
     };
 
-    let mut store = Store::new(&engine, HostState::new());
+    let mut argv = vec![path.display().to_string()];
+    argv.extend(args.iter().cloned());
+    let mut store = Store::new(&engine, HostState::new(argv));
     let memory = Memory::new(&mut store, MemoryType::new(1, None)).map_err(|e| e.to_string())?;
 
     let mut linker = Linker::new(&engine);
@@ -805,8 +827,28 @@ pub fn run_wasm_file(path: &Path) -> Result<(), String> {
             "env",
             "__mdh_blether",
             |mut caller: Caller<'_, HostState>, value: i64| {
-                let store = &mut caller.data_mut().store;
-                println!("{}", store.to_string(value));
+                let state = caller.data_mut();
+                let line = state.store.to_string(value);
+                println!("{}", line);
+                state.output.push(line);
+            },
+        )
+        .map_err(|e| e.to_string())?;
+
+    // Command line arguments, as a list of strings
+    linker
+        .func_wrap(
+            "env",
+            "__mdh_args",
+            |mut caller: Caller<'_, HostState>| -> i64 {
+                let state = caller.data_mut();
+                let items = state
+                    .args
+                    .clone()
+                    .into_iter()
+                    .map(|arg| alloc_string(&mut state.store, arg))
+                    .collect();
+                state.store.alloc(HostValue::List(items))
             },
         )
         .map_err(|e| e.to_string())?;
@@ -1234,13 +1276,31 @@ pub fn run_wasm_file(path: &Path) -> Result<(), String> {
         .map_err(|e| e.to_string())?;
 
     if let Ok(func) = instance.get_typed_func::<(), i64>(&mut store, "main") {
-        let _ = func.call(&mut store, ()).map_err(|e| e.to_string())?;
-        return Ok(());
+        let result = func.call(&mut store, ()).map_err(|e| e.to_string())?;
+        let host = &store.data().store;
+        let exit_code = match host.get(result) {
+            Some(HostValue::Int(n)) => i32::try_from(*n).unwrap_or(1),
+            _ => 0,
+        };
+        let value = match host.get(result) {
+            Some(HostValue::Nil) | None => None,
+            Some(_) => Some(host.to_string(result)),
+        };
+        let output = std::mem::take(&mut store.data_mut().output);
+        return Ok(WasmRunResult {
+            value,
+            exit_code,
+            output,
+        });
     }
 
     if let Ok(func) = instance.get_typed_func::<(), ()>(&mut store, "_start") {
         func.call(&mut store, ()).map_err(|e| e.to_string())?;
-        return Ok(());
+        return Ok(WasmRunResult {
+            value: None,
+            exit_code: 0,
+            output: std::mem::take(&mut store.data_mut().output),
+        });
     }
 
     Err("WASM module lacks exported 'main' or '_start'".to_string())
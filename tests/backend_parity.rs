@@ -77,6 +77,300 @@ blether s[0]
 "#,
             "a",
         ),
+        (
+            r#"
+blether format("{{{}}} [{:5}] [{:4}] {:.2}", "x", 42, "ab", 3.14159)
+"#,
+            "{x} [   42] [ab  ] 3.14",
+        ),
+        (
+            r#"
+fer i in 3..=1 { blether i }
+"#,
+            "3\n2\n1",
+        ),
+        (
+            r#"
+fer i in 0..7 by 3 { blether i }
+fer i in 7..0 by 3 { blether i }
+"#,
+            "0\n3\n6\n7\n4\n1",
+        ),
+        (
+            r#"
+ken fns = []
+fer i in 0..3 { shove(fns, || i * 10) }
+fer f in fns { blether f() }
+"#,
+            "0\n10\n20",
+        ),
+        (
+            r#"
+ken d = {"name": "Morag", "age": 42}
+blether d["name"]
+blether d.age
+d["age"] = d["age"] + 1
+blether d.age
+d["toon"] = "Oban"
+blether len(d)
+blether d["toon"]
+"#,
+            "Morag\n42\n43\n3\nOban",
+        ),
+        (
+            r#"
+ken k = "inner"
+ken d = {k: {"deep": [10, 20, 30]}}
+blether d["inner"]["deep"][-1]
+d.inner["deep"][0] = 5
+blether d["inner"].deep[0]
+"#,
+            "30\n5",
+        ),
+        (
+            r#"
+ken d = {"a": 1, "b": 2}
+fer pair in items(d) { blether pair[0] + "=" + tae_string(pair[1]) }
+fer k in keys(d) { blether d[k] * 10 }
+"#,
+            "a=1\nb=2\n10\n20",
+        ),
+        (
+            r#"
+ken ages = {"Morag": 34, "Hamish": 28}
+fer who, age in ages {
+    blether who + " is " + tae_string(age)
+}
+fer a, b in [[1, 2], [3, 4]] {
+    blether a * b
+}
+"#,
+            "Morag is 34\nHamish is 28\n2\n12",
+        ),
+        (
+            r#"
+kin Beastie {
+    dae init(name, legs = 4) {
+        masel.name = name
+        masel.legs = legs
+    }
+    dae describe() {
+        gie masel.name + " has " + tae_string(masel.legs) + " legs"
+    }
+    dae speak() {
+        gie "..."
+    }
+}
+kin Dug fae Beastie {
+    dae speak() {
+        gie masel.name + " says woof"
+    }
+}
+kin burdie fae Beastie {
+    dae init(name) {
+        masel.name = name
+        masel.legs = 2
+    }
+}
+ken d = Dug("Bonnie")
+blether d.describe()
+blether d.speak()
+d.legs = 3
+blether d.legs
+ken b = burdie("Robin")
+blether b.describe()
+blether b.speak()
+"#,
+            "Bonnie has 4 legs\nBonnie says woof\n3\nRobin has 2 legs\n...",
+        ),
+        (
+            r#"
+ken name = "Jo"
+ken age = 9
+blether f"Hi {name}, ye are {age} - `${{age}}` costs $5 \\o/"
+"#,
+            r#"Hi Jo, ye are 9 - `${age}` costs $5 \o/"#,
+        ),
+        (
+            r#"
+ken l = [2, 4]
+stick_in(l, 0, 1)
+stick_in(l, 2, 3)
+stick_in(l, -1, 5)
+blether len(l)
+blether tak_oot(l, 0)
+blether tak_oot(l, -2)
+blether whaur(l, 3)
+blether whaur(l, 9)
+blether len(l)
+"#,
+            "5\n1\n5\n1\n-1\n3",
+        ),
+        (
+            r#"
+blether abs(-3)
+blether pow(2, 10)
+blether pow(2, -1)
+blether sqrt(2.25)
+blether fmod(7.5, 2)
+blether fmod(-7.5, 3)
+blether floor(3.7)
+blether ceil(3.2)
+blether fmt_float(3.14159, 2)
+"#,
+            // Whole floats print as `4.0` in the interpreter but JavaScript
+            // has no separate float type, so stick to fractional results
+            "3\n1024\n0.5\n1.5\n1.5\n-1.5\n3\n4\n3.14",
+        ),
+        (
+            r#"
+dae range_by(start, stop, step) {
+    ken i = start
+    whiles i < stop {
+        gie_back i
+        i = i + step
+    }
+}
+dae doubled(src) {
+    fer x in src {
+        gie_back x * 2
+    }
+}
+fer x in doubled(range_by(1, 8, 3)) {
+    blether x
+}
+"#,
+            "2\n8\n14",
+        ),
+        (
+            r#"
+ken d = {"a": 1, "b": 2, "c": 3}
+blether get(d, "a", 0)
+blether get(d, "z", 0)
+gin has_key(d, "c") {
+    blether "got c"
+}
+gin nae has_key(d, "z") {
+    blether "nae z"
+}
+blether remove(d, "b")
+blether join(keys(d), ",")
+gin remove(d, "z") == naething {
+    blether "nowt tae remove"
+}
+"#,
+            "1\n0\ngot c\nnae z\n2\na,c\nnowt tae remove",
+        ),
+        (
+            r#"
+ken xs = [1, 2, 3, 4, 5]
+blether join(xs[1..4], ",")
+blether join(xs[-2..], ",")
+blether len(xs[4..1])
+blether join(slice(xs, -10, 2), ",")
+blether "braw day"[-3..]
+"#,
+            "2,3,4\n4,5\n0\n1,2\nday",
+        ),
+        (
+            r#"
+ken d = {"a": 1}
+gin ken v = get(d, "a", naething) { blether v + 1 } ither { blether "nane" }
+gin ken v = get(d, "z", naething) { blether v } ither gin ken w = d["a"] { blether w }
+gin ken v = naething { blether v }
+"#,
+            "2\n1",
+        ),
+        (
+            r#"
+ken a = [1, 2.5, "x", {"k": [aye, naething]}]
+shaw(a)
+shove(a, a)
+shaw_pretty([a[0], a])
+"#,
+            "List[4]: [Int:1, Float:2.5, Str:\"x\", Dict[1]: {Str:\"k\": List[2]: [Bool:aye, Naething]}]\nList[2]: [\n  Int:1,\n  List[5]: [\n    Int:1,\n    Float:2.5,\n    Str:\"x\",\n    Dict[1]: {\n      Str:\"k\": List[2]: [\n        Bool:aye,\n        Naething\n      ]\n    },\n    …\n  ]\n]",
+        ),
+        (
+            r#"
+dae naen() { gie 1 }
+dae wi_default(a, b = 2) { gie a + b }
+blether airity(naen)
+blether airity(wi_default)
+blether airity(|x, y| x + y)
+"#,
+            "0\n1\n2",
+        ),
+        (
+            r#"
+ken xs = [3, -1, 4]
+blether len([x * 2 fer x in xs])
+blether sumaw([x fer x in xs gin x > 0])
+ken pairs = [a * 10 + b fer a in 1..3 fer b in 0..2]
+blether len(pairs)
+blether sumaw(pairs)
+"#,
+            "3\n7\n4\n62",
+        ),
+        (
+            r#"
+ken rows = split_lines("braw\r\nbonnie\nbide\n")
+blether len(rows)
+blether join(rows, "+")
+blether len(join([], ","))
+blether join([1, 2.5, "x"], ",")
+"#,
+            "3\nbraw+bonnie+bide\n0\n1,2.5,x",
+        ),
+        (
+            r#"
+ken nested = [1, [2, [3, [4]]], "five"]
+blether len(flatten(nested))
+blether len(flatten_deep(nested))
+blether len(flatten_deep(nested, 2)[3])
+blether len(flatten_deep(nested, -1))
+blether sumaw(flatten_deep([[1, 2], [[3]], 4]))
+"#,
+            "4\n5\n1\n5\n10",
+        ),
+        (
+            r#"
+dae yn(b) {
+    gin b { gie "aye" }
+    gie "nae"
+}
+blether yn(5 == 5.0)
+blether yn(5 < 5.5)
+blether yn(5.0 != 5)
+blether yn(5.5 >= 5)
+"#,
+            "aye\naye\nnae\naye",
+        ),
+        (
+            r#"
+dae sum_an_diff(a, b) {
+    gie a + b, a - b
+}
+ken sum, diff = sum_an_diff(9, 4)
+blether sum
+blether diff
+ken first, ...rest = [1, 2, 3]
+blether len(rest)
+"#,
+            "13\n5\n2",
+        ),
+        (
+            r#"
+blether min([3, 1.5, 2])
+blether max([3, 7, 2])
+blether max(4, 9)
+blether max(["haggis", "neeps", "tatties"])
+blether min("neeps", "haggis")
+blether sum([1, 2.5, 3])
+blether sum([])
+blether average([1, 2])
+"#,
+            "1.5\n7\n9\ntatties\nhaggis\n6.5\n0\n1.5",
+        ),
     ];
 
     for (source, expected) in cases {
@@ -87,3 +381,49 @@ blether s[0]
         assert_eq!(js_out.trim(), *expected);
     }
 }
+
+#[test]
+fn parity_interpreter_vs_js_index_errors() {
+    if Command::new("node").arg("--version").output().is_err() {
+        eprintln!("Skipping JS parity tests: node not found");
+        return;
+    }
+
+    let cases = [
+        "ken d = {\"a\": 1}\nblether d[\"b\"]",
+        "ken xs = [1, 2, 3]\nblether xs[3]",
+        "ken xs = [1, 2, 3]\nxs[-4] = 0",
+        "ken a, b = [1, 2, 3]",
+    ];
+
+    for source in cases {
+        assert!(run_interpreter(source).is_err(), "interpreter: {source}");
+        assert!(run_js(source).is_err(), "js: {source}");
+    }
+}
+
+#[test]
+fn parity_interpreter_vs_js_tae_int_rejects_partial_matches() {
+    if Command::new("node").arg("--version").output().is_err() {
+        eprintln!("Skipping JS parity tests: node not found");
+        return;
+    }
+
+    let source = "blether tae_int(\"-ff\", 16)\nblether tae_int(\"+101\", 2)\nblether tae_int(\"42\")";
+    assert_eq!(run_interpreter(source).unwrap(), "-255\n5\n42");
+    assert_eq!(run_js(source).unwrap(), "-255\n5\n42");
+
+    let cases = [
+        ("blether tae_int(\"12xyz\", 16)", "'x' isnae a base 16 digit (in '12xyz')"),
+        ("blether tae_int(\"19\", 8)", "'9' isnae a base 8 digit (in '19')"),
+        ("blether tae_int(\"-\", 2)", "Cannae read '-' as a base 2 number"),
+        ("blether tae_int(\"12abc\")", "Cannae turn '12abc' intae an integer"),
+    ];
+
+    for (source, message) in cases {
+        let interp_err = run_interpreter(source).unwrap_err();
+        assert!(interp_err.contains(message), "interpreter: {interp_err}");
+        let js_err = run_js(source).unwrap_err();
+        assert!(js_err.contains(message), "js: {js_err}");
+    }
+}
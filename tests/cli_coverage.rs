@@ -96,6 +96,83 @@ fn cli_help_and_version_work() {
     assert!(out.trim().starts_with("mdhavers"));
 }
 
+#[test]
+fn cli_boak_exits_cleanly_without_the_panic_hook() {
+    let dir = tempdir().unwrap();
+    let home = dir.path();
+
+    let boak_braw = dir.path().join("boak.braw");
+    write_file(
+        &boak_braw,
+        r#"
+blether "afore"
+boak("deliberate")
+blether "efter"
+"#,
+    );
+
+    let (code, out, err) = run_mdhavers(&["run", boak_braw.to_str().unwrap()], None, home);
+    assert_eq!(code, 1, "stderr: {err}");
+    assert_eq!(out.trim(), "afore");
+    assert!(err.contains("deliberate"), "stderr: {err}");
+    assert!(!err.contains("Och noo!"), "stderr: {err}");
+    assert!(!err.contains("panicked"), "stderr: {err}");
+}
+
+#[test]
+fn cli_no_prelude_keeps_native_builtins_but_drops_prelude_functions() {
+    let dir = tempdir().unwrap();
+    let home = dir.path();
+
+    let natives_braw = dir.path().join("natives.braw");
+    write_file(
+        &natives_braw,
+        r#"
+ken xs = [3, 1, 2]
+shove(xs, 4)
+blether len(xs)
+blether tae_string(sumaw(xs))
+"#,
+    );
+    let (code, out, err) = run_mdhavers(
+        &["run", "--no-prelude", natives_braw.to_str().unwrap()],
+        None,
+        home,
+    );
+    assert_eq!(code, 0, "stderr: {err}");
+    assert_eq!(out.trim(), "4\n10");
+
+    // `ordinal` is written in mdhavers in the prelude, not built in
+    let prelude_braw = dir.path().join("prelude.braw");
+    write_file(&prelude_braw, "blether ordinal(1)\n");
+    let (code, out, err) = run_mdhavers(&["run", prelude_braw.to_str().unwrap()], None, home);
+    assert_eq!(code, 0, "stderr: {err}");
+    assert_eq!(out.trim(), "1st");
+
+    let (code, _out, err) = run_mdhavers(
+        &["run", "--no-prelude", prelude_braw.to_str().unwrap()],
+        None,
+        home,
+    );
+    assert_eq!(code, 1);
+    assert!(
+        err.contains("'ordinal' hasnae been defined yet"),
+        "stderr: {err}"
+    );
+
+    let (code, out, err) = run_mdhavers(
+        &["repl", "--no-prelude"],
+        Some("blether len([1, 2])\nblether ordinal(2)\nquit\n"),
+        home,
+    );
+    assert_eq!(code, 0, "stderr: {err}");
+    assert!(out.contains('2'), "stdout: {out}");
+    assert!(
+        format!("{out}{err}").contains("'ordinal' hasnae been defined yet"),
+        "stdout: {out}\nstderr: {err}"
+    );
+}
+
 #[test]
 fn cli_subcommands_cover_success_and_error_paths() {
     let dir = tempdir().unwrap();
@@ -160,6 +237,13 @@ blether x + 1
     assert_eq!(code, 0, "stderr: {err}");
     assert!(out.contains("AST:"));
 
+    // ast --json
+    let (code, out, err) = run_mdhavers(&["ast", "--json", ok_braw.to_str().unwrap()], None, home);
+    assert_eq!(code, 0, "stderr: {err}");
+    let dump: serde_json::Value = serde_json::from_str(&out).expect("ast --json is valid JSON");
+    assert_eq!(dump["statements"][0]["VarDecl"]["name"], "x");
+    assert_eq!(dump["statements"][0]["VarDecl"]["span"]["line"], 1);
+
     // trace (non-verbose)
     let (code, _out, err) = run_mdhavers(&["trace", ok_braw.to_str().unwrap()], None, home);
     assert_eq!(code, 0, "stderr: {err}");
@@ -237,6 +321,28 @@ blether x + 1
     let (code, _out, err) = run_mdhavers(&["compile", ok_braw.to_str().unwrap()], None, home);
     assert_eq!(code, 0, "stderr: {err}");
     assert!(default_js.exists());
+    assert!(!fs::read_to_string(&default_js)
+        .unwrap()
+        .contains("sourceMappingURL"));
+
+    // compile to JS with a source map alongside
+    let mapped_js = dir.path().join("mapped.js");
+    let (code, _out, err) = run_mdhavers(
+        &[
+            "compile",
+            ok_braw.to_str().unwrap(),
+            "--output",
+            mapped_js.to_str().unwrap(),
+            "--sourcemap",
+        ],
+        None,
+        home,
+    );
+    assert_eq!(code, 0, "stderr: {err}");
+    let js = fs::read_to_string(&mapped_js).unwrap();
+    assert!(js.ends_with("//# sourceMappingURL=mapped.js.map\n"));
+    let map = fs::read_to_string(dir.path().join("mapped.js.map")).unwrap();
+    assert!(map.contains("\"sources\":[\"ok.braw\"]"), "map: {map}");
 
     // wasm (default output path)
     let default_wat = dir.path().join("ok.wat");
@@ -244,6 +350,32 @@ blether x + 1
     assert_eq!(code, 0, "stderr: {err}");
     assert!(default_wat.exists());
 
+    // wasm --export-all
+    let (code, _out, err) = run_mdhavers(
+        &["wasm", ok_braw.to_str().unwrap(), "--export-all"],
+        None,
+        home,
+    );
+    assert_eq!(code, 0, "stderr: {err}");
+    let wat = fs::read_to_string(&default_wat).unwrap();
+    assert!(wat.contains("(export \"memory\" (memory 0))"), "wat: {wat}");
+
+    // wasm --binary: a real module when the assembler's built in, else WAT
+    fs::remove_file(&default_wat).unwrap();
+    let (code, _out, err) =
+        run_mdhavers(&["wasm", ok_braw.to_str().unwrap(), "--binary"], None, home);
+    assert_eq!(code, 0, "stderr: {err}");
+    let default_wasm = dir.path().join("ok.wasm");
+    if default_wasm.exists() {
+        assert!(fs::read(&default_wasm).unwrap().starts_with(b"\0asm"));
+    } else {
+        assert!(
+            err.contains("Couldnae assemble binary WASM"),
+            "stderr: {err}"
+        );
+        assert!(default_wat.exists());
+    }
+
     // trace (verbose)
     let (code, _out, err) = run_mdhavers(
         &["trace", "--verbose", ok_braw.to_str().unwrap()],
@@ -550,13 +682,76 @@ fn cli_run_and_trace_with_relative_paths_cover_empty_parent_branches() {
     assert_eq!(code, 0, "stderr: {err}");
 }
 
+#[test]
+fn cli_run_feeds_piped_stdin_tae_speir() {
+    let dir = tempdir().unwrap();
+    let home = dir.path();
+
+    write_file(
+        &dir.path().join("ask.braw"),
+        "ken a = speir \"\"\nken b = speir \"\"\nken c = speir \"\"\nblether a + b\nblether c\n",
+    );
+
+    let (code, out, err) = run_mdhavers_in_dir(
+        &["run", "ask.braw"],
+        Some("haud\nthe bus\n"),
+        home,
+        dir.path(),
+    );
+    assert_eq!(code, 0, "stderr: {err}");
+    assert_eq!(out.trim(), "haudthe bus\nnaething");
+}
+
+#[test]
+fn cli_run_sends_log_lines_at_or_above_the_level_tae_stderr() {
+    let dir = tempdir().unwrap();
+    let home = dir.path();
+
+    write_file(
+        &dir.path().join("logs.braw"),
+        "set_log_level(\"warn\")\nlog_info(\"quiet\")\nlog_error(\"loud\")\nblether \"done\"\n",
+    );
+
+    let (code, out, err) = run_mdhavers_in_dir(&["run", "logs.braw"], None, home, dir.path());
+    assert_eq!(code, 0, "stderr: {err}");
+    assert_eq!(out.trim(), "done");
+    assert!(
+        err.contains("[ROAR   ]") && err.contains("| loud"),
+        "stderr: {err}"
+    );
+    assert!(!err.contains("quiet"), "stderr: {err}");
+}
+
+#[test]
+fn cli_seed_makes_random_runs_repeatable() {
+    let dir = tempdir().unwrap();
+    let home = dir.path();
+
+    write_file(
+        &dir.path().join("deck.braw"),
+        "blether shuffle(range(0, 20))
+blether jammy(1, 1000)
+",
+    );
+
+    let run = |args: &[&str]| {
+        let (code, out, err) = run_mdhavers_in_dir(args, None, home, dir.path());
+        assert_eq!(code, 0, "stderr: {err}");
+        out
+    };
+    let first = run(&["--seed", "42", "run", "deck.braw"]);
+    let second = run(&["run", "deck.braw", "--seed", "42"]);
+    assert_eq!(first, second);
+    assert_ne!(first, run(&["--seed", "7", "deck.braw"]));
+}
+
 #[test]
 fn cli_check_and_tokens_surface_lexer_errors_and_parse_suggestions() {
     let dir = tempdir().unwrap();
     let home = dir.path();
 
     let bad_lex = dir.path().join("bad_lex.braw");
-    write_file(&bad_lex, "@\n");
+    write_file(&bad_lex, "$\n");
 
     let (code, _out, err) = run_mdhavers(&["check", bad_lex.to_str().unwrap()], None, home);
     assert_ne!(code, 0);
@@ -575,6 +770,91 @@ fn cli_check_and_tokens_surface_lexer_errors_and_parse_suggestions() {
     assert!(err.contains("Check yer brackets"), "stderr: {err}");
 }
 
+#[test]
+fn cli_check_reports_deprecated_calls() {
+    let dir = tempdir().unwrap();
+    let home = dir.path();
+
+    let file = dir.path().join("auld.braw");
+    write_file(
+        &file,
+        "@deprecated(\"use new_fn instead\")\ndae old_fn() {\n    gie 1\n}\nold_fn()\nold_fn()\n",
+    );
+
+    let (code, out, err) = run_mdhavers(&["check", file.to_str().unwrap()], None, home);
+    assert_eq!(code, 0, "stderr: {err}");
+    assert_eq!(out.matches("'old_fn' is deprecated: use new_fn instead").count(), 2);
+
+    let (code, out, err) =
+        run_mdhavers(&["check", "--json", file.to_str().unwrap()], None, home);
+    assert_eq!(code, 0, "stderr: {err}");
+    let entries: serde_json::Value = serde_json::from_str(&out).unwrap();
+    let entries = entries.as_array().unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[1]["line"], 6);
+    assert_eq!(entries[1]["severity"], "warning");
+    assert_eq!(entries[1]["end_column"], 7);
+
+    let (code, out, err) = run_mdhavers(
+        &["check", "--no-warnings", file.to_str().unwrap()],
+        None,
+        home,
+    );
+    assert_eq!(code, 0, "stderr: {err}");
+    assert!(!out.contains("deprecated"), "stdout: {out}");
+    let (code, out, _err) = run_mdhavers(
+        &["check", "--json", "--no-warnings", file.to_str().unwrap()],
+        None,
+        home,
+    );
+    assert_eq!(code, 0);
+    assert_eq!(out.trim(), "[]");
+
+    let typed = dir.path().join("typed.braw");
+    write_file(&typed, "ken x = 5\nblether x[0]\n");
+    let (code, out, err) = run_mdhavers(&["check", typed.to_str().unwrap()], None, home);
+    assert_eq!(code, 0, "stderr: {err}");
+    assert!(!out.contains("Cannae index"), "stdout: {out}");
+    let (code, out, err) = run_mdhavers(&["check", "--lint", typed.to_str().unwrap()], None, home);
+    assert_eq!(code, 0, "stderr: {err}");
+    assert!(out.contains("line 2:9: Cannae index a integer"), "stdout: {out}");
+
+    let bad = dir.path().join("bad.braw");
+    write_file(&bad, "ken =\n");
+    let (code, out, _err) = run_mdhavers(&["check", "--json", bad.to_str().unwrap()], None, home);
+    assert_ne!(code, 0);
+    assert!(out.contains("\"severity\": \"error\""), "stdout: {out}");
+    // The error points at the `=` and says where it ends
+    let entries: serde_json::Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(entries[0]["column"], 5);
+    assert_eq!(entries[0]["end_line"], 1);
+    assert_eq!(entries[0]["end_column"], 6);
+    assert_eq!(entries[0]["code"], "E0002");
+}
+
+#[test]
+fn cli_explain_describes_error_codes() {
+    let dir = tempdir().unwrap();
+    let home = dir.path();
+
+    let (code, out, err) = run_mdhavers(&["explain", "E0012"], None, home);
+    assert_eq!(code, 0, "stderr: {err}");
+    assert!(out.starts_with("E0012: Compile error\n\n"), "stdout: {out}");
+    assert!(out.contains("Common causes:"), "stdout: {out}");
+
+    let (code, _out, err) = run_mdhavers(&["explain", "E9999"], None, home);
+    assert_ne!(code, 0);
+    assert!(err.contains("nae such code as 'E9999'"), "stderr: {err}");
+
+    // Errors carry their code and point at the explanation
+    let file = dir.path().join("typo.braw");
+    write_file(&file, "blether prnt\n");
+    let (code, _out, err) = run_mdhavers(&["run", file.to_str().unwrap()], None, home);
+    assert_ne!(code, 0);
+    assert!(err.contains("[E0003] Awa' an bile yer heid!"), "stderr: {err}");
+    assert!(err.contains("mdhavers explain E0003"), "stderr: {err}");
+}
+
 #[test]
 fn cli_ast_and_fmt_parse_error_paths_are_covered() {
     let dir = tempdir().unwrap();
@@ -9,11 +9,13 @@ fn havers_error_with_line_if_zero_sets_line_on_all_supported_variants() {
             lexeme: "?".to_string(),
             line: 0,
             column: 9,
+            span: None,
         },
         HaversError::UnexpectedToken {
             expected: "ken".to_string(),
             found: "nae".to_string(),
             line: 0,
+            span: None,
         },
         HaversError::UndefinedVariable {
             name: "x".to_string(),
@@ -42,6 +44,7 @@ fn havers_error_with_line_if_zero_sets_line_on_all_supported_variants() {
         HaversError::ParseError {
             message: "nope".to_string(),
             line: 0,
+            span: None,
         },
         HaversError::BreakOutsideLoop { line: 0 },
         HaversError::ContinueOutsideLoop { line: 0 },
@@ -133,6 +136,10 @@ fn havers_error_with_line_if_zero_sets_line_on_all_supported_variants() {
             message: "nope".to_string(),
             line: 0,
         },
+        HaversError::Boak {
+            message: "nope".to_string(),
+            line: 0,
+        },
     ];
 
     for err in cases {
@@ -164,4 +171,3 @@ fn havers_error_with_line_if_zero_is_noop_for_non_line_variants() {
     assert_eq!(updated, err);
     assert_eq!(updated.line(), None);
 }
-
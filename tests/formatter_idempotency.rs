@@ -0,0 +1,153 @@
+//! Formatter idempotency tests
+//!
+//! Formatting must be a fixed point: `format(format(x)) == format(x)`.
+//! Runs a set of inline samples plus every .braw file under examples/ and
+//! stdlib/ through the formatter twice and checks nothing moves.
+//!
+//! tests/fixtures/formatter/ holds the regression corpus: each `NAME.braw`
+//! is deliberately scruffy and `NAME.expected` is what the formatter should
+//! turn it into. Set `MDH_BLESS=1` to rewrite the .expected files from the
+//! current formatter, then review the diff.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use mdhavers::format_source;
+
+/// Samples that have tripped the formatter up before
+const SAMPLES: &[&str] = &[
+    "ken total = gaun(sieve(xs, f), g)",
+    "ken r = tae_string(heid(sieve(gaun(xs, |x| x * 2), |x| x > 3)))",
+    "gin x > 1 { blether 1 } ither gin x > 0 { blether 0 } ither { blether 2 }",
+    "keek x {\n    whan 1 -> blether \"one\"\n    whan 2..5 -> gie 2\n    whan _ -> { brak }\n}",
+    r#"ken s = "quote \" backslash \\ newline \n tab \t""#,
+    r#"blether f"{{braces}} {d[\"key\"]} {name}!""#,
+    "ken f = 1.0\nken g = 2.5e10\nken h = -3",
+    "fetch \"lib/strings\" tae str",
+    "bring \"lib/strings\"\nbring \"lib/strings\" as s\nbring upper, lower frae \"lib/strings\"",
+    "ken x = {\n    ken a = 1\n    gin a > 0 { blether a }\n    a\n}",
+    "dae outer() {\n    ken inner = |y| y + 1\n    gie inner(2) |> tae_string\n}",
+    "ken t = gin x > 0 than \"pos\" ither (gin x < 0 than \"neg\" ither \"zero\")",
+    "hae_a_bash { hurl \"oops\" } gin_it_gangs_wrang e { log_roar \"bad\", e }",
+];
+
+/// Find all the .braw files under a directory
+fn discover_sources(dir: &Path) -> Vec<PathBuf> {
+    let mut sources = Vec::new();
+
+    if let Ok(entries) = fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                sources.extend(discover_sources(&path));
+            } else if path.extension().is_some_and(|e| e == "braw") {
+                sources.push(path);
+            }
+        }
+    }
+
+    sources.sort();
+    sources
+}
+
+/// Format twice and report the first line that differs, if any
+fn check_fixed_point(name: &str, source: &str) -> Result<(), String> {
+    let once = format_source(source).map_err(|e| format!("{}: first pass failed: {}", name, e))?;
+    let twice = format_source(&once)
+        .map_err(|e| format!("{}: formatted output didnae parse: {}", name, e))?;
+
+    if once == twice {
+        return Ok(());
+    }
+
+    let first = once.lines().zip(twice.lines()).position(|(a, b)| a != b);
+    let line = first.unwrap_or_else(|| once.lines().count().min(twice.lines().count()));
+    Err(format!(
+        "{}: not a fixed point at line {}\n  once:  {:?}\n  twice: {:?}",
+        name,
+        line + 1,
+        once.lines().nth(line),
+        twice.lines().nth(line)
+    ))
+}
+
+#[test]
+fn formatter_samples_are_fixed_points() {
+    let failures: Vec<String> = SAMPLES
+        .iter()
+        .enumerate()
+        .filter_map(|(i, src)| check_fixed_point(&format!("sample {}", i), src).err())
+        .collect();
+
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn formatter_corpus_is_a_fixed_point() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let mut sources = discover_sources(&root.join("examples"));
+    sources.extend(discover_sources(&root.join("stdlib")));
+    assert!(!sources.is_empty(), "No .braw files found");
+
+    let mut failures = Vec::new();
+    for path in &sources {
+        let source = fs::read_to_string(path).unwrap();
+        // Files the parser rejects outright are not the formatter's problem
+        if mdhavers::parse(&source).is_err() {
+            continue;
+        }
+        if let Err(e) = check_fixed_point(&path.display().to_string(), &source) {
+            failures.push(e);
+        }
+    }
+
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn formatter_fixtures_match_expected_and_are_fixed_points() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/formatter");
+    let sources = discover_sources(&dir);
+    assert!(!sources.is_empty(), "No formatter fixtures found");
+    let bless = std::env::var_os("MDH_BLESS").is_some();
+
+    let mut failures = Vec::new();
+    for path in &sources {
+        let name = path.display().to_string();
+        let source = fs::read_to_string(path).unwrap();
+        let formatted = match format_source(&source) {
+            Ok(formatted) => formatted,
+            Err(e) => {
+                failures.push(format!("{}: didnae parse: {}", name, e));
+                continue;
+            }
+        };
+
+        let expected_path = path.with_extension("expected");
+        if bless {
+            fs::write(&expected_path, &formatted).unwrap();
+        } else {
+            match fs::read_to_string(&expected_path) {
+                Ok(expected) if expected == formatted => {}
+                Ok(expected) => failures.push(format!(
+                    "{}: formatted output doesnae match {}\n--- expected ---\n{}--- actual ---\n{}",
+                    name,
+                    expected_path.display(),
+                    expected,
+                    formatted
+                )),
+                Err(_) => failures.push(format!(
+                    "{}: missing {} (run wi' MDH_BLESS=1 tae create it)",
+                    name,
+                    expected_path.display()
+                )),
+            }
+        }
+
+        if let Err(e) = check_fixed_point(&name, &source) {
+            failures.push(e);
+        }
+    }
+
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
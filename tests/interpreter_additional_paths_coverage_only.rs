@@ -90,8 +90,8 @@ blether f()
         r#"blether json_parse("null")"#,
         // JSON stringify branches
         r#"blether json_stringify(nae)"#,
-        r#"blether json_stringify(sqrt(-1))"#,
-        r#"blether json_stringify_pretty(sqrt(-1))"#,
+        r#"blether json_stringify(tae_float("NaN"))"#,
+        r#"blether json_stringify_pretty(tae_float("NaN"))"#,
         r#"blether json_stringify_pretty([])"#,
         r#"blether json_stringify_pretty({})"#,
         r#"blether json_stringify(chr(1))"#,
@@ -0,0 +1,112 @@
+use std::fs;
+
+use mdhavers::{parse, HaversError, Interpreter};
+
+fn write_mathlib(dir: &std::path::Path) {
+    fs::write(
+        dir.join("mathlib.braw"),
+        r#"
+blether "loadin' mathlib"
+ken scale = 10
+dae add(a, b) { gie a + b }
+dae sub(a, b) { gie a - b }
+"#,
+    )
+    .unwrap();
+}
+
+fn run_in(dir: &std::path::Path, code: &str) -> Result<String, HaversError> {
+    let program = parse(code).unwrap();
+    let mut interp = Interpreter::new();
+    interp.set_current_dir(dir);
+    interp.interpret(&program)?;
+    Ok(interp.get_output().join("\n"))
+}
+
+#[test]
+fn bring_puts_a_module_under_its_ain_name() {
+    let dir = tempfile::tempdir().unwrap();
+    write_mathlib(dir.path());
+
+    let out = run_in(
+        dir.path(),
+        r#"
+bring "mathlib"
+blether mathlib.add(1, 2)
+blether mathlib.scale
+"#,
+    )
+    .unwrap();
+    assert_eq!(out.trim(), "loadin' mathlib\n3\n10");
+
+    // Nothing leaks out of the namespace
+    let err = run_in(dir.path(), "bring \"mathlib\"\nblether add(1, 2)").unwrap_err();
+    assert!(
+        matches!(err, HaversError::UndefinedVariable { .. }),
+        "{err:?}"
+    );
+}
+
+#[test]
+fn bring_as_names_the_namespace_an_only_runs_the_module_once() {
+    let dir = tempfile::tempdir().unwrap();
+    write_mathlib(dir.path());
+
+    let out = run_in(
+        dir.path(),
+        r#"
+bring "mathlib" as m
+bring "mathlib"
+blether m.sub(5, 2)
+blether mathlib.add(m.scale, 1)
+"#,
+    )
+    .unwrap();
+    assert_eq!(out.trim(), "loadin' mathlib\n3\n11");
+}
+
+#[test]
+fn bring_frae_picks_oot_just_the_names_asked_fer() {
+    let dir = tempfile::tempdir().unwrap();
+    write_mathlib(dir.path());
+
+    let out = run_in(
+        dir.path(),
+        r#"
+bring add, scale frae "mathlib"
+blether add(scale, 2)
+"#,
+    )
+    .unwrap();
+    assert_eq!(out.trim(), "loadin' mathlib\n12");
+
+    // `sub` is near enough `sum` to get a "did ye mean", but it's still undefined
+    let err = run_in(dir.path(), "bring add frae \"mathlib\"\nblether sub(1, 2)").unwrap_err();
+    assert!(
+        matches!(err.root(), HaversError::UndefinedVariable { .. }),
+        "{err:?}"
+    );
+
+    let err = run_in(dir.path(), "bring times frae \"mathlib\"").unwrap_err();
+    assert!(err.to_string().contains("nae 'times'"), "{err}");
+}
+
+#[test]
+fn bring_reports_a_cycle_insteid_o_looping() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("hen.braw"), "bring \"egg\"\nken hen = 1\n").unwrap();
+    fs::write(
+        dir.path().join("egg.braw"),
+        "bring hen frae \"hen\"\nken egg = 2\n",
+    )
+    .unwrap();
+
+    let err = run_in(dir.path(), "bring \"hen\"").unwrap_err();
+    let HaversError::CircularImport { path } = err else {
+        panic!("expected CircularImport, got: {err:?}");
+    };
+    assert!(
+        path.contains("hen.braw") && path.contains("egg.braw"),
+        "{path}"
+    );
+}
@@ -0,0 +1,209 @@
+#![cfg(feature = "native")]
+
+use mdhavers::{parse, Interpreter};
+
+fn run(code: &str) -> Vec<String> {
+    let program = parse(code).unwrap();
+    let mut interp = Interpreter::new();
+    interp.interpret(&program).unwrap();
+    interp.get_output().to_vec()
+}
+
+#[test]
+fn interpreter_digests_match_known_vectors() {
+    let out = run(r#"
+blether sha256_hex("")
+blether sha256_hex("abc")
+blether sha1_hex("")
+blether sha1_hex("abc")
+blether md5_hex("")
+blether md5_hex("abc")
+"#);
+    assert_eq!(
+        out,
+        vec![
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            "da39a3ee5e6b4b0d3255bfef95601890afd80709",
+            "a9993e364706816aba3e25717850c26c9cd0d89d",
+            "d41d8cd98f00b204e9800998ecf8427e",
+            "900150983cd24fb0d6963f7d28e17f72",
+        ]
+    );
+}
+
+#[test]
+fn interpreter_digests_return_bytes_and_accept_bytes() {
+    let out = run(r#"
+blether bytes_len(sha256("abc"))
+blether bytes_len(sha1("abc"))
+blether bytes_len(md5("abc"))
+blether sha256_hex(bytes_from_string("abc"))
+"#);
+    assert_eq!(
+        out,
+        vec![
+            "32",
+            "20",
+            "16",
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+        ]
+    );
+}
+
+#[test]
+fn interpreter_digests_reject_non_bytes_or_string() {
+    let program = parse("sha256(42)").unwrap();
+    let mut interp = Interpreter::new();
+    let err = interp
+        .interpret(&program)
+        .expect_err("expected sha256() type error");
+    let s = format!("{err:?}");
+    assert!(
+        s.contains("sha256() expects bytes or a string"),
+        "unexpected error: {s}"
+    );
+}
+
+#[test]
+fn interpreter_hmac_sha256_matches_rfc4231_vector() {
+    let out = run(r#"
+blether hmac_sha256_hex("Jefe", "what do ya want for nothing?")
+ken mac = hmac_sha256(bytes_from_string("Jefe"), bytes_from_string("what do ya want for nothing?"))
+blether bytes_len(mac)
+"#);
+    assert_eq!(
+        out,
+        vec![
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+            "32",
+        ]
+    );
+}
+
+#[test]
+fn interpreter_hmac_sha256_matches_more_rfc4231_vectors() {
+    // Test cases 1 and 3: binary keys, and a binary message
+    let out = run(r#"
+blether hmac_sha256_hex(hex_decode("0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b"), "Hi There")
+ken key = hex_decode("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa")
+ken message = hex_decode("dddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddd")
+blether hex_encode(hmac_sha256(key, message))
+"#);
+    assert_eq!(
+        out,
+        vec![
+            "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7",
+            "773ea91e36800e46854db8ebd09181a72959098b3ef8c122d9635514ced565fe",
+        ]
+    );
+}
+
+#[test]
+fn interpreter_pbkdf2_matches_known_vectors() {
+    let out = run(r#"
+blether hex_encode(pbkdf2("password", "salt", 1, 32))
+blether hex_encode(pbkdf2(bytes_from_string("password"), "salt", 2, 32))
+blether hex_encode(pbkdf2("password", "salt", 4096, 32))
+blether bytes_len(pbkdf2("pw", random_bytes(16), 10, 30))
+"#);
+    assert_eq!(
+        out,
+        vec![
+            "120fb6cffcf8b32c43e7225256c4f837a86548c92ccc35480805987cb70be17b",
+            "ae4d0c95af6b46d32d0adff928f06dd02a303f8ef3c251dfd6e2d85a95474c43",
+            "c5e478d59288c841aa530db6845c4c8d962893a001ce4e11a4963873aa98134a",
+            "30",
+        ]
+    );
+
+    for (code, expected) in [
+        (
+            "pbkdf2(\"pw\", \"salt\", 0, 32)",
+            "pbkdf2() needs at least 1 iteration",
+        ),
+        (
+            "pbkdf2(\"pw\", \"salt\", 1, 0)",
+            "pbkdf2() key length must be atween 1 an' 1024 bytes",
+        ),
+        (
+            "pbkdf2(42, \"salt\", 1, 32)",
+            "pbkdf2() expects password as bytes or a string",
+        ),
+    ] {
+        let program = parse(code).unwrap();
+        let mut interp = Interpreter::new();
+        let err = interp
+            .interpret(&program)
+            .expect_err("expected a pbkdf2 error");
+        let s = format!("{err:?}");
+        assert!(s.contains(expected), "unexpected error for {code}: {s}");
+    }
+}
+
+#[test]
+fn interpreter_ct_eq_compares_buffers() {
+    let out = run(r#"
+ken a = hmac_sha256("key", "message")
+ken b = hmac_sha256("key", "message")
+ken c = hmac_sha256("key", "messagf")
+blether ct_eq(a, b)
+blether ct_eq(a, c)
+blether ct_eq(a, bytes_slice(a, 0, 16))
+blether ct_eq("abc", "abc")
+"#);
+    assert_eq!(out, vec!["aye", "nae", "nae", "aye"]);
+}
+
+#[test]
+fn interpreter_hmac_and_ct_eq_reject_bad_args() {
+    for (code, expected) in [
+        (
+            "hmac_sha256(1, \"msg\")",
+            "hmac_sha256() expects key as bytes or a string",
+        ),
+        (
+            "hmac_sha256_hex(\"key\", naething)",
+            "hmac_sha256_hex() expects message as bytes or a string",
+        ),
+        ("ct_eq([1], \"abc\")", "ct_eq() expects bytes or strings"),
+    ] {
+        let program = parse(code).unwrap();
+        let mut interp = Interpreter::new();
+        let err = interp.interpret(&program).expect_err("expected type error");
+        let s = format!("{err:?}");
+        assert!(s.contains(expected), "unexpected error for {code}: {s}");
+    }
+}
+
+#[test]
+fn interpreter_random_bytes_are_fresh_and_the_right_length() {
+    let out = run(r#"
+ken a = random_bytes(16)
+ken b = random_bytes(16)
+blether bytes_len(a)
+blether ct_eq(a, b)
+ken h = random_hex(30)
+blether len(h)
+blether h == random_hex(30)
+blether bytes_len(random_bytes(0))
+"#);
+    assert_eq!(out, vec!["16", "nae", "60", "nae", "0"]);
+
+    for (code, expected) in [
+        ("random_bytes(-1)", "random_bytes() count must be atween 0"),
+        ("random_hex(1048577)", "random_hex() count must be atween 0"),
+        (
+            "random_bytes(\"16\")",
+            "random_bytes() expects an integer count",
+        ),
+    ] {
+        let program = parse(code).unwrap();
+        let mut interp = Interpreter::new();
+        let err = interp
+            .interpret(&program)
+            .expect_err("expected a count error");
+        let s = format!("{err:?}");
+        assert!(s.contains(expected), "unexpected error for {code}: {s}");
+    }
+}
@@ -409,3 +409,129 @@ blether result
     let out = interp.get_output().join("\n");
     assert!(out.contains("Invalid key PEM"), "unexpected output: {out}");
 }
+
+#[test]
+fn interpreter_dtls_handshake_times_out_against_unroutable_peer() {
+    let program = parse(
+        r#"
+ken result = "nope"
+ken s = socket_udp()
+
+gin s["ok"] {
+    ken sock = s["value"]
+    ken d = dtls_server_new({"mode": "client", "insecure": aye, "remote_host": "10.255.255.1", "remote_port": 5061, "timeout_ms": 300})
+    ken hs = dtls_handshake(d["value"], sock)
+    gin nae hs["ok"] {
+        result = hs["error"]
+    }
+    socket_close(sock)
+}
+
+blether result
+"#,
+    )
+    .unwrap();
+    let mut interp = Interpreter::new();
+    let started = std::time::Instant::now();
+    interp.interpret(&program).unwrap();
+    let elapsed = started.elapsed();
+    let out = interp.get_output().join("\n");
+    assert!(out.contains("Timed oot"), "unexpected output: {out}");
+    assert!(
+        elapsed >= Duration::from_millis(250) && elapsed < Duration::from_secs(5),
+        "unexpected elapsed time: {elapsed:?}"
+    );
+}
+
+#[test]
+fn interpreter_dtls_rejects_non_positive_timeout() {
+    let program = parse(r#"dtls_server_new({"timeout_ms": 0})"#).unwrap();
+    let mut interp = Interpreter::new();
+    let err = interp
+        .interpret(&program)
+        .expect_err("expected timeout_ms error");
+    let s = format!("{err:?}");
+    assert!(
+        s.contains("timeout_ms must be a positive number"),
+        "unexpected error: {s}"
+    );
+}
+
+#[test]
+fn interpreter_dtls_handshake_reports_cipher_an_peer_fingerprint() {
+    let (cert_pem, key_pem) = generate_cert();
+    let expected = openssl::x509::X509::from_pem(cert_pem.as_bytes())
+        .unwrap()
+        .digest(openssl::hash::MessageDigest::sha256())
+        .unwrap()
+        .iter()
+        .map(|b| format!("{b:02X}"))
+        .collect::<Vec<_>>()
+        .join(":");
+    let cert_escaped = escape_for_braw(&cert_pem);
+    let key_escaped = escape_for_braw(&key_pem);
+
+    let server_port = allocate_port();
+    let client_port = allocate_port();
+
+    let run_side = move |mode: &str, port: u16, peer_port: u16| {
+        let code = format!(
+            r#"
+ken s = socket_udp()
+ken sock = s["value"]
+socket_set_reuseaddr(sock, aye)
+socket_bind(sock, "127.0.0.1", {port})
+ken d = dtls_server_new({{
+    "mode": "{mode}",
+    "server_name": "localhost",
+    "insecure": aye,
+    "cert_pem": "{cert_escaped}",
+    "key_pem": "{key_escaped}",
+    "remote_host": "127.0.0.1",
+    "remote_port": {peer_port}
+}})
+ken hs = dtls_handshake(d["value"], sock)
+socket_close(sock)
+blether hs["ok"]
+blether len(hs["value"]["cipher"]) > 0
+gin has_key(hs["value"], "fingerprint") {{
+    blether hs["value"]["fingerprint"]
+}} ither {{
+    blether "nae fingerprint"
+}}
+"#
+        );
+        let program = parse(&code).unwrap();
+        let mut interp = Interpreter::new();
+        interp.interpret(&program).unwrap();
+        interp.get_output().join("\n")
+    };
+
+    let (server_tx, server_rx) = mpsc::channel();
+    let server_side = run_side.clone();
+    let server_thread = thread::spawn(move || {
+        server_tx
+            .send(server_side("server", server_port, client_port))
+            .unwrap();
+    });
+    thread::sleep(Duration::from_millis(50));
+    let (client_tx, client_rx) = mpsc::channel();
+    let client_thread = thread::spawn(move || {
+        client_tx
+            .send(run_side("client", client_port, server_port))
+            .unwrap();
+    });
+
+    let server_out = server_rx
+        .recv_timeout(Duration::from_secs(10))
+        .expect("server timed out");
+    let client_out = client_rx
+        .recv_timeout(Duration::from_secs(10))
+        .expect("client timed out");
+    server_thread.join().unwrap();
+    client_thread.join().unwrap();
+
+    // The client sees the server's cert; the server never asked for the client's
+    assert_eq!(client_out.trim(), format!("aye\naye\n{expected}"));
+    assert_eq!(server_out.trim(), "aye\naye\nnae fingerprint");
+}
@@ -169,6 +169,74 @@ fn interpreter_clear_output_resets_buffer() {
     assert!(interp.get_output().is_empty());
 }
 
+#[test]
+fn interpreter_take_output_drains_between_runs() {
+    let mut interp = Interpreter::new();
+    interp
+        .interpret(&parse("blether \"first\"\nblether 1").unwrap())
+        .unwrap();
+    assert_eq!(interp.take_output(), ["first", "1"]);
+    assert!(interp.get_output().is_empty());
+
+    interp
+        .interpret(&parse("blether \"second\"").unwrap())
+        .unwrap();
+    let second = interp.take_output();
+    assert_eq!(second, ["second"]);
+    assert!(!second.iter().any(|line| line == "first" || line == "1"));
+}
+
+#[test]
+fn interpreter_output_sink_sees_lines_in_order_even_on_error() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let mut interp = Interpreter::new();
+    let sink = Rc::clone(&seen);
+    interp.set_output_sink(Box::new(move |line| sink.borrow_mut().push(line.to_string())));
+
+    let program = parse("fer i in 1..4 {\n    blether i * 10\n}\nblether \"done\"").unwrap();
+    interp.interpret(&program).unwrap();
+    assert_eq!(*seen.borrow(), ["10", "20", "30", "done"]);
+    assert_eq!(interp.get_output(), seen.borrow().as_slice());
+
+    // Lines before a runtime error have already reached the sink
+    seen.borrow_mut().clear();
+    interp.clear_output();
+    interp.set_keep_output(false);
+    let program = parse("blether \"afore\"\nken x = 1 / 0\nblether \"efter\"").unwrap();
+    assert!(interp.interpret(&program).is_err());
+    assert_eq!(*seen.borrow(), ["afore"]);
+    assert!(interp.get_output().is_empty());
+
+    interp.clear_output_sink();
+    interp.set_keep_output(true);
+    interp.interpret(&parse("blether 1").unwrap()).unwrap();
+    assert_eq!(seen.borrow().len(), 1);
+    assert_eq!(interp.take_output(), ["1"]);
+}
+
+#[test]
+fn interpreter_input_source_feeds_speir_in_order() {
+    let mut interp = Interpreter::new();
+    interp.set_input_source(Box::new(std::io::Cursor::new("Morag\n  42  \n")));
+
+    let program = parse(
+        r#"
+ken name = speir "Name? "
+ken age = speir "Age? "
+ken mair = speir "Onything else? "
+blether name
+blether tae_int(age) + 1
+blether mair
+"#,
+    )
+    .unwrap();
+    interp.interpret(&program).unwrap();
+    assert_eq!(interp.get_output(), ["Morag", "43", "naething"]);
+}
+
 #[test]
 fn interpreter_builtin_edges_and_errors_smoke() {
     // Table-driven sweep intended to exercise a wide variety of native builtin branches,
@@ -336,7 +404,7 @@ chan_send(ch, 1)
         ("is_blank(1)", false),
         // lists
         ("sort([3, 1, 2])", true),
-        ("sort([1, \"a\", 2])", true),
+        ("sort([1, \"a\", 2])", false),
         ("slap([1, 2], [3])", true),
         ("tail([])", true),
         ("shove([1, 2], 3)", true),
@@ -353,11 +421,11 @@ chan_send(ch, 1)
         ("median([1, \"2\"])", false),
         ("minaw([3, 1, 2])", true),
         ("minaw([])", false),
-        ("minaw([1, 2.0])", false),
+        ("minaw([1, \"a\"])", false),
         ("minaw([3.0, 1.0, 2.0])", true),
         ("maxaw([3, 1, 2])", true),
         ("maxaw([])", false),
-        ("maxaw([1, 2.0])", false),
+        ("maxaw([1, \"a\"])", false),
         ("maxaw([3.0, 1.0, 2.0])", true),
         ("range_o([1, 2, 3])", true),
         ("range_o([])", false),
@@ -0,0 +1,79 @@
+use mdhavers::{parse, Interpreter};
+
+fn run(source: &str) -> Result<(), mdhavers::HaversError> {
+    let program = parse(source)?;
+    let mut interp = Interpreter::new();
+    interp.interpret(&program).map(|_| ())
+}
+
+#[test]
+fn raising_the_log_level_hides_lower_messages_in_the_file_sink() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("app.log");
+    let path_str = path.to_string_lossy().replace('\\', "/");
+
+    let source = format!(
+        r#"
+log_to_file("{path_str}")
+set_log_level("warn")
+log_debug("debug hidden")
+log_info("info hidden")
+log_warn("warn shown")
+log_error("error shown", {{"code": 7}})
+set_log_level("error")
+log_warn("warn hidden")
+log_error("still shown")
+set_log_level("debug")
+log_debug("debug shown")
+"#
+    );
+    run(&source).unwrap();
+
+    let text = std::fs::read_to_string(&path).unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines.len(), 4, "{text}");
+    assert!(lines[0].starts_with("[HOLLER ]") && lines[0].ends_with("| warn shown"));
+    assert!(lines[1].contains("[ROAR   ]") && lines[1].contains("error shown code=7"));
+    assert!(lines[2].ends_with("| still shown"));
+    assert!(lines[3].contains("[MUTTER ]") && lines[3].ends_with("| debug shown"));
+    assert!(!text.contains("hidden"));
+    // Every line carries a timestamp
+    for line in &lines {
+        let stamp = &line.as_bytes()[10..33];
+        assert!(stamp[4] == b'-' && stamp[13] == b':', "{line}");
+    }
+
+    // A second run appends rather than starting the file over
+    run(&format!(
+        "log_to_file(\"{path_str}\")\nset_log_level(\"info\")\nlog_info(\"again\")\nlog_debug(\"nope\")"
+    ))
+    .unwrap();
+    let text = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(text.lines().count(), 5);
+    assert!(text.lines().last().unwrap().ends_with("| again"));
+
+    let err = run("log_to_file(\"/nae/such/dir/app.log\")").unwrap_err();
+    assert!(
+        err.to_string().contains("log_to_file() cannae open"),
+        "{err}"
+    );
+}
+
+#[test]
+fn log_levels_share_the_native_runtime_numbering() {
+    let program = parse(
+        r#"
+set_log_level(2)
+blether get_log_level()
+blether log_enabled("info")
+blether log_enabled(1)
+log_info("no' in the blether buffer")
+set_log_level("debug")
+blether get_log_level()
+"#,
+    )
+    .unwrap();
+    let mut interp = Interpreter::new();
+    interp.interpret(&program).unwrap();
+    assert_eq!(interp.get_output(), ["holler", "nae", "aye", "mutter"]);
+}
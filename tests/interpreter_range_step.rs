@@ -0,0 +1,62 @@
+use mdhavers::{parse, HaversError, Interpreter};
+
+fn run_output(code: &str) -> String {
+    let program = parse(code).unwrap();
+    let mut interp = Interpreter::new();
+    interp.interpret(&program).unwrap();
+    interp.get_output().join("\n")
+}
+
+#[test]
+fn interpreter_descending_range_counts_down() {
+    let code = r#"
+blether 5..1
+blether 3..=0
+ken total = 0
+fer i in 10..7 {
+    total = total + i
+}
+blether total
+"#;
+    assert_eq!(run_output(code).trim(), "[5, 4, 3, 2]\n[3, 2, 1, 0]\n27");
+}
+
+#[test]
+fn interpreter_stepped_range_counts_up() {
+    let code = r#"
+blether 0..10 by 3
+blether 0..=9 by 3
+blether range(1, 8, 2)
+"#;
+    assert_eq!(
+        run_output(code).trim(),
+        "[0, 3, 6, 9]\n[0, 3, 6, 9]\n[1, 3, 5, 7]"
+    );
+}
+
+#[test]
+fn interpreter_stepped_range_counts_down() {
+    let code = r#"
+blether 10..0 by 2
+blether 10..=0 by -5
+fer i in 9..0 by 4 {
+    blether i
+}
+blether range(5, 0, 2)
+"#;
+    assert_eq!(
+        run_output(code).trim(),
+        "[10, 8, 6, 4, 2]\n[10, 5, 0]\n9\n5\n1\n[5, 3, 1]"
+    );
+}
+
+#[test]
+fn interpreter_zero_step_range_is_an_error() {
+    let program = parse("ken r = 1..10 by 0").unwrap();
+    let err = Interpreter::new().interpret(&program).unwrap_err();
+    assert!(matches!(err, HaversError::InvalidOperation { line: 1, .. }));
+
+    let program = parse("ken r = range(1, 10, 0)").unwrap();
+    let err = Interpreter::new().interpret(&program).unwrap_err();
+    assert!(err.to_string().contains("step cannae be zero"));
+}
@@ -0,0 +1,77 @@
+use std::fs;
+
+use mdhavers::{parse, HaversError, Interpreter, Value};
+
+fn run_in(dir: &std::path::Path, code: &str) -> Result<Value, HaversError> {
+    let program = parse(code).unwrap();
+    let mut interp = Interpreter::new();
+    interp.set_current_dir(dir);
+    interp.interpret(&program)
+}
+
+#[test]
+fn interpreter_read_dir_and_walk_dir_list_a_tree() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("b.txt"), "bee").unwrap();
+    fs::write(dir.path().join("a.txt"), "aye").unwrap();
+    fs::create_dir(dir.path().join("sub")).unwrap();
+    fs::write(dir.path().join("sub").join("c.braw"), "blether 1").unwrap();
+
+    // Relative paths follow set_current_dir, not the process cwd
+    let out = run_in(
+        dir.path(),
+        r#"[read_dir("."), walk_dir("."), read_dir("sub")]"#,
+    )
+    .unwrap();
+    assert_eq!(
+        out.to_string(),
+        "[[a.txt, b.txt, sub], [a.txt, b.txt, sub/c.braw], [c.braw]]"
+    );
+
+    let out = run_in(
+        dir.path(),
+        r#"[is_dir("sub"), is_file("sub"), is_file("a.txt"), is_dir("a.txt"), is_file("nae")]"#,
+    )
+    .unwrap();
+    assert_eq!(out.to_string(), "[aye, nae, aye, nae, nae]");
+
+    let abs = dir.path().join("sub").to_string_lossy().to_string();
+    let out = run_in(
+        std::path::Path::new("/"),
+        &format!("walk_dir(\"{}\")", abs.replace('\\', "\\\\")),
+    )
+    .unwrap();
+    assert_eq!(out.to_string(), "[c.braw]");
+}
+
+#[test]
+fn interpreter_read_dir_errors_on_non_directories() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("a.txt"), "aye").unwrap();
+
+    for code in [
+        r#"read_dir("a.txt")"#,
+        r#"walk_dir("a.txt")"#,
+        r#"read_dir("missing")"#,
+        "read_dir(42)",
+    ] {
+        let err = run_in(dir.path(), code).unwrap_err();
+        assert!(
+            err.to_string().contains("read_dir") || err.to_string().contains("walk_dir"),
+            "{code}: {err}"
+        );
+    }
+
+    let out = run_in(
+        dir.path(),
+        r#"
+hae_a_bash {
+    read_dir("a.txt")
+} gin_it_gangs_wrang e {
+    e
+}
+"#,
+    )
+    .unwrap();
+    assert!(out.to_string().contains("no' a directory"), "{out}");
+}
@@ -239,3 +239,70 @@ fn interpreter_srtp_unprotect_rejects_non_bytes_packet_for_coverage() {
         "unexpected error: {s}"
     );
 }
+
+#[test]
+fn interpreter_srtp_roc_an_window_size_fae_the_config() {
+    let code = r#"
+dae make_bytes_seq(n, start) {
+    ken b = bytes(n)
+    ken i = 0
+    whiles i < n {
+        bytes_set(b, i, start + i)
+        i = i + 1
+    }
+    gie b
+}
+
+dae make_rtp_packet(seq) {
+    ken b = bytes(16)
+    bytes_set(b, 0, 128)
+    bytes_set(b, 2, seq / 256)
+    bytes_set(b, 3, seq % 256)
+    bytes_set(b, 7, 1)
+    bytes_set(b, 11, 1)
+    gie b
+}
+
+ken key = make_bytes_seq(16, 1)
+ken salt = make_bytes_seq(14, 50)
+dae cfg_wi(extra) {
+    ken cfg = {"master_key": key, "master_salt": salt}
+    fer k in keys(extra) {
+        cfg[k] = extra[k]
+    }
+    gie cfg
+}
+
+# The sender's sequence numbers wrap, so its second packet goes out at ROC 1
+ken sender = srtp_create(cfg_wi({"ssrc": 1, "window_size": 128}))
+srtp_protect(sender["value"], make_rtp_packet(65535))
+ken pkt = make_rtp_packet(1)
+ken prot = srtp_protect(sender["value"], pkt)
+
+# A receiver joining at that ROC reads it fine
+ken matched = srtp_create(cfg_wi({"ssrc": 1, "roc": 1}))
+ken unp = srtp_unprotect(matched["value"], prot["value"])
+blether unp["ok"] an unp["value"] == pkt
+
+# One left at ROC 0 cannot, until it's moved on
+ken behind = srtp_create(cfg_wi({"ssrc": 1}))
+blether srtp_unprotect(behind["value"], prot["value"])["ok"]
+blether srtp_set_roc(behind["value"], 1, 1)["ok"]
+blether srtp_unprotect(behind["value"], prot["value"])["ok"]
+
+blether srtp_create(cfg_wi({"roc": 5}))["error"]
+blether srtp_create(cfg_wi({"ssrc": -1}))["error"]
+blether srtp_create(cfg_wi({"window_size": 8}))["error"]
+blether srtp_set_roc(behind["value"], 1, 4294967296)["error"]
+"#;
+
+    let program = parse(code).unwrap();
+    let mut interp = Interpreter::new();
+    interp.interpret(&program).unwrap();
+    let out = interp.get_output();
+    assert_eq!(out[..4], ["aye", "nae", "aye", "aye"], "{out:?}");
+    assert!(out[4].contains("roc needs an ssrc"), "{out:?}");
+    assert!(out[5].contains("ssrc must be atween"), "{out:?}");
+    assert!(out[6].contains("window_size must be atween 64"), "{out:?}");
+    assert!(out[7].contains("roc must be atween"), "{out:?}");
+}
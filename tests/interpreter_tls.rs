@@ -367,3 +367,52 @@ tls_client_new(cfg)
     let s = format!("{err:?}");
     assert!(s.contains("Invalid server key"), "unexpected error: {s}");
 }
+
+#[test]
+fn interpreter_tls_connect_times_out_when_server_never_answers() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    // Accept the connection but never speak, so the client handshake stalls.
+    let server_thread = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        thread::sleep(Duration::from_secs(2));
+        drop(stream);
+    });
+
+    let code = format!(
+        r#"
+ken s = socket_tcp()
+ken result = "nope"
+
+gin s["ok"] {{
+    ken sock = s["value"]
+    ken c = socket_connect(sock, "127.0.0.1", {port})
+    gin c["ok"] {{
+        ken t = tls_client_new({{"mode": "client", "server_name": "localhost", "insecure": aye, "timeout_ms": 300}})
+        ken h = tls_connect(t["value"], sock)
+        gin nae h["ok"] {{
+            result = h["error"]
+        }}
+        tls_close(t["value"])
+    }}
+    socket_close(sock)
+}}
+
+blether result
+"#
+    );
+
+    let program = parse(&code).unwrap();
+    let mut interp = Interpreter::new();
+    let started = std::time::Instant::now();
+    interp.interpret(&program).unwrap();
+    let elapsed = started.elapsed();
+    let out = interp.get_output().join("\n");
+    assert!(out.contains("Timed oot"), "unexpected output: {out}");
+    assert!(
+        elapsed >= Duration::from_millis(250) && elapsed < Duration::from_secs(2),
+        "unexpected elapsed time: {elapsed:?}"
+    );
+    server_thread.join().unwrap();
+}
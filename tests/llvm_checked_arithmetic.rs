@@ -0,0 +1,70 @@
+//! Checked vs unchecked integer arithmetic in native builds.
+
+#![cfg(feature = "llvm")]
+
+use std::process::{Command, Output};
+
+use mdhavers::{parse, LLVMCompiler};
+use tempfile::tempdir;
+
+const FACTORIAL: &str = r#"
+dae fact(n) {
+    gin n <= 1 {
+        gie 1
+    }
+    gie n * fact(n - 1)
+}
+blether fact(20)
+blether fact(21)
+"#;
+
+fn build_and_run(source: &str, checked: bool) -> Output {
+    let program = parse(source).expect("parse");
+    let dir = tempdir().expect("tempdir");
+    let exe_path = dir.path().join("test_exe");
+
+    LLVMCompiler::new()
+        .with_checked_arithmetic(checked)
+        .compile_to_native(&program, &exe_path, 2)
+        .expect("compile");
+
+    Command::new(&exe_path).output().expect("run")
+}
+
+#[test]
+fn llvm_unchecked_factorial_wraps_on_overflow() {
+    let out = build_and_run(FACTORIAL, false);
+    assert!(out.status.success());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    let lines: Vec<&str> = stdout.trim().lines().collect();
+    assert_eq!(lines, vec!["2432902008176640000", "-4249290049419214848"]);
+}
+
+#[test]
+fn llvm_checked_factorial_traps_on_overflow() {
+    let out = build_and_run(FACTORIAL, true);
+    assert_eq!(out.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    let lines: Vec<&str> = stdout.trim().lines().collect();
+    assert_eq!(lines[0], "2432902008176640000");
+    assert!(
+        lines[1].contains("Och! Yer number's too muckle"),
+        "unexpected output: {stdout}"
+    );
+}
+
+#[test]
+fn llvm_checked_ir_uses_overflow_intrinsics() {
+    let program =
+        parse("ken a = 5\nken b = 7\nblether a + b\nblether a - b\nblether a * b").unwrap();
+    let ir = LLVMCompiler::new()
+        .with_checked_arithmetic(true)
+        .compile_to_ir(&program)
+        .unwrap();
+    assert!(ir.contains("llvm.sadd.with.overflow.i64"));
+    assert!(ir.contains("llvm.ssub.with.overflow.i64"));
+    assert!(ir.contains("llvm.smul.with.overflow.i64"));
+
+    let ir = LLVMCompiler::new().compile_to_ir(&program).unwrap();
+    assert!(!ir.contains("with.overflow"));
+}
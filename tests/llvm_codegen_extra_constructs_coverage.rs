@@ -582,10 +582,12 @@ fn llvm_codegen_inclusive_range_for_loop_ast_path_for_coverage() {
             span,
         }),
         inclusive: true,
+        step: None,
         span,
     };
     let program = Program::new(vec![Stmt::For {
         variable: "i".to_string(),
+        value_variable: None,
         iterable: range,
         body: Box::new(Stmt::Block {
             statements: vec![Stmt::Print {
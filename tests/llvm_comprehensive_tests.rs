@@ -84,18 +84,40 @@ mod arithmetic {
         assert_eq!(run("blether 10 % 3").trim(), "1");
     }
 
+    #[test]
+    fn test_float_modulo() {
+        assert_eq!(run("ken a = 7.5\nblether a % 2").trim(), "1.5");
+        assert_eq!(run("ken a = -7.5\nblether a % 2.0").trim(), "-1.5");
+    }
+
+    #[test]
+    fn test_power_operator() {
+        assert_eq!(run("ken b = 2\nblether b ** 10").trim(), "1024");
+        assert_eq!(run("ken b = 2\nblether b ** -1").trim(), "0.5");
+        assert_eq!(run("ken b = 9.0\nblether b ** 0.5").trim(), "3.0");
+    }
+
+    #[test]
+    fn test_bitwise_operators() {
+        assert_eq!(run("blether 6 & 3 == 2").trim(), "aye");
+        assert_eq!(run("blether 1 << 4 == 16").trim(), "aye");
+        assert_eq!(run("ken a = 6\nken b = 3\nblether a | b").trim(), "7");
+        assert_eq!(run("ken a = 6\nken b = 3\nblether a ^ b").trim(), "5");
+        assert_eq!(run("ken a = [64]\nblether a[0] >> 3").trim(), "8");
+    }
+
     #[test]
     fn test_float_arithmetic() {
-        assert_eq!(run("blether 3.14 + 2.86").trim(), "6");
-        assert_eq!(run("blether 10.5 - 0.5").trim(), "10");
-        assert_eq!(run("blether 2.5 * 4.0").trim(), "10");
-        assert_eq!(run("blether 7.5 / 2.5").trim(), "3");
+        assert_eq!(run("blether 3.14 + 2.86").trim(), "6.0");
+        assert_eq!(run("blether 10.5 - 0.5").trim(), "10.0");
+        assert_eq!(run("blether 2.5 * 4.0").trim(), "10.0");
+        assert_eq!(run("blether 7.5 / 2.5").trim(), "3.0");
     }
 
     #[test]
     fn test_mixed_int_float() {
         assert_eq!(run("blether 5 + 2.5").trim(), "7.5");
-        assert_eq!(run("blether 10.0 - 3").trim(), "7");
+        assert_eq!(run("blether 10.0 - 3").trim(), "7.0");
     }
 
     #[test]
@@ -160,6 +182,23 @@ mod comparison {
         assert_eq!(run("blether 5 >= 5").trim(), "aye");
         assert_eq!(run("blether 3 >= 5").trim(), "nae");
     }
+
+    #[test]
+    fn test_ints_an_floats_compare_by_value() {
+        assert_eq!(run("blether 5 == 5.0").trim(), "aye");
+        assert_eq!(run("blether 5 < 5.5").trim(), "aye");
+        assert_eq!(run("blether 5.0 != 5").trim(), "nae");
+        assert_eq!(run("blether 5.5 >= 5").trim(), "aye");
+        let code = r#"
+fer x in [0.5, 2.5, 5.0] {
+    keek x {
+        whan 1..5 -> { blether "in" }
+        whan _ -> { blether "oot" }
+    }
+}
+"#;
+        assert_eq!(run(code).trim(), "oot\nin\noot");
+    }
 }
 
 // ============================================================================
@@ -491,6 +530,22 @@ mod lists {
         assert_eq!(run("blether minaw([3, 1, 4, 1, 5])").trim(), "1");
         assert_eq!(run("blether maxaw([3, 1, 4, 1, 5])").trim(), "5");
     }
+
+    #[test]
+    fn test_list_comprehension() {
+        assert_eq!(
+            run("blether [x * 2 fer x in [1, 2, 3]]").trim(),
+            "[2, 4, 6]"
+        );
+        assert_eq!(
+            run("blether [x fer x in [3, -1, 4] gin x > 0]").trim(),
+            "[3, 4]"
+        );
+        assert_eq!(
+            run("blether [a * 10 + b fer a in 1..3 fer b in 0..2]").trim(),
+            "[10, 11, 20, 21]"
+        );
+    }
 }
 
 // ============================================================================
@@ -552,6 +607,18 @@ mod dicts {
         assert_eq!(run(code).trim(), "aye");
     }
 
+    #[test]
+    fn test_in_operator() {
+        let code = r#"
+            ken d = {"a": 1, "b": 2}
+            blether "a" in d
+            blether 3 in [1, 2, 3]
+            blether "ell" in "hello"
+            blether 4 nae in creel([1, 2])
+        "#;
+        assert_eq!(run(code).trim(), "aye\naye\naye\naye");
+    }
+
     #[test]
     fn test_dict_function_call() {
         let code = r#"
@@ -729,6 +796,18 @@ mod control_flow {
 mod functions {
     use super::*;
 
+    #[test]
+    fn test_functions_print_their_name_an_arity() {
+        let code = r#"
+dae add(a, b) { gie a + b }
+ken k = 2
+ken double = |x| x * k
+blether add
+blether double
+"#;
+        assert_eq!(run(code).trim(), "<dae add/2>\n<lambda@4:14/1>");
+    }
+
     #[test]
     fn test_function_no_params() {
         let code = r#"
@@ -930,7 +1009,7 @@ mod math {
 
     #[test]
     fn test_sqrt() {
-        assert_eq!(run("blether sqrt(16)").trim(), "4");
+        assert_eq!(run("blether sqrt(16)").trim(), "4.0");
         // Float precision may vary, check prefix
         let result = run("blether sqrt(2)");
         assert!(
@@ -983,7 +1062,7 @@ mod conversions {
     #[test]
     fn test_to_float() {
         assert_eq!(run(r#"blether tae_float("3.14")"#).trim(), "3.14");
-        assert_eq!(run("blether tae_float(42)").trim(), "42");
+        assert_eq!(run("blether tae_float(42)").trim(), "42.0");
     }
 
     #[test]
@@ -1120,6 +1199,22 @@ mod range {
         "#;
         assert_eq!(run(code).trim(), "[0, 2, 4, 6, 8]");
     }
+
+    #[test]
+    fn test_range_descending_and_by_step() {
+        let code = r#"
+            blether 5..1
+            blether 10..=0 by 5
+            blether range(0, 7, 3)
+            fer i in 9..0 by 4 {
+                blether i
+            }
+        "#;
+        assert_eq!(
+            run(code).trim(),
+            "[5, 4, 3, 2]\n[10, 5, 0]\n[0, 3, 6]\n9\n5\n1"
+        );
+    }
 }
 
 // ============================================================================
@@ -1281,6 +1376,59 @@ mod assertions {
         "#;
         assert_eq!(run(code).trim(), "All passed");
     }
+
+    #[test]
+    fn test_assert_eq_failure_can_be_caught() {
+        let code = r#"
+            assert(1 < 2)
+            assert_eq(2 + 2, 4)
+            hae_a_bash {
+                assert_eq(2 + 2, 5)
+            } gin_it_gangs_wrang e {
+                blether e
+            }
+        "#;
+        assert_eq!(run(code).trim(), "Assertion failed: Expected 5 but got 4");
+    }
+
+    #[test]
+    fn test_format_width_precision_an_braces() {
+        let code = r#"
+            blether format("{{{}}} [{:5}] [{:4}] {:.2}", "x", 42, "ab", 3.14159)
+            hae_a_bash {
+                format("{} {}", 1)
+            } gin_it_gangs_wrang e {
+                blether e
+            }
+        "#;
+        assert_eq!(
+            run(code).trim(),
+            "{x} [   42] [ab  ] 3.14\nformat() has mair placeholders than values - only gied 1"
+        );
+    }
+
+    #[test]
+    fn test_csv_round_trip_an_quoting() {
+        let code = r#"
+            ken rows = [["a,b", "say \"hi\""], ["twa\nlines", 3]]
+            ken text = csv_write(rows)
+            blether len(text)
+            ken back = csv_parse(text)
+            blether back[0][0]
+            blether back[0][1]
+            blether back[1][1]
+            blether csv_parse("x\ty,z", "\t")[0][1]
+            hae_a_bash {
+                csv_parse("\"ab\"c")
+            } gin_it_gangs_wrang e {
+                blether e
+            }
+        "#;
+        assert_eq!(
+            run(code).trim(),
+            "33\na,b\nsay \"hi\"\n3\ny,z\ncsv_parse() found stray text efter a closin' quote on line 1"
+        );
+    }
 }
 
 // ============================================================================
@@ -1368,8 +1516,8 @@ mod numeric_edge_cases {
     #[test]
     fn test_float_precision() {
         // LLVM backend rounds some float output differently
-        assert_eq!(run("blether 0.1 + 0.2").trim(), "0.3");
-        assert_eq!(run("blether 1.0 / 3.0 * 3.0").trim(), "1");
+        assert_eq!(run("blether 0.1 + 0.2").trim(), "0.30000000000000004");
+        assert_eq!(run("blether 1.0 / 3.0 * 3.0").trim(), "1.0");
     }
 
     #[test]
@@ -1409,6 +1557,26 @@ mod string_advanced {
         assert_eq!(run(r#"blether "abc" != "def""#).trim(), "aye");
     }
 
+    #[test]
+    fn test_string_ordering() {
+        assert_eq!(run(r#"blether "apple" < "banana""#).trim(), "aye");
+        assert_eq!(run(r#"blether "pear" > "banana""#).trim(), "aye");
+        assert_eq!(run(r#"blether "abc" <= "abc""#).trim(), "aye");
+        assert_eq!(run(r#"blether "abc" >= "abd""#).trim(), "nae");
+        // Contents, not pointers: a later-allocated string can still sort first
+        assert_eq!(
+            run(r#"ken a = "zebra"
+ken b = "aardvark"
+blether b < a"#)
+            .trim(),
+            "aye"
+        );
+        assert_eq!(
+            run(r#"blether join(sort(["pear", "apple", "fig"]), ",")"#).trim(),
+            "apple,fig,pear"
+        );
+    }
+
     #[test]
     fn test_empty_string() {
         assert_eq!(run(r#"blether len("")"#).trim(), "0");
@@ -1831,7 +1999,7 @@ mod conversion_advanced {
     #[test]
     fn test_to_string_float_whole() {
         // Float that's a whole number
-        assert_eq!(run("blether tae_string(5.0)").trim(), "5");
+        assert_eq!(run("blether tae_string(5.0)").trim(), "5.0");
     }
 
     #[test]
@@ -1839,9 +2007,15 @@ mod conversion_advanced {
         assert_eq!(run("blether tae_int(-3.9)").trim(), "-3");
     }
 
+    #[test]
+    fn test_to_int_with_a_base() {
+        assert_eq!(run(r#"blether tae_int("ff", 16)"#).trim(), "255");
+        assert_eq!(run(r#"blether tae_int("-101", 2)"#).trim(), "-5");
+    }
+
     #[test]
     fn test_to_float_integer() {
-        assert_eq!(run("blether tae_float(42)").trim(), "42");
+        assert_eq!(run("blether tae_float(42)").trim(), "42.0");
     }
 
     #[test]
@@ -2723,7 +2897,7 @@ mod coverage_batch1 {
 
     #[test]
     fn test_sqrt() {
-        assert_eq!(run("blether sqrt(16.0)").trim(), "4");
+        assert_eq!(run("blether sqrt(16.0)").trim(), "4.0");
     }
 
     #[test]
@@ -2749,13 +2923,13 @@ mod coverage_batch1 {
     #[test]
     fn test_sin() {
         // sin(0) = 0
-        assert_eq!(run("blether sin(0.0)").trim(), "0");
+        assert_eq!(run("blether sin(0.0)").trim(), "0.0");
     }
 
     #[test]
     fn test_cos() {
         // cos(0) = 1
-        assert_eq!(run("blether cos(0.0)").trim(), "1");
+        assert_eq!(run("blether cos(0.0)").trim(), "1.0");
     }
 
     #[test]
@@ -2768,12 +2942,12 @@ mod coverage_batch1 {
     #[test]
     fn test_exp() {
         // e^0 = 1
-        assert_eq!(run("blether exp(0.0)").trim(), "1");
+        assert_eq!(run("blether exp(0.0)").trim(), "1.0");
     }
 
     #[test]
     fn test_pow() {
-        assert_eq!(run("blether pow(2.0, 3.0)").trim(), "8");
+        assert_eq!(run("blether pow(2.0, 3.0)").trim(), "8.0");
     }
 
     // String operations
@@ -3254,27 +3428,27 @@ mod coverage_batch3 {
     // More math functions
     #[test]
     fn test_tan() {
-        assert_eq!(run("blether tan(0.0)").trim(), "0");
+        assert_eq!(run("blether tan(0.0)").trim(), "0.0");
     }
 
     #[test]
     fn test_asin() {
-        assert_eq!(run("blether asin(0.0)").trim(), "0");
+        assert_eq!(run("blether asin(0.0)").trim(), "0.0");
     }
 
     #[test]
     fn test_acos() {
-        assert_eq!(run("blether acos(1.0)").trim(), "0");
+        assert_eq!(run("blether acos(1.0)").trim(), "0.0");
     }
 
     #[test]
     fn test_atan() {
-        assert_eq!(run("blether atan(0.0)").trim(), "0");
+        assert_eq!(run("blether atan(0.0)").trim(), "0.0");
     }
 
     #[test]
     fn test_log10() {
-        assert_eq!(run("blether log10(100.0)").trim(), "2");
+        assert_eq!(run("blether log10(100.0)").trim(), "2.0");
     }
 
     // More string ops
@@ -3966,8 +4140,8 @@ blether last
     // Test sqrt
     #[test]
     fn test_sqrt() {
-        assert_eq!(run("blether sqrt(16.0)").trim(), "4");
-        assert_eq!(run("blether sqrt(25.0)").trim(), "5");
+        assert_eq!(run("blether sqrt(16.0)").trim(), "4.0");
+        assert_eq!(run("blether sqrt(25.0)").trim(), "5.0");
     }
 
     // Test contains
@@ -4010,7 +4184,7 @@ blether last
     #[test]
     fn test_tae_float() {
         assert_eq!(run(r#"blether tae_float("3.14")"#).trim(), "3.14");
-        assert_eq!(run("blether tae_float(42)").trim(), "42");
+        assert_eq!(run("blether tae_float(42)").trim(), "42.0");
     }
 
     // Test unary negation
@@ -4198,23 +4372,42 @@ blether len(c)
     // Test sin/cos/tan
     #[test]
     fn test_sin() {
-        assert_eq!(run("blether sin(0.0)").trim(), "0");
+        assert_eq!(run("blether sin(0.0)").trim(), "0.0");
     }
 
     #[test]
     fn test_cos() {
-        assert_eq!(run("blether cos(0.0)").trim(), "1");
+        assert_eq!(run("blether cos(0.0)").trim(), "1.0");
     }
 
     #[test]
     fn test_tan() {
-        assert_eq!(run("blether tan(0.0)").trim(), "0");
+        assert_eq!(run("blether tan(0.0)").trim(), "0.0");
     }
 
     // Test atan2
     #[test]
     fn test_atan2() {
-        assert_eq!(run("blether atan2(0.0, 1.0)").trim(), "0");
+        assert_eq!(run("blether atan2(0.0, 1.0)").trim(), "0.0");
+    }
+
+    // Test fmod
+    #[test]
+    fn test_fmod() {
+        assert_eq!(run("blether fmod(7.5, 2)").trim(), "1.5");
+        assert_eq!(run("blether fmod(-7, 3)").trim(), "-1.0");
+    }
+
+    #[test]
+    fn test_fmod_by_zero_hurls() {
+        let code = r#"
+hae_a_bash {
+    blether fmod(7.5, 0)
+} gin_it_gangs_wrang e {
+    blether e
+}
+        "#;
+        assert_eq!(run(code).trim(), "fmod() cannae divide by zero");
     }
 
     // Test sort
@@ -4632,24 +4825,24 @@ mod coverage_batch8 {
     // Test acos, asin, atan
     #[test]
     fn test_acos() {
-        assert_eq!(run("blether acos(1.0)").trim(), "0");
+        assert_eq!(run("blether acos(1.0)").trim(), "0.0");
     }
 
     #[test]
     fn test_asin() {
-        assert_eq!(run("blether asin(0.0)").trim(), "0");
+        assert_eq!(run("blether asin(0.0)").trim(), "0.0");
     }
 
     #[test]
     fn test_atan() {
-        assert_eq!(run("blether atan(0.0)").trim(), "0");
+        assert_eq!(run("blether atan(0.0)").trim(), "0.0");
     }
 
     // Test log10
     #[test]
     fn test_log10() {
-        assert_eq!(run("blether log10(100.0)").trim(), "2");
-        assert_eq!(run("blether log10(1000.0)").trim(), "3");
+        assert_eq!(run("blether log10(100.0)").trim(), "2.0");
+        assert_eq!(run("blether log10(1000.0)").trim(), "3.0");
     }
 
     // Test string lower/upper
@@ -5014,7 +5207,7 @@ blether x
     // Test float edge cases
     #[test]
     fn test_float_edges() {
-        assert_eq!(run("blether 0.0").trim(), "0");
+        assert_eq!(run("blether 0.0").trim(), "0.0");
         assert_eq!(run("blether 3.14159").trim(), "3.14159");
     }
 
@@ -8373,7 +8566,7 @@ blether d > 179
 
     #[test]
     fn test_pow_basic() {
-        assert_eq!(run("blether pow(2.0, 3.0)").trim(), "8");
+        assert_eq!(run("blether pow(2.0, 3.0)").trim(), "8.0");
     }
 
     #[test]
@@ -8527,7 +8720,7 @@ blether found
 
     #[test]
     fn test_sqrt_perfect() {
-        assert_eq!(run("blether sqrt(16.0)").trim(), "4");
+        assert_eq!(run("blether sqrt(16.0)").trim(), "4.0");
     }
 
     #[test]
@@ -11768,7 +11961,7 @@ mod coverage_batch28 {
 
     #[test]
     fn test_sqrt() {
-        assert_eq!(run("blether sqrt(16.0)").trim(), "4");
+        assert_eq!(run("blether sqrt(16.0)").trim(), "4.0");
     }
 
     #[test]
@@ -13050,7 +13243,7 @@ mod coverage_batch36 {
 
     #[test]
     fn test_sqrt_positive() {
-        assert_eq!(run("blether sqrt(16.0)").trim(), "4");
+        assert_eq!(run("blether sqrt(16.0)").trim(), "4.0");
     }
 
     #[test]
@@ -13188,7 +13381,7 @@ blether list
 
     #[test]
     fn test_average_list() {
-        assert_eq!(run("blether average([2, 4, 6, 8])").trim(), "5");
+        assert_eq!(run("blether average([2, 4, 6, 8])").trim(), "5.0");
     }
 
     #[test]
@@ -13320,7 +13513,7 @@ mod coverage_batch39 {
 
     #[test]
     fn test_tae_float_int() {
-        assert_eq!(run("blether tae_float(5)").trim(), "5");
+        assert_eq!(run("blether tae_float(5)").trim(), "5.0");
     }
 
     #[test]
@@ -32587,6 +32780,28 @@ blether len(keys(merged))
         assert_eq!(run(code).trim(), "2");
     }
 
+    #[test]
+    fn test_dict_get_set_has_remove() {
+        let code = r#"
+ken d = {"a": 1}
+dict_set(d, "b", 2)
+dict_set(d, "a", 10)
+blether dict_get(d, "a")
+blether dict_get(d, "zz")
+blether dict_get(d, "zz", 5)
+blether dict_has(d, "b")
+ken gone = dict_remove(d, "b")
+blether dict_has(gone, "b")
+blether len(keys(dict_remove(d, "zz")))
+ken m = dict_merge(d, {"a": 99})
+blether m["a"]
+        "#;
+        assert_eq!(
+            run(code).trim(),
+            "10\nnaething\n5\naye\nnae\n2\n99"
+        );
+    }
+
     #[test]
     fn test_dict_update() {
         let code = r#"
@@ -32634,12 +32849,12 @@ mod math_comprehensive {
 
     #[test]
     fn test_math_sqrt() {
-        assert_eq!(run("blether sqrt(16.0)").trim(), "4");
+        assert_eq!(run("blether sqrt(16.0)").trim(), "4.0");
     }
 
     #[test]
     fn test_math_pow() {
-        assert_eq!(run("blether pow(2.0, 3.0)").trim(), "8");
+        assert_eq!(run("blether pow(2.0, 3.0)").trim(), "8.0");
     }
 
     #[test]
@@ -36880,7 +37095,7 @@ mod math_builtins_coverage {
 
     #[test]
     fn test_sqrt_perfect() {
-        assert_eq!(run("blether sqrt(16)").trim(), "4");
+        assert_eq!(run("blether sqrt(16)").trim(), "4.0");
     }
 
     #[test]
@@ -42631,6 +42846,26 @@ mod builtins_coverage_batch1 {
         assert_eq!(run("blether sign(0)").trim(), "0");
     }
 
+    #[test]
+    fn test_sign_clamp_an_lerp_keep_int_ness() {
+        let code = r#"
+blether sign(-2.5)
+blether sign(0.0)
+blether clamp(15, 0, 2.5)
+blether clamp(7, 0, 10)
+blether lerp(0, 10, 0.25)
+hae_a_bash {
+    clamp(5, 10, 0)
+} gin_it_gangs_wrang e {
+    blether e
+}
+        "#;
+        assert_eq!(
+            run(code).trim(),
+            "-1.0\n0.0\n2.5\n7\n2.5\nclamp() needs lo no bigger than hi, but got 10 an' 0"
+        );
+    }
+
     #[test]
     fn test_is_positive() {
         let code = r#"blether gin is_positive(5) than "yes" ither "no""#;
@@ -43025,7 +43260,7 @@ blether len(pairs)
 
     #[test]
     fn test_lerp() {
-        assert_eq!(run("blether lerp(0.0, 10.0, 0.5)").trim(), "5");
+        assert_eq!(run("blether lerp(0.0, 10.0, 0.5)").trim(), "5.0");
     }
 
     #[test]
@@ -43075,8 +43310,8 @@ mod math_coverage_final {
 
     #[test]
     fn test_sqrt_various() {
-        assert_eq!(run("blether sqrt(16.0)").trim(), "4");
-        assert_eq!(run("blether sqrt(25.0)").trim(), "5");
+        assert_eq!(run("blether sqrt(16.0)").trim(), "4.0");
+        assert_eq!(run("blether sqrt(25.0)").trim(), "5.0");
     }
 
     #[test]
@@ -47517,6 +47752,22 @@ blether level
         "#;
         assert_eq!(run(code).trim(), "3");
     }
+
+    #[test]
+    fn test_log_level_names_an_the_default_match_the_interpreter() {
+        let code = r#"
+blether get_log_level()
+set_log_level("warn")
+blether get_log_level()
+blether log_enabled("info")
+blether log_enabled("error")
+log_info("quiet")
+log_error("loud", {"code": 7})
+blether "done"
+        "#;
+        // The log lines go to stderr, so only the blether output shows here
+        assert_eq!(run(code).trim(), "3\n2\nnae\naye\ndone");
+    }
 }
 
 /// Tests for Scottish expression builtins
@@ -48464,6 +48715,20 @@ blether s
 mod random_cov {
     use super::*;
 
+    #[test]
+    fn test_uuids_are_canonical_an_differ() {
+        let code = r#"
+ken a = uuid_v4()
+ken b = uuid_v4()
+ken c = uuid_v7()
+blether len(a)
+blether a != b
+blether a[14] + c[14]
+blether a[8] + a[13] + a[18] + a[23]
+        "#;
+        assert_eq!(run(code).trim(), "36\naye\n47\n----");
+    }
+
     #[test]
     fn test_random() {
         let code = r#"
@@ -59435,6 +59700,46 @@ blether len(f)
 mod json_cov {
     use super::*;
 
+    #[test]
+    fn test_json_query_nested_wildcard_an_out_of_range() {
+        let code = r#"
+ken doc = json_parse("{\"user\": {\"addresses\": [{\"city\": \"Perth\"}, {\"city\": \"Oban\"}]}}")
+blether json_query(doc, "user.addresses[0].city")
+blether json_query(doc, "user.addresses[-1].city")
+blether json_query(doc, "user.addresses[5].city")
+blether json_query(doc, "user.age")
+blether json_query(doc, "user.addresses[*].city")
+hae_a_bash {
+    json_query(doc, "user[0")
+} gin_it_gangs_wrang e {
+    blether e
+}
+        "#;
+        assert_eq!(
+            run(code).trim(),
+            "Perth\nOban\nnaething\nnaething\n[Perth, Oban]\njson_query() path 'user[0' has a '[' that's never closed"
+        );
+    }
+
+    #[test]
+    fn test_json_pretty_wi_an_indent_an_sorted_keys() {
+        let code = r#"
+ken d = {"zeta": 1, "alpha": [1, 2]}
+blether json_pretty(d, naething, aye)
+blether json_pretty(d, 1, aye)
+blether json_pretty(d)
+hae_a_bash {
+    json_pretty(d, "x")
+} gin_it_gangs_wrang e {
+    blether e
+}
+        "#;
+        assert_eq!(
+            run(code).trim(),
+            "{\"alpha\": [1, 2], \"zeta\": 1}\n{\n \"alpha\": [\n  1,\n  2\n ],\n \"zeta\": 1\n}\n{\n  \"zeta\": 1,\n  \"alpha\": [\n    1,\n    2\n  ]\n}\njson_pretty() indent string can only hae spaces an' tabs"
+        );
+    }
+
     #[test]
     fn test_json_parse() {
         let code = r#"
@@ -84365,22 +84670,22 @@ mod coverage_batch351 {
 
     #[test]
     fn test_sin_zero() {
-        assert_eq!(run("blether sin(0.0)").trim(), "0");
+        assert_eq!(run("blether sin(0.0)").trim(), "0.0");
     }
 
     #[test]
     fn test_cos_zero() {
-        assert_eq!(run("blether cos(0.0)").trim(), "1");
+        assert_eq!(run("blether cos(0.0)").trim(), "1.0");
     }
 
     #[test]
     fn test_tan_zero() {
-        assert_eq!(run("blether tan(0.0)").trim(), "0");
+        assert_eq!(run("blether tan(0.0)").trim(), "0.0");
     }
 
     #[test]
     fn test_exp_zero() {
-        assert_eq!(run("blether exp(0.0)").trim(), "1");
+        assert_eq!(run("blether exp(0.0)").trim(), "1.0");
     }
 
     #[test]
@@ -84394,7 +84699,7 @@ mod coverage_batch351 {
 
     #[test]
     fn test_log10_hundred() {
-        assert_eq!(run("blether log10(100.0)").trim(), "2");
+        assert_eq!(run("blether log10(100.0)").trim(), "2.0");
     }
 
     #[test]
@@ -84531,7 +84836,7 @@ mod coverage_batch353 {
 
     #[test]
     fn test_tae_float_int() {
-        assert_eq!(run("blether tae_float(42)").trim(), "42");
+        assert_eq!(run("blether tae_float(42)").trim(), "42.0");
     }
 
     #[test]
@@ -85120,7 +85425,7 @@ mod coverage_batch363 {
 
     #[test]
     fn test_sqrt() {
-        assert_eq!(run("blether sqrt(16.0)").trim(), "4");
+        assert_eq!(run("blether sqrt(16.0)").trim(), "4.0");
     }
 
     #[test]
@@ -0,0 +1,39 @@
+//! Native builds with DWARF debug info still compile, link and run.
+
+#![cfg(feature = "llvm")]
+
+use std::process::Command;
+
+use mdhavers::{parse, LLVMCompiler};
+use tempfile::tempdir;
+
+#[test]
+fn llvm_debug_build_runs_and_keeps_output() {
+    let source = r#"
+dae greet(name) {
+    ken message = "Hullo, " + name
+    gie message
+}
+ken names = ["Morag", "Hamish"]
+fer n in names {
+    blether greet(n)
+}
+ken double = |x| x * 2
+blether double(21)
+"#;
+    let program = parse(source).unwrap();
+    let dir = tempdir().unwrap();
+    let exe_path = dir.path().join("debug_exe");
+
+    for opt_level in [0, 2] {
+        LLVMCompiler::new()
+            .with_debug_info(true)
+            .compile_to_native(&program, &exe_path, opt_level)
+            .expect("debug build should compile and verify");
+
+        let output = Command::new(&exe_path).output().unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert_eq!(stdout.trim(), "Hullo, Morag\nHullo, Hamish\n42");
+    }
+}
@@ -1,10 +1,10 @@
-//! Focused tests for the Rust-FFI runtime helpers (JSON + regex).
+//! Focused tests for the Rust-FFI runtime helpers (JSON, regex, digests + HMAC, directories, TLS).
 
 #![cfg(feature = "llvm")]
 
 use std::process::Command;
 
-use mdhavers::{parse, LLVMCompiler};
+use mdhavers::{parse, Interpreter, LLVMCompiler};
 use tempfile::tempdir;
 
 fn compile_and_run(source: &str) -> Result<String, String> {
@@ -77,3 +77,161 @@ fn llvm_regex_replace_supports_capture_expansion() {
     let out = run(r#"blether regex_replace("abc123def", "([0-9]+)", "[$1]")"#);
     assert_eq!(out.trim(), "abc[123]def");
 }
+
+#[test]
+fn llvm_digests_match_known_vectors() {
+    let out = run(r#"
+blether sha256_hex("")
+blether sha256_hex("abc")
+blether sha1_hex("abc")
+blether md5_hex(bytes_from_string("abc"))
+blether bytes_len(sha256("abc"))
+"#);
+    let lines: Vec<&str> = out.trim().lines().collect();
+    assert_eq!(
+        lines,
+        vec![
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            "a9993e364706816aba3e25717850c26c9cd0d89d",
+            "900150983cd24fb0d6963f7d28e17f72",
+            "32",
+        ]
+    );
+}
+
+#[test]
+fn llvm_hmac_sha256_and_ct_eq() {
+    let out = run(r#"
+ken mac = hmac_sha256_hex("Jefe", "what do ya want for nothing?")
+blether mac
+blether ct_eq(mac, "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843")
+blether ct_eq(hmac_sha256("k", "a"), hmac_sha256("k", "b"))
+"#);
+    let lines: Vec<&str> = out.trim().lines().collect();
+    assert_eq!(
+        lines,
+        vec![
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+            "aye",
+            "nae",
+        ]
+    );
+}
+
+#[test]
+fn llvm_hex_round_trip_and_hexdump() {
+    let out = run(r#"
+ken b = hex_decode("007f80ff")
+blether hex_encode(b)
+blether bytes_get(b, 3)
+blether hex_encode(hex_decode(hex_encode(bytes_from_string("Braw bricht nicht!"))))
+blether hexdump(bytes_from_string("Braw bricht nicht!"))
+"#);
+    let lines: Vec<&str> = out.trim().lines().collect();
+    assert_eq!(
+        lines,
+        vec![
+            "007f80ff",
+            "255",
+            "4272617720627269636874206e6963687421",
+            "00000000  42 72 61 77 20 62 72 69  63 68 74 20 6e 69 63 68  |Braw bricht nich|",
+            "00000010  74 21                                             |t!|",
+        ]
+    );
+}
+
+#[test]
+fn llvm_float_display_matches_interpreter() {
+    let source = r#"
+blether 0.1 + 0.2
+blether 2.5 * 2
+blether tae_string(1.0 / 3.0)
+blether 1e16
+blether [1.5, 2.0, 3]
+blether json_stringify([0.1 + 0.2, 5.0])
+blether fmt_float(3.14159, 2)
+"#;
+    let out = run(source);
+    let lines: Vec<&str> = out.trim().lines().collect();
+    assert_eq!(
+        lines,
+        vec![
+            "0.30000000000000004",
+            "5.0",
+            "0.3333333333333333",
+            "1e16",
+            "[1.5, 2.0, 3]",
+            "[0.30000000000000004, 5.0]",
+            "3.14",
+        ]
+    );
+
+    let program = parse(source).unwrap();
+    let mut interp = Interpreter::new();
+    interp.interpret(&program).unwrap();
+    assert_eq!(interp.get_output().join("\n"), out.trim());
+}
+
+#[test]
+fn llvm_dict_get_has_key_and_remove() {
+    let out = run(r#"
+ken d = {"a": 1, "b": 2, "c": 3}
+blether get(d, "z", 0)
+blether has_key(d, "b")
+blether remove(d, "b")
+blether remove(d, "z")
+blether join(keys(d), ",")
+blether has_key(d, "b")
+"#);
+    let lines: Vec<&str> = out.trim().lines().collect();
+    assert_eq!(
+        lines,
+        vec!["0", "aye", "2", "naething", "a,c", "nae"]
+    );
+}
+
+#[test]
+fn llvm_read_dir_and_walk_dir() {
+    let tree = tempdir().unwrap();
+    std::fs::write(tree.path().join("b.txt"), "bee").unwrap();
+    std::fs::write(tree.path().join("a.txt"), "aye").unwrap();
+    std::fs::create_dir(tree.path().join("sub")).unwrap();
+    std::fs::write(tree.path().join("sub").join("c.braw"), "blether 1").unwrap();
+    let root = tree.path().to_string_lossy().to_string();
+
+    let out = run(&format!(
+        r#"
+ken root = "{root}"
+blether join(read_dir(root), ",")
+blether join(walk_dir(root), ",")
+blether is_dir(root + "/sub")
+blether is_file(root + "/sub")
+blether is_file(root + "/a.txt")
+"#
+    ));
+    let lines: Vec<&str> = out.trim().lines().collect();
+    assert_eq!(
+        lines,
+        vec![
+            "a.txt,b.txt,sub",
+            "a.txt,b.txt,sub/c.braw",
+            "aye",
+            "nae",
+            "aye",
+        ]
+    );
+}
+
+#[test]
+fn llvm_tls_connect_start_leaves_the_socket_open_on_a_bad_handle() {
+    let out = run(r#"
+ken s = socket_tcp()
+ken sock = s["value"]
+ken r = tls_connect_start(999, sock)
+blether r["ok"]
+ken c = socket_close(sock)
+blether c["ok"]
+"#);
+    assert_eq!(out.trim(), "nae\naye");
+}
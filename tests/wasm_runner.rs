@@ -0,0 +1,77 @@
+#![cfg(feature = "wasm_runner")]
+
+use std::fs;
+
+use mdhavers::wasm_compiler::compile_to_wat;
+use mdhavers::wasm_runner::{run_wasm_file, WasmRunResult};
+
+fn run_source(source: &str, args: &[&str]) -> WasmRunResult {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("sum.wat");
+    fs::write(&path, compile_to_wat(source).unwrap()).unwrap();
+    let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+    run_wasm_file(&path, &args).unwrap()
+}
+
+#[test]
+fn wasm_runner_gies_back_the_result_as_the_exit_code() {
+    let result = run_source("dae add(a, b) { gie a + b }\ngie add(6, 7) * 3 - 2", &[]);
+    assert_eq!(
+        result,
+        WasmRunResult {
+            value: Some("37".to_string()),
+            exit_code: 37,
+            output: Vec::new(),
+        }
+    );
+
+    // Falling off the end returns nil, which is a clean exit
+    let result = run_source("ken x = 1 + 2", &[]);
+    assert_eq!(result.value, None);
+    assert_eq!(result.exit_code, 0);
+}
+
+#[test]
+fn wasm_runner_hands_argv_tae_args() {
+    let result = run_source("gie args()", &["7", "--eight"]);
+    let value = result.value.unwrap();
+    assert!(value.starts_with('['), "{value}");
+    assert!(value.contains("sum.wat"), "{value}");
+    assert!(value.ends_with(", 7, --eight]"), "{value}");
+    assert_eq!(result.exit_code, 0);
+}
+
+#[test]
+fn wasm_runner_collects_whit_blether_prints() {
+    let result = run_source("blether 42", &[]);
+    assert_eq!(result.output, vec!["42"]);
+    assert_eq!(result.value, None);
+
+    let result = run_source(
+        "dae greet(name) { blether \"Hullo, \" + name }\ngreet(\"Morag\")\nblether 1.5 + 2.5",
+        &[],
+    );
+    assert_eq!(result.output, vec!["Hullo, Morag", "4.0"]);
+}
+
+#[test]
+fn wasm_runner_runs_recursive_fib() {
+    let source = r#"
+dae fib(n) {
+    gin n < 2 {
+        gie n
+    }
+    gie fib(n - 1) + fib(n - 2)
+}
+ken total = 0
+fer i in 0..10 {
+    total = total + fib(i)
+}
+blether total
+gie fib(10)
+"#;
+    let result = run_source(source, &[]);
+    assert_eq!(result.output, vec!["88"]);
+    assert_eq!(result.value, Some("55".to_string()));
+    assert_eq!(result.exit_code, 55);
+}